@@ -1,6 +1,8 @@
 use cubecl_core::CubeDim;
 use cubecl_matmul::components::{
-    MatrixLayout, StageIdent, global::memory::GlobalMemoryConfig, stage::StageMemoryConfig,
+    MatrixLayout, StageIdent,
+    global::memory::{GlobalMemoryConfig, OutputLayout},
+    stage::StageMemoryConfig,
 };
 
 use crate::components::{
@@ -60,6 +62,7 @@ impl<S: StageAttentionConfig> GlobalAttentionConfig for DummyGlobalConfig<S> {
             check_row_bounds: false,
             check_col_bounds: false,
             matrix_layout: MatrixLayout::RowMajor,
+            output_layout: OutputLayout::Strided,
         }
     }
 
@@ -80,6 +80,7 @@ fn score_attention_stage_memory_config(
         partition_size: att_partition_size.to_score_matmul_partition_size(),
         stage_size: (att_stage_size.seq_q, 1, 1).into(),
         global_partition_size: GlobalPartitionSize::new(1, 1, 1),
+        rhs_k_packing: 1,
     };
     AttentionStageMemoryConfig {
         matmul_tiling_scheme,
@@ -98,6 +99,7 @@ fn value_attention_stage_memory_config(
         partition_size: att_partition_size.to_value_matmul_partition_size(),
         stage_size: (att_stage_size.seq_q, 1, 1).into(),
         global_partition_size: GlobalPartitionSize::new(1, 1, 1),
+        rhs_k_packing: 1,
     };
     AttentionStageMemoryConfig {
         matmul_tiling_scheme,
@@ -72,6 +72,7 @@ where
                 stage_k,
                 selection.loading_precompute_strategy,
                 selection.reader_mode,
+                selection.accumulator_flush,
             )?,
             &problem.kernel_size,
             &problem.stride,
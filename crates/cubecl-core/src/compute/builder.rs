@@ -126,6 +126,12 @@ impl KernelBuilder {
         self.scope.runtime_properties = Rc::new(properties);
     }
 
+    /// Set the maximum number of times the body of an unrolled loop is expanded, overriding the
+    /// one of the [compilation config](cubecl_runtime::config::compilation::CompilationConfig).
+    pub fn max_unroll_factor(&mut self, max_factor: Option<u32>) {
+        self.scope.max_unroll_factor = max_factor;
+    }
+
     /// Build the [kernel definition](KernelDefinition).
     pub fn build(self, settings: KernelSettings) -> KernelDefinition {
         let scalars = self
@@ -155,8 +161,11 @@ impl KernelBuilder {
         } else {
             debug == 1
         };
+        let mut scope = Scope::root(debug);
+        scope.max_unroll_factor = GlobalConfig::get().compilation.max_unroll_factor;
+
         Self {
-            scope: Scope::root(debug),
+            scope,
             buffers: Default::default(),
             scalars: Default::default(),
             tensor_maps: Default::default(),
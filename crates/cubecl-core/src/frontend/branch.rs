@@ -4,7 +4,7 @@ use num_traits::NumCast;
 use crate::ir::Switch;
 use crate::ir::{Branch, If, IfElse, Loop, RangeLoop, Scope, Type};
 
-use super::{CubePrimitive, CubeType, ExpandElementTyped, Int, Numeric, assign};
+use super::{CubePrimitive, CubeType, ExpandElementTyped, Int, Numeric, add, assign, mul};
 
 /// Something that can be iterated on by a for loop. Currently only includes `Range`, `StepBy` and
 /// `Sequence`.
@@ -27,6 +27,24 @@ pub trait Iterable<T: CubeType>: Sized {
         scope: &mut Scope,
         body: impl FnMut(&mut Scope, <T as CubeType>::ExpandType),
     );
+    /// Expand an unrolled loop, unrolling at most `max_factor` iterations. Past that, the body is
+    /// unrolled `max_factor` times in a runtime loop, followed by a runtime loop over the
+    /// remaining iterations, so it may be invoked with a runtime index.
+    ///
+    /// Defaults to fully unrolling the loop, for the iterables that can't be iterated at runtime.
+    ///
+    /// # Arguments
+    /// * `scope` - the expansion scope
+    /// * `_max_factor` - the maximum number of times the body is unrolled
+    /// * `body` - the loop body to be executed repeatedly
+    fn expand_unroll_bounded(
+        self,
+        scope: &mut Scope,
+        _max_factor: u32,
+        body: impl FnMut(&mut Scope, <T as CubeType>::ExpandType),
+    ) {
+        self.expand_unroll(scope, body);
+    }
 }
 
 pub struct RangeExpand<I: Int> {
@@ -89,6 +107,29 @@ impl<I: Int> Iterable<I> for RangeExpand<I> {
         }
     }
 
+    fn expand_unroll_bounded(
+        self,
+        scope: &mut Scope,
+        max_factor: u32,
+        body: impl FnMut(&mut Scope, <I as CubeType>::ExpandType),
+    ) {
+        let start = self
+            .start
+            .expand
+            .as_const()
+            .expect("Only constant start can be unrolled.")
+            .as_i64();
+        let end = self
+            .end
+            .expand
+            .as_const()
+            .expect("Only constant end can be unrolled.")
+            .as_i64();
+        let end = if self.inclusive { end + 1 } else { end };
+
+        expand_partial_unroll::<I>(scope, start, end, 1, max_factor, body);
+    }
+
     fn expand(
         self,
         scope: &mut Scope,
@@ -176,6 +217,100 @@ impl<I: Int + Into<ExpandElement>> Iterable<I> for SteppedRangeExpand<I> {
             }
         }
     }
+
+    fn expand_unroll_bounded(
+        self,
+        scope: &mut Scope,
+        max_factor: u32,
+        body: impl FnMut(&mut Scope, <I as CubeType>::ExpandType),
+    ) {
+        let start = self
+            .start
+            .expand
+            .as_const()
+            .expect("Only constant start can be unrolled.")
+            .as_i64();
+        let end = self
+            .end
+            .expand
+            .as_const()
+            .expect("Only constant end can be unrolled.")
+            .as_i64();
+        let step = self
+            .step
+            .expand
+            .as_const()
+            .expect("Only constant step can be unrolled.")
+            .as_i64();
+        let end = if self.inclusive { end + 1 } else { end };
+
+        expand_partial_unroll::<I>(scope, start, end, step, max_factor, body);
+    }
+}
+
+/// Unroll the iterations from `start` to `end` (exclusive) by `step` when there are at most
+/// `max_factor` of them. Otherwise, a runtime loop goes over the blocks of `max_factor`
+/// iterations, with the body unrolled once per iteration of the block, and a runtime remainder
+/// loop goes over the iterations past the last block.
+fn expand_partial_unroll<I: Int>(
+    scope: &mut Scope,
+    start: i64,
+    end: i64,
+    step: i64,
+    max_factor: u32,
+    mut body: impl FnMut(&mut Scope, ExpandElementTyped<I>),
+) {
+    let constant = |value: i64| -> ExpandElementTyped<I> { I::from_int(value).into() };
+    let factor = max_factor.max(1) as i64;
+    let trip_count = if end > start {
+        (end - start + step - 1) / step
+    } else {
+        0
+    };
+
+    if trip_count <= factor {
+        for i in (start..end).step_by(step as usize) {
+            body(scope, constant(i));
+        }
+        return;
+    }
+
+    let num_blocks = trip_count / factor;
+    let index_ty = Type::new(I::as_type(scope));
+
+    let mut child = scope.child();
+    let block = child.create_local_restricted(index_ty);
+    let offset = mul::expand(&mut child, block.clone().into(), constant(factor * step));
+    for k in 0..factor {
+        let i = add::expand(&mut child, offset.clone(), constant(start + k * step));
+        body(&mut child, i);
+    }
+    scope.register(Branch::RangeLoop(Box::new(RangeLoop {
+        i: *block,
+        start: *constant(0).expand,
+        end: *constant(num_blocks).expand,
+        step: None,
+        scope: child,
+        inclusive: false,
+    })));
+
+    let remainder_start = start + num_blocks * factor * step;
+    if remainder_start < end {
+        let mut child = scope.child();
+        let i = child.create_local_restricted(index_ty);
+
+        body(&mut child, i.clone().into());
+
+        let step = (step != 1).then(|| *ExpandElementTyped::<u32>::from(step as u32).expand);
+        scope.register(Branch::RangeLoop(Box::new(RangeLoop {
+            i: *i,
+            start: *constant(remainder_start).expand,
+            end: *constant(end).expand,
+            step,
+            scope: child,
+            inclusive: false,
+        })));
+    }
 }
 
 /// integer range. Equivalent to:
@@ -261,6 +396,21 @@ pub fn for_expand<I: Numeric>(
     }
 }
 
+/// Like [for_expand], but only unrolls up to the [max unroll factor](Scope::max_unroll_factor) of
+/// the scope, past which the body is expanded with a runtime index. Only used for the loops whose
+/// body doesn't need a comptime index.
+pub fn for_expand_bounded<I: Numeric>(
+    scope: &mut Scope,
+    range: impl Iterable<I>,
+    unroll: bool,
+    body: impl FnMut(&mut Scope, ExpandElementTyped<I>),
+) {
+    match scope.max_unroll_factor {
+        Some(max_factor) if unroll => range.expand_unroll_bounded(scope, max_factor, body),
+        _ => for_expand(scope, range, unroll, body),
+    }
+}
+
 pub fn if_expand(scope: &mut Scope, runtime_cond: ExpandElement, block: impl FnOnce(&mut Scope)) {
     let comptime_cond = runtime_cond.as_const().map(|it| it.as_bool());
     match comptime_cond {
@@ -299,6 +299,63 @@ test_mulhi_impl!(
     ]
 );
 
+#[cube(launch_unchecked)]
+fn test_div_mod_const_kernel(
+    input: &Array<u32>,
+    output: &mut Array<u32>,
+    #[comptime] divisor: u32,
+) {
+    if ABSOLUTE_POS < input.len() {
+        output[2 * ABSOLUTE_POS] = input[ABSOLUTE_POS] / divisor;
+        output[2 * ABSOLUTE_POS + 1] = input[ABSOLUTE_POS] % divisor;
+    }
+}
+
+fn test_div_mod_const<R: Runtime>(client: &ComputeClient<R::Server, R::Channel>, divisor: u32) {
+    // Small values, values around multiples of the divisor and the top of the range.
+    let mut input: Vec<u32> = (0..1024).collect();
+    input.extend((1..1024).flat_map(|i| {
+        let multiple = (u32::MAX / 1024 * i) / divisor * divisor;
+        [multiple.wrapping_sub(1), multiple, multiple + 1]
+    }));
+    input.extend((0..1024).map(|i| u32::MAX - i));
+
+    let expected: Vec<u32> = input
+        .iter()
+        .flat_map(|value| [value / divisor, value % divisor])
+        .collect();
+
+    let input_handle = client.create(u32::as_bytes(&input));
+    let output_handle = client.empty(expected.len() * core::mem::size_of::<u32>());
+    let cube_dim = CubeDim::new_1d(256);
+
+    unsafe {
+        test_div_mod_const_kernel::launch_unchecked::<R>(
+            client,
+            CubeCount::new_1d((input.len() as u32).div_ceil(cube_dim.x)),
+            cube_dim,
+            ArrayArg::from_raw_parts::<u32>(&input_handle, input.len(), 1),
+            ArrayArg::from_raw_parts::<u32>(&output_handle, expected.len(), 1),
+            divisor,
+        )
+    };
+
+    let actual = client.read_one(output_handle);
+    let actual = u32::from_bytes(&actual);
+
+    assert_eq!(actual, expected, "Division and modulo by {divisor} differ");
+}
+
+pub fn test_div_mod_const_pow2<R: Runtime>(client: ComputeClient<R::Server, R::Channel>) {
+    test_div_mod_const::<R>(&client, 16);
+}
+
+pub fn test_div_mod_const_non_pow2<R: Runtime>(client: ComputeClient<R::Server, R::Channel>) {
+    test_div_mod_const::<R>(&client, 7);
+    test_div_mod_const::<R>(&client, 641);
+    test_div_mod_const::<R>(&client, 0x80000001);
+}
+
 #[allow(missing_docs)]
 #[macro_export]
 macro_rules! testgen_binary {
@@ -350,6 +407,8 @@ macro_rules! testgen_binary_untyped {
             }
 
             add_test!(test_mulhi);
+            add_test!(test_div_mod_const_pow2);
+            add_test!(test_div_mod_const_non_pow2);
         }
     };
 }
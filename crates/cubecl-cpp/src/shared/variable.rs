@@ -222,6 +222,11 @@ impl<D: Dialect> Display for Variable<D> {
                     gpu::IntKind::I32 => write!(f, "{elem}({})", *val as i32),
                     gpu::IntKind::I64 => write!(f, "{elem}({})", *val),
                 },
+                // Infinities have no literal, but dividing by zero gives them on every dialect.
+                ConstantScalarValue::Float(val, _) if val.is_infinite() => {
+                    let sign = if val.is_sign_negative() { "-" } else { "" };
+                    write!(f, "{elem}({sign}1.0f / 0.0f)")
+                }
                 ConstantScalarValue::Float(val, kind) => match kind {
                     gpu::FloatKind::E2M1
                     | gpu::FloatKind::E2M3
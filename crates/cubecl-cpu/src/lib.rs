@@ -18,6 +18,8 @@ mod tests {
     cubecl_reduce::testgen_shared_sum!([f16, f32, f64]);
 
     cubecl_reduce::testgen_reduce!([f16, f32, f64]);
+    cubecl_reduce::testgen_reduce_overflow!();
+    cubecl_reduce::testgen_reduce_benchmark!();
 }
 
 pub mod compiler;
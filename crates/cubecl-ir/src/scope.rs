@@ -38,6 +38,9 @@ pub struct Scope {
     #[cfg_attr(feature = "serde", serde(skip))]
     pub typemap: Rc<RefCell<HashMap<TypeId, StorageType>>>,
     pub runtime_properties: Rc<TargetProperties>,
+    /// The maximum number of times the body of an unrolled loop is expanded, past which it's
+    /// only partially unrolled, see `for_expand_bounded` in the frontend.
+    pub max_unroll_factor: Option<u32>,
 }
 
 /// Debug related fields, most of these are global
@@ -104,6 +107,7 @@ impl Scope {
             },
             typemap: Default::default(),
             runtime_properties: Rc::new(Default::default()),
+            max_unroll_factor: None,
         }
     }
 
@@ -213,6 +217,7 @@ impl Scope {
             debug: self.debug.clone(),
             typemap: self.typemap.clone(),
             runtime_properties: self.runtime_properties.clone(),
+            max_unroll_factor: self.max_unroll_factor,
         }
     }
 
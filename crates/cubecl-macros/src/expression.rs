@@ -89,6 +89,8 @@ pub enum Expression {
     ForLoop {
         range: Box<Expression>,
         unroll: Option<Box<Expression>>,
+        /// Whether the body can be expanded with a runtime index when partially unrolled
+        runtime_index: bool,
         var_name: syn::Ident,
         var_ty: Option<syn::Type>,
         block: Block,
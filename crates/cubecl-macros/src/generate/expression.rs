@@ -287,6 +287,7 @@ impl Expression {
             Expression::ForLoop {
                 range,
                 unroll,
+                runtime_index,
                 var_name,
                 var_ty,
                 block,
@@ -301,12 +302,17 @@ impl Expression {
                     .unwrap_or(quote![false]);
                 let block = context.in_fn_mut(scope, |ctx| block.to_tokens(ctx));
                 let var_ty = var_ty.as_ref().map(|it| quote![: #it]);
+                let for_expand = if *runtime_index {
+                    quote![for_expand_bounded]
+                } else {
+                    quote![for_expand]
+                };
 
                 quote! {
                     {
                         let _range = #range;
                         let _unroll = #unroll;
-                        #for_ty::for_expand(scope, _range, _unroll, |scope, #var_name #var_ty| #block);
+                        #for_ty::#for_expand(scope, _range, _unroll, |scope, #var_name #var_ty| #block);
                     }
                 }
             }
@@ -7,7 +7,7 @@ use crate::{
     statement::Statement,
 };
 
-use super::{helpers::Unroll, statement::parse_pat};
+use super::{helpers::Unroll, statement::parse_pat, unroll::is_runtime_index};
 
 pub fn expand_for_loop(for_loop: ExprForLoop, context: &mut Context) -> syn::Result<Expression> {
     let span = for_loop.span();
@@ -35,6 +35,7 @@ pub fn expand_for_loop(for_loop: ExprForLoop, context: &mut Context) -> syn::Res
     Ok(Expression::ForLoop {
         range: Box::new(right),
         unroll: unroll.map(Box::new),
+        runtime_index: is_runtime_index(&block, &var.ident),
         var_name: var.ident,
         var_ty: var.ty,
         block,
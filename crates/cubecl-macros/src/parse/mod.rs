@@ -11,6 +11,7 @@ pub mod helpers;
 pub mod kernel;
 pub mod operator;
 pub mod statement;
+pub mod unroll;
 
 pub struct StripDefault;
 impl VisitMut for StripDefault {
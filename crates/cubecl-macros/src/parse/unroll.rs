@@ -0,0 +1,222 @@
+use proc_macro2::{Spacing, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::Ident;
+
+use crate::{
+    expression::{Block, Expression},
+    statement::Statement,
+};
+
+/// Whether the body of an unrolled loop over `var` can be expanded with a runtime index, so the
+/// loop can be partially unrolled when it exceeds the max unroll factor.
+///
+/// The body is then expanded fewer times than there are iterations, so the index must only be
+/// used as a runtime value, and the body must not change any comptime state. When in doubt, the
+/// loop is fully unrolled as before.
+pub fn is_runtime_index(block: &Block, var: &Ident) -> bool {
+    RuntimeIndex {
+        aliases: vec![var.clone()],
+        locals: Vec::new(),
+    }
+    .block(block)
+}
+
+struct RuntimeIndex {
+    /// The loop variable, and the locals directly bound to it.
+    aliases: Vec<Ident>,
+    /// The locals declared in the body.
+    locals: Vec<Ident>,
+}
+
+impl RuntimeIndex {
+    fn block(&mut self, block: &Block) -> bool {
+        block.inner.iter().all(|stmt| self.statement(stmt))
+            && block.ret.as_ref().is_none_or(|ret| self.expr(ret))
+    }
+
+    fn statement(&mut self, stmt: &Statement) -> bool {
+        match stmt {
+            Statement::Local { variable, init } => {
+                let valid = match init {
+                    Some(init) if self.is_index(init) => {
+                        self.aliases.push(variable.name.clone());
+                        !variable.is_const
+                    }
+                    Some(init) => self.expr(init),
+                    None => true,
+                };
+                self.locals.push(variable.name.clone());
+                valid
+            }
+            Statement::Expression { expression, .. } => self.expr(expression),
+            Statement::Skip => true,
+        }
+    }
+
+    /// An expression where the index can be a runtime value.
+    fn expr(&mut self, expr: &Expression) -> bool {
+        match expr {
+            Expression::Variable(_)
+            | Expression::Path { .. }
+            | Expression::Literal { .. }
+            | Expression::Keyword { .. }
+            | Expression::Comment { .. }
+            | Expression::Terminate => true,
+            // These would apply to the runtime loop instead of the unrolled one
+            Expression::Break | Expression::Continue(_) | Expression::Return(_) => false,
+            Expression::Binary {
+                left,
+                operator,
+                right,
+                ..
+            } => !(operator.is_assign() && self.is_comptime(left)) && self.both(left, right),
+            Expression::Unary { input, .. } => self.expr(input),
+            Expression::Cast { from, .. } => self.expr(from),
+            Expression::Assignment { left, right, .. } => {
+                !self.is_comptime(left) && self.both(left, right)
+            }
+            Expression::Index { expr, index, .. } => self.both(expr, index),
+            Expression::Block(block) => self.block(block),
+            Expression::If {
+                condition,
+                then_block,
+                else_branch,
+            } => {
+                self.expr(condition)
+                    && self.block(then_block)
+                    && else_branch.as_ref().is_none_or(|it| self.expr(it))
+            }
+            Expression::Loop { block, .. } => self.block(block),
+            Expression::ForLoop {
+                range,
+                unroll,
+                block,
+                ..
+            } => {
+                let range = match unroll {
+                    Some(_) => self.arg(range),
+                    None => self.expr(range),
+                };
+                range && self.block(block)
+            }
+            Expression::Range { start, end, .. } => {
+                self.arg(start) && end.as_ref().is_none_or(|it| self.arg(it))
+            }
+            Expression::Switch {
+                value,
+                cases,
+                default,
+            } => {
+                self.expr(value)
+                    && cases.iter().all(|(_, block)| self.block(block))
+                    && self.block(default)
+            }
+            Expression::FunctionCall { func, args, .. } => {
+                self.arg(func) && args.iter().all(|arg| self.call_arg(arg))
+            }
+            Expression::MethodCall { receiver, args, .. } => {
+                !self.is_outer_mut(receiver)
+                    && self.arg(receiver)
+                    && args.iter().all(|arg| self.call_arg(arg))
+            }
+            Expression::CompilerIntrinsic { args, .. }
+            | Expression::ExpressionMacro { args, .. }
+            | Expression::Array { elements: args, .. }
+            | Expression::Tuple { elements: args } => args.iter().all(|arg| self.call_arg(arg)),
+            Expression::StructInit { fields, .. } => {
+                fields.iter().all(|(_, field)| self.call_arg(field))
+            }
+            Expression::FieldAccess { base, .. } => self.arg(base),
+            Expression::Reference { inner } => self.arg(inner),
+            Expression::Slice { expr, _ranges, .. } => {
+                self.expr(expr) && _ranges.iter().all(|range| self.arg(range))
+            }
+            Expression::ArrayInit { init, len } => self.arg(init) && self.arg(len),
+            Expression::Closure { body, .. } => self.arg(body),
+            Expression::Match { expr, arms, .. } => {
+                self.tokens(expr.to_token_stream()) && arms.iter().all(|arm| self.expr(&arm.expr))
+            }
+            Expression::Verbatim { tokens }
+            | Expression::VerbatimTerminated { tokens }
+            | Expression::RustMacro { tokens, .. } => self.tokens(tokens.clone()),
+        }
+    }
+
+    fn both(&mut self, lhs: &Expression, rhs: &Expression) -> bool {
+        self.expr(lhs) && self.expr(rhs)
+    }
+
+    /// An expression that may need a comptime value, so it can't be the index itself.
+    fn arg(&mut self, expr: &Expression) -> bool {
+        !self.is_index(expr) && self.expr(expr)
+    }
+
+    /// A call argument, which can't be the index nor a reference to a mutable outer value.
+    fn call_arg(&mut self, expr: &Expression) -> bool {
+        match expr {
+            Expression::Reference { inner } if self.is_outer_mut(inner) => false,
+            _ => self.arg(expr),
+        }
+    }
+
+    fn is_index(&self, expr: &Expression) -> bool {
+        match expr {
+            Expression::Variable(var) => self.aliases.contains(&var.name),
+            Expression::Reference { inner } | Expression::Cast { from: inner, .. } => {
+                self.is_index(inner)
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the expression is a comptime value, which can't be assigned in the body.
+    fn is_comptime(&self, expr: &Expression) -> bool {
+        match expr {
+            Expression::Variable(var) => var.is_const,
+            Expression::FieldAccess { base, .. } => self.is_comptime(base),
+            _ => false,
+        }
+    }
+
+    /// Whether the expression is an owned mutable value declared outside of the body, which may
+    /// hold comptime state changed by its methods, like a `Sequence`.
+    fn is_outer_mut(&self, expr: &Expression) -> bool {
+        match expr {
+            Expression::Variable(var) => {
+                var.is_mut && !var.is_ref && !self.locals.contains(&var.name)
+            }
+            Expression::FieldAccess { base, .. } => self.is_outer_mut(base),
+            _ => false,
+        }
+    }
+
+    /// Tokens that can't be parsed, which must neither use the index nor assign anything.
+    fn tokens(&self, tokens: TokenStream) -> bool {
+        let tokens = tokens.into_iter().collect::<Vec<_>>();
+        let joint = |index: usize, chars: &[char]| {
+            matches!(
+                tokens.get(index),
+                Some(TokenTree::Punct(punct))
+                    if punct.spacing() == Spacing::Joint && chars.contains(&punct.as_char())
+            )
+        };
+        let punct = |index: usize, chars: &[char]| {
+            matches!(
+                tokens.get(index),
+                Some(TokenTree::Punct(punct)) if chars.contains(&punct.as_char())
+            )
+        };
+
+        tokens.iter().enumerate().all(|(index, token)| match token {
+            TokenTree::Ident(ident) => !self.aliases.contains(ident),
+            TokenTree::Group(group) => self.tokens(group.stream()),
+            // Only the `=` of `==`, `!=`, `<=`, `>=` and `=>` are allowed
+            TokenTree::Punct(eq) if eq.as_char() == '=' => {
+                let after = joint(index, &['=']) && punct(index + 1, &['=', '>']);
+                let before = index > 0 && joint(index - 1, &['=', '!', '<', '>']);
+                after || before
+            }
+            _ => true,
+        })
+    }
+}
@@ -5,7 +5,7 @@ use crate::components::{
     AccG, LhsG, MatmulPrecision, RhsG,
     global::{self, GlobalConfig},
 };
-use cubecl_std::{CubeOption, tensor::r#virtual::VirtualTensor};
+use cubecl_std::{CubeOption, CubeOptionExpand, tensor::r#virtual::VirtualTensor};
 
 #[derive(CubeType)]
 /// Area of a tensor a cube is responsible of performing matmul
@@ -169,19 +169,50 @@ pub(crate) fn execute_global_matmul<MP: MatmulPrecision, GMM: global::GlobalMatm
 ) {
     let rank = out.rank();
 
-    let batch_out = nth_batch * out.stride(rank - 2) * out.shape(rank - 2);
+    // The batch coordinates are found from the shape of the output, so each tensor can place
+    // its batches at any stride, e.g. interleaved in a larger buffer.
+    let mut batch_out = 0u32.runtime();
     let mut batch_a = 0u32.runtime();
     let mut batch_b = 0u32.runtime();
-    for axis in 0..rank - 2 {
-        let tmp = batch_out / out.stride(axis);
-        batch_a += tmp % a.shape(axis) * a.stride(axis);
-        batch_b += tmp % b.shape(axis) * b.stride(axis);
+    let mut remainder = nth_batch;
+    for i in 0..rank - 2 {
+        let axis = rank - 3 - i;
+        let coordinate = remainder % out.shape(axis);
+        remainder /= out.shape(axis);
+        batch_out += coordinate * out.stride(axis);
+        batch_a += coordinate % a.shape(axis) * a.stride(axis);
+        batch_b += coordinate % b.shape(axis) * b.stride(axis);
+    }
+
+    // The accumulator can be broadcast, e.g. as a per-row bias, so it uses its own strides.
+    let mut batch_c = 0u32.runtime();
+    match c {
+        CubeOption::Some(c) => {
+            let mut remainder = nth_batch;
+            for i in 0..rank - 2 {
+                let axis = rank - 3 - i;
+                let coordinate = remainder % out.shape(axis);
+                remainder /= out.shape(axis);
+                batch_c += coordinate % c.shape(axis) * c.stride(axis);
+            }
+        }
+        CubeOption::None => {}
     }
 
     let tiling = config.tiling_scheme();
     let stage_m = tiling.elements_in_stage_m().runtime();
     let stage_n = tiling.elements_in_stage_n().runtime();
     let k_size = k_range.1 - k_range.0;
+    // Rhs has one row for every `rhs_k_packing` elements of Lhs along k.
+    let rhs_k_packing = tiling.rhs_k_packing;
+    // The offsets can be past the end of the output for cubes covering only padding,
+    // so the remaining rows and columns saturate at zero instead of wrapping around.
+    let rows = out.shape(rank - 2);
+    let cols = out.shape(rank - 1);
+    let stage_bounds = (
+        Min::min(stage_m, Max::max(rows, m_offset) - m_offset),
+        Min::min(stage_n, Max::max(cols, n_offset) - n_offset),
+    );
 
     GMM::execute(
         GMM::init_lhs_global_reader(
@@ -195,14 +226,14 @@ pub(crate) fn execute_global_matmul<MP: MatmulPrecision, GMM: global::GlobalMatm
         GMM::init_rhs_global_reader(
             b,
             batch_b,
-            (k_range.0, n_offset),
-            (k_size, stage_n),
+            (k_range.0 / rhs_k_packing, n_offset),
+            (k_size / rhs_k_packing, stage_n),
             nth_batch,
             config,
         ),
         GMM::init_acc_global_reader(
             c,
-            batch_out,
+            batch_c,
             (m_offset, n_offset),
             (stage_m, stage_n),
             nth_batch,
@@ -218,6 +249,7 @@ pub(crate) fn execute_global_matmul<MP: MatmulPrecision, GMM: global::GlobalMatm
         ),
         acc,
         k_range,
+        stage_bounds,
         config,
     );
 }
@@ -23,6 +23,12 @@ pub enum MatmulAvailabilityError {
     /// The requested cube dimensions are too large for the current runtime or hardware.
     CubeDimTooBig(CubeDim),
 
+    /// The cube needs more units than the runtime or hardware supports.
+    UnitsPerCubeTooBig { units: u32, max: u32 },
+
+    /// The stages need more shared memory, in bytes, than the runtime or hardware supports.
+    SharedMemoryTooBig { size: u32, max: u32 },
+
     /// The requested plane dimension is not supported.
     PlaneDimUnsupported { plane_dim: u32 },
 
@@ -118,6 +124,15 @@ impl Debug for MatmulAvailabilityError {
             MatmulAvailabilityError::CubeDimTooBig(dim) => {
                 writeln!(f, "Cube dim too big {dim:?}")
             }
+            MatmulAvailabilityError::UnitsPerCubeTooBig { units, max } => {
+                writeln!(f, "Too many units per cube {units}, the maximum is {max}")
+            }
+            MatmulAvailabilityError::SharedMemoryTooBig { size, max } => {
+                writeln!(
+                    f,
+                    "Too much shared memory {size} bytes, the maximum is {max} bytes"
+                )
+            }
             MatmulAvailabilityError::PlaneDimUnsupported { plane_dim } => {
                 writeln!(
                     f,
@@ -1,7 +1,7 @@
 use cubecl_core::prelude::*;
 use cubecl_core::{self as cubecl};
 
-use crate::components::global::memory::GlobalMemoryConfig;
+use crate::components::global::memory::{GlobalMemoryConfig, OutputLayout};
 use crate::components::{AccG, error::MatmulSetupError};
 use crate::components::{
     AvailableLineSizes, MatmulPrecision, MatmulProblem, MatrixLayout, TilingScheme,
@@ -16,7 +16,7 @@ use cubecl_std::{
 };
 use std::{fmt::Debug, hash::Hash};
 
-use super::read::ReaderMode;
+use super::read::{ElementwiseTransform, ReaderMode};
 
 /// A family of [matmuls](GlobalMatmul) working with any [precision](MatmulPrecision).
 pub trait GlobalMatmulFamily: Send + Sync + 'static {
@@ -84,6 +84,9 @@ pub trait GlobalMatmul<MP: MatmulPrecision>: 'static + Send + Sync {
     ///
     /// To compute the whole range of k values, use k_range=(0, K) where
     /// K is the K dimension of Lhs and Rhs.
+    ///
+    /// `stage_bounds` is the number of (rows, cols) of the output stage lying inside
+    /// the problem, used to skip padding tiles when the tile iteration is ordered.
     fn execute(
         lhs_reader: Self::LhsGlobalReader,
         rhs_reader: Self::RhsGlobalReader,
@@ -91,6 +94,7 @@ pub trait GlobalMatmul<MP: MatmulPrecision>: 'static + Send + Sync {
         writer: Self::GlobalWriter,
         acc: &mut Self::Accumulators,
         k_range: (u32, u32),
+        stage_bounds: Coords2d,
         #[comptime] config: Self::Config,
     );
 
@@ -162,6 +166,10 @@ pub trait GlobalConfig:
             check_row_bounds: self.check_row_bounds(ident),
             check_col_bounds: self.check_col_bounds(ident),
             matrix_layout: self.matrix_layout(ident),
+            output_layout: match ident {
+                MatmulIdent::Out => self.stage_config().output_layout(),
+                _ => OutputLayout::Strided,
+            },
         }
     }
 
@@ -221,6 +229,18 @@ pub trait GlobalConfig:
         self.stage_config().quantized()
     }
 
+    /// The factor multiplying the elements of `ident` as they are loaded into the stage,
+    /// or `None` if they are loaded as is
+    fn load_scale(&self, ident: MatmulIdent) -> Option<f32> {
+        self.stage_config().load_scale().factor(ident)
+    }
+
+    /// The transform applied to the elements of `ident` as they are loaded into the stage,
+    /// or `None` if they are loaded as is
+    fn load_transform(&self, ident: MatmulIdent) -> Option<ElementwiseTransform> {
+        self.stage_config().load_transform().of(ident)
+    }
+
     /// The [CubeDim] arising from the [TilingScheme]
     fn cube_dim(&self) -> CubeDim;
 }
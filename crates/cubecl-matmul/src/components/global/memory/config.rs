@@ -1,6 +1,8 @@
 use std::{fmt::Debug, hash::Hash};
 
-use crate::components::MatrixLayout;
+use crate::components::{
+    MatmulLineSizes, MatmulProblem, MatrixLayout, TilingScheme, error::MatmulSetupError,
+};
 
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
 pub struct GlobalMemoryConfig {
@@ -12,4 +14,83 @@ pub struct GlobalMemoryConfig {
     pub check_row_bounds: bool,
     pub check_col_bounds: bool,
     pub matrix_layout: MatrixLayout,
+    pub output_layout: OutputLayout,
+}
+
+#[derive(Default, Copy, Clone, Debug, Hash, PartialEq, Eq)]
+/// How the output of the matmul is laid out in global memory.
+///
+/// Inputs are always read with [OutputLayout::Strided].
+pub enum OutputLayout {
+    /// Elements are addressed with the strides of the output tensor.
+    #[default]
+    Strided,
+    /// Each tile is stored contiguously in row-major order, and tiles are ordered
+    /// row-major within each batch, so that consumers reading whole tiles don't need a repack.
+    ///
+    /// Assumes the output tensor is contiguous.
+    Blocked,
+    /// Each column is stored contiguously within each batch, interleaving the rows, so that a
+    /// reduction over `n` right after the matmul, such as the sums of attention scores, reads
+    /// consecutive rows with consecutive units instead of having each unit stride over a row.
+    ///
+    /// Assumes the output tensor is contiguous, and needs an output line size of `1` since lines
+    /// are along `n`. The written output is viewed as a tensor of the output shape with the
+    /// strides of [OutputLayout::column_major_strides], which is how it should be passed to
+    /// the reduction.
+    ColumnMajor,
+}
+
+impl OutputLayout {
+    /// Check that the output of `problem` can be written with this layout.
+    pub fn validate(
+        &self,
+        problem: &MatmulProblem,
+        tiling_scheme: &TilingScheme,
+        line_sizes: &MatmulLineSizes,
+    ) -> Result<(), MatmulSetupError> {
+        if let OutputLayout::Blocked = self {
+            let tile_m = tiling_scheme.tile_size.m() as usize;
+            let tile_n = tiling_scheme.tile_size.n() as usize;
+
+            if !problem.m.is_multiple_of(tile_m) || !problem.n.is_multiple_of(tile_n) {
+                return Err(MatmulSetupError::InvalidConfig(Box::new(format!(
+                    "Error: Blocked output needs m={} and n={} to be multiples of the tile size {tile_m}x{tile_n}.",
+                    problem.m, problem.n
+                ))));
+            }
+
+            if !tile_n.is_multiple_of(line_sizes.out as usize) {
+                return Err(MatmulSetupError::InvalidConfig(Box::new(format!(
+                    "Error: Blocked output needs the tile size n={tile_n} to be a multiple of the output line size {}.",
+                    line_sizes.out
+                ))));
+            }
+        }
+
+        if let OutputLayout::ColumnMajor = self
+            && line_sizes.out != 1
+        {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(format!(
+                "Error: Column-major output needs an output line size of 1, got {}.",
+                line_sizes.out
+            ))));
+        }
+
+        Ok(())
+    }
+
+    /// The strides of an output of the given shape written with [OutputLayout::ColumnMajor].
+    pub fn column_major_strides(shape: &[usize]) -> Vec<usize> {
+        let rank = shape.len();
+        let mut strides = vec![1; rank];
+        strides[rank - 1] = shape[rank - 2];
+
+        let mut stride = shape[rank - 2] * shape[rank - 1];
+        for axis in (0..rank - 2).rev() {
+            strides[axis] = stride;
+            stride *= shape[axis];
+        }
+        strides
+    }
 }
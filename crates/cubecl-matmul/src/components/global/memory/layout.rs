@@ -5,7 +5,7 @@ use cubecl_std::tensor::{
     r#virtual::VirtualTensor,
 };
 
-use crate::components::global::memory::GlobalMemoryConfig;
+use crate::components::global::memory::{GlobalMemoryConfig, OutputLayout};
 
 /// Global layout that uses the last two dimensions and ignores all others.
 #[derive(CubeType, Clone, Copy)]
@@ -38,6 +38,25 @@ impl SimpleGlobalLayout {
             config,
         }
     }
+
+    /// Creates a new 2D layout starting at `batch_offset`, viewing the last two dimensions of
+    /// the tensor as transposed.
+    pub fn new_transposed<T: Numeric, IO: Clone>(
+        tensor: &VirtualTensor<T, IO>,
+        batch_offset: u32,
+        #[comptime] config: GlobalMemoryConfig,
+    ) -> Self {
+        let rank = tensor.rank();
+
+        SimpleGlobalLayout {
+            rows: tensor.shape(rank - 1),
+            stride_row: tensor.stride(rank - 1),
+            columns: tensor.shape(rank - 2),
+            stride_col: tensor.stride(rank - 2),
+            batch_offset,
+            config,
+        }
+    }
 }
 
 #[cube]
@@ -48,7 +67,21 @@ impl Layout for SimpleGlobalLayout {
     fn to_source_pos(&self, coords: Self::Coordinates) -> u32 {
         let line_size = comptime![self.config.global_line_size];
         let (row, col) = coords;
-        let idx = self.batch_offset + row * self.stride_row + col * self.stride_col;
+        let idx = match comptime![self.config.output_layout] {
+            OutputLayout::Strided => {
+                self.batch_offset + row * self.stride_row + col * self.stride_col
+            }
+            OutputLayout::Blocked => {
+                let tile_rows = comptime![self.config.elements_in_tile_row];
+                let tile_cols = comptime![self.config.elements_in_tile_col];
+                let tiles_per_row = self.columns / tile_cols;
+
+                let tile = (row / tile_rows) * tiles_per_row + col / tile_cols;
+                let within_tile = (row % tile_rows) * tile_cols + col % tile_cols;
+                self.batch_offset + tile * comptime![tile_rows * tile_cols] + within_tile
+            }
+            OutputLayout::ColumnMajor => self.batch_offset + col * self.rows + row,
+        };
 
         idx / line_size
     }
@@ -71,6 +71,7 @@ where
         mut out_writer: Self::GlobalWriter,
         acc: &mut Self::Accumulators,
         k_range: (u32, u32),
+        stage_bounds: Coords2d,
         #[comptime] config: Self::Config,
     ) {
         let stage_step = config.tiling_scheme().elements_in_stage_k();
@@ -84,7 +85,8 @@ where
         SMM::load_accumulators(&acc_reader.stage(), acc, config.stage_config());
 
         let (mut lhs_tile, mut rhs_tile) = SMM::init_tile_inputs(config.stage_config());
-        let partition_scheduler = SMM::init_scheduler(config.stage_config());
+        let mut partition_scheduler = SMM::init_scheduler(config.stage_config());
+        partition_scheduler.restrict_to_bounds(stage_bounds, config.tiling_scheme().tile_size);
 
         let lhs_reader_a = lhs_reader.stage(StageBuffer::A);
         let lhs_reader_b = lhs_reader.stage(StageBuffer::B);
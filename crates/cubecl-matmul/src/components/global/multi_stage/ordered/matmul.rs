@@ -69,6 +69,7 @@ where
         mut out_writer: Self::GlobalWriter,
         acc: &mut Self::Accumulators,
         k_range: (u32, u32),
+        stage_bounds: Coords2d,
         #[comptime] config: Self::Config,
     ) {
         let stage_step = config.tiling_scheme().elements_in_stage_k();
@@ -83,7 +84,8 @@ where
         SMM::load_accumulators(&acc_reader, acc, config.stage_config());
 
         let (mut lhs_tile, mut rhs_tile) = SMM::init_tile_inputs(config.stage_config());
-        let partition_scheduler = SMM::init_scheduler(config.stage_config());
+        let mut partition_scheduler = SMM::init_scheduler(config.stage_config());
+        partition_scheduler.restrict_to_bounds(stage_bounds, config.tiling_scheme().tile_size);
 
         let lhs_stage = lhs_reader.stage();
         let rhs_stage_a = rhs_reader.stage(StageBuffer::A);
@@ -1,3 +1,4 @@
+use crate::components::global::shared::{check_flush_after_execution, check_unpacked_rhs};
 use crate::components::global::{
     GlobalWriterFamily,
     read::{SyncFullLoadingStrategy, SyncPartialLoadingStrategy},
@@ -59,6 +60,9 @@ where
         selection: &MatmulSelection,
         line_sizes: &MatmulLineSizes,
     ) -> Result<Self::Config, MatmulSetupError> {
+        check_unpacked_rhs(&selection.tiling_scheme)?;
+        check_flush_after_execution(selection.accumulator_flush)?;
+
         let max_global_readers = selection
             .load_specialization_config
             .has_specialization()
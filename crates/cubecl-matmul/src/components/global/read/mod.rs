@@ -12,8 +12,12 @@
 
 mod layout;
 mod reader;
+mod scale;
 mod strategy;
+mod transform;
 
 pub use layout::*;
 pub use reader::*;
+pub use scale::*;
 pub use strategy::*;
+pub use transform::*;
@@ -0,0 +1,186 @@
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+use cubecl_std::{
+    CubeOption, CubeOptionExpand,
+    tensor::{View, layout::Coords2d, r#virtual::VirtualTensor},
+};
+
+use crate::components::{
+    MatmulIdent, MatrixPrecision, StageIdent,
+    global::{
+        GlobalConfig,
+        memory::{GlobalMemoryConfig, OutputLayout, SimpleGlobalLayout},
+        read::{ZeroGlobalReader, stage::FullStageLayout},
+    },
+    stage::{
+        FilledStage, FilledStageFamily, NoTilingLayout, StageConfig, StageFamily, StridedStage,
+        StridedStageFamily, StridedTilingLayout, TilingLayout,
+    },
+};
+
+/// A family of [AccumulatorReader] implementations that operate with any [precision](MatrixPrecision).
+pub trait AccumulatorReaderFamily: 'static + Send + Sync {
+    /// Stage family of the loaded accumulator
+    type Stage: StageFamily;
+    /// Tiling layout of the loaded accumulator
+    type TilingLayout: TilingLayout;
+    type Reader<IP: MatrixPrecision>: AccumulatorReader<
+            IP,
+            Stage = <Self::Stage as StageFamily>::Stage<IP::Stage, Self::TilingLayout>,
+        >;
+}
+
+#[cube]
+/// Responsible of loading the initial value of the accumulators
+/// from the optional accumulator tensor of the inputs
+pub trait AccumulatorReader<IP: MatrixPrecision>: CubeType + 'static + Send + Sync {
+    /// Stage that holds the loaded accumulator
+    type Stage: CubeType;
+
+    /// Init this reader on the stage at `offset` in the accumulator tensor, if there is one
+    fn init<G: GlobalConfig>(
+        tensor: CubeOption<VirtualTensor<IP::Global>>,
+        batch_offset: u32,
+        offset: Coords2d,
+        slice_size: Coords2d,
+        #[comptime] config: G,
+    ) -> Self;
+
+    /// Load the whole stage, synchronizing the cube when needed so the stage can be read right after
+    fn load_stage<G: GlobalConfig>(this: &mut Self, #[comptime] config: G);
+
+    /// Stage used by this reader
+    fn stage(this: &Self) -> Self::Stage;
+}
+
+/// Accumulators always start at zero, the accumulator tensor isn't supported
+pub struct ZeroGlobalReaderFamily;
+
+impl AccumulatorReaderFamily for ZeroGlobalReaderFamily {
+    type Stage = FilledStageFamily;
+    type TilingLayout = NoTilingLayout;
+    type Reader<IP: MatrixPrecision> = ZeroGlobalReader<IP>;
+}
+
+#[cube]
+impl<IP: MatrixPrecision> AccumulatorReader<IP> for ZeroGlobalReader<IP> {
+    type Stage = FilledStage<IP::Stage>;
+
+    fn init<G: GlobalConfig>(
+        tensor: CubeOption<VirtualTensor<IP::Global>>,
+        _batch_offset: u32,
+        _offset: Coords2d,
+        _slice_size: Coords2d,
+        #[comptime] _config: G,
+    ) -> Self {
+        match tensor {
+            CubeOption::None => ZeroGlobalReader::new(),
+            CubeOption::Some(_) => panic!("Accumulator loading is not yet supported"),
+        }
+    }
+
+    fn load_stage<G: GlobalConfig>(_this: &mut Self, #[comptime] _config: G) {}
+
+    fn stage(_this: &Self) -> Self::Stage {
+        FilledStage::new(IP::Stage::from_int(0))
+    }
+}
+
+/// Accumulators start from the accumulator tensor when there is one, and at zero otherwise
+pub struct AccumulatorGlobalReaderFamily;
+
+impl AccumulatorReaderFamily for AccumulatorGlobalReaderFamily {
+    type Stage = Option<StridedStageFamily>;
+    type TilingLayout = StridedTilingLayout;
+    type Reader<IP: MatrixPrecision> = AccumulatorGlobalReader<IP>;
+}
+
+/// Type of the stage of the accumulator reader
+pub type AccumulatorStage<E> = CubeOption<StridedStage<E, StridedTilingLayout>>;
+
+/// Reads the whole stage of the accumulator tensor, casting it to the stage precision.
+///
+/// This allows resuming a matmul from an accumulator stored in a lower precision than the
+/// accumulation, e.g. an `f16` tensor accumulated in `f32`. It also allows splitting a large k
+/// across several launches, each one starting from the output of the previous one.
+#[derive(CubeType)]
+pub enum AccumulatorGlobalReader<IP: MatrixPrecision> {
+    Some {
+        view: View<Line<IP::Global>, Coords2d>,
+        stage: StridedStage<IP::Stage, StridedTilingLayout>,
+    },
+    None,
+}
+
+#[cube]
+impl<IP: MatrixPrecision> AccumulatorReader<IP> for AccumulatorGlobalReader<IP> {
+    type Stage = AccumulatorStage<IP::Stage>;
+
+    fn init<G: GlobalConfig>(
+        tensor: CubeOption<VirtualTensor<IP::Global>>,
+        batch_offset: u32,
+        offset: Coords2d,
+        slice_size: Coords2d,
+        #[comptime] config: G,
+    ) -> Self {
+        match tensor {
+            CubeOption::Some(tensor) => {
+                let conf = comptime![global_memory_config(config)];
+                let layout = SimpleGlobalLayout::new(&tensor, batch_offset, conf);
+                let view = tensor.view(layout).slice_unchecked(offset, slice_size);
+                let stage = StridedStage::new(
+                    StageIdent::Acc,
+                    config.stage_memory_config(MatmulIdent::Out),
+                );
+
+                AccumulatorGlobalReader::<IP>::new_Some(view, stage)
+            }
+            CubeOption::None => AccumulatorGlobalReader::new_None(),
+        }
+    }
+
+    fn load_stage<G: GlobalConfig>(this: &mut Self, #[comptime] config: G) {
+        match this {
+            AccumulatorGlobalReader::Some { view, stage } => {
+                let conf = comptime![global_memory_config(config)];
+                let line_size = conf.global_line_size;
+                let num_stage_lines =
+                    comptime!(conf.elements_in_stage_row * conf.elements_in_stage_col / line_size);
+                let unit_count = config.stage_config().num_main_flow_planes() * config.plane_dim();
+                let num_loads_per_unit = comptime!(num_stage_lines.div_ceil(unit_count));
+
+                let unit_id = UNIT_POS_Y * config.plane_dim() + UNIT_POS_X;
+                let view = view.view(FullStageLayout::new(conf));
+                let mut slice = stage.as_slice_mut(line_size);
+
+                #[unroll]
+                for i in 0..num_loads_per_unit {
+                    let unit_pos = unit_id + i * unit_count;
+
+                    if unit_pos < num_stage_lines {
+                        let read_line = view.read_checked(unit_pos * line_size);
+                        slice[unit_pos] = Line::cast_from(read_line);
+                    }
+                }
+            }
+            AccumulatorGlobalReader::None => {}
+        }
+
+        sync_cube();
+    }
+
+    fn stage(this: &Self) -> Self::Stage {
+        match this {
+            AccumulatorGlobalReader::Some { stage, .. } => CubeOption::new_Some(*stage),
+            AccumulatorGlobalReader::None => CubeOption::new_None(),
+        }
+    }
+}
+
+/// The accumulator is read like the output, except it always uses the strides of its tensor
+fn global_memory_config<G: GlobalConfig>(config: G) -> GlobalMemoryConfig {
+    GlobalMemoryConfig {
+        output_layout: OutputLayout::Strided,
+        ..config.global_memory_config(MatmulIdent::Out)
+    }
+}
@@ -1,3 +1,4 @@
+mod accumulator_reader;
 mod async_full_reader;
 mod async_partial_reader;
 mod fill_reader;
@@ -6,6 +7,7 @@ mod sync_full_reader;
 mod sync_partial_reader;
 mod tma_reader;
 
+pub use accumulator_reader::*;
 pub use async_full_reader::*;
 pub use async_partial_reader::*;
 pub use fill_reader::*;
@@ -0,0 +1,82 @@
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+
+use crate::components::{MatmulIdent, error::MatmulSetupError};
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+/// Constant factors multiplying the elements of Lhs and Rhs as they are loaded into the stage,
+/// such as the `1/sqrt(d)` applied to the query in scaled dot-product attention.
+///
+/// The product is computed in `f32` before the conversion to the stage precision.
+/// Only synchronous readers can scale, since asynchronous copies don't go through the units.
+pub struct LoadScale {
+    // Bits of the factors, so the config can be hashed.
+    lhs: u32,
+    rhs: u32,
+}
+
+impl Default for LoadScale {
+    fn default() -> Self {
+        Self::new(1.0, 1.0)
+    }
+}
+
+impl LoadScale {
+    /// Scale Lhs by `lhs` and Rhs by `rhs`.
+    pub fn new(lhs: f32, rhs: f32) -> Self {
+        Self {
+            lhs: lhs.to_bits(),
+            rhs: rhs.to_bits(),
+        }
+    }
+
+    /// Scale Lhs by `scale`, leaving Rhs untouched.
+    pub fn lhs(scale: f32) -> Self {
+        Self::new(scale, 1.0)
+    }
+
+    /// Scale Rhs by `scale`, leaving Lhs untouched.
+    pub fn rhs(scale: f32) -> Self {
+        Self::new(1.0, scale)
+    }
+
+    /// The factor of `ident`, or `None` if it is `1` and the elements are loaded as is.
+    pub fn factor(&self, ident: MatmulIdent) -> Option<f32> {
+        let factor = match ident {
+            MatmulIdent::Lhs => f32::from_bits(self.lhs),
+            MatmulIdent::Rhs => f32::from_bits(self.rhs),
+            MatmulIdent::Out => return None,
+        };
+
+        (factor != 1.0).then_some(factor)
+    }
+
+    /// Check that both factors are finite.
+    pub fn validate(&self) -> Result<(), MatmulSetupError> {
+        for ident in [MatmulIdent::Lhs, MatmulIdent::Rhs] {
+            let factor = self.factor(ident).unwrap_or(1.0);
+            if !factor.is_finite() {
+                return Err(MatmulSetupError::InvalidConfig(Box::new(format!(
+                    "Error: The load scale of {ident:?} must be finite, got {factor}."
+                ))));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cube]
+/// Convert a line read from global memory to the stage precision, multiplying it by `scale`
+/// if there is one.
+pub fn scale_line<EG: Numeric, ES: Numeric>(
+    line: Line<EG>,
+    #[comptime] scale: Option<f32>,
+) -> Line<ES> {
+    if comptime![scale.is_some()] {
+        let factor = Line::empty(line.size()).fill(f32::new(comptime![scale.unwrap()]));
+        Line::cast_from(Line::<f32>::cast_from(line) * factor)
+    } else {
+        Line::cast_from(line)
+    }
+}
@@ -10,7 +10,7 @@ use crate::components::{
 use cubecl_core::prelude::*;
 use cubecl_core::{self as cubecl, prelude::barrier::BarrierLevel};
 
-use super::{AsyncLoadingJob, LoadingValidation};
+use super::{AsyncLoadingJob, LoadingValidation, validate_unscaled_load};
 
 #[derive(CubeType, Clone, Copy)]
 /// Loads global memory into the stage without layout change,  
@@ -20,8 +20,8 @@ use super::{AsyncLoadingJob, LoadingValidation};
 pub struct AsyncFullCooperativeLoading {}
 
 impl LoadingValidation for AsyncFullCooperativeLoading {
-    fn check<C: GlobalConfig>(_config: &C, _ident: MatmulIdent) -> Result<(), InvalidConfigError> {
-        Ok(())
+    fn check<C: GlobalConfig>(config: &C, ident: MatmulIdent) -> Result<(), InvalidConfigError> {
+        validate_unscaled_load(config, ident)
     }
 }
 
@@ -12,7 +12,7 @@ use crate::components::{
 use cubecl_core::prelude::*;
 use cubecl_core::{self as cubecl, prelude::barrier::BarrierLevel};
 
-use super::{AsyncLoadingJob, LoadingValidation};
+use super::{AsyncLoadingJob, LoadingValidation, validate_unscaled_load};
 
 #[derive(CubeType, Clone, Copy)]
 /// Loads the content of all tiles in the stage memory using all planes,
@@ -24,6 +24,8 @@ pub struct AsyncFullCyclicLoading<T: TilingOrder> {
 
 impl<T: TilingOrder> LoadingValidation for AsyncFullCyclicLoading<T> {
     fn check<C: GlobalConfig>(config: &C, ident: MatmulIdent) -> Result<(), InvalidConfigError> {
+        validate_unscaled_load(config, ident)?;
+
         let total_units = config.num_loading_planes(ident) * config.plane_dim();
         let num_slices = config.tiling_scheme().elements_in_tile_row(ident)
             * config.tiling_scheme().tiles_in_stage(ident);
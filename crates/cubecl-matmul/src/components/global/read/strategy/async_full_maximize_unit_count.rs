@@ -10,7 +10,7 @@ use crate::components::{
 use cubecl_core::prelude::*;
 use cubecl_core::{self as cubecl, prelude::barrier::BarrierLevel};
 
-use super::{AsyncLoadingJob, LoadingValidation};
+use super::{AsyncLoadingJob, LoadingValidation, validate_unscaled_load};
 
 #[derive(CubeType, Clone, Copy)]
 /// Executes one memcpy_async call per unit.
@@ -19,6 +19,8 @@ pub struct AsyncFullMaximizeUnitCountLoading {}
 
 impl LoadingValidation for AsyncFullMaximizeUnitCountLoading {
     fn check<C: GlobalConfig>(config: &C, ident: MatmulIdent) -> Result<(), InvalidConfigError> {
+        validate_unscaled_load(config, ident)?;
+
         let matrix_layout = config.matrix_layout(ident);
         let line_size = config.global_line_size(ident);
 
@@ -10,7 +10,7 @@ use crate::components::{
 use cubecl_core::prelude::*;
 use cubecl_core::{self as cubecl, prelude::barrier::BarrierLevel};
 
-use super::{AsyncLoadingJob, LoadingValidation};
+use super::{AsyncLoadingJob, LoadingValidation, validate_unscaled_load};
 
 #[derive(CubeType, Clone, Copy)]
 /// Executes one `memcpy_async` call per contiguous slice.
@@ -18,8 +18,8 @@ use super::{AsyncLoadingJob, LoadingValidation};
 pub struct AsyncPartialMaximizeSliceLengthLoading {}
 
 impl LoadingValidation for AsyncPartialMaximizeSliceLengthLoading {
-    fn check<C: GlobalConfig>(_config: &C, _ident: MatmulIdent) -> Result<(), InvalidConfigError> {
-        Ok(())
+    fn check<C: GlobalConfig>(config: &C, ident: MatmulIdent) -> Result<(), InvalidConfigError> {
+        validate_unscaled_load(config, ident)
     }
 }
 
@@ -52,6 +52,26 @@ pub trait LoadingValidation {
     fn check<C: GlobalConfig>(config: &C, ident: MatmulIdent) -> Result<(), InvalidConfigError>;
 }
 
+/// Fails if `ident` is [scaled](crate::components::global::read::LoadScale) or
+/// [transformed](crate::components::global::read::LoadTransform) on load,
+/// which asynchronous copies can't do since the data never goes through the units
+pub fn validate_unscaled_load<C: GlobalConfig>(
+    config: &C,
+    ident: MatmulIdent,
+) -> Result<(), InvalidConfigError> {
+    if let Some(scale) = config.load_scale(ident) {
+        return Err(Box::new(format!(
+            "Asynchronous loading can't scale {ident:?} by {scale} on load, use a synchronous loading strategy."
+        )));
+    }
+    match config.load_transform(ident) {
+        Some(transform) => Err(Box::new(format!(
+            "Asynchronous loading can't apply {transform:?} to {ident:?} on load, use a synchronous loading strategy."
+        ))),
+        None => Ok(()),
+    }
+}
+
 /// Dummy trait implementation
 pub struct NoLoadingValidation {}
 impl LoadingValidation for NoLoadingValidation {
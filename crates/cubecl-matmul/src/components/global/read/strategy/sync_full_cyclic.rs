@@ -2,7 +2,7 @@ use std::marker::PhantomData;
 
 use crate::components::global::memory::GlobalIterator;
 use crate::components::global::multi_stage::LoadMaxRoundPlaneCount;
-use crate::components::global::read::{SyncFullLoadingStrategy, tiled::TiledLayout};
+use crate::components::global::read::{SyncFullLoadingStrategy, load_line, tiled::TiledLayout};
 use crate::components::global::{GlobalConfig, RoleRule};
 use crate::components::stage::{ContiguousTilingLayout, StridedStage, TilingOrder};
 use crate::components::{InvalidConfigError, MatmulIdent};
@@ -161,6 +161,13 @@ pub(crate) fn load_and_store_line<IP: MatrixPrecision, TO: TilingOrder, G: Globa
     );
 
     let line_read = view.read_checked((tile, pos_within_tile));
-
-    stage.as_slice_mut(job.line_size)[unit_position / job.line_size] = Line::cast_from(line_read);
+    let in_bounds = view.is_in_bounds((tile, pos_within_tile));
+
+    stage.as_slice_mut(job.line_size)[unit_position / job.line_size] =
+        load_line::<IP::Global, IP::Stage>(
+            line_read,
+            in_bounds,
+            comptime!(config.load_transform(job.ident)),
+            comptime!(config.load_scale(job.ident)),
+        );
 }
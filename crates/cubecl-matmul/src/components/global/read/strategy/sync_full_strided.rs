@@ -1,6 +1,6 @@
 use crate::components::global::memory::GlobalIterator;
 use crate::components::global::multi_stage::LoadMaxRoundPlaneCount;
-use crate::components::global::read::{SyncFullLoadingStrategy, stage::FullStageLayout};
+use crate::components::global::read::{SyncFullLoadingStrategy, load_line, stage::FullStageLayout};
 use crate::components::global::{GlobalConfig, RoleRule};
 use crate::components::stage::{StridedStage, StridedTilingLayout};
 use crate::components::{InvalidConfigError, MatmulIdent};
@@ -103,8 +103,14 @@ impl<IP: MatrixPrecision> LoadingJob<IP, StridedTilingLayout> for SyncFullStride
         let view = global_iter.view().view(layout);
 
         let line_read = view.read_checked(unit_position * this.line_size);
-
-        stage.as_slice_mut(this.line_size)[unit_position] = Line::cast_from(line_read);
+        let in_bounds = view.is_in_bounds(unit_position * this.line_size);
+
+        stage.as_slice_mut(this.line_size)[unit_position] = load_line::<IP::Global, IP::Stage>(
+            line_read,
+            in_bounds,
+            comptime!(config.load_transform(this.ident)),
+            comptime!(config.load_scale(this.ident)),
+        );
     }
 
     fn task_count(this: &Self) -> comptime_type!(u32) {
@@ -1,7 +1,7 @@
 use std::marker::PhantomData;
 
 use crate::components::global::multi_stage::LoadMaxRoundPlaneCount;
-use crate::components::global::read::SyncFullLoadingStrategy;
+use crate::components::global::read::{SyncFullLoadingStrategy, load_line};
 use crate::components::global::{RoleRule, read::tiled::TiledLayout};
 use crate::components::{
     FormattedConfigError, InvalidConfigError, MatmulIdent, MatrixPrecision, TilingScheme,
@@ -180,9 +180,15 @@ impl SyncFullTilewiseJob {
         let view = global_iter.view().view(layout);
 
         let line_read = view.read_checked((tile, line_index_within_tile * this.line_size));
+        let in_bounds = view.is_in_bounds((tile, line_index_within_tile * this.line_size));
 
         let offset = this.num_lines_to_skip + line_index_within_tile + num_lines_to_skip_local;
 
-        stage.as_slice_mut(this.line_size)[offset] = Line::cast_from(line_read);
+        stage.as_slice_mut(this.line_size)[offset] = load_line::<IP::Global, IP::Stage>(
+            line_read,
+            in_bounds,
+            comptime!(config.load_transform(this.ident)),
+            comptime!(config.load_scale(this.ident)),
+        );
     }
 }
@@ -2,7 +2,7 @@ use std::marker::PhantomData;
 
 use crate::components::global::memory::GlobalIterator;
 use crate::components::global::multi_stage::LoadMaxRoundPlaneCount;
-use crate::components::global::read::{SyncPartialLoadingStrategy, tiled::TiledLayout};
+use crate::components::global::read::{SyncPartialLoadingStrategy, load_line, tiled::TiledLayout};
 use crate::components::global::{GlobalConfig, RoleRule};
 use crate::components::stage::{ContiguousTilingLayout, StridedStage, TilingOrder};
 use crate::components::{InvalidConfigError, MatmulIdent, MatrixPrecision, TilingScheme};
@@ -212,6 +212,7 @@ pub(crate) fn load_and_store_line<IP: MatrixPrecision, TO: TilingOrder, G: Globa
     let view = global_iter.view().view(layout);
 
     let line_read = view.read_checked((tile, pos_within_tile));
+    let in_bounds = view.is_in_bounds((tile, pos_within_tile));
 
     let nth_tile_in_stage = TO::to_nth_tile(
         tile,
@@ -226,5 +227,10 @@ pub(crate) fn load_and_store_line<IP: MatrixPrecision, TO: TilingOrder, G: Globa
         .as_slice_mut(line_size)
         .slice_mut(tile_start, tile_end);
 
-    tile_slice[pos_within_tile / line_size] = Line::cast_from(line_read);
+    tile_slice[pos_within_tile / line_size] = load_line::<IP::Global, IP::Stage>(
+        line_read,
+        in_bounds,
+        comptime!(config.load_transform(job.ident)),
+        comptime!(config.load_scale(job.ident)),
+    );
 }
@@ -1,7 +1,7 @@
 use std::marker::PhantomData;
 
 use crate::components::global::multi_stage::LoadMaxRoundPlaneCount;
-use crate::components::global::read::SyncPartialLoadingStrategy;
+use crate::components::global::read::{SyncPartialLoadingStrategy, load_line};
 use crate::components::global::{RoleRule, read::tiled::TiledLayout};
 use crate::components::stage::TilingOrderEnum;
 use crate::components::{
@@ -236,9 +236,15 @@ impl SyncPartialTilewiseJob {
         let view = global_iter.view().view(layout);
 
         let line_read = view.read_checked((tile, line_index_within_tile * this.line_size));
+        let in_bounds = view.is_in_bounds((tile, line_index_within_tile * this.line_size));
 
         let offset = line_index_within_tile + num_lines_to_skip_global;
 
-        stage.as_slice_mut(this.line_size)[offset] = Line::cast_from(line_read);
+        stage.as_slice_mut(this.line_size)[offset] = load_line::<IP::Global, IP::Stage>(
+            line_read,
+            in_bounds,
+            comptime!(config.load_transform(this.ident)),
+            comptime!(config.load_scale(this.ident)),
+        );
     }
 }
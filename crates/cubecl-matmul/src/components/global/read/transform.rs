@@ -0,0 +1,139 @@
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+
+use crate::components::{MatmulIdent, error::MatmulSetupError};
+
+use super::scale_line;
+
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+/// An elementwise function applied to the elements of an input as they are loaded into the stage.
+pub enum ElementwiseTransform {
+    /// The elements are loaded as is.
+    #[default]
+    Identity,
+    /// `max(x, 0)`
+    Relu,
+    /// `|x|`
+    Abs,
+    /// `x + c`, built with [offset](Self::offset).
+    Offset {
+        /// Bits of `c`, so the config can be hashed.
+        bits: u32,
+    },
+}
+
+impl ElementwiseTransform {
+    /// Add the constant `c` to the elements.
+    pub fn offset(c: f32) -> Self {
+        Self::Offset { bits: c.to_bits() }
+    }
+
+    /// The transformed value of `x`, as computed on the host.
+    pub fn apply(&self, x: f32) -> f32 {
+        match self {
+            Self::Identity => x,
+            Self::Relu => x.max(0.0),
+            Self::Abs => x.abs(),
+            Self::Offset { .. } => x + self.added(),
+        }
+    }
+
+    /// The constant added by an offset, `0` for other transforms.
+    fn added(&self) -> f32 {
+        match self {
+            Self::Offset { bits } => f32::from_bits(*bits),
+            _ => 0.0,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+/// Elementwise transforms applied to the elements of Lhs and Rhs as they are loaded into the
+/// stage, fusing an operation such as `relu(lhs)` or `rhs + c` into the matmul instead of
+/// running it in a separate kernel.
+///
+/// The transform is computed in `f32`, before the [load scale](super::LoadScale) and the
+/// conversion to the stage precision. Only synchronous readers can transform, since
+/// asynchronous copies don't go through the units.
+pub struct LoadTransform {
+    lhs: ElementwiseTransform,
+    rhs: ElementwiseTransform,
+}
+
+impl LoadTransform {
+    /// Transform Lhs with `lhs` and Rhs with `rhs`.
+    pub fn new(lhs: ElementwiseTransform, rhs: ElementwiseTransform) -> Self {
+        Self { lhs, rhs }
+    }
+
+    /// Transform Lhs with `transform`, leaving Rhs untouched.
+    pub fn lhs(transform: ElementwiseTransform) -> Self {
+        Self::new(transform, ElementwiseTransform::Identity)
+    }
+
+    /// Transform Rhs with `transform`, leaving Lhs untouched.
+    pub fn rhs(transform: ElementwiseTransform) -> Self {
+        Self::new(ElementwiseTransform::Identity, transform)
+    }
+
+    /// The transform of `ident`, or `None` if the elements are loaded as is.
+    pub fn of(&self, ident: MatmulIdent) -> Option<ElementwiseTransform> {
+        let transform = match ident {
+            MatmulIdent::Lhs => self.lhs,
+            MatmulIdent::Rhs => self.rhs,
+            MatmulIdent::Out => return None,
+        };
+
+        (transform != ElementwiseTransform::Identity).then_some(transform)
+    }
+
+    /// Check that the offsets are finite.
+    pub fn validate(&self) -> Result<(), MatmulSetupError> {
+        for ident in [MatmulIdent::Lhs, MatmulIdent::Rhs] {
+            let offset = self.of(ident).unwrap_or_default().added();
+            if !offset.is_finite() {
+                return Err(MatmulSetupError::InvalidConfig(Box::new(format!(
+                    "Error: The load offset of {ident:?} must be finite, got {offset}."
+                ))));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cube]
+/// Convert a line read from global memory to the stage precision, applying `transform` and then
+/// multiplying it by `scale`, if there are any.
+///
+/// A line outside the bounds of the input, which was filled with zeros by the checked read, is
+/// kept at zero rather than transformed, so the padding of a ragged input adds nothing to the
+/// matmul even with a transform such as an offset.
+pub fn load_line<EG: Numeric, ES: Numeric>(
+    line: Line<EG>,
+    in_bounds: bool,
+    #[comptime] transform: Option<ElementwiseTransform>,
+    #[comptime] scale: Option<f32>,
+) -> Line<ES> {
+    if comptime![transform.is_some()] {
+        let transformed =
+            transform_line(Line::<f32>::cast_from(line), comptime![transform.unwrap()]);
+        let zero = Line::empty(line.size()).fill(f32::from_int(0));
+        let line = select_many(Line::empty(line.size()).fill(in_bounds), transformed, zero);
+        scale_line::<f32, ES>(line, scale)
+    } else {
+        scale_line::<EG, ES>(line, scale)
+    }
+}
+
+#[cube]
+fn transform_line(line: Line<f32>, #[comptime] transform: ElementwiseTransform) -> Line<f32> {
+    if comptime![transform == ElementwiseTransform::Relu] {
+        let zero = Line::empty(line.size()).fill(f32::from_int(0));
+        select_many(line.greater_than(zero), line, zero)
+    } else if comptime![transform == ElementwiseTransform::Abs] {
+        Line::abs(line)
+    } else {
+        line + Line::empty(line.size()).fill(f32::new(comptime![transform.added()]))
+    }
+}
@@ -2,6 +2,7 @@ use crate::components::{
     MatmulIdent, MatmulLineSizes, TilingScheme,
     error::MatmulSetupError,
     global::{GlobalConfig, multi_stage::LoadMaxRoundPlaneCount},
+    stage::AccumulatorFlush,
 };
 
 pub(crate) fn shared_global_config_validation<G: GlobalConfig>(
@@ -22,6 +23,30 @@ pub(crate) fn shared_global_config_validation<G: GlobalConfig>(
     Ok(config)
 }
 
+/// Rejects the [packed Rhs rows](TilingScheme::rhs_k_packing) in the global matmuls whose
+/// readers step through Lhs and Rhs by the same k.
+pub(crate) fn check_unpacked_rhs(tiling_scheme: &TilingScheme) -> Result<(), MatmulSetupError> {
+    if tiling_scheme.rhs_k_packing != 1 {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(
+            "Packed Rhs rows are only supported by the simple global matmul",
+        )));
+    }
+    Ok(())
+}
+
+/// Rejects the [interleaved accumulator flush](AccumulatorFlush::Interleaved) in the global
+/// matmuls which always write their accumulators after the execution.
+pub(crate) fn check_flush_after_execution(
+    accumulator_flush: AccumulatorFlush,
+) -> Result<(), MatmulSetupError> {
+    if accumulator_flush == AccumulatorFlush::Interleaved {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(
+            "Interleaved accumulator flush is only supported by the simple global matmul",
+        )));
+    }
+    Ok(())
+}
+
 /// Maximal number of planes each reader can handle to divide its workload evenly
 pub struct MaxGlobalReaderPlanes {
     pub lhs: u32,
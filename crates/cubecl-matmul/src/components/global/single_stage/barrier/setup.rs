@@ -1,3 +1,4 @@
+use crate::components::global::shared::{check_flush_after_execution, check_unpacked_rhs};
 use std::marker::PhantomData;
 
 use crate::components::global::single_stage::barrier::SimpleBarrierConfig;
@@ -51,6 +52,9 @@ where
         selection: &MatmulSelection,
         line_sizes: &MatmulLineSizes,
     ) -> Result<Self::Config, MatmulSetupError> {
+        check_unpacked_rhs(&selection.tiling_scheme)?;
+        check_flush_after_execution(selection.accumulator_flush)?;
+
         let stage_config = SMM::setup::<MP, R>(
             client,
             problem,
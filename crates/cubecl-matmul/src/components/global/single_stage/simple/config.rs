@@ -9,7 +9,7 @@ use crate::components::{
         read::{LoadingValidation, ReaderMode},
         shared::shared_global_config_validation,
     },
-    stage::{self, StageMemoryConfig},
+    stage::{self, AccumulatorFlush, PartitionBuffering, StageMemoryConfig},
 };
 
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
@@ -23,6 +23,7 @@ pub struct SimpleConfig<S: stage::StageConfig> {
     pub k_step: u32,
     precompute_job: LoadingPrecomputeStrategy,
     reader_mode: ReaderMode,
+    accumulator_flush: AccumulatorFlush,
 }
 
 impl<S: stage::StageConfig> global::GlobalConfig for SimpleConfig<S> {
@@ -114,8 +115,9 @@ impl<S: stage::StageConfig> SimpleConfig<S> {
     /// - a reader is invalid
     /// - CubeDim is too big
     /// - Barriers are not available
+    /// - the accumulators are flushed interleaved without [PartitionBuffering::RegisterLhs]
     pub fn new<LL: LoadingValidation, RL: LoadingValidation, MP: MatmulPrecision, R: Runtime>(
-        _client: &ComputeClient<R::Server, R::Channel>,
+        client: &ComputeClient<R::Server, R::Channel>,
         stage_config: S,
         num_planes: u32,
         check_m_bounds: bool,
@@ -124,7 +126,18 @@ impl<S: stage::StageConfig> SimpleConfig<S> {
         k_step: u32,
         precompute_job: LoadingPrecomputeStrategy,
         reader_mode: ReaderMode,
+        accumulator_flush: AccumulatorFlush,
     ) -> Result<Self, MatmulSetupError> {
+        if accumulator_flush == AccumulatorFlush::Interleaved {
+            if stage_config.partition_buffering() != PartitionBuffering::RegisterLhs {
+                return Err(MatmulSetupError::InvalidConfig(Box::new(
+                    "Interleaved accumulator flush requires the RegisterLhs partition buffering",
+                )));
+            }
+            // The output stage is written while the input stages are still read
+            stage_config.validate_against_device::<MP, R>(client)?;
+        }
+
         Self {
             stage_config,
             num_planes,
@@ -134,10 +147,16 @@ impl<S: stage::StageConfig> SimpleConfig<S> {
             k_step,
             precompute_job,
             reader_mode,
+            accumulator_flush,
         }
         .validate::<LL, RL>()
     }
 
+    /// When the accumulators are written to the output stage
+    pub fn accumulator_flush(&self) -> AccumulatorFlush {
+        self.accumulator_flush
+    }
+
     fn validate<LL: LoadingValidation, RL: LoadingValidation>(
         self,
     ) -> Result<Self, MatmulSetupError> {
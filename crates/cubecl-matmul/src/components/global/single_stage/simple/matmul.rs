@@ -1,17 +1,17 @@
 use crate::components::{
-    AccG, AccS, LhsG, LhsS, MatmulIdent, MatmulPrecision, RhsG, RhsS,
+    AccG, LhsG, LhsS, MatmulIdent, MatmulPrecision, RhsG, RhsS,
     global::{
         GlobalMatmul, GlobalWriter,
         memory::SimpleGlobalLayout,
-        read::{SyncFullLoadingStrategy, SyncFullStageGlobalReader, ZeroGlobalReader},
+        read::{AccumulatorReader, SyncFullLoadingStrategy, SyncFullStageGlobalReader},
         single_stage::simple::SimpleConfig,
     },
-    stage::{FilledStage, StageMatmul, StridedStage},
+    stage::{AccumulatorFlush, StageMatmul, StridedStage},
 };
 use cubecl_core as cubecl;
 use cubecl_core::prelude::*;
 use cubecl_std::{
-    CubeOption, CubeOptionExpand,
+    CubeOption,
     tensor::{layout::Coords2d, r#virtual::VirtualTensor},
 };
 use std::marker::PhantomData;
@@ -28,52 +28,69 @@ pub struct SimpleMatmul<
     LL: SyncFullLoadingStrategy,
     RL: SyncFullLoadingStrategy,
     GW: GlobalWriter<MP::Acc>,
+    AR: AccumulatorReader<MP::Acc>,
 > {
-    _phantom: PhantomData<(MP, SMM, LL, RL, GW)>,
+    _phantom: PhantomData<(MP, SMM, LL, RL, GW, AR)>,
 }
 
 #[cube]
-impl<MP: MatmulPrecision, SMM, LL, RL, GW> GlobalMatmul<MP> for SimpleMatmul<MP, SMM, LL, RL, GW>
+impl<MP: MatmulPrecision, SMM, LL, RL, GW, AR> GlobalMatmul<MP>
+    for SimpleMatmul<MP, SMM, LL, RL, GW, AR>
 where
     SMM: StageMatmul<
             MP,
             LhsStage = StridedStage<LhsS<MP>, LL::TilingLayout>,
             RhsStage = StridedStage<RhsS<MP>, RL::TilingLayout>,
-            AccStage = FilledStage<AccS<MP>>,
+            AccStage = AR::Stage,
             OutStage = GW::Stage,
         >,
     LL: SyncFullLoadingStrategy,
     RL: SyncFullLoadingStrategy,
     GW: GlobalWriter<MP::Acc>,
+    AR: AccumulatorReader<MP::Acc>,
 {
     type Config = SimpleConfig<SMM::Config>;
     type LhsGlobalReader = SyncFullStageGlobalReader<MP::Lhs, Self::Config, LL>;
     type RhsGlobalReader = SyncFullStageGlobalReader<MP::Rhs, Self::Config, RL>;
-    type AccGlobalReader = ZeroGlobalReader<MP::Acc>;
+    type AccGlobalReader = AR;
     type GlobalWriter = GW;
     type Accumulators = SMM::Accumulators;
 
     fn execute(
         mut lhs_reader: Self::LhsGlobalReader,
         mut rhs_reader: Self::RhsGlobalReader,
-        acc_reader: Self::AccGlobalReader,
+        mut acc_reader: Self::AccGlobalReader,
         mut out_writer: Self::GlobalWriter,
         acc: &mut Self::Accumulators,
         k_range: (u32, u32),
+        stage_bounds: Coords2d,
         #[comptime] config: Self::Config,
     ) {
         let k_step = config.k_step;
         let range = k_range.1 - k_range.0;
         let num_loops = range.div_ceil(k_step);
+        // Elements along k not yet executed, the last stage may only be partially filled.
+        let mut k_remaining = range;
 
         let (mut lhs_tile, mut rhs_tile) = SMM::init_tile_inputs(config.stage_config());
-        let partition_scheduler = SMM::init_scheduler(config.stage_config());
+        let mut partition_scheduler = SMM::init_scheduler(config.stage_config());
+        partition_scheduler.restrict_to_bounds(stage_bounds, config.tiling_scheme().tile_size);
 
-        SMM::load_accumulators(&acc_reader.stage(), acc, config.stage_config());
+        // The accumulator is fully read before the loop synchronizes the cube, and each cube only
+        // writes the output it read, so the accumulator tensor may be the output itself.
+        AR::load_stage::<Self::Config>(&mut acc_reader, config);
+        SMM::load_accumulators(&AR::stage(&acc_reader), acc, config.stage_config());
 
         let lhs_stage = &lhs_reader.stage();
         let rhs_stage = &rhs_reader.stage();
 
+        // The last stage is executed while writing the accumulators when interleaved
+        let interleaved = comptime![config.accumulator_flush() == AccumulatorFlush::Interleaved];
+        let mut num_loops = num_loops;
+        if interleaved {
+            num_loops -= 1;
+        }
+
         for _ in 0..num_loops {
             sync_cube();
 
@@ -82,6 +99,7 @@ where
 
             sync_cube();
 
+            partition_scheduler.restrict_k_to_bounds(k_remaining);
             SMM::execute(
                 lhs_stage,
                 rhs_stage,
@@ -94,29 +112,56 @@ where
 
             lhs_reader.advance_view();
             rhs_reader.advance_view();
+            k_remaining -= Min::min(k_step, k_remaining);
         }
 
-        // Frees input stages for reuse, so the output stage can be allocated into the same
-        // range. The `sync_cube` is required to ensure other planes are done reading from the stages.
-        //
-        // This is currently very unintuitive, because while the stage already exists, it actually
-        // isn't allocated until it's used (by writing to it). We should eventually separate the
-        // write call into a different function and defer creating the writer until after the stages
-        // are freed to make the order of operations more clear.
-        sync_cube();
-        lhs_reader.free_stage();
-        rhs_reader.free_stage();
-
-        let mut out_stage = Self::GlobalWriter::stage(&out_writer);
-
-        SMM::write_results::<Self::GlobalWriter, Self::Config>(
-            acc,
-            &mut out_stage,
-            &mut out_writer,
-            &partition_scheduler,
-            config.stage_config(),
-            config,
-        );
+        if interleaved {
+            sync_cube();
+
+            lhs_reader.load_stage(config);
+            rhs_reader.load_stage(config);
+
+            sync_cube();
+
+            // The input stages are still read, so the output stage can't reuse their memory.
+            let mut out_stage = Self::GlobalWriter::stage(&out_writer);
+
+            partition_scheduler.restrict_k_to_bounds(k_remaining);
+            SMM::execute_and_write_results::<Self::GlobalWriter, Self::Config>(
+                lhs_stage,
+                rhs_stage,
+                &mut lhs_tile,
+                &mut rhs_tile,
+                acc,
+                &mut out_stage,
+                &mut out_writer,
+                &partition_scheduler,
+                config.stage_config(),
+                config,
+            );
+        } else {
+            // Frees input stages for reuse, so the output stage can be allocated into the same
+            // range. The `sync_cube` is required to ensure other planes are done reading from the stages.
+            //
+            // This is currently very unintuitive, because while the stage already exists, it actually
+            // isn't allocated until it's used (by writing to it). We should eventually separate the
+            // write call into a different function and defer creating the writer until after the stages
+            // are freed to make the order of operations more clear.
+            sync_cube();
+            lhs_reader.free_stage();
+            rhs_reader.free_stage();
+
+            let mut out_stage = Self::GlobalWriter::stage(&out_writer);
+
+            SMM::write_results::<Self::GlobalWriter, Self::Config>(
+                acc,
+                &mut out_stage,
+                &mut out_writer,
+                &partition_scheduler,
+                config.stage_config(),
+                config,
+            );
+        }
     }
 
     fn init_lhs_global_reader(
@@ -147,9 +192,13 @@ where
     ) -> Self::RhsGlobalReader {
         let conf = config.global_memory_config(MatmulIdent::Rhs);
         let layout = SimpleGlobalLayout::new(&rhs, batch_offset, conf);
+        // Rhs rows can be packed along k, so its stage has its own number of rows.
+        let k_step = config
+            .tiling_scheme()
+            .elements_in_stage_row(MatmulIdent::Rhs);
         Self::RhsGlobalReader::new(
             rhs.view(layout).slice_unchecked(offset, slice_size),
-            config.k_step,
+            k_step,
             MatmulIdent::Rhs,
             config,
         )
@@ -157,16 +206,13 @@ where
 
     fn init_acc_global_reader(
         acc: CubeOption<VirtualTensor<AccG<MP>>>,
-        _batch_offset: u32,
-        _offset: Coords2d,
-        _slice_size: Coords2d,
+        batch_offset: u32,
+        offset: Coords2d,
+        slice_size: Coords2d,
         _nth_batch: u32,
-        #[comptime] _config: Self::Config,
+        #[comptime] config: Self::Config,
     ) -> Self::AccGlobalReader {
-        match acc {
-            CubeOption::None => ZeroGlobalReader::new(),
-            CubeOption::Some(_) => panic!("Accumulator loading is not yet supported"),
-        }
+        AR::init::<Self::Config>(acc, batch_offset, offset, slice_size, config)
     }
 
     fn init_global_writer(
@@ -1,6 +1,12 @@
 mod config;
 mod matmul;
+mod multi_rhs;
+mod row_sum;
 mod setup;
+mod syrk;
 
 pub use config::*;
+pub use multi_rhs::{multi_rhs_matmul, validate_multi_rhs};
+pub use row_sum::{row_sum_combine, row_sum_matmul};
 pub use setup::SimpleMatmulFamily;
+pub use syrk::{SyrkTriangle, syrk_matmul, syrk_mirror};
@@ -0,0 +1,331 @@
+use crate::components::{
+    AccS, LhsS, MatmulPrecision, RhsS, StageIdent,
+    error::{MatmulAvailabilityError, MatmulSetupError},
+    global::{
+        GlobalConfig, GlobalMatmul, GlobalWriter, GlobalWriterFamily, WriteTiling,
+        read::{
+            AccumulatorReader, AccumulatorReaderFamily, SyncFullLoadingStrategy,
+            SyncFullStageGlobalReader,
+        },
+        single_stage::simple::{SimpleConfig, matmul::SimpleMatmul},
+    },
+    stage::{
+        AccumulatorFlush, StageConfig, StageMatmul, StageMatmulFamily, StridedStage,
+        StridedStageFamily,
+    },
+};
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+use cubecl_std::{
+    CubeOption,
+    tensor::{layout::Coords2d, r#virtual::VirtualTensor},
+};
+
+/// The [SimpleMatmul] of a [stage matmul family](StageMatmulFamily), as built by
+/// [SimpleMatmulFamily](super::SimpleMatmulFamily)
+type MultiRhsMatmul<MP, SMM, LL, RL, GW, AR> = SimpleMatmul<
+    MP,
+    <SMM as StageMatmulFamily>::Matmul<
+        MP,
+        <LL as SyncFullLoadingStrategy>::TilingLayout,
+        <RL as SyncFullLoadingStrategy>::TilingLayout,
+        <AR as AccumulatorReaderFamily>::TilingLayout,
+        WriteTiling,
+    >,
+    LL,
+    RL,
+    <GW as GlobalWriterFamily>::Writer<<MP as MatmulPrecision>::Acc>,
+    <AR as AccumulatorReaderFamily>::Reader<<MP as MatmulPrecision>::Acc>,
+>;
+
+#[cube]
+impl<MP: MatmulPrecision, SMM, LL, RL, GW, AR> SimpleMatmul<MP, SMM, LL, RL, GW, AR>
+where
+    SMM: StageMatmul<
+            MP,
+            LhsStage = StridedStage<LhsS<MP>, LL::TilingLayout>,
+            RhsStage = StridedStage<RhsS<MP>, RL::TilingLayout>,
+            AccStage = AR::Stage,
+            OutStage = GW::Stage,
+        >,
+    LL: SyncFullLoadingStrategy,
+    RL: SyncFullLoadingStrategy,
+    GW: GlobalWriter<MP::Acc>,
+    AR: AccumulatorReader<MP::Acc>,
+{
+    /// Performs the matrix multiplication of Lhs with every Rhs, over the range given for K,
+    /// and stores each product with the writer at the same index of `out_writers`.
+    ///
+    /// At each step of K, the Lhs stage is loaded once and kept resident while all Rhs
+    /// stages are computed against it. All accumulators start from the same `acc_reader`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn execute_multi_rhs(
+        mut lhs_reader: SyncFullStageGlobalReader<MP::Lhs, SimpleConfig<SMM::Config>, LL>,
+        mut rhs_readers: Sequence<
+            SyncFullStageGlobalReader<MP::Rhs, SimpleConfig<SMM::Config>, RL>,
+        >,
+        mut acc_reader: AR,
+        mut out_writers: Sequence<GW>,
+        accs: &mut Sequence<SMM::Accumulators>,
+        k_range: (u32, u32),
+        stage_bounds: Coords2d,
+        #[comptime] config: SimpleConfig<SMM::Config>,
+    ) {
+        let k_step = config.k_step;
+        let range = k_range.1 - k_range.0;
+        let num_loops = range.div_ceil(k_step);
+        let num_rhs = comptime![rhs_readers.len()];
+
+        let (mut lhs_tile, mut rhs_tile) = SMM::init_tile_inputs(config.stage_config());
+        let mut partition_scheduler = SMM::init_scheduler(config.stage_config());
+        partition_scheduler.restrict_to_bounds(stage_bounds, config.tiling_scheme().tile_size);
+
+        AR::load_stage::<SimpleConfig<SMM::Config>>(&mut acc_reader, config);
+
+        let lhs_stage = &lhs_reader.stage();
+        let mut rhs_stages = Sequence::new();
+
+        #[unroll]
+        for i in 0..num_rhs {
+            SMM::load_accumulators(
+                &AR::stage(&acc_reader),
+                accs.index_mut(i),
+                config.stage_config(),
+            );
+            rhs_stages.push(rhs_readers.index(i).stage());
+        }
+
+        for _ in 0..num_loops {
+            sync_cube();
+
+            lhs_reader.load_stage(config);
+            #[unroll]
+            for i in 0..num_rhs {
+                rhs_readers.index_mut(i).load_stage(config);
+            }
+
+            sync_cube();
+
+            SMM::execute_multi_rhs(
+                lhs_stage,
+                &rhs_stages,
+                &mut lhs_tile,
+                &mut rhs_tile,
+                accs,
+                config.stage_config(),
+                &partition_scheduler,
+            );
+
+            lhs_reader.advance_view();
+            #[unroll]
+            for i in 0..num_rhs {
+                rhs_readers.index_mut(i).advance_view();
+            }
+        }
+
+        // Frees input stages for reuse by the output stages, see `SimpleMatmul::execute`.
+        sync_cube();
+        lhs_reader.free_stage();
+        #[unroll]
+        for i in 0..num_rhs {
+            rhs_readers.index(i).free_stage();
+        }
+
+        #[unroll]
+        for i in 0..num_rhs {
+            let out_writer = out_writers.index_mut(i);
+            let mut out_stage = GW::stage(out_writer);
+
+            SMM::write_results::<GW, SimpleConfig<SMM::Config>>(
+                accs.index(i),
+                &mut out_stage,
+                out_writer,
+                &partition_scheduler,
+                config.stage_config(),
+                config,
+            );
+        }
+    }
+}
+
+#[cube(launch_unchecked)]
+/// Launches the matmul of `lhs` with every tensor of `rhs`, writing each product to the tensor
+/// at the same index of `out`, with the [SimpleMatmul] of the stage matmul family `SMM`.
+///
+/// The Lhs stage is loaded once per step of K for all Rhs, which amortizes its loads when
+/// several matrices share the same input, such as multi-head projections.
+///
+/// Each cube computes one stage of every output, at `CUBE_POS_X` along m, `CUBE_POS_Y` along n
+/// and `CUBE_POS_Z` along the batches. All tensors must have the same batch shape.
+pub fn multi_rhs_matmul<
+    LhsG: Numeric,
+    RhsG: Numeric,
+    AccG: Numeric,
+    LhsS: Numeric,
+    RhsS: Numeric,
+    AccS: Numeric,
+    SMM: StageMatmulFamily<
+            LhsStage = StridedStageFamily,
+            RhsStage = StridedStageFamily,
+            AccStage = AR::Stage,
+            OutStage = GW::Stage,
+        >,
+    LL: SyncFullLoadingStrategy,
+    RL: SyncFullLoadingStrategy,
+    GW: GlobalWriterFamily,
+    AR: AccumulatorReaderFamily,
+>(
+    lhs: &Tensor<Line<LhsG>>,
+    rhs: &Sequence<Tensor<Line<RhsG>>>,
+    out: &mut Sequence<Tensor<Line<AccG>>>,
+    #[comptime] config: SimpleConfig<SMM::Config>,
+) {
+    let rank = lhs.rank();
+    let num_rhs = comptime![rhs.len()];
+    let nth_batch = CUBE_POS_Z;
+
+    let m_offset = CUBE_POS_X * config.tiling_scheme().elements_in_stage_m();
+    let n_offset = CUBE_POS_Y * config.tiling_scheme().elements_in_stage_n();
+    let stage_m = config.tiling_scheme().elements_in_stage_m().runtime();
+    let stage_n = config.tiling_scheme().elements_in_stage_n().runtime();
+    let k_size = lhs.shape(rank - 1);
+    let stage_bounds = (
+        Min::min(stage_m, lhs.shape(rank - 2) - m_offset),
+        Min::min(stage_n, out.index(0).shape(rank - 1) - n_offset),
+    );
+
+    let lhs = VirtualTensor::<LhsG>::new::<Tensor<Line<LhsG>>>(lhs);
+    let batch_lhs = nth_batch * lhs.stride(rank - 2) * lhs.shape(rank - 2);
+    let lhs_reader = MultiRhsMatmul::<
+        (LhsG, RhsG, AccG, LhsS, RhsS, AccS),
+        SMM,
+        LL,
+        RL,
+        GW,
+        AR,
+    >::init_lhs_global_reader(
+        lhs,
+        batch_lhs,
+        (m_offset, 0),
+        (stage_m, k_size),
+        nth_batch,
+        config,
+    );
+
+    let mut rhs_readers = Sequence::new();
+    let mut out_writers = Sequence::new();
+    let mut accs = Sequence::new();
+
+    #[unroll]
+    for i in 0..num_rhs {
+        let rhs = VirtualTensor::<RhsG>::new::<Tensor<Line<RhsG>>>(rhs.index(i));
+        let batch_rhs = nth_batch * rhs.stride(rank - 2) * rhs.shape(rank - 2);
+        rhs_readers.push(MultiRhsMatmul::<
+            (LhsG, RhsG, AccG, LhsS, RhsS, AccS),
+            SMM,
+            LL,
+            RL,
+            GW,
+            AR,
+        >::init_rhs_global_reader(
+            rhs,
+            batch_rhs,
+            (0, n_offset),
+            (k_size, stage_n),
+            nth_batch,
+            config,
+        ));
+
+        let out = VirtualTensor::<AccG, ReadWrite>::new::<Tensor<Line<AccG>>>(out.index_mut(i));
+        let batch_out = nth_batch * out.stride(rank - 2) * out.shape(rank - 2);
+        out_writers.push(MultiRhsMatmul::<
+            (LhsG, RhsG, AccG, LhsS, RhsS, AccS),
+            SMM,
+            LL,
+            RL,
+            GW,
+            AR,
+        >::init_global_writer(
+            out,
+            batch_out,
+            (m_offset, n_offset),
+            (stage_m, stage_n),
+            nth_batch,
+            config,
+        ));
+
+        accs.push(MultiRhsMatmul::<
+            (LhsG, RhsG, AccG, LhsS, RhsS, AccS),
+            SMM,
+            LL,
+            RL,
+            GW,
+            AR,
+        >::init_accumulators(config));
+    }
+
+    let acc_reader = MultiRhsMatmul::<
+        (LhsG, RhsG, AccG, LhsS, RhsS, AccS),
+        SMM,
+        LL,
+        RL,
+        GW,
+        AR,
+    >::init_acc_global_reader(
+        CubeOption::new_None(),
+        0,
+        (m_offset, n_offset),
+        (stage_m, stage_n),
+        nth_batch,
+        config,
+    );
+
+    MultiRhsMatmul::<(LhsG, RhsG, AccG, LhsS, RhsS, AccS), SMM, LL, RL, GW, AR>::execute_multi_rhs(
+        lhs_reader,
+        rhs_readers,
+        acc_reader,
+        out_writers,
+        &mut accs,
+        (0, k_size),
+        stage_bounds,
+        config,
+    );
+}
+
+/// Checks that the cubes of [multi_rhs_matmul] with `num_rhs` Rhs fit the limits of the device
+/// of the `client`.
+///
+/// Each Rhs is loaded into its own stage, so on top of the checks of
+/// [StageConfig::validate_against_device], this returns
+/// [MatmulAvailabilityError::SharedMemoryTooBig] if the shared memory doesn't fit the Lhs stage
+/// with `num_rhs` Rhs stages. The accumulators are always written after the execution, so an
+/// [interleaved flush](AccumulatorFlush::Interleaved) is rejected.
+pub fn validate_multi_rhs<MP: MatmulPrecision, R: Runtime, S: StageConfig>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    config: SimpleConfig<S>,
+    num_rhs: u32,
+) -> Result<(), MatmulSetupError> {
+    if config.accumulator_flush() == AccumulatorFlush::Interleaved {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(
+            "Interleaved accumulator flush is not supported by the multi Rhs matmul",
+        )));
+    }
+
+    let stage_config = config.stage_config();
+    stage_config.validate_against_device::<MP, R>(client)?;
+
+    let rhs_stage_size = config.tiling_scheme().elements_in_stage_nk()
+        * stage_config.num_stages(StageIdent::Rhs)
+        * RhsS::<MP>::elem_size();
+    let size = stage_config.shared_memory_size(
+        LhsS::<MP>::elem_size(),
+        RhsS::<MP>::elem_size(),
+        AccS::<MP>::elem_size(),
+    ) + num_rhs.saturating_sub(1) * rhs_stage_size;
+    let max = client.properties().hardware.max_shared_memory_size as u32;
+    if size > max {
+        return Err(MatmulAvailabilityError::SharedMemoryTooBig { size, max }.into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use crate::components::{
+    MatmulIdent, MatmulPrecision,
+    global::{
+        GlobalConfig, GlobalMatmul, PartitionedStageFamily, RowSumWriter, WriteTiling,
+        memory::SimpleGlobalLayout,
+        read::{AccumulatorReaderFamily, SyncFullLoadingStrategy},
+        single_stage::simple::{SimpleConfig, matmul::SimpleMatmul},
+    },
+    stage::{StageMatmulFamily, StridedStageFamily},
+};
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+use cubecl_std::{CubeOption, tensor::r#virtual::VirtualTensor};
+
+/// The [SimpleMatmul] of a [stage matmul family](StageMatmulFamily) writing its output with a
+/// [RowSumWriter]
+type RowSumMatmul<MP, SMM, LL, RL, AR> = SimpleMatmul<
+    MP,
+    <SMM as StageMatmulFamily>::Matmul<
+        MP,
+        <LL as SyncFullLoadingStrategy>::TilingLayout,
+        <RL as SyncFullLoadingStrategy>::TilingLayout,
+        <AR as AccumulatorReaderFamily>::TilingLayout,
+        WriteTiling,
+    >,
+    LL,
+    RL,
+    RowSumWriter<<MP as MatmulPrecision>::Acc>,
+    <AR as AccumulatorReaderFamily>::Reader<<MP as MatmulPrecision>::Acc>,
+>;
+
+#[cube(launch_unchecked)]
+/// Launches the matmul of `lhs` and `rhs` into `out` with the [SimpleMatmul] of the stage
+/// matmul family `SMM`, also writing the sums of the rows of each tile into `row_sums` as the
+/// tiles are flushed, see [RowSumWriter].
+///
+/// The `row_sums` must have the shape of the output, except for a last dimension of the number
+/// of tiles along `n`, and a line size of 1. Summing them with [row_sum_combine] gives the row
+/// sums of the output without another pass over it. The stage matmul must be partitioned by
+/// units, since each unit sums the tiles it writes.
+///
+/// Each cube computes one stage of the output, at `CUBE_POS_X` along m, `CUBE_POS_Y` along n
+/// and `CUBE_POS_Z` along the batches.
+pub fn row_sum_matmul<
+    LhsG: Numeric,
+    RhsG: Numeric,
+    AccG: Numeric,
+    LhsS: Numeric,
+    RhsS: Numeric,
+    AccS: Numeric,
+    SMM: StageMatmulFamily<
+            LhsStage = StridedStageFamily,
+            RhsStage = StridedStageFamily,
+            AccStage = AR::Stage,
+            OutStage = PartitionedStageFamily,
+        >,
+    LL: SyncFullLoadingStrategy,
+    RL: SyncFullLoadingStrategy,
+    AR: AccumulatorReaderFamily,
+>(
+    lhs: &Tensor<Line<LhsG>>,
+    rhs: &Tensor<Line<RhsG>>,
+    out: &mut Tensor<Line<AccG>>,
+    row_sums: &mut Tensor<Line<AccG>>,
+    #[comptime] config: SimpleConfig<SMM::Config>,
+) {
+    let rank = lhs.rank();
+    let nth_batch = CUBE_POS_Z;
+
+    let m_offset = CUBE_POS_X * config.tiling_scheme().elements_in_stage_m();
+    let n_offset = CUBE_POS_Y * config.tiling_scheme().elements_in_stage_n();
+    let stage_m = config.tiling_scheme().elements_in_stage_m().runtime();
+    let stage_n = config.tiling_scheme().elements_in_stage_n().runtime();
+    let k_size = lhs.shape(rank - 1);
+    let stage_bounds = (
+        Min::min(stage_m, lhs.shape(rank - 2) - m_offset),
+        Min::min(stage_n, out.shape(rank - 1) - n_offset),
+    );
+
+    let lhs = VirtualTensor::<LhsG>::new::<Tensor<Line<LhsG>>>(lhs);
+    let batch_lhs = nth_batch * lhs.stride(rank - 2) * lhs.shape(rank - 2);
+    let lhs_reader = RowSumMatmul::<
+        (LhsG, RhsG, AccG, LhsS, RhsS, AccS),
+        SMM,
+        LL,
+        RL,
+        AR,
+    >::init_lhs_global_reader(
+        lhs,
+        batch_lhs,
+        (m_offset, 0),
+        (stage_m, k_size),
+        nth_batch,
+        config,
+    );
+
+    let rhs = VirtualTensor::<RhsG>::new::<Tensor<Line<RhsG>>>(rhs);
+    let batch_rhs = nth_batch * rhs.stride(rank - 2) * rhs.shape(rank - 2);
+    let rhs_reader = RowSumMatmul::<
+        (LhsG, RhsG, AccG, LhsS, RhsS, AccS),
+        SMM,
+        LL,
+        RL,
+        AR,
+    >::init_rhs_global_reader(
+        rhs,
+        batch_rhs,
+        (0, n_offset),
+        (k_size, stage_n),
+        nth_batch,
+        config,
+    );
+
+    let out = VirtualTensor::<AccG, ReadWrite>::new::<Tensor<Line<AccG>>>(out);
+    let batch_out = nth_batch * out.stride(rank - 2) * out.shape(rank - 2);
+    let out_config = config.global_memory_config(MatmulIdent::Out);
+    let out_layout = SimpleGlobalLayout::new(&out, batch_out, out_config);
+    let out_view = out
+        .view_mut(out_layout)
+        .slice_mut_unchecked((m_offset, n_offset), (stage_m, stage_n));
+
+    let row_sums = VirtualTensor::<AccG, ReadWrite>::new::<Tensor<Line<AccG>>>(row_sums);
+    let batch_row_sums = nth_batch * row_sums.stride(rank - 2) * row_sums.shape(rank - 2);
+    let out_writer = RowSumWriter::<(AccG, AccS)>::new::<SMM::Config>(
+        out_view,
+        row_sums,
+        batch_row_sums,
+        (m_offset, n_offset),
+        (stage_m, stage_n),
+        stage_bounds,
+        out_config,
+        config.stage_config(),
+    );
+
+    let acc_reader = RowSumMatmul::<
+        (LhsG, RhsG, AccG, LhsS, RhsS, AccS),
+        SMM,
+        LL,
+        RL,
+        AR,
+    >::init_acc_global_reader(
+        CubeOption::new_None(),
+        0,
+        (m_offset, n_offset),
+        (stage_m, stage_n),
+        nth_batch,
+        config,
+    );
+    let mut acc =
+        RowSumMatmul::<(LhsG, RhsG, AccG, LhsS, RhsS, AccS), SMM, LL, RL, AR>::init_accumulators(
+            config,
+        );
+
+    RowSumMatmul::<(LhsG, RhsG, AccG, LhsS, RhsS, AccS), SMM, LL, RL, AR>::execute(
+        lhs_reader,
+        rhs_reader,
+        acc_reader,
+        out_writer,
+        &mut acc,
+        (0, k_size),
+        stage_bounds,
+        config,
+    );
+}
+
+#[cube(launch)]
+/// Sums the tile row sums written by [row_sum_matmul] along their last dimension, writing the
+/// row sums of the output into `out`, whose last dimension is 1.
+///
+/// Each unit writes the sum of one row, both tensors must have a line size of 1.
+pub fn row_sum_combine<E: Numeric>(row_sums: &Tensor<Line<E>>, out: &mut Tensor<Line<E>>) {
+    let rank = row_sums.rank();
+
+    if ABSOLUTE_POS >= out.len() {
+        terminate!();
+    }
+
+    let mut index = ABSOLUTE_POS;
+    let mut row_sums_offset = 0;
+    let mut out_offset = 0;
+    for i in 0..rank - 1 {
+        let dim = rank - 2 - i;
+        let coordinate = index % out.shape(dim);
+        row_sums_offset += coordinate * row_sums.stride(dim);
+        out_offset += coordinate * out.stride(dim);
+        index /= out.shape(dim);
+    }
+
+    let stride = row_sums.stride(rank - 1);
+    let mut sum = E::from_int(0);
+    for tile in 0..row_sums.shape(rank - 1) {
+        sum += row_sums[row_sums_offset + tile * stride][0];
+    }
+    out[out_offset] = Line::new(sum);
+}
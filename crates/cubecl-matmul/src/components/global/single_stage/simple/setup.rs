@@ -3,10 +3,10 @@ use crate::components::{
     error::MatmulSetupError,
     global::{
         GlobalWriterFamily, WriteTiling,
-        read::SyncFullLoadingStrategy,
+        read::{AccumulatorReaderFamily, SyncFullLoadingStrategy, ZeroGlobalReaderFamily},
         single_stage::simple::{SimpleConfig, matmul::SimpleMatmul},
     },
-    stage::{FilledStageFamily, NoTilingLayout, StageConfig, StridedStageFamily},
+    stage::{StageConfig, StridedStageFamily},
 };
 use cubecl_core::prelude::*;
 use std::marker::PhantomData;
@@ -17,37 +17,41 @@ use crate::components::{
     stage::{self},
 };
 
-/// Simple matmul family for any precision
+/// Simple matmul family for any precision, starting from zeroed accumulators by default
 pub struct SimpleMatmulFamily<
     SMM: stage::StageMatmulFamily,
     LL: SyncFullLoadingStrategy,
     RL: SyncFullLoadingStrategy,
     GW: GlobalWriterFamily,
+    AR: AccumulatorReaderFamily = ZeroGlobalReaderFamily,
 > {
     _stage_matmul: PhantomData<SMM>,
     _lhs_loading: PhantomData<LL>,
     _rhs_loading: PhantomData<RL>,
     _writer: PhantomData<GW>,
+    _acc_reader: PhantomData<AR>,
 }
 
-impl<SMM, LL, RL, GW> GlobalMatmulFamily for SimpleMatmulFamily<SMM, LL, RL, GW>
+impl<SMM, LL, RL, GW, AR> GlobalMatmulFamily for SimpleMatmulFamily<SMM, LL, RL, GW, AR>
 where
     SMM: stage::StageMatmulFamily<
             LhsStage = StridedStageFamily,
             RhsStage = StridedStageFamily,
-            AccStage = FilledStageFamily,
+            AccStage = AR::Stage,
             OutStage = GW::Stage,
         >,
     LL: SyncFullLoadingStrategy,
     RL: SyncFullLoadingStrategy,
     GW: GlobalWriterFamily,
+    AR: AccumulatorReaderFamily,
 {
     type Matmul<MP: MatmulPrecision> = SimpleMatmul<
         MP,
-        SMM::Matmul<MP, LL::TilingLayout, RL::TilingLayout, NoTilingLayout, WriteTiling>,
+        SMM::Matmul<MP, LL::TilingLayout, RL::TilingLayout, AR::TilingLayout, WriteTiling>,
         LL,
         RL,
         GW::Writer<MP::Acc>,
+        AR::Reader<MP::Acc>,
     >;
     type Config = SimpleConfig<SMM::Config>;
 
@@ -89,6 +93,7 @@ where
             stage_shape_k,
             selection.loading_precompute_strategy,
             selection.reader_mode,
+            selection.accumulator_flush,
         )
     }
 }
@@ -0,0 +1,218 @@
+use crate::components::{
+    MatmulIdent, MatmulPrecision,
+    global::{
+        GlobalConfig, GlobalMatmul, GlobalWriterFamily, WriteTiling,
+        memory::SimpleGlobalLayout,
+        read::{AccumulatorReaderFamily, SyncFullLoadingStrategy, SyncFullStageGlobalReader},
+        single_stage::simple::{SimpleConfig, matmul::SimpleMatmul},
+    },
+    stage::{StageMatmulFamily, StridedStageFamily},
+};
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+use cubecl_std::{CubeOption, tensor::r#virtual::VirtualTensor};
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+/// The triangle of the symmetric output computed by [syrk_matmul].
+pub enum SyrkTriangle {
+    /// Elements where the row is greater or equal to the column.
+    Lower,
+    /// Elements where the column is greater or equal to the row.
+    Upper,
+}
+
+/// The precision of the matmul of a matrix with its own transpose, where both operands
+/// are read from the same tensor
+type SyrkPrecision<EG, AccG, ES, AccS> = (EG, EG, AccG, ES, ES, AccS);
+
+/// The [SimpleMatmul] of a [stage matmul family](StageMatmulFamily), as built by
+/// [SimpleMatmulFamily](super::SimpleMatmulFamily)
+type SyrkMatmul<EG, AccG, ES, AccS, SMM, LL, RL, GW, AR> = SimpleMatmul<
+    SyrkPrecision<EG, AccG, ES, AccS>,
+    <SMM as StageMatmulFamily>::Matmul<
+        SyrkPrecision<EG, AccG, ES, AccS>,
+        <LL as SyncFullLoadingStrategy>::TilingLayout,
+        <RL as SyncFullLoadingStrategy>::TilingLayout,
+        <AR as AccumulatorReaderFamily>::TilingLayout,
+        WriteTiling,
+    >,
+    LL,
+    RL,
+    <GW as GlobalWriterFamily>::Writer<(AccG, AccS)>,
+    <AR as AccumulatorReaderFamily>::Reader<(AccG, AccS)>,
+>;
+
+type SyrkLhsReader<EG, AccG, ES, AccS, SMM, LL> = SyncFullStageGlobalReader<
+    <SyrkPrecision<EG, AccG, ES, AccS> as MatmulPrecision>::Lhs,
+    SimpleConfig<<SMM as StageMatmulFamily>::Config>,
+    LL,
+>;
+
+type SyrkRhsReader<EG, AccG, ES, AccS, SMM, RL> = SyncFullStageGlobalReader<
+    <SyrkPrecision<EG, AccG, ES, AccS> as MatmulPrecision>::Rhs,
+    SimpleConfig<<SMM as StageMatmulFamily>::Config>,
+    RL,
+>;
+
+#[cube(launch_unchecked)]
+/// Launches the matmul of `a` with its own transpose, `a * a^T`, with the [SimpleMatmul] of the
+/// stage matmul family `SMM`, only computing the given `triangle` of the symmetric output.
+///
+/// The Rhs is read from `a` through a transposed view, so the config must be set up with the
+/// opposite layout for the Rhs than for the Lhs. When `transposed` is set, this computes
+/// `a^T * a` instead: for `a` of shape `[k, n]`, the output has shape `[n, n]`, and the Lhs is
+/// read through the transposed view while the Rhs is read in its natural layout. Either way `a`
+/// is never transposed in memory.
+///
+/// Cubes whose stage is strictly in the other triangle terminate right away, which halves the
+/// compute for large outputs. Stages crossing the diagonal are computed fully, while the rest of
+/// the other triangle is left untouched, see [syrk_mirror] to fill it.
+///
+/// Each cube computes one stage of the output, at `CUBE_POS_X` along the rows, `CUBE_POS_Y`
+/// along the columns and `CUBE_POS_Z` along the batches.
+pub fn syrk_matmul<
+    EG: Numeric,
+    AccG: Numeric,
+    ES: Numeric,
+    AccS: Numeric,
+    SMM: StageMatmulFamily<
+            LhsStage = StridedStageFamily,
+            RhsStage = StridedStageFamily,
+            AccStage = AR::Stage,
+            OutStage = GW::Stage,
+        >,
+    LL: SyncFullLoadingStrategy,
+    RL: SyncFullLoadingStrategy,
+    GW: GlobalWriterFamily,
+    AR: AccumulatorReaderFamily,
+>(
+    a: &Tensor<Line<EG>>,
+    out: &mut Tensor<Line<AccG>>,
+    #[comptime] triangle: SyrkTriangle,
+    #[comptime] transposed: bool,
+    #[comptime] config: SimpleConfig<SMM::Config>,
+) {
+    let rank = a.rank();
+    let nth_batch = CUBE_POS_Z;
+
+    let m_offset = CUBE_POS_X * config.tiling_scheme().elements_in_stage_m();
+    let n_offset = CUBE_POS_Y * config.tiling_scheme().elements_in_stage_n();
+    let stage_m = config.tiling_scheme().elements_in_stage_m().runtime();
+    let stage_n = config.tiling_scheme().elements_in_stage_n().runtime();
+
+    let is_skipped = match comptime![triangle] {
+        SyrkTriangle::Lower => n_offset >= m_offset + stage_m,
+        SyrkTriangle::Upper => m_offset >= n_offset + stage_n,
+    };
+    if is_skipped {
+        terminate!();
+    }
+
+    let (size, k_size) = if comptime![transposed] {
+        (a.shape(rank - 1), a.shape(rank - 2))
+    } else {
+        (a.shape(rank - 2), a.shape(rank - 1))
+    };
+    let stage_bounds = (
+        Min::min(stage_m, size - m_offset),
+        Min::min(stage_n, size - n_offset),
+    );
+
+    let a = VirtualTensor::<EG>::new::<Tensor<Line<EG>>>(a);
+    let batch_a = nth_batch * a.stride(rank - 2) * a.shape(rank - 2);
+    let lhs_config = config.global_memory_config(MatmulIdent::Lhs);
+    let rhs_config = config.global_memory_config(MatmulIdent::Rhs);
+    let (lhs_layout, rhs_layout) = if comptime![transposed] {
+        (
+            SimpleGlobalLayout::new_transposed(&a, batch_a, lhs_config),
+            SimpleGlobalLayout::new(&a, batch_a, rhs_config),
+        )
+    } else {
+        (
+            SimpleGlobalLayout::new(&a, batch_a, lhs_config),
+            SimpleGlobalLayout::new_transposed(&a, batch_a, rhs_config),
+        )
+    };
+    let lhs_reader = SyrkLhsReader::<EG, AccG, ES, AccS, SMM, LL>::new(
+        a.view(lhs_layout)
+            .slice_unchecked((m_offset, 0), (stage_m, k_size)),
+        config.k_step,
+        MatmulIdent::Lhs,
+        config,
+    );
+    let rhs_reader = SyrkRhsReader::<EG, AccG, ES, AccS, SMM, RL>::new(
+        a.view(rhs_layout)
+            .slice_unchecked((0, n_offset), (k_size, stage_n)),
+        config.k_step,
+        MatmulIdent::Rhs,
+        config,
+    );
+
+    let out = VirtualTensor::<AccG, ReadWrite>::new::<Tensor<Line<AccG>>>(out);
+    let batch_out = nth_batch * out.stride(rank - 2) * out.shape(rank - 2);
+    let out_writer = SyrkMatmul::<EG, AccG, ES, AccS, SMM, LL, RL, GW, AR>::init_global_writer(
+        out,
+        batch_out,
+        (m_offset, n_offset),
+        (stage_m, stage_n),
+        nth_batch,
+        config,
+    );
+
+    let acc_reader = SyrkMatmul::<EG, AccG, ES, AccS, SMM, LL, RL, GW, AR>::init_acc_global_reader(
+        CubeOption::new_None(),
+        0,
+        (m_offset, n_offset),
+        (stage_m, stage_n),
+        nth_batch,
+        config,
+    );
+    let mut acc = SyrkMatmul::<EG, AccG, ES, AccS, SMM, LL, RL, GW, AR>::init_accumulators(config);
+
+    SyrkMatmul::<EG, AccG, ES, AccS, SMM, LL, RL, GW, AR>::execute(
+        lhs_reader,
+        rhs_reader,
+        acc_reader,
+        out_writer,
+        &mut acc,
+        (0, k_size),
+        stage_bounds,
+        config,
+    );
+}
+
+#[cube(launch)]
+/// Copies the `triangle` of each symmetric matrix of `out` to the other triangle, completing
+/// the output of [syrk_matmul].
+///
+/// Each unit writes one element of the other triangle, the tensor must have a line size of 1.
+pub fn syrk_mirror<E: Numeric>(out: &mut Tensor<Line<E>>, #[comptime] triangle: SyrkTriangle) {
+    let rank = out.rank();
+    let size = out.shape(rank - 1);
+
+    if ABSOLUTE_POS >= out.len() {
+        terminate!();
+    }
+
+    let col = ABSOLUTE_POS % size;
+    let row = (ABSOLUTE_POS / size) % size;
+
+    let mut batch = ABSOLUTE_POS / (size * size);
+    let mut batch_offset = 0;
+    for i in 0..rank - 2 {
+        let dim = rank - 3 - i;
+        batch_offset += (batch % out.shape(dim)) * out.stride(dim);
+        batch /= out.shape(dim);
+    }
+
+    let is_mirrored = match comptime![triangle] {
+        SyrkTriangle::Lower => col > row,
+        SyrkTriangle::Upper => row > col,
+    };
+    if is_mirrored {
+        let stride_row = out.stride(rank - 2);
+        let stride_col = out.stride(rank - 1);
+        out[batch_offset + row * stride_row + col * stride_col] =
+            out[batch_offset + col * stride_row + row * stride_col];
+    }
+}
@@ -9,7 +9,7 @@ use crate::components::{
     global::{
         self, LoadingSides, PlaneRoleConfig, SpecializedLoadingSides,
         multi_stage::EventLoadingMode,
-        read::{LoadingValidation, ReaderMode},
+        read::{LoadingValidation, ReaderMode, validate_unscaled_load},
         shared::shared_global_config_validation,
     },
     stage::{self, StageMemoryConfig},
@@ -147,6 +147,8 @@ impl<S: stage::StageConfig> SimpleTmaConfig<S> {
     ) -> Result<Self, MatmulSetupError> {
         LL::check(&self, MatmulIdent::Lhs)?;
         RL::check(&self, MatmulIdent::Rhs)?;
+        validate_unscaled_load(&self, MatmulIdent::Lhs)?;
+        validate_unscaled_load(&self, MatmulIdent::Rhs)?;
         shared_global_config_validation(self)?;
 
         Ok(self)
@@ -50,6 +50,7 @@ where
         mut out_writer: Self::GlobalWriter,
         acc: &mut Self::Accumulators,
         k_range: (u32, u32),
+        stage_bounds: Coords2d,
         #[comptime] config: Self::Config,
     ) {
         let k_step = config.k_step;
@@ -65,7 +66,8 @@ where
         let num_bytes_stages = num_bytes_lhs + num_bytes_rhs;
 
         let (mut lhs_tile, mut rhs_tile) = SMM::init_tile_inputs(config.stage_config());
-        let partition_scheduler = SMM::init_scheduler(config.stage_config());
+        let mut partition_scheduler = SMM::init_scheduler(config.stage_config());
+        partition_scheduler.restrict_to_bounds(stage_bounds, config.tiling_scheme().tile_size);
 
         SMM::load_accumulators(&acc_reader.stage(), acc, config.stage_config());
 
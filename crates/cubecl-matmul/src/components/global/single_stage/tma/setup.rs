@@ -1,6 +1,7 @@
 use crate::components::MatmulPrecision;
 use crate::components::global::read::NoLoadingValidation;
 use crate::components::global::read::TmaTiling;
+use crate::components::global::shared::{check_flush_after_execution, check_unpacked_rhs};
 use crate::components::global::single_stage::tma::SimpleTmaConfig;
 use crate::components::global::single_stage::tma::matmul::SimpleTmaMatmul;
 use crate::components::stage::StageConfig;
@@ -46,6 +47,9 @@ where
         selection: &MatmulSelection,
         line_sizes: &MatmulLineSizes,
     ) -> Result<Self::Config, MatmulSetupError> {
+        check_unpacked_rhs(&selection.tiling_scheme)?;
+        check_flush_after_execution(selection.accumulator_flush)?;
+
         assert!(line_sizes.lhs == 1);
         assert!(line_sizes.rhs == 1);
 
@@ -1,11 +1,13 @@
 mod base;
 mod event;
 mod plane;
+mod row_sum;
 mod stage;
 mod unit;
 
 pub use base::*;
 pub use event::*;
 pub use plane::*;
+pub use row_sum::*;
 pub use stage::*;
 pub use unit::*;
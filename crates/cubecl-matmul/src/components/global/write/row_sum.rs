@@ -0,0 +1,160 @@
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+use cubecl_std::{
+    CubeOption, CubeOptionExpand,
+    tensor::{View, layout::Coords2d, r#virtual::VirtualTensor},
+};
+
+use crate::components::{
+    MatrixLayout, MatrixPrecision,
+    global::{
+        GlobalWriter, GlobalWriterFamily, PartitionedStage, PartitionedStageFamily, UnitWriter,
+        WriteEvent, WriteEventExpand, WriteEventListener,
+        memory::{GlobalMemoryConfig, OutputLayout, SimpleGlobalLayout},
+    },
+    stage::StageConfig,
+};
+
+#[derive(CubeType)]
+/// Writes tiles from out shared memory to output global memory like the [UnitWriter], and
+/// optionally the sums of the rows of each tile into a second tensor.
+///
+/// The row sums have the shape of the output, except for one column per tile along `n`, so each
+/// unit writes the sums of its own tile without synchronizing with the others. Summing them along
+/// the last dimension gives the row sums of the output, see
+/// [row_sum_combine](crate::components::global::single_stage::simple::row_sum_combine).
+pub struct RowSumWriter<IP: MatrixPrecision> {
+    writer: UnitWriter<IP>,
+    row_sums: CubeOption<View<Line<IP::Global>, Coords2d, ReadWrite>>,
+    bounds: Coords2d,
+
+    #[cube(comptime)]
+    config: GlobalMemoryConfig,
+}
+
+#[cube]
+impl<IP: MatrixPrecision> RowSumWriter<IP> {
+    /// Creates a writer of the output `global`, also writing the row sums of the tiles into the
+    /// `row_sums` tensor starting at `batch_offset`.
+    ///
+    /// The `offset` and `size` are the ones of the `global` view in the output, and the `bounds`
+    /// are the number of rows and columns of the view within the output. The elements past them
+    /// are left out of the sums.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new<S: StageConfig>(
+        global: View<Line<IP::Global>, Coords2d, ReadWrite>,
+        row_sums: VirtualTensor<IP::Global, ReadWrite>,
+        batch_offset: u32,
+        offset: Coords2d,
+        size: Coords2d,
+        bounds: Coords2d,
+        #[comptime] global_config: GlobalMemoryConfig,
+        #[comptime] stage_config: S,
+    ) -> Self {
+        let tile_cols = comptime![global_config.elements_in_tile_col];
+        let conf = comptime![row_sums_memory_config(global_config)];
+        let layout = SimpleGlobalLayout::new(&row_sums, batch_offset, conf);
+        let row_sums = row_sums.view_mut(layout).slice_mut_unchecked(
+            (offset.0, offset.1 / tile_cols),
+            (size.0, size.1.div_ceil(tile_cols)),
+        );
+
+        RowSumWriter::<IP> {
+            writer: UnitWriter::new::<S>(global, global_config, stage_config),
+            row_sums: CubeOption::new_Some(row_sums),
+            bounds,
+            config: global_config,
+        }
+    }
+
+    fn write_row_sums(&mut self, tile: Coords2d) {
+        match self.row_sums {
+            CubeOption::Some(row_sums) => {
+                let config = comptime![self.config];
+                let tile_rows = config.elements_in_tile_row;
+                let tile_cols = config.elements_in_tile_col;
+                let line_size = config.global_line_size;
+
+                let (tile_row, tile_col) = tile;
+                let (rows, cols) = self.bounds;
+                let row_start = tile_row * tile_rows;
+                let col_start = tile_col * tile_cols;
+                // The elements of the tile past the output are padding.
+                let num_rows = Min::min(tile_rows, rows - Min::min(rows, row_start));
+                let num_cols = Min::min(tile_cols, cols - Min::min(cols, col_start));
+
+                let smem_tile = &UnitWriter::<IP>::stage(&self.writer).unit_tile;
+                let values = smem_tile.slice.with_line_size(line_size);
+
+                for row in 0..num_rows {
+                    let mut sum = IP::Stage::from_int(0);
+                    for col in 0..num_cols {
+                        let index = match comptime![config.matrix_layout] {
+                            MatrixLayout::RowMajor => row * tile_cols + col,
+                            MatrixLayout::ColMajor => col * tile_rows + row,
+                        };
+                        sum += values[index / line_size][index % line_size];
+                    }
+                    row_sums.write(
+                        (row_start + row, tile_col),
+                        Line::new(IP::Global::cast_from(sum)),
+                    );
+                }
+            }
+            CubeOption::None => {}
+        }
+    }
+}
+
+/// The row sums use the strides of their tensor, with one element per tile along `n`.
+fn row_sums_memory_config(config: GlobalMemoryConfig) -> GlobalMemoryConfig {
+    GlobalMemoryConfig {
+        global_line_size: 1,
+        matrix_layout: MatrixLayout::RowMajor,
+        output_layout: OutputLayout::Strided,
+        ..config
+    }
+}
+
+#[cube]
+impl<IP: MatrixPrecision> WriteEventListener for RowSumWriter<IP> {
+    fn on_event(this: &mut Self, event: super::WriteEvent) {
+        UnitWriter::<IP>::on_event(&mut this.writer, event);
+
+        #[allow(clippy::single_match)]
+        match event {
+            WriteEvent::TileStored { tile } => this.write_row_sums(tile),
+            _ => {}
+        }
+    }
+}
+
+#[cube]
+impl<IP: MatrixPrecision> GlobalWriter<IP> for RowSumWriter<IP> {
+    type Stage = PartitionedStage<IP::Stage>;
+
+    /// Only writes the output, the row sums need their tensor, see [RowSumWriter::new].
+    fn init<S: StageConfig>(
+        tensor: View<Line<IP::Global>, Coords2d, ReadWrite>,
+        #[comptime] config: GlobalMemoryConfig,
+        #[comptime] stage_config: S,
+    ) -> Self {
+        RowSumWriter::<IP> {
+            writer: UnitWriter::new::<S>(tensor, config, stage_config),
+            row_sums: CubeOption::new_None(),
+            bounds: (0u32, 0u32),
+            config,
+        }
+    }
+
+    fn stage(this: &Self) -> Self::Stage {
+        UnitWriter::<IP>::stage(&this.writer)
+    }
+}
+
+pub struct RowSumWriterFamily;
+
+impl GlobalWriterFamily for RowSumWriterFamily {
+    type Stage = PartitionedStageFamily;
+    type Writer<IP: MatrixPrecision> = RowSumWriter<IP>;
+}
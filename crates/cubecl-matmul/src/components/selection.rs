@@ -1,8 +1,13 @@
 use crate::components::{
     TilingScheme,
     batch::HypercubeSelection,
-    global::{LoadSpecializationConfig, read::ReaderMode},
-    stage::PartitionBuffering,
+    global::{
+        LoadSpecializationConfig,
+        memory::OutputLayout,
+        read::{LoadScale, LoadTransform, ReaderMode},
+    },
+    stage::{AccumulatorFlush, PartitionBuffering, TileIteration},
+    tile::register::ComplexProduct,
 };
 
 #[derive(Debug, Clone)]
@@ -11,6 +16,19 @@ pub struct MatmulSelection {
     pub tiling_scheme: TilingScheme,
     pub quantized: bool,
     pub partition_buffering: PartitionBuffering,
+    pub accumulator_flush: AccumulatorFlush,
+    pub tile_iteration: TileIteration,
+    /// Number of accumulator tiles along n computed together, for register blocking in n.
+    ///
+    /// `None` computes all the accumulators of a partition together.
+    pub acc_tiles_n: Option<u32>,
+    pub output_layout: OutputLayout,
+    pub load_scale: LoadScale,
+    pub load_transform: LoadTransform,
+    /// Multiply pairs of elements as complex numbers in the register tile matmul.
+    ///
+    /// `None` multiplies real numbers.
+    pub complex: Option<ComplexProduct>,
     pub loading_precompute_strategy: LoadingPrecomputeStrategy,
     pub reader_mode: ReaderMode,
     pub load_specialization_config: LoadSpecializationConfig,
@@ -33,6 +51,13 @@ pub struct MatmulSelectionBuilder {
     hypercube_selection: Option<HypercubeSelection>,
     quantized: bool,
     partition_buffering: PartitionBuffering,
+    accumulator_flush: AccumulatorFlush,
+    tile_iteration: TileIteration,
+    acc_tiles_n: Option<u32>,
+    output_layout: OutputLayout,
+    load_scale: LoadScale,
+    load_transform: LoadTransform,
+    complex: Option<ComplexProduct>,
     loading_precompute_strategy: LoadingPrecomputeStrategy,
     reader_mode: ReaderMode,
     load_specialization_config: LoadSpecializationConfig,
@@ -46,6 +71,13 @@ impl MatmulSelectionBuilder {
             hypercube_selection: None,
             quantized: false,
             partition_buffering: PartitionBuffering::default(),
+            accumulator_flush: AccumulatorFlush::default(),
+            tile_iteration: TileIteration::default(),
+            acc_tiles_n: None,
+            output_layout: OutputLayout::default(),
+            load_scale: LoadScale::default(),
+            load_transform: LoadTransform::default(),
+            complex: None,
             loading_precompute_strategy: LoadingPrecomputeStrategy::default(),
             reader_mode: ReaderMode::default(),
             load_specialization_config: LoadSpecializationConfig::default(),
@@ -77,6 +109,43 @@ impl MatmulSelectionBuilder {
         self
     }
 
+    pub fn accumulator_flush(mut self, accumulator_flush: AccumulatorFlush) -> Self {
+        self.accumulator_flush = accumulator_flush;
+        self
+    }
+
+    pub fn tile_iteration(mut self, tile_iteration: TileIteration) -> Self {
+        self.tile_iteration = tile_iteration;
+        self
+    }
+
+    pub fn acc_tiles_n(mut self, acc_tiles_n: u32) -> Self {
+        self.acc_tiles_n = Some(acc_tiles_n);
+        self
+    }
+
+    pub fn output_layout(mut self, output_layout: OutputLayout) -> Self {
+        self.output_layout = output_layout;
+        self
+    }
+
+    pub fn load_scale(mut self, load_scale: LoadScale) -> Self {
+        self.load_scale = load_scale;
+        self
+    }
+
+    pub fn load_transform(mut self, load_transform: LoadTransform) -> Self {
+        self.load_transform = load_transform;
+        self
+    }
+
+    /// Multiply complex operands with `complex`, whose Lhs holds the pairs of each number along
+    /// k, so Rhs has one row for every two elements of Lhs along k.
+    pub fn complex(mut self, complex: ComplexProduct) -> Self {
+        self.complex = Some(complex);
+        self
+    }
+
     pub fn loading_precompute_strategy(
         mut self,
         loading_precompute_strategy: LoadingPrecomputeStrategy,
@@ -99,12 +168,24 @@ impl MatmulSelectionBuilder {
     }
 
     pub fn build(self) -> MatmulSelection {
+        let mut tiling_scheme = self.tiling_scheme.unwrap();
+        if self.complex.is_some() {
+            tiling_scheme.rhs_k_packing = 2;
+        }
+
         MatmulSelection {
             plane_dim: self.plane_dim.unwrap(),
-            tiling_scheme: self.tiling_scheme.unwrap(),
+            tiling_scheme,
             hypercube_selection: self.hypercube_selection.unwrap(),
             quantized: self.quantized,
             partition_buffering: self.partition_buffering,
+            accumulator_flush: self.accumulator_flush,
+            tile_iteration: self.tile_iteration,
+            acc_tiles_n: self.acc_tiles_n,
+            output_layout: self.output_layout,
+            load_scale: self.load_scale,
+            load_transform: self.load_transform,
+            complex: self.complex,
             loading_precompute_strategy: self.loading_precompute_strategy,
             reader_mode: self.reader_mode,
             load_specialization_config: self.load_specialization_config,
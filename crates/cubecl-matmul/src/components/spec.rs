@@ -1,5 +1,6 @@
 use cubecl_core::{ir::StorageType, prelude::*};
 use half::{bf16, f16};
+use std::marker::PhantomData;
 
 use super::global::args::{MatmulArgs, TensorArgs};
 
@@ -47,6 +48,22 @@ impl<EG: Numeric, ES: Numeric> MatrixPrecision for (EG, ES) {
     type Register = ES;
 }
 
+/// Matrix stored as `ES` in global and shared memory, and upcast to `ER` once in registers.
+///
+/// The conversion is done in software when the tile is filled from the stage, so the tile matmul
+/// only needs to support arithmetic on `ER`.
+#[derive(Clone, Copy)]
+pub struct RegisterUpcast<ES: Numeric, ER: Numeric> {
+    _storage: PhantomData<ES>,
+    _register: PhantomData<ER>,
+}
+
+impl<ES: Numeric, ER: Numeric> MatrixPrecision for RegisterUpcast<ES, ER> {
+    type Global = ES;
+    type Stage = ES;
+    type Register = ER;
+}
+
 impl MatmulPrecision for f16 {
     type Lhs = (f16, f16);
     type Rhs = (f16, f16);
@@ -71,6 +88,34 @@ impl MatmulPrecision for bf16 {
     type Acc = (bf16, f32);
 }
 
+/// Operands stored as `bf16`, halving the bandwidth of `f32`, but multiplied in `f32`.
+///
+/// Meant for the register tile matmul on backends without `bf16` arithmetic, as it upcasts each
+/// element when filling the tile. The accumulator and output stay in `f32`.
+#[derive(Clone, Copy)]
+pub struct Bf16Storage;
+
+impl MatmulPrecision for Bf16Storage {
+    type Lhs = RegisterUpcast<bf16, f32>;
+    type Rhs = RegisterUpcast<bf16, f32>;
+    type Acc = (f32, f32);
+}
+
+/// `f16` activations as Lhs multiplied by `i8` weights as Rhs, accumulated in `f32`.
+///
+/// The weights are dequantized to `f16` as they are loaded into the stage, by setting their
+/// [load scale](crate::components::global::read::LoadScale::rhs) on the selection. Each operand
+/// is then cast to the accumulation type by the tile matmul, as the register tile matmul does
+/// when filling its tiles.
+#[derive(Clone, Copy)]
+pub struct Int8Weights;
+
+impl MatmulPrecision for Int8Weights {
+    type Lhs = (f16, f16);
+    type Rhs = (i8, f16);
+    type Acc = (f32, f32);
+}
+
 impl MatmulPrecision for f32 {
     type Lhs = (f32, f32);
     type Rhs = (f32, f32);
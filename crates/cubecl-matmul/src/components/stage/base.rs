@@ -1,5 +1,6 @@
 use cubecl_core as cubecl;
 use cubecl_core::prelude::*;
+use cubecl_runtime::memory_management::HardwareProperties;
 use cubecl_std::{CubeOption, CubeOptionExpand, tensor::layout::Coords2d};
 
 use crate::components::{AccS, global::MaxGlobalReaderPlanes};
@@ -8,11 +9,17 @@ use crate::components::{
 };
 use crate::components::{
     MatmulPrecision, MatmulProblem, MatrixLayout, TilingScheme,
-    global::{self, PlaneRoleConfig, RoleRuleConfig},
+    global::{
+        self, PlaneRoleConfig, RoleRuleConfig,
+        memory::OutputLayout,
+        read::{LoadScale, LoadTransform},
+    },
     tile::TileConfig,
 };
 use crate::components::{
-    error::MatmulSetupError, global::WriteEventListener, stage::StageMemoryConfig,
+    error::{MatmulAvailabilityError, MatmulSetupError},
+    global::WriteEventListener,
+    stage::StageMemoryConfig,
 };
 use crate::components::{
     stage::{NumStages, PartitionScheduler, PartitionSchedulerScheme},
@@ -154,6 +161,40 @@ pub trait StageMatmul<MP: MatmulPrecision>: 'static + Send + Sync {
         #[comptime] global_config: G,
     );
 
+    /// Executes the matrix multiplication of the last stage, handing each accumulator to the
+    /// stage writer as soon as it is complete, as described by [AccumulatorFlush::Interleaved].
+    ///
+    /// Equivalent to [execute](StageMatmul::execute) followed by
+    /// [write_results](StageMatmul::write_results), only the order of operations differs.
+    #[allow(clippy::too_many_arguments)]
+    fn execute_and_write_results<W: WriteEventListener, G: global::GlobalConfig>(
+        lhs: &Self::LhsStage,
+        rhs: &Self::RhsStage,
+        instruction_lhs: &mut Self::LhsTile,
+        instruction_rhs: &mut Self::RhsTile,
+        acc: &mut Self::Accumulators,
+        stage: &mut Self::OutStage,
+        listener: &mut W,
+        partition_scheduler: &PartitionScheduler,
+        #[comptime] stage_config: Self::Config,
+        #[comptime] global_config: G,
+    );
+
+    /// Executes the matrix multiplication of Lhs with every stage of `rhs`, adding each
+    /// product to the accumulators at the same index of `acc`.
+    ///
+    /// The Lhs fragments are loaded once per k and reused for all Rhs stages.
+    #[allow(clippy::too_many_arguments)]
+    fn execute_multi_rhs(
+        lhs: &Self::LhsStage,
+        rhs: &Sequence<Self::RhsStage>,
+        instruction_lhs: &mut Self::LhsTile,
+        instruction_rhs: &mut Self::RhsTile,
+        acc: &mut Sequence<Self::Accumulators>,
+        #[comptime] config: Self::Config,
+        partition_scheduler: &PartitionScheduler,
+    );
+
     fn init_scheduler(#[comptime] config: Self::Config) -> PartitionScheduler;
 }
 
@@ -200,6 +241,17 @@ pub trait StageConfig:
     /// Returns the [TilingScheme]
     fn tiling_scheme(&self) -> TilingScheme;
 
+    /// Number of tile matmuls in one stage, over all its partitions,
+    /// which is the number of tiles in m, n and k of the stage multiplied together
+    ///
+    /// This is counted per stage, not per cube: a cube computes it once for each stage it
+    /// iterates over along k. Unlike the element counts of the problem, this only depends on
+    /// the tiling.
+    fn total_tiles(&self) -> u32 {
+        let tiling = self.tiling_scheme();
+        tiling.tiles_in_stage_mn() * tiling.tiles_in_stage_k()
+    }
+
     /// Indicates the specialization roles for the planes
     fn plane_role_config(&self) -> PlaneRoleConfig;
 
@@ -218,8 +270,77 @@ pub trait StageConfig:
 
     fn partition_schedule_scheme(&self) -> PartitionSchedulerScheme;
 
+    /// How tiles are enumerated when executing a partition
+    fn tile_iteration(&self) -> TileIteration;
+
+    /// Number of accumulator tiles along n that are computed together for the whole k of
+    /// the stage, before moving to the next block of accumulators.
+    ///
+    /// Always divides the number of tiles in n of a partition.
+    fn acc_tiles_n(&self) -> u32;
+
+    /// How the output is laid out in global memory
+    fn output_layout(&self) -> OutputLayout;
+
+    /// The factors applied to the inputs as they are loaded into the stage
+    fn load_scale(&self) -> LoadScale;
+
+    /// The elementwise transforms applied to the inputs as they are loaded into the stage
+    fn load_transform(&self) -> LoadTransform;
+
     /// Number of stages in the stage
     fn num_stages(&self, ident: StageIdent) -> u32;
+
+    /// Number of bytes of shared memory needed by the stages and the output partitions,
+    /// given the sizes in bytes of the lhs, rhs and output elements
+    fn shared_memory_size(&self, lhs_s_size: u32, rhs_s_size: u32, eo_size: u32) -> u32;
+
+    /// Checks that the cubes of this stage matmul fit the limits of the device of the `client`
+    ///
+    /// See [StageConfig::validate_against_hardware] for the checks
+    fn validate_against_device<MP: MatmulPrecision, R: Runtime>(
+        &self,
+        client: &ComputeClient<R::Server, R::Channel>,
+    ) -> Result<(), MatmulAvailabilityError> {
+        self.validate_against_hardware::<MP>(&client.properties().hardware)
+    }
+
+    /// Checks that the cubes of this stage matmul fit the given `hardware` limits
+    ///
+    /// Returns an error if:
+    /// - all the planes, including the load-only ones, have more units than a cube can hold
+    /// - the planes can't be laid out in the maximal cube dimensions
+    /// - the shared memory needed for the precision `MP` exceeds the available amount
+    fn validate_against_hardware<MP: MatmulPrecision>(
+        &self,
+        hardware: &HardwareProperties,
+    ) -> Result<(), MatmulAvailabilityError> {
+        let num_planes = self.plane_role_config().plane_roles.total_count();
+        let cube_dim = CubeDim::new_2d(self.plane_dim(), num_planes);
+
+        let units = cube_dim.num_elems();
+        if units > hardware.max_units_per_cube {
+            return Err(MatmulAvailabilityError::UnitsPerCubeTooBig {
+                units,
+                max: hardware.max_units_per_cube,
+            });
+        }
+        if !hardware.max_cube_dim.can_contain(cube_dim) {
+            return Err(MatmulAvailabilityError::CubeDimTooBig(cube_dim));
+        }
+
+        let size = self.shared_memory_size(
+            LhsS::<MP>::elem_size(),
+            RhsS::<MP>::elem_size(),
+            AccS::<MP>::elem_size(),
+        );
+        let max = hardware.max_shared_memory_size as u32;
+        if size > max {
+            return Err(MatmulAvailabilityError::SharedMemoryTooBig { size, max });
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
@@ -227,6 +348,36 @@ pub enum PartitionBuffering {
     Single,
     #[default]
     Double,
+    /// Single buffer for Rhs, while all Lhs fragments of the partition are loaded in registers
+    /// once before iterating over Rhs. Meant for problems where M fits in a few tiles.
+    RegisterLhs,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+/// Defines when the accumulators of a partition are written to the output stage.
+pub enum AccumulatorFlush {
+    /// All accumulators are written once the last stage is fully computed.
+    #[default]
+    AfterExecution,
+    /// During the last stage, each column of accumulators is written as soon as it is complete,
+    /// overlapping the writes with the compute of the next columns.
+    ///
+    /// Only the simple global matmul with [PartitionBuffering::RegisterLhs], which computes the
+    /// partition column by column, supports it, the other setups return an error.
+    /// The output stage can't reuse the memory of the input stages, since they are still read.
+    Interleaved,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+/// Defines which tiles of a partition are loaded and computed.
+pub enum TileIteration {
+    /// Every tile of the stage is computed, including tiles made only of padding.
+    #[default]
+    Full,
+    /// Tiles are enumerated in order up to the real problem bounds.
+    /// Tiles lying entirely in the padding are skipped, partial tiles are still
+    /// computed and rely on the readers' bound checks for masking.
+    Ordered,
 }
 
 /// Stage that can be divided into tiles, with the same kind used by the
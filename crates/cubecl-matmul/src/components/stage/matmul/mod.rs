@@ -9,6 +9,13 @@ pub use plane_partitioned::{PlaneMatmulFamily, PlanePartitioner};
 pub use scheduler::{PartitionScheduler, PartitionSchedulerScheme};
 pub use unit_partitioned::{UnitMatmulFamily, UnitPartitioner};
 
+use cubecl_core::prelude::*;
+use cubecl_runtime::TypeUsage;
+
+use crate::components::{
+    AccG, LhsG, MatmulAvailabilityError, MatmulPrecision, MatmulSetupError, RhsG,
+};
+
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
 /// Number of stages in one shared memory, i.e. buffers for double buffering
 pub struct NumStages {
@@ -24,3 +31,24 @@ impl From<(u32, u32)> for NumStages {
         }
     }
 }
+
+/// Check that the global types of Lhs and Rhs can be converted, since each line is cast to its
+/// stage type when loaded. The tile matmul only checks its register types, and Lhs and Rhs may be
+/// stored in distinct types, as in [Int8Weights](crate::components::Int8Weights).
+pub(crate) fn check_input_availability<MP: MatmulPrecision, R: Runtime>(
+    client: &ComputeClient<R::Server, R::Channel>,
+) -> Result<(), MatmulSetupError> {
+    if !LhsG::<MP>::supported_uses(client).contains(TypeUsage::Conversion)
+        || !RhsG::<MP>::supported_uses(client).contains(TypeUsage::Conversion)
+    {
+        return Err(MatmulSetupError::Unavailable(
+            MatmulAvailabilityError::TypesUnavailable {
+                lhs: LhsG::<MP>::as_type_native_unchecked(),
+                rhs: RhsG::<MP>::as_type_native_unchecked(),
+                output: AccG::<MP>::as_type_native_unchecked(),
+            },
+        ));
+    }
+
+    Ok(())
+}
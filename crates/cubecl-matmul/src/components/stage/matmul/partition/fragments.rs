@@ -4,7 +4,7 @@ use crate::components::stage::StageConfig;
 use crate::components::{AccS, stage::Stage, tile::TileMatmul};
 use crate::components::{MatmulPrecision, MatrixPrecision};
 use cubecl::prelude::*;
-use cubecl_core::{self as cubecl, intrinsic};
+use cubecl_core as cubecl;
 
 #[derive(CubeType)]
 /// Wrapper over a sequence of Tile Matmul accumulators
@@ -50,7 +50,8 @@ impl<
         }
     }
 
-    /// Load all accumulators from the specified stage
+    /// Load all accumulators from the specified stage, block by block of
+    /// [acc_tiles_n](StageConfig::acc_tiles_n) columns
     pub fn load<R: Stage<AccS<MP>, ReadOnly, TileKind = TM::AccTile>>(
         &mut self,
         stage: &R,
@@ -58,14 +59,31 @@ impl<
     ) {
         let size_m = comptime![config.tiling_scheme().tiles_in_stage_partition_m()];
         let size_n = comptime![config.tiling_scheme().tiles_in_stage_partition_n()];
+        let acc_tiles_n = comptime![config.acc_tiles_n()];
+        let mut n_block = comptime![0u32];
+
         #[unroll]
-        for m in 0..size_m {
+        #[allow(clippy::explicit_counter_loop)]
+        for _ in 0..comptime![size_n / acc_tiles_n] {
+            let mut m = comptime![0u32];
+
             #[unroll]
-            for n in 0..size_n {
-                let acc = self.get_at_mut(unwrap(m), unwrap(n), config);
-                let tile = R::tile(stage, (m, n));
-                TM::load_acc(&tile, acc, config.tile_config());
+            #[allow(clippy::explicit_counter_loop)]
+            for _ in 0..size_m {
+                let mut n = comptime![n_block * acc_tiles_n];
+
+                #[unroll]
+                #[allow(clippy::explicit_counter_loop)]
+                for _ in 0..acc_tiles_n {
+                    let acc = self.get_at_mut(m, n, config);
+                    let tile = R::tile(stage, (m.runtime(), n.runtime()));
+                    TM::load_acc(&tile, acc, config.tile_config());
+
+                    comptime![n += 1];
+                }
+                comptime![m += 1];
             }
+            comptime![n_block += 1];
         }
     }
 
@@ -100,9 +118,3 @@ pub enum RhsTile<Rhs: CubeType> {
     Single(Rhs),
     Double((Rhs, Rhs)),
 }
-
-#[cube]
-#[allow(unused)]
-fn unwrap(i: u32) -> comptime_type!(u32) {
-    intrinsic!(|_| i.constant().unwrap().as_u32())
-}
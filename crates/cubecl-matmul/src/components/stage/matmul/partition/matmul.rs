@@ -1,9 +1,10 @@
 use std::marker::PhantomData;
 
 use super::fragments::{Accumulators, RhsTile, RhsTileExpand};
+use crate::components::global::{self, WriteEventListener};
 use crate::components::stage::StageConfig;
 use crate::components::stage::matmul::scheduler::PartitionScheduler;
-use crate::components::stage::{PartitionBuffering, StageEventListener};
+use crate::components::stage::{NoEvent, PartitionBuffering, StageEventListener, TileIteration};
 use crate::components::tile::TileMatmul;
 use crate::components::{AccS, stage::StageEvent};
 use crate::components::{LhsS, MatmulPrecision, RhsS};
@@ -45,7 +46,7 @@ where
 {
     #[allow(clippy::too_many_arguments)]
     /// Execute all Tile Matmuls inside the partition
-    /// Can be with single or double buffering
+    /// Can be with single or double buffering, or with lhs held in registers
     pub fn execute_with_listener<SEL: StageEventListener<S>>(
         lhs_stage: &StageLhs,
         rhs_stage: &StageRhs,
@@ -57,16 +58,31 @@ where
         partition_iterator: &PartitionScheduler,
     ) {
         match rhs_fragments {
-            RhsTile::Single(rhs_fragment) => Self::execute_single_buffer::<SEL>(
-                lhs_stage,
-                rhs_stage,
-                lhs_fragment,
-                rhs_fragment,
-                acc,
-                config,
-                listener,
-                partition_iterator,
-            ),
+            RhsTile::Single(rhs_fragment) => {
+                if comptime![config.partition_buffering() == PartitionBuffering::RegisterLhs] {
+                    Self::execute_register_lhs::<SEL>(
+                        lhs_stage,
+                        rhs_stage,
+                        lhs_fragment,
+                        rhs_fragment,
+                        acc,
+                        config,
+                        listener,
+                        partition_iterator,
+                    )
+                } else {
+                    Self::execute_single_buffer::<SEL>(
+                        lhs_stage,
+                        rhs_stage,
+                        lhs_fragment,
+                        rhs_fragment,
+                        acc,
+                        config,
+                        listener,
+                        partition_iterator,
+                    )
+                }
+            }
             RhsTile::Double(rhs_fragments) => Self::execute_double_buffer::<SEL>(
                 lhs_stage,
                 rhs_stage,
@@ -92,13 +108,27 @@ where
         let tile_config = config.tile_config();
         let mut lhs = Sequence::new();
 
+        // Lhs fragments are kept for every k only when they stay in registers
+        let num_lhs_fragments = comptime! {
+            let tiling_scheme = config.tiling_scheme();
+            match config.partition_buffering() {
+                PartitionBuffering::RegisterLhs => {
+                    tiling_scheme.tiles_in_stage_partition_m()
+                        * tiling_scheme.tiles_in_stage_partition_k()
+                }
+                _ => tiling_scheme.tiles_in_stage_partition_m(),
+            }
+        };
+
         #[unroll]
-        for _ in 0..comptime!(config.tiling_scheme().tiles_in_stage_partition_m()) {
+        for _ in 0..num_lhs_fragments {
             lhs.push(TM::allocate_lhs(tile_config));
         }
 
         let rhs = match config.partition_buffering() {
-            PartitionBuffering::Single => RhsTile::new_Single(TM::allocate_rhs(tile_config)),
+            PartitionBuffering::Single | PartitionBuffering::RegisterLhs => {
+                RhsTile::new_Single(TM::allocate_rhs(tile_config))
+            }
             PartitionBuffering::Double => {
                 RhsTile::new_Double((TM::allocate_rhs(tile_config), TM::allocate_rhs(tile_config)))
             }
@@ -128,6 +158,10 @@ where
 
     /// Execute partition matmul with a single buffer for rhs.
     ///
+    /// The accumulators are computed in blocks of [acc_tiles_n](StageConfig::acc_tiles_n)
+    /// columns, each block going through the whole k of the stage before the next one.
+    /// The lhs fragments are loaded again for each block.
+    ///
     /// This function can call functions at various events through the listener.
     #[allow(clippy::too_many_arguments)]
     fn execute_single_buffer<SEL: StageEventListener<S>>(
@@ -142,11 +176,151 @@ where
     ) {
         SEL::on_event(&mut listener, StageEvent::Begin, config);
 
+        let m_iterations = config.tiling_scheme().tiles_in_stage_partition_m();
+        let n_iterations = config.tiling_scheme().tiles_in_stage_partition_n();
+        let k_iterations = config.tiling_scheme().tiles_in_stage_partition_k();
+        let acc_tiles_n = config.acc_tiles_n();
+        let n_blocks = comptime!(n_iterations / acc_tiles_n);
+
+        let mut lhs_load_counter = comptime![0];
+        let mut rhs_load_counter = comptime![0];
+        let mut execute_counter = comptime![0];
+        let lhs_load_total = comptime!(m_iterations * k_iterations * n_blocks);
+        let rhs_load_total = comptime!(n_iterations * k_iterations);
+        let execute_total = comptime!(m_iterations * n_iterations * k_iterations);
+
+        let mut n_block = comptime![0u32];
+
+        #[allow(clippy::explicit_counter_loop)]
+        #[unroll]
+        for _ in 0..n_blocks {
+            let mut k_iter = comptime![0u32];
+
+            #[allow(clippy::explicit_counter_loop)]
+            #[unroll]
+            for _ in 0..k_iterations {
+                let mut m_iter = comptime![0u32];
+                let k_load_iter = partition_scheduler.map_k(k_iter);
+
+                #[allow(clippy::explicit_counter_loop)]
+                #[unroll]
+                for _ in 0..m_iterations {
+                    let m_load_iter = partition_scheduler.map_m(m_iter);
+
+                    if partition_scheduler.is_m_in_bounds(m_load_iter) {
+                        let tile_lhs = StageLhs::tile(lhs_stage, (m_load_iter, k_load_iter));
+                        TM::load_lhs(
+                            &tile_lhs,
+                            lhs_fragment.index_mut(m_iter),
+                            config.tile_config(),
+                        );
+                    }
+                    SEL::on_event(
+                        &mut listener,
+                        comptime![StageEvent::LhsLoaded {
+                            current: lhs_load_counter,
+                            total: lhs_load_total
+                        }],
+                        config,
+                    );
+                    comptime!(lhs_load_counter += 1);
+
+                    comptime![m_iter += 1];
+                }
+
+                let mut n_iter = comptime![n_block * acc_tiles_n];
+
+                #[unroll]
+                #[allow(clippy::explicit_counter_loop)]
+                for _ in 0..acc_tiles_n {
+                    let n_load_iter = partition_scheduler.map_n(n_iter);
+
+                    let n_in_bounds = partition_scheduler.is_n_in_bounds(n_load_iter);
+
+                    if n_in_bounds {
+                        let rhs_tile_next = StageRhs::tile(rhs_stage, (k_load_iter, n_load_iter));
+                        TM::load_rhs(&rhs_tile_next, rhs_fragment, config.tile_config());
+                    }
+                    SEL::on_event(
+                        &mut listener,
+                        comptime![StageEvent::RhsLoaded {
+                            current: rhs_load_counter,
+                            total: rhs_load_total
+                        }],
+                        config,
+                    );
+                    comptime!(rhs_load_counter += 1);
+
+                    let mut m_iter = comptime![0u32];
+
+                    #[allow(clippy::explicit_counter_loop)]
+                    #[unroll]
+                    for _ in 0..m_iterations {
+                        let m_load_iter = partition_scheduler.map_m(m_iter);
+
+                        if n_in_bounds && partition_scheduler.is_m_in_bounds(m_load_iter) {
+                            let accumulator =
+                                Accumulators::<MP, TM, S>::get_at_mut(acc, m_iter, n_iter, config);
+                            Self::execute_tile(
+                                lhs_fragment.index(m_iter),
+                                rhs_fragment,
+                                accumulator,
+                                k_load_iter,
+                                config,
+                                partition_scheduler,
+                            );
+                        }
+                        SEL::on_event(
+                            &mut listener,
+                            comptime![StageEvent::TileMatmulCompleted {
+                                current: execute_counter,
+                                total: execute_total
+                            }],
+                            config,
+                        );
+                        comptime!(execute_counter += 1);
+
+                        comptime![m_iter += 1];
+                    }
+
+                    comptime![n_iter += 1];
+                }
+
+                comptime![k_iter += 1];
+            }
+
+            comptime![n_block += 1];
+        }
+
+        assert!(lhs_load_counter == lhs_load_total);
+        assert!(rhs_load_counter == rhs_load_total);
+        assert!(execute_counter == execute_total);
+        SEL::on_event(&mut listener, comptime!(StageEvent::Finish), config);
+    }
+
+    /// Execute partition matmul with all lhs fragments held in registers.
+    ///
+    /// Every lhs tile of the partition is loaded once, then each rhs tile is loaded
+    /// and multiplied with the resident lhs fragments of the same k.
+    ///
+    /// This function can call functions at various events through the listener.
+    #[allow(clippy::too_many_arguments)]
+    fn execute_register_lhs<SEL: StageEventListener<S>>(
+        lhs_stage: &StageLhs,
+        rhs_stage: &StageRhs,
+        lhs_fragments: &mut Sequence<TM::LhsFragment>,
+        rhs_fragment: &mut TM::RhsFragment,
+        acc: &mut Accumulators<MP, TM, S>,
+        #[comptime] config: S,
+        mut listener: SEL,
+        partition_scheduler: &PartitionScheduler,
+    ) {
+        SEL::on_event(&mut listener, StageEvent::Begin, config);
+
         let m_iterations = config.tiling_scheme().tiles_in_stage_partition_m();
         let n_iterations = config.tiling_scheme().tiles_in_stage_partition_n();
         let k_iterations = config.tiling_scheme().tiles_in_stage_partition_k();
 
-        let mut k_iter = comptime![0u32];
         let mut lhs_load_counter = comptime![0];
         let mut rhs_load_counter = comptime![0];
         let mut execute_counter = comptime![0];
@@ -154,23 +328,27 @@ where
         let rhs_load_total = comptime!(n_iterations * k_iterations);
         let execute_total = comptime!(m_iterations * n_iterations * k_iterations);
 
+        let mut k_iter = comptime![0u32];
+
         #[allow(clippy::explicit_counter_loop)]
         #[unroll]
         for _ in 0..k_iterations {
-            let mut m_iter = comptime![0u32];
             let k_load_iter = partition_scheduler.map_k(k_iter);
+            let mut m_iter = comptime![0u32];
 
             #[allow(clippy::explicit_counter_loop)]
             #[unroll]
             for _ in 0..m_iterations {
                 let m_load_iter = partition_scheduler.map_m(m_iter);
 
-                let tile_lhs = StageLhs::tile(lhs_stage, (m_load_iter, k_load_iter));
-                TM::load_lhs(
-                    &tile_lhs,
-                    lhs_fragment.index_mut(m_iter),
-                    config.tile_config(),
-                );
+                if partition_scheduler.is_m_in_bounds(m_load_iter) {
+                    let tile_lhs = StageLhs::tile(lhs_stage, (m_load_iter, k_load_iter));
+                    TM::load_lhs(
+                        &tile_lhs,
+                        lhs_fragments.index_mut(comptime![k_iter * m_iterations + m_iter]),
+                        config.tile_config(),
+                    );
+                }
                 SEL::on_event(
                     &mut listener,
                     comptime![StageEvent::LhsLoaded {
@@ -184,15 +362,27 @@ where
                 comptime![m_iter += 1];
             }
 
-            let mut n_iter = comptime![0u32];
+            comptime![k_iter += 1];
+        }
+
+        let mut n_iter = comptime![0u32];
+
+        #[allow(clippy::explicit_counter_loop)]
+        #[unroll]
+        for _ in 0..n_iterations {
+            let n_load_iter = partition_scheduler.map_n(n_iter);
+            let n_in_bounds = partition_scheduler.is_n_in_bounds(n_load_iter);
+            let mut k_iter = comptime![0u32];
 
-            #[unroll]
             #[allow(clippy::explicit_counter_loop)]
-            for _ in 0..n_iterations {
-                let n_load_iter = partition_scheduler.map_n(n_iter);
+            #[unroll]
+            for _ in 0..k_iterations {
+                let k_load_iter = partition_scheduler.map_k(k_iter);
 
-                let rhs_tile_next = StageRhs::tile(rhs_stage, (k_load_iter, n_load_iter));
-                TM::load_rhs(&rhs_tile_next, rhs_fragment, config.tile_config());
+                if n_in_bounds {
+                    let rhs_tile = StageRhs::tile(rhs_stage, (k_load_iter, n_load_iter));
+                    TM::load_rhs(&rhs_tile, rhs_fragment, config.tile_config());
+                }
                 SEL::on_event(
                     &mut listener,
                     comptime![StageEvent::RhsLoaded {
@@ -208,14 +398,20 @@ where
                 #[allow(clippy::explicit_counter_loop)]
                 #[unroll]
                 for _ in 0..m_iterations {
-                    let accumulator =
-                        Accumulators::<MP, TM, S>::get_at_mut(acc, m_iter, n_iter, config);
-                    TM::execute(
-                        lhs_fragment.index(m_iter),
-                        rhs_fragment,
-                        accumulator,
-                        config.tile_config(),
-                    );
+                    let m_load_iter = partition_scheduler.map_m(m_iter);
+
+                    if n_in_bounds && partition_scheduler.is_m_in_bounds(m_load_iter) {
+                        let accumulator =
+                            Accumulators::<MP, TM, S>::get_at_mut(acc, m_iter, n_iter, config);
+                        Self::execute_tile(
+                            lhs_fragments.index(comptime![k_iter * m_iterations + m_iter]),
+                            rhs_fragment,
+                            accumulator,
+                            k_load_iter,
+                            config,
+                            partition_scheduler,
+                        );
+                    }
                     SEL::on_event(
                         &mut listener,
                         comptime![StageEvent::TileMatmulCompleted {
@@ -229,10 +425,10 @@ where
                     comptime![m_iter += 1];
                 }
 
-                comptime![n_iter += 1];
+                comptime![k_iter += 1];
             }
 
-            comptime![k_iter += 1];
+            comptime![n_iter += 1];
         }
 
         assert!(lhs_load_counter == lhs_load_total);
@@ -281,12 +477,14 @@ where
             for _ in 0..m_iterations {
                 let m_load_iter = partition_scheduler.map_m(m_iter);
 
-                let tile_lhs = StageLhs::tile(lhs_stage, (m_load_iter, k_load_iter));
-                TM::load_lhs(
-                    &tile_lhs,
-                    lhs_fragment.index_mut(m_iter),
-                    config.tile_config(),
-                );
+                if partition_scheduler.is_m_in_bounds(m_load_iter) {
+                    let tile_lhs = StageLhs::tile(lhs_stage, (m_load_iter, k_load_iter));
+                    TM::load_lhs(
+                        &tile_lhs,
+                        lhs_fragment.index_mut(m_iter),
+                        config.tile_config(),
+                    );
+                }
                 SEL::on_event(
                     &mut listener,
                     comptime![StageEvent::LhsLoaded {
@@ -303,8 +501,10 @@ where
             let mut n_iter = comptime![0u32];
             let n_load_iter = partition_scheduler.map_n(n_iter);
 
-            let rhs_tile_first = StageRhs::tile(rhs_stage, (k_load_iter, n_load_iter));
-            TM::load_rhs(&rhs_tile_first, &mut rhs_fragments.0, config.tile_config());
+            if partition_scheduler.is_n_in_bounds(n_load_iter) {
+                let rhs_tile_first = StageRhs::tile(rhs_stage, (k_load_iter, n_load_iter));
+                TM::load_rhs(&rhs_tile_first, &mut rhs_fragments.0, config.tile_config());
+            }
             SEL::on_event(
                 &mut listener,
                 comptime!(StageEvent::RhsLoaded {
@@ -325,8 +525,10 @@ where
                 };
 
                 let n_load_iter = partition_scheduler.map_n(comptime![n_iter + 1]);
-                let rhs_tile_next = StageRhs::tile(rhs_stage, (k_load_iter, n_load_iter));
-                TM::load_rhs(&rhs_tile_next, next, config.tile_config());
+                if partition_scheduler.is_n_in_bounds(n_load_iter) {
+                    let rhs_tile_next = StageRhs::tile(rhs_stage, (k_load_iter, n_load_iter));
+                    TM::load_rhs(&rhs_tile_next, next, config.tile_config());
+                }
                 SEL::on_event(
                     &mut listener,
                     comptime!(StageEvent::RhsLoaded {
@@ -337,20 +539,28 @@ where
                 );
                 comptime!(rhs_load_counter += 1);
 
+                let n_in_bounds =
+                    partition_scheduler.is_n_in_bounds(partition_scheduler.map_n(n_iter));
                 let mut m_iter = comptime![0u32];
 
                 #[allow(clippy::explicit_counter_loop)]
                 #[unroll]
                 for _ in 0..m_iterations {
-                    let accumulator =
-                        Accumulators::<MP, TM, S>::get_at_mut(acc, m_iter, n_iter, config);
+                    let m_load_iter = partition_scheduler.map_m(m_iter);
 
-                    TM::execute(
-                        lhs_fragment.index(m_iter),
-                        current,
-                        accumulator,
-                        config.tile_config(),
-                    );
+                    if n_in_bounds && partition_scheduler.is_m_in_bounds(m_load_iter) {
+                        let accumulator =
+                            Accumulators::<MP, TM, S>::get_at_mut(acc, m_iter, n_iter, config);
+
+                        Self::execute_tile(
+                            lhs_fragment.index(m_iter),
+                            current,
+                            accumulator,
+                            k_load_iter,
+                            config,
+                            partition_scheduler,
+                        );
+                    }
                     SEL::on_event(
                         &mut listener,
                         comptime!(StageEvent::TileMatmulCompleted {
@@ -373,19 +583,26 @@ where
                 &mut rhs_fragments.1
             };
 
+            let n_in_bounds = partition_scheduler.is_n_in_bounds(partition_scheduler.map_n(n_iter));
             let mut m_iter = comptime![0u32];
 
             #[allow(clippy::explicit_counter_loop)]
             #[unroll]
             for _ in 0..m_iterations {
-                let accumulator =
-                    Accumulators::<MP, TM, S>::get_at_mut(acc, m_iter, n_iter, config);
-                TM::execute(
-                    lhs_fragment.index(m_iter),
-                    last,
-                    accumulator,
-                    config.tile_config(),
-                );
+                let m_load_iter = partition_scheduler.map_m(m_iter);
+
+                if n_in_bounds && partition_scheduler.is_m_in_bounds(m_load_iter) {
+                    let accumulator =
+                        Accumulators::<MP, TM, S>::get_at_mut(acc, m_iter, n_iter, config);
+                    Self::execute_tile(
+                        lhs_fragment.index(m_iter),
+                        last,
+                        accumulator,
+                        k_load_iter,
+                        config,
+                        partition_scheduler,
+                    );
+                }
                 SEL::on_event(
                     &mut listener,
                     comptime!(StageEvent::TileMatmulCompleted {
@@ -407,4 +624,545 @@ where
         assert!(execute_counter == execute_total);
         SEL::on_event(&mut listener, comptime!(StageEvent::Finish), config);
     }
+
+    #[allow(clippy::too_many_arguments)]
+    /// Execute all Tile Matmuls inside the partition, writing each accumulator to the
+    /// `out_stage` as soon as it is complete when the partition is computed column by column.
+    ///
+    /// Otherwise, all accumulators are written after the execution.
+    pub fn execute_and_write<StageOut, W: WriteEventListener>(
+        lhs_stage: &StageLhs,
+        rhs_stage: &StageRhs,
+        lhs_fragment: &mut Sequence<TM::LhsFragment>,
+        rhs_fragments: &mut RhsTile<TM::RhsFragment>,
+        acc: &mut Accumulators<MP, TM, S>,
+        out_stage: &mut StageOut,
+        listener: &mut W,
+        #[comptime] config: S,
+        partition_scheduler: &PartitionScheduler,
+    ) where
+        StageOut: Stage<AccS<MP>, ReadWrite, TileKind = TM::OutTile>,
+    {
+        match rhs_fragments {
+            RhsTile::Single(rhs_fragment) => {
+                if comptime![config.partition_buffering() == PartitionBuffering::RegisterLhs] {
+                    Self::execute_register_lhs_and_write::<StageOut, W>(
+                        lhs_stage,
+                        rhs_stage,
+                        lhs_fragment,
+                        rhs_fragment,
+                        acc,
+                        out_stage,
+                        listener,
+                        config,
+                        partition_scheduler,
+                    );
+                } else {
+                    Self::execute_single_buffer::<NoEvent>(
+                        lhs_stage,
+                        rhs_stage,
+                        lhs_fragment,
+                        rhs_fragment,
+                        acc,
+                        config,
+                        NoEvent::new(),
+                        partition_scheduler,
+                    );
+                    Self::write_results::<StageOut, W>(
+                        acc,
+                        out_stage,
+                        listener,
+                        config,
+                        partition_scheduler,
+                    );
+                }
+            }
+            RhsTile::Double(rhs_fragments) => {
+                Self::execute_double_buffer::<NoEvent>(
+                    lhs_stage,
+                    rhs_stage,
+                    lhs_fragment,
+                    rhs_fragments,
+                    acc,
+                    config,
+                    NoEvent::new(),
+                    partition_scheduler,
+                );
+                Self::write_results::<StageOut, W>(
+                    acc,
+                    out_stage,
+                    listener,
+                    config,
+                    partition_scheduler,
+                );
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    /// Execute the partition matmul of the lhs stage with every stage of `rhs_stages`,
+    /// adding each product to the accumulators at the same index of `accs`.
+    ///
+    /// For each k, the lhs fragments are loaded once and multiplied with the rhs tiles of
+    /// all stages, so the lhs is read from the stage a single time whatever the number of rhs.
+    pub fn execute_multi_rhs(
+        lhs_stage: &StageLhs,
+        rhs_stages: &Sequence<StageRhs>,
+        lhs_fragment: &mut Sequence<TM::LhsFragment>,
+        rhs_fragments: &mut RhsTile<TM::RhsFragment>,
+        accs: &mut Sequence<Accumulators<MP, TM, S>>,
+        #[comptime] config: S,
+        partition_scheduler: &PartitionScheduler,
+    ) {
+        match rhs_fragments {
+            RhsTile::Single(rhs_fragment) => Self::execute_multi_rhs_single_buffer(
+                lhs_stage,
+                rhs_stages,
+                lhs_fragment,
+                rhs_fragment,
+                accs,
+                config,
+                partition_scheduler,
+            ),
+            RhsTile::Double(rhs_fragments) => Self::execute_multi_rhs_double_buffer(
+                lhs_stage,
+                rhs_stages,
+                lhs_fragment,
+                rhs_fragments,
+                accs,
+                config,
+                partition_scheduler,
+            ),
+        }
+    }
+
+    /// Execute the partition matmul of the lhs stage with every stage of `rhs_stages`
+    /// with a single buffer for rhs.
+    fn execute_multi_rhs_single_buffer(
+        lhs_stage: &StageLhs,
+        rhs_stages: &Sequence<StageRhs>,
+        lhs_fragment: &mut Sequence<TM::LhsFragment>,
+        rhs_fragment: &mut TM::RhsFragment,
+        accs: &mut Sequence<Accumulators<MP, TM, S>>,
+        #[comptime] config: S,
+        partition_scheduler: &PartitionScheduler,
+    ) {
+        let m_iterations = config.tiling_scheme().tiles_in_stage_partition_m();
+        let n_iterations = config.tiling_scheme().tiles_in_stage_partition_n();
+        let k_iterations = config.tiling_scheme().tiles_in_stage_partition_k();
+        let num_rhs = comptime![rhs_stages.len()];
+
+        let mut k_iter = comptime![0u32];
+
+        #[allow(clippy::explicit_counter_loop)]
+        #[unroll]
+        for _ in 0..k_iterations {
+            let mut m_iter = comptime![0u32];
+            let k_load_iter = partition_scheduler.map_k(k_iter);
+
+            #[allow(clippy::explicit_counter_loop)]
+            #[unroll]
+            for _ in 0..m_iterations {
+                let m_load_iter = partition_scheduler.map_m(m_iter);
+
+                if partition_scheduler.is_m_in_bounds(m_load_iter) {
+                    let tile_lhs = StageLhs::tile(lhs_stage, (m_load_iter, k_load_iter));
+                    TM::load_lhs(
+                        &tile_lhs,
+                        lhs_fragment.index_mut(m_iter),
+                        config.tile_config(),
+                    );
+                }
+
+                comptime![m_iter += 1];
+            }
+
+            let mut rhs_index = comptime![0u32];
+
+            #[allow(clippy::explicit_counter_loop)]
+            #[unroll]
+            for _ in 0..num_rhs {
+                let rhs_stage = rhs_stages.index(rhs_index);
+                let acc = accs.index_mut(rhs_index);
+                let mut n_iter = comptime![0u32];
+
+                #[allow(clippy::explicit_counter_loop)]
+                #[unroll]
+                for _ in 0..n_iterations {
+                    let n_load_iter = partition_scheduler.map_n(n_iter);
+                    let n_in_bounds = partition_scheduler.is_n_in_bounds(n_load_iter);
+
+                    if n_in_bounds {
+                        let rhs_tile = StageRhs::tile(rhs_stage, (k_load_iter, n_load_iter));
+                        TM::load_rhs(&rhs_tile, rhs_fragment, config.tile_config());
+                    }
+
+                    let mut m_iter = comptime![0u32];
+
+                    #[allow(clippy::explicit_counter_loop)]
+                    #[unroll]
+                    for _ in 0..m_iterations {
+                        let m_load_iter = partition_scheduler.map_m(m_iter);
+
+                        if n_in_bounds && partition_scheduler.is_m_in_bounds(m_load_iter) {
+                            let accumulator =
+                                Accumulators::<MP, TM, S>::get_at_mut(acc, m_iter, n_iter, config);
+                            Self::execute_tile(
+                                lhs_fragment.index(m_iter),
+                                rhs_fragment,
+                                accumulator,
+                                k_load_iter,
+                                config,
+                                partition_scheduler,
+                            );
+                        }
+
+                        comptime![m_iter += 1];
+                    }
+
+                    comptime![n_iter += 1];
+                }
+
+                comptime![rhs_index += 1];
+            }
+
+            comptime![k_iter += 1];
+        }
+    }
+
+    /// Execute the partition matmul of the lhs stage with every stage of `rhs_stages`
+    /// with two buffers for rhs.
+    ///
+    /// The rhs tiles of all stages are loaded one after the other, each one while the previous
+    /// one is computed, so the next tile can belong to the next stage.
+    fn execute_multi_rhs_double_buffer(
+        lhs_stage: &StageLhs,
+        rhs_stages: &Sequence<StageRhs>,
+        lhs_fragment: &mut Sequence<TM::LhsFragment>,
+        rhs_fragments: &mut (TM::RhsFragment, TM::RhsFragment),
+        accs: &mut Sequence<Accumulators<MP, TM, S>>,
+        #[comptime] config: S,
+        partition_scheduler: &PartitionScheduler,
+    ) {
+        let m_iterations = config.tiling_scheme().tiles_in_stage_partition_m();
+        let n_iterations = config.tiling_scheme().tiles_in_stage_partition_n();
+        let k_iterations = config.tiling_scheme().tiles_in_stage_partition_k();
+        let num_rhs = comptime![rhs_stages.len()];
+        let num_rhs_tiles = comptime![num_rhs * n_iterations];
+
+        let mut k_iter = comptime![0u32];
+
+        #[allow(clippy::explicit_counter_loop)]
+        #[unroll]
+        for _ in 0..k_iterations {
+            let mut m_iter = comptime![0u32];
+            let k_load_iter = partition_scheduler.map_k(k_iter);
+
+            #[allow(clippy::explicit_counter_loop)]
+            #[unroll]
+            for _ in 0..m_iterations {
+                let m_load_iter = partition_scheduler.map_m(m_iter);
+
+                if partition_scheduler.is_m_in_bounds(m_load_iter) {
+                    let tile_lhs = StageLhs::tile(lhs_stage, (m_load_iter, k_load_iter));
+                    TM::load_lhs(
+                        &tile_lhs,
+                        lhs_fragment.index_mut(m_iter),
+                        config.tile_config(),
+                    );
+                }
+
+                comptime![m_iter += 1];
+            }
+
+            let n_load_iter = partition_scheduler.map_n(0u32);
+            if partition_scheduler.is_n_in_bounds(n_load_iter) {
+                let rhs_tile_first =
+                    StageRhs::tile(rhs_stages.index(0), (k_load_iter, n_load_iter));
+                TM::load_rhs(&rhs_tile_first, &mut rhs_fragments.0, config.tile_config());
+            }
+
+            // The rhs tiles are numbered across the stages, the n tiles of each stage in a row.
+            let mut tile_iter = comptime![0u32];
+
+            #[allow(clippy::explicit_counter_loop)]
+            #[unroll]
+            for _ in 0..num_rhs_tiles {
+                let (current, next) = if comptime! {tile_iter % 2 == 0} {
+                    (&mut rhs_fragments.0, &mut rhs_fragments.1)
+                } else {
+                    (&mut rhs_fragments.1, &mut rhs_fragments.0)
+                };
+
+                if comptime![tile_iter + 1 < num_rhs_tiles] {
+                    let next_rhs_index = comptime![(tile_iter + 1) / n_iterations];
+                    let next_n_iter = comptime![(tile_iter + 1) % n_iterations];
+                    let n_load_iter = partition_scheduler.map_n(next_n_iter);
+                    if partition_scheduler.is_n_in_bounds(n_load_iter) {
+                        let rhs_tile_next = StageRhs::tile(
+                            rhs_stages.index(next_rhs_index),
+                            (k_load_iter, n_load_iter),
+                        );
+                        TM::load_rhs(&rhs_tile_next, next, config.tile_config());
+                    }
+                }
+
+                let rhs_index = comptime![tile_iter / n_iterations];
+                let n_iter = comptime![tile_iter % n_iterations];
+                let acc = accs.index_mut(rhs_index);
+                let n_in_bounds =
+                    partition_scheduler.is_n_in_bounds(partition_scheduler.map_n(n_iter));
+                let mut m_iter = comptime![0u32];
+
+                #[allow(clippy::explicit_counter_loop)]
+                #[unroll]
+                for _ in 0..m_iterations {
+                    let m_load_iter = partition_scheduler.map_m(m_iter);
+
+                    if n_in_bounds && partition_scheduler.is_m_in_bounds(m_load_iter) {
+                        let accumulator =
+                            Accumulators::<MP, TM, S>::get_at_mut(acc, m_iter, n_iter, config);
+                        Self::execute_tile(
+                            lhs_fragment.index(m_iter),
+                            current,
+                            accumulator,
+                            k_load_iter,
+                            config,
+                            partition_scheduler,
+                        );
+                    }
+
+                    comptime![m_iter += 1];
+                }
+
+                comptime![tile_iter += 1];
+            }
+
+            comptime![k_iter += 1];
+        }
+    }
+
+    /// Write all accumulators of the partition to the `out_stage`, one tile at a time
+    pub fn write_results<StageOut, W: WriteEventListener>(
+        acc: &Accumulators<MP, TM, S>,
+        out_stage: &mut StageOut,
+        listener: &mut W,
+        #[comptime] config: S,
+        partition_scheduler: &PartitionScheduler,
+    ) where
+        StageOut: Stage<AccS<MP>, ReadWrite, TileKind = TM::OutTile>,
+    {
+        let m_iterations = config.tiling_scheme().tiles_in_stage_partition_m();
+        let n_iterations = config.tiling_scheme().tiles_in_stage_partition_n();
+        let acc_tiles_n = config.acc_tiles_n();
+
+        W::on_event(listener, global::WriteEvent::new_Begin());
+
+        let mut n_block = comptime![0u32];
+
+        // Blocks of accumulators are written in the order they are computed
+        #[unroll]
+        #[allow(clippy::explicit_counter_loop)]
+        for _ in 0..comptime![n_iterations / acc_tiles_n] {
+            let mut m_iter = comptime![0u32];
+
+            #[unroll]
+            #[allow(clippy::explicit_counter_loop)]
+            for _ in 0..comptime![m_iterations] {
+                let mut n_iter = comptime![n_block * acc_tiles_n];
+
+                #[unroll]
+                #[allow(clippy::explicit_counter_loop)]
+                for _ in 0..comptime![acc_tiles_n] {
+                    Self::write_tile::<StageOut, W>(
+                        acc,
+                        out_stage,
+                        listener,
+                        m_iter,
+                        n_iter,
+                        config,
+                        partition_scheduler,
+                    );
+
+                    comptime![n_iter += 1];
+                }
+                comptime![m_iter += 1];
+            }
+            comptime![n_block += 1];
+        }
+
+        W::on_event(listener, global::WriteEvent::new_Finish());
+    }
+
+    /// Execute the tile matmul of `lhs` and `rhs` into `acc`, for the tile at the global
+    /// index `k` along k of the stage.
+    ///
+    /// With [TileIteration::Ordered], only the real elements of the tile along k contribute,
+    /// so the last tile along k can be partial.
+    fn execute_tile(
+        lhs: &TM::LhsFragment,
+        rhs: &TM::RhsFragment,
+        acc: &mut TM::AccFragment,
+        k: u32,
+        #[comptime] config: S,
+        partition_scheduler: &PartitionScheduler,
+    ) {
+        match comptime![config.tile_iteration()] {
+            TileIteration::Full => TM::execute(lhs, rhs, acc, config.tile_config()),
+            TileIteration::Ordered => TM::execute_partial_k(
+                lhs,
+                rhs,
+                acc,
+                partition_scheduler.valid_k(k, config.tiling_scheme().tile_size),
+                config.tile_config(),
+            ),
+        }
+    }
+
+    /// Write the accumulator at (`m_iter`, `n_iter`) in the partition to the `out_stage`
+    fn write_tile<StageOut, W: WriteEventListener>(
+        acc: &Accumulators<MP, TM, S>,
+        out_stage: &mut StageOut,
+        listener: &mut W,
+        #[comptime] m_iter: u32,
+        #[comptime] n_iter: u32,
+        #[comptime] config: S,
+        partition_scheduler: &PartitionScheduler,
+    ) where
+        StageOut: Stage<AccS<MP>, ReadWrite, TileKind = TM::OutTile>,
+    {
+        let tile_accumulator = Accumulators::<MP, TM, S>::get_at(acc, m_iter, n_iter, config);
+
+        let tile_pos = (
+            partition_scheduler.map_m(m_iter),
+            partition_scheduler.map_n(n_iter),
+        );
+        let mut tile = StageOut::tile(out_stage, tile_pos);
+
+        // Write the results for one tile. To save shared memory space, it reuses the same spot for
+        // all tiles in the partition
+        TM::write_results(&mut tile, tile_accumulator, config.tile_config());
+        W::on_event(listener, global::WriteEvent::new_TileStored(tile_pos));
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    /// Execute partition matmul with all lhs fragments held in registers, like
+    /// [execute_register_lhs](Self::execute_register_lhs), writing each column of accumulators
+    /// to the `out_stage` right after its last tile matmul.
+    fn execute_register_lhs_and_write<StageOut, W: WriteEventListener>(
+        lhs_stage: &StageLhs,
+        rhs_stage: &StageRhs,
+        lhs_fragments: &mut Sequence<TM::LhsFragment>,
+        rhs_fragment: &mut TM::RhsFragment,
+        acc: &mut Accumulators<MP, TM, S>,
+        out_stage: &mut StageOut,
+        listener: &mut W,
+        #[comptime] config: S,
+        partition_scheduler: &PartitionScheduler,
+    ) where
+        StageOut: Stage<AccS<MP>, ReadWrite, TileKind = TM::OutTile>,
+    {
+        let m_iterations = config.tiling_scheme().tiles_in_stage_partition_m();
+        let n_iterations = config.tiling_scheme().tiles_in_stage_partition_n();
+        let k_iterations = config.tiling_scheme().tiles_in_stage_partition_k();
+
+        let mut k_iter = comptime![0u32];
+
+        #[allow(clippy::explicit_counter_loop)]
+        #[unroll]
+        for _ in 0..k_iterations {
+            let k_load_iter = partition_scheduler.map_k(k_iter);
+            let mut m_iter = comptime![0u32];
+
+            #[allow(clippy::explicit_counter_loop)]
+            #[unroll]
+            for _ in 0..m_iterations {
+                let m_load_iter = partition_scheduler.map_m(m_iter);
+
+                if partition_scheduler.is_m_in_bounds(m_load_iter) {
+                    let tile_lhs = StageLhs::tile(lhs_stage, (m_load_iter, k_load_iter));
+                    TM::load_lhs(
+                        &tile_lhs,
+                        lhs_fragments.index_mut(comptime![k_iter * m_iterations + m_iter]),
+                        config.tile_config(),
+                    );
+                }
+
+                comptime![m_iter += 1];
+            }
+
+            comptime![k_iter += 1];
+        }
+
+        W::on_event(listener, global::WriteEvent::new_Begin());
+
+        let mut n_iter = comptime![0u32];
+
+        #[allow(clippy::explicit_counter_loop)]
+        #[unroll]
+        for _ in 0..n_iterations {
+            let n_load_iter = partition_scheduler.map_n(n_iter);
+            let n_in_bounds = partition_scheduler.is_n_in_bounds(n_load_iter);
+            let mut k_iter = comptime![0u32];
+
+            #[allow(clippy::explicit_counter_loop)]
+            #[unroll]
+            for _ in 0..k_iterations {
+                let k_load_iter = partition_scheduler.map_k(k_iter);
+
+                if n_in_bounds {
+                    let rhs_tile = StageRhs::tile(rhs_stage, (k_load_iter, n_load_iter));
+                    TM::load_rhs(&rhs_tile, rhs_fragment, config.tile_config());
+                }
+
+                let mut m_iter = comptime![0u32];
+
+                #[allow(clippy::explicit_counter_loop)]
+                #[unroll]
+                for _ in 0..m_iterations {
+                    let m_load_iter = partition_scheduler.map_m(m_iter);
+
+                    if n_in_bounds && partition_scheduler.is_m_in_bounds(m_load_iter) {
+                        let accumulator =
+                            Accumulators::<MP, TM, S>::get_at_mut(acc, m_iter, n_iter, config);
+                        Self::execute_tile(
+                            lhs_fragments.index(comptime![k_iter * m_iterations + m_iter]),
+                            rhs_fragment,
+                            accumulator,
+                            k_load_iter,
+                            config,
+                            partition_scheduler,
+                        );
+                    }
+
+                    comptime![m_iter += 1];
+                }
+
+                comptime![k_iter += 1];
+            }
+
+            // The column is complete, its writes overlap the compute of the next column
+            let mut m_iter = comptime![0u32];
+
+            #[allow(clippy::explicit_counter_loop)]
+            #[unroll]
+            for _ in 0..m_iterations {
+                Self::write_tile::<StageOut, W>(
+                    acc,
+                    out_stage,
+                    listener,
+                    m_iter,
+                    n_iter,
+                    config,
+                    partition_scheduler,
+                );
+
+                comptime![m_iter += 1];
+            }
+
+            comptime![n_iter += 1];
+        }
+
+        W::on_event(listener, global::WriteEvent::new_Finish());
+    }
 }
@@ -168,44 +168,60 @@ where
         listener: &mut W,
         partition_scheduler: &PartitionScheduler,
         #[comptime] stage_config: Self::Config,
-        #[comptime] global_config: G,
+        #[comptime] _global_config: G,
     ) {
-        let m_iterations = global_config.tiling_scheme().tiles_in_stage_partition_m();
-        let n_iterations = global_config.tiling_scheme().tiles_in_stage_partition_n();
-
-        let mut m_iter = comptime![0u32];
-
-        W::on_event(listener, global::WriteEvent::new_Begin());
-
-        // Iterate over each tile in the partition
-        #[unroll]
-        #[allow(clippy::explicit_counter_loop)]
-        for _ in 0..comptime![m_iterations] {
-            let m_load_iter = partition_scheduler.map_m(m_iter);
-            let mut n_iter = comptime![0u32];
-
-            #[unroll]
-            #[allow(clippy::explicit_counter_loop)]
-            for _ in 0..comptime![n_iterations] {
-                let n_load_iter = partition_scheduler.map_n(n_iter);
-
-                let tile_accumulator =
-                    Accumulators::<MP, TM, S>::get_at(acc, m_iter, n_iter, stage_config);
-
-                let tile_pos = (m_load_iter, n_load_iter);
-                let mut tile = Self::OutStage::tile(stage, tile_pos);
-
-                // Write the results for one tile. To save shared memory space, it reuses the same spot for
-                // all tiles in the partition
-                TM::write_results(&mut tile, tile_accumulator, stage_config.tile_config());
-                W::on_event(listener, global::WriteEvent::new_TileStored(tile_pos));
+        PartitionMatmul::<MP, TM, StageLhs, StageRhs, StageAcc, S>::write_results::<StageOut, W>(
+            acc,
+            stage,
+            listener,
+            stage_config,
+            partition_scheduler,
+        );
+    }
 
-                comptime![n_iter += 1];
-            }
-            comptime![m_iter += 1];
-        }
+    fn execute_and_write_results<W: WriteEventListener, G: global::GlobalConfig>(
+        lhs_stage: &StageLhs,
+        rhs_stage: &StageRhs,
+        lhs_fragment: &mut Self::LhsTile,
+        rhs_fragments: &mut Self::RhsTile,
+        acc: &mut Self::Accumulators,
+        stage: &mut Self::OutStage,
+        listener: &mut W,
+        partition_scheduler: &PartitionScheduler,
+        #[comptime] stage_config: Self::Config,
+        #[comptime] _global_config: G,
+    ) {
+        PartitionMatmul::<MP, TM, StageLhs, StageRhs, StageAcc, S>::execute_and_write::<StageOut, W>(
+            lhs_stage,
+            rhs_stage,
+            lhs_fragment,
+            rhs_fragments,
+            acc,
+            stage,
+            listener,
+            stage_config,
+            partition_scheduler,
+        );
+    }
 
-        W::on_event(listener, global::WriteEvent::new_Finish());
+    fn execute_multi_rhs(
+        lhs_stage: &StageLhs,
+        rhs_stages: &Sequence<StageRhs>,
+        lhs_fragment: &mut Self::LhsTile,
+        rhs_fragments: &mut Self::RhsTile,
+        acc: &mut Sequence<Self::Accumulators>,
+        #[comptime] config: Self::Config,
+        partition_scheduler: &PartitionScheduler,
+    ) {
+        PartitionMatmul::<MP, TM, StageLhs, StageRhs, StageAcc, S>::execute_multi_rhs(
+            lhs_stage,
+            rhs_stages,
+            lhs_fragment,
+            rhs_fragments,
+            acc,
+            config,
+            partition_scheduler,
+        );
     }
 
     fn init_scheduler(#[comptime] config: Self::Config) -> PartitionScheduler {
@@ -216,6 +232,7 @@ where
             partition_col,
             config.tiling_scheme().partition_size,
             config.partition_schedule_scheme(),
+            config.tile_iteration(),
         )
     }
 }
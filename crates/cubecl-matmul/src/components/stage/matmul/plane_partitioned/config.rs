@@ -1,8 +1,12 @@
 use crate::components::{
     MatrixLayout, StageIdent, TilingScheme,
     error::MatmulSetupError,
-    global::{PlaneRoleConfig, RoleRuleConfig},
-    stage::{NumStages, PartitionBuffering, PartitionSchedulerScheme, StageConfig},
+    global::{
+        PlaneRoleConfig, RoleRuleConfig,
+        memory::OutputLayout,
+        read::{LoadScale, LoadTransform},
+    },
+    stage::{NumStages, PartitionBuffering, PartitionSchedulerScheme, StageConfig, TileIteration},
     tile::TileConfig,
 };
 
@@ -13,6 +17,11 @@ pub struct PlanePartitionedStageConfig<T: TileConfig> {
     pub tiling_scheme: TilingScheme,
     pub quantized: bool,
     pub partition_buffering: PartitionBuffering,
+    pub tile_iteration: TileIteration,
+    pub acc_tiles_n: u32,
+    pub output_layout: OutputLayout,
+    pub load_scale: LoadScale,
+    pub load_transform: LoadTransform,
     pub num_stages: NumStages,
     plane_role_config: PlaneRoleConfig,
     ordered: bool,
@@ -83,6 +92,26 @@ impl<T: TileConfig> StageConfig for PlanePartitionedStageConfig<T> {
         PartitionSchedulerScheme::Naive
     }
 
+    fn tile_iteration(&self) -> TileIteration {
+        self.tile_iteration
+    }
+
+    fn acc_tiles_n(&self) -> u32 {
+        self.acc_tiles_n
+    }
+
+    fn output_layout(&self) -> OutputLayout {
+        self.output_layout
+    }
+
+    fn load_scale(&self) -> LoadScale {
+        self.load_scale
+    }
+
+    fn load_transform(&self) -> LoadTransform {
+        self.load_transform
+    }
+
     fn num_stages(&self, ident: StageIdent) -> u32 {
         match ident {
             StageIdent::Lhs => self.num_stages.lhs,
@@ -91,6 +120,13 @@ impl<T: TileConfig> StageConfig for PlanePartitionedStageConfig<T> {
             StageIdent::Out => 1,
         }
     }
+
+    fn shared_memory_size(&self, lhs_s_size: u32, rhs_s_size: u32, eo_size: u32) -> u32 {
+        let lhs_smem_size = self.tiling_scheme.elements_in_stage_mk() * self.num_stages.lhs;
+        let rhs_smem_size = self.tiling_scheme.elements_in_stage_nk() * self.num_stages.rhs;
+        let out_smem_size = self.tiling_scheme.elements_in_tile_mn() * self.num_main_flow_planes();
+        lhs_s_size * lhs_smem_size + rhs_s_size * rhs_smem_size + eo_size * out_smem_size
+    }
 }
 
 impl<T: TileConfig> PlanePartitionedStageConfig<T> {
@@ -100,12 +136,19 @@ impl<T: TileConfig> PlanePartitionedStageConfig<T> {
     /// May return an error if:
     /// - the number of computing planes is different from the number of partitions
     /// - double buffering is enabled but there is only one tile in n
+    /// - the accumulator tiles in n don't divide the tiles in n of a partition, or aren't
+    ///   all the tiles in n without single buffering
     /// - the required shared memory exceeds the available limit
     pub fn new(
         tile_config: T,
         tiling_scheme: TilingScheme,
         quantized: bool,
         partition_buffering: PartitionBuffering,
+        tile_iteration: TileIteration,
+        acc_tiles_n: Option<u32>,
+        output_layout: OutputLayout,
+        load_scale: LoadScale,
+        load_transform: LoadTransform,
         num_stages: NumStages,
         plane_role_config: PlaneRoleConfig,
         lhs_s_size: u32,
@@ -119,6 +162,11 @@ impl<T: TileConfig> PlanePartitionedStageConfig<T> {
             tiling_scheme,
             quantized,
             partition_buffering,
+            tile_iteration,
+            acc_tiles_n: acc_tiles_n.unwrap_or(tiling_scheme.tiles_in_stage_partition_n()),
+            output_layout,
+            load_scale,
+            load_transform,
             num_stages,
             plane_role_config,
             ordered,
@@ -152,11 +200,22 @@ impl<T: TileConfig> PlanePartitionedStageConfig<T> {
             )));
         }
 
-        let lhs_smem_size = self.tiling_scheme.elements_in_stage_mk() * self.num_stages.lhs;
-        let rhs_smem_size = self.tiling_scheme.elements_in_stage_nk() * self.num_stages.rhs;
-        let out_smem_size = self.tiling_scheme.elements_in_tile_mn() * num_compute_planes;
-        let smem_total_size =
-            lhs_s_size * lhs_smem_size + rhs_s_size * rhs_smem_size + eo_size * out_smem_size;
+        let tiles_n = tiling_scheme.tiles_in_stage_partition_n();
+        if self.acc_tiles_n == 0 || tiles_n % self.acc_tiles_n != 0 {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(format!(
+                "Error: Accumulator tiles in n {} should divide the {tiles_n} tiles in n of a partition.",
+                self.acc_tiles_n
+            ))));
+        }
+
+        if self.acc_tiles_n != tiles_n && self.partition_buffering() != PartitionBuffering::Single {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(
+                "Error: Blocking the accumulators in n is only supported with single partition buffering."
+                    .to_string(),
+            )));
+        }
+
+        let smem_total_size = self.shared_memory_size(lhs_s_size, rhs_s_size, eo_size);
 
         if smem_total_size > smem_limit {
             return Err(MatmulSetupError::InvalidConfig(Box::new(format!(
@@ -9,6 +9,7 @@ use crate::components::global::MaxGlobalReaderPlanes;
 use crate::components::global::PlaneRoleConfig;
 use crate::components::stage::NumStages;
 use crate::components::stage::StageFamily;
+use crate::components::stage::matmul::check_input_availability;
 use crate::components::stage::matmul::plane_partitioned::PlaneMatmul;
 use crate::components::stage::matmul::plane_partitioned::PlanePartitionedStageConfig;
 use crate::components::stage::{StageMatmulFamily, TilingLayout};
@@ -74,6 +75,7 @@ impl<
         max_global_readers: Option<MaxGlobalReaderPlanes>,
         ordered: bool,
     ) -> Result<Self::Config, MatmulSetupError> {
+        check_input_availability::<MP, R>(client)?;
         let tile_config =
             TM::setup::<LhsR<MP>, RhsR<MP>, AccR<MP>, R>(client, problem, selection, line_sizes)?;
 
@@ -96,11 +98,22 @@ impl<
             compute_planes,
         )?;
 
+        selection
+            .output_layout
+            .validate(problem, &selection.tiling_scheme, line_sizes)?;
+        selection.load_scale.validate()?;
+        selection.load_transform.validate()?;
+
         PlanePartitionedStageConfig::new(
             tile_config,
             selection.tiling_scheme,
             selection.quantized,
             selection.partition_buffering,
+            selection.tile_iteration,
+            selection.acc_tiles_n,
+            selection.output_layout,
+            selection.load_scale,
+            selection.load_transform,
             num_stages,
             plane_role_config,
             LhsS::<MP>::elem_size(),
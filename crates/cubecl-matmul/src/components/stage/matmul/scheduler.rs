@@ -1,7 +1,8 @@
-use crate::components::PartitionSize;
+use crate::components::{PartitionSize, TileSize, stage::TileIteration};
 
 use cubecl::prelude::*;
 use cubecl_core as cubecl;
+use cubecl_std::tensor::layout::Coords2d;
 
 /// Defines how partition indices are scheduled across axes.
 pub enum PartitionSchedulerScheme {
@@ -13,11 +14,21 @@ pub enum PartitionSchedulerScheme {
 
 /// Schedules global indices for M, N, and K axes in a partitioned matmul.
 /// Internally uses an `AxisScheduler` per axis.
+///
+/// With [TileIteration::Ordered], it also tracks how many tiles of the stage
+/// overlap the real problem in M and N, so that pure padding tiles can be skipped,
+/// and how many elements of the stage are real along K, so that the last tiles along K
+/// can be partial.
 #[derive(CubeType)]
 pub struct PartitionScheduler {
     pub m: AxisScheduler,
     pub n: AxisScheduler,
     pub k: AxisScheduler,
+    m_bound: u32,
+    n_bound: u32,
+    k_bound: u32,
+    #[cube(comptime)]
+    tile_iteration: TileIteration,
 }
 
 #[cube]
@@ -31,7 +42,13 @@ impl PartitionScheduler {
         partition_index_n: u32,
         #[comptime] partition_size: PartitionSize,
         #[comptime] partition_schedule_scheme: PartitionSchedulerScheme,
+        #[comptime] tile_iteration: TileIteration,
     ) -> PartitionScheduler {
+        // Unbounded until the real problem bounds are known, see `restrict_to_bounds`.
+        let m_bound = comptime!(u32::MAX).runtime();
+        let n_bound = comptime!(u32::MAX).runtime();
+        let k_bound = comptime!(u32::MAX).runtime();
+
         match partition_schedule_scheme {
             PartitionSchedulerScheme::Offset => {
                 // M-axis rotation: ensures partitions in the same row start at different M tiles.
@@ -59,6 +76,10 @@ impl PartitionScheduler {
                         0u32,
                         partition_size.k(),
                     )),
+                    m_bound,
+                    n_bound,
+                    k_bound,
+                    tile_iteration,
                 }
             }
             PartitionSchedulerScheme::Naive => PartitionScheduler {
@@ -71,6 +92,10 @@ impl PartitionScheduler {
                     partition_size.n(),
                 )),
                 k: AxisScheduler::new_Naive(NaiveAxisScheduler::new(0u32, partition_size.k())),
+                m_bound,
+                n_bound,
+                k_bound,
+                tile_iteration,
             },
         }
     }
@@ -89,6 +114,46 @@ impl PartitionScheduler {
     pub fn map_k(&self, i: u32) -> u32 {
         self.k.map(i)
     }
+
+    /// Restricts the scheduled tiles to those overlapping the first `stage_bounds`
+    /// (rows, cols) elements of the stage.
+    ///
+    /// Has no effect unless the tile iteration is [TileIteration::Ordered].
+    pub fn restrict_to_bounds(&mut self, stage_bounds: Coords2d, #[comptime] tile_size: TileSize) {
+        let (rows, cols) = stage_bounds;
+        self.m_bound = rows.div_ceil(tile_size.m());
+        self.n_bound = cols.div_ceil(tile_size.n());
+    }
+
+    /// Restricts the elements along K to the first `stage_k` of the stage.
+    ///
+    /// Has no effect unless the tile iteration is [TileIteration::Ordered].
+    pub fn restrict_k_to_bounds(&mut self, stage_k: u32) {
+        self.k_bound = stage_k;
+    }
+
+    /// The number of real elements along K in the tile at the global K index,
+    /// which is the whole tile unless it is the last one along K.
+    pub fn valid_k(&self, k: u32, #[comptime] tile_size: TileSize) -> u32 {
+        let start = k * tile_size.k();
+        Min::min(Max::max(self.k_bound, start) - start, tile_size.k())
+    }
+
+    /// Whether the tile at the global M index contains real data.
+    pub fn is_m_in_bounds(&self, m: u32) -> bool {
+        match comptime![self.tile_iteration] {
+            TileIteration::Full => true.runtime(),
+            TileIteration::Ordered => m < self.m_bound,
+        }
+    }
+
+    /// Whether the tile at the global N index contains real data.
+    pub fn is_n_in_bounds(&self, n: u32) -> bool {
+        match comptime![self.tile_iteration] {
+            TileIteration::Full => true.runtime(),
+            TileIteration::Ordered => n < self.n_bound,
+        }
+    }
 }
 
 /// Axis-specific scheduler that delegates to either `OffsetAxisScheduler` or `NaiveAxisScheduler`.
@@ -3,6 +3,7 @@ use crate::components::tile::accelerated::matmul::AcceleratedMatmul;
 use crate::components::tile::{
     TileMatmulFamily,
     accelerated::reader::{CmmaFragmentReader, CmmaStageReader},
+    check_real_product,
 };
 use crate::components::{InvalidConfigError, MatmulLineSizes, MatmulProblem, MatmulSelection};
 use crate::components::{error::MatmulSetupError, tile::io::Strided};
@@ -35,6 +36,7 @@ where
         selection: &MatmulSelection,
         matmul_line_sizes: &MatmulLineSizes,
     ) -> Result<Self::Config, MatmulSetupError> {
+        check_real_product(selection)?;
         AcceleratedConfig::new::<Lhs, Rhs, Acc, R>(
             client,
             selection.tiling_scheme.tile_size,
@@ -61,6 +61,17 @@ pub trait TileMatmulFamily: Send + Sync + 'static {
     }
 }
 
+/// Rejects the [complex](MatmulSelection::complex) selections and the packed Rhs rows in the
+/// tile matmuls that only multiply real numbers.
+pub(crate) fn check_real_product(selection: &MatmulSelection) -> Result<(), MatmulSetupError> {
+    if selection.complex.is_some() || selection.tiling_scheme.rhs_k_packing != 1 {
+        return Err(MatmulSetupError::InvalidConfig(Box::new(
+            "Complex products are only supported by the register tile matmul",
+        )));
+    }
+    Ok(())
+}
+
 /// Provides matrix multiplication operations at the tile level.
 ///
 /// At the tile level,
@@ -100,6 +111,20 @@ pub trait TileMatmul<L: Numeric, R: Numeric, A: Numeric>: 'static + Send + Sync
         #[comptime] config: Self::Config,
     );
 
+    /// Same as [execute](TileMatmul::execute), but only the first `valid_k` elements along k
+    /// contribute to the accumulator, whatever the fragments hold past them.
+    ///
+    /// This lets the last tile along k be partial without being padded with zeros beforehand.
+    /// Tile matmuls whose fragments can't be predicated per element fall back to
+    /// [execute](TileMatmul::execute), and still rely on the padding of the readers.
+    fn execute_partial_k(
+        lhs: &Self::LhsFragment,
+        rhs: &Self::RhsFragment,
+        out: &mut Self::AccFragment,
+        valid_k: u32,
+        #[comptime] config: Self::Config,
+    );
+
     /// Create the container for Lhs
     ///
     /// # Safety
@@ -49,6 +49,17 @@ where
         }
     }
 
+    fn execute_partial_k(
+        lhs: &Self::LhsFragment,
+        rhs: &Self::RhsFragment,
+        out: &mut Self::AccFragment,
+        _valid_k: u32,
+        #[comptime] config: Self::Config,
+    ) {
+        // The fragments are opaque, the readers must have zeroed the elements past `valid_k`.
+        <Self as TileMatmul<L, R, A>>::execute(lhs, rhs, out, config);
+    }
+
     fn allocate_lhs(#[comptime] config: Self::Config) -> Self::LhsFragment {
         let def = mma_definition::<L, R, A>(config);
         let line_size = def.line_size(MatrixIdent::A);
@@ -1,5 +1,5 @@
 use crate::components::tile::{
-    TileMatmulFamily,
+    TileMatmulFamily, check_real_product,
     mma::{
         MmaMatmul,
         config::MmaMatmulConfig,
@@ -37,6 +37,7 @@ where
         selection: &MatmulSelection,
         matmul_line_sizes: &MatmulLineSizes,
     ) -> Result<Self::Config, MatmulSetupError> {
+        check_real_product(selection)?;
         MmaMatmulConfig::new::<Lhs, Rhs, Acc, R>(
             client,
             selection.tiling_scheme.tile_size,
@@ -76,6 +76,36 @@ where
         }
     }
 
+    fn execute_partial_k(
+        lhs: &Self::LhsFragment,
+        rhs: &Self::RhsFragment,
+        acc: &mut Self::AccFragment,
+        valid_k: u32,
+        #[comptime] config: Self::Config,
+    ) {
+        let line_size = config.reduce_line_size();
+        // Each unit holds one line along k
+        let first_k = UNIT_POS_X * line_size;
+        let mut n = comptime![0];
+
+        #[unroll]
+        #[allow(clippy::explicit_counter_loop)]
+        for _ in 0..config.n() {
+            let lhs: Line<A> = Line::cast_from(lhs.line);
+            let rhs: Line<A> = Line::cast_from(rhs.index(n).line);
+            let mut product = lhs * rhs;
+
+            #[unroll]
+            for i in 0..line_size {
+                product[i] = select(first_k + i < valid_k, product[i], A::from_int(0));
+            }
+
+            plane_sum_lined(product, acc.index_mut(n), line_size);
+
+            comptime![n += 1];
+        }
+    }
+
     fn allocate_lhs(#[comptime] config: Self::Config) -> Self::LhsFragment {
         LineContainer::<L>::new(config.reduce_line_size())
     }
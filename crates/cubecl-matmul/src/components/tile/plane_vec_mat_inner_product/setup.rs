@@ -1,6 +1,6 @@
 use crate::components::tile::plane_vec_mat_inner_product::config::PlaneVecMatInnerProductConfig;
 use crate::components::tile::plane_vec_mat_inner_product::matmul::PlaneVecMatInnerProduct;
-use crate::components::tile::{TileMatmulFamily, io::Strided};
+use crate::components::tile::{TileMatmulFamily, check_real_product, io::Strided};
 use crate::components::{InvalidConfigError, MatmulLineSizes, MatmulProblem, MatmulSelection};
 use crate::components::{error::MatmulSetupError, tile::io::TileKind};
 use crate::components::{
@@ -35,6 +35,7 @@ where
         selection: &MatmulSelection,
         matmul_line_sizes: &MatmulLineSizes,
     ) -> Result<Self::Config, MatmulSetupError> {
+        check_real_product(selection)?;
         PlaneVecMatInnerProductConfig::new::<Lhs, Rhs, Acc, R>(
             client,
             selection.tiling_scheme,
@@ -22,10 +22,30 @@ pub enum ProductType {
     Outer,
 }
 
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+/// How the Register Matmul multiplies complex numbers, whose real and imaginary parts are stored
+/// in pairs of consecutive elements.
+///
+/// The operands are interleaved complex matrices in their natural layouts: the pairs of Lhs are
+/// along k, and the pairs of Rhs and of the accumulator are along n. With `K` complex numbers
+/// along k, Lhs has `k = 2K` real elements along k and Rhs has `K` rows, so `(m, n, k)` is the
+/// shape of Lhs and of the output in real elements. The pair `p` of Lhs multiplies the row `p`
+/// of Rhs, so a tile of `k` elements of Lhs along k multiplies `k / 2` rows of Rhs, see
+/// [rhs_k_packing](crate::components::TilingScheme::rhs_k_packing).
+pub enum ComplexProduct {
+    /// Four real multiplications, `(a + ib)(c + id) = (ac - bd) + i(ad + bc)`.
+    Standard,
+    /// Three real multiplications, Karatsuba's `(ac - bd) + i((a + b)(c + d) - ac - bd)`,
+    /// trading a multiplication for three additions at the cost of more rounding error.
+    Karatsuba,
+}
+
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
 /// Configuration for Register Matmul
 pub struct RegisterConfig {
     pub tile_size: TileSize,
+    /// Whether the elements are pairs of real and imaginary parts, and how they are multiplied
+    pub complex: Option<ComplexProduct>,
     plane_dim: u32,
     lhs_layout: MatrixLayout,
     rhs_layout: MatrixLayout,
@@ -83,6 +103,7 @@ impl RegisterConfig {
     pub fn new<Lhs: Numeric, Rhs: Numeric, Acc: Numeric, R: Runtime>(
         client: &ComputeClient<R::Server, R::Channel>,
         tile_size: TileSize,
+        complex: Option<ComplexProduct>,
         plane_dim: u32,
         lhs_layout: MatrixLayout,
         rhs_layout: MatrixLayout,
@@ -94,6 +115,7 @@ impl RegisterConfig {
     ) -> Result<Self, MatmulSetupError> {
         Self {
             tile_size,
+            complex,
             plane_dim,
             lhs_layout,
             rhs_layout,
@@ -107,6 +129,15 @@ impl RegisterConfig {
         .check_availability::<Lhs, Rhs, Acc, R>(client)
     }
 
+    /// Number of rows of the Rhs tile along k, which is half of the tile size along k with
+    /// complex operands, as each row of Rhs is multiplied by a pair of elements of Lhs.
+    pub fn rhs_tile_k(&self) -> u32 {
+        match self.complex {
+            Some(_) => self.tile_size.k() / 2,
+            None => self.tile_size.k(),
+        }
+    }
+
     pub fn product_type(&self) -> ProductType {
         // TODO: Make it configurable.
         ProductType::Outer
@@ -117,9 +148,16 @@ impl RegisterConfig {
         let n = self.tile_size().n();
         let k = self.tile_size().k();
 
+        if self.complex.is_some() && !(n.is_multiple_of(2) && k.is_multiple_of(2)) {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(format!(
+                "Tile shape {n:?} in n and {k:?} in k should be even to hold complex numbers"
+            ))));
+        }
+
         let lhs = self.lhs_stage_line_size;
         let rhs = self.rhs_stage_line_size;
         let out = self.out_global_line_size;
+        let rhs_k = self.rhs_tile_k();
 
         match self.matrix_layout(StageIdent::Lhs) {
             MatrixLayout::RowMajor => {
@@ -146,9 +184,9 @@ impl RegisterConfig {
                 }
             }
             MatrixLayout::ColMajor => {
-                if !k.is_multiple_of(rhs) {
+                if !rhs_k.is_multiple_of(rhs) {
                     return Err(MatmulSetupError::InvalidConfig(Box::new(format!(
-                        "Tile shape in lined axis {k:?} should be divisible by line size {rhs:?}"
+                        "Tile shape in lined axis {rhs_k:?} should be divisible by line size {rhs:?}"
                     ))));
                 }
             }
@@ -191,6 +229,8 @@ impl RegisterConfig {
             _ => acc,
         };
 
+        // Only the register types are computed on, as the tiles are upcast in software when
+        // filled from the stage. A storage type without arithmetic, as in `Bf16Storage`, is fine.
         if !(Lhs::supported_uses(client).contains(TypeUsage::Arithmetic)
             && Rhs::supported_uses(client).contains(TypeUsage::Arithmetic)
             && Acc::supported_uses(client).contains(TypeUsage::Arithmetic))
@@ -200,6 +240,13 @@ impl RegisterConfig {
             ));
         }
 
+        // The imaginary part of a product subtracts, which needs a signed accumulator.
+        if self.complex.is_some() && !matches!(output, StorageType::Scalar(ElemType::Float(_))) {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(format!(
+                "Complex products need a float accumulator, got {output:?}"
+            ))));
+        }
+
         Ok(self)
     }
 }
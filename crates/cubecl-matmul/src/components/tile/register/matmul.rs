@@ -4,7 +4,7 @@ use crate::components::tile::{TileConfig, TileMatmul, register::reader::Register
 use crate::components::tile::{
     io::Strided,
     register::{
-        config::{ProductType, RegisterConfig},
+        config::{ComplexProduct, ProductType, RegisterConfig},
         reader::RegisterStageReader,
     },
 };
@@ -45,9 +45,28 @@ where
         acc: &mut Self::AccFragment,
         #[comptime] config: Self::Config,
     ) {
-        match config.product_type() {
-            ProductType::Inner => Self::inner_product(lhs, rhs, acc, config),
-            ProductType::Outer => Self::outer_product(lhs, rhs, acc, config),
+        match comptime![(config.complex, config.product_type())] {
+            (Some(product), _) => {
+                Self::complex_outer_product(lhs, rhs, acc, 0u32, false, product, config)
+            }
+            (None, ProductType::Inner) => Self::inner_product(lhs, rhs, acc, 0u32, false, config),
+            (None, ProductType::Outer) => Self::outer_product(lhs, rhs, acc, 0u32, false, config),
+        }
+    }
+
+    fn execute_partial_k(
+        lhs: &Self::LhsFragment,
+        rhs: &Self::RhsFragment,
+        acc: &mut Self::AccFragment,
+        valid_k: u32,
+        #[comptime] config: Self::Config,
+    ) {
+        match comptime![(config.complex, config.product_type())] {
+            (Some(product), _) => {
+                Self::complex_outer_product(lhs, rhs, acc, valid_k, true, product, config)
+            }
+            (None, ProductType::Inner) => Self::inner_product(lhs, rhs, acc, valid_k, true, config),
+            (None, ProductType::Outer) => Self::outer_product(lhs, rhs, acc, valid_k, true, config),
         }
     }
 
@@ -56,7 +75,7 @@ where
     }
 
     fn allocate_rhs(#[comptime] config: Self::Config) -> Self::RhsFragment {
-        Array::new(config.tile_size().nk())
+        Array::new(config.tile_size().n() * config.rhs_tile_k())
     }
 
     fn allocate_acc(#[comptime] config: Self::Config) -> Self::AccFragment {
@@ -98,10 +117,14 @@ where
 
 #[cube]
 impl<Acc: TileKind> RegisterMatmul<Acc> {
+    /// Adds the product of `lhs` and `rhs` to `acc`. When `predicated`, the products along k
+    /// from `valid_k` onward are replaced by zero, so they can't propagate NaN or infinity.
     fn inner_product<Lhs: Numeric, Rhs: Numeric, EA: Numeric>(
         lhs: &Array<Lhs>,
         rhs: &Array<Rhs>,
         acc: &mut Array<EA>,
+        valid_k: u32,
+        #[comptime] predicated: bool,
         #[comptime] config: RegisterConfig,
     ) {
         let (m, n, k) =
@@ -115,16 +138,19 @@ impl<Acc: TileKind> RegisterMatmul<Acc> {
                 for k_ in 0..k {
                     let lhs_elem = EA::cast_from(lhs[m_ * k + k_]);
                     let rhs_elem = EA::cast_from(rhs[n_ * k + k_]);
-                    acc[m_ * n + n_] += lhs_elem * rhs_elem;
+                    acc[m_ * n + n_] += predicate(lhs_elem * rhs_elem, k_, valid_k, predicated);
                 }
             }
         }
     }
 
+    /// Same as [inner_product](Self::inner_product), as a sum of outer products along k.
     fn outer_product<Lhs: Numeric, Rhs: Numeric, EA: Numeric>(
         lhs: &Array<Lhs>,
         rhs: &Array<Rhs>,
         acc: &mut Array<EA>,
+        valid_k: u32,
+        #[comptime] predicated: bool,
         #[comptime] config: RegisterConfig,
     ) {
         let (m, n, k) =
@@ -138,7 +164,54 @@ impl<Acc: TileKind> RegisterMatmul<Acc> {
                 #[unroll(UNROLL)]
                 for n_ in 0..n {
                     let rhs_elem = EA::cast_from(rhs[k_ * n + n_]);
-                    acc[m_ * n + n_] += lhs_elem * rhs_elem;
+                    acc[m_ * n + n_] += predicate(lhs_elem * rhs_elem, k_, valid_k, predicated);
+                }
+            }
+        }
+    }
+
+    /// Same as [outer_product](Self::outer_product), multiplying the pairs of Lhs along k by the
+    /// pairs of Rhs along n as complex numbers with `product`.
+    ///
+    /// Lhs steps by a pair along k for each row of Rhs, which has `k / 2` rows. The fragments
+    /// are in the layout of the outer product, which is always the one used.
+    fn complex_outer_product<Lhs: Numeric, Rhs: Numeric, EA: Numeric>(
+        lhs: &Array<Lhs>,
+        rhs: &Array<Rhs>,
+        acc: &mut Array<EA>,
+        valid_k: u32,
+        #[comptime] predicated: bool,
+        #[comptime] product: ComplexProduct,
+        #[comptime] config: RegisterConfig,
+    ) {
+        let (m, n, k) =
+            comptime! {let (m, n, k): (u32, u32, u32) = (*config.tile_size()).into(); (m, n, k)};
+
+        #[unroll(UNROLL)]
+        for rhs_k in 0..k / 2 {
+            let k_ = 2 * rhs_k;
+            #[unroll(UNROLL)]
+            for m_ in 0..m {
+                let lhs_re = EA::cast_from(lhs[k_ * m + m_]);
+                let lhs_im = EA::cast_from(lhs[(k_ + 1) * m + m_]);
+                #[unroll(UNROLL)]
+                for n_pair in 0..n / 2 {
+                    let n_ = 2 * n_pair;
+                    let rhs_re = EA::cast_from(rhs[rhs_k * n + n_]);
+                    let rhs_im = EA::cast_from(rhs[rhs_k * n + n_ + 1]);
+
+                    let re_re = lhs_re * rhs_re;
+                    let im_im = lhs_im * rhs_im;
+                    let re = re_re - im_im;
+                    let im = match comptime![product] {
+                        ComplexProduct::Standard => lhs_re * rhs_im + lhs_im * rhs_re,
+                        ComplexProduct::Karatsuba => {
+                            (lhs_re + lhs_im) * (rhs_re + rhs_im) - re_re - im_im
+                        }
+                    };
+
+                    acc[m_ * n + n_] += predicate(re, k_, valid_k, predicated);
+                    acc[m_ * n + n_ + 1] += predicate(im, k_, valid_k, predicated);
                 }
             }
         }
@@ -191,3 +264,13 @@ impl<Acc: TileKind> RegisterMatmul<Acc> {
         }
     }
 }
+
+/// The `product` at index `k` along k, or zero past `valid_k` when `predicated`.
+#[cube]
+fn predicate<EA: Numeric>(product: EA, k: u32, valid_k: u32, #[comptime] predicated: bool) -> EA {
+    if comptime![predicated] {
+        select(k < valid_k, product, EA::from_int(0))
+    } else {
+        product
+    }
+}
@@ -4,4 +4,5 @@ mod reader;
 mod setup;
 mod writer;
 
+pub use config::ComplexProduct;
 pub use matmul::*;
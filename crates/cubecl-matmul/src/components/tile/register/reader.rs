@@ -95,24 +95,25 @@ fn load_rhs<E: Numeric, V: Numeric>(
     #[comptime] config: RegisterConfig,
 ) {
     let size = config.tile_size();
+    let rhs_k = config.rhs_tile_k();
     let line_size = config.stage_line_size(StageIdent::Rhs);
     let layout = config.matrix_layout(StageIdent::Rhs);
 
     match config.product_type() {
         ProductType::Inner => match layout {
             MatrixLayout::RowMajor => {
-                MM::load_transposed(tile, frag, size.k(), size.n(), line_size);
+                MM::load_transposed(tile, frag, rhs_k, size.n(), line_size);
             }
             MatrixLayout::ColMajor => {
-                MM::load_plain(tile, frag, size.n(), size.k(), line_size);
+                MM::load_plain(tile, frag, size.n(), rhs_k, line_size);
             }
         },
         ProductType::Outer => match layout {
             MatrixLayout::RowMajor => {
-                MM::load_plain(tile, frag, size.k(), size.n(), line_size);
+                MM::load_plain(tile, frag, rhs_k, size.n(), line_size);
             }
             MatrixLayout::ColMajor => {
-                MM::load_transposed(tile, frag, size.n(), size.k(), line_size);
+                MM::load_transposed(tile, frag, size.n(), rhs_k, line_size);
             }
         },
     }
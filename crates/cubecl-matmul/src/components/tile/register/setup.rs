@@ -37,9 +37,19 @@ where
         selection: &MatmulSelection,
         matmul_line_sizes: &MatmulLineSizes,
     ) -> Result<Self::Config, MatmulSetupError> {
+        // Complex Lhs has two elements along k for each row of Rhs.
+        let rhs_k_packing = if selection.complex.is_some() { 2 } else { 1 };
+        if selection.tiling_scheme.rhs_k_packing != rhs_k_packing {
+            return Err(MatmulSetupError::InvalidConfig(Box::new(format!(
+                "Rhs k packing {:?} doesn't match the product, expected {rhs_k_packing:?}",
+                selection.tiling_scheme.rhs_k_packing
+            ))));
+        }
+
         RegisterConfig::new::<Lhs, Rhs, Acc, R>(
             client,
             selection.tiling_scheme.tile_size,
+            selection.complex,
             selection.plane_dim,
             problem.lhs_layout,
             problem.rhs_layout,
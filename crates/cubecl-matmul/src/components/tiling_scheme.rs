@@ -9,6 +9,12 @@ pub struct TilingScheme {
     pub partition_size: PartitionSize,
     pub stage_size: StageSize,
     pub global_partition_size: GlobalPartitionSize,
+    /// Number of elements of Lhs along k multiplied by each row of Rhs along k, so the tiles
+    /// and stages of Rhs have `k / rhs_k_packing` rows.
+    ///
+    /// It's 2 for [complex](crate::components::tile::register::ComplexProduct) operands, whose
+    /// Lhs holds the real and imaginary parts of each number in a pair along k, and 1 otherwise.
+    pub rhs_k_packing: u32,
 }
 
 impl TilingScheme {
@@ -25,6 +31,7 @@ pub struct TilingSchemeBuilder {
     partition_size: Option<PartitionSize>,
     stage_size: Option<StageSize>,
     global_partition_size: Option<GlobalPartitionSize>,
+    rhs_k_packing: Option<u32>,
 }
 
 impl TilingSchemeBuilder {
@@ -60,6 +67,14 @@ impl TilingSchemeBuilder {
         self
     }
 
+    /// Optional: specify the number of Lhs elements along k for each Rhs row
+    ///
+    /// If not specified, will default to 1
+    pub fn with_rhs_k_packing(mut self, rhs_k_packing: u32) -> Self {
+        self.rhs_k_packing = Some(rhs_k_packing);
+        self
+    }
+
     /// Finish building
     pub fn build(self) -> Result<TilingScheme, &'static str> {
         Ok(TilingScheme {
@@ -69,6 +84,7 @@ impl TilingSchemeBuilder {
             global_partition_size: self
                 .global_partition_size
                 .unwrap_or(GlobalPartitionSize::new(1, 1, 1)),
+            rhs_k_packing: self.rhs_k_packing.unwrap_or(1),
         })
     }
 }
@@ -160,6 +176,15 @@ impl TilingScheme {
             })
     }
 
+    /// The divisor of the elements of Rhs along k, as it has one row for `rhs_k_packing`
+    /// elements of Lhs.
+    fn rhs_k_divisor(&self, child_level: TilingLevel) -> u32 {
+        match child_level {
+            TilingLevel::Element => self.rhs_k_packing,
+            _ => 1,
+        }
+    }
+
     fn count_1d_ident_row(
         &self,
         child_level: TilingLevel,
@@ -168,7 +193,10 @@ impl TilingScheme {
     ) -> u32 {
         match ident {
             StageIdent::Lhs => self.count_1d(child_level, parent_level, MatmulDim::M),
-            StageIdent::Rhs => self.count_1d(child_level, parent_level, MatmulDim::K),
+            StageIdent::Rhs => {
+                self.count_1d(child_level, parent_level, MatmulDim::K)
+                    / self.rhs_k_divisor(child_level)
+            }
             StageIdent::Acc => self.count_1d(child_level, parent_level, MatmulDim::M),
             StageIdent::Out => self.count_1d(child_level, parent_level, MatmulDim::M),
         }
@@ -209,7 +237,10 @@ impl TilingScheme {
     ) -> u32 {
         match ident {
             StageIdent::Lhs => self.count_2d(child_level, parent_level, MatmulDim::M, MatmulDim::K),
-            StageIdent::Rhs => self.count_2d(child_level, parent_level, MatmulDim::K, MatmulDim::N),
+            StageIdent::Rhs => {
+                self.count_2d(child_level, parent_level, MatmulDim::K, MatmulDim::N)
+                    / self.rhs_k_divisor(child_level)
+            }
             StageIdent::Acc => self.count_2d(child_level, parent_level, MatmulDim::M, MatmulDim::N),
             StageIdent::Out => self.count_2d(child_level, parent_level, MatmulDim::M, MatmulDim::N),
         }
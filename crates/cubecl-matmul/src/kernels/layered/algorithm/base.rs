@@ -7,10 +7,11 @@ use crate::components::{
     MatmulSelection, MatmulSetupError,
 };
 use cubecl_core::prelude::*;
+use std::fmt::Debug;
 
 /// Specifications for a matmul algorithm
 pub trait Algorithm {
-    type SelectionArgs: Default + Clone;
+    type SelectionArgs: Default + Clone + Debug;
     type TileMatmul: TileMatmulFamily;
     type StageMatmul: StageMatmulFamily;
     type GlobalMatmul: GlobalMatmulFamily;
@@ -1,5 +1,6 @@
 use cubecl_core::{Runtime, client::ComputeClient};
 
+use cubecl_std::CubeOption;
 use std::marker::PhantomData;
 
 use crate::{
@@ -8,14 +9,20 @@ use crate::{
         batch::{PartitionedBatchMatmulFamily, RowMajorGlobalPartitionMatmul},
         global::{
             UnitWriterFamily,
-            read::{SyncFullLoadingStrategy, sync_full_cyclic::SyncFullCyclicLoading},
+            read::{
+                AccumulatorGlobalReaderFamily, SyncFullLoadingStrategy,
+                sync_full_cyclic::SyncFullCyclicLoading,
+            },
             single_stage::simple::SimpleMatmulFamily,
         },
         stage::{
             ColMajorTilingOrder, FilledStageFamily, RowMajorTilingOrder, StridedStageFamily,
             UnitMatmulFamily,
         },
-        tile::{io::Filled, register::RegisterMatmul},
+        tile::{
+            io::{Filled, Strided},
+            register::RegisterMatmul,
+        },
     },
     kernels::layered::{
         TileSizeSelection,
@@ -85,3 +92,51 @@ where
         client.properties().hardware.plane_size_min
     }
 }
+
+/// Same as [SimpleUnitAlgorithm], but the accumulators start from the accumulator tensor of the
+/// inputs when there is one, upcast to the accumulation precision
+pub struct SimpleUnitAccumulatorAlgorithm<
+    LL = SyncFullCyclicLoading<ColMajorTilingOrder>,
+    RL = SyncFullCyclicLoading<RowMajorTilingOrder>,
+> {
+    pub _ll: PhantomData<LL>,
+    pub _rl: PhantomData<RL>,
+}
+
+impl<LL, RL> Algorithm for SimpleUnitAccumulatorAlgorithm<LL, RL>
+where
+    LL: SyncFullLoadingStrategy,
+    RL: SyncFullLoadingStrategy,
+{
+    type SelectionArgs = SimpleUnitSelectionArgs;
+    type TileMatmul = RegisterMatmul<CubeOption<Strided>>;
+    type StageMatmul =
+        UnitMatmulFamily<Self::TileMatmul, StridedStageFamily, Option<StridedStageFamily>>;
+    type GlobalMatmul = SimpleMatmulFamily<
+        Self::StageMatmul,
+        LL,
+        RL,
+        UnitWriterFamily,
+        AccumulatorGlobalReaderFamily,
+    >;
+
+    type BatchMatmul =
+        PartitionedBatchMatmulFamily<Self::GlobalMatmul, RowMajorGlobalPartitionMatmul>;
+
+    fn selection<R: Runtime>(
+        client: &ComputeClient<R::Server, R::Channel>,
+        problem: &MatmulProblem,
+        plane_dim: u32,
+        line_sizes: &MatmulLineSizes,
+        elems: MatmulElems,
+        args: &Self::SelectionArgs,
+    ) -> Result<MatmulSelection, MatmulSetupError> {
+        SimpleUnitAlgorithm::<LL, RL>::selection::<R>(
+            client, problem, plane_dim, line_sizes, elems, args,
+        )
+    }
+
+    fn select_plane_dim<R: Runtime>(client: &ComputeClient<R::Server, R::Channel>) -> u32 {
+        SimpleUnitAlgorithm::<LL, RL>::select_plane_dim::<R>(client)
+    }
+}
@@ -5,6 +5,9 @@ mod selector;
 pub use algorithm::*;
 pub use base::{Selection, launch, launch_ref, launch_with_config, matmul_cmma_tma_ref_no_check};
 pub use selector::{
-    NUM_SM_APPROX, NUM_TENSOR_CORES_APPROX, TileSizeSelection, find_instruction_size,
-    launch_kernel_concrete, launch_kernel_virtual,
+    NUM_SM_APPROX, NUM_TENSOR_CORES_APPROX, SelectionTuner, TileSizeSelection,
+    find_instruction_size, launch_kernel_concrete, launch_kernel_virtual,
 };
+
+#[cfg(feature = "export_tests")]
+pub(crate) use selector::validate_plane_dim;
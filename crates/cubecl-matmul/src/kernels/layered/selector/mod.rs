@@ -1,9 +1,11 @@
 mod plane;
 mod select_kernel;
+mod tune;
 mod unit;
 
 pub use plane::*;
 pub use select_kernel::*;
+pub use tune::*;
 pub use unit::*;
 
 use crate::components::{MatmulProblem, TileSize};
@@ -93,7 +93,9 @@ pub fn plane_matmul_selection<TMM: TileMatmulFamily, R: Runtime>(
         .unwrap();
 
     let partition_buffering = options.partition_buffering.unwrap_or_else(|| {
-        if tiling_scheme.tiles_in_stage_partition_n() > 1 {
+        if problem.m as u32 <= tile_size.m() {
+            PartitionBuffering::RegisterLhs
+        } else if tiling_scheme.tiles_in_stage_partition_n() > 1 {
             PartitionBuffering::Double
         } else {
             PartitionBuffering::Single
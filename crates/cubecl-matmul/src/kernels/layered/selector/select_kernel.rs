@@ -1,7 +1,8 @@
 use crate::MatmulInputHandleRef;
 use crate::components::batch::BatchConfig;
 use crate::components::{
-    InputArg, InputRuntimeArg, MatmulElems, MatmulLineSizes, MatmulSetupError, OutputRuntimeArg,
+    InputArg, InputRuntimeArg, MatmulAvailabilityError, MatmulElems, MatmulLineSizes,
+    MatmulSelection, MatmulSetupError, OutputRuntimeArg,
 };
 use crate::components::{
     MatmulProblem, MatmulSpec, OutputArg,
@@ -38,6 +39,8 @@ where
             A::selection::<R>(client, &problem, plane_dim, &line_sizes, elems, args)?
         }
     };
+    validate_plane_dim::<R>(client, &selection)?;
+
     let config = A::setup::<MS::Precision, R>(client, &problem, &selection, &line_sizes)?;
     let cube_count_plan = config.hypercube_config().cube_count_plan(
         &problem,
@@ -81,6 +84,8 @@ pub fn launch_kernel_virtual<'a, MS: MatmulSpec, R: Runtime, A: Algorithm>(
             A::selection::<R>(client, &problem, plane_dim, &line_sizes, elems, args)?
         }
     };
+    validate_plane_dim::<R>(client, &selection)?;
+
     let config = A::setup::<MS::Precision, R>(client, &problem, &selection, &line_sizes)?;
 
     let cube_count_plan = config.hypercube_config().cube_count_plan(
@@ -98,3 +103,26 @@ pub fn launch_kernel_virtual<'a, MS: MatmulSpec, R: Runtime, A: Algorithm>(
         config,
     )
 }
+
+/// Check that the plane dimension of the `selection`, which may have been forced rather than
+/// read from the client properties, is supported by the client.
+///
+/// Clients that don't report a plane size can still run the algorithms without plane
+/// instructions, so any plane dimension is accepted for them.
+#[allow(clippy::result_large_err)]
+pub(crate) fn validate_plane_dim<R: Runtime>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    selection: &MatmulSelection,
+) -> Result<(), MatmulSetupError> {
+    let hw_props = &client.properties().hardware;
+    let plane_dim = selection.plane_dim;
+
+    if hw_props.plane_size_max != 0
+        && (plane_dim < hw_props.plane_size_min || plane_dim > hw_props.plane_size_max)
+    {
+        return Err(MatmulSetupError::Unavailable(
+            MatmulAvailabilityError::PlaneDimUnsupported { plane_dim },
+        ));
+    }
+    Ok(())
+}
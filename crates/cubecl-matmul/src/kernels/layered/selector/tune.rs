@@ -0,0 +1,244 @@
+use core::any::TypeId;
+use core::time::Duration;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use cubecl_core::prelude::TensorHandleRef;
+use cubecl_core::{Runtime, client::ComputeClient};
+
+use crate::MatmulInputHandleRef;
+use crate::components::batch::BatchConfig;
+use crate::components::global::args::TensorArgs;
+use crate::components::stage::{PartitionBuffering, TileIteration};
+use crate::components::{
+    MatmulElems, MatmulLineSizes, MatmulPrecision, MatmulProblem, MatmulSelection,
+    MatmulSetupError, MatrixLayout, StageSize, TilingScheme,
+};
+use crate::kernels::layered::{Algorithm, Selection, launch_kernel_concrete};
+
+const STAGE_SIZES: [(u32, u32); 5] = [(1, 1), (2, 2), (4, 4), (2, 1), (1, 2)];
+const PARTITION_BUFFERINGS: [PartitionBuffering; 2] =
+    [PartitionBuffering::Single, PartitionBuffering::Double];
+const TILE_ITERATIONS: [TileIteration; 2] = [TileIteration::Full, TileIteration::Ordered];
+
+/// Problem shape, kernel types, line sizes, device and selection args a tuned selection is
+/// cached for.
+#[derive(Hash, PartialEq, Eq, Clone, Debug)]
+struct SelectionTuneKey {
+    m: usize,
+    n: usize,
+    k: usize,
+    lhs_batches: Vec<usize>,
+    rhs_batches: Vec<usize>,
+    lhs_layout: MatrixLayout,
+    rhs_layout: MatrixLayout,
+    precision: TypeId,
+    algorithm: TypeId,
+    line_sizes: (u8, u8, u8),
+    /// The runtime and the address of the client properties, which the clones of a client share.
+    device: (TypeId, usize),
+    /// The selection args, which are plain data so their debug output tells them apart.
+    args: String,
+}
+
+impl SelectionTuneKey {
+    fn new<R: Runtime, MP: MatmulPrecision, A: Algorithm + 'static>(
+        client: &ComputeClient<R::Server, R::Channel>,
+        problem: &MatmulProblem,
+        line_sizes: &MatmulLineSizes,
+        args: &A::SelectionArgs,
+    ) -> Self {
+        Self {
+            m: problem.m,
+            n: problem.n,
+            k: problem.k,
+            lhs_batches: problem.lhs_batches.clone(),
+            rhs_batches: problem.rhs_batches.clone(),
+            lhs_layout: problem.lhs_layout,
+            rhs_layout: problem.rhs_layout,
+            precision: TypeId::of::<MP>(),
+            algorithm: TypeId::of::<A>(),
+            line_sizes: (line_sizes.lhs, line_sizes.rhs, line_sizes.out),
+            device: (
+                TypeId::of::<R>(),
+                std::ptr::from_ref(client.properties()) as usize,
+            ),
+            args: format!("{args:?}"),
+        }
+    }
+}
+
+/// Search the [selection](MatmulSelection) of an [algorithm](Algorithm) by benchmarking a small
+/// grid of candidates around the one inferred by the algorithm.
+///
+/// The candidates vary the stage size, the [partition buffering](PartitionBuffering) and the
+/// [tile iteration](TileIteration), while the tile and partition sizes are kept from the
+/// inferred selection since they depend on the instruction. The tiling orders are part of the
+/// algorithm type, so they are compared by tuning each algorithm. Every candidate is validated
+/// with [`Algorithm::setup`] and skipped if it fails or doesn't fit the hardware, then launched
+/// and timed on the given tensors.
+///
+/// The fastest selection is cached by problem shape, precision, algorithm, line sizes, client and
+/// selection args, so later calls for the same launch return it without benchmarking.
+pub struct SelectionTuner {
+    num_samples: usize,
+    cache: Mutex<HashMap<SelectionTuneKey, MatmulSelection>>,
+}
+
+impl Default for SelectionTuner {
+    fn default() -> Self {
+        Self::new(5)
+    }
+}
+
+impl SelectionTuner {
+    /// Create a tuner timing each candidate `num_samples` times, after a warmup launch.
+    pub fn new(num_samples: usize) -> Self {
+        Self {
+            num_samples: num_samples.max(1),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Return the fastest selection for the `problem`, benchmarked on `lhs`, `rhs` and `out`.
+    ///
+    /// The tensors must match the `problem` and `line_sizes`, and `out` is overwritten by the
+    /// benchmarks. Return the error of the inferred selection if no candidate can be launched.
+    #[allow(clippy::result_large_err, clippy::too_many_arguments)]
+    pub fn tune<R: Runtime, MP: MatmulPrecision, A: Algorithm + 'static>(
+        &self,
+        client: &ComputeClient<R::Server, R::Channel>,
+        lhs: &MatmulInputHandleRef<'_, R>,
+        rhs: &MatmulInputHandleRef<'_, R>,
+        out: &TensorHandleRef<'_, R>,
+        problem: &MatmulProblem,
+        line_sizes: &MatmulLineSizes,
+        args: &A::SelectionArgs,
+    ) -> Result<MatmulSelection, MatmulSetupError> {
+        let key = SelectionTuneKey::new::<R, MP, A>(client, problem, line_sizes, args);
+        if let Some(selection) = self.cache.lock().unwrap().get(&key) {
+            return Ok(selection.clone());
+        }
+
+        let plane_dim = match A::select_plane_dim::<R>(client) {
+            0 => 32,
+            plane_dim => plane_dim,
+        };
+        let inferred = A::selection::<R>(
+            client,
+            problem,
+            plane_dim,
+            line_sizes,
+            MatmulElems::new::<MP>(),
+            args,
+        )?;
+        // Surface the reason the algorithm can't run instead of failing on every candidate.
+        A::setup::<MP, R>(client, problem, &inferred, line_sizes)?;
+
+        let mut fastest: Option<(Duration, MatmulSelection)> = None;
+        for candidate in candidates(&inferred) {
+            if !fits_hardware::<R, MP, A>(client, problem, &candidate, line_sizes) {
+                continue;
+            }
+
+            let launch = || {
+                launch_kernel_concrete::<(MP, TensorArgs), R, A>(
+                    client,
+                    lhs,
+                    rhs,
+                    out,
+                    problem.clone(),
+                    *line_sizes,
+                    plane_dim,
+                    &Selection::Forced(candidate.clone()),
+                )
+            };
+            if launch().is_err() {
+                continue;
+            }
+
+            let Some(duration) = self.benchmark(client, launch) else {
+                continue;
+            };
+            if fastest.as_ref().is_none_or(|(best, _)| duration < *best) {
+                fastest = Some((duration, candidate));
+            }
+        }
+
+        let selection = fastest.map(|(_, selection)| selection).unwrap_or(inferred);
+        self.cache.lock().unwrap().insert(key, selection.clone());
+        Ok(selection)
+    }
+
+    /// Clear the cached selections.
+    pub fn clear(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+
+    /// The median duration of the launches, or `None` if they couldn't be profiled.
+    fn benchmark<R: Runtime>(
+        &self,
+        client: &ComputeClient<R::Server, R::Channel>,
+        launch: impl Fn() -> Result<(), MatmulSetupError>,
+    ) -> Option<Duration> {
+        let mut durations = Vec::with_capacity(self.num_samples);
+        for _ in 0..self.num_samples {
+            let profile = client.profile(|| launch(), "matmul selection tuning");
+            match profile {
+                Ok(profile) => {
+                    let ticks = cubecl_common::future::block_on(profile.resolve());
+                    durations.push(ticks.duration());
+                }
+                Err(_) => return None,
+            }
+        }
+
+        durations.sort();
+        Some(durations[durations.len() / 2])
+    }
+}
+
+/// The grid of selections to benchmark, starting with the `inferred` one.
+fn candidates(inferred: &MatmulSelection) -> Vec<MatmulSelection> {
+    let mut candidates = vec![inferred.clone()];
+
+    for (stage_m, stage_n) in STAGE_SIZES {
+        let tiling_scheme = TilingScheme {
+            stage_size: StageSize::new(stage_m, stage_n, 1),
+            ..inferred.tiling_scheme
+        };
+        for partition_buffering in PARTITION_BUFFERINGS {
+            for tile_iteration in TILE_ITERATIONS {
+                let candidate = MatmulSelection {
+                    tiling_scheme,
+                    partition_buffering,
+                    tile_iteration,
+                    ..inferred.clone()
+                };
+                let is_inferred = tiling_scheme == inferred.tiling_scheme
+                    && partition_buffering == inferred.partition_buffering
+                    && tile_iteration == inferred.tile_iteration;
+                if !is_inferred {
+                    candidates.push(candidate);
+                }
+            }
+        }
+    }
+
+    candidates
+}
+
+/// Whether the config of the `selection` is valid and its cubes fit the hardware limits.
+fn fits_hardware<R: Runtime, MP: MatmulPrecision, A: Algorithm>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    problem: &MatmulProblem,
+    selection: &MatmulSelection,
+    line_sizes: &MatmulLineSizes,
+) -> bool {
+    let Ok(config) = A::setup::<MP, R>(client, problem, selection, line_sizes) else {
+        return false;
+    };
+    let props = &client.properties().hardware;
+    props.max_cube_dim.can_contain(config.cube_dim())
+        && config.cube_dim().num_elems() <= props.max_units_per_cube
+}
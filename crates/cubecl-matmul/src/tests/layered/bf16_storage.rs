@@ -0,0 +1,164 @@
+use cubecl_core::prelude::*;
+use cubecl_core::{
+    CubeElement,
+    server::{Allocation, AllocationDescriptor},
+};
+use half::bf16;
+
+use crate::components::{
+    AvailableLineSizes, Bf16Storage, MatmulIdent, MatmulProblem, MatmulSelection, MatrixLayout,
+};
+use crate::kernels::layered::Algorithm;
+use crate::tests::layered::matmul_test_launcher::{
+    TensorRawParts, launch_matmul, setup_matmul_test, tensor_raw_parts, transpose,
+};
+use crate::tests::test_utils::assert_equals_approx;
+
+/// Test the matmul of `bf16` operands upcast to `f32` in registers with [Bf16Storage], against
+/// the `f32` matmul of the same operands widened before the launch.
+///
+/// Both matmuls compute in `f32` over the same values, so the outputs must match within the
+/// rounding of `bf16`.
+pub fn test_bf16_storage_matmul<A, R>(
+    client: ComputeClient<R::Server, R::Channel>,
+    problem: MatmulProblem,
+    selection: MatmulSelection,
+) where
+    A: Algorithm,
+    R: Runtime,
+{
+    if !bf16::supported_uses(&client).contains(TypeUsage::Conversion) {
+        println!("Skipping test, bf16 can't be stored on this device");
+        return;
+    }
+
+    let lhs = tensor_raw_parts::<(bf16, bf16), R>(&client, &problem, MatmulIdent::Lhs);
+    let rhs = tensor_raw_parts::<(bf16, bf16), R>(&client, &problem, MatmulIdent::Rhs);
+    let lhs_f32 = widened_raw_parts::<R>(
+        &client,
+        lhs.original_data.as_ref().unwrap(),
+        &lhs.shape,
+        problem.lhs_layout,
+    );
+    let rhs_f32 = widened_raw_parts::<R>(
+        &client,
+        rhs.original_data.as_ref().unwrap(),
+        &rhs.shape,
+        problem.rhs_layout,
+    );
+
+    let out = tensor_raw_parts::<(f32, f32), R>(&client, &problem, MatmulIdent::Out);
+    let out_f32 = tensor_raw_parts::<(f32, f32), R>(&client, &problem, MatmulIdent::Out);
+
+    let line_sizes = AvailableLineSizes::from_types::<R>(
+        &bf16::as_type_native_unchecked(),
+        &bf16::as_type_native_unchecked(),
+        &f32::as_type_native_unchecked(),
+    )
+    .filter_lhs_with_tensor(&lhs.strides, &lhs.shape, problem.lhs_layout)
+    .filter_rhs_with_tensor(&rhs.strides, &rhs.shape, problem.rhs_layout)
+    .filter_out_with_tensor(&out.strides, &out.shape);
+    let line_sizes_f32 = AvailableLineSizes::from_types::<R>(
+        &f32::as_type_native_unchecked(),
+        &f32::as_type_native_unchecked(),
+        &f32::as_type_native_unchecked(),
+    )
+    .filter_lhs_with_tensor(&lhs_f32.strides, &lhs_f32.shape, problem.lhs_layout)
+    .filter_rhs_with_tensor(&rhs_f32.strides, &rhs_f32.shape, problem.rhs_layout)
+    .filter_out_with_tensor(&out_f32.strides, &out_f32.shape);
+
+    let setup = setup_matmul_test::<A, Bf16Storage, R>(&client, &problem, &selection, line_sizes);
+    let setup_f32 = setup_matmul_test::<A, f32, R>(&client, &problem, &selection, line_sizes_f32);
+    let ((config, line_sizes), (config_f32, line_sizes_f32)) = match (setup, setup_f32) {
+        (Ok(setup), Ok(setup_f32)) => (setup, setup_f32),
+        (Err(msg), _) | (_, Err(msg)) => {
+            println!("{msg}");
+            return;
+        }
+    };
+    launch_matmul::<A, Bf16Storage, R, _, _, _>(
+        &client,
+        &problem,
+        config,
+        &line_sizes,
+        &lhs,
+        &rhs,
+        None,
+        &out,
+    );
+    launch_matmul::<A, f32, R, _, _, _>(
+        &client,
+        &problem,
+        config_f32,
+        &line_sizes_f32,
+        &lhs_f32,
+        &rhs_f32,
+        None,
+        &out_f32,
+    );
+
+    let expected = client.read_one_tensor(out_f32.handle.copy_descriptor(
+        &out_f32.shape,
+        &out_f32.strides,
+        size_of::<f32>(),
+    ));
+    let expected = f32::from_bytes(&expected);
+
+    if let Err(e) = assert_equals_approx::<R, f32>(
+        &client,
+        out.handle,
+        &out.shape,
+        &out.strides,
+        expected,
+        bf16::EPSILON.to_f32(),
+    ) {
+        panic!("{}", e);
+    }
+}
+
+/// The `f32` tensor of the given `bf16` data, in the same layout as [tensor_raw_parts]
+fn widened_raw_parts<R: Runtime>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    data: &[bf16],
+    shape: &[usize],
+    layout: MatrixLayout,
+) -> TensorRawParts<f32> {
+    let original_data = data.iter().map(|x| x.to_f32()).collect::<Vec<_>>();
+
+    let mut tensor_shape = shape.to_vec();
+    let rank = tensor_shape.len();
+    let (rows, cols) = (tensor_shape[rank - 2], tensor_shape[rank - 1]);
+    let batches = original_data.len() / (rows * cols);
+
+    let data = match layout {
+        MatrixLayout::RowMajor => original_data.clone(),
+        MatrixLayout::ColMajor => {
+            tensor_shape.swap(rank - 1, rank - 2);
+            transpose::<f32>(&original_data, batches, rows, cols)
+        }
+    };
+
+    let descriptors = vec![(
+        AllocationDescriptor::optimized(tensor_shape.as_slice(), size_of::<f32>()),
+        f32::as_bytes(&data),
+    )];
+
+    let mut tensors = client.create_tensors(descriptors);
+    let Allocation {
+        handle,
+        mut strides,
+    } = tensors.remove(0);
+
+    if matches!(layout, MatrixLayout::ColMajor) {
+        tensor_shape.swap(rank - 1, rank - 2);
+        strides.swap(rank - 1, rank - 2);
+    }
+
+    TensorRawParts {
+        handle,
+        scale: None,
+        shape: tensor_shape,
+        strides,
+        original_data: Some(original_data),
+    }
+}
@@ -0,0 +1,215 @@
+use cubecl_core::prelude::*;
+
+use crate::components::{
+    AvailableLineSizes, MatmulIdent, MatmulProblem, MatmulSelection, MatrixLayout,
+};
+use crate::kernels::layered::simple_unit::SimpleUnitAccumulatorAlgorithm;
+use crate::tests::layered::matmul_test_launcher::{
+    TensorRawParts, launch_matmul, setup_matmul_test, strides, tensor_raw_parts,
+};
+use crate::tests::test_utils::{TestPrecision, assert_equals_approx};
+
+/// Test a matmul whose k is split across two launches of [SimpleUnitAccumulatorAlgorithm],
+/// the second launch starting from the output of the first as its accumulator, against a
+/// single launch over the whole k.
+///
+/// The second launch is run twice: once into a separate output, and once writing back in place
+/// into the output of the first launch, which is then both its accumulator and its output.
+///
+/// The split is at a multiple of the stage size along k, so the first launch only runs full
+/// stages. Both operands must be row-major, so the chunks along k are plain sub-slices.
+pub fn test_chunked_k_matmul<P, R>(
+    client: ComputeClient<R::Server, R::Channel>,
+    problem: MatmulProblem,
+    selection: MatmulSelection,
+) where
+    P: TestPrecision,
+    P::EG: Float,
+    R: Runtime,
+{
+    assert_eq!(problem.lhs_layout, MatrixLayout::RowMajor);
+    assert_eq!(problem.rhs_layout, MatrixLayout::RowMajor);
+
+    let stage_k = selection.tiling_scheme.elements_in_stage_k() as usize;
+    let k_split = (problem.k / 2).div_ceil(stage_k) * stage_k;
+    if k_split == 0 || k_split >= problem.k {
+        println!("Skipping test, k must span more than one stage");
+        return;
+    }
+
+    let lhs = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Lhs);
+    let rhs = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Rhs);
+    let lhs_data = lhs.original_data.clone().unwrap();
+    let rhs_data = rhs.original_data.clone().unwrap();
+
+    let chunks = [(0, k_split), (k_split, problem.k)].map(|k_range| {
+        let chunk_problem = MatmulProblem {
+            k: k_range.1 - k_range.0,
+            ..problem.clone()
+        };
+        let lhs =
+            k_chunk_raw_parts::<P, R>(&client, &problem, MatmulIdent::Lhs, &lhs_data, k_range);
+        let rhs =
+            k_chunk_raw_parts::<P, R>(&client, &problem, MatmulIdent::Rhs, &rhs_data, k_range);
+        (chunk_problem, lhs, rhs)
+    });
+
+    let single_out = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Out);
+    let partial_out = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Out);
+    let chunked_out = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Out);
+    let in_place_out = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Out);
+
+    let mut line_sizes = AvailableLineSizes::from_types::<R>(
+        &P::EG::as_type_native_unchecked(),
+        &P::EG::as_type_native_unchecked(),
+        &P::EG::as_type_native_unchecked(),
+    )
+    .filter_lhs_with_tensor(&lhs.strides, &lhs.shape, problem.lhs_layout)
+    .filter_rhs_with_tensor(&rhs.strides, &rhs.shape, problem.rhs_layout)
+    .filter_out_with_tensor(&single_out.strides, &single_out.shape);
+    for (_, lhs, rhs) in chunks.iter() {
+        line_sizes = line_sizes
+            .filter_lhs_with_tensor(&lhs.strides, &lhs.shape, problem.lhs_layout)
+            .filter_rhs_with_tensor(&rhs.strides, &rhs.shape, problem.rhs_layout);
+    }
+
+    let [
+        (first_problem, first_lhs, first_rhs),
+        (second_problem, second_lhs, second_rhs),
+    ] = chunks;
+    let launches = [
+        (&problem, &lhs, &rhs, None, &single_out),
+        (&first_problem, &first_lhs, &first_rhs, None, &partial_out),
+        (
+            &second_problem,
+            &second_lhs,
+            &second_rhs,
+            Some(&partial_out),
+            &chunked_out,
+        ),
+        (&first_problem, &first_lhs, &first_rhs, None, &in_place_out),
+        (
+            &second_problem,
+            &second_lhs,
+            &second_rhs,
+            Some(&in_place_out),
+            &in_place_out,
+        ),
+    ];
+    for (problem, lhs, rhs, acc, out) in launches {
+        if let Err(msg) = launch::<P, R>(
+            &client,
+            problem,
+            &selection,
+            &line_sizes,
+            lhs,
+            rhs,
+            acc,
+            out,
+        ) {
+            println!("{msg}");
+            return;
+        }
+    }
+
+    P::assert_result::<R>(
+        &lhs_data,
+        &rhs_data,
+        None,
+        &problem,
+        &client,
+        single_out.handle.clone(),
+        &single_out.shape,
+        &single_out.strides,
+    );
+
+    let expected = client.read_one_tensor(single_out.handle.copy_descriptor(
+        &single_out.shape,
+        &single_out.strides,
+        size_of::<P::EG>(),
+    ));
+    let expected = P::EG::from_bytes(&expected).to_vec();
+
+    for out in [chunked_out, in_place_out] {
+        if let Err(e) = assert_equals_approx::<R, P::EG>(
+            &client,
+            out.handle,
+            &out.shape,
+            &out.strides,
+            &expected,
+            3.0 * 10e-6,
+        ) {
+            panic!("{}", e);
+        }
+    }
+}
+
+/// Contiguous chunk `[k_range.0, k_range.1)` along k of the row-major Lhs or Rhs `data`
+fn k_chunk_raw_parts<P: TestPrecision, R: Runtime>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    problem: &MatmulProblem,
+    ident: MatmulIdent,
+    data: &[P::EG],
+    k_range: (usize, usize),
+) -> TensorRawParts<P::EG> {
+    let chunk_problem = MatmulProblem {
+        k: k_range.1 - k_range.0,
+        ..problem.clone()
+    };
+
+    let chunk = match ident {
+        MatmulIdent::Lhs => data
+            .chunks(problem.k)
+            .flat_map(|row| &row[k_range.0..k_range.1])
+            .copied()
+            .collect::<Vec<_>>(),
+        MatmulIdent::Rhs => data
+            .chunks(problem.k * problem.n)
+            .flat_map(|batch| &batch[k_range.0 * problem.n..k_range.1 * problem.n])
+            .copied()
+            .collect::<Vec<_>>(),
+        MatmulIdent::Out => unreachable!("The output has no k axis"),
+    };
+
+    TensorRawParts {
+        handle: client.create(P::EG::as_bytes(&chunk)),
+        scale: None,
+        shape: chunk_problem.shape(ident),
+        strides: strides(&chunk_problem, ident),
+        original_data: Some(chunk),
+    }
+}
+
+/// Launch [SimpleUnitAccumulatorAlgorithm] on the `problem`, starting from `acc` if provided.
+///
+/// Returns the reason the launch was skipped if the algorithm can't run on this device.
+#[allow(clippy::too_many_arguments)]
+fn launch<P: TestPrecision, R: Runtime>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    problem: &MatmulProblem,
+    selection: &MatmulSelection,
+    line_sizes: &AvailableLineSizes,
+    lhs: &TensorRawParts<P::EG>,
+    rhs: &TensorRawParts<P::EG>,
+    acc: Option<&TensorRawParts<P::EG>>,
+    out: &TensorRawParts<P::EG>,
+) -> Result<(), String> {
+    let (config, line_sizes) = setup_matmul_test::<
+        SimpleUnitAccumulatorAlgorithm,
+        (P::EG, P::EG, P::EG, P::ES, P::ES, P::EA),
+        R,
+    >(client, problem, selection, line_sizes.clone())?;
+
+    launch_matmul::<SimpleUnitAccumulatorAlgorithm, P::MP, R, _, _, _>(
+        client,
+        problem,
+        config,
+        &line_sizes,
+        lhs,
+        rhs,
+        acc,
+        out,
+    );
+
+    Ok(())
+}
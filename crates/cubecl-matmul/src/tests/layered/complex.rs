@@ -0,0 +1,111 @@
+use cubecl_core::prelude::*;
+
+use crate::components::{AvailableLineSizes, MatmulIdent, MatmulProblem, MatmulSelection};
+use crate::kernels::layered::Algorithm;
+use crate::tests::layered::matmul_test_launcher::{
+    host_raw_parts, launch_matmul, setup_matmul_test, tensor_raw_parts,
+};
+use crate::tests::test_utils::assert_equals_approx;
+
+/// Test the matmul of interleaved complex operands with the [complex](MatmulSelection::complex)
+/// product of the `selection`, against a complex matmul on the host.
+///
+/// The `problem` is in real elements of Lhs and of the output, so `n` and `k` are twice the
+/// complex sizes. Lhs holds random complex numbers interleaved along k, and Rhs holds `k / 2`
+/// rows of complex numbers interleaved along n.
+pub fn test_complex_matmul<A, R>(
+    client: ComputeClient<R::Server, R::Channel>,
+    problem: MatmulProblem,
+    selection: MatmulSelection,
+) where
+    A: Algorithm,
+    R: Runtime,
+{
+    assert_eq!(problem.lhs_batches, problem.rhs_batches);
+    assert!(selection.complex.is_some());
+
+    let lhs = tensor_raw_parts::<(f32, f32), R>(&client, &problem, MatmulIdent::Lhs);
+    let mut rhs_shape = problem.shape(MatmulIdent::Rhs);
+    let rank = rhs_shape.len();
+    rhs_shape[rank - 2] = problem.k / 2;
+    let values = (0..rhs_shape.iter().product::<usize>())
+        .map(|i| ((i * 37 + 11) % 17) as f32 / 8.0 - 1.0)
+        .collect::<Vec<_>>();
+    let rhs = host_raw_parts::<f32, R>(&client, values, rhs_shape, problem.rhs_layout);
+    let out = tensor_raw_parts::<(f32, f32), R>(&client, &problem, MatmulIdent::Out);
+
+    let line_sizes = AvailableLineSizes::from_types::<R>(
+        &f32::as_type_native_unchecked(),
+        &f32::as_type_native_unchecked(),
+        &f32::as_type_native_unchecked(),
+    );
+    let line_sizes = line_sizes
+        .filter_lhs_with_tensor(&lhs.strides, &lhs.shape, problem.lhs_layout)
+        .filter_rhs_with_tensor(&rhs.strides, &rhs.shape, problem.rhs_layout)
+        .filter_out_with_tensor(&out.strides, &out.shape);
+
+    let (config, line_sizes) =
+        match setup_matmul_test::<A, f32, R>(&client, &problem, &selection, line_sizes) {
+            Ok(setup) => setup,
+            Err(msg) => {
+                println!("{msg}");
+                return;
+            }
+        };
+    launch_matmul::<A, f32, R, _, _, _>(
+        &client,
+        &problem,
+        config,
+        &line_sizes,
+        &lhs,
+        &rhs,
+        None,
+        &out,
+    );
+
+    let expected = complex_cpu_reference(
+        lhs.original_data.as_ref().unwrap(),
+        rhs.original_data.as_ref().unwrap(),
+        &problem,
+    );
+
+    if let Err(e) = assert_equals_approx::<R, f32>(
+        &client,
+        out.handle,
+        &out.shape,
+        &out.strides,
+        &expected,
+        10e-5,
+    ) {
+        panic!("{}", e);
+    }
+}
+
+/// The complex product of the row-major `lhs` interleaved along k by the row-major `rhs` with
+/// `k / 2` rows interleaved along n, interleaved along n, for batches of equal shapes on both
+/// sides
+fn complex_cpu_reference(lhs: &[f32], rhs: &[f32], problem: &MatmulProblem) -> Vec<f32> {
+    let (m, n, k) = (problem.m, problem.n, problem.k);
+    let rhs_k = k / 2;
+    let mut out = vec![0.0; problem.num_batches() * m * n];
+
+    for b in 0..problem.num_batches() {
+        for i in 0..m {
+            for j in (0..n).step_by(2) {
+                let (mut re, mut im) = (0.0, 0.0);
+                for l in 0..rhs_k {
+                    let lhs_re = lhs[(b * m + i) * k + 2 * l];
+                    let lhs_im = lhs[(b * m + i) * k + 2 * l + 1];
+                    let rhs_re = rhs[(b * rhs_k + l) * n + j];
+                    let rhs_im = rhs[(b * rhs_k + l) * n + j + 1];
+                    re += lhs_re * rhs_re - lhs_im * rhs_im;
+                    im += lhs_re * rhs_im + lhs_im * rhs_re;
+                }
+                out[(b * m + i) * n + j] = re;
+                out[(b * m + i) * n + j + 1] = im;
+            }
+        }
+    }
+
+    out
+}
@@ -0,0 +1,114 @@
+use cubecl_core::prelude::*;
+use half::f16;
+
+use crate::components::global::read::LoadScale;
+use crate::components::{
+    AvailableLineSizes, Int8Weights, MatmulIdent, MatmulProblem, MatmulSelection,
+};
+use crate::kernels::layered::Algorithm;
+use crate::tests::layered::matmul_test_launcher::{
+    host_raw_parts, launch_matmul, setup_matmul_test, tensor_raw_parts,
+};
+use crate::tests::test_utils::assert_equals_approx;
+
+/// The scale dequantizing the weights, a power of two so the dequantized weights are exact in
+/// `f16`.
+const WEIGHT_SCALE: f32 = 1.0 / 64.0;
+
+/// Test the matmul of `f16` activations by `i8` weights with [Int8Weights], the weights being
+/// dequantized by the Rhs load scale, against a CPU reference accumulating in `f32`.
+///
+/// The activations and the dequantized weights are exact in `f16`, so the outputs must match
+/// up to the order of the `f32` accumulation.
+pub fn test_int8_weights_matmul<A, R>(
+    client: ComputeClient<R::Server, R::Channel>,
+    problem: MatmulProblem,
+    selection: MatmulSelection,
+) where
+    A: Algorithm,
+    R: Runtime,
+{
+    assert_eq!(problem.lhs_batches, problem.rhs_batches);
+
+    let selection = MatmulSelection {
+        load_scale: LoadScale::rhs(WEIGHT_SCALE),
+        ..selection
+    };
+
+    let lhs = tensor_raw_parts::<(f16, f16), R>(&client, &problem, MatmulIdent::Lhs);
+    let rhs_shape = problem.shape(MatmulIdent::Rhs);
+    let weights = (0..rhs_shape.iter().product::<usize>())
+        .map(|i| ((i * 37 + 11) % 255) as i32 - 127)
+        .map(|x| x as i8)
+        .collect::<Vec<_>>();
+    let rhs = host_raw_parts::<i8, R>(&client, weights, rhs_shape, problem.rhs_layout);
+    let out = tensor_raw_parts::<(f32, f32), R>(&client, &problem, MatmulIdent::Out);
+
+    let line_sizes = AvailableLineSizes::from_types::<R>(
+        &f16::as_type_native_unchecked(),
+        &i8::as_type_native_unchecked(),
+        &f32::as_type_native_unchecked(),
+    );
+    let line_sizes = line_sizes
+        .filter_lhs_with_tensor(&lhs.strides, &lhs.shape, problem.lhs_layout)
+        .filter_rhs_with_tensor(&rhs.strides, &rhs.shape, problem.rhs_layout)
+        .filter_out_with_tensor(&out.strides, &out.shape);
+
+    let (config, line_sizes) =
+        match setup_matmul_test::<A, Int8Weights, R>(&client, &problem, &selection, line_sizes) {
+            Ok(setup) => setup,
+            Err(msg) => {
+                println!("{msg}");
+                return;
+            }
+        };
+    launch_matmul::<A, Int8Weights, R, _, _, _>(
+        &client,
+        &problem,
+        config,
+        &line_sizes,
+        &lhs,
+        &rhs,
+        None,
+        &out,
+    );
+
+    let expected = int8_weights_cpu_reference(
+        lhs.original_data.as_ref().unwrap(),
+        rhs.original_data.as_ref().unwrap(),
+        &problem,
+    );
+
+    if let Err(e) = assert_equals_approx::<R, f32>(
+        &client,
+        out.handle,
+        &out.shape,
+        &out.strides,
+        &expected,
+        3.0 * 10e-6,
+    ) {
+        panic!("{}", e);
+    }
+}
+
+/// The product of the row-major `lhs` by the row-major `rhs` dequantized by [WEIGHT_SCALE],
+/// accumulated in `f32`, for batches of equal shapes on both sides
+fn int8_weights_cpu_reference(lhs: &[f16], rhs: &[i8], problem: &MatmulProblem) -> Vec<f32> {
+    let (m, n, k) = (problem.m, problem.n, problem.k);
+    let mut out = vec![0.0; problem.num_batches() * m * n];
+
+    for b in 0..problem.num_batches() {
+        for i in 0..m {
+            for j in 0..n {
+                out[(b * m + i) * n + j] = (0..k)
+                    .map(|l| {
+                        lhs[(b * m + i) * k + l].to_f32()
+                            * (rhs[(b * k + l) * n + j] as f32 * WEIGHT_SCALE)
+                    })
+                    .sum();
+            }
+        }
+    }
+
+    out
+}
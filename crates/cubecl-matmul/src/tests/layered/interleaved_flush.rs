@@ -0,0 +1,54 @@
+use cubecl_core::prelude::*;
+
+use crate::components::error::MatmulSetupError;
+use crate::components::stage::AccumulatorFlush;
+use crate::components::{AvailableLineSizes, MatmulProblem, MatmulSelection};
+use crate::kernels::layered::Algorithm;
+use crate::tests::layered::matmul_test_launcher::setup_matmul_test;
+use crate::tests::test_utils::TestPrecision;
+
+/// Check that the setup of the algorithm `A` rejects the interleaved accumulator flush of the
+/// `selection`, while the same selection flushed after the execution is set up fine
+pub fn test_interleaved_flush_rejected<A, P, R>(
+    client: ComputeClient<R::Server, R::Channel>,
+    problem: MatmulProblem,
+    selection: MatmulSelection,
+) where
+    A: Algorithm,
+    P: TestPrecision,
+    R: Runtime,
+{
+    let line_sizes = AvailableLineSizes::from_types::<R>(
+        &P::EG::as_type_native_unchecked(),
+        &P::EG::as_type_native_unchecked(),
+        &P::EG::as_type_native_unchecked(),
+    );
+    let after_execution = MatmulSelection {
+        accumulator_flush: AccumulatorFlush::AfterExecution,
+        ..selection.clone()
+    };
+
+    let line_sizes =
+        match setup_matmul_test::<A, P::MP, R>(&client, &problem, &after_execution, line_sizes) {
+            Ok((_, line_sizes)) => line_sizes,
+            Err(msg) => {
+                println!("{msg}");
+                return;
+            }
+        };
+
+    let interleaved = MatmulSelection {
+        accumulator_flush: AccumulatorFlush::Interleaved,
+        ..selection
+    };
+    let err = A::setup::<P::MP, R>(&client, &problem, &interleaved, &line_sizes);
+    assert!(
+        matches!(
+            &err,
+            Err(MatmulSetupError::InvalidConfig(err))
+                if err.to_string().contains("Interleaved accumulator flush")
+        ),
+        "Unexpected result {:?}",
+        err.map(|_| ())
+    );
+}
@@ -13,6 +13,58 @@ macro_rules! testgen_matmul_launch {
         }
     };
 
+    (Accumulate, $algorithm: ty, $precision: ty, $selection: expr, $problem: expr) => {
+        use super::*;
+        use $crate::tests::layered::matmul_test_launcher::test_matmul_algorithm_accumulate;
+
+        #[test]
+        pub fn test() {
+            let client = TestRuntime::client(&Default::default());
+            test_matmul_algorithm_accumulate::<$algorithm, $precision, TestRuntime>(
+                client, $problem, $selection,
+            );
+        }
+    };
+
+    (RowBias, $algorithm: ty, $precision: ty, $selection: expr, $problem: expr) => {
+        use super::*;
+        use $crate::tests::layered::matmul_test_launcher::test_matmul_algorithm_row_bias;
+
+        #[test]
+        pub fn test() {
+            let client = TestRuntime::client(&Default::default());
+            test_matmul_algorithm_row_bias::<$algorithm, $precision, TestRuntime>(
+                client, $problem, $selection,
+            );
+        }
+    };
+
+    (InterleavedOut, $algorithm: ty, $precision: ty, $selection: expr, $problem: expr) => {
+        use super::*;
+        use $crate::tests::layered::matmul_test_launcher::test_matmul_algorithm_interleaved_out;
+
+        #[test]
+        pub fn test() {
+            let client = TestRuntime::client(&Default::default());
+            test_matmul_algorithm_interleaved_out::<$algorithm, $precision, TestRuntime>(
+                client, $problem, $selection,
+            );
+        }
+    };
+
+    (StageLimits, $algorithm: ty, $precision: ty, $selection: expr, $problem: expr) => {
+        use super::*;
+        use $crate::tests::layered::stage_limits::test_stage_device_limits;
+
+        #[test]
+        pub fn test() {
+            let client = TestRuntime::client(&Default::default());
+            test_stage_device_limits::<$algorithm, $precision, TestRuntime>(
+                client, $problem, $selection,
+            );
+        }
+    };
+
     (Tma, $algorithm: ty, $precision: ty, $selection: expr, $problem: expr) => {
         use super::*;
         use $crate::tests::layered::tma_test_launcher::test_tma_matmul_algorithm;
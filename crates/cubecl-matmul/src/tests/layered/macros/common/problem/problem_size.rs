@@ -104,6 +104,219 @@ macro_rules! testgen_matmul_problem_size {
             );
         }
 
+        // Trailing N-tiles of the stage are pure padding, and should be skipped
+        #[cfg(feature = "matmul_tests_alt_shapes")]
+        mod g100x16x100_ordered_tiles {
+            use super::*;
+            use $crate::components::stage::TileIteration;
+
+            $crate::testgen_matmul_launch!(
+                $kind,
+                $algorithm,
+                $precision,
+                MatmulSelection {
+                    tile_iteration: TileIteration::Ordered,
+                    ..$selection
+                },
+                MatmulProblem {
+                    m: 100,
+                    n: 16,
+                    k: 100,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: $layouts.0,
+                    rhs_layout: $layouts.1,
+                }
+            );
+        }
+
+        // The last tile along K only has 3 real elements, the others are predicated out
+        #[cfg(feature = "matmul_tests_alt_shapes")]
+        mod g100x100xk_partial_k_tile {
+            use super::*;
+            use $crate::components::stage::TileIteration;
+
+            $crate::testgen_matmul_launch!(
+                $kind,
+                $algorithm,
+                $precision,
+                MatmulSelection {
+                    tile_iteration: TileIteration::Ordered,
+                    ..$selection
+                },
+                MatmulProblem {
+                    m: 100,
+                    n: 100,
+                    k: ($selection).tiling_scheme.tile_size.k() as usize * 2 + 3,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: $layouts.0,
+                    rhs_layout: $layouts.1,
+                }
+            );
+        }
+
+        // Lhs is scaled by 1/sqrt(64) as it is loaded, as the query of an attention
+        #[cfg(feature = "matmul_tests_alt_shapes")]
+        mod g100x100x100_lhs_load_scale {
+            use super::*;
+            use $crate::components::global::read::LoadScale;
+
+            $crate::testgen_matmul_launch!(
+                $kind,
+                $algorithm,
+                $precision,
+                MatmulSelection {
+                    load_scale: LoadScale::lhs(0.125),
+                    ..$selection
+                },
+                MatmulProblem {
+                    m: 100,
+                    n: 100,
+                    k: 100,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: $layouts.0,
+                    rhs_layout: $layouts.1,
+                }
+            );
+        }
+
+        // A ReLU on Lhs is fused into its load, with the reference applying it before the matmul
+        #[cfg(feature = "matmul_tests_alt_shapes")]
+        mod g100x100x100_lhs_relu_on_load {
+            use super::*;
+            use $crate::components::global::read::{ElementwiseTransform, LoadTransform};
+
+            $crate::testgen_matmul_launch!(
+                $kind,
+                $algorithm,
+                $precision,
+                MatmulSelection {
+                    load_transform: LoadTransform::lhs(ElementwiseTransform::Relu),
+                    ..$selection
+                },
+                MatmulProblem {
+                    m: 100,
+                    n: 100,
+                    k: 100,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: $layouts.0,
+                    rhs_layout: $layouts.1,
+                }
+            );
+        }
+
+        // Both inputs are offset on load with a k that doesn't fill the last stage, so the
+        // padding along k must stay at zero instead of being offset
+        #[cfg(feature = "matmul_tests_alt_shapes")]
+        mod g100x100xk_offset_on_load_partial_k {
+            use super::*;
+            use $crate::components::global::read::{ElementwiseTransform, LoadTransform};
+
+            $crate::testgen_matmul_launch!(
+                $kind,
+                $algorithm,
+                $precision,
+                MatmulSelection {
+                    load_transform: LoadTransform::new(
+                        ElementwiseTransform::offset(0.5),
+                        ElementwiseTransform::offset(-0.25),
+                    ),
+                    ..$selection
+                },
+                MatmulProblem {
+                    m: 100,
+                    n: 100,
+                    k: ($selection).tiling_scheme.elements_in_stage_k() as usize + 3,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: $layouts.0,
+                    rhs_layout: $layouts.1,
+                }
+            );
+        }
+
+        // M fits in a single tile, lhs fragments can stay in registers
+        #[cfg(feature = "matmul_tests_alt_shapes")]
+        mod g8x256x256_register_lhs {
+            use super::*;
+            use $crate::components::stage::PartitionBuffering;
+
+            $crate::testgen_matmul_launch!(
+                $kind,
+                $algorithm,
+                $precision,
+                MatmulSelection {
+                    partition_buffering: PartitionBuffering::RegisterLhs,
+                    ..$selection
+                },
+                MatmulProblem {
+                    m: 8,
+                    n: 256,
+                    k: 256,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: $layouts.0,
+                    rhs_layout: $layouts.1,
+                }
+            );
+        }
+
+        // Tiles of the output are stored contiguously
+        #[cfg(feature = "matmul_tests_alt_shapes")]
+        mod g256x256x256_blocked_output {
+            use super::*;
+            use $crate::components::global::memory::OutputLayout;
+
+            $crate::testgen_matmul_launch!(
+                $kind,
+                $algorithm,
+                $precision,
+                MatmulSelection {
+                    output_layout: OutputLayout::Blocked,
+                    ..$selection
+                },
+                MatmulProblem {
+                    m: 256,
+                    n: 256,
+                    k: 256,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: $layouts.0,
+                    rhs_layout: $layouts.1,
+                }
+            );
+        }
+
+        // Accumulators computed one column at a time, over the whole k of each stage
+        #[cfg(feature = "matmul_tests_alt_shapes")]
+        mod g256x256x256_register_blocked_acc {
+            use super::*;
+            use $crate::components::stage::PartitionBuffering;
+
+            $crate::testgen_matmul_launch!(
+                $kind,
+                $algorithm,
+                $precision,
+                MatmulSelection {
+                    partition_buffering: PartitionBuffering::Single,
+                    acc_tiles_n: Some(1),
+                    ..$selection
+                },
+                MatmulProblem {
+                    m: 256,
+                    n: 256,
+                    k: 256,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: $layouts.0,
+                    rhs_layout: $layouts.1,
+                }
+            );
+        }
+
         #[cfg(feature = "matmul_tests_vecmat")]
         mod g1x256x256 {
             use super::*;
@@ -96,5 +96,27 @@ macro_rules! testgen_matmul_plane_accelerated_algorithm {
 
             $crate::testgen_matmul_accelerated_precision!(OrderedDoubleBufferingAlgorithm<TMM>);
         }
+
+        #[cfg(all(feature = "matmul_tests_simple", feature = "matmul_tests_tune"))]
+        mod simple_selection_tuner {
+            use super::*;
+            use $crate::components::{MatmulProblem, MatrixLayout};
+            use $crate::tests::layered::selection_tuner::test_selection_tuner;
+
+            #[test]
+            pub fn test() {
+                let client = TestRuntime::client(&Default::default());
+                let problem = MatmulProblem {
+                    m: 256,
+                    n: 256,
+                    k: 256,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: MatrixLayout::RowMajor,
+                    rhs_layout: MatrixLayout::RowMajor,
+                };
+                test_selection_tuner::<SimpleAlgorithm<TMM>, (f32, f32), TestRuntime>(client, problem);
+            }
+        }
     };
 }
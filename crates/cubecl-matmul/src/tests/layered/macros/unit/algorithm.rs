@@ -2,7 +2,9 @@
 macro_rules! testgen_matmul_unit_algorithm {
     () => {
         use $crate::kernels::layered::double_unit::DoubleUnitAlgorithm;
-        use $crate::kernels::layered::simple_unit::SimpleUnitAlgorithm;
+        use $crate::kernels::layered::simple_unit::{
+            SimpleUnitAccumulatorAlgorithm, SimpleUnitAlgorithm,
+        };
 
         #[cfg(feature = "matmul_tests_simple")]
         mod simple {
@@ -11,6 +13,629 @@ macro_rules! testgen_matmul_unit_algorithm {
             $crate::testgen_matmul_unit_precision!(SimpleUnitAlgorithm);
         }
 
+        // f16 accumulator tensor, accumulated in f32
+        #[cfg(all(feature = "matmul_tests_simple", feature = "matmul_tests_f16"))]
+        mod simple_accumulator {
+            use super::*;
+            use $crate::components::{PartitionSize, StageSize, TileSize, TilingScheme};
+
+            $crate::testgen_matmul_advanced!(
+                Accumulate,
+                SimpleUnitAccumulatorAlgorithm,
+                (half::f16, half::f16),
+                TilingScheme::builder()
+                    .with_tile_size(TileSize { m: 4, n: 4, k: 4 })
+                    .with_partition_size(PartitionSize { m: 1, n: 1, k: 4 })
+                    .with_stage_size(StageSize { m: 4, n: 4, k: 1 })
+            );
+        }
+
+        // Per-row bias, broadcast along n from a vector of m values per batch
+        #[cfg(all(feature = "matmul_tests_simple", feature = "matmul_tests_f32"))]
+        mod simple_row_bias {
+            use super::*;
+            use $crate::components::{PartitionSize, StageSize, TileSize, TilingScheme};
+
+            $crate::testgen_matmul_advanced!(
+                RowBias,
+                SimpleUnitAccumulatorAlgorithm,
+                (f32, f32),
+                TilingScheme::builder()
+                    .with_tile_size(TileSize { m: 4, n: 4, k: 4 })
+                    .with_partition_size(PartitionSize { m: 1, n: 1, k: 4 })
+                    .with_stage_size(StageSize { m: 4, n: 4, k: 1 })
+            );
+        }
+
+        // Output batches interleaved row by row in a single buffer
+        #[cfg(all(feature = "matmul_tests_simple", feature = "matmul_tests_f32"))]
+        mod simple_interleaved_out {
+            use super::*;
+            use $crate::components::{PartitionSize, StageSize, TileSize, TilingScheme};
+
+            $crate::testgen_matmul_advanced!(
+                InterleavedOut,
+                SimpleUnitAlgorithm,
+                (f32, f32),
+                TilingScheme::builder()
+                    .with_tile_size(TileSize { m: 4, n: 4, k: 4 })
+                    .with_partition_size(PartitionSize { m: 1, n: 1, k: 4 })
+                    .with_stage_size(StageSize { m: 4, n: 4, k: 1 })
+            );
+        }
+
+        // Stage config checked against lowered device limits
+        #[cfg(all(feature = "matmul_tests_simple", feature = "matmul_tests_f32"))]
+        mod simple_stage_limits {
+            use super::*;
+            use $crate::components::{PartitionSize, StageSize, TileSize, TilingScheme};
+
+            $crate::testgen_matmul_advanced!(
+                StageLimits,
+                SimpleUnitAlgorithm,
+                (f32, f32),
+                TilingScheme::builder()
+                    .with_tile_size(TileSize { m: 4, n: 4, k: 4 })
+                    .with_partition_size(PartitionSize { m: 1, n: 1, k: 4 })
+                    .with_stage_size(StageSize { m: 4, n: 4, k: 1 })
+            );
+        }
+
+        // Tiles of the stage made only of padding skipped by the ordered tile iteration
+        #[cfg(all(feature = "matmul_tests_simple", feature = "matmul_tests_f32"))]
+        mod simple_ordered_tiles {
+            use super::*;
+            use $crate::tests::layered::ordered_tiles::test_ordered_tiles;
+
+            #[test]
+            pub fn test() {
+                test_ordered_tiles::<TestRuntime>(TestRuntime::client(&Default::default()));
+            }
+        }
+
+        // Three rhs sharing the same lhs, against a separate matmul for each
+        #[cfg(all(feature = "matmul_tests_simple", feature = "matmul_tests_f32"))]
+        mod simple_multi_rhs {
+            use super::*;
+            use $crate::components::{
+                MatmulProblem, MatmulSelection, MatrixLayout, PartitionSize, StageSize, TileSize,
+                TilingScheme,
+            };
+            use $crate::tests::layered::multi_rhs::test_multi_rhs_matmul;
+
+            #[test]
+            pub fn test() {
+                let client = TestRuntime::client(&Default::default());
+                let tiling_scheme = TilingScheme::builder()
+                    .with_tile_size(TileSize { m: 4, n: 4, k: 4 })
+                    .with_partition_size(PartitionSize { m: 1, n: 1, k: 4 })
+                    .with_stage_size(StageSize { m: 4, n: 4, k: 1 })
+                    .build()
+                    .unwrap();
+                let plane_dim = client.properties().hardware.plane_size_max;
+                let selection = MatmulSelection::builder(tiling_scheme, plane_dim).build();
+                let problem = MatmulProblem {
+                    m: 40,
+                    n: 36,
+                    k: 52,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: MatrixLayout::RowMajor,
+                    rhs_layout: MatrixLayout::RowMajor,
+                };
+
+                test_multi_rhs_matmul::<(f32, f32), TestRuntime>(client, problem, selection);
+            }
+        }
+
+        // Same with double buffering, alternating the rhs tiles across the stages of all rhs
+        #[cfg(all(feature = "matmul_tests_simple", feature = "matmul_tests_f32"))]
+        mod simple_multi_rhs_double_buffer {
+            use super::*;
+            use $crate::components::stage::PartitionBuffering;
+            use $crate::components::{
+                MatmulProblem, MatmulSelection, MatrixLayout, PartitionSize, StageSize, TileSize,
+                TilingScheme,
+            };
+            use $crate::tests::layered::multi_rhs::test_multi_rhs_matmul;
+
+            #[test]
+            pub fn test() {
+                let client = TestRuntime::client(&Default::default());
+                let tiling_scheme = TilingScheme::builder()
+                    .with_tile_size(TileSize { m: 4, n: 4, k: 4 })
+                    .with_partition_size(PartitionSize { m: 1, n: 2, k: 4 })
+                    .with_stage_size(StageSize { m: 4, n: 2, k: 1 })
+                    .build()
+                    .unwrap();
+                let plane_dim = client.properties().hardware.plane_size_max;
+                let selection = MatmulSelection::builder(tiling_scheme, plane_dim)
+                    .partition_buffering(PartitionBuffering::Double)
+                    .build();
+                let problem = MatmulProblem {
+                    m: 40,
+                    n: 36,
+                    k: 52,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: MatrixLayout::RowMajor,
+                    rhs_layout: MatrixLayout::RowMajor,
+                };
+
+                test_multi_rhs_matmul::<(f32, f32), TestRuntime>(client, problem, selection);
+            }
+        }
+
+        // A single plane of 64 units, forced rather than read from the client
+        #[cfg(all(feature = "matmul_tests_simple", feature = "matmul_tests_f32"))]
+        mod simple_plane_dim_64 {
+            use super::*;
+            use $crate::components::{
+                MatmulProblem, MatmulSelection, MatrixLayout, PartitionSize, StageSize, TileSize,
+                TilingScheme,
+            };
+            use $crate::tests::layered::plane_dim::test_forced_plane_dim;
+
+            #[test]
+            pub fn test() {
+                let client = TestRuntime::client(&Default::default());
+                let tiling_scheme = TilingScheme::builder()
+                    .with_tile_size(TileSize { m: 4, n: 4, k: 4 })
+                    .with_partition_size(PartitionSize { m: 1, n: 1, k: 4 })
+                    .with_stage_size(StageSize { m: 8, n: 8, k: 1 })
+                    .build()
+                    .unwrap();
+                let plane_dim = client.properties().hardware.plane_size_max;
+                let selection = MatmulSelection::builder(tiling_scheme, plane_dim).build();
+                let problem = MatmulProblem {
+                    m: 72,
+                    n: 64,
+                    k: 52,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: MatrixLayout::RowMajor,
+                    rhs_layout: MatrixLayout::RowMajor,
+                };
+
+                test_forced_plane_dim::<SimpleUnitAlgorithm, (f32, f32), TestRuntime>(
+                    client, problem, selection, 64,
+                );
+            }
+        }
+
+        // Lhs kept in registers for a single tile in m, against the Single and Double bufferings
+        #[cfg(all(feature = "matmul_tests_simple", feature = "matmul_tests_f32"))]
+        mod simple_register_lhs_parity {
+            use super::*;
+            use $crate::components::{
+                MatmulProblem, MatmulSelection, MatrixLayout, PartitionSize, StageSize, TileSize,
+                TilingScheme,
+            };
+            use $crate::tests::layered::register_lhs::test_register_lhs_parity;
+
+            #[test]
+            pub fn test() {
+                let client = TestRuntime::client(&Default::default());
+                let tiling_scheme = TilingScheme::builder()
+                    .with_tile_size(TileSize { m: 4, n: 4, k: 4 })
+                    .with_partition_size(PartitionSize { m: 1, n: 4, k: 4 })
+                    .with_stage_size(StageSize { m: 1, n: 16, k: 1 })
+                    .build()
+                    .unwrap();
+                let plane_dim = client.properties().hardware.plane_size_max;
+                let selection = MatmulSelection::builder(tiling_scheme, plane_dim).build();
+                let problem = MatmulProblem {
+                    m: 4,
+                    n: 256,
+                    k: 100,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: MatrixLayout::RowMajor,
+                    rhs_layout: MatrixLayout::RowMajor,
+                };
+
+                test_register_lhs_parity::<SimpleUnitAlgorithm, (f32, f32), TestRuntime>(
+                    client, problem, selection,
+                );
+            }
+        }
+
+        // Each column of accumulators written while the next ones compute, which only the simple
+        // matmul with registered Lhs supports
+        #[cfg(all(feature = "matmul_tests_simple", feature = "matmul_tests_f32"))]
+        mod simple_interleaved_flush {
+            use super::*;
+            use $crate::components::stage::{AccumulatorFlush, PartitionBuffering};
+            use $crate::components::{
+                MatmulProblem, MatmulSelection, MatrixLayout, PartitionSize, StageSize, TileSize,
+                TilingScheme,
+            };
+            use $crate::tests::layered::interleaved_flush::test_interleaved_flush_rejected;
+            use $crate::tests::layered::matmul_test_launcher::test_matmul_algorithm;
+
+            fn selection(buffering: PartitionBuffering, plane_dim: u32) -> MatmulSelection {
+                let tiling_scheme = TilingScheme::builder()
+                    .with_tile_size(TileSize { m: 4, n: 4, k: 4 })
+                    .with_partition_size(PartitionSize { m: 1, n: 4, k: 4 })
+                    .with_stage_size(StageSize { m: 2, n: 8, k: 1 })
+                    .build()
+                    .unwrap();
+                MatmulSelection::builder(tiling_scheme, plane_dim)
+                    .partition_buffering(buffering)
+                    .accumulator_flush(AccumulatorFlush::Interleaved)
+                    .build()
+            }
+
+            fn problem() -> MatmulProblem {
+                MatmulProblem {
+                    m: 8,
+                    n: 256,
+                    k: 256,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: MatrixLayout::RowMajor,
+                    rhs_layout: MatrixLayout::RowMajor,
+                }
+            }
+
+            #[test]
+            pub fn register_lhs() {
+                let client = TestRuntime::client(&Default::default());
+                let plane_dim = client.properties().hardware.plane_size_max;
+                let selection = selection(PartitionBuffering::RegisterLhs, plane_dim);
+
+                test_matmul_algorithm::<SimpleUnitAlgorithm, (f32, f32), TestRuntime>(
+                    client,
+                    problem(),
+                    selection,
+                );
+            }
+
+            #[test]
+            pub fn single_buffering_rejected() {
+                let client = TestRuntime::client(&Default::default());
+                let plane_dim = client.properties().hardware.plane_size_max;
+                let selection = selection(PartitionBuffering::Single, plane_dim);
+
+                test_interleaved_flush_rejected::<SimpleUnitAlgorithm, (f32, f32), TestRuntime>(
+                    client,
+                    problem(),
+                    selection,
+                );
+            }
+
+            #[test]
+            pub fn double_unit_rejected() {
+                let client = TestRuntime::client(&Default::default());
+                let plane_dim = client.properties().hardware.plane_size_max;
+                let selection = selection(PartitionBuffering::RegisterLhs, plane_dim);
+
+                test_interleaved_flush_rejected::<DoubleUnitAlgorithm, (f32, f32), TestRuntime>(
+                    client,
+                    problem(),
+                    selection,
+                );
+            }
+        }
+
+        // k split across two launches, the second resuming from the output of the first
+        #[cfg(all(feature = "matmul_tests_simple", feature = "matmul_tests_f32"))]
+        mod simple_chunked_k {
+            use super::*;
+            use $crate::components::{
+                MatmulProblem, MatmulSelection, MatrixLayout, PartitionSize, StageSize, TileSize,
+                TilingScheme,
+            };
+            use $crate::tests::layered::chunked_k::test_chunked_k_matmul;
+
+            #[test]
+            pub fn test() {
+                let client = TestRuntime::client(&Default::default());
+                let tiling_scheme = TilingScheme::builder()
+                    .with_tile_size(TileSize { m: 4, n: 4, k: 4 })
+                    .with_partition_size(PartitionSize { m: 1, n: 1, k: 4 })
+                    .with_stage_size(StageSize { m: 4, n: 4, k: 1 })
+                    .build()
+                    .unwrap();
+                let plane_dim = client.properties().hardware.plane_size_max;
+                let selection = MatmulSelection::builder(tiling_scheme, plane_dim).build();
+                let problem = MatmulProblem {
+                    m: 36,
+                    n: 36,
+                    k: 100,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: MatrixLayout::RowMajor,
+                    rhs_layout: MatrixLayout::RowMajor,
+                };
+
+                test_chunked_k_matmul::<(f32, f32), TestRuntime>(client, problem, selection);
+            }
+        }
+
+        // One triangle of a matrix times its own transpose, mirrored and compared to the dense product
+        #[cfg(all(feature = "matmul_tests_simple", feature = "matmul_tests_f32"))]
+        mod simple_syrk {
+            use super::*;
+            use $crate::components::{
+                MatmulProblem, MatmulSelection, MatrixLayout, PartitionSize, StageSize, TileSize,
+                TilingScheme, global::single_stage::simple::SyrkTriangle,
+            };
+            use $crate::tests::layered::syrk::test_syrk_matmul;
+
+            fn test(triangle: SyrkTriangle) {
+                let client = TestRuntime::client(&Default::default());
+                let tiling_scheme = TilingScheme::builder()
+                    .with_tile_size(TileSize { m: 4, n: 4, k: 4 })
+                    .with_partition_size(PartitionSize { m: 1, n: 1, k: 4 })
+                    .with_stage_size(StageSize { m: 4, n: 4, k: 1 })
+                    .build()
+                    .unwrap();
+                let plane_dim = client.properties().hardware.plane_size_max;
+                let selection = MatmulSelection::builder(tiling_scheme, plane_dim).build();
+                let problem = MatmulProblem {
+                    m: 36,
+                    n: 36,
+                    k: 52,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: MatrixLayout::RowMajor,
+                    rhs_layout: MatrixLayout::ColMajor,
+                };
+
+                test_syrk_matmul::<(f32, f32), TestRuntime>(
+                    client, problem, selection, triangle, false,
+                );
+            }
+
+            #[test]
+            pub fn lower() {
+                test(SyrkTriangle::Lower);
+            }
+
+            #[test]
+            pub fn upper() {
+                test(SyrkTriangle::Upper);
+            }
+        }
+
+        // The transpose of a matrix times the matrix, compared to the general matmul
+        #[cfg(all(feature = "matmul_tests_simple", feature = "matmul_tests_f32"))]
+        mod simple_syrk_transposed {
+            use super::*;
+            use $crate::components::{
+                MatmulProblem, MatmulSelection, MatrixLayout, PartitionSize, StageSize, TileSize,
+                TilingScheme, global::single_stage::simple::SyrkTriangle,
+            };
+            use $crate::tests::layered::syrk::test_syrk_matmul;
+
+            fn test(triangle: SyrkTriangle) {
+                let client = TestRuntime::client(&Default::default());
+                let tiling_scheme = TilingScheme::builder()
+                    .with_tile_size(TileSize { m: 4, n: 4, k: 4 })
+                    .with_partition_size(PartitionSize { m: 1, n: 1, k: 4 })
+                    .with_stage_size(StageSize { m: 4, n: 4, k: 1 })
+                    .build()
+                    .unwrap();
+                let plane_dim = client.properties().hardware.plane_size_max;
+                let selection = MatmulSelection::builder(tiling_scheme, plane_dim).build();
+                let problem = MatmulProblem {
+                    m: 36,
+                    n: 36,
+                    k: 52,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: MatrixLayout::ColMajor,
+                    rhs_layout: MatrixLayout::RowMajor,
+                };
+
+                test_syrk_matmul::<(f32, f32), TestRuntime>(
+                    client, problem, selection, triangle, true,
+                );
+            }
+
+            #[test]
+            pub fn lower() {
+                test(SyrkTriangle::Lower);
+            }
+
+            #[test]
+            pub fn upper() {
+                test(SyrkTriangle::Upper);
+            }
+        }
+
+        // Column-major output summed over n by the reduce crate, reading it where it was written
+        #[cfg(all(feature = "matmul_tests_simple", feature = "matmul_tests_f32"))]
+        mod simple_column_major_out_reduce {
+            use super::*;
+            use $crate::components::{
+                MatmulProblem, MatmulSelection, MatrixLayout, PartitionSize, StageSize, TileSize,
+                TilingScheme,
+            };
+            use $crate::tests::layered::reduce_pipeline::test_matmul_reduce_pipeline;
+
+            #[test]
+            pub fn test() {
+                let client = TestRuntime::client(&Default::default());
+                let tiling_scheme = TilingScheme::builder()
+                    .with_tile_size(TileSize { m: 4, n: 4, k: 4 })
+                    .with_partition_size(PartitionSize { m: 1, n: 1, k: 4 })
+                    .with_stage_size(StageSize { m: 4, n: 4, k: 1 })
+                    .build()
+                    .unwrap();
+                let plane_dim = client.properties().hardware.plane_size_max;
+                let selection = MatmulSelection::builder(tiling_scheme, plane_dim).build();
+                let problem = MatmulProblem {
+                    m: 40,
+                    n: 36,
+                    k: 52,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: MatrixLayout::RowMajor,
+                    rhs_layout: MatrixLayout::RowMajor,
+                };
+
+                test_matmul_reduce_pipeline::<TestRuntime>(client, problem, selection);
+            }
+        }
+
+        // The row sums written with the output, against the matmul followed by a reduction
+        #[cfg(all(feature = "matmul_tests_simple", feature = "matmul_tests_f32"))]
+        mod simple_row_sum {
+            use super::*;
+            use $crate::components::{
+                MatmulProblem, MatmulSelection, MatrixLayout, PartitionSize, StageSize, TileSize,
+                TilingScheme,
+            };
+            use $crate::tests::layered::row_sum::test_row_sum_matmul;
+
+            #[test]
+            pub fn test() {
+                let client = TestRuntime::client(&Default::default());
+                let tiling_scheme = TilingScheme::builder()
+                    .with_tile_size(TileSize { m: 4, n: 4, k: 4 })
+                    .with_partition_size(PartitionSize { m: 1, n: 2, k: 4 })
+                    .with_stage_size(StageSize { m: 4, n: 2, k: 1 })
+                    .build()
+                    .unwrap();
+                let plane_dim = client.properties().hardware.plane_size_max;
+                let selection = MatmulSelection::builder(tiling_scheme, plane_dim).build();
+                let problem = MatmulProblem {
+                    m: 42,
+                    n: 38,
+                    k: 52,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: MatrixLayout::RowMajor,
+                    rhs_layout: MatrixLayout::RowMajor,
+                };
+
+                test_row_sum_matmul::<TestRuntime>(client, problem, selection);
+            }
+        }
+
+        // bf16 operands upcast to f32 in registers, against the same matmul in f32 storage
+        #[cfg(all(feature = "matmul_tests_simple", feature = "matmul_tests_f32"))]
+        mod simple_bf16_storage {
+            use super::*;
+            use $crate::components::{
+                MatmulProblem, MatmulSelection, MatrixLayout, PartitionSize, StageSize, TileSize,
+                TilingScheme,
+            };
+            use $crate::tests::layered::bf16_storage::test_bf16_storage_matmul;
+
+            #[test]
+            pub fn test() {
+                let client = TestRuntime::client(&Default::default());
+                let tiling_scheme = TilingScheme::builder()
+                    .with_tile_size(TileSize { m: 4, n: 4, k: 4 })
+                    .with_partition_size(PartitionSize { m: 1, n: 1, k: 4 })
+                    .with_stage_size(StageSize { m: 4, n: 4, k: 1 })
+                    .build()
+                    .unwrap();
+                let plane_dim = client.properties().hardware.plane_size_max;
+                let selection = MatmulSelection::builder(tiling_scheme, plane_dim).build();
+                let problem = MatmulProblem {
+                    m: 40,
+                    n: 36,
+                    k: 52,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: MatrixLayout::RowMajor,
+                    rhs_layout: MatrixLayout::ColMajor,
+                };
+
+                test_bf16_storage_matmul::<SimpleUnitAlgorithm, TestRuntime>(
+                    client, problem, selection,
+                );
+            }
+        }
+
+        // f16 activations by i8 weights dequantized by the load scale, accumulated in f32
+        #[cfg(all(feature = "matmul_tests_simple", feature = "matmul_tests_f16"))]
+        mod simple_int8_weights {
+            use super::*;
+            use $crate::components::{
+                MatmulProblem, MatmulSelection, MatrixLayout, PartitionSize, StageSize, TileSize,
+                TilingScheme,
+            };
+            use $crate::tests::layered::int8_weights::test_int8_weights_matmul;
+
+            #[test]
+            pub fn test() {
+                let client = TestRuntime::client(&Default::default());
+                let tiling_scheme = TilingScheme::builder()
+                    .with_tile_size(TileSize { m: 4, n: 4, k: 4 })
+                    .with_partition_size(PartitionSize { m: 1, n: 1, k: 4 })
+                    .with_stage_size(StageSize { m: 4, n: 4, k: 1 })
+                    .build()
+                    .unwrap();
+                let plane_dim = client.properties().hardware.plane_size_max;
+                let selection = MatmulSelection::builder(tiling_scheme, plane_dim).build();
+                let problem = MatmulProblem {
+                    m: 40,
+                    n: 36,
+                    k: 52,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: MatrixLayout::RowMajor,
+                    rhs_layout: MatrixLayout::ColMajor,
+                };
+
+                test_int8_weights_matmul::<SimpleUnitAlgorithm, TestRuntime>(
+                    client, problem, selection,
+                );
+            }
+        }
+
+        // Interleaved complex operands, with the products of the register tile matmul
+        #[cfg(feature = "matmul_tests_simple")]
+        mod simple_complex {
+            use super::*;
+            use $crate::components::tile::register::ComplexProduct;
+            use $crate::components::{
+                MatmulProblem, MatmulSelection, MatrixLayout, PartitionSize, StageSize, TileSize,
+                TilingScheme,
+            };
+            use $crate::tests::layered::complex::test_complex_matmul;
+
+            fn launch(product: ComplexProduct) {
+                let client = TestRuntime::client(&Default::default());
+                let tiling_scheme = TilingScheme::builder()
+                    .with_tile_size(TileSize { m: 4, n: 4, k: 4 })
+                    .with_partition_size(PartitionSize { m: 1, n: 1, k: 4 })
+                    .with_stage_size(StageSize { m: 4, n: 4, k: 1 })
+                    .build()
+                    .unwrap();
+                let plane_dim = client.properties().hardware.plane_size_max;
+                let selection = MatmulSelection::builder(tiling_scheme, plane_dim)
+                    .complex(product)
+                    .build();
+                let problem = MatmulProblem {
+                    m: 40,
+                    n: 36,
+                    k: 52,
+                    lhs_batches: vec![2],
+                    rhs_batches: vec![2],
+                    lhs_layout: MatrixLayout::RowMajor,
+                    rhs_layout: MatrixLayout::RowMajor,
+                };
+
+                test_complex_matmul::<SimpleUnitAlgorithm, TestRuntime>(client, problem, selection);
+            }
+
+            #[test]
+            pub fn standard() {
+                launch(ComplexProduct::Standard);
+            }
+
+            #[test]
+            pub fn karatsuba() {
+                launch(ComplexProduct::Karatsuba);
+            }
+        }
+
         #[cfg(feature = "matmul_tests_double")]
         mod double_buffering {
             use super::*;
@@ -4,10 +4,12 @@ use cubecl_core::{
 };
 use cubecl_core::{prelude::*, server::AllocationDescriptor};
 
-use crate::components::MatrixLayout;
 use crate::components::batch::{BatchConfig, BatchMatmulFamily};
 use crate::components::global::args::TensorInputsLaunch;
+use crate::components::global::memory::OutputLayout;
+use crate::components::global::read::ElementwiseTransform;
 use crate::components::{AvailableLineSizes, MatmulIdent};
+use crate::components::{MatmulLineSizes, MatmulPrecision, MatrixLayout};
 use crate::components::{MatmulProblem, MatmulSelection};
 use crate::kernels::layered::Algorithm;
 use crate::tests::test_utils::Sample;
@@ -33,48 +35,228 @@ pub fn test_matmul_algorithm<A, P, R>(
     P: TestPrecision,
     R: Runtime,
 {
-    let env = std::env::var("MATMUL_TEST_MODE");
+    launch_matmul_test::<A, P, R>(
+        client,
+        problem,
+        selection,
+        TestAccumulator::None,
+        TestOutput::Contiguous,
+    )
+}
 
-    let panic_on_launch_err = match env {
-        Ok(val) => match val.as_str() {
-            "panic" => true,
-            "skip" => false,
-            _ => false,
-        },
-        Err(_) => false,
-    };
+/// Same as [test_matmul_algorithm], but the matmul also adds a random accumulator tensor
+/// to the product
+pub fn test_matmul_algorithm_accumulate<A, P, R>(
+    client: ComputeClient<R::Server, R::Channel>,
+    problem: MatmulProblem,
+    selection: MatmulSelection,
+) where
+    A: Algorithm,
+    P: TestPrecision,
+    R: Runtime,
+{
+    launch_matmul_test::<A, P, R>(
+        client,
+        problem,
+        selection,
+        TestAccumulator::Random,
+        TestOutput::Contiguous,
+    )
+}
+
+/// Same as [test_matmul_algorithm], but the matmul also adds a per-row bias to the product,
+/// given as an accumulator tensor broadcast along `n`
+pub fn test_matmul_algorithm_row_bias<A, P, R>(
+    client: ComputeClient<R::Server, R::Channel>,
+    problem: MatmulProblem,
+    selection: MatmulSelection,
+) where
+    A: Algorithm,
+    P: TestPrecision,
+    R: Runtime,
+{
+    launch_matmul_test::<A, P, R>(
+        client,
+        problem,
+        selection,
+        TestAccumulator::RowBias,
+        TestOutput::Contiguous,
+    )
+}
+
+/// Same as [test_matmul_algorithm], but the batches of the output are interleaved row by row
+/// in a single buffer, so the batch stride of the output is smaller than its row stride
+pub fn test_matmul_algorithm_interleaved_out<A, P, R>(
+    client: ComputeClient<R::Server, R::Channel>,
+    problem: MatmulProblem,
+    selection: MatmulSelection,
+) where
+    A: Algorithm,
+    P: TestPrecision,
+    R: Runtime,
+{
+    launch_matmul_test::<A, P, R>(
+        client,
+        problem,
+        selection,
+        TestAccumulator::None,
+        TestOutput::Interleaved,
+    )
+}
+
+/// The accumulator tensor the matmul starts from
+enum TestAccumulator {
+    None,
+    Random,
+    RowBias,
+}
+
+/// The layout of the output tensor the matmul writes to
+enum TestOutput {
+    Contiguous,
+    Interleaved,
+}
+
+fn launch_matmul_test<A, P, R>(
+    client: ComputeClient<R::Server, R::Channel>,
+    problem: MatmulProblem,
+    selection: MatmulSelection,
+    accumulator: TestAccumulator,
+    output: TestOutput,
+) where
+    A: Algorithm,
+    P: TestPrecision,
+    R: Runtime,
+{
     let lhs = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Lhs);
     let rhs = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Rhs);
-    let out = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Out);
+    let acc = match accumulator {
+        TestAccumulator::None => None,
+        TestAccumulator::Random => Some(acc_raw_parts::<P, R>(&client, &problem)),
+        TestAccumulator::RowBias => Some(row_bias_raw_parts::<P, R>(&client, &problem)),
+    };
+    let out = match (selection.output_layout, &output) {
+        (OutputLayout::Blocked, _) => contiguous_out_raw_parts::<P, R>(&client, &problem),
+        (OutputLayout::ColumnMajor, _) => column_major_out_raw_parts::<P, R>(&client, &problem),
+        (OutputLayout::Strided, TestOutput::Contiguous) => {
+            tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Out)
+        }
+        (OutputLayout::Strided, TestOutput::Interleaved) => {
+            interleaved_out_raw_parts::<P, R>(&client, &problem)
+        }
+    };
 
     let line_sizes = AvailableLineSizes::from_types::<R>(
         &P::EG::as_type_native_unchecked(),
         &P::EG::as_type_native_unchecked(),
         &P::EG::as_type_native_unchecked(),
-    );
-    let line_sizes = A::filter_line_sizes(line_sizes);
-    let line_sizes = line_sizes
-        .filter_lhs_with_tensor(&lhs.strides, &lhs.shape, problem.lhs_layout)
-        .filter_rhs_with_tensor(&rhs.strides, &rhs.shape, problem.rhs_layout)
-        .filter_out_with_tensor(&out.strides, &out.shape)
-        .pick_max()
-        .unwrap();
-
-    let config = match A::setup::<(P::EG, P::EG, P::EG, P::ES, P::ES, P::EA), R>(
+    )
+    .filter_lhs_with_tensor(&lhs.strides, &lhs.shape, problem.lhs_layout)
+    .filter_rhs_with_tensor(&rhs.strides, &rhs.shape, problem.rhs_layout)
+    .filter_out_with_tensor(&out.strides, &out.shape);
+    let line_sizes = match &acc {
+        Some(acc) => line_sizes.filter_out_with_tensor(&acc.strides, &acc.shape),
+        None => line_sizes,
+    };
+
+    let (config, line_sizes) =
+        match setup_matmul_test::<A, (P::EG, P::EG, P::EG, P::ES, P::ES, P::EA), R>(
+            &client, &problem, &selection, line_sizes,
+        ) {
+            Ok(setup) => setup,
+            Err(msg) => {
+                println!("{msg}");
+                return;
+            }
+        };
+    launch_matmul::<A, P::MP, R, _, _, _>(
         &client,
         &problem,
-        &selection,
+        config,
         &line_sizes,
-    ) {
+        &lhs,
+        &rhs,
+        acc.as_ref(),
+        &out,
+    );
+
+    let (out_handle, out_strides) = match (selection.output_layout, output) {
+        (OutputLayout::Blocked, _) => (
+            unblock_out::<P, R>(&client, out.handle, &problem, &selection),
+            out.strides,
+        ),
+        (OutputLayout::ColumnMajor, _) => (
+            deinterleave_out::<P, R>(&client, out.handle, &out.shape, &out.strides),
+            strides(&problem, MatmulIdent::Out),
+        ),
+        (OutputLayout::Strided, TestOutput::Contiguous) => (out.handle, out.strides),
+        (OutputLayout::Strided, TestOutput::Interleaved) => (
+            deinterleave_out::<P, R>(&client, out.handle, &out.shape, &out.strides),
+            strides(&problem, MatmulIdent::Out),
+        ),
+    };
+
+    // The reference transforms and scales the inputs before the matmul, instead of on load
+    let lhs_data = scale_on_load(
+        transform_on_load(
+            lhs.original_data.unwrap(),
+            selection.load_transform.of(MatmulIdent::Lhs),
+        ),
+        selection.load_scale.factor(MatmulIdent::Lhs),
+    );
+    let rhs_data = scale_on_load(
+        transform_on_load(
+            rhs.original_data.unwrap(),
+            selection.load_transform.of(MatmulIdent::Rhs),
+        ),
+        selection.load_scale.factor(MatmulIdent::Rhs),
+    );
+
+    P::assert_result::<R>(
+        &lhs_data,
+        &rhs_data,
+        acc.and_then(|it| it.original_data).as_deref(),
+        &problem,
+        &client,
+        out_handle,
+        &out.shape,
+        &out_strides,
+    );
+}
+
+/// The config of the algorithm `A` in the precision `MP` for the `problem`, with the largest
+/// of the `line_sizes` supported by `A`.
+///
+/// Returns the reason to skip the test if the algorithm can't run on this device. A config that
+/// can't be set up panics instead when `MATMUL_TEST_MODE` is `panic`.
+pub(crate) fn setup_matmul_test<A, MP, R>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    problem: &MatmulProblem,
+    selection: &MatmulSelection,
+    line_sizes: AvailableLineSizes,
+) -> Result<
+    (
+        <A::BatchMatmul as BatchMatmulFamily>::Config,
+        MatmulLineSizes,
+    ),
+    String,
+>
+where
+    A: Algorithm,
+    MP: MatmulPrecision,
+    R: Runtime,
+{
+    let panic_on_launch_err = matches!(std::env::var("MATMUL_TEST_MODE").as_deref(), Ok("panic"));
+
+    let line_sizes = A::filter_line_sizes(line_sizes).pick_max().unwrap();
+    let config = match A::setup::<MP, R>(client, problem, selection, &line_sizes) {
         Ok(config) => config,
         Err(err) => {
             let msg = format!("Can't launch the test: {err}");
             if panic_on_launch_err {
                 panic!("{msg}");
-            } else {
-                println!("{msg}");
-                return;
             }
+            return Err(msg);
         }
     };
 
@@ -82,22 +264,43 @@ pub fn test_matmul_algorithm<A, P, R>(
     if !props.max_cube_dim.can_contain(config.cube_dim())
         || config.cube_dim().num_elems() > props.max_units_per_cube
     {
-        println!("Skipping test, too many resources requested");
-        return;
+        return Err("Skipping test, too many resources requested".to_string());
     }
 
-    let cube_count_plan = config.hypercube_config().cube_count_plan(
-        &problem,
-        client.properties().hardware.max_cube_count.clone(),
-    );
+    Ok((config, line_sizes))
+}
+
+/// Launches the matmul of `lhs` and `rhs` into `out` with the algorithm `A` in the precision
+/// `MP`, starting from `acc` if provided, with a config and line sizes of [setup_matmul_test].
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn launch_matmul<A, MP, R, L, Rh, O>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    problem: &MatmulProblem,
+    config: <A::BatchMatmul as BatchMatmulFamily>::Config,
+    line_sizes: &MatmulLineSizes,
+    lhs: &TensorRawParts<L>,
+    rhs: &TensorRawParts<Rh>,
+    acc: Option<&TensorRawParts<O>>,
+    out: &TensorRawParts<O>,
+) where
+    A: Algorithm,
+    MP: MatmulPrecision,
+    R: Runtime,
+    L: Numeric + CubeElement,
+    Rh: Numeric + CubeElement,
+    O: Numeric + CubeElement,
+{
+    let cube_count_plan = config
+        .hypercube_config()
+        .cube_count_plan(problem, client.properties().hardware.max_cube_count.clone());
 
     unsafe {
-        A::BatchMatmul::launch_unchecked::<P::MP, R>(
-            &client,
+        A::BatchMatmul::launch_unchecked::<MP, R>(
+            client,
             config.cube_dim(),
             cube_count_plan.resolve(),
             TensorInputsLaunch::new(
-                TensorArg::<R>::from_raw_parts::<P::EG>(
+                TensorArg::<R>::from_raw_parts::<L>(
                     &lhs.handle,
                     &lhs.strides,
                     &lhs.shape,
@@ -105,9 +308,9 @@ pub fn test_matmul_algorithm<A, P, R>(
                 ),
                 lhs.scale
                     .as_ref()
-                    .map(|it| TensorArg::<R>::from_raw_parts::<P::EG>(it, &[1], &[1], 1))
+                    .map(|it| TensorArg::<R>::from_raw_parts::<L>(it, &[1], &[1], 1))
                     .into(),
-                TensorArg::<R>::from_raw_parts::<P::EG>(
+                TensorArg::<R>::from_raw_parts::<Rh>(
                     &rhs.handle,
                     &rhs.strides,
                     &rhs.shape,
@@ -115,11 +318,19 @@ pub fn test_matmul_algorithm<A, P, R>(
                 ),
                 rhs.scale
                     .as_ref()
-                    .map(|it| TensorArg::<R>::from_raw_parts::<P::EG>(it, &[1], &[1], 1))
+                    .map(|it| TensorArg::<R>::from_raw_parts::<Rh>(it, &[1], &[1], 1))
                     .into(),
-                None.into(),
+                acc.map(|it| {
+                    TensorArg::<R>::from_raw_parts::<O>(
+                        &it.handle,
+                        &it.strides,
+                        &it.shape,
+                        line_sizes.out,
+                    )
+                })
+                .into(),
             ),
-            TensorArg::<R>::from_raw_parts::<P::EG>(
+            TensorArg::<R>::from_raw_parts::<O>(
                 &out.handle,
                 &out.strides,
                 &out.shape,
@@ -129,19 +340,9 @@ pub fn test_matmul_algorithm<A, P, R>(
             config,
         );
     }
-
-    P::assert_result::<R>(
-        &lhs.original_data.unwrap(),
-        &rhs.original_data.unwrap(),
-        &problem,
-        &client,
-        out.handle,
-        &out.shape,
-        &out.strides,
-    );
 }
 
-fn tensor_raw_parts<P: TestPrecision, R: Runtime>(
+pub(crate) fn tensor_raw_parts<P: TestPrecision, R: Runtime>(
     client: &ComputeClient<R::Server, R::Channel>,
     problem: &MatmulProblem,
     ident: MatmulIdent,
@@ -192,24 +393,13 @@ fn tensor_raw_parts<P: TestPrecision, R: Runtime>(
                 original_data: Some(original_data),
             }
         }
-        MatmulIdent::Rhs => {
-            let mut tensor_shape = problem.shape(MatmulIdent::Rhs);
-
-            let handle = P::EG::sample::<R>(client, &tensor_shape, 5678);
-
-            let data = client.read_one_tensor(handle.as_copy_descriptor());
-            let data = P::EG::from_bytes(&data);
-            let original_data = data.to_owned();
+        MatmulIdent::Rhs => rhs_raw_parts::<P, R>(client, problem, 5678),
+        MatmulIdent::Out => {
+            let zero = P::EG::from_int(0);
 
-            let rank = tensor_shape.len();
+            let data = vec![zero; tensor_size(problem, MatmulIdent::Out)];
 
-            let data = match problem.rhs_layout {
-                MatrixLayout::RowMajor => original_data.clone(),
-                MatrixLayout::ColMajor => {
-                    tensor_shape.swap(rank - 1, rank - 2);
-                    transpose::<P::EG>(&original_data, problem.num_batches(), problem.k, problem.n)
-                }
-            };
+            let tensor_shape = problem.shape(MatmulIdent::Out);
 
             let descriptors = vec![(
                 AllocationDescriptor::optimized(tensor_shape.as_slice(), size_of::<P::EG>()),
@@ -217,52 +407,336 @@ fn tensor_raw_parts<P: TestPrecision, R: Runtime>(
             )];
 
             let mut tensors = client.create_tensors(descriptors);
-            let Allocation {
-                handle,
-                mut strides,
-            } = tensors.remove(0);
+            let Allocation { handle, strides } = tensors.remove(0);
             let _offs = tensors.pop();
             let scale = tensors.pop().map(|it| it.handle);
 
-            if matches!(problem.rhs_layout, MatrixLayout::ColMajor) {
-                tensor_shape.swap(rank - 1, rank - 2);
-                strides.swap(rank - 1, rank - 2);
-            }
-
             TensorRawParts {
                 handle,
                 scale,
                 shape: tensor_shape,
                 strides,
-                original_data: Some(original_data),
+                original_data: None,
             }
         }
-        MatmulIdent::Out => {
-            let zero = P::EG::from_int(0);
+    }
+}
 
-            let data = vec![zero; tensor_size(problem, MatmulIdent::Out)];
+/// Random Rhs sampled from `seed`, in the layout of the `problem`
+pub(crate) fn rhs_raw_parts<P: TestPrecision, R: Runtime>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    problem: &MatmulProblem,
+    seed: u64,
+) -> TensorRawParts<P::EG> {
+    let mut tensor_shape = problem.shape(MatmulIdent::Rhs);
 
-            let tensor_shape = problem.shape(MatmulIdent::Out);
+    let handle = P::EG::sample::<R>(client, &tensor_shape, seed);
 
-            let descriptors = vec![(
-                AllocationDescriptor::optimized(tensor_shape.as_slice(), size_of::<P::EG>()),
-                P::EG::as_bytes(&data),
-            )];
+    let data = client.read_one_tensor(handle.as_copy_descriptor());
+    let data = P::EG::from_bytes(&data);
+    let original_data = data.to_owned();
 
-            let mut tensors = client.create_tensors(descriptors);
-            let Allocation { handle, strides } = tensors.remove(0);
-            let _offs = tensors.pop();
-            let scale = tensors.pop().map(|it| it.handle);
+    let rank = tensor_shape.len();
 
-            TensorRawParts {
-                handle,
-                scale,
-                shape: tensor_shape,
-                strides,
-                original_data: None,
+    let data = match problem.rhs_layout {
+        MatrixLayout::RowMajor => original_data.clone(),
+        MatrixLayout::ColMajor => {
+            tensor_shape.swap(rank - 1, rank - 2);
+            transpose::<P::EG>(&original_data, problem.num_batches(), problem.k, problem.n)
+        }
+    };
+
+    let descriptors = vec![(
+        AllocationDescriptor::optimized(tensor_shape.as_slice(), size_of::<P::EG>()),
+        P::EG::as_bytes(&data),
+    )];
+
+    let mut tensors = client.create_tensors(descriptors);
+    let Allocation {
+        handle,
+        mut strides,
+    } = tensors.remove(0);
+    let _offs = tensors.pop();
+    let scale = tensors.pop().map(|it| it.handle);
+
+    if matches!(problem.rhs_layout, MatrixLayout::ColMajor) {
+        tensor_shape.swap(rank - 1, rank - 2);
+        strides.swap(rank - 1, rank - 2);
+    }
+
+    TensorRawParts {
+        handle,
+        scale,
+        shape: tensor_shape,
+        strides,
+        original_data: Some(original_data),
+    }
+}
+
+/// Random accumulator with the shape of the output
+fn acc_raw_parts<P: TestPrecision, R: Runtime>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    problem: &MatmulProblem,
+) -> TensorRawParts<P::EG> {
+    let tensor_shape = problem.shape(MatmulIdent::Out);
+
+    let handle = P::EG::sample::<R>(client, &tensor_shape, 9012);
+
+    let data = client.read_one_tensor(handle.as_copy_descriptor());
+    let data = P::EG::from_bytes(&data);
+    let original_data = data.to_owned();
+
+    let descriptors = vec![(
+        AllocationDescriptor::optimized(tensor_shape.as_slice(), size_of::<P::EG>()),
+        P::EG::as_bytes(&original_data),
+    )];
+
+    let mut tensors = client.create_tensors(descriptors);
+    let Allocation { handle, strides } = tensors.remove(0);
+
+    TensorRawParts {
+        handle,
+        scale: None,
+        shape: tensor_shape,
+        strides,
+        original_data: Some(original_data),
+    }
+}
+
+/// Bias with a distinct value for each row of each batch, broadcast along `n` with a stride of `0`.
+/// The original data is expanded to the shape of the output, as for a regular accumulator.
+fn row_bias_raw_parts<P: TestPrecision, R: Runtime>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    problem: &MatmulProblem,
+) -> TensorRawParts<P::EG> {
+    let tensor_shape = problem.shape(MatmulIdent::Out);
+    let rank = tensor_shape.len();
+    let num_rows = problem.num_batches() * problem.m;
+
+    let bias = (0..num_rows)
+        .map(|row| P::EG::from_int((row % 16) as i64 - 8))
+        .collect::<Vec<_>>();
+    let original_data = bias
+        .iter()
+        .flat_map(|value| std::iter::repeat_n(*value, problem.n))
+        .collect();
+
+    let mut strides = vec![0; rank];
+    let mut stride = 1;
+    for axis in (0..rank - 1).rev() {
+        strides[axis] = stride;
+        stride *= tensor_shape[axis];
+    }
+
+    TensorRawParts {
+        handle: client.create(P::EG::as_bytes(&bias)),
+        scale: None,
+        shape: tensor_shape,
+        strides,
+        original_data: Some(original_data),
+    }
+}
+
+/// Zero-initialized output without padding, as needed by [OutputLayout::Blocked]
+pub(crate) fn contiguous_out_raw_parts<P: TestPrecision, R: Runtime>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    problem: &MatmulProblem,
+) -> TensorRawParts<P::EG> {
+    let data = vec![P::EG::from_int(0); tensor_size(problem, MatmulIdent::Out)];
+
+    TensorRawParts {
+        handle: client.create(P::EG::as_bytes(&data)),
+        scale: None,
+        shape: problem.shape(MatmulIdent::Out),
+        strides: strides(problem, MatmulIdent::Out),
+        original_data: None,
+    }
+}
+
+/// Zero-initialized contiguous output viewed with the strides of [OutputLayout::ColumnMajor],
+/// which also restricts the output line size to `1`
+pub(crate) fn column_major_out_raw_parts<P: TestPrecision, R: Runtime>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    problem: &MatmulProblem,
+) -> TensorRawParts<P::EG> {
+    let shape = problem.shape(MatmulIdent::Out);
+    let data = vec![P::EG::from_int(0); tensor_size(problem, MatmulIdent::Out)];
+
+    TensorRawParts {
+        handle: client.create(P::EG::as_bytes(&data)),
+        scale: None,
+        strides: OutputLayout::column_major_strides(&shape),
+        shape,
+        original_data: None,
+    }
+}
+
+/// Zero-initialized output whose batches are interleaved row by row: the rows of all batches
+/// with the same index are contiguous, so the innermost batch axis has a stride of `n`
+fn interleaved_out_raw_parts<P: TestPrecision, R: Runtime>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    problem: &MatmulProblem,
+) -> TensorRawParts<P::EG> {
+    let shape = problem.shape(MatmulIdent::Out);
+    let rank = shape.len();
+    let data = vec![P::EG::from_int(0); tensor_size(problem, MatmulIdent::Out)];
+
+    let mut strides = vec![1; rank];
+    let mut stride = problem.n;
+    for axis in (0..rank - 2).rev() {
+        strides[axis] = stride;
+        stride *= shape[axis];
+    }
+    strides[rank - 2] = stride;
+
+    TensorRawParts {
+        handle: client.create(P::EG::as_bytes(&data)),
+        scale: None,
+        shape,
+        strides,
+        original_data: None,
+    }
+}
+
+/// Gathers a strided output into a new row-major handle, so it can be compared to the reference
+pub(crate) fn deinterleave_out<P: TestPrecision, R: Runtime>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    out: server::Handle,
+    shape: &[usize],
+    out_strides: &[usize],
+) -> server::Handle {
+    let data = client.read_one(out);
+    let data = P::EG::from_bytes(&data);
+
+    let num_elems = shape.iter().product::<usize>();
+    let data = (0..num_elems)
+        .map(|index| {
+            let mut remainder = index;
+            let mut offset = 0;
+            for axis in (0..shape.len()).rev() {
+                offset += (remainder % shape[axis]) * out_strides[axis];
+                remainder /= shape[axis];
+            }
+            data[offset]
+        })
+        .collect::<Vec<_>>();
+
+    client.create(P::EG::as_bytes(&data))
+}
+
+/// Reorders a blocked output into a new row-major handle, so it can be compared to the reference
+pub(crate) fn unblock_out<P: TestPrecision, R: Runtime>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    out: server::Handle,
+    problem: &MatmulProblem,
+    selection: &MatmulSelection,
+) -> server::Handle {
+    let data = client.read_one(out);
+    let data = P::EG::from_bytes(&data);
+
+    let tile_size = selection.tiling_scheme.tile_size;
+    let data = unblock::<P::EG>(
+        data,
+        problem.num_batches(),
+        problem.m,
+        problem.n,
+        tile_size.m() as usize,
+        tile_size.n() as usize,
+    );
+
+    client.create(P::EG::as_bytes(&data))
+}
+
+pub(crate) fn unblock<E: Copy>(
+    array: &[E],
+    batches: usize,
+    rows: usize,
+    cols: usize,
+    tile_rows: usize,
+    tile_cols: usize,
+) -> Vec<E> {
+    let tiles_per_row = cols / tile_cols;
+    let mut result = vec![array[0]; array.len()];
+    for b in 0..batches {
+        for i in 0..rows {
+            for j in 0..cols {
+                let tile = (i / tile_rows) * tiles_per_row + j / tile_cols;
+                let within_tile = (i % tile_rows) * tile_cols + j % tile_cols;
+                result[(b * rows * cols) + i * cols + j] =
+                    array[(b * rows * cols) + tile * tile_rows * tile_cols + within_tile];
             }
         }
     }
+    result
+}
+
+/// Applies the elementwise transform the matmul applies as it loads the input, if any
+pub(crate) fn transform_on_load<E: Numeric>(
+    data: Vec<E>,
+    transform: Option<ElementwiseTransform>,
+) -> Vec<E> {
+    match transform {
+        Some(transform) => data
+            .into_iter()
+            .map(|x| E::from(transform.apply(x.to_f32().unwrap())).unwrap())
+            .collect(),
+        None => data,
+    }
+}
+
+/// Multiplies the data by the factor the matmul applies as it loads the input, if any
+pub(crate) fn scale_on_load<E: Numeric>(data: Vec<E>, factor: Option<f32>) -> Vec<E> {
+    match factor {
+        Some(factor) => data
+            .into_iter()
+            .map(|x| E::from(x.to_f32().unwrap() * factor).unwrap())
+            .collect(),
+        None => data,
+    }
+}
+
+/// The tensor of the row-major `data`, in the same layout as [tensor_raw_parts]
+pub(crate) fn host_raw_parts<E: Numeric + CubeElement, R: Runtime>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    original_data: Vec<E>,
+    mut tensor_shape: Vec<usize>,
+    layout: MatrixLayout,
+) -> TensorRawParts<E> {
+    let rank = tensor_shape.len();
+    let (rows, cols) = (tensor_shape[rank - 2], tensor_shape[rank - 1]);
+    let batches = original_data.len() / (rows * cols);
+
+    let data = match layout {
+        MatrixLayout::RowMajor => original_data.clone(),
+        MatrixLayout::ColMajor => {
+            tensor_shape.swap(rank - 1, rank - 2);
+            transpose::<E>(&original_data, batches, rows, cols)
+        }
+    };
+
+    let descriptors = vec![(
+        AllocationDescriptor::optimized(tensor_shape.as_slice(), size_of::<E>()),
+        E::as_bytes(&data),
+    )];
+
+    let mut tensors = client.create_tensors(descriptors);
+    let Allocation {
+        handle,
+        mut strides,
+    } = tensors.remove(0);
+
+    if matches!(layout, MatrixLayout::ColMajor) {
+        tensor_shape.swap(rank - 1, rank - 2);
+        strides.swap(rank - 1, rank - 2);
+    }
+
+    TensorRawParts {
+        handle,
+        scale: None,
+        shape: tensor_shape,
+        strides,
+        original_data: Some(original_data),
+    }
 }
 
 pub(crate) fn transpose<E: Copy>(array: &[E], batches: usize, rows: usize, cols: usize) -> Vec<E> {
@@ -286,6 +760,15 @@ pub(crate) fn tensor_size(problem: &MatmulProblem, ident: MatmulIdent) -> usize
     }
 }
 
+/// Returns the row-major strides of a contiguous tensor of the given shape
+pub(crate) fn contiguous_strides(shape: &[usize]) -> Vec<usize> {
+    let mut strides = vec![1; shape.len()];
+    for axis in (0..shape.len() - 1).rev() {
+        strides[axis] = strides[axis + 1] * shape[axis + 1];
+    }
+    strides
+}
+
 /// Returns the stride of the identified tensor, inferred by the problem definition
 pub(crate) fn strides(problem: &MatmulProblem, ident: MatmulIdent) -> Vec<usize> {
     let shape = problem.shape(ident);
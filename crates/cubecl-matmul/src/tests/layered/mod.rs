@@ -1,3 +1,17 @@
+pub mod bf16_storage;
+pub mod chunked_k;
+pub mod complex;
+pub mod int8_weights;
+pub mod interleaved_flush;
 mod macros;
 pub mod matmul_test_launcher;
+pub mod multi_rhs;
+pub mod ordered_tiles;
+pub mod plane_dim;
+pub mod reduce_pipeline;
+pub mod register_lhs;
+pub mod row_sum;
+pub mod selection_tuner;
+pub mod stage_limits;
+pub mod syrk;
 pub mod tma_test_launcher;
@@ -0,0 +1,145 @@
+use cubecl_core::prelude::*;
+
+use crate::components::batch::BatchConfig;
+use crate::components::global::read::{
+    ZeroGlobalReaderFamily, sync_full_cyclic::SyncFullCyclicLoading,
+};
+use crate::components::global::single_stage::simple::{multi_rhs_matmul, validate_multi_rhs};
+use crate::components::global::{GlobalConfig, UnitWriterFamily};
+use crate::components::stage::{ColMajorTilingOrder, RowMajorTilingOrder};
+use crate::components::{AvailableLineSizes, MatmulIdent, MatmulProblem, MatmulSelection};
+use crate::kernels::layered::Algorithm;
+use crate::kernels::layered::simple_unit::SimpleUnitAlgorithm;
+use crate::tests::layered::matmul_test_launcher::{
+    rhs_raw_parts, setup_matmul_test, tensor_raw_parts,
+};
+use crate::tests::test_utils::TestPrecision;
+
+/// Number of Rhs multiplied with the same Lhs
+const NUM_RHS: u64 = 3;
+
+/// Test the matmul of one Lhs with several Rhs, using the stages of [SimpleUnitAlgorithm],
+/// against a separate naive CPU matmul for each Rhs
+pub fn test_multi_rhs_matmul<P, R>(
+    client: ComputeClient<R::Server, R::Channel>,
+    problem: MatmulProblem,
+    selection: MatmulSelection,
+) where
+    P: TestPrecision,
+    R: Runtime,
+{
+    let lhs = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Lhs);
+    let rhs = (0..NUM_RHS)
+        .map(|i| rhs_raw_parts::<P, R>(&client, &problem, 5678 + i))
+        .collect::<Vec<_>>();
+    let out = (0..NUM_RHS)
+        .map(|_| tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Out))
+        .collect::<Vec<_>>();
+
+    let line_sizes = AvailableLineSizes::from_types::<R>(
+        &P::EG::as_type_native_unchecked(),
+        &P::EG::as_type_native_unchecked(),
+        &P::EG::as_type_native_unchecked(),
+    )
+    .filter_lhs_with_tensor(&lhs.strides, &lhs.shape, problem.lhs_layout)
+    .filter_rhs_with_tensor(&rhs[0].strides, &rhs[0].shape, problem.rhs_layout)
+    .filter_out_with_tensor(&out[0].strides, &out[0].shape);
+
+    let (config, line_sizes) = match setup_matmul_test::<
+        SimpleUnitAlgorithm,
+        (P::EG, P::EG, P::EG, P::ES, P::ES, P::EA),
+        R,
+    >(&client, &problem, &selection, line_sizes)
+    {
+        Ok(setup) => setup,
+        Err(msg) => {
+            println!("{msg}");
+            return;
+        }
+    };
+
+    let global_config = config.global_config();
+    // Each Rhs has its own stages, which the config of a single Rhs doesn't account for
+    if let Err(err) = validate_multi_rhs::<(P::EG, P::EG, P::EG, P::ES, P::ES, P::EA), R, _>(
+        &client,
+        global_config,
+        NUM_RHS as u32,
+    ) {
+        println!("Skipping test, invalid multi Rhs config: {err:?}");
+        return;
+    }
+
+    let tiling_scheme = global_config.tiling_scheme();
+    let cube_count = CubeCount::Static(
+        problem
+            .m
+            .div_ceil(tiling_scheme.elements_in_stage_m() as usize) as u32,
+        problem
+            .n
+            .div_ceil(tiling_scheme.elements_in_stage_n() as usize) as u32,
+        problem.num_batches() as u32,
+    );
+
+    let mut rhs_args = SequenceArg::new();
+    for rhs in rhs.iter() {
+        rhs_args.push(TensorArg::<R>::from_raw_parts::<P::EG>(
+            &rhs.handle,
+            &rhs.strides,
+            &rhs.shape,
+            line_sizes.rhs,
+        ));
+    }
+    let mut out_args = SequenceArg::new();
+    for out in out.iter() {
+        out_args.push(TensorArg::<R>::from_raw_parts::<P::EG>(
+            &out.handle,
+            &out.strides,
+            &out.shape,
+            line_sizes.out,
+        ));
+    }
+
+    unsafe {
+        multi_rhs_matmul::launch_unchecked::<
+            P::EG,
+            P::EG,
+            P::EG,
+            P::ES,
+            P::ES,
+            P::EA,
+            <SimpleUnitAlgorithm as Algorithm>::StageMatmul,
+            SyncFullCyclicLoading<ColMajorTilingOrder>,
+            SyncFullCyclicLoading<RowMajorTilingOrder>,
+            UnitWriterFamily,
+            ZeroGlobalReaderFamily,
+            R,
+        >(
+            &client,
+            cube_count,
+            config.cube_dim(),
+            TensorArg::<R>::from_raw_parts::<P::EG>(
+                &lhs.handle,
+                &lhs.strides,
+                &lhs.shape,
+                line_sizes.lhs,
+            ),
+            rhs_args,
+            out_args,
+            global_config,
+        );
+    }
+
+    let lhs_data = lhs.original_data.unwrap();
+    for (rhs, out) in rhs.into_iter().zip(out) {
+        P::assert_result::<R>(
+            &lhs_data,
+            &rhs.original_data.unwrap(),
+            None,
+            &problem,
+            &client,
+            out.handle,
+            &out.shape,
+            &out.strides,
+        );
+    }
+}
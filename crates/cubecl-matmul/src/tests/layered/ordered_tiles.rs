@@ -0,0 +1,68 @@
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+
+use crate::components::stage::{PartitionScheduler, PartitionSchedulerScheme, TileIteration};
+use crate::components::{PartitionSize, TileSize};
+
+/// Write whether each tile of the partition along m, then along n, is in the bounds of a stage
+/// restricted to `rows` and `cols` with the ordered tile iteration.
+#[cube(launch)]
+fn ordered_tiles_kernel(
+    rows: u32,
+    cols: u32,
+    in_bounds: &mut Array<u32>,
+    #[comptime] partition_size: PartitionSize,
+    #[comptime] tile_size: TileSize,
+) {
+    let mut scheduler = PartitionScheduler::new(
+        0u32,
+        0u32,
+        partition_size,
+        comptime!(PartitionSchedulerScheme::Naive),
+        comptime!(TileIteration::Ordered),
+    );
+    scheduler.restrict_to_bounds((rows, cols), tile_size);
+
+    let tiles_m = comptime!(partition_size.m());
+    #[unroll]
+    for m in 0..tiles_m {
+        in_bounds[m] = u32::cast_from(scheduler.is_m_in_bounds(scheduler.map_m(m)));
+    }
+    #[unroll]
+    for n in 0..comptime!(partition_size.n()) {
+        in_bounds[tiles_m + n] = u32::cast_from(scheduler.is_n_in_bounds(scheduler.map_n(n)));
+    }
+}
+
+/// Check that the ordered tile iteration keeps exactly the tiles overlapping the real rows and
+/// columns of the stage, and skips those made only of padding, including when no row or column
+/// of the stage is real.
+pub fn test_ordered_tiles<R: Runtime>(client: ComputeClient<R::Server, R::Channel>) {
+    let partition_size = PartitionSize { m: 4, n: 4, k: 1 };
+    let tile_size = TileSize { m: 4, n: 4, k: 4 };
+
+    for (rows, cols, expected) in [
+        (16, 16, [1, 1, 1, 1, 1, 1, 1, 1]),
+        (10, 5, [1, 1, 1, 0, 1, 1, 0, 0]),
+        (4, 13, [1, 0, 0, 0, 1, 1, 1, 1]),
+        (0, 0, [0, 0, 0, 0, 0, 0, 0, 0]),
+    ] {
+        let handle = client.empty(expected.len() * size_of::<u32>());
+        ordered_tiles_kernel::launch::<R>(
+            &client,
+            CubeCount::Static(1, 1, 1),
+            CubeDim::new(1, 1, 1),
+            ScalarArg::new(rows),
+            ScalarArg::new(cols),
+            unsafe { ArrayArg::from_raw_parts::<u32>(&handle, expected.len(), 1) },
+            partition_size,
+            tile_size,
+        );
+
+        let actual = u32::from_bytes(&client.read_one(handle)).to_vec();
+        assert_eq!(
+            actual, expected,
+            "Unexpected tiles in bounds for {rows} rows and {cols} columns"
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use cubecl_core::prelude::*;
+
+use crate::components::{
+    MatmulAvailabilityError, MatmulProblem, MatmulSelection, MatmulSetupError,
+};
+use crate::kernels::layered::{Algorithm, validate_plane_dim};
+use crate::tests::layered::matmul_test_launcher::test_matmul_algorithm;
+use crate::tests::test_utils::TestPrecision;
+
+/// Test the matmul with the plane dimension of the `selection` forced to `plane_dim`, rather than
+/// read from the client properties.
+///
+/// A plane dimension outside of the plane sizes of the client must be rejected like the launch
+/// does, in which case the matmul itself is skipped.
+pub fn test_forced_plane_dim<A, P, R>(
+    client: ComputeClient<R::Server, R::Channel>,
+    problem: MatmulProblem,
+    selection: MatmulSelection,
+    plane_dim: u32,
+) where
+    A: Algorithm,
+    P: TestPrecision,
+    R: Runtime,
+{
+    let hw_props = &client.properties().hardware;
+    let (min, max) = (hw_props.plane_size_min, hw_props.plane_size_max);
+    let selection = MatmulSelection {
+        plane_dim,
+        ..selection
+    };
+
+    // Clients without a plane size accept any plane dimension
+    let supported = max == 0 || (min..=max).contains(&plane_dim);
+    let result = validate_plane_dim::<R>(&client, &selection);
+    if supported {
+        result.unwrap();
+    } else {
+        assert!(
+            matches!(
+                result,
+                Err(MatmulSetupError::Unavailable(
+                    MatmulAvailabilityError::PlaneDimUnsupported { plane_dim: actual }
+                )) if actual == plane_dim
+            ),
+            "Unexpected result {result:?}"
+        );
+        println!("Skipping test, the client only supports the plane sizes {min}..={max}");
+        return;
+    }
+
+    test_matmul_algorithm::<A, P, R>(client, problem, selection);
+}
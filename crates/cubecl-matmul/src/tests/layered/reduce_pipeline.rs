@@ -0,0 +1,123 @@
+use cubecl_core::prelude::*;
+use cubecl_reduce::instructions::{Sum, SumConfig};
+
+use crate::components::global::memory::OutputLayout;
+use crate::components::{AvailableLineSizes, MatmulIdent, MatmulProblem, MatmulSelection};
+use crate::kernels::layered::simple_unit::SimpleUnitAlgorithm;
+use crate::tests::layered::matmul_test_launcher::{
+    column_major_out_raw_parts, contiguous_strides, deinterleave_out, launch_matmul,
+    setup_matmul_test, strides, tensor_raw_parts,
+};
+use crate::tests::test_utils::{TestPrecision, assert_equals_approx, matmul_cpu_reference};
+
+type P = (f32, f32);
+
+/// Test a matmul written with [OutputLayout::ColumnMajor] followed by a sum over `n` reading
+/// its output in place, using [SimpleUnitAlgorithm], against a naive CPU matmul and the sums of
+/// its rows.
+pub fn test_matmul_reduce_pipeline<R: Runtime>(
+    client: ComputeClient<R::Server, R::Channel>,
+    problem: MatmulProblem,
+    selection: MatmulSelection,
+) {
+    let selection = MatmulSelection {
+        output_layout: OutputLayout::ColumnMajor,
+        ..selection
+    };
+    let lhs = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Lhs);
+    let rhs = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Rhs);
+    let out = column_major_out_raw_parts::<P, R>(&client, &problem);
+
+    let line_sizes = AvailableLineSizes::from_types::<R>(
+        &f32::as_type_native_unchecked(),
+        &f32::as_type_native_unchecked(),
+        &f32::as_type_native_unchecked(),
+    );
+    let line_sizes = line_sizes
+        .filter_lhs_with_tensor(&lhs.strides, &lhs.shape, problem.lhs_layout)
+        .filter_rhs_with_tensor(&rhs.strides, &rhs.shape, problem.rhs_layout)
+        .filter_out_with_tensor(&out.strides, &out.shape);
+
+    let (config, line_sizes) =
+        match setup_matmul_test::<SimpleUnitAlgorithm, (f32, f32, f32, f32, f32, f32), R>(
+            &client, &problem, &selection, line_sizes,
+        ) {
+            Ok(setup) => setup,
+            Err(msg) => {
+                println!("{msg}");
+                return;
+            }
+        };
+    launch_matmul::<SimpleUnitAlgorithm, <P as TestPrecision>::MP, R, _, _, _>(
+        &client,
+        &problem,
+        config,
+        &line_sizes,
+        &lhs,
+        &rhs,
+        None,
+        &out,
+    );
+
+    // The reduction reads the output where the matmul wrote it, through its column-major strides
+    let rank = out.shape.len();
+    let mut sums_shape = out.shape.clone();
+    sums_shape[rank - 1] = 1;
+    let sums_strides = contiguous_strides(&sums_shape);
+    let sums_handle = client.empty(sums_shape.iter().product::<usize>() * size_of::<f32>());
+    let reduced = cubecl_reduce::reduce::<R, f32, f32, Sum>(
+        &client,
+        unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &out.handle,
+                &out.strides,
+                &out.shape,
+                size_of::<f32>(),
+            )
+        },
+        unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &sums_handle,
+                &sums_strides,
+                &sums_shape,
+                size_of::<f32>(),
+            )
+        },
+        rank - 1,
+        None,
+        SumConfig::default(),
+    );
+    if let Err(err) = reduced {
+        panic!("Can't reduce the matmul output: {err}");
+    }
+
+    let lhs_data = lhs.original_data.unwrap();
+    let rhs_data = rhs.original_data.unwrap();
+    let expected = matmul_cpu_reference::<P>(&lhs_data, &rhs_data, None, &problem);
+    let expected_sums = expected
+        .chunks(problem.n)
+        .map(|row| row.iter().sum::<f32>())
+        .collect::<Vec<_>>();
+
+    P::assert_result::<R>(
+        &lhs_data,
+        &rhs_data,
+        None,
+        &problem,
+        &client,
+        deinterleave_out::<P, R>(&client, out.handle, &out.shape, &out.strides),
+        &out.shape,
+        &strides(&problem, MatmulIdent::Out),
+    );
+    if let Err(e) = assert_equals_approx::<R, f32>(
+        &client,
+        sums_handle,
+        &sums_shape,
+        &sums_strides,
+        &expected_sums,
+        // Each sum adds up `n` elements with the error of the matmul
+        problem.n as f32 * 3.0 * 10e-6,
+    ) {
+        panic!("{}", e);
+    }
+}
@@ -0,0 +1,95 @@
+use cubecl_core::prelude::*;
+
+use crate::components::stage::PartitionBuffering;
+use crate::components::{AvailableLineSizes, MatmulIdent, MatmulProblem, MatmulSelection};
+use crate::kernels::layered::Algorithm;
+use crate::tests::layered::matmul_test_launcher::{
+    launch_matmul, setup_matmul_test, tensor_raw_parts,
+};
+use crate::tests::test_utils::{TestPrecision, assert_equals_approx};
+
+/// Test that the matmul with the Lhs kept in registers by [PartitionBuffering::RegisterLhs]
+/// gives the same output as the Single and Double partition bufferings over the same operands.
+///
+/// The problem should have an `m` of at most one tile, which is when the plane selector picks
+/// [PartitionBuffering::RegisterLhs] by default.
+pub fn test_register_lhs_parity<A, P, R>(
+    client: ComputeClient<R::Server, R::Channel>,
+    problem: MatmulProblem,
+    selection: MatmulSelection,
+) where
+    A: Algorithm,
+    P: TestPrecision,
+    P::EG: Float,
+    R: Runtime,
+{
+    let lhs = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Lhs);
+    let rhs = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Rhs);
+
+    let launch = |partition_buffering: PartitionBuffering| {
+        let out = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Out);
+        let line_sizes = AvailableLineSizes::from_types::<R>(
+            &P::EG::as_type_native_unchecked(),
+            &P::EG::as_type_native_unchecked(),
+            &P::EG::as_type_native_unchecked(),
+        )
+        .filter_lhs_with_tensor(&lhs.strides, &lhs.shape, problem.lhs_layout)
+        .filter_rhs_with_tensor(&rhs.strides, &rhs.shape, problem.rhs_layout)
+        .filter_out_with_tensor(&out.strides, &out.shape);
+        let selection = MatmulSelection {
+            partition_buffering,
+            ..selection.clone()
+        };
+
+        let (config, line_sizes) =
+            setup_matmul_test::<A, (P::EG, P::EG, P::EG, P::ES, P::ES, P::EA), R>(
+                &client, &problem, &selection, line_sizes,
+            )?;
+        launch_matmul::<A, P::MP, R, _, _, _>(
+            &client,
+            &problem,
+            config,
+            &line_sizes,
+            &lhs,
+            &rhs,
+            None,
+            &out,
+        );
+        Ok::<_, String>(out)
+    };
+
+    let register_lhs = match launch(PartitionBuffering::RegisterLhs) {
+        Ok(out) => out,
+        Err(msg) => {
+            println!("{msg}");
+            return;
+        }
+    };
+    let expected = client.read_one_tensor(register_lhs.handle.copy_descriptor(
+        &register_lhs.shape,
+        &register_lhs.strides,
+        size_of::<P::EG>(),
+    ));
+    let expected = P::EG::from_bytes(&expected).to_vec();
+
+    for partition_buffering in [PartitionBuffering::Single, PartitionBuffering::Double] {
+        let out = match launch(partition_buffering) {
+            Ok(out) => out,
+            Err(msg) => {
+                println!("{msg}");
+                continue;
+            }
+        };
+
+        if let Err(e) = assert_equals_approx::<R, P::EG>(
+            &client,
+            out.handle,
+            &out.shape,
+            &out.strides,
+            &expected,
+            3.0 * 10e-6,
+        ) {
+            panic!("{partition_buffering:?} differs from RegisterLhs: {e}");
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use cubecl_core::prelude::*;
+use cubecl_reduce::instructions::{Sum, SumConfig};
+
+use crate::components::batch::BatchConfig;
+use crate::components::global::GlobalConfig;
+use crate::components::global::read::{
+    ZeroGlobalReaderFamily, sync_full_cyclic::SyncFullCyclicLoading,
+};
+use crate::components::global::single_stage::simple::{row_sum_combine, row_sum_matmul};
+use crate::components::stage::{ColMajorTilingOrder, RowMajorTilingOrder};
+use crate::components::{AvailableLineSizes, MatmulIdent, MatmulProblem, MatmulSelection};
+use crate::kernels::layered::Algorithm;
+use crate::kernels::layered::simple_unit::SimpleUnitAlgorithm;
+use crate::tests::layered::matmul_test_launcher::{
+    contiguous_strides, launch_matmul, setup_matmul_test, tensor_raw_parts,
+};
+use crate::tests::test_utils::{TestPrecision, assert_equals_approx};
+
+type P = (f32, f32);
+
+/// Test the matmul writing the row sums of its output as it flushes the tiles, using the stages
+/// of [SimpleUnitAlgorithm], against a naive CPU matmul for the output, and against the general
+/// matmul followed by a sum over `n` for the row sums.
+pub fn test_row_sum_matmul<R: Runtime>(
+    client: ComputeClient<R::Server, R::Channel>,
+    problem: MatmulProblem,
+    selection: MatmulSelection,
+) {
+    let lhs = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Lhs);
+    let rhs = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Rhs);
+    let out = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Out);
+
+    let line_sizes = AvailableLineSizes::from_types::<R>(
+        &f32::as_type_native_unchecked(),
+        &f32::as_type_native_unchecked(),
+        &f32::as_type_native_unchecked(),
+    )
+    .filter_lhs_with_tensor(&lhs.strides, &lhs.shape, problem.lhs_layout)
+    .filter_rhs_with_tensor(&rhs.strides, &rhs.shape, problem.rhs_layout)
+    .filter_out_with_tensor(&out.strides, &out.shape);
+
+    let (config, line_sizes) =
+        match setup_matmul_test::<SimpleUnitAlgorithm, (f32, f32, f32, f32, f32, f32), R>(
+            &client, &problem, &selection, line_sizes,
+        ) {
+            Ok(setup) => setup,
+            Err(msg) => {
+                println!("{msg}");
+                return;
+            }
+        };
+
+    let global_config = config.global_config();
+    let tiling_scheme = global_config.tiling_scheme();
+    let cube_count = CubeCount::Static(
+        problem
+            .m
+            .div_ceil(tiling_scheme.elements_in_stage_m() as usize) as u32,
+        problem
+            .n
+            .div_ceil(tiling_scheme.elements_in_stage_n() as usize) as u32,
+        problem.num_batches() as u32,
+    );
+
+    // One sum per row of each tile along `n`
+    let rank = out.shape.len();
+    let mut tile_sums_shape = out.shape.clone();
+    tile_sums_shape[rank - 1] = problem
+        .n
+        .div_ceil(tiling_scheme.elements_in_tile_n() as usize);
+    let tile_sums_strides = contiguous_strides(&tile_sums_shape);
+    let tile_sums_handle =
+        client.empty(tile_sums_shape.iter().product::<usize>() * size_of::<f32>());
+
+    unsafe {
+        row_sum_matmul::launch_unchecked::<
+            f32,
+            f32,
+            f32,
+            f32,
+            f32,
+            f32,
+            <SimpleUnitAlgorithm as Algorithm>::StageMatmul,
+            SyncFullCyclicLoading<ColMajorTilingOrder>,
+            SyncFullCyclicLoading<RowMajorTilingOrder>,
+            ZeroGlobalReaderFamily,
+            R,
+        >(
+            &client,
+            cube_count,
+            config.cube_dim(),
+            TensorArg::<R>::from_raw_parts::<f32>(
+                &lhs.handle,
+                &lhs.strides,
+                &lhs.shape,
+                line_sizes.lhs,
+            ),
+            TensorArg::<R>::from_raw_parts::<f32>(
+                &rhs.handle,
+                &rhs.strides,
+                &rhs.shape,
+                line_sizes.rhs,
+            ),
+            TensorArg::<R>::from_raw_parts::<f32>(
+                &out.handle,
+                &out.strides,
+                &out.shape,
+                line_sizes.out,
+            ),
+            TensorArg::<R>::from_raw_parts::<f32>(
+                &tile_sums_handle,
+                &tile_sums_strides,
+                &tile_sums_shape,
+                1,
+            ),
+            global_config,
+        );
+    }
+
+    let mut sums_shape = out.shape.clone();
+    sums_shape[rank - 1] = 1;
+    let sums_strides = contiguous_strides(&sums_shape);
+    let num_rows = sums_shape.iter().product::<usize>();
+    let sums_handle = client.empty(num_rows * size_of::<f32>());
+    let cube_dim = CubeDim::default();
+    row_sum_combine::launch::<f32, R>(
+        &client,
+        CubeCount::Static(
+            num_rows.div_ceil(cube_dim.num_elems() as usize) as u32,
+            1,
+            1,
+        ),
+        cube_dim,
+        unsafe {
+            TensorArg::<R>::from_raw_parts::<f32>(
+                &tile_sums_handle,
+                &tile_sums_strides,
+                &tile_sums_shape,
+                1,
+            )
+        },
+        unsafe {
+            TensorArg::<R>::from_raw_parts::<f32>(&sums_handle, &sums_strides, &sums_shape, 1)
+        },
+    );
+
+    // The general matmul, then a separate reduction over its output
+    let dense_out = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Out);
+    launch_matmul::<SimpleUnitAlgorithm, <P as TestPrecision>::MP, R, _, _, _>(
+        &client,
+        &problem,
+        config,
+        &line_sizes,
+        &lhs,
+        &rhs,
+        None,
+        &dense_out,
+    );
+    let expected_sums_handle = client.empty(num_rows * size_of::<f32>());
+    let reduced = cubecl_reduce::reduce::<R, f32, f32, Sum>(
+        &client,
+        unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &dense_out.handle,
+                &dense_out.strides,
+                &dense_out.shape,
+                size_of::<f32>(),
+            )
+        },
+        unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &expected_sums_handle,
+                &sums_strides,
+                &sums_shape,
+                size_of::<f32>(),
+            )
+        },
+        rank - 1,
+        None,
+        SumConfig::default(),
+    );
+    if let Err(err) = reduced {
+        panic!("Can't reduce the matmul output: {err}");
+    }
+    let expected_sums = client.read_one_tensor(expected_sums_handle.copy_descriptor(
+        &sums_shape,
+        &sums_strides,
+        size_of::<f32>(),
+    ));
+    let expected_sums = f32::from_bytes(&expected_sums).to_vec();
+
+    P::assert_result::<R>(
+        &lhs.original_data.unwrap(),
+        &rhs.original_data.unwrap(),
+        None,
+        &problem,
+        &client,
+        out.handle,
+        &out.shape,
+        &out.strides,
+    );
+    if let Err(e) = assert_equals_approx::<R, f32>(
+        &client,
+        sums_handle,
+        &sums_shape,
+        &sums_strides,
+        &expected_sums,
+        // The sums add up `n` elements in a different order
+        problem.n as f32 * 3.0 * 10e-6,
+    ) {
+        panic!("{}", e);
+    }
+}
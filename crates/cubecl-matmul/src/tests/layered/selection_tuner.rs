@@ -0,0 +1,75 @@
+use cubecl_core::prelude::*;
+
+use crate::MatmulInputHandleRef;
+use crate::components::{AvailableLineSizes, MatmulIdent, MatmulProblem};
+use crate::kernels::layered::{Algorithm, SelectionTuner};
+use crate::tests::layered::matmul_test_launcher::tensor_raw_parts;
+use crate::tests::test_utils::TestPrecision;
+
+/// Tune the selection of the specified Matmul over the given problem,
+/// and check that the selection returned by the tuner gives a valid config
+pub fn test_selection_tuner<A, P, R>(
+    client: ComputeClient<R::Server, R::Channel>,
+    problem: MatmulProblem,
+) where
+    A: Algorithm + 'static,
+    P: TestPrecision,
+    R: Runtime,
+{
+    let lhs = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Lhs);
+    let rhs = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Rhs);
+    let out = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Out);
+
+    let line_sizes = AvailableLineSizes::from_types::<R>(
+        &P::EG::as_type_native_unchecked(),
+        &P::EG::as_type_native_unchecked(),
+        &P::EG::as_type_native_unchecked(),
+    );
+    let line_sizes = A::filter_line_sizes(line_sizes)
+        .filter_lhs_with_tensor(&lhs.strides, &lhs.shape, problem.lhs_layout)
+        .filter_rhs_with_tensor(&rhs.strides, &rhs.shape, problem.rhs_layout)
+        .filter_out_with_tensor(&out.strides, &out.shape)
+        .pick_max()
+        .unwrap();
+
+    let elem_size = size_of::<P::EG>();
+    let lhs_ref = MatmulInputHandleRef::Normal(unsafe {
+        TensorHandleRef::<R>::from_raw_parts(&lhs.handle, &lhs.strides, &lhs.shape, elem_size)
+    });
+    let rhs_ref = MatmulInputHandleRef::Normal(unsafe {
+        TensorHandleRef::<R>::from_raw_parts(&rhs.handle, &rhs.strides, &rhs.shape, elem_size)
+    });
+    let out_ref = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(&out.handle, &out.strides, &out.shape, elem_size)
+    };
+
+    let tuner = SelectionTuner::new(2);
+    let tune = || {
+        tuner.tune::<R, P::MP, A>(
+            &client,
+            &lhs_ref,
+            &rhs_ref,
+            &out_ref,
+            &problem,
+            &line_sizes,
+            &Default::default(),
+        )
+    };
+
+    let selection = match tune() {
+        Ok(selection) => selection,
+        Err(err) => {
+            println!("Can't launch the test: {err}");
+            return;
+        }
+    };
+
+    if let Err(err) = A::setup::<P::MP, R>(&client, &problem, &selection, &line_sizes) {
+        panic!("The tuned selection should give a valid config: {err}");
+    }
+
+    let cached = tune().unwrap();
+    assert_eq!(cached.tiling_scheme, selection.tiling_scheme);
+    assert_eq!(cached.partition_buffering, selection.partition_buffering);
+    assert_eq!(cached.tile_iteration, selection.tile_iteration);
+}
@@ -0,0 +1,68 @@
+use cubecl_core::prelude::*;
+
+use crate::components::batch::BatchConfig;
+use crate::components::error::MatmulAvailabilityError;
+use crate::components::global::GlobalConfig;
+use crate::components::stage::StageConfig;
+use crate::components::{AvailableLineSizes, MatmulProblem, MatmulSelection};
+use crate::kernels::layered::Algorithm;
+use crate::tests::layered::matmul_test_launcher::setup_matmul_test;
+use crate::tests::test_utils::TestPrecision;
+
+/// Check that the stage config set up for the device of the `client` fits its limits,
+/// and that lowering the unit count and the shared memory of the device below what the stage
+/// needs is reported with the matching error
+pub fn test_stage_device_limits<A, P, R>(
+    client: ComputeClient<R::Server, R::Channel>,
+    problem: MatmulProblem,
+    selection: MatmulSelection,
+) where
+    A: Algorithm,
+    P: TestPrecision,
+    R: Runtime,
+{
+    let line_sizes = AvailableLineSizes::from_types::<R>(
+        &P::EG::as_type_native_unchecked(),
+        &P::EG::as_type_native_unchecked(),
+        &P::EG::as_type_native_unchecked(),
+    );
+
+    let config = match setup_matmul_test::<A, P::MP, R>(&client, &problem, &selection, line_sizes) {
+        Ok((config, _)) => config,
+        Err(msg) => {
+            println!("{msg}");
+            return;
+        }
+    };
+    let stage_config = config.global_config().stage_config();
+    let hardware = &client.properties().hardware;
+
+    stage_config
+        .validate_against_device::<P::MP, R>(&client)
+        .unwrap();
+
+    let units =
+        stage_config.plane_dim() * stage_config.plane_role_config().plane_roles.total_count();
+    let mut few_units = hardware.clone();
+    few_units.max_units_per_cube = units - 1;
+    let err = stage_config.validate_against_hardware::<P::MP>(&few_units);
+    assert!(
+        matches!(
+            err,
+            Err(MatmulAvailabilityError::UnitsPerCubeTooBig { units: actual, max })
+                if actual == units && max == units - 1
+        ),
+        "Unexpected result {err:?}"
+    );
+
+    let mut little_shared_memory = hardware.clone();
+    little_shared_memory.max_shared_memory_size = 0;
+    let err = stage_config.validate_against_hardware::<P::MP>(&little_shared_memory);
+    assert!(
+        matches!(
+            err,
+            Err(MatmulAvailabilityError::SharedMemoryTooBig { max: 0, .. })
+        ),
+        "Unexpected result {err:?}"
+    );
+}
@@ -0,0 +1,199 @@
+use cubecl_core::prelude::*;
+
+use crate::components::batch::BatchConfig;
+use crate::components::global::read::{
+    ZeroGlobalReaderFamily, sync_full_cyclic::SyncFullCyclicLoading,
+};
+use crate::components::global::single_stage::simple::{SyrkTriangle, syrk_matmul, syrk_mirror};
+use crate::components::global::{GlobalConfig, UnitWriterFamily};
+use crate::components::stage::{ColMajorTilingOrder, RowMajorTilingOrder};
+use crate::components::{
+    AvailableLineSizes, MatmulIdent, MatmulProblem, MatmulSelection, MatrixLayout,
+};
+use crate::kernels::layered::Algorithm;
+use crate::kernels::layered::simple_unit::SimpleUnitAlgorithm;
+use crate::tests::layered::matmul_test_launcher::{
+    TensorRawParts, launch_matmul, setup_matmul_test, tensor_raw_parts, transpose,
+};
+use crate::tests::test_utils::{TestPrecision, assert_equals_approx};
+
+/// Test the matmul of a matrix with its own transpose, using the stages of [SimpleUnitAlgorithm],
+/// computing only one triangle then mirroring it.
+///
+/// Without `transposed`, this computes `a * a^T`, against a dense naive CPU matmul. The problem
+/// must be square with a row-major Lhs and a col-major Rhs, which is the layout of the transposed
+/// view of the Lhs.
+///
+/// With `transposed`, this computes `a^T * a`, against the general matmul of
+/// [SimpleUnitAlgorithm] over the same operands. The problem must be square with a col-major Lhs
+/// and a row-major Rhs: the Lhs is the transposed view of the row-major matrix `a` of shape
+/// `[k, n]`, which is the kernel input.
+pub fn test_syrk_matmul<P, R>(
+    client: ComputeClient<R::Server, R::Channel>,
+    problem: MatmulProblem,
+    selection: MatmulSelection,
+    triangle: SyrkTriangle,
+    transposed: bool,
+) where
+    P: TestPrecision,
+    P::EG: Float,
+    R: Runtime,
+{
+    let (lhs_layout, rhs_layout) = match transposed {
+        false => (MatrixLayout::RowMajor, MatrixLayout::ColMajor),
+        true => (MatrixLayout::ColMajor, MatrixLayout::RowMajor),
+    };
+    assert_eq!(problem.m, problem.n, "The output of syrk must be square");
+    assert_eq!(problem.lhs_layout, lhs_layout);
+    assert_eq!(problem.rhs_layout, rhs_layout);
+
+    // The Rhs is the transposed view of the Lhs, over the same memory.
+    let lhs = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Lhs);
+    let rank = lhs.shape.len();
+    let mut rhs = TensorRawParts::<P::EG> {
+        handle: lhs.handle.clone(),
+        scale: None,
+        shape: lhs.shape.clone(),
+        strides: lhs.strides.clone(),
+        original_data: None,
+    };
+    rhs.shape.swap(rank - 1, rank - 2);
+    rhs.strides.swap(rank - 1, rank - 2);
+    let out = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Out);
+
+    let line_sizes = AvailableLineSizes::from_types::<R>(
+        &P::EG::as_type_native_unchecked(),
+        &P::EG::as_type_native_unchecked(),
+        &P::EG::as_type_native_unchecked(),
+    )
+    .filter_lhs_with_tensor(&lhs.strides, &lhs.shape, problem.lhs_layout)
+    .filter_rhs_with_tensor(&rhs.strides, &rhs.shape, problem.rhs_layout)
+    .filter_out_with_tensor(&out.strides, &out.shape);
+
+    let (config, line_sizes) = match setup_matmul_test::<
+        SimpleUnitAlgorithm,
+        (P::EG, P::EG, P::EG, P::ES, P::ES, P::EA),
+        R,
+    >(&client, &problem, &selection, line_sizes)
+    {
+        Ok(setup) => setup,
+        Err(msg) => {
+            println!("{msg}");
+            return;
+        }
+    };
+    if line_sizes.lhs != line_sizes.rhs {
+        println!("Skipping test, both operands must be read with the same line size");
+        return;
+    }
+
+    // The kernel reads `a` in its row-major layout, which is the Rhs when transposed.
+    let a = if transposed { &rhs } else { &lhs };
+    let global_config = config.global_config();
+    let tiling_scheme = global_config.tiling_scheme();
+    let cube_count = CubeCount::Static(
+        problem
+            .m
+            .div_ceil(tiling_scheme.elements_in_stage_m() as usize) as u32,
+        problem
+            .n
+            .div_ceil(tiling_scheme.elements_in_stage_n() as usize) as u32,
+        problem.num_batches() as u32,
+    );
+
+    unsafe {
+        syrk_matmul::launch_unchecked::<
+            P::EG,
+            P::EG,
+            P::ES,
+            P::EA,
+            <SimpleUnitAlgorithm as Algorithm>::StageMatmul,
+            SyncFullCyclicLoading<ColMajorTilingOrder>,
+            SyncFullCyclicLoading<RowMajorTilingOrder>,
+            UnitWriterFamily,
+            ZeroGlobalReaderFamily,
+            R,
+        >(
+            &client,
+            cube_count,
+            config.cube_dim(),
+            TensorArg::<R>::from_raw_parts::<P::EG>(
+                &a.handle,
+                &a.strides,
+                &a.shape,
+                line_sizes.lhs,
+            ),
+            TensorArg::<R>::from_raw_parts::<P::EG>(
+                &out.handle,
+                &out.strides,
+                &out.shape,
+                line_sizes.out,
+            ),
+            triangle,
+            transposed,
+            global_config,
+        );
+    }
+
+    let num_elems = problem.num_batches() * problem.m * problem.n;
+    let cube_dim = CubeDim::default();
+    syrk_mirror::launch::<P::EG, R>(
+        &client,
+        CubeCount::Static(
+            num_elems.div_ceil(cube_dim.num_elems() as usize) as u32,
+            1,
+            1,
+        ),
+        cube_dim,
+        unsafe {
+            TensorArg::<R>::from_raw_parts::<P::EG>(&out.handle, &out.strides, &out.shape, 1)
+        },
+        triangle,
+    );
+
+    if !transposed {
+        let a_data = lhs.original_data.unwrap();
+        let a_t_data = transpose::<P::EG>(&a_data, problem.num_batches(), problem.m, problem.k);
+        P::assert_result::<R>(
+            &a_data,
+            &a_t_data,
+            None,
+            &problem,
+            &client,
+            out.handle,
+            &out.shape,
+            &out.strides,
+        );
+        return;
+    }
+
+    // `transpose(a) * a` through the general matmul, computing the whole output.
+    let dense_out = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Out);
+    launch_matmul::<SimpleUnitAlgorithm, P::MP, R, _, _, _>(
+        &client,
+        &problem,
+        config,
+        &line_sizes,
+        &lhs,
+        &rhs,
+        None,
+        &dense_out,
+    );
+    let expected = client.read_one_tensor(dense_out.handle.copy_descriptor(
+        &dense_out.shape,
+        &dense_out.strides,
+        size_of::<P::EG>(),
+    ));
+    let expected = P::EG::from_bytes(&expected).to_vec();
+
+    if let Err(e) = assert_equals_approx::<R, P::EG>(
+        &client,
+        out.handle,
+        &out.shape,
+        &out.strides,
+        &expected,
+        3.0 * 10e-6,
+    ) {
+        panic!("{}", e);
+    }
+}
@@ -13,11 +13,15 @@ use crate::components::batch::BatchConfig;
 use crate::components::batch::BatchMatmulFamily;
 use crate::components::global::args::TensorMapArgs;
 use crate::components::global::args::{ConcreteInputsFactory, TensorMapInputs};
+use crate::components::global::memory::OutputLayout;
 use crate::kernels::layered::Algorithm;
 use crate::tests::test_utils::Sample;
 use crate::tests::test_utils::TestPrecision;
 
-use super::matmul_test_launcher::{TensorRawParts, tensor_size, transpose};
+use super::matmul_test_launcher::{
+    TensorRawParts, column_major_out_raw_parts, contiguous_out_raw_parts, deinterleave_out,
+    strides, tensor_size, transpose, unblock_out,
+};
 
 /// Test the correctness of the specified Matmul on the given device,
 /// against a naive CPU implementation over the given problem
@@ -42,7 +46,11 @@ pub fn test_tma_matmul_algorithm<A, P, R>(
     };
     let lhs = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Lhs);
     let rhs = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Rhs);
-    let out = tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Out);
+    let out = match selection.output_layout {
+        OutputLayout::Strided => tensor_raw_parts::<P, R>(&client, &problem, MatmulIdent::Out),
+        OutputLayout::Blocked => contiguous_out_raw_parts::<P, R>(&client, &problem),
+        OutputLayout::ColumnMajor => column_major_out_raw_parts::<P, R>(&client, &problem),
+    };
 
     let elem_size = size_of::<P::EG>();
     let lhs_handle = MatmulInputHandleRef::Normal(TensorHandleRef {
@@ -122,14 +130,27 @@ pub fn test_tma_matmul_algorithm<A, P, R>(
         );
     }
 
+    let (out_handle, out_strides) = match selection.output_layout {
+        OutputLayout::Strided => (out.handle, out.strides),
+        OutputLayout::Blocked => (
+            unblock_out::<P, R>(&client, out.handle, &problem, &selection),
+            out.strides,
+        ),
+        OutputLayout::ColumnMajor => (
+            deinterleave_out::<P, R>(&client, out.handle, &out.shape, &out.strides),
+            strides(&problem, MatmulIdent::Out),
+        ),
+    };
+
     P::assert_result::<R>(
         &lhs.original_data.unwrap(),
         &rhs.original_data.unwrap(),
+        None,
         &problem,
         &client,
-        out.handle,
+        out_handle,
         &out.shape,
-        &out.strides,
+        &out_strides,
     );
 }
 
@@ -26,6 +26,7 @@ pub trait TestPrecision {
     fn assert_result<R: Runtime>(
         lhs: &[Self::EG],
         rhs: &[Self::EG],
+        acc: Option<&[Self::EG]>,
         problem: &MatmulProblem,
         client: &ComputeClient<R::Server, R::Channel>,
         out: server::Handle,
@@ -48,6 +49,7 @@ where
     fn assert_result<R: Runtime>(
         lhs: &[EG],
         rhs: &[EG],
+        acc: Option<&[EG]>,
         problem: &MatmulProblem,
         client: &ComputeClient<R::Server, R::Channel>,
         out: server::Handle,
@@ -77,7 +79,7 @@ where
             false => 3.0 * 10e-6,
         };
 
-        let expected = matmul_cpu_reference::<Self>(lhs, rhs, problem)
+        let expected = matmul_cpu_reference::<Self>(lhs, rhs, acc, problem)
             .into_iter()
             .map(|x| x.cast_into())
             .collect::<Vec<EG>>();
@@ -298,13 +300,15 @@ impl Sample for tf32 {
     }
 }
 
-/// Solves a matmul problem with EG inputs, multiplied as ES and accumulated as EA.
+/// Solves a matmul problem with EG inputs, multiplied as ES and accumulated as EA,
+/// starting from the accumulator `acc` if provided.
 ///
 /// This is a naive CPU implementation, very slow on large payloads,
 /// not designed to be used for other purposes than testing.
 pub(crate) fn matmul_cpu_reference<P: TestPrecision>(
     lhs: &[P::EG],
     rhs: &[P::EG],
+    acc: Option<&[P::EG]>,
     problem: &MatmulProblem,
 ) -> Vec<P::EA>
 where
@@ -325,7 +329,16 @@ where
     let rhs_strides = strides(problem, MatmulIdent::Rhs);
     let out_strides = strides(problem, MatmulIdent::Out);
 
-    let mut out = vec![P::EA::from_int(0); m * n * num_batches];
+    let mut out = match acc {
+        Some(acc) => acc
+            .iter()
+            .map(|c| {
+                let c: P::ES = (*c).cast_into();
+                c.cast_into()
+            })
+            .collect(),
+        None => vec![P::EA::from_int(0); m * n * num_batches],
+    };
 
     for nth_batch in 0..num_batches {
         let batch_out = nth_batch * m * n;
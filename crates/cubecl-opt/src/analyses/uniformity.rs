@@ -12,6 +12,7 @@ use super::Analysis;
 pub struct Uniformity {
     block_uniformity: HashMap<NodeIndex, bool>,
     variable_uniformity: HashMap<Variable, bool>,
+    branch_uniformity: HashMap<NodeIndex, bool>,
     visited: HashSet<EdgeIndex>,
 }
 
@@ -98,6 +99,8 @@ impl Uniformity {
                 merge,
             } => {
                 let is_uniform = self.is_var_uniform(*cond);
+                self.branch_uniformity
+                    .insert(block_id, is_uniform && block_uniform);
                 self.block_uniformity
                     .insert(*then, is_uniform && block_uniform);
                 self.block_uniformity
@@ -113,6 +116,8 @@ impl Uniformity {
                 merge,
             } => {
                 let is_uniform = self.is_var_uniform(*value);
+                self.branch_uniformity
+                    .insert(block_id, is_uniform && block_uniform);
                 self.block_uniformity
                     .insert(*default, is_uniform && block_uniform);
                 for branch in branches {
@@ -129,6 +134,7 @@ impl Uniformity {
                 merge,
             } => {
                 // If we don't know the break condition, we can't detect whether it's uniform
+                self.branch_uniformity.insert(block_id, false);
                 self.block_uniformity.insert(block_id, false);
                 self.block_uniformity.insert(*body, false);
                 self.block_uniformity.insert(*continue_target, false);
@@ -141,6 +147,8 @@ impl Uniformity {
                 merge,
             } => {
                 let is_uniform = self.is_var_uniform(*break_cond);
+                self.branch_uniformity
+                    .insert(block_id, is_uniform && block_uniform);
                 self.block_uniformity
                     .insert(block_id, is_uniform && block_uniform);
                 self.block_uniformity
@@ -252,4 +260,11 @@ impl Uniformity {
     pub fn is_block_uniform(&self, block: NodeIndex) -> bool {
         self.block_uniformity.get(&block).copied().unwrap_or(true)
     }
+
+    /// Whether the condition of the control flow ending `block` is plane uniform, so all units
+    /// of a plane reaching it take the same branch. Backends can then use a uniform branch
+    /// instead of predicating each unit. Blocks that don't branch are always uniform.
+    pub fn is_branch_uniform(&self, block: NodeIndex) -> bool {
+        self.branch_uniformity.get(&block).copied().unwrap_or(true)
+    }
 }
@@ -51,6 +51,17 @@ pub enum ControlFlow {
     None,
 }
 
+/// The form of control flow the optimizer should output
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlFlowMode {
+    /// Keep merge blocks and loop constructs, as required by structured targets like SPIR-V.
+    #[default]
+    Structured,
+    /// Lower all control flow to plain and conditional branches, dropping merge information.
+    /// Loops become regular back edges. Useful for targets with arbitrary branches like LLVM.
+    Unstructured,
+}
+
 impl Optimizer {
     pub(crate) fn parse_control_flow(&mut self, branch: Branch) {
         match branch {
@@ -335,6 +346,56 @@ impl Optimizer {
             ));
     }
 
+    /// Lower all structured control flow to [`ControlFlow::IfElse`] and [`ControlFlow::Switch`]
+    /// without merge targets, or [`ControlFlow::None`]. The edges of the graph are unchanged, so
+    /// the program is semantically equivalent, but it can no longer be emitted as structured code.
+    pub(crate) fn lower_to_unstructured(&mut self) {
+        for block in self.node_ids() {
+            let control_flow = self.program[block].control_flow.borrow().clone();
+            let lowered = match control_flow {
+                ControlFlow::IfElse {
+                    cond,
+                    then,
+                    or_else,
+                    ..
+                } => ControlFlow::IfElse {
+                    cond,
+                    then,
+                    or_else,
+                    merge: None,
+                },
+                ControlFlow::Switch {
+                    value,
+                    default,
+                    branches,
+                    ..
+                } => ControlFlow::Switch {
+                    value,
+                    default,
+                    branches,
+                    merge: None,
+                },
+                // The header only has an edge to the body, breaks branch out of the body directly.
+                ControlFlow::Loop { .. } => ControlFlow::None,
+                ControlFlow::LoopBreak {
+                    break_cond,
+                    body,
+                    merge,
+                    ..
+                } => ControlFlow::IfElse {
+                    cond: break_cond,
+                    then: body,
+                    or_else: merge,
+                    merge: None,
+                },
+                other => other,
+            };
+            *self.program[block].control_flow.borrow_mut() = lowered;
+            self.program[block].block_use.clear();
+        }
+        self.invalidate_structure();
+    }
+
     pub(crate) fn split_critical_edges(&mut self) {
         for block in self.node_ids() {
             let successors = self.program.edges(block);
@@ -38,12 +38,13 @@ use cubecl_ir::{
     self as core, Allocator, Branch, Id, Operation, Operator, Processor, Scope, Type, Variable,
     VariableKind,
 };
-use gvn::GvnPass;
 use passes::{
-    CompositeMerge, ConstEval, ConstOperandSimplify, CopyPropagateArray, CopyTransform,
-    EliminateConstBranches, EliminateDeadBlocks, EliminateDeadPhi, EliminateUnusedVariables,
-    EmptyBranchToSelect, InlineAssignments, MergeBlocks, MergeSameExpressions, OptimizerPass,
-    ReduceStrength, RemoveIndexScalar,
+    CoalesceLoopPhis, CollapseRepeatedAdds, CompositeMerge, ConstEval, ConstOperandSimplify,
+    ContractFma, CopyPropagateArray, CopyTransform, EliminateConstBranches, EliminateDeadBlocks,
+    EliminateDeadPhi, EliminateUnusedVariables, EmptyBranchToSelect, FoldRedundantCasts,
+    ForwardConstIndexStores, InlineAssignments, InlineSmallLoops, MergeBlocks, MergeBranchTails,
+    MergeSameExpressions, OptimizerPass, RecognizePlaneReductions, ReduceStrength,
+    RemoveIndexScalar, RemoveRedundantSyncs, ShortBranchToSelect, UnswitchLoops,
 };
 use petgraph::{
     Direction,
@@ -58,16 +59,23 @@ mod control_flow;
 mod debug;
 mod gvn;
 mod instructions;
-mod passes;
+mod options;
+pub mod passes;
 mod phi_frontiers;
+mod pipeline;
 mod transformers;
+mod verify;
 mod version;
 
 pub use analyses::uniformity::Uniformity;
 pub use block::*;
 pub use control_flow::*;
+pub use gvn::GvnPass;
+pub use options::*;
 pub use petgraph::graph::{EdgeIndex, NodeIndex};
+pub use pipeline::*;
 pub use transformers::*;
+pub use verify::SsaError;
 pub use version::PhiInstruction;
 
 pub use crate::analyses::liveness::shared::SharedLiveness;
@@ -150,6 +158,8 @@ pub struct Optimizer {
     pub(crate) cube_dim: CubeDim,
     pub(crate) transformers: Vec<Rc<dyn IrTransformer>>,
     pub(crate) processors: Rc<Vec<Box<dyn Processor>>>,
+    /// The form of the output and the optional passes to run
+    pub(crate) options: OptimizerOptions,
 }
 
 impl Default for Optimizer {
@@ -165,6 +175,7 @@ impl Default for Optimizer {
             analysis_cache: Default::default(),
             transformers: Default::default(),
             processors: Default::default(),
+            options: Default::default(),
         }
     }
 }
@@ -177,6 +188,45 @@ impl Optimizer {
         cube_dim: CubeDim,
         transformers: Vec<Rc<dyn IrTransformer>>,
         processors: Vec<Box<dyn Processor>>,
+    ) -> Self {
+        Self::with_control_flow(
+            expand,
+            cube_dim,
+            transformers,
+            processors,
+            ControlFlowMode::default(),
+        )
+    }
+
+    /// Create a new optimizer like [`Optimizer::new`], but with control flow output in the
+    /// given `control_flow_mode`.
+    pub fn with_control_flow(
+        expand: Scope,
+        cube_dim: CubeDim,
+        transformers: Vec<Rc<dyn IrTransformer>>,
+        processors: Vec<Box<dyn Processor>>,
+        control_flow_mode: ControlFlowMode,
+    ) -> Self {
+        Self::with_options(
+            expand,
+            cube_dim,
+            transformers,
+            processors,
+            OptimizerOptions {
+                control_flow_mode,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Create a new optimizer like [`Optimizer::with_control_flow`], with the control flow mode
+    /// and the optional passes given by `options`.
+    pub fn with_options(
+        expand: Scope,
+        cube_dim: CubeDim,
+        transformers: Vec<Rc<dyn IrTransformer>>,
+        processors: Vec<Box<dyn Processor>>,
+        options: OptimizerOptions,
     ) -> Self {
         let mut opt = Self {
             root_scope: expand.clone(),
@@ -184,6 +234,7 @@ impl Optimizer {
             allocator: expand.allocator.clone(),
             transformers,
             processors: Rc::new(processors),
+            options,
             ..Default::default()
         };
         opt.run_opt();
@@ -212,8 +263,19 @@ impl Optimizer {
         self.parse_graph(self.root_scope.clone());
         self.split_critical_edges();
         self.apply_pre_ssa_passes();
+        // Before the loops are inlined, so the loop is still recognizable.
+        if self.options.plane_reductions {
+            RecognizePlaneReductions.apply_pre_ssa(self, AtomicCounter::new(0));
+        }
+        if let Some(max_trip_count) = self.options.max_inline_trip_count {
+            InlineSmallLoops { max_trip_count }.apply_pre_ssa(self, AtomicCounter::new(0));
+        }
+        if self.options.unswitch_loops {
+            UnswitchLoops.apply_pre_ssa(self, AtomicCounter::new(0));
+        }
         self.exempt_index_assign_locals();
         self.ssa_transform();
+        self.debug_verify_ssa("SSA transform");
         self.apply_post_ssa_passes();
 
         // Special expensive passes that should only run once.
@@ -221,25 +283,51 @@ impl Optimizer {
 
         let arrays_prop = AtomicCounter::new(0);
         CopyPropagateArray.apply_post_ssa(self, arrays_prop.clone());
+        self.debug_verify_ssa(CopyPropagateArray.name());
         if arrays_prop.get() > 0 {
             self.invalidate_analysis::<Liveness>();
             self.ssa_transform();
+            self.debug_verify_ssa("SSA transform");
             self.apply_post_ssa_passes();
         }
 
+        if self.options.forward_stores {
+            let forwarded = AtomicCounter::new(0);
+            ForwardConstIndexStores.apply_post_ssa(self, forwarded.clone());
+            self.debug_verify_ssa(ForwardConstIndexStores.name());
+            if forwarded.get() > 0 {
+                self.apply_post_ssa_passes();
+            }
+        }
+
         let gvn_count = AtomicCounter::new(0);
         GvnPass.apply_post_ssa(self, gvn_count.clone());
+        self.debug_verify_ssa(GvnPass.name());
         ReduceStrength.apply_post_ssa(self, gvn_count.clone());
+        self.debug_verify_ssa(ReduceStrength.name());
         CopyTransform.apply_post_ssa(self, gvn_count.clone());
+        self.debug_verify_ssa(CopyTransform.name());
 
         if gvn_count.get() > 0 {
             self.apply_post_ssa_passes();
         }
 
+        if self.options.contract_fma {
+            ContractFma.apply_post_ssa(self, AtomicCounter::new(0));
+            self.debug_verify_ssa(ContractFma.name());
+        }
+
         self.split_free();
+        self.debug_verify_ssa("split free");
         self.analysis::<SharedLiveness>();
 
         MergeBlocks.apply_post_ssa(self, AtomicCounter::new(0));
+        self.debug_verify_ssa(MergeBlocks.name());
+
+        if self.options.control_flow_mode == ControlFlowMode::Unstructured {
+            self.lower_to_unstructured();
+            self.debug_verify_ssa("unstructured lowering");
+        }
     }
 
     /// Run only the shared memory analysis
@@ -298,15 +386,26 @@ impl Optimizer {
             Box::new(ConstEval),
             Box::new(RemoveIndexScalar),
             Box::new(EliminateConstBranches),
+            Box::new(MergeBranchTails),
             Box::new(EmptyBranchToSelect),
             Box::new(EliminateDeadBlocks),
             Box::new(EliminateDeadPhi),
+            Box::new(CoalesceLoopPhis),
+            Box::new(CollapseRepeatedAdds),
+            Box::new(FoldRedundantCasts),
+            Box::new(RemoveRedundantSyncs),
         ];
+        // The hoisted arms are left empty, so `EmptyBranchToSelect` replaces the branch by
+        // selects in the next round.
+        if let Some(max_arm_size) = self.options.max_select_arm_size {
+            passes.push(Box::new(ShortBranchToSelect { max_arm_size }));
+        }
 
         loop {
             let counter = AtomicCounter::default();
             for pass in &mut passes {
                 pass.apply_post_ssa(self, counter.clone());
+                self.debug_verify_ssa(pass.name());
             }
 
             if counter.get() == 0 {
@@ -472,12 +571,25 @@ pub fn visit_noop(_opt: &mut Optimizer, _var: &mut Variable) {}
 
 #[cfg(test)]
 mod test {
+    use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
     use cubecl_core as cubecl;
     use cubecl_core::cube;
     use cubecl_core::prelude::*;
-    use cubecl_ir::{ElemType, ExpandElement, Type, UIntKind, Variable, VariableKind};
+    use cubecl_ir::{
+        Arithmetic, Comparison, ConstantScalarValue, ElemType, ExpandElement, FloatKind, IntKind,
+        Operation, Operator, Plane, Synchronization, Type, UIntKind, Variable, VariableKind,
+    };
 
-    use crate::Optimizer;
+    use crate::{
+        AtomicCounter, ControlFlow, ControlFlowMode, Optimizer, OptimizerBuilder, PassPipeline,
+        SsaError, Uniformity,
+        passes::{
+            EliminateConstBranches, EliminateDeadBlocks, MergeBlocks, OptimizerPass,
+            ReorderMemoryAccesses, VectorizeMemory,
+        },
+        visit_noop,
+    };
 
     #[allow(unused)]
     #[cube(launch)]
@@ -513,4 +625,1420 @@ mod test {
         let opt = Optimizer::new(ctx, CubeDim::default(), vec![], vec![]);
         println!("{opt}")
     }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn if_else_kernel(x: u32, cond: u32, out: &mut Array<u32>) {
+        if cond == 0 {
+            out[0] = x + 4;
+        } else {
+            out[1] = x * 2;
+        }
+    }
+
+    #[test]
+    fn test_unstructured_if_else() {
+        let optimize = |mode| {
+            let mut ctx = Scope::root(false);
+            let x = ExpandElement::Plain(Variable::new(
+                VariableKind::GlobalScalar(0),
+                Type::scalar(ElemType::UInt(UIntKind::U32)),
+            ));
+            let cond = ExpandElement::Plain(Variable::new(
+                VariableKind::GlobalScalar(1),
+                Type::scalar(ElemType::UInt(UIntKind::U32)),
+            ));
+            let arr = ExpandElement::Plain(Variable::new(
+                VariableKind::GlobalOutputArray(0),
+                Type::scalar(ElemType::UInt(UIntKind::U32)),
+            ));
+
+            if_else_kernel::expand(&mut ctx, x.into(), cond.into(), arr.into());
+            Optimizer::with_control_flow(ctx, CubeDim::default(), vec![], vec![], mode)
+        };
+        let structured = optimize(ControlFlowMode::Structured);
+        let unstructured = optimize(ControlFlowMode::Unstructured);
+
+        let mut has_merge = false;
+        assert_eq!(structured.node_ids(), unstructured.node_ids());
+        for node in structured.node_ids() {
+            let mut successors = structured.successors(node);
+            let mut lowered_successors = unstructured.successors(node);
+            successors.sort();
+            lowered_successors.sort();
+            assert_eq!(successors, lowered_successors);
+
+            let control_flow = structured.program[node].control_flow.borrow().clone();
+            let lowered = unstructured.program[node].control_flow.borrow().clone();
+            match (control_flow, lowered) {
+                (
+                    ControlFlow::IfElse {
+                        cond,
+                        then,
+                        or_else,
+                        merge,
+                    },
+                    ControlFlow::IfElse {
+                        cond: lowered_cond,
+                        then: lowered_then,
+                        or_else: lowered_or_else,
+                        merge: lowered_merge,
+                    },
+                ) => {
+                    has_merge |= merge.is_some();
+                    assert_eq!(cond, lowered_cond);
+                    assert_eq!((then, or_else), (lowered_then, lowered_or_else));
+                    assert_eq!(lowered_merge, None);
+                }
+                (ControlFlow::Return, ControlFlow::Return)
+                | (ControlFlow::None, ControlFlow::None) => {}
+                (control_flow, lowered) => {
+                    panic!("Unexpected control flow {control_flow:?} lowered to {lowered:?}")
+                }
+            }
+        }
+        assert!(has_merge, "Structured if-else should have a merge block");
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn contiguous_stores_kernel(x: f32, out: &mut Array<f32>, #[comptime] offset: u32) {
+        let base = ABSOLUTE_POS * 4 + offset;
+        unsafe {
+            out.index_assign_unchecked(base, x);
+            out.index_assign_unchecked(base + 1, x * 2.0);
+            out.index_assign_unchecked(base + 2, x * 3.0);
+            out.index_assign_unchecked(base + 3, x * 4.0);
+        }
+    }
+
+    /// The line sizes of the unchecked stores after vectorizing the memory accesses.
+    fn vectorized_store_line_sizes(offset: u32) -> Vec<u32> {
+        let mut ctx = Scope::root(false);
+        let x = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalScalar(0),
+            Type::scalar(ElemType::Float(FloatKind::F32)),
+        ));
+        let arr = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalOutputArray(0),
+            Type::scalar(ElemType::Float(FloatKind::F32)),
+        ));
+
+        contiguous_stores_kernel::expand(&mut ctx, x.into(), arr.into(), offset);
+        let mut opt = Optimizer::new(ctx, CubeDim::default(), vec![], vec![]);
+        VectorizeMemory.apply_post_ssa(&mut opt, AtomicCounter::new(0));
+
+        let mut line_sizes = Vec::new();
+        for node in opt.node_ids() {
+            for inst in opt.block(node).ops.borrow().values() {
+                if let Operation::Operator(Operator::UncheckedIndexAssign(op)) = &inst.operation {
+                    line_sizes.push(op.line_size);
+                }
+            }
+        }
+        line_sizes
+    }
+
+    #[test]
+    fn test_vectorize_contiguous_stores() {
+        assert_eq!(vectorized_store_line_sizes(0), vec![4]);
+    }
+
+    #[test]
+    fn test_vectorize_misaligned_stores() {
+        // Only the two stores at `ABSOLUTE_POS * 4 + 2` and `ABSOLUTE_POS * 4 + 3` are aligned.
+        assert_eq!(vectorized_store_line_sizes(1), vec![1, 2, 1]);
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn interleaved_loads_kernel(a: &Array<u32>, b: &Array<u32>, out: &mut Array<u32>) {
+        let base = ABSOLUTE_POS * 4;
+        unsafe {
+            let a0 = *a.index_unchecked(base);
+            let b0 = *b.index_unchecked(base);
+            let a1 = *a.index_unchecked(base + 1);
+            // Indexed by an earlier load, so it must stay after it.
+            let b1 = *b.index_unchecked(a0);
+            out.index_assign_unchecked(base, a0 + b0);
+            // After a store, so it can't be grouped with the other loads of `a`.
+            let a2 = *a.index_unchecked(base + 2);
+            out.index_assign_unchecked(base + 1, a1 + b1 + a2);
+        }
+    }
+
+    /// The memory accesses of the block after reordering them, as the array they access and
+    /// whether they are a store.
+    fn reordered_memory_accesses() -> Vec<(VariableKind, bool)> {
+        let mut ctx = Scope::root(false);
+        let [a, b] = [0, 1].map(|id| {
+            ExpandElement::Plain(Variable::new(
+                VariableKind::GlobalInputArray(id),
+                Type::scalar(ElemType::UInt(UIntKind::U32)),
+            ))
+        });
+        let out = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalOutputArray(0),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+
+        interleaved_loads_kernel::expand(&mut ctx, a.into(), b.into(), out.into());
+        let mut opt = Optimizer::new(ctx, CubeDim::default(), vec![], vec![]);
+        ReorderMemoryAccesses.apply_post_ssa(&mut opt, AtomicCounter::new(0));
+        opt.verify_ssa().unwrap();
+
+        let mut accesses = Vec::new();
+        for node in opt.node_ids() {
+            for inst in opt.block(node).ops.borrow().values() {
+                match &inst.operation {
+                    Operation::Operator(Operator::UncheckedIndex(op)) => {
+                        accesses.push((op.list.kind, false));
+                    }
+                    Operation::Operator(Operator::UncheckedIndexAssign(_)) => {
+                        accesses.push((inst.out().kind, true));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        accesses
+    }
+
+    #[test]
+    fn test_reorder_groups_independent_loads() {
+        let (a, b, out) = (
+            VariableKind::GlobalInputArray(0),
+            VariableKind::GlobalInputArray(1),
+            VariableKind::GlobalOutputArray(0),
+        );
+        assert_eq!(
+            reordered_memory_accesses(),
+            vec![
+                (a, false),
+                (a, false),
+                (b, false),
+                (b, false),
+                (out, true),
+                (a, false),
+                (out, true),
+            ]
+        );
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn phi_kernel(x: u32, cond: u32, out: &mut Array<u32>) {
+        let mut y = x;
+        if cond == 0 {
+            y = x + 4;
+            out[1] = x;
+        }
+        out[0] = y * 2;
+    }
+
+    fn optimized_phi_kernel() -> Optimizer {
+        let mut ctx = Scope::root(false);
+        let x = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalScalar(0),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+        let cond = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalScalar(1),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+        let arr = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalOutputArray(0),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+
+        phi_kernel::expand(&mut ctx, x.into(), cond.into(), arr.into());
+        Optimizer::new(ctx, CubeDim::default(), vec![], vec![])
+    }
+
+    /// Deliberately broken pass reversing the instructions of each block,
+    /// so definitions come after their uses.
+    struct ReverseInstructions;
+
+    impl OptimizerPass for ReverseInstructions {
+        fn apply_post_ssa(&mut self, opt: &mut Optimizer, changes: AtomicCounter) {
+            for node in opt.node_ids() {
+                let ops = opt.program[node].ops.clone();
+                let reversed = ops.borrow().values().rev().cloned().collect::<Vec<_>>();
+                let mut ops = ops.borrow_mut();
+                ops.clear();
+                for inst in reversed {
+                    ops.push(inst);
+                }
+                changes.inc();
+            }
+        }
+    }
+
+    /// Deliberately broken pass making the first phi entry come from the return block.
+    struct RetargetPhiEntry;
+
+    impl OptimizerPass for RetargetPhiEntry {
+        fn apply_post_ssa(&mut self, opt: &mut Optimizer, changes: AtomicCounter) {
+            for node in opt.node_ids() {
+                let phi_nodes = opt.program[node].phi_nodes.clone();
+                if let Some(phi) = phi_nodes.borrow_mut().first_mut() {
+                    phi.entries[0].block = opt.ret;
+                    changes.inc();
+                    return;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_valid_ssa() {
+        let mut opt = optimized_phi_kernel();
+        assert_eq!(opt.verify_ssa(), Ok(()));
+    }
+
+    #[test]
+    fn test_verify_use_before_def() {
+        let mut opt = optimized_phi_kernel();
+        ReverseInstructions.apply_post_ssa(&mut opt, AtomicCounter::new(0));
+
+        let err = opt.verify_ssa().unwrap_err();
+        assert!(
+            matches!(err, SsaError::UseNotDominated { block, def_block, .. } if block == def_block),
+            "Unexpected error {err:?}"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid SSA after ReverseInstructions")]
+    fn test_ssa_verification_reports_broken_pass() {
+        let mut opt = optimized_phi_kernel();
+        opt.options.verify_ssa = true;
+        ReverseInstructions.apply_post_ssa(&mut opt, AtomicCounter::new(0));
+        opt.debug_verify_ssa("ReverseInstructions");
+    }
+
+    #[test]
+    fn test_ssa_verification_disabled_by_default() {
+        let mut opt = optimized_phi_kernel();
+        ReverseInstructions.apply_post_ssa(&mut opt, AtomicCounter::new(0));
+        opt.debug_verify_ssa("ReverseInstructions");
+    }
+
+    #[test]
+    fn test_verify_phi_entry_predecessor() {
+        let mut opt = optimized_phi_kernel();
+        let changes = AtomicCounter::new(0);
+        RetargetPhiEntry.apply_post_ssa(&mut opt, changes.clone());
+        assert_eq!(changes.get(), 1, "The kernel should have a phi node");
+
+        let err = opt.verify_ssa().unwrap_err();
+        assert_eq!(
+            err,
+            SsaError::PhiEntryNotPredecessor {
+                phi: opt.program[phi_block(&opt)].phi_nodes.borrow()[0].out,
+                block: phi_block(&opt),
+                entry: opt.ret,
+            }
+        );
+    }
+
+    fn phi_block(opt: &Optimizer) -> crate::NodeIndex {
+        opt.node_ids()
+            .into_iter()
+            .find(|node| !opt.program[*node].phi_nodes.borrow().is_empty())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_folded_branch_removes_dead_block() {
+        let mut opt = optimized_phi_kernel();
+        let (header, then) = opt
+            .node_ids()
+            .into_iter()
+            .find_map(|node| match *opt.program[node].control_flow.borrow() {
+                ControlFlow::IfElse { then, .. } => Some((node, then)),
+                _ => None,
+            })
+            .expect("The kernel should have a branch");
+        let merge = phi_block(&opt);
+
+        // Fold the branch, so its `then` block is left unreachable.
+        if let ControlFlow::IfElse { cond, .. } =
+            &mut *opt.program[header].control_flow.borrow_mut()
+        {
+            *cond = Variable::constant(ConstantScalarValue::Bool(false));
+        }
+        EliminateConstBranches.apply_post_ssa(&mut opt, AtomicCounter::new(0));
+        let changes = AtomicCounter::new(0);
+        EliminateDeadBlocks.apply_post_ssa(&mut opt, changes.clone());
+
+        assert!(changes.get() > 0);
+        assert!(!opt.node_ids().contains(&then));
+        assert!(opt.node_ids().contains(&opt.ret));
+        let phi_nodes = opt.program[merge].phi_nodes.borrow().clone();
+        assert!(phi_nodes.iter().all(|phi| phi.entries.len() == 1));
+        assert_eq!(opt.verify_ssa(), Ok(()));
+    }
+
+    #[test]
+    fn test_merge_blocks_substitutes_single_entry_phi() {
+        let mut opt = optimized_phi_kernel();
+        let header = opt
+            .node_ids()
+            .into_iter()
+            .find(|node| {
+                matches!(
+                    *opt.program[*node].control_flow.borrow(),
+                    ControlFlow::IfElse { .. }
+                )
+            })
+            .expect("The kernel should have a branch");
+        let merge = phi_block(&opt);
+
+        // Fold the branch and remove its dead arm, leaving the merge block with a single
+        // predecessor and phi entry.
+        if let ControlFlow::IfElse { cond, .. } =
+            &mut *opt.program[header].control_flow.borrow_mut()
+        {
+            *cond = Variable::constant(ConstantScalarValue::Bool(false));
+        }
+        EliminateConstBranches.apply_post_ssa(&mut opt, AtomicCounter::new(0));
+        EliminateDeadBlocks.apply_post_ssa(&mut opt, AtomicCounter::new(0));
+        let phi_nodes = opt.program[merge].phi_nodes.borrow().clone();
+        assert!(!phi_nodes.is_empty());
+        assert!(phi_nodes.iter().all(|phi| phi.entries.len() == 1));
+
+        let changes = AtomicCounter::new(0);
+        MergeBlocks.apply_post_ssa(&mut opt, changes.clone());
+
+        assert!(changes.get() > 0);
+        assert!(!opt.node_ids().contains(&merge));
+        assert!(
+            opt.node_ids()
+                .iter()
+                .all(|node| opt.program[*node].phi_nodes.borrow().is_empty())
+        );
+        let reads = Rc::new(RefCell::new(Vec::new()));
+        opt.visit_all(
+            {
+                let reads = reads.clone();
+                move |_, var| reads.borrow_mut().push(*var)
+            },
+            visit_noop,
+        );
+        for phi in phi_nodes {
+            assert!(!reads.borrow().contains(&phi.out));
+            assert!(reads.borrow().contains(&phi.entries[0].value));
+        }
+        assert_eq!(opt.verify_ssa(), Ok(()));
+    }
+
+    #[test]
+    fn test_pipeline_composes_passes() {
+        let mut opt = optimized_phi_kernel();
+        let (header, then) = opt
+            .node_ids()
+            .into_iter()
+            .find_map(|node| match *opt.program[node].control_flow.borrow() {
+                ControlFlow::IfElse { then, .. } => Some((node, then)),
+                _ => None,
+            })
+            .expect("The kernel should have a branch");
+        if let ControlFlow::IfElse { cond, .. } =
+            &mut *opt.program[header].control_flow.borrow_mut()
+        {
+            *cond = Variable::constant(ConstantScalarValue::Bool(false));
+        }
+
+        // Neither pass removes the `then` block on its own: the branch must be folded before the
+        // block is unreachable.
+        let mut dead_blocks_only = PassPipeline::new().with_pass(EliminateDeadBlocks);
+        assert_eq!(dead_blocks_only.run(&mut opt), 0);
+        assert!(opt.node_ids().contains(&then));
+
+        let changes = PassPipeline::new()
+            .with_pass(EliminateConstBranches)
+            .with_pass(EliminateDeadBlocks)
+            .run(&mut opt);
+        assert!(changes > 0);
+        assert!(!opt.node_ids().contains(&then));
+        assert!(!matches!(
+            *opt.program[header].control_flow.borrow(),
+            ControlFlow::IfElse { .. }
+        ));
+        assert_eq!(opt.verify_ssa(), Ok(()));
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn loop_phi_kernel(x: u32, cond: u32, out: &mut Array<u32>) {
+        let mut y = x;
+        for i in 0..out.len() {
+            if i == cond {
+                y = x;
+                out[i] = 0;
+            }
+            out[i] += y;
+        }
+    }
+
+    /// The number of phi entries coming from a loop back edge that copy another variable.
+    fn back_edge_copies(opt: &mut Optimizer) -> usize {
+        let doms = opt.analysis::<crate::analyses::dominance::Dominators>();
+        let dominates = |header, block| {
+            doms.dominators(block)
+                .is_some_and(|mut it| it.any(|node| node == header))
+        };
+
+        let mut copies = 0;
+        for header in opt.node_ids() {
+            let is_loop = matches!(
+                *opt.program[header].control_flow.borrow(),
+                ControlFlow::Loop { .. } | ControlFlow::LoopBreak { .. }
+            );
+            if !is_loop {
+                continue;
+            }
+            for phi in opt.program[header].phi_nodes.borrow().iter() {
+                copies += phi
+                    .entries
+                    .iter()
+                    .filter(|it| dominates(header, it.block) && it.value != phi.out)
+                    .count();
+            }
+        }
+        copies
+    }
+
+    #[test]
+    fn test_loop_invariant_phi_has_no_back_edge_copy() {
+        let mut ctx = Scope::root(false);
+        let x = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalScalar(0),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+        let cond = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalScalar(1),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+        let arr = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalOutputArray(0),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+
+        loop_phi_kernel::expand(&mut ctx, x.into(), cond.into(), arr.into());
+        let mut opt = Optimizer::new(ctx, CubeDim::default(), vec![], vec![]);
+
+        // `y` is only ever `x`, so only the loop counter is copied on the back edge.
+        assert_eq!(back_edge_copies(&mut opt), 1);
+        assert_eq!(opt.verify_ssa(), Ok(()));
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn repeated_add_kernel(x: i32, out: &mut Array<i32>) {
+        let mut sum = 0i32;
+        #[unroll]
+        for _ in 0..3 {
+            sum += x;
+        }
+        out[0] = sum;
+    }
+
+    #[test]
+    fn test_unrolled_adds_become_multiply() {
+        let mut ctx = Scope::root(false);
+        let x = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalScalar(0),
+            Type::scalar(ElemType::Int(IntKind::I32)),
+        ));
+        let arr = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalOutputArray(0),
+            Type::scalar(ElemType::Int(IntKind::I32)),
+        ));
+
+        repeated_add_kernel::expand(&mut ctx, x.into(), arr.into());
+        let mut opt = Optimizer::new(ctx, CubeDim::default(), vec![], vec![]);
+
+        let mut adds = 0;
+        let mut muls = Vec::new();
+        for node in opt.node_ids() {
+            for inst in opt.block(node).ops.borrow().values() {
+                match &inst.operation {
+                    Operation::Arithmetic(Arithmetic::Add(_)) => adds += 1,
+                    Operation::Arithmetic(Arithmetic::Mul(op)) => {
+                        muls.push((op.lhs, op.rhs.as_const().map(|it| it.as_i64())))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        assert_eq!(adds, 0);
+        assert_eq!(muls, vec![(*x, Some(3))]);
+        assert_eq!(opt.verify_ssa(), Ok(()));
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn affine_index_kernel(input: &Array<u32>, out: &mut Array<u32>, base: u32, stride: u32) {
+        for i in 0..out.len() {
+            out[i] = input[base + i * stride];
+        }
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn shared_product_kernel(input: &Array<u32>, out: &mut Array<u32>, base: u32, stride: u32) {
+        for i in 0..out.len() {
+            let offset = i * stride;
+            out[i] = input[offset] + input[base + offset];
+        }
+    }
+
+    /// The number of `Mul` instructions of the optimized kernel, the increments of the phis of its
+    /// loop header, and whether all the loads of the input are indexed by one of those phis.
+    fn strength_reduced_loop(
+        expand: impl FnOnce(&mut Scope, ExpandElement, ExpandElement, [ExpandElement; 2]),
+    ) -> (usize, Vec<Variable>, bool) {
+        let mut ctx = Scope::root(false);
+        let input = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalInputArray(0),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+        let out = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalOutputArray(0),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+        let scalars = [0, 1].map(|id| {
+            ExpandElement::Plain(Variable::new(
+                VariableKind::GlobalScalar(id),
+                Type::scalar(ElemType::UInt(UIntKind::U32)),
+            ))
+        });
+
+        expand(&mut ctx, input, out, scalars);
+        let mut opt = Optimizer::new(ctx, CubeDim::default(), vec![], vec![]);
+        assert_eq!(opt.verify_ssa(), Ok(()));
+
+        let (mut muls, mut definitions, mut load_indices) = (0, HashMap::new(), Vec::new());
+        for node in opt.node_ids() {
+            for inst in opt.block(node).ops.borrow().values() {
+                match &inst.operation {
+                    Operation::Arithmetic(Arithmetic::Mul(_)) => muls += 1,
+                    Operation::Operator(Operator::Index(op) | Operator::UncheckedIndex(op))
+                        if matches!(op.list.kind, VariableKind::GlobalInputArray(_)) =>
+                    {
+                        load_indices.push(op.index);
+                    }
+                    _ => {}
+                }
+                if let Some(out) = inst.out {
+                    definitions.insert(out, inst.operation.clone());
+                }
+            }
+        }
+
+        let (mut increments, mut phis) = (Vec::new(), Vec::new());
+        for header in opt.node_ids() {
+            let is_loop = matches!(
+                *opt.program[header].control_flow.borrow(),
+                ControlFlow::LoopBreak { .. }
+            );
+            if !is_loop {
+                continue;
+            }
+            for phi in opt.program[header].phi_nodes.borrow().iter() {
+                phis.push(phi.out);
+                for entry in phi.entries.iter() {
+                    if let Some(Operation::Arithmetic(Arithmetic::Add(op))) =
+                        definitions.get(&entry.value)
+                    {
+                        let increment = match (op.lhs == phi.out, op.rhs == phi.out) {
+                            (true, false) => op.rhs,
+                            (false, true) => op.lhs,
+                            _ => continue,
+                        };
+                        increments.push(copied(&definitions, increment));
+                    }
+                }
+            }
+        }
+        let phi_indexed = load_indices
+            .into_iter()
+            .all(|index| phis.contains(&copied(&definitions, index)));
+        (muls, increments, phi_indexed)
+    }
+
+    /// The variable copied into `var`, if it's a copy.
+    fn copied(definitions: &HashMap<Variable, Operation>, var: Variable) -> Variable {
+        match definitions.get(&var) {
+            Some(Operation::Copy(value)) => copied(definitions, *value),
+            _ => var,
+        }
+    }
+
+    #[test]
+    fn test_affine_index_accumulated_in_loop() {
+        let (muls, increments, phi_indexed) =
+            strength_reduced_loop(|ctx, input, out, [base, stride]| {
+                affine_index_kernel::expand(
+                    ctx,
+                    input.into(),
+                    out.into(),
+                    base.into(),
+                    stride.into(),
+                )
+            });
+
+        // The loop counter is incremented by 1, and the index of the load by the stride.
+        let one = ElemType::UInt(UIntKind::U32).constant_from_u64(1);
+        let stride = Variable::new(
+            VariableKind::GlobalScalar(1),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        );
+        assert_eq!(muls, 0);
+        assert_eq!(increments.len(), 2);
+        assert!(increments.contains(&one) && increments.contains(&stride));
+        assert!(phi_indexed);
+    }
+
+    #[test]
+    fn test_shared_affine_product_accumulated_in_loop() {
+        let (muls, increments, phi_indexed) =
+            strength_reduced_loop(|ctx, input, out, [base, stride]| {
+                shared_product_kernel::expand(
+                    ctx,
+                    input.into(),
+                    out.into(),
+                    base.into(),
+                    stride.into(),
+                )
+            });
+
+        // The product is used twice, so it's accumulated instead of each sum.
+        assert_eq!(muls, 0);
+        assert_eq!(increments.len(), 2);
+        assert!(!phi_indexed);
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn fma_kernel(a: f32, b: f32, c: f32, out: &mut Array<f32>) {
+        out[0] = a * b + c;
+        let shared = a * c;
+        out[1] = shared + b;
+        out[2] = shared;
+    }
+
+    /// The number of `Mul`, `Add` and `Fma` instructions of the optimized kernel.
+    fn fma_kernel_ops(contract_fma: bool) -> (usize, usize, usize) {
+        let mut ctx = Scope::root(false);
+        let scalar = |id| {
+            ExpandElement::Plain(Variable::new(
+                VariableKind::GlobalScalar(id),
+                Type::scalar(ElemType::Float(FloatKind::F32)),
+            ))
+        };
+        let arr = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalOutputArray(0),
+            Type::scalar(ElemType::Float(FloatKind::F32)),
+        ));
+
+        fma_kernel::expand(
+            &mut ctx,
+            scalar(0).into(),
+            scalar(1).into(),
+            scalar(2).into(),
+            arr.into(),
+        );
+        let mut opt = OptimizerBuilder::default()
+            .with_fma_contraction(contract_fma)
+            .optimize(ctx, CubeDim::default());
+        assert_eq!(opt.verify_ssa(), Ok(()));
+
+        let (mut muls, mut adds, mut fmas) = (0, 0, 0);
+        for node in opt.node_ids() {
+            for inst in opt.block(node).ops.borrow().values() {
+                match &inst.operation {
+                    Operation::Arithmetic(Arithmetic::Mul(_)) => muls += 1,
+                    Operation::Arithmetic(Arithmetic::Add(_)) => adds += 1,
+                    Operation::Arithmetic(Arithmetic::Fma(_)) => fmas += 1,
+                    _ => {}
+                }
+            }
+        }
+        (muls, adds, fmas)
+    }
+
+    #[test]
+    fn test_contract_single_use_product_into_fma() {
+        // `a * c` is also stored on its own, so only `a * b + c` is contracted.
+        assert_eq!(fma_kernel_ops(true), (1, 1, 1));
+    }
+
+    #[test]
+    fn test_fma_contraction_disabled_by_default() {
+        assert_eq!(fma_kernel_ops(false), (2, 2, 0));
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn cast_round_trip_kernel<F: Float, G: Float>(x: F, out: &mut Array<F>) {
+        out[0] = F::cast_from(G::cast_from(x));
+    }
+
+    /// The number of casts left in the optimized kernel casting an `outer` float to `inner`
+    /// and back.
+    fn cast_round_trip_casts(outer: FloatKind, inner: FloatKind) -> usize {
+        let mut ctx = Scope::root(false);
+        // Registered as different types, so the frontend emits the casts even for the same kind.
+        ctx.register_type::<FloatExpand<0>>(ElemType::Float(outer).into());
+        ctx.register_type::<FloatExpand<1>>(ElemType::Float(inner).into());
+        let x = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalScalar(0),
+            Type::scalar(ElemType::Float(outer)),
+        ));
+        let arr = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalOutputArray(0),
+            Type::scalar(ElemType::Float(outer)),
+        ));
+
+        cast_round_trip_kernel::expand::<FloatExpand<0>, FloatExpand<1>>(
+            &mut ctx,
+            x.into(),
+            arr.into(),
+        );
+        let opt = Optimizer::new(ctx, CubeDim::default(), vec![], vec![]);
+        assert_eq!(opt.verify_ssa(), Ok(()));
+
+        opt.node_ids()
+            .into_iter()
+            .flat_map(|node| {
+                opt.block(node)
+                    .ops
+                    .borrow()
+                    .values()
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .filter(|inst| matches!(inst.operation, Operation::Operator(Operator::Cast(_))))
+            .count()
+    }
+
+    #[test]
+    fn test_identity_casts_removed() {
+        assert_eq!(cast_round_trip_casts(FloatKind::F32, FloatKind::F32), 0);
+    }
+
+    #[test]
+    fn test_lossless_cast_round_trip_removed() {
+        assert_eq!(cast_round_trip_casts(FloatKind::F16, FloatKind::F32), 0);
+    }
+
+    #[test]
+    fn test_lossy_cast_round_trip_preserved() {
+        assert_eq!(cast_round_trip_casts(FloatKind::F32, FloatKind::F16), 2);
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn sync_kernel(x: u32, out: &mut Array<u32>, #[comptime] access_between: bool) {
+        let mut shared = SharedMemory::<u32>::new(2);
+        shared[UNIT_POS] = x;
+        sync_cube();
+        if comptime![access_between] {
+            shared[UNIT_POS + 1] = x;
+        }
+        sync_cube();
+        out[UNIT_POS] = shared[0];
+    }
+
+    /// The number of cube synchronizations left in the optimized kernel.
+    fn sync_kernel_syncs(access_between: bool) -> usize {
+        let mut ctx = Scope::root(false);
+        let x = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalScalar(0),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+        let arr = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalOutputArray(0),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+
+        sync_kernel::expand(&mut ctx, x.into(), arr.into(), access_between);
+        let opt = Optimizer::new(ctx, CubeDim::default(), vec![], vec![]);
+        assert_eq!(opt.verify_ssa(), Ok(()));
+
+        opt.node_ids()
+            .into_iter()
+            .flat_map(|node| {
+                opt.block(node)
+                    .ops
+                    .borrow()
+                    .values()
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .filter(|inst| {
+                matches!(
+                    inst.operation,
+                    Operation::Synchronization(Synchronization::SyncCube)
+                )
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_back_to_back_syncs_collapse() {
+        assert_eq!(sync_kernel_syncs(false), 1);
+    }
+
+    #[test]
+    fn test_sync_after_shared_write_preserved() {
+        assert_eq!(sync_kernel_syncs(true), 2);
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn invariant_branch_kernel(x: u32, cond: u32, out: &mut Array<u32>) {
+        for i in 0..out.len() {
+            if cond == 0 {
+                out[i] = x;
+            } else {
+                out[i] = i;
+            }
+        }
+    }
+
+    /// The number of loops and branches of the optimized kernel branching on an invariant
+    /// condition in its loop.
+    fn invariant_branch_structure(unswitch_loops: bool) -> (usize, usize) {
+        let mut ctx = Scope::root(false);
+        let x = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalScalar(0),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+        let cond = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalScalar(1),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+        let arr = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalOutputArray(0),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+
+        invariant_branch_kernel::expand(&mut ctx, x.into(), cond.into(), arr.into());
+        let mut opt = OptimizerBuilder::default()
+            .with_loop_unswitching(unswitch_loops)
+            .optimize(ctx, CubeDim::default());
+        assert_eq!(opt.verify_ssa(), Ok(()));
+
+        let (mut loops, mut branches) = (0, 0);
+        for node in opt.node_ids() {
+            match *opt.program[node].control_flow.borrow() {
+                ControlFlow::Loop { .. } | ControlFlow::LoopBreak { .. } => loops += 1,
+                ControlFlow::IfElse { .. } => branches += 1,
+                _ => {}
+            }
+        }
+        (loops, branches)
+    }
+
+    #[test]
+    fn test_invariant_branch_unswitched_into_two_loops() {
+        // The only branch left selects the loop, instead of running on every iteration.
+        assert_eq!(invariant_branch_structure(true), (2, 1));
+    }
+
+    #[test]
+    fn test_loop_unswitching_disabled_by_default() {
+        assert_eq!(invariant_branch_structure(false), (1, 1));
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn unrolled_loop_kernel(x: u32, out: &mut Array<u32>, #[comptime] trip_count: u32) {
+        #[unroll]
+        for i in 0..trip_count {
+            out[i] = x + i;
+        }
+    }
+
+    #[cube]
+    fn write_at(out: &mut Array<u32>, index: u32, value: u32) {
+        out[index] = value;
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn unrolled_call_kernel(x: u32, out: &mut Array<u32>, #[comptime] trip_count: u32) {
+        #[unroll]
+        for i in 0..trip_count {
+            write_at(out, i, x);
+        }
+    }
+
+    /// The bounds of the loops of the optimized kernel, along with its number of writes and
+    /// instructions.
+    fn unrolled_loop_structure(
+        max_unroll_factor: Option<u32>,
+        expand: impl FnOnce(&mut Scope, ExpandElement, ExpandElement),
+    ) -> (Vec<u64>, usize, usize) {
+        let mut ctx = Scope::root(false);
+        ctx.max_unroll_factor = max_unroll_factor;
+        let x = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalScalar(0),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+        let arr = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalOutputArray(0),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+
+        expand(&mut ctx, x, arr);
+        let opt = OptimizerBuilder::default().optimize(ctx, CubeDim::default());
+        assert_eq!(opt.verify_ssa(), Ok(()));
+
+        let (mut bounds, mut writes, mut instructions) = (Vec::new(), 0, 0);
+        for node in opt.node_ids() {
+            let is_loop = matches!(
+                *opt.program[node].control_flow.borrow(),
+                ControlFlow::LoopBreak { .. }
+            );
+            for inst in opt.program[node].ops.borrow().values() {
+                instructions += 1;
+                match &inst.operation {
+                    Operation::Comparison(Comparison::Lower(op)) if is_loop => {
+                        bounds.extend(op.rhs.as_const().map(|it| it.as_u64()));
+                    }
+                    Operation::Operator(
+                        Operator::IndexAssign(_) | Operator::UncheckedIndexAssign(_),
+                    ) => writes += 1,
+                    _ => {}
+                }
+            }
+        }
+        bounds.sort();
+        (bounds, writes, instructions)
+    }
+
+    fn unrolled_loop(max_unroll_factor: Option<u32>, trip_count: u32) -> (Vec<u64>, usize, usize) {
+        unrolled_loop_structure(max_unroll_factor, |ctx, x, arr| {
+            unrolled_loop_kernel::expand(ctx, x.into(), arr.into(), trip_count)
+        })
+    }
+
+    #[test]
+    fn test_unrolled_loop_partially_unrolled_with_remainder() {
+        // 4 iterations of 8 copies of the body, then a remainder loop for the last 5 iterations.
+        let (bounds, writes, _) = unrolled_loop(Some(8), 37);
+        assert_eq!((bounds, writes), (vec![4, 37], 9));
+    }
+
+    #[test]
+    fn test_unrolled_loop_within_unroll_factor_fully_unrolled() {
+        let (bounds, writes, _) = unrolled_loop(Some(64), 37);
+        assert_eq!((bounds, writes), (vec![], 37));
+    }
+
+    #[test]
+    fn test_unrolled_loop_unbounded_by_default() {
+        let (bounds, writes, _) = unrolled_loop(None, 37);
+        assert_eq!((bounds, writes), (vec![], 37));
+    }
+
+    #[test]
+    fn test_large_unrolled_loop_bounded_instruction_count() {
+        let (_, _, small) = unrolled_loop(Some(8), 37);
+        let (bounds, writes, large) = unrolled_loop(Some(8), 4099);
+        assert_eq!((bounds, writes), (vec![512, 4099], 9));
+        // Only the loop bounds differ from the small loop.
+        assert_eq!(large, small);
+
+        let (_, writes, full) = unrolled_loop(None, 4099);
+        assert_eq!(writes, 4099);
+        assert!(full > 100 * large);
+    }
+
+    #[test]
+    fn test_unrolled_loop_needing_comptime_index_fully_unrolled() {
+        // The index is passed to a function, which could need it at comptime.
+        let (bounds, writes, _) = unrolled_loop_structure(Some(8), |ctx, x, arr| {
+            unrolled_call_kernel::expand(ctx, x.into(), arr.into(), 37)
+        });
+        assert_eq!((bounds, writes), (vec![], 37));
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn uniform_branch_kernel(x: u32, out: &mut Array<u32>, #[comptime] threshold: u32) {
+        if x > threshold {
+            out[0] = x;
+        }
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn divergent_branch_kernel(out: &mut Array<u32>, #[comptime] threshold: u32) {
+        if UNIT_POS > threshold {
+            out[UNIT_POS] = threshold;
+        }
+    }
+
+    /// The uniformity of the branches of the optimized kernel expanded by `expand`.
+    fn branch_uniformity(expand: impl FnOnce(&mut Scope)) -> Vec<bool> {
+        let mut ctx = Scope::root(false);
+        expand(&mut ctx);
+        let mut opt = OptimizerBuilder::default().optimize(ctx, CubeDim::default());
+        let uniformity = opt.analysis::<Uniformity>();
+        opt.node_ids()
+            .into_iter()
+            .filter(|node| {
+                matches!(
+                    *opt.program[*node].control_flow.borrow(),
+                    ControlFlow::IfElse { .. }
+                )
+            })
+            .map(|node| uniformity.is_branch_uniform(node))
+            .collect()
+    }
+
+    #[test]
+    fn test_branch_on_comptime_and_scalar_is_uniform() {
+        let uniformity = branch_uniformity(|ctx| {
+            let x = ExpandElement::Plain(Variable::new(
+                VariableKind::GlobalScalar(0),
+                Type::scalar(ElemType::UInt(UIntKind::U32)),
+            ));
+            let arr = ExpandElement::Plain(Variable::new(
+                VariableKind::GlobalOutputArray(0),
+                Type::scalar(ElemType::UInt(UIntKind::U32)),
+            ));
+            uniform_branch_kernel::expand(ctx, x.into(), arr.into(), 4);
+        });
+        assert_eq!(uniformity, vec![true]);
+    }
+
+    #[test]
+    fn test_branch_on_unit_pos_is_divergent() {
+        let uniformity = branch_uniformity(|ctx| {
+            let arr = ExpandElement::Plain(Variable::new(
+                VariableKind::GlobalOutputArray(0),
+                Type::scalar(ElemType::UInt(UIntKind::U32)),
+            ));
+            divergent_branch_kernel::expand(ctx, arr.into(), 4);
+        });
+        assert_eq!(uniformity, vec![false]);
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn small_loop_kernel(x: u32, out: &mut Array<u32>) {
+        for i in 0..3u32 {
+            out[i] = x + i;
+        }
+    }
+
+    /// The number of loops of the optimized kernel writing in a loop of 3 iterations, along with
+    /// the indices of its writes, `None` when they aren't constant.
+    fn small_loop_structure(max_inline_trip_count: Option<u32>) -> (usize, Vec<Option<u64>>) {
+        let mut ctx = Scope::root(false);
+        let x = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalScalar(0),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+        let arr = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalOutputArray(0),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+
+        small_loop_kernel::expand(&mut ctx, x.into(), arr.into());
+        let mut builder = OptimizerBuilder::default();
+        if let Some(trip_count) = max_inline_trip_count {
+            builder = builder.with_max_inline_trip_count(trip_count);
+        }
+        let mut opt = builder.optimize(ctx, CubeDim::default());
+        assert_eq!(opt.verify_ssa(), Ok(()));
+
+        let (mut loops, mut indices) = (0, Vec::new());
+        for node in opt.node_ids() {
+            if matches!(
+                *opt.program[node].control_flow.borrow(),
+                ControlFlow::LoopBreak { .. }
+            ) {
+                loops += 1;
+            }
+            for inst in opt.program[node].ops.borrow().values() {
+                if let Operation::Operator(
+                    Operator::IndexAssign(op) | Operator::UncheckedIndexAssign(op),
+                ) = &inst.operation
+                {
+                    indices.push(op.index.as_const().map(|it| it.as_u64()));
+                }
+            }
+        }
+        indices.sort();
+        (loops, indices)
+    }
+
+    #[test]
+    fn test_small_loop_inlined_with_folded_indices() {
+        assert_eq!(
+            small_loop_structure(Some(3)),
+            (0, vec![Some(0), Some(1), Some(2)])
+        );
+    }
+
+    #[test]
+    fn test_loop_longer_than_inline_trip_count_untouched() {
+        assert_eq!(small_loop_structure(Some(2)), (1, vec![None]));
+    }
+
+    #[test]
+    fn test_loop_inlining_disabled_by_default() {
+        assert_eq!(small_loop_structure(None), (1, vec![None]));
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn shared_const_index_kernel(out: &mut Array<u32>) {
+        let mut shared = SharedMemory::<u32>::new(4);
+        shared[1] = 5;
+        if shared[1] == 5 {
+            out[0] = 1;
+        } else {
+            out[0] = 2;
+        }
+    }
+
+    /// The number of branches and shared memory loads of the optimized kernel branching on a
+    /// value it stored to shared memory.
+    fn shared_const_index_structure(forward_stores: bool) -> (usize, usize) {
+        let mut ctx = Scope::root(false);
+        let arr = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalOutputArray(0),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+
+        shared_const_index_kernel::expand(&mut ctx, arr.into());
+        let mut opt = OptimizerBuilder::default()
+            .with_store_forwarding(forward_stores)
+            .optimize(ctx, CubeDim::default());
+        assert_eq!(opt.verify_ssa(), Ok(()));
+
+        let (mut branches, mut loads) = (0, 0);
+        for node in opt.node_ids() {
+            if let ControlFlow::IfElse { .. } = *opt.program[node].control_flow.borrow() {
+                branches += 1;
+            }
+            for inst in opt.block(node).ops.borrow().values() {
+                if let Operation::Operator(Operator::Index(load) | Operator::UncheckedIndex(load)) =
+                    &inst.operation
+                    && matches!(load.list.kind, VariableKind::SharedMemory { .. })
+                {
+                    loads += 1;
+                }
+            }
+        }
+        (branches, loads)
+    }
+
+    #[test]
+    fn test_forwarded_shared_load_folds_branch() {
+        assert_eq!(shared_const_index_structure(true), (0, 0));
+    }
+
+    #[test]
+    fn test_store_forwarding_disabled_by_default() {
+        assert_eq!(shared_const_index_structure(false), (1, 1));
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn tail_store_kernel(x: u32, cond: u32, out: &mut Array<u32>) {
+        if cond == 0 {
+            out[1] = x;
+            out[0] = 1;
+        } else {
+            out[1] = x + 1;
+            out[0] = 1;
+        }
+    }
+
+    #[test]
+    fn test_identical_tail_stores_merged() {
+        let mut ctx = Scope::root(false);
+        let x = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalScalar(0),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+        let cond = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalScalar(1),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+        let arr = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalOutputArray(0),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+
+        tail_store_kernel::expand(&mut ctx, x.into(), cond.into(), arr.into());
+        let opt = Optimizer::new(ctx, CubeDim::default(), vec![], vec![]);
+        assert_eq!(opt.verify_ssa(), Ok(()));
+
+        // The store to `out[0]` is left once, after both arms, which keep their own store.
+        let mut stores = Vec::new();
+        for node in opt.node_ids() {
+            for inst in opt.block(node).ops.borrow().values() {
+                if let Operation::Operator(
+                    Operator::IndexAssign(op) | Operator::UncheckedIndexAssign(op),
+                ) = &inst.operation
+                {
+                    let index = op.index.as_const().map(|it| it.as_i64());
+                    stores.push((index, opt.predecessors(node).len()));
+                }
+            }
+        }
+        stores.sort();
+        assert_eq!(stores, vec![(Some(0), 2), (Some(1), 1), (Some(1), 1)]);
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn serial_plane_sum_kernel(x: f32, out: &mut Array<f32>) {
+        let mut acc = x;
+        for i in 0..PLANE_DIM {
+            acc += plane_broadcast(x, i);
+        }
+        out[0] = acc;
+    }
+
+    /// The number of loops, broadcasts and plane sums of the optimized kernel summing the values
+    /// of the plane in a loop, and whether the stored value adds the plane sum to `x`.
+    fn serial_plane_sum_structure(plane_reductions: bool) -> (usize, usize, usize, bool) {
+        let mut ctx = Scope::root(false);
+        let x = Variable::new(
+            VariableKind::GlobalScalar(0),
+            Type::scalar(ElemType::Float(FloatKind::F32)),
+        );
+        let arr = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalOutputArray(0),
+            Type::scalar(ElemType::Float(FloatKind::F32)),
+        ));
+
+        serial_plane_sum_kernel::expand(&mut ctx, ExpandElement::Plain(x).into(), arr.into());
+        let mut opt = OptimizerBuilder::default()
+            .with_plane_reductions(plane_reductions)
+            .optimize(ctx, CubeDim::default());
+        assert_eq!(opt.verify_ssa(), Ok(()));
+
+        let (mut loops, mut broadcasts) = (0, 0);
+        let (mut sums, mut adds, mut stored) = (Vec::new(), Vec::new(), Vec::new());
+        for node in opt.node_ids() {
+            if let ControlFlow::Loop { .. } | ControlFlow::LoopBreak { .. } =
+                *opt.program[node].control_flow.borrow()
+            {
+                loops += 1;
+            }
+            for inst in opt.block(node).ops.borrow().values() {
+                match &inst.operation {
+                    Operation::Plane(Plane::Broadcast(_)) => broadcasts += 1,
+                    Operation::Plane(Plane::Sum(op)) if op.input == x => {
+                        sums.push(inst.out.unwrap())
+                    }
+                    Operation::Arithmetic(Arithmetic::Add(op)) => {
+                        adds.push((inst.out.unwrap(), op.lhs, op.rhs))
+                    }
+                    Operation::Operator(
+                        Operator::IndexAssign(op) | Operator::UncheckedIndexAssign(op),
+                    ) => stored.push(op.value),
+                    _ => {}
+                }
+            }
+        }
+        let sum_stored = sums.len() == 1
+            && stored.len() == 1
+            && adds
+                .iter()
+                .any(|&(out, lhs, rhs)| out == stored[0] && lhs == x && rhs == sums[0]);
+        (loops, broadcasts, sums.len(), sum_stored)
+    }
+
+    #[test]
+    fn test_serial_plane_sum_becomes_plane_sum() {
+        // `x + sum(broadcast(x, i))` over the plane is `x + plane_sum(x)`.
+        assert_eq!(serial_plane_sum_structure(true), (0, 0, 1, true));
+    }
+
+    #[test]
+    fn test_plane_reductions_disabled_by_default() {
+        assert_eq!(serial_plane_sum_structure(false), (1, 1, 0, false));
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn branch_min_kernel(a: u32, b: u32, scale: u32, out: &mut Array<u32>) {
+        let min = if a < b { a * scale } else { b * scale };
+        out[0] = min;
+    }
+
+    /// The number of branches, selects and multiplications of the optimized kernel computing the
+    /// minimum of two scaled values with an if-else.
+    fn branch_min_structure(max_select_arm_size: Option<u32>) -> (usize, usize, usize) {
+        let mut ctx = Scope::root(false);
+        let scalar = |id| {
+            ExpandElement::Plain(Variable::new(
+                VariableKind::GlobalScalar(id),
+                Type::scalar(ElemType::UInt(UIntKind::U32)),
+            ))
+        };
+        let arr = ExpandElement::Plain(Variable::new(
+            VariableKind::GlobalOutputArray(0),
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ));
+
+        branch_min_kernel::expand(
+            &mut ctx,
+            scalar(0).into(),
+            scalar(1).into(),
+            scalar(2).into(),
+            arr.into(),
+        );
+        let mut builder = OptimizerBuilder::default();
+        if let Some(arm_size) = max_select_arm_size {
+            builder = builder.with_max_select_arm_size(arm_size);
+        }
+        let mut opt = builder.optimize(ctx, CubeDim::default());
+        assert_eq!(opt.verify_ssa(), Ok(()));
+
+        let (mut branches, mut selects, mut muls) = (0, 0, 0);
+        for node in opt.node_ids() {
+            if let ControlFlow::IfElse { .. } = *opt.program[node].control_flow.borrow() {
+                branches += 1;
+            }
+            for inst in opt.block(node).ops.borrow().values() {
+                match &inst.operation {
+                    Operation::Operator(Operator::Select(_)) => selects += 1,
+                    Operation::Arithmetic(Arithmetic::Mul(_)) => muls += 1,
+                    _ => {}
+                }
+            }
+        }
+        (branches, selects, muls)
+    }
+
+    #[test]
+    fn test_short_branch_min_becomes_select() {
+        // Both products are computed, and the smallest one is selected.
+        assert_eq!(branch_min_structure(Some(1)), (0, 1, 2));
+    }
+
+    #[test]
+    fn test_branch_longer_than_select_arm_size_untouched() {
+        assert_eq!(branch_min_structure(Some(0)), (1, 0, 2));
+    }
+
+    #[test]
+    fn test_short_branch_to_select_disabled_by_default() {
+        assert_eq!(branch_min_structure(None), (1, 0, 2));
+    }
 }
@@ -0,0 +1,24 @@
+use crate::ControlFlowMode;
+
+/// The options of an [`Optimizer`](crate::Optimizer), selecting the form of its output and the
+/// optional passes it runs, which are all disabled by default.
+#[derive(Default, Debug, Clone)]
+pub struct OptimizerOptions {
+    /// The form of the control flow output
+    pub control_flow_mode: ControlFlowMode,
+    /// Whether to contract multiplications and additions into fused multiply-adds
+    pub contract_fma: bool,
+    /// Whether to unswitch loops branching on a loop invariant condition
+    pub unswitch_loops: bool,
+    /// Whether to forward values stored at constant indices to the loads of the same index
+    pub forward_stores: bool,
+    /// The largest trip count of the constant loops replaced by straight-line code
+    pub max_inline_trip_count: Option<u32>,
+    /// Whether to replace serial reductions over the plane by plane collectives
+    pub plane_reductions: bool,
+    /// The largest number of instructions of the if-else arms replaced by selects
+    pub max_select_arm_size: Option<u32>,
+    /// Whether to verify that the program is still in valid SSA form after every pass, and panic
+    /// with the name of the culprit pass otherwise
+    pub verify_ssa: bool,
+}
@@ -0,0 +1,100 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use cubecl_ir::{
+    Arithmetic, BinaryOperator, ElemType, FmaOperator, Instruction, Operation, Variable,
+    VariableKind,
+};
+
+use crate::{AtomicCounter, Optimizer, visit_noop};
+
+use super::OptimizerPass;
+
+/// Contract a float multiplication only used by an addition into a single fused multiply-add.
+/// Example
+/// ```rust,ignore
+/// let p = a * b;
+/// let d = p + c;
+/// ```
+/// to
+/// ```rust,ignore
+/// let d = fma(a, b, c);
+/// ```
+/// The fused operation only rounds once, so the result can differ from the separate operations.
+/// This is why it only runs when enabled with [`OptimizerBuilder::with_fma_contraction`], which
+/// the SPIR-V compiler does for the kernels whose fast math mode allows contraction.
+///
+/// [`OptimizerBuilder::with_fma_contraction`]: crate::OptimizerBuilder::with_fma_contraction
+pub struct ContractFma;
+
+impl OptimizerPass for ContractFma {
+    fn apply_post_ssa(&mut self, opt: &mut Optimizer, changes: AtomicCounter) {
+        let uses = RefCell::new(HashMap::<Variable, usize>::new());
+        opt.visit_all(
+            |_, var| *uses.borrow_mut().entry(*var).or_default() += 1,
+            visit_noop,
+        );
+        let uses = uses.into_inner();
+
+        for block in opt.node_ids() {
+            // The single use products defined so far in the block, with their index.
+            let mut products = HashMap::<Variable, (usize, BinaryOperator)>::new();
+            let ops = opt.program[block].ops.clone();
+            let indices = ops.borrow().indices().collect::<Vec<_>>();
+
+            for idx in indices {
+                let inst = ops.borrow()[idx].clone();
+                let Some(out) = inst.out else {
+                    continue;
+                };
+                if !matches!(out.elem_type(), ElemType::Float(_)) {
+                    continue;
+                }
+
+                match &inst.operation {
+                    Operation::Arithmetic(Arithmetic::Mul(op))
+                        if uses.get(&out) == Some(&1)
+                            && op.lhs.ty == out.ty
+                            && op.rhs.ty == out.ty
+                            && is_immutable(op.lhs)
+                            && is_immutable(op.rhs) =>
+                    {
+                        products.insert(out, (idx, op.clone()));
+                    }
+                    Operation::Arithmetic(Arithmetic::Add(op)) => {
+                        let (product, addend) = match (products.get(&op.lhs), products.get(&op.rhs))
+                        {
+                            (Some(product), _) if op.rhs.ty == out.ty => (product, op.rhs),
+                            (_, Some(product)) if op.lhs.ty == out.ty => (product, op.lhs),
+                            _ => continue,
+                        };
+                        let (mul_idx, mul) = product.clone();
+
+                        let fma = Arithmetic::Fma(FmaOperator {
+                            a: mul.lhs,
+                            b: mul.rhs,
+                            c: addend,
+                        });
+                        ops.borrow_mut()[idx] = Instruction::new(fma, out);
+                        ops.borrow_mut().remove(mul_idx);
+                        products.retain(|_, (it, _)| *it != mul_idx);
+                        changes.inc();
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// Whether the variable can't be reassigned between the multiplication and the addition, so the
+/// operands of the product can be moved to the addition.
+fn is_immutable(var: Variable) -> bool {
+    matches!(
+        var.kind,
+        VariableKind::LocalConst { .. }
+            | VariableKind::Versioned { .. }
+            | VariableKind::GlobalScalar(_)
+            | VariableKind::Builtin(_)
+            | VariableKind::ConstantScalar(_)
+    )
+}
@@ -11,7 +11,7 @@ use cubecl_ir::{ConstantScalarValue, Instruction, Operation, OperationReflect, V
 use petgraph::{graph::NodeIndex, visit::EdgeRef};
 
 use crate::{
-    AtomicCounter, BasicBlock, BlockUse, ControlFlow, Optimizer,
+    AtomicCounter, BasicBlock, BlockUse, ControlFlow, Optimizer, PhiInstruction,
     analyses::{liveness::Liveness, post_order::PostOrder},
     visit_noop,
 };
@@ -141,13 +141,22 @@ impl OptimizerPass for EliminateConstBranches {
 }
 
 /// Eliminates dead code blocks left over from other optimizations like branch elimination.
+///
+/// A block is dead when it can't be reached from the entry. The phi entries coming from dead
+/// blocks are removed from their successors, so they don't refer to a removed block. The return
+/// block is always kept, even if it became unreachable, since other passes rely on it.
 pub struct EliminateDeadBlocks;
 
 impl OptimizerPass for EliminateDeadBlocks {
     fn apply_post_ssa(&mut self, opt: &mut Optimizer, changes: AtomicCounter) {
         let post_order = opt.analysis::<PostOrder>().forward();
         for node in opt.node_ids() {
-            if !post_order.contains(&node) {
+            if !post_order.contains(&node) && node != opt.ret {
+                for successor in opt.successors(node) {
+                    for phi in opt.program[successor].phi_nodes.borrow_mut().iter_mut() {
+                        phi.entries.retain(|it| it.block != node);
+                    }
+                }
                 opt.program.remove_node(node);
                 changes.inc();
             }
@@ -194,7 +203,8 @@ impl OptimizerPass for EliminateDeadPhi {
 }
 
 /// Merges unnecessary basic blocks left over from constant branch evaluation and dead code
-/// elimination
+/// elimination. The phi nodes of a merged successor only have an entry from the block it's merged
+/// into, so they are removed and their uses replaced by the value of that entry.
 pub struct MergeBlocks;
 
 impl OptimizerPass for MergeBlocks {
@@ -218,6 +228,11 @@ fn merge_blocks(opt: &mut Optimizer) -> bool {
             let b_ops = block.ops.borrow().values().cloned().collect::<Vec<_>>();
             let s_ops = successor.ops.borrow().values().cloned().collect::<Vec<_>>();
 
+            // The successor only has phis when the block is its single predecessor, so they are
+            // trivially redundant and their lone value is substituted once the blocks are merged.
+            let (s_phi, single_entry_phi): (Vec<_>, Vec<_>) =
+                s_phi.into_iter().partition(|phi| phi.entries.len() != 1);
+
             new_block.phi_nodes.borrow_mut().extend(b_phi);
             new_block.phi_nodes.borrow_mut().extend(s_phi);
             new_block.ops.borrow_mut().extend(b_ops);
@@ -242,6 +257,9 @@ fn merge_blocks(opt: &mut Optimizer) -> bool {
             opt.invalidate_structure();
             opt.invalidate_analysis::<Liveness>();
             update_references(opt, successors[0], block_idx);
+            for phi in single_entry_phi {
+                substitute_phi(opt, phi);
+            }
             return true;
         }
     }
@@ -249,6 +267,19 @@ fn merge_blocks(opt: &mut Optimizer) -> bool {
     false
 }
 
+/// Replace the uses of the output of a phi node with a single entry by the value of its entry.
+fn substitute_phi(opt: &mut Optimizer, phi: PhiInstruction) {
+    let value = phi.entries[0].value;
+    opt.visit_all(
+        |_, var| {
+            if *var == phi.out {
+                *var = value;
+            }
+        },
+        visit_noop,
+    );
+}
+
 fn can_merge(opt: &mut Optimizer, block: NodeIndex, successor: NodeIndex) -> bool {
     let b_is_empty = opt.program[block].ops.borrow().is_empty()
         && opt.program[block].phi_nodes.borrow().is_empty();
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use cubecl_ir::{
+    ElemType, FloatKind, Instruction, IntKind, Operation, Operator, StorageType, UIntKind,
+    Variable, VariableKind,
+};
+
+use crate::{AtomicCounter, Optimizer};
+
+use super::OptimizerPass;
+
+/// Fold casts that can't change the value, as left behind by fusion.
+/// Example
+/// ```rust,ignore
+/// let a = cast::<f32>(x_f32);
+/// let b = cast::<f32>(x_f16);
+/// let c = cast::<f16>(b);
+/// ```
+/// to
+/// ```rust,ignore
+/// let a = x_f32;
+/// let b = cast::<f32>(x_f16);
+/// let c = x_f16;
+/// ```
+/// The copies are then inlined, and the intermediate casts removed by dead code elimination
+/// if they have no other use.
+///
+/// A round-trip is only folded when the first cast is lossless, so `f32 -> f16 -> f32` is kept
+/// since the narrowing is intended to round, while `f16 -> f32 -> f16` gives back the input.
+pub struct FoldRedundantCasts;
+
+impl OptimizerPass for FoldRedundantCasts {
+    fn apply_post_ssa(&mut self, opt: &mut Optimizer, changes: AtomicCounter) {
+        // The input of each cast with an immutable input and output.
+        let mut casts = HashMap::<Variable, Variable>::new();
+
+        for block in opt.node_ids() {
+            let ops = opt.program[block].ops.clone();
+            let indices = ops.borrow().indices().collect::<Vec<_>>();
+
+            for idx in indices {
+                let inst = ops.borrow()[idx].clone();
+                let (Operation::Operator(Operator::Cast(op)), Some(out)) =
+                    (&inst.operation, inst.out)
+                else {
+                    continue;
+                };
+                let input = op.input;
+
+                if input.ty == out.ty {
+                    ops.borrow_mut()[idx] = Instruction::new(Operation::Copy(input), out);
+                    changes.inc();
+                    continue;
+                }
+                if let Some(source) = casts.get(&input).copied()
+                    && source.ty == out.ty
+                    && is_lossless(source.storage_type(), input.storage_type())
+                {
+                    ops.borrow_mut()[idx] = Instruction::new(Operation::Copy(source), out);
+                    changes.inc();
+                    continue;
+                }
+                if is_immutable(input) && is_immutable(out) {
+                    casts.insert(out, input);
+                }
+            }
+        }
+    }
+}
+
+/// Whether every value of `from` can be represented exactly by `to`.
+fn is_lossless(from: StorageType, to: StorageType) -> bool {
+    let (StorageType::Scalar(from), StorageType::Scalar(to)) = (from, to) else {
+        return false;
+    };
+
+    match (from, to) {
+        (ElemType::Float(from), ElemType::Float(to)) => {
+            match (float_precision(from), float_precision(to)) {
+                (Some((from_exp, from_mantissa)), Some((to_exp, to_mantissa))) => {
+                    from_exp <= to_exp && from_mantissa <= to_mantissa
+                }
+                _ => false,
+            }
+        }
+        (ElemType::Int(from), ElemType::Int(to)) => int_bits(from) <= int_bits(to),
+        (ElemType::UInt(from), ElemType::UInt(to)) => uint_bits(from) <= uint_bits(to),
+        (ElemType::UInt(from), ElemType::Int(to)) => uint_bits(from) < int_bits(to),
+        (ElemType::Int(from), ElemType::Float(to)) => {
+            float_precision(to).is_some_and(|(_, mantissa)| int_bits(from) - 1 <= mantissa + 1)
+        }
+        (ElemType::UInt(from), ElemType::Float(to)) => {
+            float_precision(to).is_some_and(|(_, mantissa)| uint_bits(from) <= mantissa + 1)
+        }
+        _ => false,
+    }
+}
+
+/// The number of exponent and mantissa bits of the IEEE-like float kinds. Kinds with a relaxed
+/// or non standard precision return `None`, so they are never considered lossless.
+fn float_precision(kind: FloatKind) -> Option<(u32, u32)> {
+    match kind {
+        FloatKind::F16 => Some((5, 10)),
+        FloatKind::BF16 => Some((8, 7)),
+        FloatKind::F32 => Some((8, 23)),
+        FloatKind::F64 => Some((11, 52)),
+        _ => None,
+    }
+}
+
+fn int_bits(kind: IntKind) -> u32 {
+    match kind {
+        IntKind::I8 => 8,
+        IntKind::I16 => 16,
+        IntKind::I32 => 32,
+        IntKind::I64 => 64,
+    }
+}
+
+fn uint_bits(kind: UIntKind) -> u32 {
+    match kind {
+        UIntKind::U8 => 8,
+        UIntKind::U16 => 16,
+        UIntKind::U32 => 32,
+        UIntKind::U64 => 64,
+    }
+}
+
+/// Whether the variable can't be reassigned, so the input of the first cast can replace the
+/// second cast anywhere the intermediate value is used.
+fn is_immutable(var: Variable) -> bool {
+    matches!(
+        var.kind,
+        VariableKind::LocalConst { .. }
+            | VariableKind::Versioned { .. }
+            | VariableKind::GlobalScalar(_)
+            | VariableKind::Builtin(_)
+            | VariableKind::ConstantScalar(_)
+    )
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use cubecl_ir::{Id, Operation, Operator, Variable, VariableKind};
+
+use crate::{AtomicCounter, Optimizer};
+
+use super::OptimizerPass;
+
+/// Forward values stored at a constant index of a shared memory or local array to the loads of
+/// the same index later in the block, so the loaded value is known and can be propagated into
+/// dependent expressions and branches.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// shared[1] = 5;
+/// let x = shared[1];
+/// if x == 5 { ... }
+/// ```
+/// transforms to
+/// ```rust,ignore
+/// shared[1] = 5;
+/// let x = 5;
+/// if x == 5 { ... }
+/// ```
+/// and the branch can then be folded.
+///
+/// The store itself is kept, since other units or blocks may still load it. Only straight-line
+/// code within a single block is considered, and a known value is dropped on any synchronization,
+/// store at a non-constant index, or other instruction touching the same memory. Loads and stores
+/// with an explicit line size are never forwarded.
+pub struct ForwardConstIndexStores;
+
+impl OptimizerPass for ForwardConstIndexStores {
+    fn apply_post_ssa(&mut self, opt: &mut Optimizer, changes: AtomicCounter) {
+        for block in opt.node_ids() {
+            let ops = opt.program[block].ops.clone();
+            let indices = ops.borrow().indices().collect::<Vec<_>>();
+            // The values known to be stored at each constant index of each array in this block.
+            let mut stored = HashMap::<Array, HashMap<i64, Variable>>::new();
+
+            for idx in indices {
+                let mut inst = ops.borrow()[idx].clone();
+                match &inst.operation {
+                    Operation::Operator(
+                        Operator::IndexAssign(assign) | Operator::UncheckedIndexAssign(assign),
+                    ) => {
+                        if let Some(array) = inst.out.as_ref().and_then(Array::of) {
+                            let known = stored.entry(array).or_default();
+                            match assign.index.as_const() {
+                                Some(index) if assign.line_size == 0 => {
+                                    known.insert(index.as_i64(), assign.value);
+                                }
+                                _ => known.clear(),
+                            }
+                            continue;
+                        }
+                    }
+                    Operation::Operator(Operator::Index(load) | Operator::UncheckedIndex(load)) => {
+                        if let Some(array) = Array::of(&load.list) {
+                            let value = load
+                                .index
+                                .as_const()
+                                .filter(|_| load.line_size == 0)
+                                .and_then(|index| stored.get(&array)?.get(&index.as_i64()))
+                                .filter(|value| inst.out.is_some_and(|out| out.ty == value.ty));
+                            if let Some(value) = value {
+                                ops.borrow_mut()[idx].operation = Operation::Copy(*value);
+                                changes.inc();
+                            }
+                            continue;
+                        }
+                    }
+                    Operation::Synchronization(_) => {
+                        stored.retain(|array, _| matches!(array, Array::Local(_)));
+                        continue;
+                    }
+                    _ => {}
+                }
+
+                let mut touched = inst
+                    .out
+                    .as_ref()
+                    .and_then(Array::of)
+                    .into_iter()
+                    .collect::<Vec<_>>();
+                opt.visit_operation(&mut inst.operation, &mut inst.out, |_, var| {
+                    touched.extend(Array::of(var));
+                });
+                for array in touched {
+                    stored.remove(&array);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Array {
+    Shared(Id),
+    Local(Id),
+}
+
+impl Array {
+    /// The shared memory or local array backing the variable, if any.
+    fn of(var: &Variable) -> Option<Self> {
+        match var.kind {
+            VariableKind::SharedMemory { id, .. } => Some(Array::Shared(id)),
+            VariableKind::LocalArray { id, .. } => Some(Array::Local(id)),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{AtomicCounter, BlockUse, Optimizer};
+
+use super::{
+    OptimizerPass,
+    range_loops::{RangeLoop, copy_body, find_range_loop},
+};
+
+/// Replace range loops with a constant trip count of at most `max_trip_count` by their
+/// iterations in straight-line code, which removes the loop overhead and lets the later passes
+/// fold the index of each iteration into a constant.
+/// Example with 3 iterations
+/// ```rust,ignore
+/// for i in 0..3 {
+///     out[i] = x + i;
+/// }
+/// ```
+/// to
+/// ```rust,ignore
+/// out[0] = x;
+/// out[1] = x + 1;
+/// out[2] = x + 2;
+/// ```
+/// Unlike `#[unroll]`, which is expanded by the frontend and needs a comptime index, this works
+/// on the loops parsed from runtime range loops whose bounds happen to be constants.
+///
+/// This runs before the SSA transformation, so the copies share the loop index and the other
+/// mutable variables of the loop. Only loops with a single block body, a constant start and end,
+/// and a positive constant step are inlined.
+/// This only runs when enabled with [`OptimizerBuilder::with_max_inline_trip_count`].
+///
+/// [`OptimizerBuilder::with_max_inline_trip_count`]: crate::OptimizerBuilder::with_max_inline_trip_count
+pub struct InlineSmallLoops {
+    pub max_trip_count: u32,
+}
+
+impl OptimizerPass for InlineSmallLoops {
+    fn apply_pre_ssa(&mut self, opt: &mut Optimizer, changes: AtomicCounter) {
+        let mut inlined = false;
+        for header in opt.node_ids() {
+            // The blocks of inlined loops are removed.
+            if !opt.program.contains_node(header) {
+                continue;
+            }
+            let Some(range) = find_range_loop(opt, header) else {
+                continue;
+            };
+            if range.trip_count() <= self.max_trip_count as i64 {
+                inline(opt, range);
+                inlined = true;
+                changes.inc();
+            }
+        }
+        if inlined {
+            opt.invalidate_structure();
+        }
+    }
+}
+
+/// Append the iterations to the preheader, which then jumps straight to the merge block.
+fn inline(opt: &mut Optimizer, range: RangeLoop) {
+    let RangeLoop {
+        header,
+        preheader,
+        body,
+        merge,
+        ..
+    } = range;
+    let ops = copy_body(opt, body, range.trip_count());
+    opt.program[preheader].ops.borrow_mut().extend(ops);
+    opt.program[merge]
+        .block_use
+        .retain(|it| *it != BlockUse::Merge);
+
+    opt.program.remove_node(body);
+    opt.program.remove_node(header);
+    opt.program.add_edge(preheader, merge, 0);
+}
@@ -0,0 +1,129 @@
+use std::collections::{HashMap, HashSet};
+
+use cubecl_ir::Variable;
+
+use crate::{AtomicCounter, ControlFlow, NodeIndex, Optimizer, visit_noop};
+
+use super::OptimizerPass;
+
+/// Coalesce the phi nodes of loop headers to reduce the copies executed on each back edge.
+///
+/// Non-SSA targets resolve each phi with a copy at the end of every predecessor, so the entry
+/// coming from the back edge costs a copy per iteration, while the entry from before the loop only
+/// runs once. This removes two kinds of redundant header phis:
+///
+/// * Loop invariant phis, where the phi and the phis it transitively depends on only ever merge
+///   themselves with a single outside value, like a variable only reassigned to its initial value.
+///   The whole group is replaced by that value, so the loop carries no copy for it at all.
+/// * Phis with the same entries as another phi of the same header, which are merged into one so
+///   only a single copy runs on the back edge.
+pub struct CoalesceLoopPhis;
+
+impl OptimizerPass for CoalesceLoopPhis {
+    fn apply_post_ssa(&mut self, opt: &mut Optimizer, changes: AtomicCounter) {
+        while coalesce_loop_phis(opt) {
+            changes.inc();
+        }
+    }
+}
+
+fn coalesce_loop_phis(opt: &mut Optimizer) -> bool {
+    let mut phi_entries = HashMap::new();
+    for block in opt.node_ids() {
+        for phi in opt.program[block].phi_nodes.borrow().iter() {
+            let values = phi.entries.iter().map(|it| it.value).collect::<Vec<_>>();
+            phi_entries.insert(phi.out, values);
+        }
+    }
+
+    for header in loop_headers(opt) {
+        let phi_nodes = opt.program[header].phi_nodes.borrow().clone();
+
+        for phi in phi_nodes.iter() {
+            if let Some((group, value)) = invariant_group(&phi_entries, phi.out) {
+                replace_phis(opt, &group, value);
+                return true;
+            }
+        }
+
+        let sorted_entries = |index: usize| {
+            let mut entries = phi_nodes[index]
+                .entries
+                .iter()
+                .map(|it| (it.block, it.value))
+                .collect::<Vec<_>>();
+            entries.sort_by_key(|(block, _)| *block);
+            entries
+        };
+        for i in 0..phi_nodes.len() {
+            for j in i + 1..phi_nodes.len() {
+                if sorted_entries(i) == sorted_entries(j) {
+                    let duplicate = HashSet::from([phi_nodes[j].out]);
+                    replace_phis(opt, &duplicate, phi_nodes[i].out);
+                    return true;
+                }
+            }
+        }
+    }
+
+    false
+}
+
+fn loop_headers(opt: &Optimizer) -> Vec<NodeIndex> {
+    opt.node_ids()
+        .into_iter()
+        .filter(|block| {
+            matches!(
+                *opt.program[*block].control_flow.borrow(),
+                ControlFlow::Loop { .. } | ControlFlow::LoopBreak { .. }
+            )
+        })
+        .collect()
+}
+
+/// Find the phis reachable from `phi` through phi entries, and return them with the single
+/// outside value they merge, if there is only one.
+fn invariant_group(
+    phi_entries: &HashMap<Variable, Vec<Variable>>,
+    phi: Variable,
+) -> Option<(HashSet<Variable>, Variable)> {
+    let mut group = HashSet::from([phi]);
+    let mut outside = None;
+    let mut stack = vec![phi];
+
+    while let Some(current) = stack.pop() {
+        for value in phi_entries[&current].iter() {
+            if group.contains(value) {
+                continue;
+            }
+            if phi_entries.contains_key(value) {
+                group.insert(*value);
+                stack.push(*value);
+            } else if outside.is_some_and(|outside| outside != *value) {
+                return None;
+            } else {
+                outside = Some(*value);
+            }
+        }
+    }
+
+    outside.map(|value| (group, value))
+}
+
+/// Replace all uses of the `phis` with `value` and remove their phi nodes.
+fn replace_phis(opt: &mut Optimizer, phis: &HashSet<Variable>, value: Variable) {
+    for block in opt.node_ids() {
+        opt.program[block]
+            .phi_nodes
+            .borrow_mut()
+            .retain(|phi| !phis.contains(&phi.out));
+    }
+    opt.visit_all(
+        |_, var| {
+            if phis.contains(var) {
+                *var = value;
+            }
+        },
+        visit_noop,
+    );
+}
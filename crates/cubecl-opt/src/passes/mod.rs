@@ -1,26 +1,56 @@
 mod array_copy_propagate;
 mod composite;
 mod constant_prop;
+mod contract_fma;
 mod dead_code;
 mod expression_merge;
+mod fold_casts;
+mod forward_stores;
 mod index_merge;
+mod inline_small_loops;
 mod inlined_if_to_select;
+mod loop_phi;
+mod plane_reduce;
 mod reduce_strength;
+mod redundant_sync;
+mod reorder_memory;
+mod repeated_add;
+mod short_branch_to_select;
+mod tail_merge;
+mod unswitch_loops;
+mod vectorize_memory;
 
 pub use array_copy_propagate::*;
 pub use composite::*;
 pub use constant_prop::*;
+pub use contract_fma::*;
 pub use dead_code::*;
 pub use expression_merge::*;
+pub use fold_casts::*;
+pub use forward_stores::*;
 pub use index_merge::*;
+pub use inline_small_loops::*;
 pub use inlined_if_to_select::*;
+pub use loop_phi::*;
+pub use plane_reduce::*;
 pub use reduce_strength::*;
+pub use redundant_sync::*;
+pub use reorder_memory::*;
+pub use repeated_add::*;
+pub use short_branch_to_select::*;
+pub use tail_merge::*;
+pub use unswitch_loops::*;
+pub use vectorize_memory::*;
 
 use crate::AtomicCounter;
 
 use super::Optimizer;
 
 pub trait OptimizerPass {
+    /// The name of the pass used when debugging.
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
     #[allow(unused)]
     fn apply_pre_ssa(&mut self, opt: &mut Optimizer, changes: AtomicCounter) {}
     #[allow(unused)]
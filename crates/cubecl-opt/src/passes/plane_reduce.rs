@@ -0,0 +1,194 @@
+use cubecl_ir::{
+    Arithmetic, BinaryOperator, Builtin, Instruction, Operation, Plane, UnaryOperator, Variable,
+    VariableKind,
+};
+
+use crate::{AtomicCounter, BlockUse, Optimizer};
+
+use super::{
+    OptimizerPass,
+    range_loops::{CountedLoop, find_counted_loop},
+    unswitch_loops::is_local_const,
+};
+
+/// Replace serial reductions over the plane, which read the value of each unit with a broadcast,
+/// by the matching plane collective.
+/// Example
+/// ```rust,ignore
+/// let mut acc = init;
+/// for i in 0..PLANE_DIM {
+///     acc += plane_broadcast(x, i);
+/// }
+/// ```
+/// to
+/// ```rust,ignore
+/// let mut acc = init;
+/// acc += plane_sum(x);
+/// ```
+/// Sums, maximums and minimums are recognized, whether the loop accumulates in place or assigns
+/// the combined value back to the accumulator.
+///
+/// This is aggressive: float sums are reordered by the collective, and like the broadcasts it
+/// replaces, the collective expects every unit of the plane to take part. It runs before the SSA
+/// transformation, on loops from `0` to `PLANE_DIM` with a single block body and a step of `1`.
+/// This only runs when enabled with [`OptimizerBuilder::with_plane_reductions`].
+///
+/// [`OptimizerBuilder::with_plane_reductions`]: crate::OptimizerBuilder::with_plane_reductions
+pub struct RecognizePlaneReductions;
+
+impl OptimizerPass for RecognizePlaneReductions {
+    fn apply_pre_ssa(&mut self, opt: &mut Optimizer, changes: AtomicCounter) {
+        let mut replaced = false;
+        for header in opt.node_ids() {
+            // The blocks of replaced loops are removed.
+            if !opt.program.contains_node(header) {
+                continue;
+            }
+            let Some(counted) = find_counted_loop(opt, header) else {
+                continue;
+            };
+            let Some(reduction) = find_plane_reduction(opt, &counted) else {
+                continue;
+            };
+            replace(opt, counted, reduction);
+            replaced = true;
+            changes.inc();
+        }
+        if replaced {
+            opt.invalidate_structure();
+        }
+    }
+}
+
+/// The kind of accumulation of a serial plane reduction.
+#[derive(Clone, Copy)]
+enum Accumulation {
+    Sum,
+    Max,
+    Min,
+}
+
+/// A serial reduction of `value` over the plane into the mutable `acc`.
+struct PlaneReduction {
+    value: Variable,
+    acc: Variable,
+    accumulation: Accumulation,
+}
+
+/// The serial plane reduction computed by the loop, if its body only broadcasts the value of the
+/// unit at the loop index and accumulates it.
+fn find_plane_reduction(opt: &Optimizer, counted: &CountedLoop) -> Option<PlaneReduction> {
+    if counted.start != 0
+        || counted.step != 1
+        || counted.inclusive
+        || !matches!(counted.end.kind, VariableKind::Builtin(Builtin::PlaneDim))
+    {
+        return None;
+    }
+
+    let ops = opt.program[counted.body].ops.borrow();
+    let ops = ops.values().collect::<Vec<_>>();
+    // The last instruction increments the index.
+    let (broadcast, accumulate, assign) = match ops[..] {
+        [broadcast, accumulate, _] => (broadcast, accumulate, None),
+        [broadcast, accumulate, assign, _] => (broadcast, accumulate, Some(assign)),
+        _ => return None,
+    };
+
+    let (value, item) = match (&broadcast.operation, broadcast.out) {
+        (Operation::Plane(Plane::Broadcast(op)), Some(item))
+            if op.rhs == counted.index && is_local_const(&item) =>
+        {
+            (op.lhs, item)
+        }
+        _ => return None,
+    };
+
+    let (accumulation, op) = match &accumulate.operation {
+        Operation::Arithmetic(Arithmetic::Add(op)) => (Accumulation::Sum, op),
+        Operation::Arithmetic(Arithmetic::Max(op)) => (Accumulation::Max, op),
+        Operation::Arithmetic(Arithmetic::Min(op)) => (Accumulation::Min, op),
+        _ => return None,
+    };
+    let acc = match (op.lhs, op.rhs) {
+        (acc, rhs) if rhs == item => acc,
+        (lhs, acc) if lhs == item => acc,
+        _ => return None,
+    };
+    let combined = accumulate.out?;
+    match assign {
+        None if combined == acc => {}
+        Some(assign) if is_local_const(&combined) => {
+            if assign.out != Some(acc)
+                || !matches!(assign.operation, Operation::Copy(source) if source == combined)
+            {
+                return None;
+            }
+        }
+        _ => return None,
+    }
+
+    if !matches!(acc.kind, VariableKind::LocalMut { .. })
+        || acc == counted.index
+        || value == acc
+        || value == counted.index
+        || value.ty != acc.ty
+    {
+        return None;
+    }
+
+    Some(PlaneReduction {
+        value,
+        acc,
+        accumulation,
+    })
+}
+
+/// Accumulate the plane collective in the preheader, which then jumps straight to the merge
+/// block. The index is left at the end of the loop, as if it had run.
+fn replace(opt: &mut Optimizer, counted: CountedLoop, reduction: PlaneReduction) {
+    let CountedLoop {
+        header,
+        preheader,
+        body,
+        merge,
+        index,
+        end,
+        ..
+    } = counted;
+    let PlaneReduction {
+        value,
+        acc,
+        accumulation,
+    } = reduction;
+
+    let reduced = *opt.allocator.create_local(value.ty);
+    let input = UnaryOperator { input: value };
+    let collective = match accumulation {
+        Accumulation::Sum => Plane::Sum(input),
+        Accumulation::Max => Plane::Max(input),
+        Accumulation::Min => Plane::Min(input),
+    };
+    let combine = BinaryOperator {
+        lhs: acc,
+        rhs: reduced,
+    };
+    let combine = match accumulation {
+        Accumulation::Sum => Arithmetic::Add(combine),
+        Accumulation::Max => Arithmetic::Max(combine),
+        Accumulation::Min => Arithmetic::Min(combine),
+    };
+
+    opt.program[preheader].ops.borrow_mut().extend([
+        Instruction::new(Operation::Plane(collective), reduced),
+        Instruction::new(Operation::Arithmetic(combine), acc),
+        Instruction::new(Operation::Copy(end), index),
+    ]);
+    opt.program[merge]
+        .block_use
+        .retain(|it| *it != BlockUse::Merge);
+
+    opt.program.remove_node(body);
+    opt.program.remove_node(header);
+    opt.program.add_edge(preheader, merge, 0);
+}
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+
+use cubecl_ir::{
+    Arithmetic, Comparison, ConstantScalarValue, Instruction, Operation, Variable, VariableKind,
+};
+
+use crate::{ControlFlow, NodeIndex, Optimizer};
+
+use super::unswitch_loops::is_local_const;
+
+/// A range loop with constant bounds.
+pub(super) struct RangeLoop {
+    pub(super) header: NodeIndex,
+    pub(super) preheader: NodeIndex,
+    pub(super) body: NodeIndex,
+    pub(super) merge: NodeIndex,
+    start: i64,
+    end: ConstantScalarValue,
+    inclusive: bool,
+    step: i64,
+}
+
+impl RangeLoop {
+    pub(super) fn trip_count(&self) -> i64 {
+        let end = self.end.as_i64() + self.inclusive as i64;
+        if end <= self.start {
+            0
+        } else {
+            (end - self.start + self.step - 1) / self.step
+        }
+    }
+}
+
+/// The range loop headed by `header`, if it has a single block body and constant bounds.
+pub(super) fn find_range_loop(opt: &mut Optimizer, header: NodeIndex) -> Option<RangeLoop> {
+    let counted = find_counted_loop(opt, header)?;
+    let end = counted
+        .end
+        .as_const()
+        .filter(|it| it.try_as_i64().is_some())?;
+
+    Some(RangeLoop {
+        header: counted.header,
+        preheader: counted.preheader,
+        body: counted.body,
+        merge: counted.merge,
+        start: counted.start,
+        end,
+        inclusive: counted.inclusive,
+        step: counted.step,
+    })
+}
+
+/// A range loop with a constant start and step, whose end may only be known at runtime.
+pub(super) struct CountedLoop {
+    pub(super) header: NodeIndex,
+    pub(super) preheader: NodeIndex,
+    pub(super) body: NodeIndex,
+    pub(super) merge: NodeIndex,
+    pub(super) index: Variable,
+    pub(super) start: i64,
+    pub(super) end: Variable,
+    pub(super) inclusive: bool,
+    pub(super) step: i64,
+}
+
+/// The range loop headed by `header`, if it has a single block body, and a constant start and
+/// step.
+pub(super) fn find_counted_loop(opt: &mut Optimizer, header: NodeIndex) -> Option<CountedLoop> {
+    let ControlFlow::LoopBreak {
+        break_cond,
+        body,
+        continue_target,
+        merge,
+    } = *opt.program[header].control_flow.borrow()
+    else {
+        return None;
+    };
+    if body != continue_target
+        || opt.successors(body) != [header]
+        || opt.program[header].phi_nodes.borrow().len() != 1
+    {
+        return None;
+    }
+
+    let (index, end, inclusive) = {
+        let ops = opt.program[header].ops.borrow();
+        let [inst] = ops.values().collect::<Vec<_>>()[..] else {
+            return None;
+        };
+        if inst.out != Some(break_cond) {
+            return None;
+        }
+        match &inst.operation {
+            Operation::Comparison(Comparison::Lower(op)) => (op.lhs, op.rhs, false),
+            Operation::Comparison(Comparison::LowerEqual(op)) => (op.lhs, op.rhs, true),
+            _ => return None,
+        }
+    };
+    if !matches!(index.kind, VariableKind::LocalMut { .. }) {
+        return None;
+    }
+
+    let outside = opt
+        .predecessors(header)
+        .into_iter()
+        .filter(|it| *it != body)
+        .collect::<Vec<_>>();
+    let [preheader] = outside[..] else {
+        return None;
+    };
+    if !matches!(
+        *opt.program[preheader].control_flow.borrow(),
+        ControlFlow::None
+    ) {
+        return None;
+    }
+    let start = opt.program[preheader]
+        .ops
+        .borrow()
+        .values()
+        .filter(|inst| inst.out == Some(index))
+        .last()
+        .and_then(|inst| match inst.operation {
+            Operation::Copy(start) => start.as_const()?.try_as_i64(),
+            _ => None,
+        })?;
+
+    // The body must end by incrementing the index, and not write it anywhere else.
+    let step = {
+        let ops = opt.program[body].ops.borrow();
+        let ops = ops.values().collect::<Vec<_>>();
+        let (increment, rest) = ops.split_last()?;
+        if rest.iter().any(|inst| inst.out == Some(index)) || increment.out != Some(index) {
+            return None;
+        }
+        match &increment.operation {
+            Operation::Arithmetic(Arithmetic::Add(op)) if op.lhs == index => {
+                op.rhs.as_const()?.try_as_i64()?
+            }
+            _ => return None,
+        }
+    };
+    if step <= 0 || defines_locals_used_outside(opt, body) {
+        return None;
+    }
+
+    Some(CountedLoop {
+        header,
+        preheader,
+        body,
+        merge,
+        index,
+        start,
+        end,
+        inclusive,
+        step,
+    })
+}
+
+/// Whether the immutable locals defined by `body` are used by other blocks, which couldn't tell
+/// which copy they come from.
+fn defines_locals_used_outside(opt: &mut Optimizer, body: NodeIndex) -> bool {
+    let locals = opt.program[body]
+        .ops
+        .borrow()
+        .values()
+        .filter_map(|inst| inst.out.filter(is_local_const))
+        .collect::<Vec<_>>();
+    let mut used_outside = false;
+    for block in opt.node_ids() {
+        if block == body {
+            continue;
+        }
+        let ops = opt.program[block].ops.clone();
+        for inst in ops.borrow_mut().values_mut() {
+            opt.visit_operation(&mut inst.operation, &mut inst.out, |_, var| {
+                used_outside |= locals.contains(var);
+            });
+        }
+    }
+    used_outside
+}
+
+/// `copies` copies of the instructions of `body` in sequence, each with fresh immutable locals,
+/// sharing the mutable ones.
+pub(super) fn copy_body(opt: &mut Optimizer, body: NodeIndex, copies: i64) -> Vec<Instruction> {
+    let body_ops = opt.program[body]
+        .ops
+        .borrow()
+        .values()
+        .cloned()
+        .collect::<Vec<_>>();
+    let mut ops = Vec::with_capacity(body_ops.len() * copies as usize);
+    for _ in 0..copies {
+        let mut locals = HashMap::new();
+        for inst in body_ops.iter() {
+            if let Some(out) = inst.out.filter(is_local_const) {
+                locals
+                    .entry(out)
+                    .or_insert_with(|| *opt.allocator.create_local(out.ty));
+            }
+        }
+        for inst in body_ops.iter() {
+            let mut inst = inst.clone();
+            let rename = |_: &mut Optimizer, var: &mut Variable| {
+                if let Some(local) = locals.get(var) {
+                    *var = *local;
+                }
+            };
+            opt.visit_instruction(&mut inst, rename, rename);
+            ops.push(inst);
+        }
+    }
+    ops
+}
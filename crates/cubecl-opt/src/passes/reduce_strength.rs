@@ -21,6 +21,16 @@ use super::OptimizerPass;
 /// let b = x >> 3;
 /// let c = (x << 4) - x;
 /// ```
+///
+/// Unsigned division and modulo by other constants are replaced with a multiply-shift sequence.
+/// ```rust,ignore
+/// let q = x / 7;
+/// ```
+/// to
+/// ```rust,ignore
+/// let t = mul_hi(x, 0x24924925);
+/// let q = (t + ((x - t) >> 1)) >> 2;
+/// ```
 pub struct ReduceStrength;
 
 impl OptimizerPass for ReduceStrength {
@@ -118,6 +128,11 @@ impl OptimizerPass for ReduceStrength {
                         ));
                         changes.inc();
                     }
+                    Arithmetic::Div(op) if is_divisor_const(&op) => {
+                        let divisor = op.rhs.as_const().unwrap().as_u32();
+                        div_by_const(opt, &mut new_ops, op.lhs, divisor, inst.out());
+                        changes.inc();
+                    }
                     Arithmetic::Modulo(op) if is_pow2(op.rhs) => {
                         let const_val = op.rhs.as_const().unwrap().as_u32();
 
@@ -130,6 +145,27 @@ impl OptimizerPass for ReduceStrength {
                         ));
                         changes.inc();
                     }
+                    Arithmetic::Modulo(op) if is_divisor_const(&op) => {
+                        let divisor = op.rhs.as_const().unwrap().as_u32();
+                        let quotient = *opt.allocator.create_local(inst.ty());
+                        div_by_const(opt, &mut new_ops, op.lhs, divisor, quotient);
+                        let product = *opt.allocator.create_local(inst.ty());
+                        new_ops.push(Instruction::new(
+                            Arithmetic::Mul(BinaryOperator {
+                                lhs: quotient,
+                                rhs: divisor.into(),
+                            }),
+                            product,
+                        ));
+                        new_ops.push(Instruction::new(
+                            Arithmetic::Sub(BinaryOperator {
+                                lhs: op.lhs,
+                                rhs: product,
+                            }),
+                            inst.out(),
+                        ));
+                        changes.inc();
+                    }
                     _ => {
                         new_ops.push(inst);
                     }
@@ -147,3 +183,76 @@ fn is_pow2(var: Variable) -> bool {
             .map(|it| it.as_u32().is_power_of_two())
             .unwrap_or(false)
 }
+
+/// Whether `op` is an unsigned division of a dynamic value by a constant that isn't a power of two.
+/// Powers of two are handled with shifts and masks instead.
+fn is_divisor_const(op: &BinaryOperator) -> bool {
+    op.lhs.as_const().is_none()
+        && op.rhs.ty.elem_type() == ElemType::UInt(UIntKind::U32)
+        && op
+            .rhs
+            .as_const()
+            .map(|it| it.as_u32() > 1 && !it.as_u32().is_power_of_two())
+            .unwrap_or(false)
+}
+
+/// Magic numbers `(multiplier, shift)` used to divide any `u32` by `divisor` with
+/// `(t + ((x - t) >> 1)) >> shift`, where `t = mul_hi(x, multiplier)`.
+///
+/// See Hacker's Delight, chapter 10. The `divisor` must be greater than 1.
+fn div_magic(divisor: u32) -> (u32, u32) {
+    let log = 32 - (divisor - 1).leading_zeros();
+    let multiplier = ((1u64 << 32) * ((1u64 << log) - divisor as u64)) / divisor as u64 + 1;
+    (multiplier as u32, log - 1)
+}
+
+/// Push the instructions computing `dividend / divisor` into `ops`, writing the quotient into `out`.
+fn div_by_const(
+    opt: &mut Optimizer,
+    ops: &mut Vec<Instruction>,
+    dividend: Variable,
+    divisor: u32,
+    out: Variable,
+) {
+    let (multiplier, shift) = div_magic(divisor);
+    let high = *opt.allocator.create_local(out.ty);
+    let diff = *opt.allocator.create_local(out.ty);
+    let half = *opt.allocator.create_local(out.ty);
+    let sum = *opt.allocator.create_local(out.ty);
+
+    ops.push(Instruction::new(
+        Arithmetic::MulHi(BinaryOperator {
+            lhs: dividend,
+            rhs: multiplier.into(),
+        }),
+        high,
+    ));
+    ops.push(Instruction::new(
+        Arithmetic::Sub(BinaryOperator {
+            lhs: dividend,
+            rhs: high,
+        }),
+        diff,
+    ));
+    ops.push(Instruction::new(
+        Bitwise::ShiftRight(BinaryOperator {
+            lhs: diff,
+            rhs: 1u32.into(),
+        }),
+        half,
+    ));
+    ops.push(Instruction::new(
+        Arithmetic::Add(BinaryOperator {
+            lhs: high,
+            rhs: half,
+        }),
+        sum,
+    ));
+    ops.push(Instruction::new(
+        Bitwise::ShiftRight(BinaryOperator {
+            lhs: sum,
+            rhs: shift.into(),
+        }),
+        out,
+    ));
+}
@@ -0,0 +1,77 @@
+use cubecl_ir::{Operation, Synchronization, Variable, VariableKind};
+
+use crate::{AtomicCounter, Optimizer};
+
+use super::OptimizerPass;
+
+/// Remove synchronizations that can't order any memory access, because the same
+/// synchronization was already executed earlier in the block with no access in between.
+/// Example
+/// ```rust,ignore
+/// shared[0] = x;
+/// sync_cube();
+/// sync_cube();
+/// let y = shared[1];
+/// ```
+/// to
+/// ```rust,ignore
+/// shared[0] = x;
+/// sync_cube();
+/// let y = shared[1];
+/// ```
+///
+/// Any instruction reading or writing memory visible to other units, or issuing an async copy,
+/// is conservatively treated as a hazard. Only straight-line code within a single block is
+/// considered, so a synchronization at the start of a block is always kept.
+pub struct RemoveRedundantSyncs;
+
+impl OptimizerPass for RemoveRedundantSyncs {
+    fn apply_post_ssa(&mut self, opt: &mut Optimizer, changes: AtomicCounter) {
+        for block in opt.node_ids() {
+            let ops = opt.program[block].ops.clone();
+            let indices = ops.borrow().indices().collect::<Vec<_>>();
+            // The synchronizations executed since the last memory access in this block.
+            let mut synced = Vec::<Synchronization>::new();
+
+            for idx in indices {
+                let mut inst = ops.borrow()[idx].clone();
+                if let Operation::Synchronization(sync) = &inst.operation {
+                    if synced.contains(sync) {
+                        ops.borrow_mut().remove(idx);
+                        changes.inc();
+                    } else {
+                        synced.push(sync.clone());
+                    }
+                    continue;
+                }
+
+                if matches!(inst.operation, Operation::Barrier(_) | Operation::Tma(_)) {
+                    synced.clear();
+                    continue;
+                }
+
+                let mut accesses_memory = inst.out.as_ref().is_some_and(is_shared_with_cube);
+                opt.visit_operation(&mut inst.operation, &mut inst.out, |_, var| {
+                    accesses_memory |= is_shared_with_cube(var);
+                });
+                if accesses_memory {
+                    synced.clear();
+                }
+            }
+        }
+    }
+}
+
+/// Whether the variable is backed by memory other units of the cube can access.
+fn is_shared_with_cube(var: &Variable) -> bool {
+    matches!(
+        var.kind,
+        VariableKind::SharedMemory { .. }
+            | VariableKind::GlobalInputArray(_)
+            | VariableKind::GlobalOutputArray(_)
+            | VariableKind::TensorMapInput(_)
+            | VariableKind::TensorMapOutput(_)
+            | VariableKind::Pipeline { .. }
+            | VariableKind::Barrier { .. }
+    )
+}
@@ -0,0 +1,193 @@
+use std::{collections::HashMap, mem::take};
+
+use cubecl_ir::{Instruction, Operation, Operator, Variable};
+use petgraph::graph::NodeIndex;
+
+use crate::{AtomicCounter, Optimizer};
+
+use super::{
+    OptimizerPass,
+    vectorize_memory::{definitions, split_offset},
+};
+
+/// Group independent loads from the same array, ordered by their offset from a common base, so
+/// accesses to the same cache lines are issued back to back.
+/// Example
+/// ```rust,ignore
+/// let a0 = a[base];
+/// let b0 = b[base];
+/// let a1 = a[base + 1];
+/// let b1 = b[base + 1];
+/// ```
+/// to
+/// ```rust,ignore
+/// let a0 = a[base];
+/// let a1 = a[base + 1];
+/// let b0 = b[base];
+/// let b1 = b[base + 1];
+/// ```
+///
+/// Loads are only moved within a run of loads and pure instructions. Any store, atomic,
+/// synchronization or other side effect ends the run, so no memory access is ever moved across
+/// it. Within a run, every instruction stays after the instructions defining its operands and
+/// before those redefining them, so the instructions computing an index move along with the load.
+///
+/// This pass isn't part of the default pipeline, because issuing loads earlier lengthens the
+/// live ranges of their values, which only pays off on some backends.
+pub struct ReorderMemoryAccesses;
+
+impl OptimizerPass for ReorderMemoryAccesses {
+    fn apply_post_ssa(&mut self, opt: &mut Optimizer, changes: AtomicCounter) {
+        for block in opt.node_ids() {
+            reorder_block(opt, block, &changes);
+        }
+    }
+}
+
+fn reorder_block(opt: &mut Optimizer, block: NodeIndex, changes: &AtomicCounter) {
+    let defs = definitions(opt, block);
+    let ops = take(&mut *opt.block(block).ops.borrow_mut());
+    let mut new_ops = Vec::with_capacity(ops.capacity());
+    let mut run = Vec::new();
+
+    for (_, inst) in ops.into_iter() {
+        if load_list(&inst).is_some() || is_pure(&inst.operation) {
+            run.push(inst);
+        } else {
+            new_ops.extend(reorder_run(opt, &defs, take(&mut run), changes));
+            new_ops.push(inst);
+        }
+    }
+    new_ops.extend(reorder_run(opt, &defs, run, changes));
+
+    opt.block(block).ops.borrow_mut().extend(new_ops);
+}
+
+/// Reorder a run of loads and pure instructions, grouping the loads by array.
+fn reorder_run(
+    opt: &mut Optimizer,
+    defs: &HashMap<Variable, Operation>,
+    mut run: Vec<Instruction>,
+    changes: &AtomicCounter,
+) -> Vec<Instruction> {
+    let deps = dependencies(opt, &mut run);
+
+    // The loads grouped by array in order of first access, then by base and offset.
+    let mut groups: Vec<(Variable, Vec<(usize, Variable, u32)>)> = Vec::new();
+    for (i, inst) in run.iter().enumerate() {
+        let Some((list, index)) = load_list(inst) else {
+            continue;
+        };
+        let (base, offset) = split_offset(defs, index);
+        match groups.iter_mut().find(|(it, _)| *it == list) {
+            Some((_, loads)) => loads.push((i, base, offset)),
+            None => groups.push((list, vec![(i, base, offset)])),
+        }
+    }
+    for (_, loads) in groups.iter_mut() {
+        let mut bases = Vec::new();
+        for (_, base, _) in loads.iter() {
+            if !bases.contains(base) {
+                bases.push(*base);
+            }
+        }
+        let base_rank = |base: &Variable| bases.iter().position(|it| it == base);
+        loads.sort_by_key(|(i, base, offset)| (base_rank(base), *offset, *i));
+    }
+
+    let mut emitted = vec![false; run.len()];
+    let mut order = Vec::with_capacity(run.len());
+    let loads = groups
+        .iter()
+        .flat_map(|(_, loads)| loads.iter().map(|it| it.0));
+    for i in loads.chain(0..run.len()) {
+        emit(i, &deps, &mut emitted, &mut order);
+    }
+
+    if order.iter().enumerate().any(|(pos, i)| pos != *i) {
+        changes.inc();
+    }
+    let mut run = run.into_iter().map(Some).collect::<Vec<_>>();
+    order.into_iter().map(|i| run[i].take().unwrap()).collect()
+}
+
+/// For each instruction of the run, the earlier instructions it must stay after: those
+/// defining its operands, and those reading or defining its output.
+fn dependencies(opt: &mut Optimizer, run: &mut [Instruction]) -> Vec<Vec<usize>> {
+    let mut last_write = HashMap::<Variable, usize>::new();
+    let mut reads_since_write = HashMap::<Variable, Vec<usize>>::new();
+    let mut deps = Vec::with_capacity(run.len());
+
+    for (i, inst) in run.iter_mut().enumerate() {
+        let mut reads = Vec::new();
+        opt.visit_operation(&mut inst.operation, &mut inst.out, |_, var| {
+            reads.push(*var)
+        });
+
+        let mut inst_deps = Vec::new();
+        for var in &reads {
+            inst_deps.extend(last_write.get(var));
+        }
+        if let Some(out) = inst.out {
+            inst_deps.extend(last_write.get(&out));
+            inst_deps.extend(reads_since_write.remove(&out).unwrap_or_default());
+        }
+        for var in reads {
+            reads_since_write.entry(var).or_default().push(i);
+        }
+        if let Some(out) = inst.out {
+            last_write.insert(out, i);
+        }
+        deps.push(inst_deps);
+    }
+    deps
+}
+
+/// Emit `root` after all the instructions it depends on, unless it was already emitted.
+fn emit(root: usize, deps: &[Vec<usize>], emitted: &mut [bool], order: &mut Vec<usize>) {
+    // Iterative, since dependency chains can be as long as an unrolled loop.
+    let mut stack = vec![(root, 0)];
+    while let Some((i, next)) = stack.last_mut() {
+        let i = *i;
+        if emitted[i] {
+            stack.pop();
+        } else if let Some(&dep) = deps[i].get(*next) {
+            *next += 1;
+            if !emitted[dep] {
+                stack.push((dep, 0));
+            }
+        } else {
+            emitted[i] = true;
+            order.push(i);
+            stack.pop();
+        }
+    }
+}
+
+/// The array and index of a load from an array.
+fn load_list(inst: &Instruction) -> Option<(Variable, Variable)> {
+    match &inst.operation {
+        Operation::Operator(Operator::Index(op) | Operator::UncheckedIndex(op))
+            if op.list.is_array() =>
+        {
+            Some((op.list, op.index))
+        }
+        _ => None,
+    }
+}
+
+/// Whether the operation has no effect besides defining its output, so it can be moved
+/// anywhere its operands are defined.
+pub(crate) fn is_pure(op: &Operation) -> bool {
+    match op {
+        Operation::Copy(_)
+        | Operation::Arithmetic(_)
+        | Operation::Comparison(_)
+        | Operation::Bitwise(_) => true,
+        Operation::Operator(op) => matches!(
+            op,
+            Operator::Cast(_) | Operator::InitLine(_) | Operator::Select(_)
+        ),
+        _ => false,
+    }
+}
@@ -0,0 +1,377 @@
+use std::collections::{HashMap, HashSet};
+
+use cubecl_ir::{
+    Arithmetic, BinaryOperator, ElemType, Instruction, Operation, Variable, VariableKind,
+};
+
+use crate::{AtomicCounter, ControlFlow, NodeIndex, Optimizer, PhiInstruction, version::PhiEntry};
+
+use super::OptimizerPass;
+
+/// Collapse chains of additions of the same value into a multiplication by a constant, as left
+/// behind by unrolled loops.
+/// Example
+/// ```rust,ignore
+/// let a = x + x;
+/// let b = a + x;
+/// let c = b + x;
+/// ```
+/// to
+/// ```rust,ignore
+/// let a = x + x;
+/// let b = x * 3;
+/// let c = x * 4;
+/// ```
+/// The intermediate sums are then removed by dead code elimination if they have no other use.
+/// A single `x + x` is kept as is, since it's no more expensive than `x * 2`.
+///
+/// Only scalar integers are collapsed, since their wrapping arithmetic gives the same result for
+/// both forms, while rounding makes repeated float additions differ from the product.
+///
+/// Loops are strength reduced the same way: an affine function `base + i * stride` of an
+/// induction variable `i`, carried by a phi of the loop header and incremented by a constant `c`
+/// on each iteration, is replaced by a new phi accumulating `stride * c` on each iteration.
+/// Example
+/// ```rust,ignore
+/// for i in 0..n {
+///     out[i] = input[base + i * stride];
+/// }
+/// ```
+/// to
+/// ```rust,ignore
+/// let mut index = base + 0 * stride;
+/// let step = 1 * stride;
+/// for i in 0..n {
+///     out[i] = input[index];
+///     index += step;
+/// }
+/// ```
+/// The `base` and `stride` must be loop invariant. When the product has any other use, the
+/// product itself is accumulated instead.
+pub struct CollapseRepeatedAdds;
+
+impl OptimizerPass for CollapseRepeatedAdds {
+    fn apply_post_ssa(&mut self, opt: &mut Optimizer, changes: AtomicCounter) {
+        for block in opt.node_ids() {
+            // The `(base, count)` each variable of the block is a multiple of.
+            let mut multiples = HashMap::<Variable, (Variable, u64)>::new();
+            let ops = opt.program[block].ops.clone();
+            let indices = ops.borrow().indices().collect::<Vec<_>>();
+
+            for idx in indices {
+                let inst = ops.borrow()[idx].clone();
+                let Some(out) = inst.out else {
+                    continue;
+                };
+                if !is_scalar_int(out) || !is_immutable(out) {
+                    continue;
+                }
+
+                match &inst.operation {
+                    Operation::Arithmetic(Arithmetic::Add(op)) => {
+                        let term = |var: Variable| {
+                            multiples
+                                .get(&var)
+                                .copied()
+                                .or_else(|| is_immutable(var).then_some((var, 1)))
+                        };
+                        let (Some((lhs, lhs_count)), Some((rhs, rhs_count))) =
+                            (term(op.lhs), term(op.rhs))
+                        else {
+                            continue;
+                        };
+                        if lhs != rhs {
+                            continue;
+                        }
+
+                        let count = lhs_count.wrapping_add(rhs_count);
+                        multiples.insert(out, (lhs, count));
+                        if lhs_count > 1 || rhs_count > 1 {
+                            let mul = Arithmetic::Mul(BinaryOperator {
+                                lhs,
+                                rhs: out.elem_type().constant_from_u64(count),
+                            });
+                            ops.borrow_mut()[idx] = Instruction::new(mul, out);
+                            changes.inc();
+                        }
+                    }
+                    Operation::Arithmetic(Arithmetic::Mul(op)) => {
+                        let (count, base) = match (op.lhs.as_const(), op.rhs.as_const()) {
+                            (None, Some(val)) => (val.as_u64(), op.lhs),
+                            (Some(val), None) => (val.as_u64(), op.rhs),
+                            _ => continue,
+                        };
+                        if is_immutable(base) {
+                            multiples.insert(out, (base, count));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        for header in opt.node_ids() {
+            if reduce_induction_variables(opt, header) {
+                changes.inc();
+            }
+        }
+    }
+}
+
+/// An affine function `base + var * stride` of an induction variable, computed by the
+/// instruction at `position`.
+struct Affine {
+    position: (NodeIndex, usize),
+    out: Variable,
+    base: Option<Variable>,
+    var: Variable,
+    stride: Variable,
+}
+
+/// Strength reduce the affine functions of the induction variables of the loop headed by
+/// `header`, returning whether any was reduced.
+fn reduce_induction_variables(opt: &mut Optimizer, header: NodeIndex) -> bool {
+    let latch = match *opt.program[header].control_flow.borrow() {
+        ControlFlow::Loop {
+            continue_target, ..
+        }
+        | ControlFlow::LoopBreak {
+            continue_target, ..
+        } => continue_target,
+        _ => return false,
+    };
+    let predecessors = opt.predecessors(header);
+    let [preheader] = predecessors
+        .iter()
+        .filter(|it| **it != latch)
+        .copied()
+        .collect::<Vec<_>>()[..]
+    else {
+        return false;
+    };
+    if predecessors.len() != 2 {
+        return false;
+    }
+
+    let blocks = loop_blocks(opt, header, latch);
+    let mut definitions = HashMap::new();
+    let mut instructions = Vec::new();
+    for block in blocks {
+        for phi in opt.program[block].phi_nodes.borrow().iter() {
+            definitions.insert(phi.out, None);
+        }
+        for (idx, inst) in opt.program[block].ops.borrow().iter() {
+            if let Some(out) = inst.out {
+                definitions.insert(out, Some(inst.operation.clone()));
+                instructions.push(((block, idx), out, inst.operation.clone()));
+            }
+        }
+    }
+    let invariant = |var: Variable| {
+        var.as_const().is_some() || (is_immutable(var) && !definitions.contains_key(&var))
+    };
+
+    // The `(start, step)` of each induction variable.
+    let mut inductions = HashMap::new();
+    for phi in opt.program[header].phi_nodes.borrow().iter() {
+        let entry = |block| phi.entries.iter().find(|it| it.block == block);
+        let (Some(start), Some(next)) = (entry(preheader), entry(latch)) else {
+            continue;
+        };
+        if !is_scalar_int(phi.out) {
+            continue;
+        }
+        let step = match definitions.get(&next.value) {
+            Some(Some(Operation::Arithmetic(Arithmetic::Add(op)))) => {
+                match (op.lhs == phi.out, op.rhs == phi.out) {
+                    (true, false) => op.rhs,
+                    (false, true) => op.lhs,
+                    _ => continue,
+                }
+            }
+            _ => continue,
+        };
+        if step.as_const().is_some() {
+            inductions.insert(phi.out, (start.value, step));
+        }
+    }
+    if inductions.is_empty() {
+        return false;
+    }
+
+    let mut products = Vec::new();
+    let mut sums = Vec::new();
+    for (position, out, operation) in instructions {
+        if !is_scalar_int(out) || !is_immutable(out) {
+            continue;
+        }
+        match operation {
+            Operation::Arithmetic(Arithmetic::Mul(op)) => {
+                let (var, stride) = if inductions.contains_key(&op.lhs) {
+                    (op.lhs, op.rhs)
+                } else {
+                    (op.rhs, op.lhs)
+                };
+                if inductions.contains_key(&var) && invariant(stride) {
+                    products.push(Affine {
+                        position,
+                        out,
+                        base: None,
+                        var,
+                        stride,
+                    });
+                }
+            }
+            Operation::Arithmetic(Arithmetic::Add(op)) => {
+                sums.push((position, out, op.lhs, op.rhs));
+            }
+            _ => {}
+        }
+    }
+
+    let reads = read_counts(opt);
+    let mut reduced = Vec::new();
+    for product in products {
+        let added = sums
+            .iter()
+            .filter_map(|(position, out, lhs, rhs)| {
+                let base = match (*lhs == product.out, *rhs == product.out) {
+                    (true, false) => *rhs,
+                    (false, true) => *lhs,
+                    _ => return None,
+                };
+                invariant(base).then_some(Affine {
+                    position: *position,
+                    out: *out,
+                    base: Some(base),
+                    ..product
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // A product only used by a single sum is left to dead code elimination. Otherwise, each
+        // sum would need its own phi, so the product is accumulated instead.
+        if added.len() == 1 && reads.get(&product.out) == Some(&1) {
+            reduced.extend(added);
+        } else {
+            reduced.push(product);
+        }
+    }
+
+    let changed = !reduced.is_empty();
+    for affine in reduced {
+        let (start, step) = inductions[&affine.var];
+        accumulate(opt, header, preheader, latch, affine, start, step);
+    }
+    changed
+}
+
+/// Replace the affine function by a phi of the loop header, starting at `base + start * stride`
+/// and incremented by `step * stride` on each iteration.
+fn accumulate(
+    opt: &mut Optimizer,
+    header: NodeIndex,
+    preheader: NodeIndex,
+    latch: NodeIndex,
+    affine: Affine,
+    start: Variable,
+    step: Variable,
+) {
+    let ty = affine.out.ty;
+    let id = opt.allocator.new_local_index();
+    let acc = Variable::new(VariableKind::Versioned { id, version: 0 }, ty);
+
+    let push = |block: NodeIndex, operation: Arithmetic| {
+        let out = *opt.allocator.create_local(ty);
+        opt.program[block]
+            .ops
+            .borrow_mut()
+            .push(Instruction::new(operation, out));
+        out
+    };
+    let mul = |lhs, rhs| Arithmetic::Mul(BinaryOperator { lhs, rhs });
+    let add = |lhs, rhs| Arithmetic::Add(BinaryOperator { lhs, rhs });
+
+    let offset = push(preheader, mul(start, affine.stride));
+    let init = match affine.base {
+        Some(base) => push(preheader, add(base, offset)),
+        None => offset,
+    };
+    let increment = push(preheader, mul(step, affine.stride));
+    let next = push(latch, add(acc, increment));
+    opt.program[header]
+        .phi_nodes
+        .borrow_mut()
+        .push(PhiInstruction {
+            out: acc,
+            entries: vec![
+                PhiEntry {
+                    block: preheader,
+                    value: init,
+                },
+                PhiEntry {
+                    block: latch,
+                    value: next,
+                },
+            ],
+        });
+
+    let (block, idx) = affine.position;
+    opt.program[block].ops.borrow_mut()[idx] = Instruction::new(Operation::Copy(acc), affine.out);
+}
+
+/// The blocks of the loop from `header` to the back edge from `latch`, in program order.
+fn loop_blocks(opt: &Optimizer, header: NodeIndex, latch: NodeIndex) -> Vec<NodeIndex> {
+    let mut blocks = HashSet::from([header]);
+    let mut stack = vec![latch];
+    while let Some(block) = stack.pop() {
+        if blocks.insert(block) {
+            stack.extend(opt.predecessors(block));
+        }
+    }
+    let mut blocks = blocks.into_iter().collect::<Vec<_>>();
+    blocks.sort();
+    blocks
+}
+
+/// The number of times each variable is read by the instructions, phi nodes and branches.
+fn read_counts(opt: &mut Optimizer) -> HashMap<Variable, usize> {
+    let mut reads = HashMap::new();
+    for block in opt.node_ids() {
+        for phi in opt.program[block].phi_nodes.borrow().iter() {
+            for entry in phi.entries.iter() {
+                *reads.entry(entry.value).or_default() += 1;
+            }
+        }
+        match &*opt.program[block].control_flow.borrow() {
+            ControlFlow::IfElse { cond: var, .. }
+            | ControlFlow::Switch { value: var, .. }
+            | ControlFlow::LoopBreak {
+                break_cond: var, ..
+            } => *reads.entry(*var).or_default() += 1,
+            _ => {}
+        }
+        let ops = opt.program[block].ops.clone();
+        for inst in ops.borrow_mut().values_mut() {
+            opt.visit_operation(&mut inst.operation, &mut inst.out, |_, var| {
+                *reads.entry(*var).or_default() += 1;
+            });
+        }
+    }
+    reads
+}
+
+fn is_scalar_int(var: Variable) -> bool {
+    var.ty.line_size() == 1 && matches!(var.elem_type(), ElemType::Int(_) | ElemType::UInt(_))
+}
+
+/// Whether the variable can't be reassigned between its uses, so multiples of it stay valid.
+fn is_immutable(var: Variable) -> bool {
+    matches!(
+        var.kind,
+        VariableKind::LocalConst { .. }
+            | VariableKind::Versioned { .. }
+            | VariableKind::GlobalScalar(_)
+            | VariableKind::Builtin(_)
+    )
+}
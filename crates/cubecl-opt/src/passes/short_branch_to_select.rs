@@ -0,0 +1,103 @@
+use cubecl_ir::{Arithmetic, Operation, VariableKind};
+
+use crate::{AtomicCounter, ControlFlow, NodeIndex, Optimizer};
+
+use super::{OptimizerPass, reorder_memory::is_pure};
+
+/// Hoist the instructions of short if-else arms that only compute the values of the phis of the
+/// merge block above the branch, so both values are computed by every unit and the branch is
+/// replaced by selects.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let min = if a < b { a * scale } else { b * scale };
+/// ```
+/// to
+/// ```rust,ignore
+/// let then = a * scale;
+/// let or_else = b * scale;
+/// let min = select(a < b, then, or_else);
+/// ```
+///
+/// This avoids divergence when the arms are cheap enough to compute both. Each arm must be a
+/// single block going straight to the merge block, with at most `max_arm_size` instructions that
+/// have no effect besides defining their output, so memory accesses, plane operations and
+/// synchronizations are never hoisted. Divisions and remainders are excluded too, since the
+/// branch may guard against a division by zero. The arms are left empty, and
+/// [`EmptyBranchToSelect`](super::EmptyBranchToSelect) replaces the phis by selects.
+/// This only runs when enabled with [`OptimizerBuilder::with_max_select_arm_size`].
+///
+/// [`OptimizerBuilder::with_max_select_arm_size`]: crate::OptimizerBuilder::with_max_select_arm_size
+pub struct ShortBranchToSelect {
+    /// The largest number of instructions of an arm that is hoisted.
+    pub max_arm_size: u32,
+}
+
+impl OptimizerPass for ShortBranchToSelect {
+    fn apply_post_ssa(&mut self, opt: &mut Optimizer, changes: AtomicCounter) {
+        for block in opt.node_ids() {
+            let (then, or_else, merge) = match &*opt.program[block].control_flow.borrow() {
+                ControlFlow::IfElse {
+                    then,
+                    or_else,
+                    merge: Some(merge),
+                    ..
+                } => (*then, *or_else, *merge),
+                _ => continue,
+            };
+            if then == or_else
+                || opt.predecessors(merge).len() != 2
+                || !is_short_arm(opt, block, then, merge, self.max_arm_size)
+                || !is_short_arm(opt, block, or_else, merge, self.max_arm_size)
+            {
+                continue;
+            }
+
+            let mut hoisted = Vec::new();
+            for arm in [then, or_else] {
+                let ops = std::mem::take(&mut *opt.program[arm].ops.borrow_mut());
+                hoisted.extend(ops.into_iter().map(|(_, inst)| inst));
+            }
+            if hoisted.is_empty() {
+                continue;
+            }
+            opt.program[block].ops.borrow_mut().extend(hoisted);
+            changes.inc();
+        }
+    }
+}
+
+/// Whether `arm` is a single block branched to from `block` and going straight to `merge`, with
+/// at most `max_arm_size` instructions that can be executed by every unit.
+fn is_short_arm(
+    opt: &Optimizer,
+    block: NodeIndex,
+    arm: NodeIndex,
+    merge: NodeIndex,
+    max_arm_size: u32,
+) -> bool {
+    if arm == block
+        || arm == merge
+        || opt.predecessors(arm) != [block]
+        || opt.successors(arm) != [merge]
+        || !matches!(*opt.program[arm].control_flow.borrow(), ControlFlow::None)
+        || !opt.program[arm].phi_nodes.borrow().is_empty()
+    {
+        return false;
+    }
+
+    let ops = opt.program[arm].ops.borrow();
+    ops.num_elements() <= max_arm_size as usize
+        && ops.values().all(|inst| {
+            is_pure(&inst.operation)
+                && !matches!(
+                    inst.operation,
+                    Operation::Arithmetic(
+                        Arithmetic::Div(_) | Arithmetic::Modulo(_) | Arithmetic::Remainder(_)
+                    )
+                )
+                // Locals exempted from the SSA transformation may be assigned by both arms.
+                && !matches!(inst.out().kind, VariableKind::LocalMut { .. })
+        })
+}
@@ -0,0 +1,194 @@
+use cubecl_ir::{Instruction, Operation, Variable, VariableKind};
+
+use crate::{AtomicCounter, ControlFlow, NodeIndex, Optimizer};
+
+use super::OptimizerPass;
+
+/// Move the instructions both arms of a branch end with into the merge block, so they're only
+/// generated once.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// if cond {
+///     out[1] = x;
+///     out[0] = 1;
+/// } else {
+///     out[1] = y;
+///     out[0] = 1;
+/// }
+/// ```
+/// to
+/// ```rust,ignore
+/// if cond {
+///     out[1] = x;
+/// } else {
+///     out[1] = y;
+/// }
+/// out[0] = 1;
+/// ```
+///
+/// Each path still runs the same instructions in the same order, since the instructions are only
+/// merged when they're identical at the end of both arms. A pair defining a value in each arm is
+/// merged too when the values are only used by the same phi of the merge block, which is then
+/// replaced by the merged instruction. Instructions that need the units of a plane or cube to
+/// execute them together, such as plane operations and synchronizations, are never moved out of
+/// a branch, since more units would execute them after the merge.
+pub struct MergeBranchTails;
+
+impl OptimizerPass for MergeBranchTails {
+    fn apply_post_ssa(&mut self, opt: &mut Optimizer, changes: AtomicCounter) {
+        for block in opt.node_ids() {
+            let merge = match &*opt.program[block].control_flow.borrow() {
+                ControlFlow::IfElse {
+                    merge: Some(merge), ..
+                } => *merge,
+                _ => continue,
+            };
+            let Some((then, or_else)) = arm_ends(opt, block, merge) else {
+                continue;
+            };
+
+            let mut merged = Vec::new();
+            while let Some(inst) = merge_last(opt, then, or_else, merge) {
+                merged.push(inst);
+                changes.inc();
+            }
+            if merged.is_empty() {
+                continue;
+            }
+
+            merged.reverse();
+            let ops = opt.program[merge].ops.clone();
+            let instructions =
+                std::mem::replace(&mut *ops.borrow_mut(), merged.into_iter().collect());
+            ops.borrow_mut()
+                .extend(instructions.into_iter().map(|it| it.1));
+        }
+    }
+}
+
+/// The last blocks of the two arms of the branch in `block`, if the merge block is only reached
+/// from them and both go straight to it.
+fn arm_ends(opt: &Optimizer, block: NodeIndex, merge: NodeIndex) -> Option<(NodeIndex, NodeIndex)> {
+    let predecessors = opt.predecessors(merge);
+    let [then, or_else] = predecessors[..] else {
+        return None;
+    };
+    let is_arm_end = |arm: NodeIndex| {
+        arm != block
+            && arm != merge
+            && opt.successors(arm) == [merge]
+            && matches!(*opt.program[arm].control_flow.borrow(), ControlFlow::None)
+    };
+    (then != or_else && is_arm_end(then) && is_arm_end(or_else)).then_some((then, or_else))
+}
+
+/// Remove the last instruction of both arms and return the merged instruction, if they can be
+/// merged. A phi replaced by the merged instruction is removed from the merge block.
+fn merge_last(
+    opt: &mut Optimizer,
+    then: NodeIndex,
+    or_else: NodeIndex,
+    merge: NodeIndex,
+) -> Option<Instruction> {
+    let (then_idx, then_inst) = last_instruction(opt, then)?;
+    let (else_idx, else_inst) = last_instruction(opt, or_else)?;
+    if then_inst.operation != else_inst.operation || !can_leave_branch(&then_inst.operation) {
+        return None;
+    }
+
+    let out = match (then_inst.out, else_inst.out) {
+        (None, None) => None,
+        (Some(then_out), Some(else_out)) if then_out == else_out && !is_value(&then_out) => {
+            Some(then_out)
+        }
+        (Some(then_out), Some(else_out)) if is_value(&then_out) && is_value(&else_out) => {
+            let phi = merged_phi(opt, merge, (then, then_out), (or_else, else_out))?;
+            let phi = opt.program[merge].phi_nodes.borrow_mut().remove(phi);
+            Some(phi.out)
+        }
+        _ => return None,
+    };
+
+    opt.program[then].ops.borrow_mut().remove(then_idx);
+    opt.program[or_else].ops.borrow_mut().remove(else_idx);
+    Some(Instruction { out, ..then_inst })
+}
+
+fn last_instruction(opt: &Optimizer, block: NodeIndex) -> Option<(usize, Instruction)> {
+    let ops = opt.program[block].ops.borrow();
+    let idx = ops.indices().last()?;
+    Some((idx, ops[idx].clone()))
+}
+
+/// The index of the phi of the merge block picking between the two values, if they aren't used
+/// anywhere else.
+fn merged_phi(
+    opt: &mut Optimizer,
+    merge: NodeIndex,
+    (then, then_value): (NodeIndex, Variable),
+    (or_else, else_value): (NodeIndex, Variable),
+) -> Option<usize> {
+    let phi = opt.program[merge]
+        .phi_nodes
+        .borrow()
+        .iter()
+        .position(|phi| {
+            phi.entries.len() == 2
+                && phi.entries.iter().all(|entry| {
+                    (entry.block == then && entry.value == then_value)
+                        || (entry.block == or_else && entry.value == else_value)
+                })
+        })?;
+    (count_reads(opt, then_value) == 1 && count_reads(opt, else_value) == 1).then_some(phi)
+}
+
+/// The number of times the variable is read by the program.
+fn count_reads(opt: &mut Optimizer, var: Variable) -> usize {
+    let mut reads = 0;
+    for block in opt.node_ids() {
+        for phi in opt.program[block].phi_nodes.borrow().iter() {
+            reads += phi.entries.iter().filter(|it| it.value == var).count();
+        }
+        let ops = opt.program[block]
+            .ops
+            .borrow()
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        for mut inst in ops {
+            opt.visit_operation(&mut inst.operation, &mut inst.out, |_, read| {
+                reads += (*read == var) as usize;
+            });
+        }
+        match &*opt.program[block].control_flow.borrow() {
+            ControlFlow::IfElse { cond, .. } => reads += (*cond == var) as usize,
+            ControlFlow::LoopBreak { break_cond, .. } => reads += (*break_cond == var) as usize,
+            ControlFlow::Switch { value, .. } => reads += (*value == var) as usize,
+            _ => {}
+        }
+    }
+    reads
+}
+
+/// Whether the variable is an SSA value, defined only once.
+fn is_value(var: &Variable) -> bool {
+    matches!(
+        var.kind,
+        VariableKind::Versioned { .. } | VariableKind::LocalConst { .. }
+    )
+}
+
+/// Whether the operation can execute after the merge, where the units of both arms execute it
+/// together.
+fn can_leave_branch(op: &Operation) -> bool {
+    !matches!(
+        op,
+        Operation::Synchronization(_)
+            | Operation::Plane(_)
+            | Operation::CoopMma(_)
+            | Operation::Barrier(_)
+            | Operation::Tma(_)
+    )
+}
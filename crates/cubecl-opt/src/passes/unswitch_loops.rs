@@ -0,0 +1,451 @@
+use std::collections::{HashMap, HashSet};
+
+use cubecl_ir::{ConstantScalarValue, Operation, Operator, Variable, VariableKind};
+
+use petgraph::visit::EdgeRef;
+
+use crate::{AtomicCounter, BasicBlock, BlockUse, ControlFlow, NodeIndex, Optimizer};
+
+use super::OptimizerPass;
+
+/// The largest number of instructions of a loop that can be duplicated by unswitching it.
+const MAX_LOOP_SIZE: usize = 64;
+/// The largest number of loops unswitched in a kernel, since each one duplicates a loop.
+const MAX_UNSWITCHES: usize = 4;
+
+/// Unswitch loops containing a branch on a loop invariant condition, so the branch is taken once
+/// before the loop instead of on every iteration.
+/// Example
+/// ```rust,ignore
+/// for i in 0..n {
+///     if cond == 0 {
+///         out[i] = x;
+///     } else {
+///         out[i] = i;
+///     }
+/// }
+/// ```
+/// to
+/// ```rust,ignore
+/// if cond == 0 {
+///     for i in 0..n {
+///         out[i] = x;
+///     }
+/// } else {
+///     for i in 0..n {
+///         out[i] = i;
+///     }
+/// }
+/// ```
+/// A condition is invariant if it isn't written in the loop, or if it's computed in the loop by
+/// comparisons, logical or bitwise operations of invariant values. These are hoisted before the
+/// loop. The branch of each copy is given a constant condition, so it's removed by
+/// [`EliminateConstBranches`](super::EliminateConstBranches) after the SSA transformation.
+///
+/// This runs before the SSA transformation, so the copy can share the mutable variables of the
+/// original loop. Loops with more than [`MAX_LOOP_SIZE`] instructions are left untouched, as are
+/// those synchronizing units or using plane and cooperative operations, since units taking
+/// different copies would no longer execute them together. At most [`MAX_UNSWITCHES`] loops are
+/// unswitched per kernel, since every copy increases the size of the kernel.
+/// This only runs when enabled with [`OptimizerBuilder::with_loop_unswitching`].
+///
+/// [`OptimizerBuilder::with_loop_unswitching`]: crate::OptimizerBuilder::with_loop_unswitching
+pub struct UnswitchLoops;
+
+impl OptimizerPass for UnswitchLoops {
+    fn apply_pre_ssa(&mut self, opt: &mut Optimizer, changes: AtomicCounter) {
+        for _ in 0..MAX_UNSWITCHES {
+            let Some(candidate) = find_candidate(opt) else {
+                break;
+            };
+            unswitch(opt, candidate);
+            changes.inc();
+        }
+    }
+}
+
+/// A loop with a branch on an invariant condition.
+struct Candidate {
+    header: NodeIndex,
+    preheader: NodeIndex,
+    merge: NodeIndex,
+    /// The header and the blocks of the loop body.
+    blocks: Vec<NodeIndex>,
+    /// The block ending with the branch.
+    branch: NodeIndex,
+    cond: Variable,
+    /// The instructions computing the condition in the loop, in order.
+    hoisted: Vec<(NodeIndex, usize)>,
+}
+
+fn find_candidate(opt: &mut Optimizer) -> Option<Candidate> {
+    for header in opt.node_ids() {
+        let merge = match *opt.program[header].control_flow.borrow() {
+            ControlFlow::Loop { merge, .. } | ControlFlow::LoopBreak { merge, .. } => merge,
+            _ => continue,
+        };
+        let Some(blocks) = loop_blocks(opt, header, merge) else {
+            continue;
+        };
+        let Some(preheader) = preheader(opt, header, &blocks) else {
+            continue;
+        };
+        if !can_duplicate(opt, &blocks) {
+            continue;
+        }
+
+        let defs = definitions(opt, &blocks);
+        for &branch in blocks.iter() {
+            let cond = match *opt.program[branch].control_flow.borrow() {
+                ControlFlow::IfElse { cond, .. } if cond.as_const().is_none() => cond,
+                _ => continue,
+            };
+            let mut hoisted = Vec::new();
+            if invariant(opt, &defs, cond, &mut hoisted) {
+                return Some(Candidate {
+                    header,
+                    preheader,
+                    merge,
+                    blocks,
+                    branch,
+                    cond,
+                    hoisted,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// The blocks reachable from the `header` without leaving the loop through `merge`, or `None` if
+/// the loop can also be left by returning.
+fn loop_blocks(opt: &Optimizer, header: NodeIndex, merge: NodeIndex) -> Option<Vec<NodeIndex>> {
+    let mut blocks = vec![header];
+    let mut stack = vec![header];
+    while let Some(block) = stack.pop() {
+        for successor in opt.successors(block) {
+            if successor == opt.ret {
+                return None;
+            }
+            if successor != merge && !blocks.contains(&successor) {
+                blocks.push(successor);
+                stack.push(successor);
+            }
+        }
+    }
+    Some(blocks)
+}
+
+/// The single block entering the loop, if it has no other successor.
+fn preheader(opt: &Optimizer, header: NodeIndex, blocks: &[NodeIndex]) -> Option<NodeIndex> {
+    let outside = opt
+        .predecessors(header)
+        .into_iter()
+        .filter(|it| !blocks.contains(it))
+        .collect::<Vec<_>>();
+    let [preheader] = outside[..] else {
+        return None;
+    };
+    let is_plain = matches!(
+        *opt.program[preheader].control_flow.borrow(),
+        ControlFlow::None
+    );
+    (is_plain && opt.successors(preheader).len() == 1).then_some(preheader)
+}
+
+/// Whether the loop is small enough to be duplicated, doesn't need all units to execute it
+/// together, and only defines immutable locals used inside it.
+fn can_duplicate(opt: &mut Optimizer, blocks: &[NodeIndex]) -> bool {
+    let mut size = 0;
+    let mut locals = HashSet::new();
+    for block in blocks {
+        for inst in opt.program[*block].ops.borrow().values() {
+            size += 1;
+            match inst.operation {
+                Operation::Synchronization(_)
+                | Operation::Plane(_)
+                | Operation::CoopMma(_)
+                | Operation::Barrier(_)
+                | Operation::Tma(_) => return false,
+                _ => {}
+            }
+            if let Some(out) = inst.out.filter(is_local_const) {
+                locals.insert(out);
+            }
+        }
+    }
+    if size > MAX_LOOP_SIZE {
+        return false;
+    }
+
+    let mut used_outside = false;
+    for block in opt.node_ids() {
+        if blocks.contains(&block) {
+            continue;
+        }
+        let ops = opt.program[block].ops.clone();
+        for inst in ops.borrow_mut().values_mut() {
+            opt.visit_operation(&mut inst.operation, &mut inst.out, |_, var| {
+                used_outside |= locals.contains(var);
+            });
+        }
+    }
+    !used_outside
+}
+
+/// The instructions defining each variable in the loop.
+fn definitions(
+    opt: &Optimizer,
+    blocks: &[NodeIndex],
+) -> HashMap<Variable, Vec<(NodeIndex, usize)>> {
+    let mut defs = HashMap::<Variable, Vec<_>>::new();
+    for block in blocks {
+        for (index, inst) in opt.program[*block].ops.borrow().iter() {
+            if let Some(out) = inst.out {
+                defs.entry(out).or_default().push((*block, index));
+            }
+        }
+    }
+    defs
+}
+
+/// Whether `var` has the same value on every iteration of the loop, pushing the instructions
+/// computing it in the loop to `hoisted` after those computing their operands.
+fn invariant(
+    opt: &mut Optimizer,
+    defs: &HashMap<Variable, Vec<(NodeIndex, usize)>>,
+    var: Variable,
+    hoisted: &mut Vec<(NodeIndex, usize)>,
+) -> bool {
+    let Some(var_defs) = defs.get(&var) else {
+        return true;
+    };
+    let [(block, index)] = var_defs[..] else {
+        return false;
+    };
+    if hoisted.contains(&(block, index)) {
+        return true;
+    }
+
+    let mut inst = opt.program[block].ops.borrow()[index].clone();
+    if !is_local_const(&var) || !is_hoistable(&inst.operation) {
+        return false;
+    }
+    let mut operands = Vec::new();
+    opt.visit_operation(&mut inst.operation, &mut inst.out, |_, var| {
+        operands.push(*var)
+    });
+    for operand in operands {
+        if !invariant(opt, defs, operand, hoisted) {
+            return false;
+        }
+    }
+    hoisted.push((block, index));
+    true
+}
+
+pub(super) fn is_local_const(var: &Variable) -> bool {
+    matches!(var.kind, VariableKind::LocalConst { .. })
+}
+
+/// Whether the operation can be executed before the loop, even if the loop wouldn't have executed
+/// it, because it has no effect and can't fault.
+fn is_hoistable(op: &Operation) -> bool {
+    match op {
+        Operation::Copy(_) | Operation::Comparison(_) | Operation::Bitwise(_) => true,
+        Operation::Operator(op) => matches!(
+            op,
+            Operator::And(_) | Operator::Or(_) | Operator::Not(_) | Operator::Cast(_)
+        ),
+        _ => false,
+    }
+}
+
+fn unswitch(opt: &mut Optimizer, candidate: Candidate) {
+    let Candidate {
+        header,
+        preheader,
+        merge,
+        blocks,
+        branch,
+        cond,
+        hoisted,
+    } = candidate;
+
+    for (block, index) in hoisted.iter() {
+        let inst = opt.program[*block].ops.borrow_mut().remove(*index).unwrap();
+        opt.program[preheader].ops.borrow_mut().push(inst);
+    }
+
+    // Each loop leaves through its own merge block, so the branch before them can merge at the
+    // original one.
+    let then_merge = new_merge(opt, merge);
+    let else_merge = new_merge(opt, merge);
+
+    // Copy the blocks with fresh immutable locals, sharing the mutable ones.
+    let copies = blocks
+        .iter()
+        .map(|block| (*block, opt.program.add_node(BasicBlock::default())))
+        .collect::<HashMap<_, _>>();
+    let mut locals = HashMap::new();
+    for block in blocks.iter() {
+        for inst in opt.program[*block].ops.borrow().values() {
+            if let Some(out) = inst.out.filter(is_local_const) {
+                locals
+                    .entry(out)
+                    .or_insert_with(|| *opt.allocator.create_local(out.ty));
+            }
+        }
+    }
+    for block in blocks.iter() {
+        let copy = copies[block];
+        let original = opt.program[*block].clone();
+        let mut ops = original.ops.borrow().values().cloned().collect::<Vec<_>>();
+        for inst in ops.iter_mut() {
+            let rename = |_: &mut Optimizer, var: &mut Variable| {
+                if let Some(local) = locals.get(var) {
+                    *var = *local;
+                }
+            };
+            opt.visit_instruction(inst, rename, rename);
+        }
+        let mut phi_nodes = original.phi_nodes.borrow().clone();
+        for phi in phi_nodes.iter_mut() {
+            for entry in phi.entries.iter_mut() {
+                entry.block = copies.get(&entry.block).copied().unwrap_or(entry.block);
+            }
+        }
+        let mut control_flow = original.control_flow.borrow().clone();
+        remap_control_flow(&mut control_flow, |it| {
+            if *it == merge {
+                *it = else_merge;
+            } else if let Some(copy) = copies.get(it) {
+                *it = *copy;
+            }
+        });
+        match &mut control_flow {
+            ControlFlow::IfElse { cond: var, .. }
+            | ControlFlow::LoopBreak {
+                break_cond: var, ..
+            }
+            | ControlFlow::Switch { value: var, .. } => {
+                if let Some(local) = locals.get(var) {
+                    *var = *local;
+                }
+            }
+            _ => {}
+        }
+
+        let copy_block = &mut opt.program[copy];
+        copy_block.block_use = original.block_use.clone();
+        copy_block.ops.borrow_mut().extend(ops);
+        *copy_block.phi_nodes.borrow_mut() = phi_nodes;
+        *copy_block.control_flow.borrow_mut() = control_flow;
+
+        for successor in opt.successors(*block) {
+            let target = match copies.get(&successor) {
+                Some(copy) => *copy,
+                None if successor == merge => else_merge,
+                None => successor,
+            };
+            opt.program.add_edge(copy, target, 0);
+        }
+    }
+
+    // The original loop leaves through its own merge block too.
+    for block in blocks.iter() {
+        let edges = opt
+            .program
+            .edges(*block)
+            .filter(|it| it.target() == merge)
+            .map(|it| it.id())
+            .collect::<Vec<_>>();
+        for edge in edges {
+            opt.program.remove_edge(edge);
+            opt.program.add_edge(*block, then_merge, 0);
+        }
+        remap_control_flow(&mut opt.program[*block].control_flow.borrow_mut(), |it| {
+            if *it == merge {
+                *it = then_merge;
+            }
+        });
+    }
+
+    // Specialize each copy for its side of the branch.
+    let specialize = |opt: &mut Optimizer, block: NodeIndex, value: bool| {
+        if let ControlFlow::IfElse { cond, .. } = &mut *opt.program[block].control_flow.borrow_mut()
+        {
+            *cond = Variable::constant(ConstantScalarValue::Bool(value));
+        }
+    };
+    specialize(opt, branch, true);
+    specialize(opt, copies[&branch], false);
+
+    let copied_header = copies[&header];
+    opt.program.add_edge(preheader, copied_header, 0);
+    *opt.program[preheader].control_flow.borrow_mut() = ControlFlow::IfElse {
+        cond,
+        then: header,
+        or_else: copied_header,
+        merge: Some(merge),
+    };
+    opt.invalidate_structure();
+    // Each header is now entered from a branch, so give each loop its own preheader again.
+    opt.split_critical_edges();
+}
+
+/// Add an empty merge block leading to `merge`.
+fn new_merge(opt: &mut Optimizer, merge: NodeIndex) -> NodeIndex {
+    let block = opt.program.add_node(BasicBlock::default());
+    opt.program[block].block_use.push(BlockUse::Merge);
+    opt.program.add_edge(block, merge, 0);
+    block
+}
+
+/// Call `update` with each block referenced by the control flow.
+fn remap_control_flow(control_flow: &mut ControlFlow, mut update: impl FnMut(&mut NodeIndex)) {
+    match control_flow {
+        ControlFlow::IfElse {
+            then,
+            or_else,
+            merge,
+            ..
+        } => {
+            update(then);
+            update(or_else);
+            if let Some(merge) = merge {
+                update(merge);
+            }
+        }
+        ControlFlow::Switch {
+            default,
+            branches,
+            merge,
+            ..
+        } => {
+            update(default);
+            for branch in branches {
+                update(&mut branch.1);
+            }
+            if let Some(merge) = merge {
+                update(merge);
+            }
+        }
+        ControlFlow::Loop {
+            body,
+            continue_target,
+            merge,
+        }
+        | ControlFlow::LoopBreak {
+            body,
+            continue_target,
+            merge,
+            ..
+        } => {
+            update(body);
+            update(continue_target);
+            update(merge);
+        }
+        ControlFlow::Return | ControlFlow::None => {}
+    }
+}
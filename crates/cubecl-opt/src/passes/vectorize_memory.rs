@@ -0,0 +1,354 @@
+use std::{collections::HashMap, mem::take};
+
+use cubecl_ir::{
+    Arithmetic, BinaryOperator, Bitwise, ElemType, IndexAssignOperator, IndexOperator, Instruction,
+    LineInitOperator, Operation, Operator, Type, UIntKind, Variable,
+};
+use petgraph::graph::NodeIndex;
+use stable_vec::StableVec;
+
+use crate::{AtomicCounter, Optimizer};
+
+use super::OptimizerPass;
+
+/// The line sizes to try when fusing accesses, largest first.
+const LINE_SIZES: [u32; 2] = [4, 2];
+
+/// Fuse runs of contiguous scalar unchecked loads and stores to the same array into line
+/// accesses.
+/// Example
+/// ```rust,ignore
+/// let base = ABSOLUTE_POS * 4;
+/// out[base] = a;
+/// out[base + 1] = b;
+/// out[base + 2] = c;
+/// out[base + 3] = d;
+/// ```
+/// to
+/// ```rust,ignore
+/// out.with_line_size(4)[ABSOLUTE_POS] = Line::new(a, b, c, d);
+/// ```
+///
+/// A run is only fused when its first index is provably a multiple of the line size, and when
+/// no other memory access, synchronization or control flow happens in between.
+/// Checked accesses are left untouched, since fusing them would change the bounds check.
+///
+/// This pass isn't part of the default pipeline, because it relies on the backend honoring the
+/// `line_size` override of index operations.
+pub struct VectorizeMemory;
+
+impl OptimizerPass for VectorizeMemory {
+    fn apply_post_ssa(&mut self, opt: &mut Optimizer, changes: AtomicCounter) {
+        for block in opt.node_ids() {
+            vectorize_stores(opt, block, &changes);
+            vectorize_loads(opt, block, &changes);
+        }
+    }
+}
+
+/// A scalar access to `list` at `base + offset`, at position `pos` in the block.
+struct Access {
+    pos: usize,
+    list: Variable,
+    index: Variable,
+    base: Variable,
+    offset: u32,
+    value: Variable,
+}
+
+impl Access {
+    fn continues(&self, last: &Access) -> bool {
+        self.list == last.list && self.base == last.base && self.offset == last.offset + 1
+    }
+}
+
+fn vectorize_stores(opt: &mut Optimizer, block: NodeIndex, changes: &AtomicCounter) {
+    let defs = definitions(opt, block);
+    let ops = take(&mut *opt.block(block).ops.borrow_mut());
+    let mut runs = Vec::new();
+    let mut run: Vec<Access> = Vec::new();
+
+    for (pos, inst) in ops.iter() {
+        match &inst.operation {
+            Operation::Operator(Operator::UncheckedIndexAssign(op)) => {
+                let list = inst.out();
+                if !is_scalar_access(list, op.value, op.line_size) {
+                    runs.push(take(&mut run));
+                    continue;
+                }
+                let (base, offset) = split_offset(&defs, op.index);
+                let access = Access {
+                    pos,
+                    list,
+                    index: op.index,
+                    base,
+                    offset,
+                    value: op.value,
+                };
+                if !run.last().is_some_and(|last| access.continues(last)) {
+                    runs.push(take(&mut run));
+                }
+                run.push(access);
+            }
+            op if is_transparent(op) => {}
+            _ => runs.push(take(&mut run)),
+        }
+    }
+    runs.push(run);
+
+    let mut removed = Vec::new();
+    let mut replaced = HashMap::new();
+    for run in runs {
+        for (chunk, line_size) in fusable_chunks(&defs, &run) {
+            let last = chunk.last().unwrap();
+            let list = last.list;
+            let line_ty = list.ty.line(line_size);
+            let line = *opt.allocator.create_local(line_ty);
+            let index = *opt.allocator.create_local(chunk[0].index.ty);
+
+            let fused = vec![
+                Instruction::new(
+                    Operator::InitLine(LineInitOperator {
+                        inputs: chunk.iter().map(|it| it.value).collect(),
+                    }),
+                    line,
+                ),
+                line_index(chunk[0].index, line_size, index),
+                Instruction::new(
+                    Operator::UncheckedIndexAssign(IndexAssignOperator {
+                        index,
+                        value: line,
+                        line_size,
+                        unroll_factor: 1,
+                    }),
+                    list,
+                ),
+            ];
+            removed.extend(chunk[..chunk.len() - 1].iter().map(|it| it.pos));
+            replaced.insert(last.pos, fused);
+            changes.inc();
+        }
+    }
+
+    rebuild(opt, block, ops, removed, replaced);
+}
+
+fn vectorize_loads(opt: &mut Optimizer, block: NodeIndex, changes: &AtomicCounter) {
+    let defs = definitions(opt, block);
+    let ops = take(&mut *opt.block(block).ops.borrow_mut());
+    let mut runs = Vec::new();
+    let mut run: Vec<Access> = Vec::new();
+
+    for (pos, inst) in ops.iter() {
+        match &inst.operation {
+            Operation::Operator(Operator::UncheckedIndex(op)) => {
+                let out = inst.out();
+                if !is_scalar_access(op.list, out, op.line_size) {
+                    runs.push(take(&mut run));
+                    continue;
+                }
+                let (base, offset) = split_offset(&defs, op.index);
+                let access = Access {
+                    pos,
+                    list: op.list,
+                    index: op.index,
+                    base,
+                    offset,
+                    value: out,
+                };
+                if !run.last().is_some_and(|last| access.continues(last)) {
+                    runs.push(take(&mut run));
+                }
+                run.push(access);
+            }
+            op if is_transparent(op) => {}
+            _ => runs.push(take(&mut run)),
+        }
+    }
+    runs.push(run);
+
+    let mut replaced = HashMap::new();
+    for run in runs {
+        for (chunk, line_size) in fusable_chunks(&defs, &run) {
+            let first = &chunk[0];
+            let line_ty = first.list.ty.line(line_size);
+            let line = *opt.allocator.create_local(line_ty);
+            let index = *opt.allocator.create_local(first.index.ty);
+
+            // The indices of the later accesses are defined before them, but not necessarily
+            // before the first one, so the line is loaded from the index of the first access.
+            replaced.insert(
+                first.pos,
+                vec![
+                    line_index(first.index, line_size, index),
+                    Instruction::new(
+                        Operator::UncheckedIndex(IndexOperator {
+                            list: first.list,
+                            index,
+                            line_size,
+                            unroll_factor: 1,
+                        }),
+                        line,
+                    ),
+                    extract(line, 0, first.value),
+                ],
+            );
+            for (k, access) in chunk.iter().enumerate().skip(1) {
+                replaced.insert(access.pos, vec![extract(line, k as u32, access.value)]);
+            }
+            changes.inc();
+        }
+    }
+
+    rebuild(opt, block, ops, Vec::new(), replaced);
+}
+
+/// Map each variable defined in `block` to the operation defining it.
+pub(super) fn definitions(opt: &Optimizer, block: NodeIndex) -> HashMap<Variable, Operation> {
+    opt.block(block)
+        .ops
+        .borrow()
+        .values()
+        .filter_map(|inst| Some((inst.out?, inst.operation.clone())))
+        .collect()
+}
+
+/// Whether this is a scalar access to a scalar array, with a scalar value.
+fn is_scalar_access(list: Variable, value: Variable, line_size: u32) -> bool {
+    list.is_array() && line_size <= 1 && matches!(list.ty, Type::Scalar(_)) && value.ty == list.ty
+}
+
+/// Whether the operation can't access memory or affect control flow, so it can be kept inside a
+/// run of fused accesses.
+fn is_transparent(op: &Operation) -> bool {
+    match op {
+        Operation::Copy(_)
+        | Operation::Arithmetic(_)
+        | Operation::Comparison(_)
+        | Operation::Bitwise(_) => true,
+        Operation::Operator(op) => matches!(
+            op,
+            Operator::Cast(_) | Operator::InitLine(_) | Operator::Select(_)
+        ),
+        Operation::NonSemantic(_) => true,
+        _ => false,
+    }
+}
+
+/// Split an index into a dynamic base and a constant offset, looking through nested additions
+/// of constants.
+pub(super) fn split_offset(
+    defs: &HashMap<Variable, Operation>,
+    index: Variable,
+) -> (Variable, u32) {
+    if let Some(Operation::Arithmetic(Arithmetic::Add(op))) = defs.get(&index) {
+        match (op.lhs.as_const(), op.rhs.as_const()) {
+            (None, Some(offset)) if is_u32(op.rhs) => {
+                let (base, inner) = split_offset(defs, op.lhs);
+                return (base, inner.wrapping_add(offset.as_u32()));
+            }
+            (Some(offset), None) if is_u32(op.lhs) => {
+                let (base, inner) = split_offset(defs, op.rhs);
+                return (base, inner.wrapping_add(offset.as_u32()));
+            }
+            _ => {}
+        }
+    }
+    (index, 0)
+}
+
+/// The largest power of two `base` is known to be a multiple of.
+fn alignment(defs: &HashMap<Variable, Operation>, base: Variable) -> u32 {
+    let const_alignment = |var: Variable| var.as_const().map(|it| pow2_factor(it.as_u32()));
+    match defs.get(&base) {
+        Some(Operation::Arithmetic(Arithmetic::Mul(op))) => const_alignment(op.lhs)
+            .or(const_alignment(op.rhs))
+            .unwrap_or(1),
+        Some(Operation::Bitwise(Bitwise::ShiftLeft(op))) => op
+            .rhs
+            .as_const()
+            .map(|it| 1u32.checked_shl(it.as_u32()).unwrap_or(0))
+            .unwrap_or(1),
+        _ => 1,
+    }
+}
+
+/// The largest power of two `value` is a multiple of, or 0 if every power of two is.
+fn pow2_factor(value: u32) -> u32 {
+    value & value.wrapping_neg()
+}
+
+/// Split a run of contiguous accesses into aligned chunks that can be fused, along with the
+/// line size of each chunk.
+fn fusable_chunks<'a>(
+    defs: &HashMap<Variable, Operation>,
+    run: &'a [Access],
+) -> Vec<(&'a [Access], u32)> {
+    let Some(first) = run.first() else {
+        return Vec::new();
+    };
+    let base_alignment = alignment(defs, first.base);
+    let mut chunks = Vec::new();
+    let mut i = 0;
+    while is_u32(first.index) && i < run.len() {
+        let line_size = LINE_SIZES.into_iter().find(|&size| {
+            i + size as usize <= run.len()
+                && run[i].offset % size == 0
+                && (base_alignment == 0 || base_alignment % size == 0)
+        });
+        match line_size {
+            Some(size) => {
+                chunks.push((&run[i..i + size as usize], size));
+                i += size as usize;
+            }
+            None => i += 1,
+        }
+    }
+    chunks
+}
+
+/// `out = index / line_size`, which is exact since the index is aligned.
+fn line_index(index: Variable, line_size: u32, out: Variable) -> Instruction {
+    Instruction::new(
+        Bitwise::ShiftRight(BinaryOperator {
+            lhs: index,
+            rhs: line_size.trailing_zeros().into(),
+        }),
+        out,
+    )
+}
+
+/// `out = line[k]`
+fn extract(line: Variable, k: u32, out: Variable) -> Instruction {
+    Instruction::new(
+        Operator::Index(IndexOperator {
+            list: line,
+            index: k.into(),
+            line_size: 0,
+            unroll_factor: 1,
+        }),
+        out,
+    )
+}
+
+fn is_u32(var: Variable) -> bool {
+    var.ty.elem_type() == ElemType::UInt(UIntKind::U32)
+}
+
+fn rebuild(
+    opt: &mut Optimizer,
+    block: NodeIndex,
+    ops: StableVec<Instruction>,
+    removed: Vec<usize>,
+    mut replaced: HashMap<usize, Vec<Instruction>>,
+) {
+    let mut new_ops = Vec::with_capacity(ops.capacity());
+    for (pos, inst) in ops.into_iter() {
+        if let Some(fused) = replaced.remove(&pos) {
+            new_ops.extend(fused);
+        } else if !removed.contains(&pos) {
+            new_ops.push(inst);
+        }
+    }
+    opt.block(block).ops.borrow_mut().extend(new_ops);
+}
@@ -0,0 +1,61 @@
+use crate::{AtomicCounter, Optimizer, passes::OptimizerPass};
+
+/// A sequence of passes run in a chosen order on an optimized program, to experiment with or
+/// tune the optimizations of a backend without editing the optimizer.
+///
+/// The passes run on the SSA form of the program, like the ones run by [`Optimizer::new`] after
+/// the SSA transformation, so the pipeline should be run on an optimizer built with
+/// [`Optimizer::new`] or [`OptimizerBuilder`](crate::OptimizerBuilder).
+/// Example
+/// ```rust,ignore
+/// let changes = PassPipeline::new()
+///     .with_pass(EliminateConstBranches)
+///     .with_pass(EliminateDeadBlocks)
+///     .run(&mut opt);
+/// ```
+#[derive(Default)]
+pub struct PassPipeline {
+    passes: Vec<Box<dyn OptimizerPass>>,
+    max_rounds: Option<usize>,
+}
+
+impl PassPipeline {
+    /// Create an empty pipeline
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a pass to run after the ones already added
+    pub fn with_pass(mut self, pass: impl OptimizerPass + 'static) -> Self {
+        self.passes.push(Box::new(pass));
+        self
+    }
+
+    /// Limit the number of times the passes are run, by default they run until none of them
+    /// makes any change
+    pub fn with_max_rounds(mut self, max_rounds: usize) -> Self {
+        self.max_rounds = Some(max_rounds);
+        self
+    }
+
+    /// Run the passes in order on `opt`, repeating the whole sequence until none of them makes
+    /// any change, and return the total number of changes.
+    pub fn run(&mut self, opt: &mut Optimizer) -> usize {
+        let mut total = 0;
+        let mut rounds = 0;
+        while self.max_rounds.is_none_or(|max| rounds < max) {
+            let counter = AtomicCounter::default();
+            for pass in &mut self.passes {
+                pass.apply_post_ssa(opt, counter.clone());
+                opt.debug_verify_ssa(pass.name());
+            }
+            rounds += 1;
+            total += counter.get();
+
+            if counter.get() == 0 {
+                break;
+            }
+        }
+        total
+    }
+}
@@ -3,13 +3,14 @@ use std::rc::Rc;
 use cubecl_common::CubeDim;
 use cubecl_ir::{Instruction, Processor, Scope};
 
-use crate::Optimizer;
+use crate::{ControlFlowMode, Optimizer, OptimizerOptions};
 
 /// Build an optimizer with IR transformers
 #[derive(Default)]
 pub struct OptimizerBuilder {
     transformers: Vec<Rc<dyn IrTransformer>>,
     processors: Vec<Box<dyn Processor>>,
+    options: OptimizerOptions,
 }
 
 impl OptimizerBuilder {
@@ -24,9 +25,79 @@ impl OptimizerBuilder {
         self
     }
 
+    /// Replace all the options of the optimizer, including those set by the other methods
+    pub fn with_options(mut self, options: OptimizerOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set the form of the control flow output, structured by default
+    pub fn with_control_flow(mut self, control_flow_mode: ControlFlowMode) -> Self {
+        self.options.control_flow_mode = control_flow_mode;
+        self
+    }
+
+    /// Contract float multiplications only used by an addition into fused multiply-adds,
+    /// disabled by default since the result is only rounded once, unlike the separate operations
+    pub fn with_fma_contraction(mut self, enabled: bool) -> Self {
+        self.options.contract_fma = enabled;
+        self
+    }
+
+    /// Unswitch small loops branching on a loop invariant condition into one loop per side of
+    /// the branch, disabled by default since it duplicates the loops
+    pub fn with_loop_unswitching(mut self, enabled: bool) -> Self {
+        self.options.unswitch_loops = enabled;
+        self
+    }
+
+    /// Forward values stored at a constant index of a shared memory or local array to the later
+    /// loads of that index in the same block, disabled by default
+    pub fn with_store_forwarding(mut self, enabled: bool) -> Self {
+        self.options.forward_stores = enabled;
+        self
+    }
+
+    /// Replace loops with a constant trip count of at most `trip_count` by their iterations in
+    /// straight-line code, disabled by default
+    pub fn with_max_inline_trip_count(mut self, trip_count: u32) -> Self {
+        self.options.max_inline_trip_count = Some(trip_count);
+        self
+    }
+
+    /// Replace serial reductions over the plane, which broadcast the value of each unit in a loop
+    /// up to `PLANE_DIM`, by the matching plane collective, disabled by default since it reorders
+    /// float sums
+    pub fn with_plane_reductions(mut self, enabled: bool) -> Self {
+        self.options.plane_reductions = enabled;
+        self
+    }
+
+    /// Replace if-else branches whose arms only compute the value they merge with at most
+    /// `arm_size` side-effect free instructions each by selects, computing both values, disabled
+    /// by default since every unit then executes both arms
+    pub fn with_max_select_arm_size(mut self, arm_size: u32) -> Self {
+        self.options.max_select_arm_size = Some(arm_size);
+        self
+    }
+
+    /// Verify that the program is still in valid SSA form after every pass, and panic with the
+    /// name of the culprit pass otherwise, disabled by default since it computes the dominators
+    /// after each pass
+    pub fn with_ssa_verification(mut self, enabled: bool) -> Self {
+        self.options.verify_ssa = enabled;
+        self
+    }
+
     /// Build and run optimizer on the scope
     pub fn optimize(self, expand: Scope, cube_dim: CubeDim) -> Optimizer {
-        Optimizer::new(expand, cube_dim, self.transformers, self.processors)
+        Optimizer::with_options(
+            expand,
+            cube_dim,
+            self.transformers,
+            self.processors,
+            self.options,
+        )
     }
 }
 
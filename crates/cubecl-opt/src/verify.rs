@@ -0,0 +1,184 @@
+use std::{collections::HashMap, fmt::Display};
+
+use cubecl_ir::{Id, Variable, VariableKind};
+use petgraph::algo::dominators;
+
+use crate::{ControlFlow, NodeIndex, Optimizer};
+
+/// A violation of the SSA form found by [`Optimizer::verify_ssa`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SsaError {
+    /// A variable is used in `block` where its definition in `def_block` doesn't dominate the use.
+    UseNotDominated {
+        variable: Variable,
+        block: NodeIndex,
+        def_block: NodeIndex,
+    },
+    /// The phi node defining `phi` in `block` has an entry for `entry`, which isn't a predecessor.
+    PhiEntryNotPredecessor {
+        phi: Variable,
+        block: NodeIndex,
+        entry: NodeIndex,
+    },
+    /// The phi node defining `phi` in `block` doesn't have exactly one entry for `predecessor`.
+    PhiMissingPredecessor {
+        phi: Variable,
+        block: NodeIndex,
+        predecessor: NodeIndex,
+    },
+}
+
+impl Display for SsaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SsaError::UseNotDominated {
+                variable,
+                block,
+                def_block,
+            } => write!(
+                f,
+                "{variable} is used in bb{} but its definition in bb{} doesn't dominate it",
+                block.index(),
+                def_block.index()
+            ),
+            SsaError::PhiEntryNotPredecessor { phi, block, entry } => write!(
+                f,
+                "The phi of {phi} in bb{} has an entry for bb{}, which isn't a predecessor",
+                block.index(),
+                entry.index()
+            ),
+            SsaError::PhiMissingPredecessor {
+                phi,
+                block,
+                predecessor,
+            } => write!(
+                f,
+                "The phi of {phi} in bb{} needs exactly one entry for its predecessor bb{}",
+                block.index(),
+                predecessor.index()
+            ),
+        }
+    }
+}
+
+/// Key of the variables that must be defined exactly once.
+fn ssa_key(var: &Variable) -> Option<(Id, u16)> {
+    match var.kind {
+        VariableKind::Versioned { id, version } => Some((id, version)),
+        VariableKind::LocalConst { id } => Some((id, u16::MAX)),
+        _ => None,
+    }
+}
+
+/// Where a variable is defined or used in a block. Phi nodes come first and the control flow last.
+type Position = (NodeIndex, usize);
+
+impl Optimizer {
+    /// Check that every use of an SSA variable is dominated by its definition, and that the
+    /// entries of each phi node match the predecessors of its block.
+    ///
+    /// The entries of a phi node are used at the end of their predecessor. Variables that are
+    /// never defined and blocks that are unreachable from the entry are ignored.
+    ///
+    /// The dominators are recomputed instead of using the cached analysis, since a broken pass may
+    /// also have forgotten to invalidate it.
+    pub fn verify_ssa(&mut self) -> Result<(), SsaError> {
+        let doms = dominators::simple_fast(&self.program.graph, self.entry());
+        let dominates = |def: Position, usage: Position| {
+            if def.0 == usage.0 {
+                return def.1 < usage.1;
+            }
+            doms.dominators(usage.0)
+                .is_some_and(|mut it| it.any(|node| node == def.0))
+        };
+
+        let mut definitions = HashMap::new();
+        let mut uses = Vec::new();
+
+        for block in self.node_ids() {
+            if doms.dominators(block).is_none() {
+                continue;
+            }
+            let phi_nodes = self.program[block].phi_nodes.borrow().clone();
+            let predecessors = self.predecessors(block);
+
+            for phi in phi_nodes.iter() {
+                if let Some(key) = ssa_key(&phi.out) {
+                    definitions.insert(key, (block, 0));
+                }
+
+                for entry in phi.entries.iter() {
+                    if !predecessors.contains(&entry.block) {
+                        return Err(SsaError::PhiEntryNotPredecessor {
+                            phi: phi.out,
+                            block,
+                            entry: entry.block,
+                        });
+                    }
+                    uses.push((entry.value, (entry.block, usize::MAX)));
+                }
+                for predecessor in predecessors.iter() {
+                    let count = phi.entries.iter().filter(|it| it.block == *predecessor);
+                    if count.count() != 1 {
+                        return Err(SsaError::PhiMissingPredecessor {
+                            phi: phi.out,
+                            block,
+                            predecessor: *predecessor,
+                        });
+                    }
+                }
+            }
+
+            let ops = self.program[block].ops.borrow().clone();
+            for (position, inst) in ops.values().enumerate() {
+                let mut inst = inst.clone();
+                let position = (block, position + 1);
+                let mut reads = Vec::new();
+                let mut writes = Vec::new();
+                self.visit_instruction(
+                    &mut inst,
+                    |_, var| reads.push(*var),
+                    |_, var| writes.push(*var),
+                );
+                uses.extend(reads.into_iter().map(|var| (var, position)));
+                for key in writes.iter().filter_map(ssa_key) {
+                    definitions.insert(key, position);
+                }
+            }
+
+            let position = (block, usize::MAX);
+            match &*self.program[block].control_flow.borrow() {
+                ControlFlow::IfElse { cond, .. } => uses.push((*cond, position)),
+                ControlFlow::LoopBreak { break_cond, .. } => uses.push((*break_cond, position)),
+                ControlFlow::Switch { value, .. } => uses.push((*value, position)),
+                _ => {}
+            }
+        }
+
+        for (variable, usage) in uses {
+            let Some(key) = ssa_key(&variable) else {
+                continue;
+            };
+            if let Some(def) = definitions.get(&key)
+                && !dominates(*def, usage)
+            {
+                return Err(SsaError::UseNotDominated {
+                    variable,
+                    block: usage.0,
+                    def_block: def.0,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Panic if `pass` left the program in an invalid SSA form, when enabled by
+    /// [`OptimizerOptions::verify_ssa`](crate::OptimizerOptions::verify_ssa).
+    pub(crate) fn debug_verify_ssa(&mut self, pass: &str) {
+        if self.options.verify_ssa
+            && let Err(err) = self.verify_ssa()
+        {
+            panic!("Invalid SSA after {pass}: {err}\n{self}");
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use cubecl_core::prelude::*;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise};
+use cubecl_std::tensor::is_contiguous;
+
+use crate::instructions::{ArgMax, Sum, SumConfig, TieBreak};
+use crate::precision::ReducePrecision;
+use crate::{ReduceError, gather_reduce, reduce, reduce_cube_partials};
+
+/// Write into `output` the position of the maximum of the whole `input` tensor, reduced by
+/// `cube_count` cubes.
+///
+/// The contiguous `input` is read as a flat buffer split into chunks of consecutive items, and
+/// [`reduce_cube_partials`] finds the position of the maximum of each chunk with [`ArgMax`].
+/// The outputs of [`ArgMax`] can't be combined since the maximum values aren't kept, so the
+/// value of each partial is read back from `input` at its position. A normal [`ArgMax`]
+/// reduction of those values then finds the cube holding the global maximum, and its position is
+/// gathered into `output`. The chunks are ordered like the items, so the position chosen by the
+/// `tie_break`, the lowest one by default, is selected among equal maxima, whatever the number of
+/// cubes, except for [`TieBreak::Any`]. The position is the index of the item in the flat input,
+/// and `output` must have a single element. Nothing is written for an empty input.
+///
+/// This returns [`ReduceError::InputNotContiguous`] if `input` isn't contiguous,
+/// [`ReduceError::MismatchShape`] if `output` has more than one element, and
+/// [`ReduceError::CubeCountTooLarge`] if `cube_count` can't be launched.
+pub fn reduce_argmax_global<R: Runtime, P: ReducePrecision, Out: Numeric>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    cube_count: u32,
+    tie_break: TieBreak,
+) -> Result<(), ReduceError> {
+    if !is_contiguous(input.shape, input.strides) {
+        return Err(ReduceError::InputNotContiguous {
+            shape: input.shape.to_vec(),
+            strides: input.strides.to_vec(),
+        });
+    }
+    let expected_shape = vec![1; output.shape.len().max(1)];
+    if output.shape != expected_shape {
+        return Err(ReduceError::MismatchShape {
+            expected_shape,
+            output_shape: output.shape.to_vec(),
+        });
+    }
+
+    let input_len = input.shape.iter().product::<usize>();
+    if input_len == 0 {
+        return Ok(());
+    }
+    // Drop the cubes that would be left with an empty chunk, so every partial has a position.
+    let chunk_length = input_len.div_ceil((cube_count as usize).clamp(1, input_len));
+    let num_cubes = input_len.div_ceil(chunk_length);
+
+    let flat_input = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(input.handle, &[1], &[input_len], input.elem_size)
+    };
+    let coordinates_handle = client.empty(num_cubes * size_of::<u32>());
+    let coordinates = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(
+            &coordinates_handle,
+            &[1, 1],
+            &[num_cubes, 1],
+            size_of::<u32>(),
+        )
+    };
+    reduce_cube_partials::<R, P, u32, ArgMax>(client, flat_input, coordinates, 0, tie_break)?;
+
+    let values_handle = client.empty(num_cubes * size_of::<P::EI>());
+    let cube_dim = CubeDim::default();
+    unsafe {
+        partial_values_kernel::launch_unchecked::<P::EI, R>(
+            client,
+            calculate_cube_count_elemwise(num_cubes, cube_dim),
+            cube_dim,
+            ArrayArg::from_raw_parts::<P::EI>(input.handle, input_len, 1),
+            ArrayArg::from_raw_parts::<u32>(&coordinates_handle, num_cubes, 1),
+            ArrayArg::from_raw_parts::<P::EI>(&values_handle, num_cubes, 1),
+        );
+    }
+    let values = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(&values_handle, &[1], &[num_cubes], size_of::<P::EI>())
+    };
+
+    let winner_handle = client.empty(size_of::<u32>());
+    let winner = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(&winner_handle, &[1], &[1], size_of::<u32>())
+    };
+    reduce::<R, (P::EI, P::EA), u32, ArgMax>(client, values, winner, 0, None, tie_break)?;
+
+    let coordinates = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(
+            &coordinates_handle,
+            &[1],
+            &[num_cubes],
+            size_of::<u32>(),
+        )
+    };
+    let output = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(output.handle, &[1], &[1], output.elem_size)
+    };
+    gather_reduce::<R, u32, Out, Sum>(client, coordinates, winner, output, 0, SumConfig::default())
+}
+
+/// Read the value of each partial from `input` at its position, with the smallest value for a
+/// chunk without any position, such as a chunk of NaN.
+#[cube(launch_unchecked)]
+fn partial_values_kernel<In: Numeric>(
+    input: &Array<In>,
+    coordinates: &Array<u32>,
+    values: &mut Array<In>,
+) {
+    if ABSOLUTE_POS < values.len() {
+        let coordinate = coordinates[ABSOLUTE_POS];
+        let inside = coordinate < input.len();
+        let index = select(inside, coordinate, 0u32);
+        values[ABSOLUTE_POS] = select(inside, input[index], In::min_value());
+    }
+}
@@ -0,0 +1,152 @@
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+
+use crate::args::{TensorArgs, init_tensors};
+use crate::instructions::IntegerSum;
+use crate::launch::reduce_kernel_virtual_with;
+use crate::naive::{naive_cube_count, reduce_naive_inner};
+use crate::precision::ReducePrecision;
+use crate::shared_transpose::{
+    SharedTransposeParams, reduce_shared_transpose_inner, shared_transpose_cube_count,
+    supports_shared_transpose,
+};
+use crate::{
+    ReduceConfig, ReduceError, ReduceParams, ReduceStrategy, resolve_strategy, valid_output_shape,
+    validate_axis,
+};
+
+/// Sum the given `axis` of the integer `input` tensor into `output`,
+/// returning [`ReduceError::Overflow`] if any of the sums overflowed.
+///
+/// A sum overflows when it doesn't fit in the accumulation type of `P` or in `Out`.
+/// The output holds the wrapped sums even when an overflow is reported.
+///
+/// The sums are reduced by [`IntegerSum::checked`] with the given strategy, or the one picked for
+/// the client like [`reduce`](crate::reduce) when it's `None`. The instruction raises an overflow
+/// flag shared by all the sums when writing them, which is read back to be checked on the host.
+///
+/// This returns the errors of [`reduce`](crate::reduce) for an invalid axis, output shape or
+/// strategy.
+pub fn reduce_sum_checked<R: Runtime, P: ReducePrecision, Out: Numeric>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    strategy: Option<ReduceStrategy>,
+) -> Result<(), ReduceError> {
+    validate_axis(input.shape.len(), axis)?;
+    valid_output_shape(input.shape, output.shape, axis)?;
+    let strategy = resolve_strategy::<R>(client, strategy, input.shape, axis, size_of::<P::EI>())?;
+
+    if output.shape.iter().product::<usize>() == 0 {
+        return Ok(());
+    }
+
+    let overflow_handle = client.create(u32::as_bytes(&[0]));
+    let overflow = unsafe { ArrayArg::from_raw_parts::<Atomic<u32>>(&overflow_handle, 1, 1) };
+
+    if strategy.naive {
+        let (cube_count, cube_dim) = naive_cube_count(&output);
+        unsafe {
+            checked_sum_naive_kernel::launch_unchecked::<P::EI, Out, P::EA, R>(
+                client,
+                cube_count,
+                cube_dim,
+                input.as_tensor_arg(1),
+                output.as_tensor_arg(1),
+                overflow,
+                ScalarArg::new(axis as u32),
+            );
+        }
+    } else if supports_shared_transpose(&strategy, &input, axis) {
+        let (cube_count, cube_dim, params) =
+            shared_transpose_cube_count(&input, axis, R::max_cube_count())?;
+        unsafe {
+            checked_sum_shared_transpose_kernel::launch_unchecked::<P::EI, Out, P::EA, R>(
+                client,
+                cube_count,
+                cube_dim,
+                input.as_tensor_arg(1),
+                output.as_tensor_arg(1),
+                overflow,
+                ScalarArg::new(axis as u32),
+                params,
+            );
+        }
+    } else {
+        let config = ReduceConfig::generate::<R, P::EI>(
+            client,
+            &input,
+            &output,
+            axis,
+            &strategy,
+            R::max_cube_count(),
+        );
+        unsafe {
+            checked_sum_kernel::launch_unchecked::<P::EI, Out, P::EA, R>(
+                client,
+                config.cube_count,
+                config.cube_dim,
+                input.as_tensor_arg(config.line_size_input as u8),
+                output.as_tensor_arg(config.line_size_output as u8),
+                overflow,
+                ScalarArg::new(axis as u32),
+                ReduceParams::new(&config, &strategy),
+            );
+        }
+    }
+
+    let bytes = client.read_one(overflow_handle);
+    match u32::from_bytes(&bytes)[0] {
+        0 => Ok(()),
+        _ => Err(ReduceError::Overflow),
+    }
+}
+
+#[cube(launch_unchecked)]
+fn checked_sum_kernel<In: Numeric, Out: Numeric, Acc: Numeric>(
+    input: &Tensor<Line<In>>,
+    output: &mut Tensor<Line<Out>>,
+    overflow: &mut Array<Atomic<u32>>,
+    axis_reduce: u32,
+    #[comptime] params: ReduceParams,
+) {
+    let (input, mut output) = init_tensors::<TensorArgs, In, Out>(input, output);
+    let inst = &IntegerSum::checked(overflow);
+    reduce_kernel_virtual_with::<(In, Acc), Out, IntegerSum>(
+        &input,
+        &mut output,
+        inst,
+        axis_reduce,
+        params,
+    );
+}
+
+#[cube(launch_unchecked)]
+fn checked_sum_naive_kernel<In: Numeric, Out: Numeric, Acc: Numeric>(
+    input: &Tensor<Line<In>>,
+    output: &mut Tensor<Out>,
+    overflow: &mut Array<Atomic<u32>>,
+    axis: u32,
+) {
+    let inst = &IntegerSum::checked(overflow);
+    reduce_naive_inner::<(In, Acc), Out, IntegerSum>(input, output, inst, axis);
+}
+
+#[cube(launch_unchecked)]
+fn checked_sum_shared_transpose_kernel<In: Numeric, Out: Numeric, Acc: Numeric>(
+    input: &Tensor<Line<In>>,
+    output: &mut Tensor<Line<Out>>,
+    overflow: &mut Array<Atomic<u32>>,
+    axis_reduce: u32,
+    #[comptime] params: SharedTransposeParams,
+) {
+    let inst = &IntegerSum::checked(overflow);
+    reduce_shared_transpose_inner::<(In, Acc), Out, IntegerSum>(
+        input,
+        output,
+        axis_reduce,
+        inst,
+        params,
+    );
+}
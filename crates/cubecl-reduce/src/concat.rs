@@ -0,0 +1,49 @@
+use cubecl_core::prelude::*;
+
+use crate::instructions::ReduceFamily;
+use crate::precision::ReducePrecision;
+use crate::{
+    ReduceAccumulator, ReduceError, ReduceStrategy, reduce_update, valid_output_shape,
+    validate_axis,
+};
+
+/// Reduce the `inputs` along `axis` as if they were concatenated along that axis using the
+/// instruction `Inst`, and write the result into `output`.
+///
+/// The concatenation is never materialized: each input is reduced in turn and merged into
+/// `output` with [`reduce_update`], so the instruction must be able to combine outputs.
+/// All the inputs must have the same shape except along `axis`, and `output` must have that
+/// shape with a value of 1 for `axis`.
+///
+/// This returns [`ReduceError::EmptyConcat`] when there are no `inputs`, and otherwise the same
+/// errors as [`reduce`](crate::reduce). The shapes of all the inputs are validated before
+/// anything is launched.
+pub fn reduce_concat<R: Runtime, P: ReducePrecision, Out: Numeric, Inst: ReduceFamily>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    inputs: &[TensorHandleRef<R>],
+    output: TensorHandleRef<R>,
+    axis: usize,
+    strategy: Option<ReduceStrategy>,
+    inst_config: Inst::Config,
+) -> Result<(), ReduceError> {
+    if inputs.is_empty() {
+        return Err(ReduceError::EmptyConcat);
+    }
+    for input in inputs {
+        validate_axis(input.shape.len(), axis)?;
+        valid_output_shape(input.shape, output.shape, axis)?;
+    }
+
+    let mut accumulator = ReduceAccumulator::new(output);
+    for input in inputs {
+        reduce_update::<R, P, Out, Inst>(
+            client,
+            *input,
+            &mut accumulator,
+            axis,
+            strategy,
+            inst_config,
+        )?;
+    }
+    Ok(())
+}
@@ -4,10 +4,10 @@ use cubecl_core::{
 };
 use cubecl_std::tensor::is_contiguous;
 
-use crate::ReduceStrategy;
+use crate::{ReduceError, ReduceStrategy, valid_output_shape};
 
 // TODO: Should we allows the user to change that?
-const DEFAULT_PLANE_COUNT: u32 = 8;
+pub(crate) const DEFAULT_PLANE_COUNT: u32 = 8;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum LineMode {
@@ -39,22 +39,138 @@ pub struct ReduceConfig {
     pub line_size_output: u32,
     pub bound_checks: bool,
     pub bound_checks_inner: BoundChecksInner,
+    /// Whether the cube count had to be capped to the max supported,
+    /// in which case each agent loops over multiple reductions.
+    pub grid_stride: bool,
+}
+
+/// How the index of each reduction maps to its coordinates in the output,
+/// see [ReduceConfig::output_mapping].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReduceOutputMapping {
+    /// The shape of the output with the rank of the input, with a size of 1 for the reduced axis.
+    pub shape: Vec<usize>,
+    /// The strides of the output with the rank of the input.
+    pub strides: Vec<usize>,
+}
+
+impl ReduceOutputMapping {
+    /// The coordinates in the output of the reduction at `reduce_index`,
+    /// whose result is written at the position `reduce_index` of the output buffer.
+    pub fn coordinates(&self, reduce_index: usize) -> Vec<usize> {
+        self.shape
+            .iter()
+            .zip(&self.strides)
+            .map(|(shape, stride)| (reduce_index / stride) % shape)
+            .collect()
+    }
 }
 
 impl ReduceConfig {
+    /// Compute how the kernels map the index of each reduction to its coordinates in an output
+    /// of the given shape and strides when reducing `axis` of an input of shape `input_shape`.
+    ///
+    /// The output either has the shape of the input with a size of 1 for `axis`, or the shape
+    /// of the input without `axis`, in which case the mapping has the reduced axis inserted
+    /// back. Any strides are accepted as long as they address each position of a contiguous
+    /// buffer exactly once, since the reduction at each index writes its result at the same
+    /// position of the output buffer. Otherwise, this returns [ReduceError::OutputNotDense].
+    pub fn output_mapping(
+        input_shape: &[usize],
+        output_shape: &[usize],
+        output_strides: &[usize],
+        axis: usize,
+    ) -> Result<ReduceOutputMapping, ReduceError> {
+        let rank = input_shape.len();
+        let (shape, mut strides) = if output_shape.len() + 1 == rank {
+            let expected_shape = input_shape
+                .iter()
+                .enumerate()
+                .filter(|(a, _)| *a != axis)
+                .map(|(_, shape)| *shape)
+                .collect::<Vec<_>>();
+            if output_shape != expected_shape {
+                return Err(ReduceError::MismatchShape {
+                    expected_shape,
+                    output_shape: output_shape.to_vec(),
+                });
+            }
+            let mut shape = output_shape.to_vec();
+            shape.insert(axis, 1);
+            let mut strides = output_strides.to_vec();
+            strides.insert(axis, 0);
+            (shape, strides)
+        } else {
+            valid_output_shape(input_shape, output_shape, axis)?;
+            (output_shape.to_vec(), output_strides.to_vec())
+        };
+
+        let not_dense = || ReduceError::OutputNotDense {
+            shape: output_shape.to_vec(),
+            strides: output_strides.to_vec(),
+        };
+        let mut sorted = shape
+            .iter()
+            .zip(&strides)
+            .filter(|(shape, _)| **shape > 1)
+            .map(|(shape, stride)| (*stride, *shape))
+            .collect::<Vec<_>>();
+        sorted.sort_unstable();
+        let mut expected_stride = 1;
+        for (stride, shape) in sorted {
+            if stride != expected_stride {
+                return Err(not_dense());
+            }
+            expected_stride *= shape;
+        }
+
+        // The kernels divide by the strides to find the coordinates, so the axes with a single
+        // item, where any stride is valid, are given the one of a contiguous output, which
+        // doesn't prevent writing the output with lines.
+        for a in (0..rank).rev() {
+            if shape[a] == 1 && strides[a] == 0 {
+                strides[a] = if a + 1 < rank {
+                    strides[a + 1] * shape[a + 1]
+                } else {
+                    1
+                };
+            }
+        }
+
+        Ok(ReduceOutputMapping { shape, strides })
+    }
+
     pub(crate) fn generate<R: Runtime, In: CubePrimitive>(
         client: &ComputeClient<R::Server, R::Channel>,
         input: &TensorHandleRef<R>,
         output: &TensorHandleRef<R>,
         axis: usize,
         strategy: &ReduceStrategy,
+        max_cube_count: (u32, u32, u32),
     ) -> ReduceConfig {
         let reduce_count = output.size() as u32;
         ReduceConfig::new()
-            .generate_line_mode(input, axis)
+            .generate_line_mode(input.strides, axis)
             .generate_line_size::<R, In>(input, output, axis)
-            .generate_cube_dim(client, strategy.use_planes)
-            .generate_cube_count::<R>(reduce_count, strategy)
+            .generate_cube_dim(client, strategy.use_planes, strategy.plane_dim)
+            .generate_cube_count(reduce_count, strategy, max_cube_count)
+    }
+
+    /// Same as [ReduceConfig::generate], but for an input that can only be read one item at a
+    /// time, so both line sizes are 1. The input is only described by its `input_strides`.
+    pub(crate) fn generate_unlined<R: Runtime>(
+        client: &ComputeClient<R::Server, R::Channel>,
+        input_strides: &[usize],
+        output: &TensorHandleRef<R>,
+        axis: usize,
+        strategy: &ReduceStrategy,
+        max_cube_count: (u32, u32, u32),
+    ) -> ReduceConfig {
+        let reduce_count = output.size() as u32;
+        ReduceConfig::new()
+            .generate_line_mode(input_strides, axis)
+            .generate_cube_dim(client, strategy.use_planes, strategy.plane_dim)
+            .generate_cube_count(reduce_count, strategy, max_cube_count)
     }
 
     fn new() -> Self {
@@ -67,11 +183,12 @@ impl ReduceConfig {
             line_size_output: 1,
             bound_checks: true,
             bound_checks_inner: BoundChecksInner::Mask,
+            grid_stride: false,
         }
     }
 
-    fn generate_line_mode<R: Runtime>(mut self, input: &TensorHandleRef<R>, axis: usize) -> Self {
-        let stride = input.strides[axis];
+    fn generate_line_mode(mut self, input_strides: &[usize], axis: usize) -> Self {
+        let stride = input_strides[axis];
         self.line_mode = if stride == 1 {
             LineMode::Parallel
         } else {
@@ -171,21 +288,27 @@ impl ReduceConfig {
         mut self,
         client: &ComputeClient<S, C>,
         use_planes: bool,
+        plane_dim: Option<u32>,
     ) -> Self {
-        self.cube_dim = if use_planes {
-            let plane_dim = client.properties().hardware.plane_size_min;
-            CubeDim::new_2d(plane_dim, DEFAULT_PLANE_COUNT)
+        let hw_props = &client.properties().hardware;
+        let plane_dim = plane_dim.unwrap_or(if use_planes {
+            hw_props.plane_size_min
         } else {
-            let plane_dim = client.properties().hardware.plane_size_max;
-            CubeDim::new_2d(plane_dim, DEFAULT_PLANE_COUNT)
-        };
+            hw_props.plane_size_max
+        });
+        self.cube_dim = CubeDim::new_2d(plane_dim, DEFAULT_PLANE_COUNT);
         self
     }
 
-    pub fn generate_cube_count<R: Runtime>(
+    /// Generate a cube count launching one agent per reduction within the `max_cube_count` limits.
+    ///
+    /// When there are more reductions than the max number of agents that can be launched,
+    /// the cube count is capped and each agent loops over multiple reductions.
+    pub fn generate_cube_count(
         mut self,
         reduce_count: u32,
         strategy: &ReduceStrategy,
+        max_cube_count: (u32, u32, u32),
     ) -> Self {
         let agent_count_per_cube =  // An agent is either a unit, a plane or a whole cube depending on the strategy.
             match strategy {
@@ -200,23 +323,31 @@ impl ReduceConfig {
 
         let cube_count = reduce_count.div_ceil(reduce_count_per_cube);
 
-        self.do_bound_checks_if(reduce_count_per_cube * cube_count > reduce_count);
-
         // If needed, we decompose the cube count to be within runtime limitation.
-        let (max_x, max_y, _) = R::max_cube_count();
+        // Rounding up never launches fewer cubes than required, the extra ones are bound checked.
+        let (max_x, max_y, max_z) = max_cube_count;
         let mut cube_count_x = cube_count;
         let mut cube_count_y = 1;
         let mut cube_count_z = 1;
         while cube_count_x > max_x {
-            cube_count_x /= 2;
+            cube_count_x = cube_count_x.div_ceil(2);
             cube_count_y *= 2;
         }
         while cube_count_y > max_y {
-            cube_count_y /= 2;
+            cube_count_y = cube_count_y.div_ceil(2);
             cube_count_z *= 2;
         }
+        if cube_count_z > max_z {
+            cube_count_z = max_z;
+            self.grid_stride = true;
+        }
         self.cube_count = CubeCount::new_3d(cube_count_x, cube_count_y, cube_count_z);
-        self.do_bound_checks_if(cube_count_x * cube_count_y != cube_count);
+
+        let launched_count = cube_count_x as u64
+            * cube_count_y as u64
+            * cube_count_z as u64
+            * reduce_count_per_cube as u64;
+        self.do_bound_checks_if(launched_count != reduce_count as u64);
 
         self
     }
@@ -0,0 +1,162 @@
+use cubecl_core::prelude::*;
+use cubecl_core::{self as cubecl};
+
+use crate::instructions::{ReduceFamily, ReduceInstruction};
+use crate::precision::ReducePrecision;
+use crate::primitives::{ReduceRange, SliceAxes, reduce_slice_shared, reduce_tree, slice_offsets};
+use crate::{BoundChecksInner, LineMode, ReduceError};
+
+/// The number of units of each cube, combining their accumulators in shared memory.
+const CUBE_SIZE: u32 = 256;
+
+/// Reduce the `input` tensor along `axis` using the instruction `Inst`, splitting each slice
+/// between several cubes, and write the partial value of each cube into `output`.
+///
+/// This is the first phase of a hierarchical reduction, leaving the combine of the partials to
+/// the caller. The `output` has shape `[num_cubes, ...]`, where the remaining axes are the shape of
+/// `input` with `axis` set to 1, like the output of [`reduce`](crate::reduce). Each slice is split
+/// into `num_cubes` chunks of `shape[axis].div_ceil(num_cubes)` consecutive items, and
+/// `output[c, ...]` is the reduction of the chunk `c` by a single cube, so the last cubes can be
+/// left with an empty chunk. The coordinates given to the instruction are the positions along
+/// `axis`, so [`ArgMax`] finds the position of the maximum of each chunk in the whole slice.
+/// The length given to [`ReduceInstruction::merge_line`] is the length of the chunk, so the
+/// partials of [`Mean`] are the means of the chunks.
+///
+/// This returns [`ReduceError::InvalidAxis`] for an invalid axis,
+/// [`ReduceError::MismatchShape`] for an invalid output shape, and
+/// [`ReduceError::CubeCountTooLarge`] if there are more slices or cubes per slice than can be
+/// launched.
+///
+/// [`ArgMax`]: crate::instructions::ArgMax
+/// [`Mean`]: crate::instructions::Mean
+pub fn reduce_cube_partials<R: Runtime, P: ReducePrecision, Out: Numeric, Inst: ReduceFamily>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    inst_config: Inst::Config,
+) -> Result<(), ReduceError> {
+    let rank = input.shape.len();
+    if axis >= rank {
+        return Err(ReduceError::InvalidAxis { axis, rank });
+    }
+
+    let num_cubes = output.shape.first().copied().unwrap_or(0);
+    let mut expected_shape = vec![num_cubes];
+    expected_shape.extend_from_slice(input.shape);
+    expected_shape[axis + 1] = 1;
+    if output.shape != expected_shape {
+        return Err(ReduceError::MismatchShape {
+            expected_shape,
+            output_shape: output.shape.to_vec(),
+        });
+    }
+
+    let shape_axis = input.shape[axis];
+    let num_slices = input.shape.iter().product::<usize>() / shape_axis.max(1);
+    if num_cubes == 0 || num_slices == 0 {
+        return Ok(());
+    }
+    let (max_x, max_y, _) = R::max_cube_count();
+    if num_slices > max_x as usize || num_cubes > max_y as usize {
+        return Err(ReduceError::CubeCountTooLarge);
+    }
+    let chunk_length = shape_axis.div_ceil(num_cubes) as u32;
+
+    // Lines are only read along a contiguous axis, and must not straddle two chunks or slices.
+    let elem = P::EI::as_type_native_unchecked();
+    let line_size = if input.strides[axis] == 1 {
+        R::io_optimized_line_sizes_unchecked(&elem)
+            .filter(|line_size| {
+                let line_size = *line_size as usize;
+                shape_axis.is_multiple_of(line_size)
+                    && (chunk_length as usize).is_multiple_of(line_size)
+                    && input
+                        .strides
+                        .iter()
+                        .enumerate()
+                        .all(|(i, stride)| i == axis || stride.is_multiple_of(line_size))
+            })
+            .max()
+            .unwrap_or(1) as u32
+    } else {
+        1
+    };
+
+    let cube_dim = CubeDim::new_1d(CUBE_SIZE);
+    let cube_count = CubeCount::new_2d(num_slices as u32, num_cubes as u32);
+
+    unsafe {
+        reduce_cube_partials_kernel::launch_unchecked::<P::EI, Out, P::EA, Inst, R>(
+            client,
+            cube_count,
+            cube_dim,
+            input.as_tensor_arg(line_size as u8),
+            output.as_tensor_arg(1),
+            ScalarArg::new(axis as u32),
+            ScalarArg::new(chunk_length),
+            line_size,
+            CUBE_SIZE,
+            inst_config,
+        );
+    }
+    Ok(())
+}
+
+#[cube(launch_unchecked)]
+fn reduce_cube_partials_kernel<In: Numeric, Out: Numeric, Acc: Numeric, R: ReduceFamily>(
+    input: &Tensor<Line<In>>,
+    output: &mut Tensor<Out>,
+    axis: u32,
+    chunk_length: u32,
+    #[comptime] line_size: u32,
+    #[comptime] cube_size: u32,
+    #[comptime] config: R::Config,
+) {
+    // The output has the partials of each chunk along its leading axis.
+    let (input_offset, output_offset) =
+        slice_offsets(input, output, CUBE_POS_X, axis, SliceAxes::Leading);
+    let output_offset = output_offset + CUBE_POS_Y * output.stride(0);
+
+    let shape_axis = input.shape(axis);
+    let stride_axis = input.stride(axis);
+    let coordinate_start = Min::min(CUBE_POS_Y * chunk_length, shape_axis);
+    let coordinate_end = Min::min(coordinate_start + chunk_length, shape_axis);
+    // The axis is contiguous when the lines are larger than a single item.
+    let index_step = if comptime![line_size == 1] {
+        stride_axis
+    } else {
+        1u32.runtime()
+    };
+    let range = ReduceRange {
+        index_start: (input_offset + coordinate_start * stride_axis) / line_size,
+        index_step,
+        coordinate_start,
+        coordinate_end,
+        coordinate_step: CUBE_DIM * line_size,
+    };
+
+    let inst = &R::Instruction::<(In, Acc)>::from_config(config);
+    let mut accumulator =
+        reduce_slice_shared::<(In, Acc), Tensor<Line<In>>, R::Instruction<(In, Acc)>>(
+            input,
+            inst,
+            range,
+            cube_size,
+            line_size,
+            LineMode::Parallel,
+            false,
+            BoundChecksInner::Mask,
+        );
+    sync_cube();
+    let accumulator =
+        reduce_tree::<(In, Acc), R::Instruction<(In, Acc)>>(inst, &mut accumulator, cube_size);
+
+    if UNIT_POS == 0 {
+        output[output_offset] = R::Instruction::<(In, Acc)>::merge_line::<Out>(
+            inst,
+            accumulator,
+            coordinate_end - coordinate_start,
+        );
+    }
+}
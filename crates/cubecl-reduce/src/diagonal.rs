@@ -0,0 +1,42 @@
+use cubecl_core::prelude::*;
+
+use crate::instructions::ReduceFamily;
+use crate::precision::ReducePrecision;
+use crate::{ReduceError, ReduceStrategy, reduce};
+
+/// Reduce the diagonal of each matrix formed by the last two axes of `input` using the
+/// instruction `Inst` and write the result into `output`, such as the trace with [`Sum`].
+///
+/// The diagonal is read in place through a view with a stride of the sum of the strides of the
+/// two matrix axes, so it is never copied into a new buffer. Rectangular matrices are supported,
+/// in which case the diagonal has as many items as the smallest matrix axis.
+///
+/// The shape of `output` must be the one of `input` with the last axis removed
+/// and the second-to-last one set to 1, so `[batch, 1]` for an input of shape `[batch, m, n]`.
+///
+/// This returns [`ReduceError::InvalidDiagonalRank`] when `input` has fewer than two axes,
+/// and otherwise the same errors as [`reduce`].
+///
+/// [`Sum`]: crate::instructions::Sum
+pub fn reduce_diagonal<R: Runtime, P: ReducePrecision, Out: Numeric, Inst: ReduceFamily>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    strategy: Option<ReduceStrategy>,
+    inst_config: Inst::Config,
+) -> Result<(), ReduceError> {
+    let rank = input.shape.len();
+    if rank < 2 {
+        return Err(ReduceError::InvalidDiagonalRank { rank });
+    }
+
+    let mut shape = input.shape[..rank - 1].to_vec();
+    shape[rank - 2] = input.shape[rank - 2].min(input.shape[rank - 1]);
+    let mut strides = input.strides[..rank - 1].to_vec();
+    strides[rank - 2] += input.strides[rank - 1];
+
+    let diagonal = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(input.handle, &strides, &shape, input.elem_size)
+    };
+    reduce::<R, P, Out, Inst>(client, diagonal, output, rank - 2, strategy, inst_config)
+}
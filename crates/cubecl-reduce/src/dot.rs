@@ -0,0 +1,126 @@
+use cubecl_core::prelude::*;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise};
+
+use crate::precision::ReducePrecision;
+use crate::primitives::{SliceAxes, slice_offsets};
+use crate::{ReduceError, valid_output_shape, validate_axis};
+
+/// The sums accumulated by [`reduce_dot_product`] while it reads both inputs.
+#[derive_cube_comptime]
+pub struct DotProduct {
+    /// Also accumulate the sums of squares `sum(lhs * lhs)` and `sum(rhs * rhs)`.
+    pub squares: bool,
+}
+
+/// Compute the dot products `sum(lhs * rhs)` of the vectors laid along the given `axis` and write
+/// them into `output`, such as the similarity scores between many pairs of embeddings.
+///
+/// When `squares` are given, the sums of squares `sum(lhs * lhs)` and `sum(rhs * rhs)` are also
+/// written into them, so the cosine similarities `dot / sqrt(lhs_squares * rhs_squares)` only need
+/// a division afterward. The three sums are accumulated in `P::EA` by the same kernel, which reads
+/// each item of `lhs` and `rhs` once, and each unit reduces one pair of vectors.
+///
+/// The `lhs` and `rhs` must have the same shape and strides, and all the outputs the same shape
+/// except for a value of 1 for the given `axis`. This returns [`ReduceError::MismatchWeights`]
+/// when the layouts of `lhs` and `rhs` differ, and the errors of [`reduce`](crate::reduce) for
+/// an invalid axis or output shape.
+pub fn reduce_dot_product<R: Runtime, P: ReducePrecision, Out: Numeric>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    lhs: TensorHandleRef<R>,
+    rhs: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    squares: Option<(TensorHandleRef<R>, TensorHandleRef<R>)>,
+    axis: usize,
+) -> Result<(), ReduceError> {
+    validate_axis(lhs.shape.len(), axis)?;
+    valid_output_shape(lhs.shape, output.shape, axis)?;
+    if let Some((lhs_squares, rhs_squares)) = &squares {
+        valid_output_shape(lhs.shape, lhs_squares.shape, axis)?;
+        valid_output_shape(lhs.shape, rhs_squares.shape, axis)?;
+    }
+    if lhs.shape != rhs.shape || lhs.strides != rhs.strides {
+        return Err(ReduceError::MismatchWeights {
+            values_shape: lhs.shape.to_vec(),
+            values_strides: lhs.strides.to_vec(),
+            weights_shape: rhs.shape.to_vec(),
+            weights_strides: rhs.strides.to_vec(),
+        });
+    }
+
+    let num_vectors = output.shape.iter().product::<usize>();
+    if num_vectors == 0 {
+        return Ok(());
+    }
+
+    // Without sums of squares, the kernel still takes their outputs but never writes them.
+    let unused_handle = client.empty(size_of::<Out>());
+    let unused = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(&unused_handle, &[1], &[1], size_of::<Out>())
+    };
+    let dot_product = DotProduct {
+        squares: squares.is_some(),
+    };
+    let (lhs_squares, rhs_squares) = squares.unwrap_or((unused, unused));
+
+    let cube_dim = CubeDim::default();
+    let cube_count = calculate_cube_count_elemwise(num_vectors, cube_dim);
+
+    unsafe {
+        dot_product_kernel::launch_unchecked::<P::EI, P::EA, Out, R>(
+            client,
+            cube_count,
+            cube_dim,
+            lhs.as_tensor_arg(1),
+            rhs.as_tensor_arg(1),
+            output.as_tensor_arg(1),
+            lhs_squares.as_tensor_arg(1),
+            rhs_squares.as_tensor_arg(1),
+            ScalarArg::new(axis as u32),
+            dot_product,
+        );
+    }
+    Ok(())
+}
+
+#[cube(launch_unchecked)]
+fn dot_product_kernel<In: Numeric, Acc: Numeric, Out: Numeric>(
+    lhs: &Tensor<In>,
+    rhs: &Tensor<In>,
+    output: &mut Tensor<Out>,
+    lhs_squares: &mut Tensor<Out>,
+    rhs_squares: &mut Tensor<Out>,
+    axis: u32,
+    #[comptime] dot_product: DotProduct,
+) {
+    if ABSOLUTE_POS >= output.len() {
+        terminate!();
+    }
+
+    // The inputs share their layout, so a single offset is enough for both.
+    let (input_offset, output_offset) =
+        slice_offsets(lhs, output, ABSOLUTE_POS, axis, SliceAxes::Same);
+
+    let mut dot = Acc::from_int(0);
+    let mut lhs_sum = Acc::from_int(0);
+    let mut rhs_sum = Acc::from_int(0);
+    for index in 0..lhs.shape(axis) {
+        let position = input_offset + index * lhs.stride(axis);
+        let lhs_item = Acc::cast_from(lhs[position]);
+        let rhs_item = Acc::cast_from(rhs[position]);
+        dot += lhs_item * rhs_item;
+        if comptime![dot_product.squares] {
+            lhs_sum += lhs_item * lhs_item;
+            rhs_sum += rhs_item * rhs_item;
+        }
+    }
+
+    output[output_offset] = Out::cast_from(dot);
+    if comptime![dot_product.squares] {
+        let (_, lhs_squares_offset) =
+            slice_offsets(lhs, lhs_squares, ABSOLUTE_POS, axis, SliceAxes::Same);
+        let (_, rhs_squares_offset) =
+            slice_offsets(lhs, rhs_squares, ABSOLUTE_POS, axis, SliceAxes::Same);
+        lhs_squares[lhs_squares_offset] = Out::cast_from(lhs_sum);
+        rhs_squares[rhs_squares_offset] = Out::cast_from(rhs_sum);
+    }
+}
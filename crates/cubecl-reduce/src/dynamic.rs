@@ -0,0 +1,58 @@
+use cubecl_core::ir::{ElemType, FloatKind, IntKind, UIntKind};
+use cubecl_core::prelude::*;
+use cubecl_core::{flex32, tf32};
+
+use crate::instructions::ReduceFamily;
+use crate::precision::ReducePrecision;
+use crate::{ReduceError, ReduceStrategy, reduce};
+
+/// Same as [`reduce`], but with the element type of `output` only known at runtime.
+///
+/// The matching monomorphization of [`reduce`] is launched for `output_elem`, which can be
+/// any float, integer or unsigned integer type except the minifloats smaller than 16 bits.
+/// Return [`ReduceError::UnsupportedOutputElem`] for the other types.
+pub fn reduce_dyn<R: Runtime, P: ReducePrecision, Inst: ReduceFamily>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    output_elem: ElemType,
+    axis: usize,
+    strategy: Option<ReduceStrategy>,
+    inst_config: Inst::Config,
+) -> Result<(), ReduceError> {
+    macro_rules! launch {
+        ($out:ty) => {
+            reduce::<R, P, $out, Inst>(client, input, output, axis, strategy, inst_config)
+        };
+    }
+
+    match output_elem {
+        ElemType::Float(kind) => match kind {
+            FloatKind::F16 => launch!(half::f16),
+            FloatKind::BF16 => launch!(half::bf16),
+            FloatKind::Flex32 => launch!(flex32),
+            FloatKind::F32 => launch!(f32),
+            FloatKind::TF32 => launch!(tf32),
+            FloatKind::F64 => launch!(f64),
+            FloatKind::E2M1
+            | FloatKind::E2M3
+            | FloatKind::E3M2
+            | FloatKind::E4M3
+            | FloatKind::E5M2
+            | FloatKind::UE8M0 => Err(ReduceError::UnsupportedOutputElem(output_elem)),
+        },
+        ElemType::Int(kind) => match kind {
+            IntKind::I8 => launch!(i8),
+            IntKind::I16 => launch!(i16),
+            IntKind::I32 => launch!(i32),
+            IntKind::I64 => launch!(i64),
+        },
+        ElemType::UInt(kind) => match kind {
+            UIntKind::U8 => launch!(u8),
+            UIntKind::U16 => launch!(u16),
+            UIntKind::U32 => launch!(u32),
+            UIntKind::U64 => launch!(u64),
+        },
+        ElemType::Bool => Err(ReduceError::UnsupportedOutputElem(output_elem)),
+    }
+}
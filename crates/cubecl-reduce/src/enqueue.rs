@@ -0,0 +1,36 @@
+use cubecl_core::prelude::*;
+use cubecl_std::tensor::TensorHandle;
+
+use crate::instructions::ReduceFamily;
+use crate::precision::ReducePrecision;
+use crate::{ReduceError, ReduceStrategy, reduce};
+
+/// Enqueue the reduction of the given `axis` of the `input` tensor and return the output,
+/// allocated with the shape of `input` except for a value of 1 for the given `axis`.
+///
+/// This returns as soon as the kernels are enqueued on the `client`, without waiting for them
+/// to complete. Launches on the same client and stream execute in the order they are enqueued,
+/// so later kernels can use the output right away. The host only sees the result once the client
+/// is synchronized, which reading the output does implicitly, so many independent reductions can
+/// be enqueued back-to-back and read after a single synchronization.
+///
+/// Return the same errors as [`reduce`], in which case no kernel is enqueued.
+pub fn reduce_enqueue<R: Runtime, P: ReducePrecision, Out: Numeric, Inst: ReduceFamily>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    axis: usize,
+    strategy: Option<ReduceStrategy>,
+    inst_config: Inst::Config,
+) -> Result<TensorHandle<R, Out>, ReduceError> {
+    let rank = input.shape.len();
+    if axis >= rank {
+        return Err(ReduceError::InvalidAxis { axis, rank });
+    }
+
+    let mut shape = input.shape.to_vec();
+    shape[axis] = 1;
+    let output = TensorHandle::<R, Out>::empty(client, shape);
+
+    reduce::<R, P, Out, Inst>(client, input, output.as_ref(), axis, strategy, inst_config)?;
+    Ok(output)
+}
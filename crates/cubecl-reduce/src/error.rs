@@ -1,6 +1,6 @@
 use core::fmt;
 
-use cubecl_core::ir::StorageType;
+use cubecl_core::ir::{ElemType, StorageType};
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub enum ReduceError {
@@ -10,15 +10,114 @@ pub enum ReduceError {
     CubeCountTooLarge,
     /// Indicate that min_plane_dim != max_plane_dim, thus the exact plane_dim is not fixed.
     ImprecisePlaneDim,
+    /// Indicate that the plane dimension forced by the strategy isn't supported by the client.
+    PlaneDimUnsupported { plane_dim: u32, min: u32, max: u32 },
     /// Indicate the axis is too large.
     InvalidAxis { axis: usize, rank: usize },
+    /// Indicate that the input doesn't have the two axes needed to reduce its diagonal.
+    InvalidDiagonalRank { rank: usize },
+    /// Indicate that no input was given to reduce their concatenation.
+    EmptyConcat,
+    /// Indicate that the pooling windows and strides must be positive, with one of each for
+    /// every spatial axis of the input.
+    InvalidPoolWindow {
+        window: Vec<usize>,
+        stride: Vec<usize>,
+        rank: usize,
+    },
+    /// Indicate that the indices of a gather reduction aren't a vector.
+    InvalidGatherIndices { shape: Vec<usize> },
+    /// Indicate that the indices of a scatter reduction aren't a vector with one destination per
+    /// position along the reduced axis.
+    InvalidScatterIndices { shape: Vec<usize>, length: usize },
+    /// Indicate that the segment ids of a segmented scan aren't a vector with one id per
+    /// position along the scanned axis.
+    InvalidSegmentIds { shape: Vec<usize>, length: usize },
+    /// Indicate that the lengths of a reduction up to a length per slice don't have the shape of
+    /// the input without the reduced axis.
+    InvalidLengths {
+        shape: Vec<usize>,
+        expected_shape: Vec<usize>,
+    },
+    /// Indicate that a histogram has no bins, or that its range isn't finite with `min < max`.
+    InvalidHistogram { bins: u32 },
+    /// Indicate that a requested quantile isn't between `0` and `1`.
+    InvalidQuantile { index: usize },
+    /// Indicate that a decay factor isn't between `0` and `1`.
+    InvalidDecay,
+    /// Indicate that the axis of a softmax is too long to be kept by a single unit.
+    SoftmaxAxisTooLong { length: usize, max: usize },
+    /// Indicate that subnormal inputs were asked to be preserved, but the backend flushes them
+    /// to zero and they can't be emulated.
+    SubnormalsFlushed,
+    /// Indicate that the output permutation isn't made of the axes of the input except the
+    /// reduced one, each appearing once.
+    InvalidPermutation {
+        permutation: Vec<usize>,
+        axis: usize,
+        rank: usize,
+    },
     /// Indicate that the shape of the output tensor is invalid for the given input and axis.
     MismatchShape {
         expected_shape: Vec<usize>,
         output_shape: Vec<usize>,
     },
+    /// Indicate that the output strides don't address each position of a contiguous buffer
+    /// exactly once.
+    OutputNotDense {
+        shape: Vec<usize>,
+        strides: Vec<usize>,
+    },
     /// Indicate that we can't launch a shared sum because the atomic addition is not supported.
     MissingAtomicAdd(StorageType),
+    /// Indicate that we can't launch a compare-and-swap loop because the atomic compare-and-swap
+    /// is not supported.
+    MissingAtomicCompareAndSwap(StorageType),
+    /// Indicate that the instruction can't combine the outputs of two reductions of the same
+    /// slice, which is needed to update a reduction or to scatter into an output.
+    CombineUnsupported,
+    /// Indicate that an integer sum overflowed its accumulation or output type.
+    Overflow,
+    /// Indicate that a packed input isn't contiguous along its last axis,
+    /// where the values are packed.
+    PackedAxisNotContiguous { stride: usize },
+    /// Indicate that the input must be contiguous, but isn't.
+    InputNotContiguous {
+        shape: Vec<usize>,
+        strides: Vec<usize>,
+    },
+    /// Indicate that the weights don't have the same shape and strides as the weighted values.
+    MismatchWeights {
+        values_shape: Vec<usize>,
+        values_strides: Vec<usize>,
+        weights_shape: Vec<usize>,
+        weights_strides: Vec<usize>,
+    },
+    /// Indicate that the outputs written by the same reduction don't have the same strides.
+    MismatchOutputStrides {
+        strides: Vec<usize>,
+        other_strides: Vec<usize>,
+    },
+    /// Indicate that the naive strategy was asked for a reduction that has no naive kernel.
+    NaiveUnsupported,
+    /// Indicate that the shared transpose strategy applies to a reduction that has no shared
+    /// transpose kernel.
+    SharedTransposeUnsupported,
+    /// Indicate that the element type requested for the output isn't supported.
+    UnsupportedOutputElem(ElemType),
+    /// Indicate that a caller-provided scratch buffer is smaller than the reduction needs.
+    ScratchTooSmall { size: usize, required: usize },
+}
+
+impl ReduceError {
+    /// Whether the error only comes from a strategy the client doesn't support, so the same
+    /// reduction can succeed with another strategy.
+    pub fn is_unsupported_strategy(&self) -> bool {
+        matches!(
+            self,
+            Self::PlanesUnavailable | Self::ImprecisePlaneDim | Self::PlaneDimUnsupported { .. }
+        )
+    }
 }
 
 impl fmt::Display for ReduceError {
@@ -35,10 +134,77 @@ impl fmt::Display for ReduceError {
                 f,
                 "Trying to launch a kernel using plane instructions, but the min and max plane dimensions are different."
             ),
+            Self::PlaneDimUnsupported {
+                plane_dim,
+                min,
+                max,
+            } => write!(
+                f,
+                "The plane dimension {plane_dim} isn't supported, it must be a power of 2 between {min} and {max}."
+            ),
             Self::InvalidAxis { axis, rank } => write!(
                 f,
                 "The provided axis ({axis}) must be smaller than the input tensor rank ({rank})."
             ),
+            Self::InvalidDiagonalRank { rank } => write!(
+                f,
+                "The input must have at least two axes to reduce its diagonal, but it has {rank}."
+            ),
+            Self::EmptyConcat => write!(
+                f,
+                "At least one input must be given to reduce their concatenation."
+            ),
+            Self::InvalidPoolWindow {
+                window,
+                stride,
+                rank,
+            } => write!(
+                f,
+                "The pooling window {window:?} and stride {stride:?} must be positive and have the same length, at most the input rank ({rank})."
+            ),
+            Self::InvalidGatherIndices { shape } => write!(
+                f,
+                "The gather indices must be a vector, but they have the shape {shape:?}."
+            ),
+            Self::InvalidScatterIndices { shape, length } => write!(
+                f,
+                "The scatter indices must be a vector of {length} destinations, one per position along the axis, but they have the shape {shape:?}."
+            ),
+            Self::InvalidSegmentIds { shape, length } => write!(
+                f,
+                "The segment ids must be a vector of {length} ids, one per position along the axis, but they have the shape {shape:?}."
+            ),
+            Self::InvalidLengths {
+                shape,
+                expected_shape,
+            } => write!(
+                f,
+                "The lengths must have the shape {expected_shape:?} of the input without the reduced axis, but they have the shape {shape:?}."
+            ),
+            Self::InvalidHistogram { bins } => write!(
+                f,
+                "A histogram needs at least one bin (got {bins}) and a finite range with min < max."
+            ),
+            Self::InvalidQuantile { index } => {
+                write!(f, "The quantile at index {index} must be between 0 and 1.")
+            }
+            Self::InvalidDecay => write!(f, "The decay factor must be between 0 and 1."),
+            Self::SoftmaxAxisTooLong { length, max } => write!(
+                f,
+                "The softmax axis has {length} items, but at most {max} can be kept by a single unit."
+            ),
+            Self::SubnormalsFlushed => write!(
+                f,
+                "The subnormal inputs can't be preserved, the backend flushes them to zero and only f32 inputs can be emulated, on a backend supporting f64."
+            ),
+            Self::InvalidPermutation {
+                permutation,
+                axis,
+                rank,
+            } => write!(
+                f,
+                "The output permutation {permutation:?} must hold each axis below {rank} except the reduced axis {axis} exactly once."
+            ),
             Self::MismatchShape {
                 expected_shape,
                 output_shape,
@@ -48,9 +214,65 @@ impl fmt::Display for ReduceError {
                     "The output shape (currently {output_shape:?}) should be {expected_shape:?}."
                 )
             }
+            Self::OutputNotDense { shape, strides } => write!(
+                f,
+                "The output (shape {shape:?}, strides {strides:?}) must address each position of a contiguous buffer exactly once."
+            ),
             Self::MissingAtomicAdd(elem) => {
                 write!(f, "Atomic add not supported by the client for {elem}")
             }
+            Self::MissingAtomicCompareAndSwap(elem) => {
+                write!(
+                    f,
+                    "Atomic compare-and-swap not supported by the client for {elem}"
+                )
+            }
+            Self::CombineUnsupported => write!(
+                f,
+                "The instruction can't combine the outputs of two reductions of the same slice."
+            ),
+            Self::Overflow => {
+                write!(f, "The sum overflowed the accumulation or output type.")
+            }
+            Self::PackedAxisNotContiguous { stride } => write!(
+                f,
+                "The packed input must have a stride of 1 along its last axis, but it is {stride}."
+            ),
+            Self::InputNotContiguous { shape, strides } => write!(
+                f,
+                "The input (shape {shape:?}, strides {strides:?}) must be contiguous."
+            ),
+            Self::MismatchWeights {
+                values_shape,
+                values_strides,
+                weights_shape,
+                weights_strides,
+            } => write!(
+                f,
+                "The weights (shape {weights_shape:?}, strides {weights_strides:?}) must have the same layout as the values (shape {values_shape:?}, strides {values_strides:?})."
+            ),
+            Self::MismatchOutputStrides {
+                strides,
+                other_strides,
+            } => write!(
+                f,
+                "The outputs of the reduction must have the same strides, but they have {strides:?} and {other_strides:?}."
+            ),
+            Self::NaiveUnsupported => write!(
+                f,
+                "The naive strategy isn't supported by this reduction, use another strategy."
+            ),
+            Self::SharedTransposeUnsupported => write!(
+                f,
+                "The shared transpose strategy isn't supported by this reduction, use another strategy."
+            ),
+            Self::UnsupportedOutputElem(elem) => {
+                write!(f, "The output element type {elem} isn't supported.")
+            }
+            Self::ScratchTooSmall { size, required } => write!(
+                f,
+                "The scratch buffer has {size} bytes, but at least {required} bytes are needed."
+            ),
         }
     }
 }
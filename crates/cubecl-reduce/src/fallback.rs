@@ -0,0 +1,32 @@
+use cubecl_core::prelude::*;
+
+use crate::instructions::ReduceFamily;
+use crate::precision::ReducePrecision;
+use crate::{ReduceError, ReduceStrategy, reduce};
+
+/// Same as [`reduce`], but when `strategy` isn't supported by the `client`, retry with the
+/// strategy [`reduce`] picks by default instead of returning the error.
+///
+/// The errors that trigger the retry are the ones for which
+/// [`ReduceError::is_unsupported_strategy`] holds. The fallback strategy keeps the `shared` and
+/// `shared_transpose` choices of `strategy`, since every client supports them, and only uses
+/// planes if the `client` can. Any other error, or an error of the fallback, is returned as is.
+pub fn try_reduce<R: Runtime, P: ReducePrecision, Out: Numeric, Inst: ReduceFamily>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    strategy: ReduceStrategy,
+    inst_config: Inst::Config,
+) -> Result<(), ReduceError> {
+    match reduce::<R, P, Out, Inst>(client, input, output, axis, Some(strategy), inst_config) {
+        Err(error) if error.is_unsupported_strategy() => {
+            let fallback = ReduceStrategy {
+                shared_transpose: strategy.shared_transpose,
+                ..ReduceStrategy::new::<R>(client, strategy.shared)
+            };
+            reduce::<R, P, Out, Inst>(client, input, output, axis, Some(fallback), inst_config)
+        }
+        result => result,
+    }
+}
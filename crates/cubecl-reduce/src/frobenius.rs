@@ -0,0 +1,44 @@
+use cubecl_core::prelude::*;
+use cubecl_std::tensor::is_contiguous;
+
+use crate::instructions::{LpNorm, LpNormConfig};
+use crate::precision::ReducePrecision;
+use crate::{ReduceError, reduce};
+
+/// Compute the Frobenius norm of the whole `input` tensor, the square root of the sum of the
+/// squares of all its elements, and read it back to the host.
+///
+/// The contiguous `input` is reduced as a flat vector by [`LpNorm`] of order `2`, so the squares
+/// are never materialized and are accumulated in `f32` scaled by the greatest absolute value,
+/// which doesn't overflow for large elements. The reduction uses the strategy selected by
+/// [`reduce`], and the norm of an empty tensor is `0`.
+///
+/// This returns [`ReduceError::InputNotContiguous`] if `input` isn't contiguous.
+pub fn frobenius_norm<R: Runtime, P: ReducePrecision>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+) -> Result<f32, ReduceError> {
+    if !is_contiguous(input.shape, input.strides) {
+        return Err(ReduceError::InputNotContiguous {
+            shape: input.shape.to_vec(),
+            strides: input.strides.to_vec(),
+        });
+    }
+    let len = input.shape.iter().product::<usize>();
+    if len == 0 {
+        return Ok(0.0);
+    }
+
+    let flat_shape = [len];
+    let flat = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(input.handle, &[1], &flat_shape, input.elem_size)
+    };
+    let output_handle = client.empty(size_of::<f32>());
+    let output = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(&output_handle, &[1], &[1], size_of::<f32>())
+    };
+    reduce::<R, P, f32, LpNorm>(client, flat, output, 0, None, LpNormConfig::new(2.0))?;
+
+    let bytes = client.read_one(output_handle);
+    Ok(f32::from_bytes(&bytes)[0])
+}
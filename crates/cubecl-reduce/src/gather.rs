@@ -0,0 +1,113 @@
+use cubecl_core::prelude::*;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise};
+
+use crate::instructions::{ReduceCoordinate, ReduceFamily, ReduceInstruction, reduce_inplace};
+use crate::precision::ReducePrecision;
+use crate::primitives::{SliceAxes, slice_offsets};
+use crate::{ReduceError, valid_output_shape, validate_axis};
+
+/// Reduce the items of `input` at the positions along `axis` given by `indices` using the
+/// instruction `Inst` and write the result into `output`, such as a sparse sum of selected rows
+/// with [`Sum`].
+///
+/// The gathered tensor is never materialized, each unit reads the selected items of one slice
+/// in place. `indices` is a vector of `u32` positions along `axis`, in any order. A position
+/// appearing multiple times is reduced as many times, so it counts multiply for [`Sum`] and
+/// [`Mean`]. Positions past the end of the axis read the null input of the instruction with the
+/// coordinate `u32::MAX`, so they are ignored by [`Sum`] and [`Max`]. Coordinates given to the instruction are the positions
+/// along `axis`, so [`ArgMax`] finds the position of the maximum in `input`.
+///
+/// The shape of `output` must be the same as input except with a value of 1 for the given
+/// `axis`. The input is read one item at a time. This returns
+/// [`ReduceError::InvalidGatherIndices`] if `indices` isn't a vector, and otherwise the same
+/// errors as [`reduce`](crate::reduce) for the axis and the output shape.
+///
+/// [`Sum`]: crate::instructions::Sum
+/// [`Mean`]: crate::instructions::Mean
+/// [`Max`]: crate::instructions::Max
+/// [`ArgMax`]: crate::instructions::ArgMax
+pub fn gather_reduce<R: Runtime, P: ReducePrecision, Out: Numeric, Inst: ReduceFamily>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    indices: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    inst_config: Inst::Config,
+) -> Result<(), ReduceError> {
+    validate_axis(input.shape.len(), axis)?;
+    valid_output_shape(input.shape, output.shape, axis)?;
+    if indices.shape.len() != 1 {
+        return Err(ReduceError::InvalidGatherIndices {
+            shape: indices.shape.to_vec(),
+        });
+    }
+
+    let num_elems = output.shape.iter().product::<usize>();
+    let cube_dim = CubeDim::default();
+    let cube_count = calculate_cube_count_elemwise(num_elems, cube_dim);
+
+    unsafe {
+        gather_reduce_kernel::launch_unchecked::<P::EI, Out, P::EA, Inst, R>(
+            client,
+            cube_count,
+            cube_dim,
+            input.as_tensor_arg(1),
+            indices.as_tensor_arg(1),
+            output.as_tensor_arg(1),
+            ScalarArg::new(axis as u32),
+            inst_config,
+        );
+    }
+    Ok(())
+}
+
+#[cube(launch_unchecked)]
+fn gather_reduce_kernel<In: Numeric, Out: Numeric, Acc: Numeric, R: ReduceFamily>(
+    input: &Tensor<Line<In>>,
+    indices: &Tensor<u32>,
+    output: &mut Tensor<Out>,
+    axis: u32,
+    #[comptime] config: R::Config,
+) {
+    if ABSOLUTE_POS >= output.len() {
+        terminate!();
+    }
+
+    let inst = &R::Instruction::<(In, Acc)>::from_config(config);
+    let requirements = R::Instruction::<(In, Acc)>::requirements(inst);
+
+    // The output has a single item along `axis`, so it has an item per slice.
+    let (offset, output_offset) = slice_offsets(input, output, ABSOLUTE_POS, axis, SliceAxes::Same);
+
+    let axis_len = input.shape(axis);
+    let axis_stride = input.stride(axis);
+    let num_indices = indices.shape(0);
+
+    let mut accumulator = R::Instruction::<(In, Acc)>::null_accumulator(inst, 1u32);
+    for k in 0..num_indices {
+        let position = indices[k * indices.stride(0)];
+        let inside = position < axis_len;
+        let index = select(inside, offset + position * axis_stride, offset);
+        let item = select(
+            inside,
+            input[index],
+            R::Instruction::<(In, Acc)>::null_input(inst, 1u32),
+        );
+
+        let coordinate = if comptime![requirements.coordinates] {
+            ReduceCoordinate::new_Required(Line::new(select(inside, position, u32::MAX)))
+        } else {
+            ReduceCoordinate::new_NotRequired()
+        };
+        reduce_inplace::<(In, Acc), R::Instruction<(In, Acc)>>(
+            inst,
+            &mut accumulator,
+            item,
+            coordinate,
+            false,
+        );
+    }
+
+    output[output_offset] =
+        R::Instruction::<(In, Acc)>::merge_line::<Out>(inst, accumulator, num_indices);
+}
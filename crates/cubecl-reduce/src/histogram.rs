@@ -0,0 +1,147 @@
+use cubecl_core::prelude::*;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise};
+
+use crate::primitives::{SliceAxes, slice_offsets};
+use crate::{ReduceError, validate_axis};
+
+/// How [`reduce_histogram`] handles the items outside of the range of the bins.
+#[derive_cube_comptime]
+pub enum HistogramOutliers {
+    /// Count the items below the range in the first bin and those above it in the last one.
+    Clamp,
+    /// Ignore the items outside of the range, they aren't part of the total either.
+    Drop,
+}
+
+/// The bins computed by [`reduce_histogram`].
+#[derive_cube_comptime]
+pub struct Histogram {
+    /// The number of bins, splitting the range into equal widths.
+    pub bins: u32,
+    pub outliers: HistogramOutliers,
+    /// Normalize the counts to a density, dividing them by the total count and the bin width,
+    /// so the histogram integrates to `1` over the range.
+    pub density: bool,
+}
+
+/// Count the items of each slice along `axis` of `input` falling into the bins of `histogram`
+/// over the range `(min, max)`, and write the counts into `output`.
+///
+/// The bins are half-open, except the last one which also holds `max`. NaN items are never
+/// counted. The shape of `output` must be the same as input except with `histogram.bins` for
+/// the given `axis`. The counts are exact up to the largest integer of `Out` without rounding,
+/// for `f16` this is 2048. A slice without any counted item has a density of `0` everywhere.
+///
+/// The input is read one item at a time, and each unit computes the histogram of one slice.
+/// This returns [`ReduceError::InvalidHistogram`] if there are no bins or the range isn't
+/// finite with `min < max`, and [`ReduceError::MismatchShape`] if `output` doesn't have the
+/// shape of the bins.
+pub fn reduce_histogram<R: Runtime, In: Numeric, Out: Float>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    histogram: Histogram,
+    range: (f32, f32),
+) -> Result<(), ReduceError> {
+    validate_axis(input.shape.len(), axis)?;
+    let (min, max) = range;
+    if histogram.bins == 0 || !min.is_finite() || !max.is_finite() || min >= max {
+        return Err(ReduceError::InvalidHistogram {
+            bins: histogram.bins,
+        });
+    }
+
+    let mut expected_shape = input.shape.to_vec();
+    expected_shape[axis] = histogram.bins as usize;
+    if output.shape != expected_shape {
+        return Err(ReduceError::MismatchShape {
+            expected_shape,
+            output_shape: output.shape.to_vec(),
+        });
+    }
+
+    // Computed once on the host, so every backend finds the same bin for the same item.
+    let scale = histogram.bins as f32 / (max - min);
+    let bin_width = (max - min) / histogram.bins as f32;
+
+    let num_slices = input.shape.iter().product::<usize>() / input.shape[axis];
+    let cube_dim = CubeDim::default();
+    let cube_count = calculate_cube_count_elemwise(num_slices, cube_dim);
+
+    unsafe {
+        histogram_kernel::launch_unchecked::<In, Out, R>(
+            client,
+            cube_count,
+            cube_dim,
+            input.as_tensor_arg(1),
+            output.as_tensor_arg(1),
+            ScalarArg::new(axis as u32),
+            ScalarArg::new(min),
+            ScalarArg::new(max),
+            ScalarArg::new(scale),
+            ScalarArg::new(bin_width),
+            histogram,
+        );
+    }
+    Ok(())
+}
+
+#[cube(launch_unchecked)]
+fn histogram_kernel<In: Numeric, Out: Float>(
+    input: &Tensor<In>,
+    output: &mut Tensor<Out>,
+    axis: u32,
+    min: f32,
+    max: f32,
+    scale: f32,
+    bin_width: f32,
+    #[comptime] histogram: Histogram,
+) {
+    let bins = histogram.bins;
+    if ABSOLUTE_POS * bins >= output.len() {
+        terminate!();
+    }
+
+    let (input_offset, output_offset) =
+        slice_offsets(input, output, ABSOLUTE_POS, axis, SliceAxes::Same);
+
+    let output_stride = output.stride(axis);
+    for bin in 0..bins {
+        output[output_offset + bin * output_stride] = Out::from_int(0);
+    }
+
+    let mut total = 0u32;
+    for index in 0..input.shape(axis) {
+        let item = f32::cast_from(input[input_offset + index * input.stride(axis)]);
+        let in_range = item >= min && item <= max;
+        let counted = match comptime![histogram.outliers] {
+            // NaN is neither below nor above the range.
+            HistogramOutliers::Clamp => in_range || item < min || item > max,
+            HistogramOutliers::Drop => in_range,
+        };
+
+        if counted {
+            // Truncating the position is its floor once it is clamped to the bins, and `max`
+            // falls in the last bin.
+            let position = Min::min(
+                Max::max((item - min) * scale, f32::new(0.0)),
+                f32::cast_from(bins - 1),
+            );
+            let bin = u32::cast_from(position);
+            let offset = output_offset + bin * output_stride;
+            output[offset] = output[offset] + Out::from_int(1);
+            total += 1;
+        }
+    }
+
+    if comptime![histogram.density] {
+        if total > 0 {
+            let norm = Out::cast_from(f32::cast_from(total) * bin_width);
+            for bin in 0..bins {
+                let offset = output_offset + bin * output_stride;
+                output[offset] = output[offset] / norm;
+            }
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+
+use crate::precision::ReducePrecision;
+
+use super::{
+    ReduceCoordinate, ReduceFamily, ReduceInstruction, ReduceRequirements, SharedAccumulator,
+};
+
+/// Which result [`AllEqual`] outputs.
+#[derive_cube_comptime]
+pub enum AllEqualOutput {
+    /// The value of the items when they are all equal, and one of the items otherwise.
+    Value,
+    /// `1` when all the items are equal, `0` otherwise.
+    Flag,
+}
+
+#[derive_cube_comptime]
+pub struct AllEqualConfig {
+    pub output: AllEqualOutput,
+}
+
+/// Nothing was reduced yet, the seen value is meaningless.
+const STATE_EMPTY: u32 = 0;
+/// All the reduced items are equal to the seen value.
+const STATE_EQUAL: u32 = 1;
+/// At least two reduced items differ.
+const STATE_DIFFERENT: u32 = 2;
+
+/// Detect the constant slices, such as blocks that compress to a single value.
+///
+/// The accumulator tracks a seen value and a state, which is empty until an item is reduced,
+/// then equal until an item differs from the seen value. The states are ordered, so fusing two
+/// accumulators keeps the highest one, or marks them different when both saw distinct values.
+/// Both results are accumulated together, but a reduction only has a single output, so the config
+/// selects the one that is written.
+///
+/// The padding of the input is the lowest finite value, so an item equal to it is ignored. NaN is
+/// never equal to anything, so a slice with a NaN isn't constant, except through plane
+/// instructions where it depends on the backend. An empty slice is constant.
+#[derive(Debug, CubeType, Clone)]
+pub struct AllEqual {
+    #[cube(comptime)]
+    pub output: AllEqualOutput,
+}
+
+impl ReduceFamily for AllEqual {
+    type Instruction<P: ReducePrecision> = Self;
+    type Config = AllEqualConfig;
+
+    fn supports_combine(_config: Self::Config) -> bool {
+        false
+    }
+}
+
+#[cube]
+fn fill_state(#[comptime] line_size: u32, #[comptime] state: u32) -> Line<u32> {
+    Line::empty(line_size).fill(state)
+}
+
+#[cube]
+impl<P: ReducePrecision> ReduceInstruction<P> for AllEqual {
+    type AccumulatorItem = (Line<P::EA>, Line<u32>);
+    type SharedAccumulator = AllEqualAccumulator<P::EA>;
+    type Config = AllEqualConfig;
+
+    fn requirements(_this: &Self) -> ReduceRequirements {
+        ReduceRequirements { coordinates: false }
+    }
+
+    fn from_config(#[comptime] config: Self::Config) -> Self {
+        AllEqual {
+            output: config.output,
+        }
+    }
+
+    fn null_input(_this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
+        Line::empty(line_size).fill(P::EI::min_value())
+    }
+
+    fn identity(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        (
+            Line::empty(line_size).fill(P::EA::cast_from(P::EI::min_value())),
+            fill_state(line_size, STATE_EMPTY),
+        )
+    }
+
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <AllEqual as ReduceInstruction<P>>::identity(this, line_size)
+    }
+
+    fn assign_accumulator(
+        _this: &Self,
+        destination: &mut Self::AccumulatorItem,
+        source: &Self::AccumulatorItem,
+    ) {
+        destination.0 = source.0;
+        destination.1 = source.1;
+    }
+
+    fn reduce(
+        this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        item: Line<P::EI>,
+        _coordinate: ReduceCoordinate,
+        #[comptime] use_planes: bool,
+    ) -> Self::AccumulatorItem {
+        let line_size = item.size();
+        let is_padding = item.equal(<AllEqual as ReduceInstruction<P>>::null_input(
+            this, line_size,
+        ));
+        let state = select_many(
+            is_padding,
+            fill_state(line_size, STATE_EMPTY),
+            fill_state(line_size, STATE_EQUAL),
+        );
+
+        let item = if use_planes {
+            // The padding is the lowest value, so it is ignored by the maximum but must be
+            // replaced by the highest value for the minimum.
+            let max = plane_max(item);
+            let min = plane_min(select_many(
+                is_padding,
+                Line::empty(line_size).fill(P::EI::max_value()),
+                item,
+            ));
+            let state = select_many(
+                plane_max(state).equal(fill_state(line_size, STATE_EMPTY)),
+                fill_state(line_size, STATE_EMPTY),
+                select_many(
+                    max.equal(min),
+                    fill_state(line_size, STATE_EQUAL),
+                    fill_state(line_size, STATE_DIFFERENT),
+                ),
+            );
+            (Line::<P::EA>::cast_from(max), state)
+        } else {
+            (Line::<P::EA>::cast_from(item), state)
+        };
+
+        <AllEqual as ReduceInstruction<P>>::fuse_accumulators(this, *accumulator, item)
+    }
+
+    fn fuse_accumulators(
+        _this: &Self,
+        lhs: Self::AccumulatorItem,
+        rhs: Self::AccumulatorItem,
+    ) -> Self::AccumulatorItem {
+        let line_size = lhs.1.size();
+        let empty = fill_state(line_size, STATE_EMPTY);
+        let lhs_empty = lhs.1.equal(empty);
+
+        let differ = select_many(
+            lhs.0.equal(rhs.0),
+            fill_state(line_size, STATE_EQUAL),
+            fill_state(line_size, STATE_DIFFERENT),
+        );
+        let state = select_many(
+            lhs_empty,
+            rhs.1,
+            select_many(
+                rhs.1.equal(empty),
+                lhs.1,
+                Max::max(Max::max(lhs.1, rhs.1), differ),
+            ),
+        );
+
+        (select_many(lhs_empty, rhs.0, lhs.0), state)
+    }
+
+    fn merge_line<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        shape_axis_reduce: u32,
+    ) -> Out {
+        // The lanes are fused as accumulators of a single lane.
+        let mut value = Line::empty(1u32).fill(accumulator.0[0]);
+        let mut state = Line::empty(1u32).fill(accumulator.1[0]);
+        #[unroll]
+        for k in 1..accumulator.0.size() {
+            let fused = <AllEqual as ReduceInstruction<P>>::fuse_accumulators(
+                this,
+                (value, state),
+                (
+                    Line::empty(1u32).fill(accumulator.0[k]),
+                    Line::empty(1u32).fill(accumulator.1[k]),
+                ),
+            );
+            value = fused.0;
+            state = fused.1;
+        }
+        <AllEqual as ReduceInstruction<P>>::to_output_perpendicular::<Out>(
+            this,
+            (value, state),
+            shape_axis_reduce,
+        )[0]
+    }
+
+    fn to_output_perpendicular<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Line<Out> {
+        let line_size = accumulator.1.size();
+        match comptime!(this.output) {
+            AllEqualOutput::Value => Line::cast_from(accumulator.0),
+            AllEqualOutput::Flag => select_many(
+                accumulator.1.equal(fill_state(line_size, STATE_DIFFERENT)),
+                Line::empty(line_size).fill(Out::from_int(0)),
+                Line::empty(line_size).fill(Out::from_int(1)),
+            ),
+        }
+    }
+
+    fn combine_outputs<Out: Numeric>(
+        _this: &Self,
+        lhs: Line<Out>,
+        _lhs_count: u32,
+        _rhs: Line<Out>,
+        _rhs_count: u32,
+    ) -> Line<Out> {
+        // Never called, `supports_combine` is false.
+        lhs
+    }
+}
+
+/// A pair of shared memory used for [`AllEqual`], holding the seen values and the states.
+#[derive(CubeType)]
+pub struct AllEqualAccumulator<N: Numeric> {
+    pub values: SharedMemory<Line<N>>,
+    pub states: SharedMemory<Line<u32>>,
+}
+
+#[cube]
+impl<N: Numeric> SharedAccumulator for AllEqualAccumulator<N> {
+    type Item = (Line<N>, Line<u32>);
+
+    fn allocate(
+        #[comptime] length: u32,
+        #[comptime] line_size: u32,
+        #[comptime] _coordinate: bool,
+    ) -> Self {
+        AllEqualAccumulator::<N> {
+            values: SharedMemory::new_lined(length, line_size),
+            states: SharedMemory::new_lined(length, line_size),
+        }
+    }
+
+    fn read(accumulator: &Self, index: u32) -> Self::Item {
+        (accumulator.values[index], accumulator.states[index])
+    }
+
+    fn write(accumulator: &mut Self, index: u32, item: Self::Item) {
+        accumulator.values[index] = item.0;
+        accumulator.states[index] = item.1;
+    }
+}
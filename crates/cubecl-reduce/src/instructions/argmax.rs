@@ -5,12 +5,20 @@ use crate::{instructions::ReduceRequirements, precision::ReducePrecision};
 
 use super::{
     ArgAccumulator, ReduceCoordinate, ReduceCoordinateExpand, ReduceFamily, ReduceInstruction,
-    lowest_coordinate_matching,
+    TieBreak, coordinate_matching, wins_tie,
 };
 
-/// Compute the coordinate of the maximum item returning the smallest coordinate in case of equality.
+/// Compute the coordinate of the maximum item.
+///
+/// The coordinate chosen by the [`TieBreak`] config is selected in case of equality, the lowest
+/// one by default, whatever the strategy, so the output can be used as a tie-breaking key by sort
+/// kernels. With [`TieBreak::Any`], the coordinate of any of the equal maxima can be selected,
+/// which saves a comparison for each item.
 #[derive(Debug, CubeType, Clone)]
-pub struct ArgMax {}
+pub struct ArgMax {
+    #[cube(comptime)]
+    pub tie_break: TieBreak,
+}
 
 #[cube]
 impl ArgMax {
@@ -22,48 +30,108 @@ impl ArgMax {
         coordinates0: Line<u32>,
         items1: Line<N>,
         coordinates1: Line<u32>,
+    ) -> (Line<N>, Line<u32>) {
+        Self::choose_argmax_tie_break(
+            items0,
+            coordinates0,
+            items1,
+            coordinates1,
+            TieBreak::LowestIndex,
+        )
+    }
+
+    /// Same as [ArgMax::choose_argmax], but in case of equality the coordinate is selected by
+    /// `tie_break`.
+    pub fn choose_argmax_tie_break<N: Numeric>(
+        items0: Line<N>,
+        coordinates0: Line<u32>,
+        items1: Line<N>,
+        coordinates1: Line<u32>,
+        #[comptime] tie_break: TieBreak,
     ) -> (Line<N>, Line<u32>) {
         let to_keep = select_many(
             items0.equal(items1),
-            coordinates0.less_than(coordinates1),
+            wins_tie(coordinates0, coordinates1, tie_break),
             items0.greater_than(items1),
         );
         let items = select_many(to_keep, items0, items1);
         let coordinates = select_many(to_keep, coordinates0, coordinates1);
         (items, coordinates)
     }
+
+    /// Same as [ArgMax::choose_argmax], but in case of equality the second pair is selected
+    /// without comparing the coordinates.
+    pub fn choose_argmax_unstable<N: Numeric>(
+        items0: Line<N>,
+        coordinates0: Line<u32>,
+        items1: Line<N>,
+        coordinates1: Line<u32>,
+    ) -> (Line<N>, Line<u32>) {
+        let to_keep = items0.greater_than(items1);
+        let items = select_many(to_keep, items0, items1);
+        let coordinates = select_many(to_keep, coordinates0, coordinates1);
+        (items, coordinates)
+    }
+
+    fn choose<N: Numeric>(
+        this: &Self,
+        items0: Line<N>,
+        coordinates0: Line<u32>,
+        items1: Line<N>,
+        coordinates1: Line<u32>,
+    ) -> (Line<N>, Line<u32>) {
+        if comptime![this.tie_break != TieBreak::Any] {
+            Self::choose_argmax_tie_break(
+                items0,
+                coordinates0,
+                items1,
+                coordinates1,
+                this.tie_break,
+            )
+        } else {
+            Self::choose_argmax_unstable(items0, coordinates0, items1, coordinates1)
+        }
+    }
 }
 
 impl ReduceFamily for ArgMax {
     type Instruction<P: ReducePrecision> = Self;
-    type Config = ();
+    type Config = TieBreak;
+
+    fn supports_combine(_config: Self::Config) -> bool {
+        false
+    }
 }
 
 #[cube]
 impl<P: ReducePrecision> ReduceInstruction<P> for ArgMax {
     type AccumulatorItem = (Line<P::EA>, Line<u32>);
     type SharedAccumulator = ArgAccumulator<P::EA>;
-    type Config = ();
+    type Config = TieBreak;
 
     fn requirements(_this: &Self) -> ReduceRequirements {
         ReduceRequirements { coordinates: true }
     }
 
-    fn from_config(_config: Self::Config) -> Self {
-        ArgMax {}
+    fn from_config(#[comptime] config: Self::Config) -> Self {
+        ArgMax { tie_break: config }
     }
 
     fn null_input(_this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
         Line::empty(line_size).fill(P::EI::min_value())
     }
 
-    fn null_accumulator(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+    fn identity(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
         (
             Line::empty(line_size).fill(P::EA::min_value()),
             Line::empty(line_size).fill(u32::MAX),
         )
     }
 
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <ArgMax as ReduceInstruction<P>>::identity(this, line_size)
+    }
+
     fn assign_accumulator(
         _this: &Self,
         destination: &mut Self::AccumulatorItem,
@@ -74,7 +142,7 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ArgMax {
     }
 
     fn reduce(
-        _this: &Self,
+        this: &Self,
         accumulator: &Self::AccumulatorItem,
         item: Line<P::EI>,
         coordinate: ReduceCoordinate,
@@ -91,13 +159,15 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ArgMax {
 
         let (candidate_item, candidate_coordinate) = if use_planes {
             let candidate_item = plane_max(item);
-            let candidate_coordinate = lowest_coordinate_matching(candidate_item, item, coordinate);
+            let candidate_coordinate =
+                coordinate_matching(candidate_item, item, coordinate, this.tie_break);
             (candidate_item, candidate_coordinate)
         } else {
             (item, coordinate)
         };
 
-        Self::choose_argmax(
+        Self::choose(
+            this,
             Line::cast_from(candidate_item),
             candidate_coordinate,
             accumulator.0,
@@ -106,15 +176,15 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ArgMax {
     }
 
     fn fuse_accumulators(
-        _this: &Self,
+        this: &Self,
         lhs: Self::AccumulatorItem,
         rhs: Self::AccumulatorItem,
     ) -> Self::AccumulatorItem {
-        Self::choose_argmax(lhs.0, lhs.1, rhs.0, rhs.1)
+        Self::choose(this, lhs.0, lhs.1, rhs.0, rhs.1)
     }
 
     fn merge_line<Out: Numeric>(
-        _this: &Self,
+        this: &Self,
         accumulator: Self::AccumulatorItem,
         _shape_axis_reduce: u32,
     ) -> Out {
@@ -126,11 +196,18 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ArgMax {
             for k in 0..line_size {
                 let acc_element = accumulator.0[k];
                 let acc_coordinate = accumulator.1[k];
-                if acc_element == max && acc_coordinate < coordinate {
-                    coordinate = acc_coordinate;
-                } else if acc_element > max {
+                if acc_element > max {
                     max = acc_element;
                     coordinate = acc_coordinate;
+                } else if comptime![this.tie_break != TieBreak::Any] {
+                    let wins = wins_tie(
+                        Line::empty(1u32).fill(acc_coordinate),
+                        Line::empty(1u32).fill(coordinate),
+                        this.tie_break,
+                    );
+                    if acc_element == max && wins[0] {
+                        coordinate = acc_coordinate;
+                    }
                 }
             }
             Out::cast_from(coordinate)
@@ -146,4 +223,15 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ArgMax {
     ) -> Line<Out> {
         Line::cast_from(accumulator.1)
     }
+
+    fn combine_outputs<Out: Numeric>(
+        _this: &Self,
+        lhs: Line<Out>,
+        _lhs_count: u32,
+        _rhs: Line<Out>,
+        _rhs_count: u32,
+    ) -> Line<Out> {
+        // Never called, `supports_combine` is false.
+        lhs
+    }
 }
@@ -5,16 +5,24 @@ use crate::precision::ReducePrecision;
 
 use super::{
     ArgAccumulator, ReduceCoordinate, ReduceCoordinateExpand, ReduceFamily, ReduceInstruction,
-    ReduceRequirements, lowest_coordinate_matching,
+    ReduceRequirements, TieBreak, coordinate_matching, wins_tie,
 };
 
-/// Compute the coordinate of the maximum item returning the smallest coordinate in case of equality.
+/// Compute the coordinate of the minimum item, returning the coordinate chosen by the
+/// [`TieBreak`] config in case of equality, the smallest one by default.
 #[derive(Debug, CubeType, Clone)]
-pub struct ArgMin {}
+pub struct ArgMin {
+    #[cube(comptime)]
+    pub tie_break: TieBreak,
+}
 
 impl ReduceFamily for ArgMin {
     type Instruction<P: ReducePrecision> = Self;
-    type Config = ();
+    type Config = TieBreak;
+
+    fn supports_combine(_config: Self::Config) -> bool {
+        false
+    }
 }
 
 #[cube]
@@ -27,10 +35,28 @@ impl ArgMin {
         coordinates0: Line<u32>,
         items1: Line<N>,
         coordinates1: Line<u32>,
+    ) -> (Line<N>, Line<u32>) {
+        Self::choose_argmin_tie_break(
+            items0,
+            coordinates0,
+            items1,
+            coordinates1,
+            TieBreak::LowestIndex,
+        )
+    }
+
+    /// Same as [ArgMin::choose_argmin], but in case of equality the coordinate is selected by
+    /// `tie_break`.
+    pub fn choose_argmin_tie_break<N: Numeric>(
+        items0: Line<N>,
+        coordinates0: Line<u32>,
+        items1: Line<N>,
+        coordinates1: Line<u32>,
+        #[comptime] tie_break: TieBreak,
     ) -> (Line<N>, Line<u32>) {
         let to_keep = select_many(
             items0.equal(items1),
-            coordinates0.less_than(coordinates1),
+            wins_tie(coordinates0, coordinates1, tie_break),
             items0.less_than(items1),
         );
         let items = select_many(to_keep, items0, items1);
@@ -43,26 +69,30 @@ impl ArgMin {
 impl<P: ReducePrecision> ReduceInstruction<P> for ArgMin {
     type AccumulatorItem = (Line<P::EA>, Line<u32>);
     type SharedAccumulator = ArgAccumulator<P::EA>;
-    type Config = ();
+    type Config = TieBreak;
 
     fn requirements(_this: &Self) -> ReduceRequirements {
         ReduceRequirements { coordinates: true }
     }
-    fn from_config(_config: Self::Config) -> Self {
-        ArgMin {}
+    fn from_config(#[comptime] config: Self::Config) -> Self {
+        ArgMin { tie_break: config }
     }
 
     fn null_input(_this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
         Line::empty(line_size).fill(P::EI::max_value())
     }
 
-    fn null_accumulator(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+    fn identity(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
         (
             Line::empty(line_size).fill(P::EA::max_value()),
             Line::empty(line_size).fill(u32::MAX),
         )
     }
 
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <ArgMin as ReduceInstruction<P>>::identity(this, line_size)
+    }
+
     fn assign_accumulator(
         _this: &Self,
         destination: &mut Self::AccumulatorItem,
@@ -73,7 +103,7 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ArgMin {
     }
 
     fn reduce(
-        _this: &Self,
+        this: &Self,
         accumulator: &Self::AccumulatorItem,
         item: Line<P::EI>,
         coordinate: ReduceCoordinate,
@@ -90,30 +120,32 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ArgMin {
 
         let (candidate_item, candidate_coordinate) = if use_planes {
             let candidate_item = plane_min(item);
-            let candidate_coordinate = lowest_coordinate_matching(candidate_item, item, coordinate);
+            let candidate_coordinate =
+                coordinate_matching(candidate_item, item, coordinate, this.tie_break);
             (candidate_item, candidate_coordinate)
         } else {
             (item, coordinate)
         };
 
-        Self::choose_argmin(
+        Self::choose_argmin_tie_break(
             accumulator.0,
             accumulator.1,
             Line::cast_from(candidate_item),
             candidate_coordinate,
+            this.tie_break,
         )
     }
 
     fn fuse_accumulators(
-        _this: &Self,
+        this: &Self,
         lhs: Self::AccumulatorItem,
         rhs: Self::AccumulatorItem,
     ) -> Self::AccumulatorItem {
-        Self::choose_argmin(lhs.0, lhs.1, rhs.0, rhs.1)
+        Self::choose_argmin_tie_break(lhs.0, lhs.1, rhs.0, rhs.1, this.tie_break)
     }
 
     fn merge_line<Out: Numeric>(
-        _this: &Self,
+        this: &Self,
         accumulator: Self::AccumulatorItem,
         _shape_axis_reduce: u32,
     ) -> Out {
@@ -126,8 +158,13 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ArgMin {
             for k in 0..line_size {
                 let acc_element = accumulator.0[k];
                 let acc_coordinate = accumulator.1[k];
+                let wins = wins_tie(
+                    Line::empty(1u32).fill(acc_coordinate),
+                    Line::empty(1u32).fill(coordinate),
+                    this.tie_break,
+                );
                 // TODO replace with select
-                if acc_element == min && acc_coordinate < coordinate {
+                if acc_element == min && wins[0] {
                     coordinate = acc_coordinate;
                 } else if acc_element < min {
                     min = acc_element;
@@ -147,4 +184,15 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ArgMin {
     ) -> Line<Out> {
         Line::cast_from(accumulator.1)
     }
+
+    fn combine_outputs<Out: Numeric>(
+        _this: &Self,
+        lhs: Line<Out>,
+        _lhs_count: u32,
+        _rhs: Line<Out>,
+        _rhs_count: u32,
+    ) -> Line<Out> {
+        // Never called, `supports_combine` is false.
+        lhs
+    }
 }
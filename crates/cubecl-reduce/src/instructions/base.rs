@@ -6,6 +6,26 @@ use crate::precision::ReducePrecision;
 pub trait ReduceFamily: Send + Sync + 'static + std::fmt::Debug {
     type Instruction<P: ReducePrecision>: ReduceInstruction<P, Config = Self::Config>;
     type Config: CubeComptime + Send + Sync;
+
+    /// The output of the reduction of an empty slice, if there is one.
+    ///
+    /// This can be used on the host to pre-fill buffers that are later reduced into.
+    /// Reductions such as [`Mean`](super::Mean) or [`ArgMax`](super::ArgMax) have no identity output.
+    fn identity<Out: Numeric>(_config: Self::Config) -> Option<Out> {
+        None
+    }
+
+    /// Whether the outputs of two reductions of the same slice can be combined with
+    /// [`combine_outputs`](ReduceInstruction::combine_outputs).
+    ///
+    /// This is checked on the host by [`reduce_update`](crate::reduce_update) and
+    /// [`scatter_reduce`](crate::scatter_reduce), which return
+    /// [`ReduceError::CombineUnsupported`](crate::ReduceError::CombineUnsupported) otherwise.
+    /// Reductions such as [`ArgMax`](super::ArgMax) or [`Range`](super::Range) don't keep what
+    /// is needed to combine their outputs.
+    fn supports_combine(_config: Self::Config) -> bool {
+        true
+    }
 }
 
 #[derive(CubeType)]
@@ -45,8 +65,11 @@ pub trait ReduceInstruction<P: ReducePrecision>:
     /// is guaranteed to return `accumulator` unchanged for any choice of `coordinate`.
     fn null_input(this: &Self, #[comptime] line_size: u32) -> Line<P::EI>;
 
-    /// A accumulator such that `Self::fuse_accumulators(accumulator, Self::null_accumulator()` always returns
+    /// The identity element of the reduction, such that `Self::fuse_accumulators(accumulator, Self::identity())`
     /// is guaranteed to return `accumulator` unchanged.
+    fn identity(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem;
+
+    /// The accumulator a reduction starts from, usually the [identity](ReduceInstruction::identity).
     fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem;
 
     /// Assign the value of `source` into `destination`.
@@ -88,6 +111,19 @@ pub trait ReduceInstruction<P: ReducePrecision>:
         accumulator: Self::AccumulatorItem,
         shape_axis_reduce: u32,
     ) -> Line<Out>;
+
+    /// Combine the outputs of two reductions of the same slice, computed over `lhs_count` and `rhs_count`
+    /// elements respectively, into the output of a single reduction over all those elements.
+    ///
+    /// This is what allows a reduction to be updated chunk by chunk with [`reduce_update`](crate::reduce_update).
+    /// It's only called when [`ReduceFamily::supports_combine`] is true for the config.
+    fn combine_outputs<Out: Numeric>(
+        this: &Self,
+        lhs: Line<Out>,
+        lhs_count: u32,
+        rhs: Line<Out>,
+        rhs_count: u32,
+    ) -> Line<Out>;
 }
 
 #[derive(CubeType)]
@@ -0,0 +1,228 @@
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+
+use crate::precision::ReducePrecision;
+
+use super::{
+    ReduceCoordinate, ReduceCoordinateExpand, ReduceFamily, ReduceInstruction, ReduceRequirements,
+    SharedAccumulator,
+};
+
+#[derive_cube_comptime]
+pub struct CentroidConfig {
+    // Bits of the output of the slices without weight, so the config can be hashed.
+    zero_weight: u32,
+}
+
+impl Default for CentroidConfig {
+    fn default() -> Self {
+        Self::nan()
+    }
+}
+
+impl CentroidConfig {
+    /// Output NaN for the slices whose weights sum to zero, only meaningful for float outputs.
+    pub fn nan() -> Self {
+        Self::sentinel(f32::NAN)
+    }
+
+    /// Output `sentinel` for the slices whose weights sum to zero, such as `-1` which can't be
+    /// a coordinate.
+    pub fn sentinel(sentinel: f32) -> Self {
+        Self {
+            zero_weight: sentinel.to_bits(),
+        }
+    }
+
+    /// The output of the slices whose weights sum to zero.
+    pub fn zero_weight(&self) -> f32 {
+        f32::from_bits(self.zero_weight)
+    }
+}
+
+/// Compute the centroid of the coordinates along the reduced axis, weighted by the items,
+/// `sum(i * x_i) / sum(x_i)`, such as the center of mass of a profile.
+///
+/// The weighted sum of the coordinates and the sum of the weights are accumulated together, and
+/// their ratio is computed in `f32`. A slice whose weights sum to zero has no centroid, and
+/// outputs the value chosen by the config, NaN by default.
+/// The outputs can't be combined by [`reduce_update`](crate::reduce_update), since the weights
+/// aren't kept.
+#[derive(Debug, CubeType, Clone)]
+pub struct Centroid {
+    #[cube(comptime)]
+    pub config: CentroidConfig,
+}
+
+impl ReduceFamily for Centroid {
+    type Instruction<P: ReducePrecision> = Self;
+    type Config = CentroidConfig;
+
+    fn supports_combine(_config: Self::Config) -> bool {
+        false
+    }
+}
+
+/// The ratio of the weighted coordinates by the weights, or the zero weight output of the config.
+#[cube]
+fn centroid<N: Numeric>(
+    weighted: Line<N>,
+    weights: Line<N>,
+    #[comptime] config: CentroidConfig,
+) -> Line<f32> {
+    let line_size = weights.size();
+    let ratio = Line::<f32>::cast_from(weighted) / Line::<f32>::cast_from(weights);
+    select_many(
+        weights.equal(Line::empty(line_size).fill(N::from_int(0))),
+        Line::empty(line_size).fill(f32::new(comptime![config.zero_weight()])),
+        ratio,
+    )
+}
+
+#[cube]
+impl<P: ReducePrecision> ReduceInstruction<P> for Centroid {
+    type AccumulatorItem = (Line<P::EA>, Line<P::EA>);
+    type SharedAccumulator = CentroidAccumulator<P::EA>;
+    type Config = CentroidConfig;
+
+    fn requirements(_this: &Self) -> ReduceRequirements {
+        ReduceRequirements { coordinates: true }
+    }
+
+    fn from_config(#[comptime] config: Self::Config) -> Self {
+        Centroid { config }
+    }
+
+    fn null_input(_this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
+        Line::empty(line_size).fill(P::EI::from_int(0))
+    }
+
+    fn identity(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        (
+            Line::empty(line_size).fill(P::EA::from_int(0)),
+            Line::empty(line_size).fill(P::EA::from_int(0)),
+        )
+    }
+
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <Centroid as ReduceInstruction<P>>::identity(this, line_size)
+    }
+
+    fn assign_accumulator(
+        _this: &Self,
+        destination: &mut Self::AccumulatorItem,
+        source: &Self::AccumulatorItem,
+    ) {
+        destination.0 = source.0;
+        destination.1 = source.1;
+    }
+
+    fn reduce(
+        _this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        item: Line<P::EI>,
+        coordinate: ReduceCoordinate,
+        #[comptime] use_planes: bool,
+    ) -> Self::AccumulatorItem {
+        let coordinate = match coordinate {
+            ReduceCoordinate::Required(val) => val,
+            ReduceCoordinate::NotRequired => {
+                comptime! {panic!("Coordinates are required for Centroid")};
+                #[allow(unreachable_code)]
+                Line::new(0)
+            }
+        };
+
+        let item = Line::<P::EA>::cast_from(item);
+        let weighted = item * Line::<P::EA>::cast_from(coordinate);
+        if use_planes {
+            (
+                accumulator.0 + plane_sum(weighted),
+                accumulator.1 + plane_sum(item),
+            )
+        } else {
+            (accumulator.0 + weighted, accumulator.1 + item)
+        }
+    }
+
+    fn fuse_accumulators(
+        _this: &Self,
+        lhs: Self::AccumulatorItem,
+        rhs: Self::AccumulatorItem,
+    ) -> Self::AccumulatorItem {
+        (lhs.0 + rhs.0, lhs.1 + rhs.1)
+    }
+
+    fn merge_line<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Out {
+        let mut weighted = P::EA::from_int(0);
+        let mut weights = P::EA::from_int(0);
+        #[unroll]
+        for k in 0..accumulator.0.size() {
+            weighted += accumulator.0[k];
+            weights += accumulator.1[k];
+        }
+        Out::cast_from(
+            centroid::<P::EA>(
+                Line::empty(1u32).fill(weighted),
+                Line::empty(1u32).fill(weights),
+                this.config,
+            )[0],
+        )
+    }
+
+    fn to_output_perpendicular<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Line<Out> {
+        Line::cast_from(centroid::<P::EA>(accumulator.0, accumulator.1, this.config))
+    }
+
+    fn combine_outputs<Out: Numeric>(
+        _this: &Self,
+        lhs: Line<Out>,
+        _lhs_count: u32,
+        _rhs: Line<Out>,
+        _rhs_count: u32,
+    ) -> Line<Out> {
+        // Never called, `supports_combine` is false.
+        lhs
+    }
+}
+
+/// A pair of shared memory used for [`Centroid`], holding the weighted coordinates and the
+/// weights.
+#[derive(CubeType)]
+pub struct CentroidAccumulator<N: Numeric> {
+    pub weighted: SharedMemory<Line<N>>,
+    pub weights: SharedMemory<Line<N>>,
+}
+
+#[cube]
+impl<N: Numeric> SharedAccumulator for CentroidAccumulator<N> {
+    type Item = (Line<N>, Line<N>);
+
+    fn allocate(
+        #[comptime] length: u32,
+        #[comptime] line_size: u32,
+        #[comptime] _coordinate: bool,
+    ) -> Self {
+        CentroidAccumulator::<N> {
+            weighted: SharedMemory::new_lined(length, line_size),
+            weights: SharedMemory::new_lined(length, line_size),
+        }
+    }
+
+    fn read(accumulator: &Self, index: u32) -> Self::Item {
+        (accumulator.weighted[index], accumulator.weights[index])
+    }
+
+    fn write(accumulator: &mut Self, index: u32, item: Self::Item) {
+        accumulator.weighted[index] = item.0;
+        accumulator.weights[index] = item.1;
+    }
+}
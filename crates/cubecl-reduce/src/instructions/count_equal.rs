@@ -0,0 +1,197 @@
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+
+use crate::precision::ReducePrecision;
+
+use super::{ReduceCoordinate, ReduceFamily, ReduceInstruction, ReduceRequirements};
+
+#[derive_cube_comptime]
+pub enum CountEqualConfig {
+    /// Count the items exactly equal to `target`.
+    Exact { target: i64 },
+    /// Count the items within a tolerance of a target, compared in `f32`.
+    Approx {
+        // Bits of the target and the tolerance, so the config can be hashed.
+        target: u32,
+        tolerance: u32,
+    },
+}
+
+impl CountEqualConfig {
+    /// Count the items equal to `target`.
+    pub fn exact(target: i64) -> Self {
+        Self::Exact { target }
+    }
+
+    /// Count the items `x` such that `|x - target| <= tolerance`.
+    pub fn approx(target: f32, tolerance: f32) -> Self {
+        assert!(
+            tolerance >= 0.0 && tolerance.is_finite(),
+            "The tolerance must be finite and non-negative, got {tolerance}"
+        );
+        Self::Approx {
+            target: target.to_bits(),
+            tolerance: tolerance.to_bits(),
+        }
+    }
+
+    /// The target and the tolerance of an approximate comparison.
+    pub fn tolerance(&self) -> Option<(f32, f32)> {
+        match self {
+            Self::Exact { .. } => None,
+            Self::Approx { target, tolerance } => {
+                Some((f32::from_bits(*target), f32::from_bits(*tolerance)))
+            }
+        }
+    }
+
+    fn exact_target(&self) -> i64 {
+        match self {
+            Self::Exact { target } => *target,
+            Self::Approx { .. } => unreachable!("Approximate comparisons have a float target"),
+        }
+    }
+
+    /// An item that never matches, used as the null input.
+    fn mismatch(&self) -> f32 {
+        match self.tolerance() {
+            Some((target, tolerance)) if target.abs() > tolerance => 0.0,
+            Some((target, tolerance)) => target + 2.0 * tolerance + 1.0,
+            None if self.exact_target() == 0 => 1.0,
+            None => 0.0,
+        }
+    }
+}
+
+/// Count the items equal to a comptime target, such as the occurrences of a class label.
+///
+/// Exact equality is fragile for floats, so [`CountEqualConfig::approx`] counts the items
+/// within a tolerance of the target instead. The matches are accumulated in `u32`, whatever the
+/// reduce precision, so counts stay exact past the integers a float accumulator can represent.
+#[derive(Debug, CubeType, Clone)]
+pub struct CountEqual {
+    #[cube(comptime)]
+    pub config: CountEqualConfig,
+}
+
+impl ReduceFamily for CountEqual {
+    type Instruction<P: ReducePrecision> = Self;
+    type Config = CountEqualConfig;
+
+    fn identity<Out: Numeric>(_config: Self::Config) -> Option<Out> {
+        Some(Out::from_int(0))
+    }
+}
+
+#[cube]
+impl CountEqual {
+    fn count<N: Numeric>(this: &Self, item: Line<N>) -> Line<u32> {
+        let line_size = item.size();
+        let matches = if comptime![this.config.tolerance().is_some()] {
+            let target = f32::new(comptime![this.config.tolerance().unwrap().0]);
+            let tolerance = f32::new(comptime![this.config.tolerance().unwrap().1]);
+            let distance =
+                Line::abs(Line::<f32>::cast_from(item) - Line::empty(line_size).fill(target));
+            distance.less_equal(Line::empty(line_size).fill(tolerance))
+        } else {
+            let target = N::from_int(comptime![this.config.exact_target()]);
+            item.equal(Line::empty(line_size).fill(target))
+        };
+        select_many(
+            matches,
+            Line::empty(line_size).fill(1u32),
+            Line::empty(line_size).fill(0u32),
+        )
+    }
+}
+
+#[cube]
+impl<P: ReducePrecision> ReduceInstruction<P> for CountEqual {
+    type AccumulatorItem = Line<u32>;
+    type SharedAccumulator = SharedMemory<Line<u32>>;
+    type Config = CountEqualConfig;
+
+    fn requirements(_this: &Self) -> ReduceRequirements {
+        ReduceRequirements { coordinates: false }
+    }
+
+    fn from_config(#[comptime] config: Self::Config) -> Self {
+        CountEqual { config }
+    }
+
+    fn null_input(this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
+        Line::empty(line_size).fill(P::EI::cast_from(f32::new(comptime![
+            this.config.mismatch()
+        ])))
+    }
+
+    fn identity(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        Line::empty(line_size).fill(0u32)
+    }
+
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <CountEqual as ReduceInstruction<P>>::identity(this, line_size)
+    }
+
+    fn assign_accumulator(
+        _this: &Self,
+        destination: &mut Self::AccumulatorItem,
+        source: &Self::AccumulatorItem,
+    ) {
+        *destination = *source;
+    }
+
+    fn reduce(
+        this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        item: Line<P::EI>,
+        _coordinate: ReduceCoordinate,
+        #[comptime] use_planes: bool,
+    ) -> Self::AccumulatorItem {
+        let count = Self::count::<P::EI>(this, item);
+        if use_planes {
+            *accumulator + plane_sum(count)
+        } else {
+            *accumulator + count
+        }
+    }
+
+    fn fuse_accumulators(
+        _this: &Self,
+        lhs: Self::AccumulatorItem,
+        rhs: Self::AccumulatorItem,
+    ) -> Self::AccumulatorItem {
+        lhs + rhs
+    }
+
+    fn merge_line<Out: Numeric>(
+        _this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Out {
+        let mut count = 0u32;
+        #[unroll]
+        for k in 0..accumulator.size() {
+            count += accumulator[k];
+        }
+        Out::cast_from(count)
+    }
+
+    fn to_output_perpendicular<Out: Numeric>(
+        _this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Line<Out> {
+        Line::cast_from(accumulator)
+    }
+
+    fn combine_outputs<Out: Numeric>(
+        _this: &Self,
+        lhs: Line<Out>,
+        _lhs_count: u32,
+        rhs: Line<Out>,
+        _rhs_count: u32,
+    ) -> Line<Out> {
+        lhs + rhs
+    }
+}
@@ -0,0 +1,120 @@
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+
+use crate::precision::ReducePrecision;
+
+use super::{ReduceCoordinate, ReduceFamily, ReduceInstruction, ReduceRequirements};
+
+/// Count the items that are not zero.
+#[derive(Debug, CubeType, Clone)]
+pub struct CountNonzero {}
+
+impl ReduceFamily for CountNonzero {
+    type Instruction<P: ReducePrecision> = Self;
+    type Config = ();
+
+    fn identity<Out: Numeric>(_config: Self::Config) -> Option<Out> {
+        Some(Out::from_int(0))
+    }
+}
+
+#[cube]
+impl CountNonzero {
+    fn count<N: Numeric, Acc: Numeric>(item: Line<N>) -> Line<Acc> {
+        let line_size = item.size();
+        select_many(
+            item.not_equal(Line::empty(line_size).fill(N::from_int(0))),
+            Line::empty(line_size).fill(Acc::from_int(1)),
+            Line::empty(line_size).fill(Acc::from_int(0)),
+        )
+    }
+}
+
+#[cube]
+impl<P: ReducePrecision> ReduceInstruction<P> for CountNonzero {
+    type AccumulatorItem = Line<P::EA>;
+    type SharedAccumulator = SharedMemory<Line<P::EA>>;
+    type Config = ();
+
+    fn requirements(_this: &Self) -> ReduceRequirements {
+        ReduceRequirements { coordinates: false }
+    }
+
+    fn from_config(_config: Self::Config) -> Self {
+        CountNonzero {}
+    }
+
+    fn null_input(_this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
+        Line::empty(line_size).fill(P::EI::from_int(0))
+    }
+
+    fn identity(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        Line::empty(line_size).fill(P::EA::from_int(0))
+    }
+
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <CountNonzero as ReduceInstruction<P>>::identity(this, line_size)
+    }
+
+    fn assign_accumulator(
+        _this: &Self,
+        destination: &mut Self::AccumulatorItem,
+        source: &Self::AccumulatorItem,
+    ) {
+        *destination = *source;
+    }
+
+    fn reduce(
+        _this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        item: Line<P::EI>,
+        _coordinate: ReduceCoordinate,
+        #[comptime] use_planes: bool,
+    ) -> Self::AccumulatorItem {
+        let count = Self::count::<P::EI, P::EA>(item);
+        if use_planes {
+            *accumulator + plane_sum(count)
+        } else {
+            *accumulator + count
+        }
+    }
+
+    fn fuse_accumulators(
+        _this: &Self,
+        lhs: Self::AccumulatorItem,
+        rhs: Self::AccumulatorItem,
+    ) -> Self::AccumulatorItem {
+        lhs + rhs
+    }
+
+    fn merge_line<Out: Numeric>(
+        _this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Out {
+        let mut count = P::EA::from_int(0);
+        #[unroll]
+        for k in 0..accumulator.size() {
+            count += accumulator[k];
+        }
+        Out::cast_from(count)
+    }
+
+    fn to_output_perpendicular<Out: Numeric>(
+        _this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Line<Out> {
+        Line::cast_from(accumulator)
+    }
+
+    fn combine_outputs<Out: Numeric>(
+        _this: &Self,
+        lhs: Line<Out>,
+        _lhs_count: u32,
+        rhs: Line<Out>,
+        _rhs_count: u32,
+    ) -> Line<Out> {
+        lhs + rhs
+    }
+}
@@ -0,0 +1,267 @@
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+
+use crate::ReduceError;
+use crate::precision::ReducePrecision;
+
+use super::{Max, Min, ReduceCoordinate, ReduceFamily, ReduceInstruction, ReduceRequirements};
+
+#[derive_cube_comptime]
+pub struct DecayConfig {
+    // Bits of the decay factor, so the config can be hashed.
+    decay: u32,
+}
+
+impl DecayConfig {
+    /// The factor applied to the running value before each update, between `0` and `1`.
+    ///
+    /// This returns [`ReduceError::InvalidDecay`] for the other factors, including NaN.
+    pub fn new(decay: f32) -> Result<Self, ReduceError> {
+        if !(0.0..=1.0).contains(&decay) {
+            return Err(ReduceError::InvalidDecay);
+        }
+        Ok(Self {
+            decay: decay.to_bits(),
+        })
+    }
+
+    /// The factor applied to the running value before each update.
+    pub fn decay(&self) -> f32 {
+        f32::from_bits(self.decay)
+    }
+}
+
+/// The running value scaled by the decay factor, computed in `f32`.
+#[cube]
+fn decayed<Out: Numeric>(running: Line<Out>, #[comptime] config: DecayConfig) -> Line<Out> {
+    let decay = Line::empty(running.size()).fill(f32::new(comptime![config.decay()]));
+    Line::cast_from(Line::<f32>::cast_from(running) * decay)
+}
+
+/// Return the item with the maximum value like [`Max`], with old values decaying when updated
+/// by [`reduce_update`](crate::reduce_update).
+///
+/// Each chunk is reduced to its maximum, and the running value becomes
+/// `max(decay * running, chunk)`, an exponential moving maximum over the chunks.
+/// The decayed value is computed in `f32` before being cast back to the output type.
+#[derive(Debug, CubeType, Clone)]
+pub struct DecayedMax {
+    pub max: Max,
+    #[cube(comptime)]
+    pub config: DecayConfig,
+}
+
+impl ReduceFamily for DecayedMax {
+    type Instruction<P: ReducePrecision> = Self;
+    type Config = DecayConfig;
+
+    fn identity<Out: Numeric>(_config: Self::Config) -> Option<Out> {
+        <Max as ReduceFamily>::identity(())
+    }
+}
+
+#[cube]
+impl<P: ReducePrecision> ReduceInstruction<P> for DecayedMax {
+    type AccumulatorItem = Line<P::EA>;
+    type SharedAccumulator = SharedMemory<Line<P::EA>>;
+    type Config = DecayConfig;
+
+    fn requirements(this: &Self) -> ReduceRequirements {
+        <Max as ReduceInstruction<P>>::requirements(&this.max)
+    }
+
+    fn from_config(#[comptime] config: Self::Config) -> Self {
+        DecayedMax {
+            max: Max {},
+            config,
+        }
+    }
+
+    fn null_input(this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
+        <Max as ReduceInstruction<P>>::null_input(&this.max, line_size)
+    }
+
+    fn identity(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <Max as ReduceInstruction<P>>::identity(&this.max, line_size)
+    }
+
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <Max as ReduceInstruction<P>>::null_accumulator(&this.max, line_size)
+    }
+
+    fn assign_accumulator(
+        _this: &Self,
+        destination: &mut Self::AccumulatorItem,
+        source: &Self::AccumulatorItem,
+    ) {
+        *destination = *source;
+    }
+
+    fn reduce(
+        this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        item: Line<P::EI>,
+        coordinate: ReduceCoordinate,
+        #[comptime] use_planes: bool,
+    ) -> Self::AccumulatorItem {
+        <Max as ReduceInstruction<P>>::reduce(&this.max, accumulator, item, coordinate, use_planes)
+    }
+
+    fn fuse_accumulators(
+        this: &Self,
+        lhs: Self::AccumulatorItem,
+        rhs: Self::AccumulatorItem,
+    ) -> Self::AccumulatorItem {
+        <Max as ReduceInstruction<P>>::fuse_accumulators(&this.max, lhs, rhs)
+    }
+
+    fn merge_line<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        shape_axis_reduce: u32,
+    ) -> Out {
+        <Max as ReduceInstruction<P>>::merge_line::<Out>(&this.max, accumulator, shape_axis_reduce)
+    }
+
+    fn to_output_perpendicular<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        shape_axis_reduce: u32,
+    ) -> Line<Out> {
+        <Max as ReduceInstruction<P>>::to_output_perpendicular::<Out>(
+            &this.max,
+            accumulator,
+            shape_axis_reduce,
+        )
+    }
+
+    fn combine_outputs<Out: Numeric>(
+        this: &Self,
+        lhs: Line<Out>,
+        lhs_count: u32,
+        rhs: Line<Out>,
+        rhs_count: u32,
+    ) -> Line<Out> {
+        <Max as ReduceInstruction<P>>::combine_outputs::<Out>(
+            &this.max,
+            decayed::<Out>(lhs, this.config),
+            lhs_count,
+            rhs,
+            rhs_count,
+        )
+    }
+}
+
+/// Return the item with the minimum value like [`Min`], with old values decaying when updated
+/// by [`reduce_update`](crate::reduce_update).
+///
+/// Each chunk is reduced to its minimum, and the running value becomes
+/// `min(decay * running, chunk)`, an exponential moving minimum over the chunks.
+/// The decayed value is computed in `f32` before being cast back to the output type.
+#[derive(Debug, CubeType, Clone)]
+pub struct DecayedMin {
+    pub min: Min,
+    #[cube(comptime)]
+    pub config: DecayConfig,
+}
+
+impl ReduceFamily for DecayedMin {
+    type Instruction<P: ReducePrecision> = Self;
+    type Config = DecayConfig;
+
+    fn identity<Out: Numeric>(_config: Self::Config) -> Option<Out> {
+        <Min as ReduceFamily>::identity(())
+    }
+}
+
+#[cube]
+impl<P: ReducePrecision> ReduceInstruction<P> for DecayedMin {
+    type AccumulatorItem = Line<P::EA>;
+    type SharedAccumulator = SharedMemory<Line<P::EA>>;
+    type Config = DecayConfig;
+
+    fn requirements(this: &Self) -> ReduceRequirements {
+        <Min as ReduceInstruction<P>>::requirements(&this.min)
+    }
+
+    fn from_config(#[comptime] config: Self::Config) -> Self {
+        DecayedMin {
+            min: Min {},
+            config,
+        }
+    }
+
+    fn null_input(this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
+        <Min as ReduceInstruction<P>>::null_input(&this.min, line_size)
+    }
+
+    fn identity(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <Min as ReduceInstruction<P>>::identity(&this.min, line_size)
+    }
+
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <Min as ReduceInstruction<P>>::null_accumulator(&this.min, line_size)
+    }
+
+    fn assign_accumulator(
+        _this: &Self,
+        destination: &mut Self::AccumulatorItem,
+        source: &Self::AccumulatorItem,
+    ) {
+        *destination = *source;
+    }
+
+    fn reduce(
+        this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        item: Line<P::EI>,
+        coordinate: ReduceCoordinate,
+        #[comptime] use_planes: bool,
+    ) -> Self::AccumulatorItem {
+        <Min as ReduceInstruction<P>>::reduce(&this.min, accumulator, item, coordinate, use_planes)
+    }
+
+    fn fuse_accumulators(
+        this: &Self,
+        lhs: Self::AccumulatorItem,
+        rhs: Self::AccumulatorItem,
+    ) -> Self::AccumulatorItem {
+        <Min as ReduceInstruction<P>>::fuse_accumulators(&this.min, lhs, rhs)
+    }
+
+    fn merge_line<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        shape_axis_reduce: u32,
+    ) -> Out {
+        <Min as ReduceInstruction<P>>::merge_line::<Out>(&this.min, accumulator, shape_axis_reduce)
+    }
+
+    fn to_output_perpendicular<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        shape_axis_reduce: u32,
+    ) -> Line<Out> {
+        <Min as ReduceInstruction<P>>::to_output_perpendicular::<Out>(
+            &this.min,
+            accumulator,
+            shape_axis_reduce,
+        )
+    }
+
+    fn combine_outputs<Out: Numeric>(
+        this: &Self,
+        lhs: Line<Out>,
+        lhs_count: u32,
+        rhs: Line<Out>,
+        rhs_count: u32,
+    ) -> Line<Out> {
+        <Min as ReduceInstruction<P>>::combine_outputs::<Out>(
+            &this.min,
+            decayed::<Out>(lhs, this.config),
+            lhs_count,
+            rhs,
+            rhs_count,
+        )
+    }
+}
@@ -0,0 +1,199 @@
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+
+use crate::precision::ReducePrecision;
+
+use super::{
+    ReduceCoordinate, ReduceFamily, ReduceInstruction, ReduceRequirements, SharedAccumulator,
+};
+
+#[derive_cube_comptime]
+pub struct EntropyConfig {
+    /// Divide the items by their sum before computing the entropy,
+    /// for inputs that aren't already probabilities summing to `1`.
+    pub normalize: bool,
+}
+
+/// Compute the entropy `-sum(p * log(p))` of probabilities, in nats.
+///
+/// Zero probabilities don't contribute to the entropy, following the convention `0 * log(0) = 0`.
+/// When `normalize` is set, the sum of the items `s` is accumulated along with `sum(p * log(p))`,
+/// and the entropy of `p / s` is computed as `log(s) - sum(p * log(p)) / s` in the output, so
+/// the input is still read only once. The items must not be negative.
+///
+/// The accumulation is always done in `f32`, regardless of the reduce precision.
+/// Normalized entropies can't be combined by [`reduce_update`](crate::reduce_update),
+/// since the sums of the chunks aren't kept in the output.
+#[derive(Debug, CubeType, Clone)]
+pub struct Entropy {
+    #[cube(comptime)]
+    pub normalize: bool,
+}
+
+impl ReduceFamily for Entropy {
+    type Instruction<P: ReducePrecision> = Self;
+    type Config = EntropyConfig;
+
+    fn identity<Out: Numeric>(config: Self::Config) -> Option<Out> {
+        // The entropy of an empty distribution is zero, but it can't be normalized.
+        (!config.normalize).then(|| Out::from_int(0))
+    }
+
+    fn supports_combine(config: Self::Config) -> bool {
+        // The sums of the normalized entropies aren't kept.
+        !config.normalize
+    }
+}
+
+#[cube]
+impl Entropy {
+    /// Compute `p * log(p)` and `p` for the items, with `0 * log(0) = 0`.
+    fn split<N: Numeric>(items: Line<N>) -> (Line<f32>, Line<f32>) {
+        let items = Line::<f32>::cast_from(items);
+        let zero = Line::empty(items.size()).fill(f32::from_int(0));
+        let plogp = select_many(items.equal(zero), zero, items * Log::log(items));
+        (plogp, items)
+    }
+
+    fn entropy(this: &Self, plogp: Line<f32>, sum: Line<f32>) -> Line<f32> {
+        if comptime!(this.normalize) {
+            Log::log(sum) - plogp / sum
+        } else {
+            Line::empty(plogp.size()).fill(f32::from_int(0)) - plogp
+        }
+    }
+}
+
+#[cube]
+impl<P: ReducePrecision> ReduceInstruction<P> for Entropy {
+    type AccumulatorItem = (Line<f32>, Line<f32>);
+    type SharedAccumulator = EntropyAccumulator;
+    type Config = EntropyConfig;
+
+    fn requirements(_this: &Self) -> ReduceRequirements {
+        ReduceRequirements { coordinates: false }
+    }
+
+    fn from_config(#[comptime] config: Self::Config) -> Self {
+        Entropy {
+            normalize: config.normalize,
+        }
+    }
+
+    fn null_input(_this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
+        Line::empty(line_size).fill(P::EI::from_int(0))
+    }
+
+    fn identity(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        (
+            Line::empty(line_size).fill(f32::from_int(0)),
+            Line::empty(line_size).fill(f32::from_int(0)),
+        )
+    }
+
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <Entropy as ReduceInstruction<P>>::identity(this, line_size)
+    }
+
+    fn assign_accumulator(
+        _this: &Self,
+        destination: &mut Self::AccumulatorItem,
+        source: &Self::AccumulatorItem,
+    ) {
+        destination.0 = source.0;
+        destination.1 = source.1;
+    }
+
+    fn reduce(
+        _this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        item: Line<P::EI>,
+        _coordinate: ReduceCoordinate,
+        #[comptime] use_planes: bool,
+    ) -> Self::AccumulatorItem {
+        let (plogp, items) = Self::split(item);
+        if use_planes {
+            (
+                accumulator.0 + plane_sum(plogp),
+                accumulator.1 + plane_sum(items),
+            )
+        } else {
+            (accumulator.0 + plogp, accumulator.1 + items)
+        }
+    }
+
+    fn fuse_accumulators(
+        _this: &Self,
+        lhs: Self::AccumulatorItem,
+        rhs: Self::AccumulatorItem,
+    ) -> Self::AccumulatorItem {
+        (lhs.0 + rhs.0, lhs.1 + rhs.1)
+    }
+
+    fn merge_line<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Out {
+        let mut plogp = f32::from_int(0);
+        let mut sum = f32::from_int(0);
+        #[unroll]
+        for k in 0..accumulator.0.size() {
+            plogp += accumulator.0[k];
+            sum += accumulator.1[k];
+        }
+        let entropy = Self::entropy(this, Line::new(plogp), Line::new(sum));
+        Out::cast_from(entropy[0])
+    }
+
+    fn to_output_perpendicular<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Line<Out> {
+        Line::cast_from(Self::entropy(this, accumulator.0, accumulator.1))
+    }
+
+    fn combine_outputs<Out: Numeric>(
+        _this: &Self,
+        lhs: Line<Out>,
+        _lhs_count: u32,
+        rhs: Line<Out>,
+        _rhs_count: u32,
+    ) -> Line<Out> {
+        // Normalized entropies are never combined, `supports_combine` is false for them.
+        lhs + rhs
+    }
+}
+
+/// A pair of shared memory used for [`Entropy`], holding the sums of `p * log(p)` and of `p`.
+#[derive(CubeType)]
+pub struct EntropyAccumulator {
+    pub plogp: SharedMemory<Line<f32>>,
+    pub sums: SharedMemory<Line<f32>>,
+}
+
+#[cube]
+impl SharedAccumulator for EntropyAccumulator {
+    type Item = (Line<f32>, Line<f32>);
+
+    fn allocate(
+        #[comptime] length: u32,
+        #[comptime] line_size: u32,
+        #[comptime] _coordinate: bool,
+    ) -> Self {
+        EntropyAccumulator {
+            plogp: SharedMemory::new_lined(length, line_size),
+            sums: SharedMemory::new_lined(length, line_size),
+        }
+    }
+
+    fn read(accumulator: &Self, index: u32) -> Self::Item {
+        (accumulator.plogp[index], accumulator.sums[index])
+    }
+
+    fn write(accumulator: &mut Self, index: u32, item: Self::Item) {
+        accumulator.plogp[index] = item.0;
+        accumulator.sums[index] = item.1;
+    }
+}
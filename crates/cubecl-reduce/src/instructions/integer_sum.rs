@@ -0,0 +1,337 @@
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+use cubecl_std::{CubeOption, CubeOptionExpand};
+
+use crate::precision::ReducePrecision;
+
+use super::{
+    ReduceCoordinate, ReduceFamily, ReduceInstruction, ReduceRequirements, SharedAccumulator,
+};
+
+/// How [`IntegerSum`] handles a sum that doesn't fit in the accumulation or output type.
+#[derive_cube_comptime]
+pub enum OverflowMode {
+    /// Wrap around, like a plain integer addition.
+    Wrap,
+    /// Clamp the sum to the bounds of the type.
+    Saturate,
+    /// Wrap around, and raise the overflow flag of a [checked](IntegerSum::checked) sum when
+    /// any sum overflowed.
+    ///
+    /// See [`reduce_sum_checked`](crate::reduce_sum_checked) to get an error on the host when the
+    /// flag is raised. The instruction of a config has no flag, so the sums of
+    /// [`reduce`](crate::reduce) are the same as with [`OverflowMode::Wrap`].
+    Error,
+}
+
+/// Compute the sum of integer items, handling overflows according to an [`OverflowMode`].
+///
+/// The sum overflows when it doesn't fit in the accumulation type while accumulating,
+/// or when it doesn't fit in the output type when writing the output.
+///
+/// With planes, the items of a plane are only summed with a saturating or checked addition one
+/// lane at a time, so [`OverflowMode::Wrap`] is much faster there.
+#[derive(CubeType)]
+pub struct IntegerSum {
+    #[cube(comptime)]
+    pub mode: OverflowMode,
+    /// The flag set to `1` when a sum overflows in [`OverflowMode::Error`].
+    pub overflow: CubeOption<Array<Atomic<u32>>>,
+}
+
+impl core::fmt::Debug for IntegerSum {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("IntegerSum")
+            .field("mode", &self.mode)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ReduceFamily for IntegerSum {
+    type Instruction<P: ReducePrecision> = Self;
+    type Config = OverflowMode;
+
+    fn identity<Out: Numeric>(_config: Self::Config) -> Option<Out> {
+        Some(Out::from_int(0))
+    }
+}
+
+#[cube]
+impl IntegerSum {
+    /// An integer sum in [`OverflowMode::Error`], raising the `overflow` flag when a sum
+    /// overflows.
+    ///
+    /// The flag is only ever set to `1`, so it must be `0` before the launch.
+    pub fn checked(overflow: &mut Array<Atomic<u32>>) -> IntegerSum {
+        IntegerSum {
+            mode: comptime![OverflowMode::Error],
+            overflow: CubeOption::new_Some(*overflow),
+        }
+    }
+
+    /// Add the lines with a wrapping sum, and flag the lanes where it overflowed with `1`.
+    fn checked_add<N: Numeric>(lhs: Line<N>, rhs: Line<N>) -> (Line<N>, Line<u32>) {
+        let zero = Line::empty(lhs.size()).fill(N::from_int(0));
+        let sum = lhs + rhs;
+
+        // Adding a positive item can't decrease the sum, nor a negative one increase it.
+        let overflowed = select_many(
+            rhs.greater_equal(zero),
+            sum.less_than(lhs),
+            sum.greater_than(lhs),
+        );
+        (sum, Self::flag(overflowed))
+    }
+
+    /// Add the lines with a sum clamped to the bounds of `N`,
+    /// and flag the lanes where it overflowed with `1`.
+    fn saturating_add<N: Numeric>(lhs: Line<N>, rhs: Line<N>) -> (Line<N>, Line<u32>) {
+        let line_size = lhs.size();
+        let zero = Line::empty(line_size).fill(N::from_int(0));
+        let (sum, overflowed) = Self::checked_add(lhs, rhs);
+
+        let bound = select_many(
+            rhs.greater_equal(zero),
+            Line::empty(line_size).fill(N::max_value()),
+            Line::empty(line_size).fill(N::min_value()),
+        );
+        let saturated = select_many(
+            overflowed.not_equal(Line::empty(line_size).fill(0u32)),
+            bound,
+            sum,
+        );
+        (saturated, overflowed)
+    }
+
+    fn flag(condition: Line<bool>) -> Line<u32> {
+        let line_size = condition.size();
+        select_many(
+            condition,
+            Line::empty(line_size).fill(1u32),
+            Line::empty(line_size).fill(0u32),
+        )
+    }
+
+    /// Add `rhs` to the sum, wrapping around unless the mode asks otherwise.
+    fn accumulate<N: Numeric>(
+        this: &Self,
+        sum: Line<N>,
+        overflowed: Line<u32>,
+        rhs: Line<N>,
+    ) -> (Line<N>, Line<u32>) {
+        match comptime!(this.mode) {
+            OverflowMode::Wrap => (sum + rhs, overflowed),
+            OverflowMode::Saturate => {
+                let (sum, rhs_overflowed) = Self::saturating_add(sum, rhs);
+                (sum, overflowed | rhs_overflowed)
+            }
+            OverflowMode::Error => {
+                let (sum, rhs_overflowed) = Self::checked_add(sum, rhs);
+                (sum, overflowed | rhs_overflowed)
+            }
+        }
+    }
+
+    /// Raise the overflow flag, if there is one, when any lane of `overflowed` is flagged.
+    fn raise(this: &Self, overflowed: Line<u32>) {
+        match this.overflow {
+            CubeOption::Some(flag) => {
+                let mut any = 0u32;
+                #[unroll]
+                for k in 0..overflowed.size() {
+                    any |= overflowed[k];
+                }
+                if any != 0 {
+                    Atomic::store(&flag[0], 1u32);
+                }
+            }
+            CubeOption::None => {}
+        }
+    }
+
+    /// Convert the accumulated sums to the output type according to the mode.
+    fn to_output<Acc: Numeric, Out: Numeric>(
+        this: &Self,
+        sum: Line<Acc>,
+        overflowed: Line<u32>,
+    ) -> Line<Out> {
+        let line_size = sum.size();
+        let output = Line::<Out>::cast_from(sum);
+        // Whether the conversion to the output type lost information.
+        let truncated = Line::<Acc>::cast_from(output).not_equal(sum);
+
+        match comptime!(this.mode) {
+            OverflowMode::Wrap => output,
+            OverflowMode::Saturate => select_many(
+                truncated,
+                select_many(
+                    sum.less_than(Line::empty(line_size).fill(Acc::from_int(0))),
+                    Line::empty(line_size).fill(Out::min_value()),
+                    Line::empty(line_size).fill(Out::max_value()),
+                ),
+                output,
+            ),
+            OverflowMode::Error => {
+                Self::raise(this, overflowed | Self::flag(truncated));
+                output
+            }
+        }
+    }
+}
+
+#[cube]
+impl<P: ReducePrecision> ReduceInstruction<P> for IntegerSum {
+    type AccumulatorItem = (Line<P::EA>, Line<u32>);
+    type SharedAccumulator = IntegerSumAccumulator<P::EA>;
+    type Config = OverflowMode;
+
+    fn requirements(_this: &Self) -> ReduceRequirements {
+        ReduceRequirements { coordinates: false }
+    }
+
+    fn from_config(#[comptime] config: Self::Config) -> Self {
+        IntegerSum {
+            mode: config,
+            overflow: CubeOption::new_None(),
+        }
+    }
+
+    fn null_input(_this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
+        Line::empty(line_size).fill(P::EI::from_int(0))
+    }
+
+    fn identity(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        (
+            Line::empty(line_size).fill(P::EA::from_int(0)),
+            Line::empty(line_size).fill(0u32),
+        )
+    }
+
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <IntegerSum as ReduceInstruction<P>>::identity(this, line_size)
+    }
+
+    fn assign_accumulator(
+        _this: &Self,
+        destination: &mut Self::AccumulatorItem,
+        source: &Self::AccumulatorItem,
+    ) {
+        destination.0 = source.0;
+        destination.1 = source.1;
+    }
+
+    fn reduce(
+        this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        item: Line<P::EI>,
+        _coordinate: ReduceCoordinate,
+        #[comptime] use_planes: bool,
+    ) -> Self::AccumulatorItem {
+        let item = Line::<P::EA>::cast_from(item);
+        if use_planes {
+            match comptime!(this.mode) {
+                OverflowMode::Wrap => (accumulator.0 + plane_sum(item), accumulator.1),
+                _ => {
+                    let mut sum = accumulator.0;
+                    let mut overflowed = accumulator.1;
+                    for lane in 0..PLANE_DIM {
+                        let (lane_sum, lane_overflowed) =
+                            Self::accumulate(this, sum, overflowed, plane_broadcast(item, lane));
+                        sum = lane_sum;
+                        overflowed = lane_overflowed;
+                    }
+                    (sum, overflowed)
+                }
+            }
+        } else {
+            Self::accumulate(this, accumulator.0, accumulator.1, item)
+        }
+    }
+
+    fn fuse_accumulators(
+        this: &Self,
+        lhs: Self::AccumulatorItem,
+        rhs: Self::AccumulatorItem,
+    ) -> Self::AccumulatorItem {
+        Self::accumulate(this, lhs.0, lhs.1 | rhs.1, rhs.0)
+    }
+
+    fn merge_line<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Out {
+        let mut sum = Line::empty(1u32).fill(P::EA::from_int(0));
+        let mut overflowed = Line::empty(1u32).fill(0u32);
+        #[unroll]
+        for k in 0..accumulator.0.size() {
+            let (lane_sum, lane_overflowed) = Self::accumulate(
+                this,
+                sum,
+                overflowed | Line::empty(1u32).fill(accumulator.1[k]),
+                Line::empty(1u32).fill(accumulator.0[k]),
+            );
+            sum = lane_sum;
+            overflowed = lane_overflowed;
+        }
+        Self::to_output::<P::EA, Out>(this, sum, overflowed)[0]
+    }
+
+    fn to_output_perpendicular<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Line<Out> {
+        Self::to_output::<P::EA, Out>(this, accumulator.0, accumulator.1)
+    }
+
+    fn combine_outputs<Out: Numeric>(
+        this: &Self,
+        lhs: Line<Out>,
+        _lhs_count: u32,
+        rhs: Line<Out>,
+        _rhs_count: u32,
+    ) -> Line<Out> {
+        match comptime!(this.mode) {
+            OverflowMode::Wrap => lhs + rhs,
+            OverflowMode::Saturate => Self::saturating_add(lhs, rhs).0,
+            OverflowMode::Error => {
+                let (sum, overflowed) = Self::checked_add(lhs, rhs);
+                Self::raise(this, overflowed);
+                sum
+            }
+        }
+    }
+}
+
+/// A pair of shared memory used for [`IntegerSum`], holding the sums and the overflow flags.
+#[derive(CubeType)]
+pub struct IntegerSumAccumulator<N: Numeric> {
+    pub sums: SharedMemory<Line<N>>,
+    pub overflows: SharedMemory<Line<u32>>,
+}
+
+#[cube]
+impl<N: Numeric> SharedAccumulator for IntegerSumAccumulator<N> {
+    type Item = (Line<N>, Line<u32>);
+
+    fn allocate(
+        #[comptime] length: u32,
+        #[comptime] line_size: u32,
+        #[comptime] _coordinate: bool,
+    ) -> Self {
+        IntegerSumAccumulator::<N> {
+            sums: SharedMemory::new_lined(length, line_size),
+            overflows: SharedMemory::new_lined(length, line_size),
+        }
+    }
+
+    fn read(accumulator: &Self, index: u32) -> Self::Item {
+        (accumulator.sums[index], accumulator.overflows[index])
+    }
+
+    fn write(accumulator: &mut Self, index: u32, item: Self::Item) {
+        accumulator.sums[index] = item.0;
+        accumulator.overflows[index] = item.1;
+    }
+}
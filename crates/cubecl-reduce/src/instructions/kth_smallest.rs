@@ -0,0 +1,289 @@
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+
+use crate::precision::ReducePrecision;
+
+use super::{
+    ReduceCoordinate, ReduceCoordinateExpand, ReduceFamily, ReduceInstruction, ReduceRequirements,
+    SharedAccumulator,
+};
+
+/// Which part of the k-th smallest item [`KthSmallest`] outputs.
+#[derive_cube_comptime]
+pub enum KthSmallestOutput {
+    Value,
+    /// The coordinate of the item along the reduced axis.
+    Index,
+}
+
+#[derive_cube_comptime]
+pub struct KthSmallestConfig {
+    /// The rank of the item to find, starting at `1` for the minimum.
+    pub k: u32,
+    pub output: KthSmallestOutput,
+}
+
+/// Compute the k-th smallest item, or its coordinate, with the smallest coordinate selected
+/// in case of equality.
+///
+/// The `k` smallest items seen so far are kept sorted in the accumulator, so `k` is comptime
+/// and should stay small: each item costs `k` comparisons and the accumulator takes `k` lines
+/// of registers. The median of an axis is only practical this way when the axis is small too,
+/// larger axes would need a multi-pass selection.
+///
+/// The accumulator doesn't fit in the shared memory of the other instructions, so only the
+/// strategies without `shared` are supported. The axis must have at least `k` items.
+#[derive(Debug, CubeType, Clone)]
+pub struct KthSmallest {
+    #[cube(comptime)]
+    pub k: u32,
+    #[cube(comptime)]
+    pub output: KthSmallestOutput,
+}
+
+impl ReduceFamily for KthSmallest {
+    type Instruction<P: ReducePrecision> = Self;
+    type Config = KthSmallestConfig;
+
+    fn supports_combine(_config: Self::Config) -> bool {
+        false
+    }
+}
+
+#[cube]
+impl KthSmallest {
+    /// Insert the candidates into the sorted items, dropping the largest one of each lane.
+    fn insert<N: Numeric>(
+        values: &mut Array<Line<N>>,
+        coordinates: &mut Array<Line<u32>>,
+        value: Line<N>,
+        coordinate: Line<u32>,
+        #[comptime] k: u32,
+    ) {
+        let mut value = value;
+        let mut coordinate = coordinate;
+
+        #[unroll]
+        for i in 0..k {
+            let current_value = values[i];
+            let current_coordinate = coordinates[i];
+            let is_before = select_many(
+                value.equal(current_value),
+                coordinate.less_than(current_coordinate),
+                value.less_than(current_value),
+            );
+
+            values[i] = select_many(is_before, value, current_value);
+            coordinates[i] = select_many(is_before, coordinate, current_coordinate);
+            value = select_many(is_before, current_value, value);
+            coordinate = select_many(is_before, current_coordinate, coordinate);
+        }
+    }
+
+    fn copy<N: Numeric>(
+        items: &(Array<Line<N>>, Array<Line<u32>>),
+        #[comptime] k: u32,
+    ) -> (Array<Line<N>>, Array<Line<u32>>) {
+        let line_size = items.0.line_size();
+        let mut values = Array::<Line<N>>::vectorized(k, line_size);
+        let mut coordinates = Array::<Line<u32>>::vectorized(k, line_size);
+
+        #[unroll]
+        for i in 0..k {
+            values[i] = items.0[i];
+            coordinates[i] = items.1[i];
+        }
+        (values, coordinates)
+    }
+
+    fn to_output<N: Numeric, Out: Numeric>(
+        this: &Self,
+        value: Line<N>,
+        coordinate: Line<u32>,
+    ) -> Line<Out> {
+        match comptime!(this.output) {
+            KthSmallestOutput::Value => Line::cast_from(value),
+            KthSmallestOutput::Index => Line::cast_from(coordinate),
+        }
+    }
+}
+
+#[cube]
+impl<P: ReducePrecision> ReduceInstruction<P> for KthSmallest {
+    type AccumulatorItem = (Array<Line<P::EA>>, Array<Line<u32>>);
+    type SharedAccumulator = KthSmallestAccumulator<P::EA>;
+    type Config = KthSmallestConfig;
+
+    fn requirements(_this: &Self) -> ReduceRequirements {
+        ReduceRequirements { coordinates: true }
+    }
+
+    fn from_config(#[comptime] config: Self::Config) -> Self {
+        KthSmallest {
+            k: config.k,
+            output: config.output,
+        }
+    }
+
+    fn null_input(_this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
+        Line::empty(line_size).fill(P::EI::max_value())
+    }
+
+    fn identity(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        let mut values = Array::<Line<P::EA>>::vectorized(this.k, line_size);
+        let mut coordinates = Array::<Line<u32>>::vectorized(this.k, line_size);
+
+        #[unroll]
+        for i in 0..this.k {
+            values[i] = Line::empty(line_size).fill(P::EA::max_value());
+            coordinates[i] = Line::empty(line_size).fill(u32::MAX);
+        }
+        (values, coordinates)
+    }
+
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <KthSmallest as ReduceInstruction<P>>::identity(this, line_size)
+    }
+
+    fn assign_accumulator(
+        this: &Self,
+        destination: &mut Self::AccumulatorItem,
+        source: &Self::AccumulatorItem,
+    ) {
+        #[unroll]
+        for i in 0..this.k {
+            destination.0[i] = source.0[i];
+            destination.1[i] = source.1[i];
+        }
+    }
+
+    fn reduce(
+        this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        item: Line<P::EI>,
+        coordinate: ReduceCoordinate,
+        #[comptime] use_planes: bool,
+    ) -> Self::AccumulatorItem {
+        let coordinate = match coordinate {
+            ReduceCoordinate::Required(val) => val,
+            ReduceCoordinate::NotRequired => {
+                comptime! {panic!("Coordinates are required for KthSmallest")};
+                #[allow(unreachable_code)]
+                Line::new(0)
+            }
+        };
+        let item = Line::<P::EA>::cast_from(item);
+        let (mut values, mut coordinates) = Self::copy(accumulator, this.k);
+
+        if use_planes {
+            for lane in 0..PLANE_DIM {
+                Self::insert(
+                    &mut values,
+                    &mut coordinates,
+                    plane_broadcast(item, lane),
+                    plane_broadcast(coordinate, lane),
+                    this.k,
+                );
+            }
+        } else {
+            Self::insert(&mut values, &mut coordinates, item, coordinate, this.k);
+        }
+        (values, coordinates)
+    }
+
+    fn fuse_accumulators(
+        this: &Self,
+        lhs: Self::AccumulatorItem,
+        rhs: Self::AccumulatorItem,
+    ) -> Self::AccumulatorItem {
+        let (mut values, mut coordinates) = Self::copy(&lhs, this.k);
+
+        #[unroll]
+        for i in 0..this.k {
+            Self::insert(&mut values, &mut coordinates, rhs.0[i], rhs.1[i], this.k);
+        }
+        (values, coordinates)
+    }
+
+    fn merge_line<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Out {
+        let (mut values, mut coordinates) =
+            <KthSmallest as ReduceInstruction<P>>::identity(this, 1u32);
+
+        #[unroll]
+        for lane in 0..accumulator.0.line_size() {
+            #[unroll]
+            for i in 0..this.k {
+                Self::insert(
+                    &mut values,
+                    &mut coordinates,
+                    Line::empty(1u32).fill(accumulator.0[i][lane]),
+                    Line::empty(1u32).fill(accumulator.1[i][lane]),
+                    this.k,
+                );
+            }
+        }
+        let last = comptime!(this.k - 1);
+        Self::to_output::<P::EA, Out>(this, values[last], coordinates[last])[0]
+    }
+
+    fn to_output_perpendicular<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Line<Out> {
+        let last = comptime!(this.k - 1);
+        Self::to_output::<P::EA, Out>(this, accumulator.0[last], accumulator.1[last])
+    }
+
+    fn combine_outputs<Out: Numeric>(
+        _this: &Self,
+        lhs: Line<Out>,
+        _lhs_count: u32,
+        _rhs: Line<Out>,
+        _rhs_count: u32,
+    ) -> Line<Out> {
+        // Never called, `supports_combine` is false.
+        lhs
+    }
+}
+
+/// The shared accumulator of [`KthSmallest`], which can't be allocated since the number of
+/// items kept isn't known when allocating.
+#[derive(CubeType)]
+pub struct KthSmallestAccumulator<N: Numeric> {
+    pub values: SharedMemory<Line<N>>,
+}
+
+#[cube]
+impl<N: Numeric> SharedAccumulator for KthSmallestAccumulator<N> {
+    type Item = (Array<Line<N>>, Array<Line<u32>>);
+
+    fn allocate(
+        #[comptime] length: u32,
+        #[comptime] line_size: u32,
+        #[comptime] _coordinate: bool,
+    ) -> Self {
+        comptime! {panic!("KthSmallest doesn't support shared strategies")};
+        #[allow(unreachable_code)]
+        KthSmallestAccumulator::<N> {
+            values: SharedMemory::new_lined(length, line_size),
+        }
+    }
+
+    fn read(_accumulator: &Self, _index: u32) -> Self::Item {
+        comptime! {panic!("KthSmallest doesn't support shared strategies")};
+        #[allow(unreachable_code)]
+        (
+            Array::<Line<N>>::vectorized(1u32, 1u32),
+            Array::<Line<u32>>::vectorized(1u32, 1u32),
+        )
+    }
+
+    fn write(_accumulator: &mut Self, _index: u32, _item: Self::Item) {
+        comptime! {panic!("KthSmallest doesn't support shared strategies")};
+    }
+}
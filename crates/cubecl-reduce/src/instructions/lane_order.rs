@@ -0,0 +1,58 @@
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+
+/// The order in which the lanes of an accumulator are combined into a single value by
+/// [`merge_line`](super::ReduceInstruction::merge_line).
+#[derive_cube_comptime]
+#[derive(Default)]
+pub enum LaneOrder {
+    /// Combine the lanes one after the other, from the first one.
+    #[default]
+    Serial,
+    /// Combine the neighbouring lanes by pairs, then the results by pairs, as a tree.
+    ///
+    /// Each lane goes through a number of roundings logarithmic in the line size instead of
+    /// linear, which keeps the rounding error of wide lines lower.
+    Pairwise,
+}
+
+/// The sum of the lanes of `line`, added as a tree, where the line size is a power of two.
+#[cube]
+pub fn pairwise_sum<N: Numeric>(line: Line<N>) -> N {
+    let line_size = line.size();
+    let mut lanes = line;
+    let mut jump = comptime![1u32];
+
+    #[unroll]
+    #[allow(clippy::explicit_counter_loop)]
+    for _ in 0..comptime![line_size.trailing_zeros()] {
+        #[unroll]
+        for j in 0..comptime![line_size / (2 * jump)] {
+            let k = j * comptime![2 * jump];
+            lanes[k] = lanes[k] + lanes[k + comptime![jump]];
+        }
+        comptime![jump *= 2];
+    }
+    lanes[0]
+}
+
+/// The product of the lanes of `line`, multiplied as a tree, where the line size is a power of
+/// two.
+#[cube]
+pub fn pairwise_prod<N: Numeric>(line: Line<N>) -> N {
+    let line_size = line.size();
+    let mut lanes = line;
+    let mut jump = comptime![1u32];
+
+    #[unroll]
+    #[allow(clippy::explicit_counter_loop)]
+    for _ in 0..comptime![line_size.trailing_zeros()] {
+        #[unroll]
+        for j in 0..comptime![line_size / (2 * jump)] {
+            let k = j * comptime![2 * jump];
+            lanes[k] = lanes[k] * lanes[k + comptime![jump]];
+        }
+        comptime![jump *= 2];
+    }
+    lanes[0]
+}
@@ -0,0 +1,259 @@
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+
+use crate::precision::ReducePrecision;
+
+use super::{
+    ReduceCoordinate, ReduceFamily, ReduceInstruction, ReduceRequirements, SharedAccumulator,
+};
+
+#[derive_cube_comptime]
+pub struct LpNormConfig {
+    // Bits of `p`, so the config can be hashed.
+    p: u32,
+}
+
+impl LpNormConfig {
+    /// The norm of order `p`, which must be at least `1` and may be infinite.
+    pub fn new(p: f32) -> Self {
+        assert!(p >= 1.0, "The order of a norm must be at least 1, got {p}");
+        Self { p: p.to_bits() }
+    }
+
+    /// The maximum of the absolute values of the items.
+    pub fn infinity() -> Self {
+        Self::new(f32::INFINITY)
+    }
+
+    /// The order of the norm.
+    pub fn p(&self) -> f32 {
+        f32::from_bits(self.p)
+    }
+}
+
+/// Compute the p-norm `sum(|x|^p)^(1/p)` of the items.
+///
+/// The norm of order `1` is the sum of the absolute values and the norm of infinite order is
+/// their maximum, both computed without any power. For other orders, the accumulator keeps the
+/// greatest absolute value `s` seen so far along with `sum((|x| / s)^p)`, so the powers never
+/// exceed `1` and don't overflow, and the norm is computed as `s * sum^(1/p)` in the output.
+///
+/// The accumulation is always done in `f32`, regardless of the reduce precision. Norms can't be
+/// combined by [`reduce_update`](crate::reduce_update), except those of order `1` and infinity.
+#[derive(Debug, CubeType, Clone)]
+pub struct LpNorm {
+    #[cube(comptime)]
+    pub config: LpNormConfig,
+}
+
+impl ReduceFamily for LpNorm {
+    type Instruction<P: ReducePrecision> = Self;
+    type Config = LpNormConfig;
+
+    fn identity<Out: Numeric>(_config: Self::Config) -> Option<Out> {
+        Some(Out::from_int(0))
+    }
+
+    fn supports_combine(config: Self::Config) -> bool {
+        config.p() == 1.0 || config.p() == f32::INFINITY
+    }
+}
+
+#[cube]
+impl LpNorm {
+    /// `(lhs / rhs)^p`, where `lhs` isn't greater than `rhs`, with `0` when both are `0`.
+    fn ratio_pow(this: &Self, lhs: Line<f32>, rhs: Line<f32>) -> Line<f32> {
+        let zero = Line::empty(lhs.size()).fill(f32::from_int(0));
+        let p = Line::empty(lhs.size()).fill(f32::new(comptime![this.config.p()]));
+        select_many(rhs.equal(zero), zero, Powf::powf(lhs / rhs, p))
+    }
+
+    /// Fuse two pairs of a scale and a sum of powers scaled by it.
+    fn fuse_scaled(
+        this: &Self,
+        lhs: (Line<f32>, Line<f32>),
+        rhs: (Line<f32>, Line<f32>),
+    ) -> (Line<f32>, Line<f32>) {
+        let scale = select_many(lhs.0.greater_than(rhs.0), lhs.0, rhs.0);
+        let sum = lhs.1 * Self::ratio_pow(this, lhs.0, scale)
+            + rhs.1 * Self::ratio_pow(this, rhs.0, scale);
+        (scale, sum)
+    }
+
+    fn norm(this: &Self, accumulator: (Line<f32>, Line<f32>)) -> Line<f32> {
+        let p = comptime![this.config.p()];
+        if comptime![p == 1.0 || p == f32::INFINITY] {
+            accumulator.0
+        } else {
+            let inverse = Line::empty(accumulator.1.size()).fill(f32::new(comptime![1.0 / p]));
+            accumulator.0 * Powf::powf(accumulator.1, inverse)
+        }
+    }
+}
+
+#[cube]
+impl<P: ReducePrecision> ReduceInstruction<P> for LpNorm {
+    type AccumulatorItem = (Line<f32>, Line<f32>);
+    type SharedAccumulator = LpNormAccumulator;
+    type Config = LpNormConfig;
+
+    fn requirements(_this: &Self) -> ReduceRequirements {
+        ReduceRequirements { coordinates: false }
+    }
+
+    fn from_config(#[comptime] config: Self::Config) -> Self {
+        LpNorm { config }
+    }
+
+    fn null_input(_this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
+        Line::empty(line_size).fill(P::EI::from_int(0))
+    }
+
+    fn identity(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        (
+            Line::empty(line_size).fill(f32::from_int(0)),
+            Line::empty(line_size).fill(f32::from_int(0)),
+        )
+    }
+
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <LpNorm as ReduceInstruction<P>>::identity(this, line_size)
+    }
+
+    fn assign_accumulator(
+        _this: &Self,
+        destination: &mut Self::AccumulatorItem,
+        source: &Self::AccumulatorItem,
+    ) {
+        destination.0 = source.0;
+        destination.1 = source.1;
+    }
+
+    fn reduce(
+        this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        item: Line<P::EI>,
+        _coordinate: ReduceCoordinate,
+        #[comptime] use_planes: bool,
+    ) -> Self::AccumulatorItem {
+        let p = comptime![this.config.p()];
+        let item = Line::<f32>::cast_from(Line::abs(item));
+
+        if comptime![p == 1.0] {
+            let item = if use_planes { plane_sum(item) } else { item };
+            (accumulator.0 + item, accumulator.1)
+        } else if comptime![p == f32::INFINITY] {
+            let item = if use_planes { plane_max(item) } else { item };
+            (
+                select_many(accumulator.0.greater_than(item), accumulator.0, item),
+                accumulator.1,
+            )
+        } else if use_planes {
+            let scale = plane_max(item);
+            let sum = plane_sum(Self::ratio_pow(this, item, scale));
+            Self::fuse_scaled(this, (accumulator.0, accumulator.1), (scale, sum))
+        } else {
+            // A single power: the greater of the item and the scale becomes the new scale.
+            let greater = item.greater_than(accumulator.0);
+            let scale = select_many(greater, item, accumulator.0);
+            let smaller = select_many(greater, accumulator.0, item);
+            let ratio = Self::ratio_pow(this, smaller, scale);
+            let one = Line::empty(item.size()).fill(f32::from_int(1));
+            let sum = select_many(greater, accumulator.1 * ratio + one, accumulator.1 + ratio);
+            (scale, sum)
+        }
+    }
+
+    fn fuse_accumulators(
+        this: &Self,
+        lhs: Self::AccumulatorItem,
+        rhs: Self::AccumulatorItem,
+    ) -> Self::AccumulatorItem {
+        let p = comptime![this.config.p()];
+        if comptime![p == 1.0] {
+            (lhs.0 + rhs.0, lhs.1)
+        } else if comptime![p == f32::INFINITY] {
+            (select_many(lhs.0.greater_than(rhs.0), lhs.0, rhs.0), lhs.1)
+        } else {
+            Self::fuse_scaled(this, lhs, rhs)
+        }
+    }
+
+    fn merge_line<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Out {
+        let mut scale = Line::new(accumulator.0[0]);
+        let mut sum = Line::new(accumulator.1[0]);
+        #[unroll]
+        for k in 1..accumulator.0.size() {
+            let fused = <LpNorm as ReduceInstruction<P>>::fuse_accumulators(
+                this,
+                (scale, sum),
+                (Line::new(accumulator.0[k]), Line::new(accumulator.1[k])),
+            );
+            scale = fused.0;
+            sum = fused.1;
+        }
+        Out::cast_from(Self::norm(this, (scale, sum))[0])
+    }
+
+    fn to_output_perpendicular<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Line<Out> {
+        Line::cast_from(Self::norm(this, accumulator))
+    }
+
+    fn combine_outputs<Out: Numeric>(
+        this: &Self,
+        lhs: Line<Out>,
+        _lhs_count: u32,
+        rhs: Line<Out>,
+        _rhs_count: u32,
+    ) -> Line<Out> {
+        let p = comptime![this.config.p()];
+        if comptime![p == 1.0] {
+            lhs + rhs
+        } else if comptime![p == f32::INFINITY] {
+            select_many(lhs.greater_than(rhs), lhs, rhs)
+        } else {
+            // Never called, `supports_combine` is false.
+            lhs
+        }
+    }
+}
+
+/// A pair of shared memory used for [`LpNorm`], holding the scales and the sums of powers.
+#[derive(CubeType)]
+pub struct LpNormAccumulator {
+    pub scales: SharedMemory<Line<f32>>,
+    pub sums: SharedMemory<Line<f32>>,
+}
+
+#[cube]
+impl SharedAccumulator for LpNormAccumulator {
+    type Item = (Line<f32>, Line<f32>);
+
+    fn allocate(
+        #[comptime] length: u32,
+        #[comptime] line_size: u32,
+        #[comptime] _coordinate: bool,
+    ) -> Self {
+        LpNormAccumulator {
+            scales: SharedMemory::new_lined(length, line_size),
+            sums: SharedMemory::new_lined(length, line_size),
+        }
+    }
+
+    fn read(accumulator: &Self, index: u32) -> Self::Item {
+        (accumulator.scales[index], accumulator.sums[index])
+    }
+
+    fn write(accumulator: &mut Self, index: u32, item: Self::Item) {
+        accumulator.scales[index] = item.0;
+        accumulator.sums[index] = item.1;
+    }
+}
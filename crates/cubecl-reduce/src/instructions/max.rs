@@ -1,18 +1,26 @@
 use cubecl_core as cubecl;
 use cubecl_core::prelude::*;
+use num_traits::NumCast;
 
 use crate::{instructions::ReduceRequirements, precision::ReducePrecision};
 
-use super::{ReduceCoordinate, ReduceFamily, ReduceInstruction};
+use super::{ReduceCoordinate, ReduceFamily, ReduceInstruction, keeps_extremum, lowest_value};
 
-// TODO Add to test framework.
 /// Return the item with the maximum value.
+///
+/// The identity, which is also the padding of the input, is `-inf` for floats and the lowest
+/// value for integers, so infinities are reduced like any other value. A NaN in the slice gives
+/// NaN, except through plane instructions where it depends on the backend.
 #[derive(Debug, CubeType, Clone)]
 pub struct Max;
 
 impl ReduceFamily for Max {
     type Instruction<P: ReducePrecision> = Self;
     type Config = ();
+
+    fn identity<Out: Numeric>(_config: Self::Config) -> Option<Out> {
+        Some(<Out as NumCast>::from(f32::NEG_INFINITY).unwrap_or_else(Out::min_value))
+    }
 }
 
 #[cube]
@@ -30,11 +38,15 @@ impl<P: ReducePrecision> ReduceInstruction<P> for Max {
     }
 
     fn null_input(_this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
-        Line::empty(line_size).fill(P::EI::min_value())
+        Line::empty(line_size).fill(lowest_value::<P::EI>())
     }
 
-    fn null_accumulator(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
-        Line::empty(line_size).fill(P::EA::min_value())
+    fn identity(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        Line::empty(line_size).fill(lowest_value::<P::EA>())
+    }
+
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <Max as ReduceInstruction<P>>::identity(this, line_size)
     }
 
     fn assign_accumulator(
@@ -52,17 +64,16 @@ impl<P: ReducePrecision> ReduceInstruction<P> for Max {
         _coordinate: ReduceCoordinate,
         #[comptime] use_planes: bool,
     ) -> Self::AccumulatorItem {
-        if use_planes {
-            let candidate_item = Line::cast_from(plane_max(item));
-            select_many(
-                accumulator.greater_than(candidate_item),
-                *accumulator,
-                candidate_item,
-            )
+        let item = if use_planes {
+            Line::cast_from(plane_max(item))
         } else {
-            let item = Line::cast_from(item);
-            select_many(accumulator.greater_than(item), *accumulator, item)
-        }
+            Line::cast_from(item)
+        };
+        select_many(
+            keeps_extremum(*accumulator, accumulator.greater_than(item)),
+            *accumulator,
+            item,
+        )
     }
 
     fn fuse_accumulators(
@@ -70,7 +81,7 @@ impl<P: ReducePrecision> ReduceInstruction<P> for Max {
         lhs: Self::AccumulatorItem,
         rhs: Self::AccumulatorItem,
     ) -> Self::AccumulatorItem {
-        select_many(lhs.greater_than(rhs), lhs, rhs)
+        select_many(keeps_extremum(lhs, lhs.greater_than(rhs)), lhs, rhs)
     }
 
     fn merge_line<Out: Numeric>(
@@ -78,11 +89,12 @@ impl<P: ReducePrecision> ReduceInstruction<P> for Max {
         accumulator: Self::AccumulatorItem,
         _shape_axis_reduce: u32,
     ) -> Out {
-        let mut max = P::EA::min_value();
+        let mut max = lowest_value::<P::EA>();
         #[unroll]
         for k in 0..accumulator.size() {
             let candidate = accumulator[k];
-            max = select(candidate > max, candidate, max);
+            let keep = max > candidate || max != max;
+            max = select(keep, max, candidate);
         }
         Out::cast_from(max)
     }
@@ -94,4 +106,14 @@ impl<P: ReducePrecision> ReduceInstruction<P> for Max {
     ) -> Line<Out> {
         Line::cast_from(accumulator)
     }
+
+    fn combine_outputs<Out: Numeric>(
+        _this: &Self,
+        lhs: Line<Out>,
+        _lhs_count: u32,
+        rhs: Line<Out>,
+        _rhs_count: u32,
+    ) -> Line<Out> {
+        select_many(keeps_extremum(lhs, lhs.greater_than(rhs)), lhs, rhs)
+    }
 }
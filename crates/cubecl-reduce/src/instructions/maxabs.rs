@@ -13,6 +13,10 @@ pub struct MaxAbs;
 impl ReduceFamily for MaxAbs {
     type Instruction<P: ReducePrecision> = Self;
     type Config = ();
+
+    fn identity<Out: Numeric>(_config: Self::Config) -> Option<Out> {
+        Some(Out::from_int(0))
+    }
 }
 
 #[cube]
@@ -33,10 +37,14 @@ impl<P: ReducePrecision> ReduceInstruction<P> for MaxAbs {
         Line::empty(line_size).fill(P::EI::from_int(0))
     }
 
-    fn null_accumulator(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+    fn identity(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
         Line::empty(line_size).fill(P::EA::from_int(0))
     }
 
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <MaxAbs as ReduceInstruction<P>>::identity(this, line_size)
+    }
+
     fn assign_accumulator(
         _this: &Self,
         destination: &mut Self::AccumulatorItem,
@@ -94,4 +102,14 @@ impl<P: ReducePrecision> ReduceInstruction<P> for MaxAbs {
     ) -> Line<Out> {
         Line::cast_from(accumulator)
     }
+
+    fn combine_outputs<Out: Numeric>(
+        _this: &Self,
+        lhs: Line<Out>,
+        _lhs_count: u32,
+        rhs: Line<Out>,
+        _rhs_count: u32,
+    ) -> Line<Out> {
+        select_many(lhs.greater_than(rhs), lhs, rhs)
+    }
 }
@@ -3,7 +3,10 @@ use cubecl_core::prelude::*;
 
 use crate::precision::ReducePrecision;
 
-use super::{ReduceCoordinate, ReduceFamily, ReduceInstruction, ReduceRequirements, Sum};
+use super::{
+    LaneOrder, ReduceCoordinate, ReduceFamily, ReduceInstruction, ReduceRequirements, Sum,
+    SumAccumulator,
+};
 
 #[derive(Debug, CubeType, Clone)]
 pub struct Mean {
@@ -25,23 +28,32 @@ fn null_input<P: ReducePrecision, SI: ReduceInstruction<P>>(
 
 #[cube]
 impl<P: ReducePrecision> ReduceInstruction<P> for Mean {
-    type AccumulatorItem = Line<P::EA>;
-    type SharedAccumulator = SharedMemory<Line<P::EA>>;
+    type AccumulatorItem = (Line<P::EA>, Line<P::EA>);
+    type SharedAccumulator = SumAccumulator<P::EA>;
     type Config = ();
 
     fn requirements(_this: &Self) -> ReduceRequirements {
         ReduceRequirements { coordinates: false }
     }
     fn from_config(_config: Self::Config) -> Self {
-        Mean { sum: Sum {} }
+        Mean {
+            sum: Sum {
+                compensated: false,
+                lane_order: LaneOrder::Serial,
+            },
+        }
     }
 
     fn null_input(this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
         <Sum as ReduceInstruction<P>>::null_input(&this.sum, line_size)
     }
 
+    fn identity(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <Sum as ReduceInstruction<P>>::identity(&this.sum, line_size)
+    }
+
     fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
-        <Sum as ReduceInstruction<P>>::null_accumulator(&this.sum, line_size)
+        <Mean as ReduceInstruction<P>>::identity(this, line_size)
     }
 
     fn assign_accumulator(
@@ -91,6 +103,22 @@ impl<P: ReducePrecision> ReduceInstruction<P> for Mean {
             accumulator,
             shape_axis_reduce,
         );
-        sum / Line::empty(accumulator.size()).fill(Out::cast_from(shape_axis_reduce))
+        sum / Line::empty(accumulator.0.size()).fill(Out::cast_from(shape_axis_reduce))
+    }
+
+    fn combine_outputs<Out: Numeric>(
+        _this: &Self,
+        lhs: Line<Out>,
+        lhs_count: u32,
+        rhs: Line<Out>,
+        rhs_count: u32,
+    ) -> Line<Out> {
+        // Weighted average of both means, computed with the accumulation precision.
+        let lhs: Line<P::EA> = Line::cast_from(lhs);
+        let rhs: Line<P::EA> = Line::cast_from(rhs);
+        let lhs_weight = Line::empty(lhs.size()).fill(P::EA::cast_from(lhs_count));
+        let rhs_weight = Line::empty(rhs.size()).fill(P::EA::cast_from(rhs_count));
+
+        Line::cast_from((lhs * lhs_weight + rhs * rhs_weight) / (lhs_weight + rhs_weight))
     }
 }
@@ -1,18 +1,26 @@
 use cubecl_core as cubecl;
 use cubecl_core::prelude::*;
+use num_traits::NumCast;
 
 use crate::{instructions::ReduceRequirements, precision::ReducePrecision};
 
-use super::{ReduceCoordinate, ReduceFamily, ReduceInstruction};
+use super::{ReduceCoordinate, ReduceFamily, ReduceInstruction, highest_value, keeps_extremum};
 
-// TODO Add to test framework.
-/// Return the item with the maximum absolute value.
+/// Return the item with the minimum value.
+///
+/// The identity, which is also the padding of the input, is `+inf` for floats and the highest
+/// value for integers, so infinities are reduced like any other value. A NaN in the slice gives
+/// NaN, except through plane instructions where it depends on the backend.
 #[derive(Debug, CubeType, Clone)]
 pub struct Min;
 
 impl ReduceFamily for Min {
     type Instruction<P: ReducePrecision> = Self;
     type Config = ();
+
+    fn identity<Out: Numeric>(_config: Self::Config) -> Option<Out> {
+        Some(<Out as NumCast>::from(f32::INFINITY).unwrap_or_else(Out::max_value))
+    }
 }
 
 #[cube]
@@ -29,11 +37,15 @@ impl<P: ReducePrecision> ReduceInstruction<P> for Min {
         Min {}
     }
     fn null_input(_this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
-        Line::empty(line_size).fill(P::EI::max_value())
+        Line::empty(line_size).fill(highest_value::<P::EI>())
     }
 
-    fn null_accumulator(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
-        Line::empty(line_size).fill(P::EA::max_value())
+    fn identity(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        Line::empty(line_size).fill(highest_value::<P::EA>())
+    }
+
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <Min as ReduceInstruction<P>>::identity(this, line_size)
     }
 
     fn assign_accumulator(
@@ -51,17 +63,16 @@ impl<P: ReducePrecision> ReduceInstruction<P> for Min {
         _coordinate: ReduceCoordinate,
         #[comptime] use_planes: bool,
     ) -> Self::AccumulatorItem {
-        if use_planes {
-            let candidate_item = Line::cast_from(plane_min(item));
-            select_many(
-                accumulator.less_than(candidate_item),
-                *accumulator,
-                candidate_item,
-            )
+        let item = if use_planes {
+            Line::cast_from(plane_min(item))
         } else {
-            let item = Line::cast_from(item);
-            select_many(accumulator.less_than(item), *accumulator, item)
-        }
+            Line::cast_from(item)
+        };
+        select_many(
+            keeps_extremum(*accumulator, accumulator.less_than(item)),
+            *accumulator,
+            item,
+        )
     }
 
     fn fuse_accumulators(
@@ -69,7 +80,7 @@ impl<P: ReducePrecision> ReduceInstruction<P> for Min {
         lhs: Self::AccumulatorItem,
         rhs: Self::AccumulatorItem,
     ) -> Self::AccumulatorItem {
-        select_many(lhs.less_than(rhs), lhs, rhs)
+        select_many(keeps_extremum(lhs, lhs.less_than(rhs)), lhs, rhs)
     }
 
     fn merge_line<Out: Numeric>(
@@ -77,11 +88,12 @@ impl<P: ReducePrecision> ReduceInstruction<P> for Min {
         accumulator: Self::AccumulatorItem,
         _shape_axis_reduce: u32,
     ) -> Out {
-        let mut min = P::EA::max_value();
+        let mut min = highest_value::<P::EA>();
         #[unroll]
         for k in 0..accumulator.size() {
             let candidate = accumulator[k];
-            min = select(candidate < min, candidate, min);
+            let keep = min < candidate || min != min;
+            min = select(keep, min, candidate);
         }
         Out::cast_from(min)
     }
@@ -93,4 +105,14 @@ impl<P: ReducePrecision> ReduceInstruction<P> for Min {
     ) -> Line<Out> {
         Line::cast_from(accumulator)
     }
+
+    fn combine_outputs<Out: Numeric>(
+        _this: &Self,
+        lhs: Line<Out>,
+        _lhs_count: u32,
+        rhs: Line<Out>,
+        _rhs_count: u32,
+    ) -> Line<Out> {
+        select_many(keeps_extremum(lhs, lhs.less_than(rhs)), lhs, rhs)
+    }
 }
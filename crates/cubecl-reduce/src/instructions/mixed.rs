@@ -5,8 +5,9 @@ use cubecl_std::{CubeOption, CubeOptionExpand};
 use crate::precision::ReducePrecision;
 
 use super::{
-    ArgMax, ArgMin, Max, MaxAbs, Mean, Min, Prod, ReduceCoordinate, ReduceFamily,
-    ReduceInstruction, ReduceRequirements, SharedAccumulator, Sum,
+    ArgMax, ArgMin, LaneOrder, Max, MaxAbs, Mean, Min, Prod, ProdConfig, ReduceCoordinate,
+    ReduceFamily, ReduceInstruction, ReduceRequirements, SharedAccumulator, Sum, SumConfig,
+    TieBreak,
 };
 
 #[derive(Debug, CubeType, Clone)]
@@ -36,6 +37,23 @@ pub enum ReduceFnConfig {
 impl ReduceFamily for ReduceFn {
     type Instruction<P: ReducePrecision> = Self;
     type Config = ReduceFnConfig;
+
+    fn identity<Out: Numeric>(config: Self::Config) -> Option<Out> {
+        match config {
+            ReduceFnConfig::Sum => <Sum as ReduceFamily>::identity(SumConfig::default()),
+            ReduceFnConfig::Prod => <Prod as ReduceFamily>::identity(ProdConfig::default()),
+            ReduceFnConfig::Mean => <Mean as ReduceFamily>::identity(()),
+            ReduceFnConfig::MaxAbs => <MaxAbs as ReduceFamily>::identity(()),
+            ReduceFnConfig::ArgMax => <ArgMax as ReduceFamily>::identity(TieBreak::default()),
+            ReduceFnConfig::ArgMin => <ArgMin as ReduceFamily>::identity(TieBreak::default()),
+            ReduceFnConfig::Max => <Max as ReduceFamily>::identity(()),
+            ReduceFnConfig::Min => <Min as ReduceFamily>::identity(()),
+        }
+    }
+
+    fn supports_combine(config: Self::Config) -> bool {
+        !matches!(config, ReduceFnConfig::ArgMax | ReduceFnConfig::ArgMin)
+    }
 }
 
 #[derive(CubeType)]
@@ -117,12 +135,26 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceFn {
 
     fn from_config(#[comptime] config: Self::Config) -> Self {
         match config {
-            ReduceFnConfig::Sum => ReduceFn::new_Sum(Sum {}),
-            ReduceFnConfig::Prod => ReduceFn::new_Prod(Prod {}),
-            ReduceFnConfig::Mean => ReduceFn::new_Mean(Mean { sum: Sum {} }),
+            ReduceFnConfig::Sum => ReduceFn::new_Sum(Sum {
+                compensated: false,
+                lane_order: LaneOrder::Serial,
+            }),
+            ReduceFnConfig::Prod => ReduceFn::new_Prod(Prod {
+                lane_order: LaneOrder::Serial,
+            }),
+            ReduceFnConfig::Mean => ReduceFn::new_Mean(Mean {
+                sum: Sum {
+                    compensated: false,
+                    lane_order: LaneOrder::Serial,
+                },
+            }),
             ReduceFnConfig::MaxAbs => ReduceFn::new_MaxAbs(MaxAbs {}),
-            ReduceFnConfig::ArgMax => ReduceFn::new_ArgMax(ArgMax {}),
-            ReduceFnConfig::ArgMin => ReduceFn::new_ArgMin(ArgMin {}),
+            ReduceFnConfig::ArgMax => ReduceFn::new_ArgMax(ArgMax {
+                tie_break: TieBreak::LowestIndex,
+            }),
+            ReduceFnConfig::ArgMin => ReduceFn::new_ArgMin(ArgMin {
+                tie_break: TieBreak::LowestIndex,
+            }),
             ReduceFnConfig::Max => ReduceFn::new_Max(Max {}),
             ReduceFnConfig::Min => ReduceFn::new_Min(Min {}),
         }
@@ -147,10 +179,10 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceFn {
         }
     }
 
-    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+    fn identity(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
         match this {
             ReduceFn::Sum(sum) => {
-                let elements = <Sum as ReduceInstruction<P>>::null_accumulator(sum, line_size);
+                let (elements, _) = <Sum as ReduceInstruction<P>>::identity(sum, line_size);
 
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
@@ -158,7 +190,7 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceFn {
                 }
             }
             ReduceFn::Mean(sum) => {
-                let elements = <Mean as ReduceInstruction<P>>::null_accumulator(sum, line_size);
+                let (elements, _) = <Mean as ReduceInstruction<P>>::identity(sum, line_size);
 
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
@@ -166,7 +198,7 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceFn {
                 }
             }
             ReduceFn::Prod(sum) => {
-                let elements = <Prod as ReduceInstruction<P>>::null_accumulator(sum, line_size);
+                let elements = <Prod as ReduceInstruction<P>>::identity(sum, line_size);
 
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
@@ -174,8 +206,7 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceFn {
                 }
             }
             ReduceFn::MaxAbs(maxabs) => {
-                let elements =
-                    <MaxAbs as ReduceInstruction<P>>::null_accumulator(maxabs, line_size);
+                let elements = <MaxAbs as ReduceInstruction<P>>::identity(maxabs, line_size);
 
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
@@ -184,7 +215,7 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceFn {
             }
             ReduceFn::ArgMax(argmax) => {
                 let (elements, args) =
-                    <ArgMax as ReduceInstruction<P>>::null_accumulator(argmax, line_size);
+                    <ArgMax as ReduceInstruction<P>>::identity(argmax, line_size);
 
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
@@ -193,7 +224,7 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceFn {
             }
             ReduceFn::ArgMin(argmin) => {
                 let (elements, args) =
-                    <ArgMin as ReduceInstruction<P>>::null_accumulator(argmin, line_size);
+                    <ArgMin as ReduceInstruction<P>>::identity(argmin, line_size);
 
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
@@ -201,7 +232,7 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceFn {
                 }
             }
             ReduceFn::Max(max) => {
-                let elements = <Max as ReduceInstruction<P>>::null_accumulator(max, line_size);
+                let elements = <Max as ReduceInstruction<P>>::identity(max, line_size);
 
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
@@ -209,7 +240,7 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceFn {
                 }
             }
             ReduceFn::Min(min) => {
-                let elements = <Min as ReduceInstruction<P>>::null_accumulator(min, line_size);
+                let elements = <Min as ReduceInstruction<P>>::identity(min, line_size);
 
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
@@ -219,6 +250,10 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceFn {
         }
     }
 
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <ReduceFn as ReduceInstruction<P>>::identity(this, line_size)
+    }
+
     fn assign_accumulator(
         _this: &Self,
         destination: &mut Self::AccumulatorItem,
@@ -241,9 +276,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceFn {
     ) -> Self::AccumulatorItem {
         match this {
             ReduceFn::Sum(sum) => {
-                let elements = <Sum as ReduceInstruction<P>>::reduce(
+                let (elements, _) = <Sum as ReduceInstruction<P>>::reduce(
                     sum,
-                    &accumulator.elements,
+                    &Sum::without_compensation::<P::EA>(accumulator.elements),
                     item,
                     coordinate,
                     use_planes,
@@ -267,9 +302,9 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceFn {
                 }
             }
             ReduceFn::Mean(sum) => {
-                let elements = <Mean as ReduceInstruction<P>>::reduce(
+                let (elements, _) = <Mean as ReduceInstruction<P>>::reduce(
                     sum,
-                    &accumulator.elements,
+                    &Sum::without_compensation::<P::EA>(accumulator.elements),
                     item,
                     coordinate,
                     use_planes,
@@ -356,10 +391,10 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceFn {
     ) -> Self::AccumulatorItem {
         match this {
             ReduceFn::Sum(sum) => {
-                let elements = <Sum as ReduceInstruction<P>>::fuse_accumulators(
+                let (elements, _) = <Sum as ReduceInstruction<P>>::fuse_accumulators(
                     sum,
-                    lhs.elements,
-                    rhs.elements,
+                    Sum::without_compensation::<P::EA>(lhs.elements),
+                    Sum::without_compensation::<P::EA>(rhs.elements),
                 );
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
@@ -378,10 +413,10 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceFn {
                 }
             }
             ReduceFn::Mean(mean) => {
-                let elements = <Mean as ReduceInstruction<P>>::fuse_accumulators(
+                let (elements, _) = <Mean as ReduceInstruction<P>>::fuse_accumulators(
                     mean,
-                    lhs.elements,
-                    rhs.elements,
+                    Sum::without_compensation::<P::EA>(lhs.elements),
+                    Sum::without_compensation::<P::EA>(rhs.elements),
                 );
                 DynamicAccumulatorItem::<P::EA> {
                     elements,
@@ -456,7 +491,7 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceFn {
         match this {
             ReduceFn::Sum(sum) => <Sum as ReduceInstruction<P>>::merge_line::<Out>(
                 sum,
-                accumulator.elements,
+                Sum::without_compensation::<P::EA>(accumulator.elements),
                 shape_axis_reduce,
             ),
             ReduceFn::Prod(prod) => <Prod as ReduceInstruction<P>>::merge_line::<Out>(
@@ -466,7 +501,7 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceFn {
             ),
             ReduceFn::Mean(mean) => <Mean as ReduceInstruction<P>>::merge_line::<Out>(
                 mean,
-                accumulator.elements,
+                Sum::without_compensation::<P::EA>(accumulator.elements),
                 shape_axis_reduce,
             ),
             ReduceFn::MaxAbs(maxabs) => <MaxAbs as ReduceInstruction<P>>::merge_line::<Out>(
@@ -505,7 +540,7 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceFn {
         match this {
             ReduceFn::Sum(sum) => <Sum as ReduceInstruction<P>>::to_output_perpendicular::<Out>(
                 sum,
-                accumulator.elements,
+                Sum::without_compensation::<P::EA>(accumulator.elements),
                 shape_axis_reduce,
             ),
             ReduceFn::Prod(prod) => <Prod as ReduceInstruction<P>>::to_output_perpendicular::<Out>(
@@ -515,7 +550,7 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceFn {
             ),
             ReduceFn::Mean(mean) => <Mean as ReduceInstruction<P>>::to_output_perpendicular::<Out>(
                 mean,
-                accumulator.elements,
+                Sum::without_compensation::<P::EA>(accumulator.elements),
                 shape_axis_reduce,
             ),
             ReduceFn::MaxAbs(maxabs) => {
@@ -551,4 +586,39 @@ impl<P: ReducePrecision> ReduceInstruction<P> for ReduceFn {
             ),
         }
     }
+
+    fn combine_outputs<Out: Numeric>(
+        this: &Self,
+        lhs: Line<Out>,
+        lhs_count: u32,
+        rhs: Line<Out>,
+        rhs_count: u32,
+    ) -> Line<Out> {
+        match this {
+            ReduceFn::Sum(sum) => <Sum as ReduceInstruction<P>>::combine_outputs::<Out>(
+                sum, lhs, lhs_count, rhs, rhs_count,
+            ),
+            ReduceFn::Prod(prod) => <Prod as ReduceInstruction<P>>::combine_outputs::<Out>(
+                prod, lhs, lhs_count, rhs, rhs_count,
+            ),
+            ReduceFn::Mean(mean) => <Mean as ReduceInstruction<P>>::combine_outputs::<Out>(
+                mean, lhs, lhs_count, rhs, rhs_count,
+            ),
+            ReduceFn::MaxAbs(maxabs) => <MaxAbs as ReduceInstruction<P>>::combine_outputs::<Out>(
+                maxabs, lhs, lhs_count, rhs, rhs_count,
+            ),
+            ReduceFn::ArgMax(argmax) => <ArgMax as ReduceInstruction<P>>::combine_outputs::<Out>(
+                argmax, lhs, lhs_count, rhs, rhs_count,
+            ),
+            ReduceFn::ArgMin(argmin) => <ArgMin as ReduceInstruction<P>>::combine_outputs::<Out>(
+                argmin, lhs, lhs_count, rhs, rhs_count,
+            ),
+            ReduceFn::Max(max) => <Max as ReduceInstruction<P>>::combine_outputs::<Out>(
+                max, lhs, lhs_count, rhs, rhs_count,
+            ),
+            ReduceFn::Min(min) => <Min as ReduceInstruction<P>>::combine_outputs::<Out>(
+                min, lhs, lhs_count, rhs, rhs_count,
+            ),
+        }
+    }
 }
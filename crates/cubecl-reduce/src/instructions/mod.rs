@@ -1,23 +1,55 @@
+mod all_equal;
 mod argmax;
 mod argmin;
 mod base;
+mod centroid;
+mod count_equal;
+mod count_nonzero;
+mod decayed;
+mod entropy;
+mod integer_sum;
+mod kth_smallest;
+mod lane_order;
+mod lp_norm;
 mod max;
 mod maxabs;
 mod mean;
 mod min;
 mod mixed;
+mod moments;
+mod popcount;
 mod prod;
+mod range;
+mod stable_prod;
 mod sum;
+mod tie_break;
+mod transformed;
 mod utils;
 
+pub use all_equal::*;
 pub use argmax::*;
 pub use argmin::*;
 pub use base::*;
+pub use centroid::*;
+pub use count_equal::*;
+pub use count_nonzero::*;
+pub use decayed::*;
+pub use entropy::*;
+pub use integer_sum::*;
+pub use kth_smallest::*;
+pub use lane_order::*;
+pub use lp_norm::*;
 pub use max::*;
 pub use maxabs::*;
 pub use mean::*;
 pub use min::*;
 pub use mixed::*;
+pub use moments::*;
+pub use popcount::*;
 pub use prod::*;
+pub use range::*;
+pub use stable_prod::*;
 pub use sum::*;
+pub use tie_break::*;
+pub use transformed::*;
 pub(crate) use utils::*;
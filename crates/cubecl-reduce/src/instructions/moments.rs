@@ -0,0 +1,183 @@
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+
+use crate::precision::ReducePrecision;
+
+use super::{
+    ReduceCoordinate, ReduceFamily, ReduceInstruction, ReduceRequirements, SharedAccumulator,
+};
+
+/// Which moment [`Moments2`] outputs.
+#[derive_cube_comptime]
+pub enum Moments2Output {
+    /// The sum of the items, `sum(x)`.
+    Sum,
+    /// The sum of the squares of the items, `sum(x^2)`.
+    SumOfSquares,
+}
+
+#[derive_cube_comptime]
+pub struct Moments2Config {
+    pub output: Moments2Output,
+}
+
+/// Compute the first two raw moments `sum(x)` and `sum(x^2)`, from which the mean and variance
+/// can be derived as `sum(x) / n` and `sum(x^2) / n - (sum(x) / n)^2`.
+///
+/// This is cheaper than a Welford update, at the cost of precision when the variance is small
+/// compared to the squared mean. Both sums are accumulated together, and the config selects the
+/// one that is written, see [`reduce_moments2`](crate::reduce_moments2) to write both from a
+/// single launch.
+#[derive(Debug, CubeType, Clone)]
+pub struct Moments2 {
+    #[cube(comptime)]
+    pub output: Moments2Output,
+}
+
+impl ReduceFamily for Moments2 {
+    type Instruction<P: ReducePrecision> = Self;
+    type Config = Moments2Config;
+
+    fn identity<Out: Numeric>(_config: Self::Config) -> Option<Out> {
+        Some(Out::from_int(0))
+    }
+}
+
+#[cube]
+impl<P: ReducePrecision> ReduceInstruction<P> for Moments2 {
+    type AccumulatorItem = (Line<P::EA>, Line<P::EA>);
+    type SharedAccumulator = Moments2Accumulator<P::EA>;
+    type Config = Moments2Config;
+
+    fn requirements(_this: &Self) -> ReduceRequirements {
+        ReduceRequirements { coordinates: false }
+    }
+
+    fn from_config(#[comptime] config: Self::Config) -> Self {
+        Moments2 {
+            output: config.output,
+        }
+    }
+
+    fn null_input(_this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
+        Line::empty(line_size).fill(P::EI::from_int(0))
+    }
+
+    fn identity(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        (
+            Line::empty(line_size).fill(P::EA::from_int(0)),
+            Line::empty(line_size).fill(P::EA::from_int(0)),
+        )
+    }
+
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <Moments2 as ReduceInstruction<P>>::identity(this, line_size)
+    }
+
+    fn assign_accumulator(
+        _this: &Self,
+        destination: &mut Self::AccumulatorItem,
+        source: &Self::AccumulatorItem,
+    ) {
+        destination.0 = source.0;
+        destination.1 = source.1;
+    }
+
+    fn reduce(
+        _this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        item: Line<P::EI>,
+        _coordinate: ReduceCoordinate,
+        #[comptime] use_planes: bool,
+    ) -> Self::AccumulatorItem {
+        let item = Line::<P::EA>::cast_from(item);
+        let square = item * item;
+        if use_planes {
+            (
+                accumulator.0 + plane_sum(item),
+                accumulator.1 + plane_sum(square),
+            )
+        } else {
+            (accumulator.0 + item, accumulator.1 + square)
+        }
+    }
+
+    fn fuse_accumulators(
+        _this: &Self,
+        lhs: Self::AccumulatorItem,
+        rhs: Self::AccumulatorItem,
+    ) -> Self::AccumulatorItem {
+        (lhs.0 + rhs.0, lhs.1 + rhs.1)
+    }
+
+    fn merge_line<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Out {
+        let mut sum = P::EA::from_int(0);
+        let mut squares = P::EA::from_int(0);
+        #[unroll]
+        for k in 0..accumulator.0.size() {
+            sum += accumulator.0[k];
+            squares += accumulator.1[k];
+        }
+        match comptime!(this.output) {
+            Moments2Output::Sum => Out::cast_from(sum),
+            Moments2Output::SumOfSquares => Out::cast_from(squares),
+        }
+    }
+
+    fn to_output_perpendicular<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Line<Out> {
+        match comptime!(this.output) {
+            Moments2Output::Sum => Line::cast_from(accumulator.0),
+            Moments2Output::SumOfSquares => Line::cast_from(accumulator.1),
+        }
+    }
+
+    fn combine_outputs<Out: Numeric>(
+        _this: &Self,
+        lhs: Line<Out>,
+        _lhs_count: u32,
+        rhs: Line<Out>,
+        _rhs_count: u32,
+    ) -> Line<Out> {
+        lhs + rhs
+    }
+}
+
+/// A pair of shared memory used for [`Moments2`], holding the sums and the sums of squares.
+#[derive(CubeType)]
+pub struct Moments2Accumulator<N: Numeric> {
+    pub sums: SharedMemory<Line<N>>,
+    pub squares: SharedMemory<Line<N>>,
+}
+
+#[cube]
+impl<N: Numeric> SharedAccumulator for Moments2Accumulator<N> {
+    type Item = (Line<N>, Line<N>);
+
+    fn allocate(
+        #[comptime] length: u32,
+        #[comptime] line_size: u32,
+        #[comptime] _coordinate: bool,
+    ) -> Self {
+        Moments2Accumulator::<N> {
+            sums: SharedMemory::new_lined(length, line_size),
+            squares: SharedMemory::new_lined(length, line_size),
+        }
+    }
+
+    fn read(accumulator: &Self, index: u32) -> Self::Item {
+        (accumulator.sums[index], accumulator.squares[index])
+    }
+
+    fn write(accumulator: &mut Self, index: u32, item: Self::Item) {
+        accumulator.sums[index] = item.0;
+        accumulator.squares[index] = item.1;
+    }
+}
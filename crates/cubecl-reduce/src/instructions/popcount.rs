@@ -0,0 +1,166 @@
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+
+use crate::precision::ReducePrecision;
+
+use super::{
+    ReduceCoordinate, ReduceCoordinateExpand, ReduceFamily, ReduceInstruction, ReduceRequirements,
+};
+
+#[derive_cube_comptime]
+pub struct PopcountConfig {
+    /// The number of valid bits along the reduced axis, starting from the lowest bit of the
+    /// first word. The bits of the final partial word past it are masked out.
+    ///
+    /// `None` when every bit of every word is valid.
+    pub valid_bits: Option<u32>,
+}
+
+/// Count the set bits of masks packed in `u32` words.
+///
+/// Each word is counted with the `count_ones` intrinsic of the backend, so the input items
+/// must be `u32`. The coordinates are only required to mask the final partial word.
+#[derive(Debug, CubeType, Clone)]
+pub struct Popcount {
+    #[cube(comptime)]
+    pub valid_bits: Option<u32>,
+}
+
+impl ReduceFamily for Popcount {
+    type Instruction<P: ReducePrecision> = Self;
+    type Config = PopcountConfig;
+
+    fn identity<Out: Numeric>(_config: Self::Config) -> Option<Out> {
+        Some(Out::from_int(0))
+    }
+}
+
+#[cube]
+impl Popcount {
+    /// The mask of the valid bits of the words at the given coordinates.
+    fn valid_mask(coordinate: Line<u32>, #[comptime] valid_bits: u32) -> Line<u32> {
+        let line_size = coordinate.size();
+        let word_bits = Line::empty(line_size).fill(32u32);
+        let valid_bits = Line::empty(line_size).fill(valid_bits);
+
+        let start = Min::min(coordinate * word_bits, valid_bits);
+        let remaining = valid_bits - start;
+        let is_full = remaining.greater_equal(word_bits);
+
+        // A shift by the number of bits of the word is undefined, so full words are selected
+        // after the shift.
+        let shift = select_many(is_full, Line::empty(line_size).fill(0u32), remaining);
+        let partial =
+            (Line::empty(line_size).fill(1u32) << shift) - Line::empty(line_size).fill(1u32);
+        select_many(is_full, Line::empty(line_size).fill(u32::MAX), partial)
+    }
+}
+
+#[cube]
+impl<P: ReducePrecision> ReduceInstruction<P> for Popcount {
+    type AccumulatorItem = Line<P::EA>;
+    type SharedAccumulator = SharedMemory<Line<P::EA>>;
+    type Config = PopcountConfig;
+
+    fn requirements(this: &Self) -> ReduceRequirements {
+        ReduceRequirements {
+            coordinates: comptime![this.valid_bits.is_some()],
+        }
+    }
+
+    fn from_config(#[comptime] config: Self::Config) -> Self {
+        Popcount {
+            valid_bits: config.valid_bits,
+        }
+    }
+
+    fn null_input(_this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
+        Line::empty(line_size).fill(P::EI::from_int(0))
+    }
+
+    fn identity(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        Line::empty(line_size).fill(P::EA::from_int(0))
+    }
+
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <Popcount as ReduceInstruction<P>>::identity(this, line_size)
+    }
+
+    fn assign_accumulator(
+        _this: &Self,
+        destination: &mut Self::AccumulatorItem,
+        source: &Self::AccumulatorItem,
+    ) {
+        *destination = *source;
+    }
+
+    fn reduce(
+        this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        item: Line<P::EI>,
+        coordinate: ReduceCoordinate,
+        #[comptime] use_planes: bool,
+    ) -> Self::AccumulatorItem {
+        let mut word = Line::<u32>::cast_from(item);
+        match comptime![this.valid_bits] {
+            Some(valid_bits) => {
+                let coordinate = match coordinate {
+                    ReduceCoordinate::Required(val) => val,
+                    ReduceCoordinate::NotRequired => {
+                        comptime! {panic!("Coordinates are required to mask a partial word")};
+                        #[allow(unreachable_code)]
+                        Line::new(0)
+                    }
+                };
+                word &= Self::valid_mask(coordinate, valid_bits);
+            }
+            None => {}
+        }
+
+        let count = Line::<P::EA>::cast_from(word.count_ones());
+        if use_planes {
+            *accumulator + plane_sum(count)
+        } else {
+            *accumulator + count
+        }
+    }
+
+    fn fuse_accumulators(
+        _this: &Self,
+        lhs: Self::AccumulatorItem,
+        rhs: Self::AccumulatorItem,
+    ) -> Self::AccumulatorItem {
+        lhs + rhs
+    }
+
+    fn merge_line<Out: Numeric>(
+        _this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Out {
+        let mut count = P::EA::from_int(0);
+        #[unroll]
+        for k in 0..accumulator.size() {
+            count += accumulator[k];
+        }
+        Out::cast_from(count)
+    }
+
+    fn to_output_perpendicular<Out: Numeric>(
+        _this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Line<Out> {
+        Line::cast_from(accumulator)
+    }
+
+    fn combine_outputs<Out: Numeric>(
+        _this: &Self,
+        lhs: Line<Out>,
+        _lhs_count: u32,
+        rhs: Line<Out>,
+        _rhs_count: u32,
+    ) -> Line<Out> {
+        lhs + rhs
+    }
+}
@@ -3,37 +3,58 @@ use cubecl_core::prelude::*;
 
 use crate::{instructions::ReduceRequirements, precision::ReducePrecision};
 
-use super::{ReduceCoordinate, ReduceFamily, ReduceInstruction};
+use super::{LaneOrder, ReduceCoordinate, ReduceFamily, ReduceInstruction, pairwise_prod};
 
+#[derive_cube_comptime]
+#[derive(Default)]
+pub struct ProdConfig {
+    /// The order of the multiplications of the lanes of a line.
+    pub lane_order: LaneOrder,
+}
+
+/// Compute the product of the items, multiplying the lanes of a line in the given [`LaneOrder`].
 #[derive(Debug, CubeType, Clone)]
-pub struct Prod {}
+pub struct Prod {
+    #[cube(comptime)]
+    pub lane_order: LaneOrder,
+}
 
 impl ReduceFamily for Prod {
     type Instruction<P: ReducePrecision> = Self;
-    type Config = ();
+    type Config = ProdConfig;
+
+    fn identity<Out: Numeric>(_config: Self::Config) -> Option<Out> {
+        Some(Out::from_int(1))
+    }
 }
 
 #[cube]
 impl<P: ReducePrecision> ReduceInstruction<P> for Prod {
     type AccumulatorItem = Line<P::EA>;
     type SharedAccumulator = SharedMemory<Line<P::EA>>;
-    type Config = ();
+    type Config = ProdConfig;
 
     fn requirements(_this: &Self) -> ReduceRequirements {
         ReduceRequirements { coordinates: false }
     }
 
-    fn from_config(_config: Self::Config) -> Self {
-        Prod {}
+    fn from_config(#[comptime] config: Self::Config) -> Self {
+        Prod {
+            lane_order: config.lane_order,
+        }
     }
     fn null_input(_this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
         Line::empty(line_size).fill(P::EI::from_int(1))
     }
 
-    fn null_accumulator(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+    fn identity(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
         Line::empty(line_size).fill(P::EA::from_int(1))
     }
 
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <Prod as ReduceInstruction<P>>::identity(this, line_size)
+    }
+
     fn assign_accumulator(
         _this: &Self,
         destination: &mut Self::AccumulatorItem,
@@ -66,16 +87,20 @@ impl<P: ReducePrecision> ReduceInstruction<P> for Prod {
     }
 
     fn merge_line<Out: Numeric>(
-        _this: &Self,
+        this: &Self,
         accumulator: Self::AccumulatorItem,
         _shape_axis_reduce: u32,
     ) -> Out {
-        let mut prod = P::EA::from_int(1);
-        #[unroll]
-        for k in 0..accumulator.size() {
-            prod *= accumulator[k];
+        if comptime![this.lane_order == LaneOrder::Pairwise] {
+            Out::cast_from(pairwise_prod::<P::EA>(accumulator))
+        } else {
+            let mut prod = P::EA::from_int(1);
+            #[unroll]
+            for k in 0..accumulator.size() {
+                prod *= accumulator[k];
+            }
+            Out::cast_from(prod)
         }
-        Out::cast_from(prod)
     }
 
     fn to_output_perpendicular<Out: Numeric>(
@@ -85,4 +110,14 @@ impl<P: ReducePrecision> ReduceInstruction<P> for Prod {
     ) -> Line<Out> {
         Line::cast_from(accumulator)
     }
+
+    fn combine_outputs<Out: Numeric>(
+        _this: &Self,
+        lhs: Line<Out>,
+        _lhs_count: u32,
+        rhs: Line<Out>,
+        _rhs_count: u32,
+    ) -> Line<Out> {
+        lhs * rhs
+    }
 }
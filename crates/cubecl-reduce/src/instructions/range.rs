@@ -0,0 +1,197 @@
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+
+use crate::precision::ReducePrecision;
+
+use super::{
+    Max, Min, ReduceCoordinate, ReduceCoordinateExpand, ReduceFamily, ReduceInstruction,
+    ReduceRequirements, SharedAccumulator,
+};
+
+/// Compute the range `max - min` of the items, tracking both extrema in a single pass.
+///
+/// The extrema are reduced as by [Max] and [Min], so infinities are handled like any other
+/// value and a NaN in the slice gives NaN, except through plane instructions where it depends
+/// on the backend. A constant slice has a range of `0`.
+///
+/// The padding of the input is `-inf` for floats, which is ignored by [Max], and is recognized by
+/// its `u32::MAX` coordinate to be replaced by the null input of [Min] before the minimum is
+/// reduced, so real items equal to `-inf` are still kept by the minimum.
+#[derive(Debug, CubeType, Clone)]
+pub struct Range {
+    pub(crate) max: Max,
+    pub(crate) min: Min,
+}
+
+impl ReduceFamily for Range {
+    type Instruction<P: ReducePrecision> = Self;
+    type Config = ();
+
+    fn supports_combine(_config: Self::Config) -> bool {
+        false
+    }
+}
+
+#[cube]
+impl<P: ReducePrecision> ReduceInstruction<P> for Range {
+    type AccumulatorItem = (Line<P::EA>, Line<P::EA>);
+    type SharedAccumulator = RangeAccumulator<P::EA>;
+    type Config = ();
+
+    fn requirements(_this: &Self) -> ReduceRequirements {
+        ReduceRequirements { coordinates: true }
+    }
+
+    fn from_config(_config: Self::Config) -> Self {
+        Range {
+            max: Max {},
+            min: Min {},
+        }
+    }
+
+    fn null_input(this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
+        <Max as ReduceInstruction<P>>::null_input(&this.max, line_size)
+    }
+
+    fn identity(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        (
+            <Max as ReduceInstruction<P>>::identity(&this.max, line_size),
+            <Min as ReduceInstruction<P>>::identity(&this.min, line_size),
+        )
+    }
+
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <Range as ReduceInstruction<P>>::identity(this, line_size)
+    }
+
+    fn assign_accumulator(
+        _this: &Self,
+        destination: &mut Self::AccumulatorItem,
+        source: &Self::AccumulatorItem,
+    ) {
+        destination.0 = source.0;
+        destination.1 = source.1;
+    }
+
+    fn reduce(
+        this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        item: Line<P::EI>,
+        coordinate: ReduceCoordinate,
+        #[comptime] use_planes: bool,
+    ) -> Self::AccumulatorItem {
+        let coordinate = match coordinate {
+            ReduceCoordinate::Required(val) => val,
+            ReduceCoordinate::NotRequired => {
+                comptime! {panic!("Coordinates are required for Range")};
+                #[allow(unreachable_code)]
+                Line::new(0)
+            }
+        };
+
+        let line_size = item.size();
+        let max = <Max as ReduceInstruction<P>>::reduce(
+            &this.max,
+            &accumulator.0,
+            item,
+            ReduceCoordinate::new_NotRequired(),
+            use_planes,
+        );
+
+        let is_padding = coordinate.equal(Line::empty(line_size).fill(u32::MAX));
+        let min_item = select_many(
+            is_padding,
+            <Min as ReduceInstruction<P>>::null_input(&this.min, line_size),
+            item,
+        );
+        let min = <Min as ReduceInstruction<P>>::reduce(
+            &this.min,
+            &accumulator.1,
+            min_item,
+            ReduceCoordinate::new_NotRequired(),
+            use_planes,
+        );
+
+        (max, min)
+    }
+
+    fn fuse_accumulators(
+        this: &Self,
+        lhs: Self::AccumulatorItem,
+        rhs: Self::AccumulatorItem,
+    ) -> Self::AccumulatorItem {
+        (
+            <Max as ReduceInstruction<P>>::fuse_accumulators(&this.max, lhs.0, rhs.0),
+            <Min as ReduceInstruction<P>>::fuse_accumulators(&this.min, lhs.1, rhs.1),
+        )
+    }
+
+    fn merge_line<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        shape_axis_reduce: u32,
+    ) -> Out {
+        let max = <Max as ReduceInstruction<P>>::merge_line::<P::EA>(
+            &this.max,
+            accumulator.0,
+            shape_axis_reduce,
+        );
+        let min = <Min as ReduceInstruction<P>>::merge_line::<P::EA>(
+            &this.min,
+            accumulator.1,
+            shape_axis_reduce,
+        );
+        Out::cast_from(max - min)
+    }
+
+    fn to_output_perpendicular<Out: Numeric>(
+        _this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Line<Out> {
+        Line::cast_from(accumulator.0 - accumulator.1)
+    }
+
+    fn combine_outputs<Out: Numeric>(
+        _this: &Self,
+        lhs: Line<Out>,
+        _lhs_count: u32,
+        _rhs: Line<Out>,
+        _rhs_count: u32,
+    ) -> Line<Out> {
+        // Never called, `supports_combine` is false.
+        lhs
+    }
+}
+
+/// A pair of shared memory used for [`Range`], holding the maxima and the minima.
+#[derive(CubeType)]
+pub struct RangeAccumulator<N: Numeric> {
+    pub maxima: SharedMemory<Line<N>>,
+    pub minima: SharedMemory<Line<N>>,
+}
+
+#[cube]
+impl<N: Numeric> SharedAccumulator for RangeAccumulator<N> {
+    type Item = (Line<N>, Line<N>);
+
+    fn allocate(
+        #[comptime] length: u32,
+        #[comptime] line_size: u32,
+        #[comptime] _coordinate: bool,
+    ) -> Self {
+        RangeAccumulator::<N> {
+            maxima: SharedMemory::new_lined(length, line_size),
+            minima: SharedMemory::new_lined(length, line_size),
+        }
+    }
+
+    fn read(accumulator: &Self, index: u32) -> Self::Item {
+        (accumulator.maxima[index], accumulator.minima[index])
+    }
+
+    fn write(accumulator: &mut Self, index: u32, item: Self::Item) {
+        accumulator.maxima[index] = item.0;
+        accumulator.minima[index] = item.1;
+    }
+}
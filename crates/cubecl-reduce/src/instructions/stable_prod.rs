@@ -0,0 +1,178 @@
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+
+use crate::precision::ReducePrecision;
+
+use super::{
+    ReduceCoordinate, ReduceFamily, ReduceInstruction, ReduceRequirements, SharedAccumulator,
+};
+
+/// Compute the product of the items without overflowing or underflowing along the way.
+///
+/// Instead of the product itself, the sum of the logarithms of the magnitudes is accumulated
+/// along with the product of the signs, and the product is only reconstructed in the output.
+/// The sign of a zero is zero, so that any zero item makes the whole product zero.
+///
+/// The accumulation is always done in `f32`, regardless of the reduce precision.
+#[derive(Debug, CubeType, Clone)]
+pub struct StableProd {}
+
+impl ReduceFamily for StableProd {
+    type Instruction<P: ReducePrecision> = Self;
+    type Config = ();
+
+    fn identity<Out: Numeric>(_config: Self::Config) -> Option<Out> {
+        Some(Out::from_int(1))
+    }
+}
+
+#[cube]
+impl StableProd {
+    /// Split the items into the logarithms of their magnitudes and their signs.
+    /// Zeros have a logarithm of zero, so that they don't propagate infinities.
+    fn split<N: Numeric>(items: Line<N>) -> (Line<f32>, Line<f32>) {
+        let items = Line::<f32>::cast_from(items);
+        let line_size = items.size();
+        let zero = Line::empty(line_size).fill(f32::from_int(0));
+        let is_zero = items.equal(zero);
+
+        let log_magnitudes = select_many(is_zero, zero, Log::log(Line::abs(items)));
+        let signs = select_many(
+            items.less_than(zero),
+            Line::empty(line_size).fill(f32::from_int(-1)),
+            select_many(is_zero, zero, Line::empty(line_size).fill(f32::from_int(1))),
+        );
+        (log_magnitudes, signs)
+    }
+}
+
+#[cube]
+impl<P: ReducePrecision> ReduceInstruction<P> for StableProd {
+    type AccumulatorItem = (Line<f32>, Line<f32>);
+    type SharedAccumulator = StableProdAccumulator;
+    type Config = ();
+
+    fn requirements(_this: &Self) -> ReduceRequirements {
+        ReduceRequirements { coordinates: false }
+    }
+
+    fn from_config(_config: Self::Config) -> Self {
+        StableProd {}
+    }
+
+    fn null_input(_this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
+        Line::empty(line_size).fill(P::EI::from_int(1))
+    }
+
+    fn identity(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        (
+            Line::empty(line_size).fill(f32::from_int(0)),
+            Line::empty(line_size).fill(f32::from_int(1)),
+        )
+    }
+
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <StableProd as ReduceInstruction<P>>::identity(this, line_size)
+    }
+
+    fn assign_accumulator(
+        _this: &Self,
+        destination: &mut Self::AccumulatorItem,
+        source: &Self::AccumulatorItem,
+    ) {
+        destination.0 = source.0;
+        destination.1 = source.1;
+    }
+
+    fn reduce(
+        _this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        item: Line<P::EI>,
+        _coordinate: ReduceCoordinate,
+        #[comptime] use_planes: bool,
+    ) -> Self::AccumulatorItem {
+        let (log_magnitudes, signs) = Self::split(item);
+        if use_planes {
+            (
+                accumulator.0 + plane_sum(log_magnitudes),
+                accumulator.1 * plane_prod(signs),
+            )
+        } else {
+            (accumulator.0 + log_magnitudes, accumulator.1 * signs)
+        }
+    }
+
+    fn fuse_accumulators(
+        _this: &Self,
+        lhs: Self::AccumulatorItem,
+        rhs: Self::AccumulatorItem,
+    ) -> Self::AccumulatorItem {
+        (lhs.0 + rhs.0, lhs.1 * rhs.1)
+    }
+
+    fn merge_line<Out: Numeric>(
+        _this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Out {
+        let mut log_magnitude = f32::from_int(0);
+        let mut sign = f32::from_int(1);
+        #[unroll]
+        for k in 0..accumulator.0.size() {
+            log_magnitude += accumulator.0[k];
+            sign *= accumulator.1[k];
+        }
+        Out::cast_from(sign * Exp::exp(log_magnitude))
+    }
+
+    fn to_output_perpendicular<Out: Numeric>(
+        _this: &Self,
+        accumulator: Self::AccumulatorItem,
+        _shape_axis_reduce: u32,
+    ) -> Line<Out> {
+        Line::cast_from(accumulator.1 * Exp::exp(accumulator.0))
+    }
+
+    fn combine_outputs<Out: Numeric>(
+        _this: &Self,
+        lhs: Line<Out>,
+        _lhs_count: u32,
+        rhs: Line<Out>,
+        _rhs_count: u32,
+    ) -> Line<Out> {
+        lhs * rhs
+    }
+}
+
+/// A pair of shared memory used for [`StableProd`], holding the logarithms of the magnitudes
+/// and the signs.
+#[derive(CubeType)]
+pub struct StableProdAccumulator {
+    pub log_magnitudes: SharedMemory<Line<f32>>,
+    pub signs: SharedMemory<Line<f32>>,
+}
+
+#[cube]
+impl SharedAccumulator for StableProdAccumulator {
+    type Item = (Line<f32>, Line<f32>);
+
+    fn allocate(
+        #[comptime] length: u32,
+        #[comptime] line_size: u32,
+        #[comptime] _coordinate: bool,
+    ) -> Self {
+        StableProdAccumulator {
+            log_magnitudes: SharedMemory::new_lined(length, line_size),
+            signs: SharedMemory::new_lined(length, line_size),
+        }
+    }
+
+    fn read(accumulator: &Self, index: u32) -> Self::Item {
+        (accumulator.log_magnitudes[index], accumulator.signs[index])
+    }
+
+    fn write(accumulator: &mut Self, index: u32, item: Self::Item) {
+        accumulator.log_magnitudes[index] = item.0;
+        accumulator.signs[index] = item.1;
+    }
+}
@@ -3,35 +3,121 @@ use cubecl_core::prelude::*;
 
 use crate::precision::ReducePrecision;
 
-use super::{ReduceCoordinate, ReduceFamily, ReduceInstruction, ReduceRequirements};
+use super::{
+    LaneOrder, ReduceCoordinate, ReduceFamily, ReduceInstruction, ReduceRequirements,
+    SharedAccumulator, pairwise_sum,
+};
 
+#[derive_cube_comptime]
+#[derive(Default)]
+pub struct SumConfig {
+    /// Whether the additions are compensated with the Kahan algorithm.
+    pub compensated: bool,
+    /// The order of the additions of the lanes of a line, when they aren't compensated.
+    pub lane_order: LaneOrder,
+}
+
+/// Compute the sum of the items.
+///
+/// When `compensated` is set, the accumulator of each unit keeps the rounding error of the
+/// additions in a compensation term that is subtracted from the following items, which keeps the
+/// error of long `f32` sums close to a single rounding instead of growing with the length of the
+/// axis. Only this per-unit accumulation is compensated: the items of a plane are added by an
+/// uncompensated `plane_sum` before the compensated addition, and [`SumAccumulator`] stores the
+/// corrected sums, so each addition between the units of a cube rounds once. Those levels add a
+/// few items per output instead of the length of the axis, so their error stays small.
+///
+/// The compensation only makes a difference for float accumulators, and relies on the backend
+/// keeping the order of the float operations, which rules out fast-math compilation.
+/// Without compensation, the lanes of a line are added in the given [`LaneOrder`].
 #[derive(Debug, CubeType, Clone)]
-pub struct Sum {}
+pub struct Sum {
+    #[cube(comptime)]
+    pub compensated: bool,
+    #[cube(comptime)]
+    pub lane_order: LaneOrder,
+}
 
 impl ReduceFamily for Sum {
     type Instruction<P: ReducePrecision> = Self;
-    type Config = ();
+    type Config = SumConfig;
+
+    fn identity<Out: Numeric>(_config: Self::Config) -> Option<Out> {
+        Some(Out::from_int(0))
+    }
+}
+
+#[cube]
+impl Sum {
+    /// The accumulator of an uncompensated sum of `elements`, with a null compensation.
+    pub fn without_compensation<N: Numeric>(elements: Line<N>) -> (Line<N>, Line<N>) {
+        (elements, Line::empty(elements.size()).fill(N::from_int(0)))
+    }
+
+    /// The sum of an accumulator, with its compensation subtracted.
+    pub fn corrected<N: Numeric>(accumulator: (Line<N>, Line<N>)) -> Line<N> {
+        accumulator.0 - accumulator.1
+    }
+}
+
+/// The shared memory of [`Sum`] and [`Mean`](super::Mean).
+///
+/// The compensation of an accumulator is subtracted from its sum when it is written and not kept,
+/// so a compensated sum uses as much shared memory as a plain one.
+#[derive(CubeType)]
+pub struct SumAccumulator<N: Numeric> {
+    pub sums: SharedMemory<Line<N>>,
+}
+
+#[cube]
+impl<In: Numeric> SharedAccumulator for SumAccumulator<In> {
+    type Item = (Line<In>, Line<In>);
+
+    fn allocate(
+        #[comptime] length: u32,
+        #[comptime] line_size: u32,
+        #[comptime] _coordinate: bool,
+    ) -> Self {
+        SumAccumulator::<In> {
+            sums: SharedMemory::new_lined(length, line_size),
+        }
+    }
+
+    fn read(accumulator: &Self, index: u32) -> Self::Item {
+        Sum::without_compensation::<In>(accumulator.sums[index])
+    }
+
+    fn write(accumulator: &mut Self, index: u32, item: Self::Item) {
+        accumulator.sums[index] = Sum::corrected::<In>(item);
+    }
 }
 
 #[cube]
 impl<P: ReducePrecision> ReduceInstruction<P> for Sum {
-    type AccumulatorItem = Line<P::EA>;
-    type SharedAccumulator = SharedMemory<Line<P::EA>>;
-    type Config = ();
+    type AccumulatorItem = (Line<P::EA>, Line<P::EA>);
+    type SharedAccumulator = SumAccumulator<P::EA>;
+    type Config = SumConfig;
 
     fn requirements(_this: &Self) -> ReduceRequirements {
         ReduceRequirements { coordinates: false }
     }
 
-    fn from_config(_config: Self::Config) -> Self {
-        Sum {}
+    fn from_config(#[comptime] config: Self::Config) -> Self {
+        Sum {
+            compensated: config.compensated,
+            lane_order: config.lane_order,
+        }
     }
     fn null_input(_this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
         Line::empty(line_size).fill(P::EI::from_int(0))
     }
 
-    fn null_accumulator(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
-        Line::empty(line_size).fill(P::EA::from_int(0))
+    fn identity(_this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        Sum::without_compensation::<P::EA>(Line::empty(line_size).fill(P::EA::from_int(0)))
+    }
+
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        <Sum as ReduceInstruction<P>>::identity(this, line_size)
     }
 
     fn assign_accumulator(
@@ -39,42 +125,75 @@ impl<P: ReducePrecision> ReduceInstruction<P> for Sum {
         destination: &mut Self::AccumulatorItem,
         source: &Self::AccumulatorItem,
     ) {
-        *destination = *source;
+        destination.0 = source.0;
+        destination.1 = source.1;
     }
 
     fn reduce(
-        _this: &Self,
+        this: &Self,
         accumulator: &Self::AccumulatorItem,
         item: Line<P::EI>,
         _coordinate: ReduceCoordinate,
         #[comptime] use_planes: bool,
     ) -> Self::AccumulatorItem {
-        if use_planes {
-            *accumulator + plane_sum(Line::cast_from(item))
+        let item = if use_planes {
+            plane_sum(Line::cast_from(item))
         } else {
-            *accumulator + Line::cast_from(item)
+            Line::cast_from(item)
+        };
+
+        if comptime![this.compensated] {
+            // Kahan: the low bits of `corrected` lost by the addition are recovered
+            // in the compensation and subtracted from the next item.
+            let corrected = item - accumulator.1;
+            let total = accumulator.0 + corrected;
+            (total, (total - accumulator.0) - corrected)
+        } else {
+            (accumulator.0 + item, accumulator.1)
         }
     }
 
     fn fuse_accumulators(
-        _this: &Self,
+        this: &Self,
         lhs: Self::AccumulatorItem,
         rhs: Self::AccumulatorItem,
     ) -> Self::AccumulatorItem {
-        lhs + rhs
+        if comptime![this.compensated] {
+            // The error of the addition of the sums is computed exactly (2Sum),
+            // and moved into the compensation along with those of both accumulators.
+            let total = lhs.0 + rhs.0;
+            let rhs_part = total - lhs.0;
+            let error = (lhs.0 - (total - rhs_part)) + (rhs.0 - rhs_part);
+            (total, lhs.1 + rhs.1 - error)
+        } else {
+            (lhs.0 + rhs.0, lhs.1)
+        }
     }
 
     fn merge_line<Out: Numeric>(
-        _this: &Self,
+        this: &Self,
         accumulator: Self::AccumulatorItem,
         _shape_axis_reduce: u32,
     ) -> Out {
-        let mut sum = P::EA::from_int(0);
-        #[unroll]
-        for k in 0..accumulator.size() {
-            sum += accumulator[k];
+        let line = Sum::corrected::<P::EA>(accumulator);
+        if comptime![!this.compensated && this.lane_order == LaneOrder::Pairwise] {
+            Out::cast_from(pairwise_sum::<P::EA>(line))
+        } else {
+            let mut sum = P::EA::from_int(0);
+            let mut compensation = P::EA::from_int(0);
+            #[unroll]
+            for k in 0..line.size() {
+                if comptime![this.compensated] {
+                    let corrected = line[k] - compensation;
+                    let total = sum + corrected;
+                    compensation = (total - sum) - corrected;
+                    sum = total;
+                } else {
+                    sum += line[k];
+                }
+            }
+            Out::cast_from(sum)
         }
-        Out::cast_from(sum)
     }
 
     fn to_output_perpendicular<Out: Numeric>(
@@ -82,6 +201,16 @@ impl<P: ReducePrecision> ReduceInstruction<P> for Sum {
         accumulator: Self::AccumulatorItem,
         _shape_axis_reduce: u32,
     ) -> Line<Out> {
-        Line::cast_from(accumulator)
+        Line::cast_from(Sum::corrected::<P::EA>(accumulator))
+    }
+
+    fn combine_outputs<Out: Numeric>(
+        _this: &Self,
+        lhs: Line<Out>,
+        _lhs_count: u32,
+        rhs: Line<Out>,
+        _rhs_count: u32,
+    ) -> Line<Out> {
+        lhs + rhs
     }
 }
@@ -0,0 +1,41 @@
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+
+/// The coordinate selected by [`ArgMax`](super::ArgMax) and [`ArgMin`](super::ArgMin) among
+/// equal extrema, used directly as their config.
+#[derive_cube_comptime]
+#[derive(Default)]
+pub enum TieBreak {
+    /// Select the coordinate of any of the equal extrema, depending on the strategy, which saves
+    /// a comparison for each item.
+    Any,
+    /// Select the lowest coordinate, the first occurrence of the extremum.
+    #[default]
+    LowestIndex,
+    /// Select the highest coordinate, the last occurrence of the extremum.
+    HighestIndex,
+}
+
+/// Whether each of `coordinates0` is selected over the matching one of `coordinates1` by
+/// `tie_break` when their items are equal.
+///
+/// The coordinate `u32::MAX` is used by the identity and by the items past the end of the
+/// axis, so it is never selected over another coordinate, even for [`TieBreak::HighestIndex`].
+/// No coordinate is selected over another for [`TieBreak::Any`].
+#[cube]
+pub fn wins_tie(
+    coordinates0: Line<u32>,
+    coordinates1: Line<u32>,
+    #[comptime] tie_break: TieBreak,
+) -> Line<bool> {
+    match comptime![tie_break] {
+        TieBreak::Any => Line::empty(coordinates0.size()).fill(false),
+        TieBreak::LowestIndex => coordinates0.less_than(coordinates1),
+        TieBreak::HighestIndex => {
+            let none = Line::empty(coordinates0.size()).fill(u32::MAX);
+            coordinates1.equal(none).or(coordinates0
+                .not_equal(none)
+                .and(coordinates0.greater_than(coordinates1)))
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use std::marker::PhantomData;
+
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+
+use crate::precision::ReducePrecision;
+
+use super::{ReduceCoordinate, ReduceFamily, ReduceInstruction, ReduceRequirements};
+
+/// A function applied by [`Transformed`] to each reduced value, moving it to another numeric
+/// domain such as a logarithmic scale.
+///
+/// The logarithms are only defined for positive values, so those of values that aren't positive
+/// are the logarithms of the smallest positive normal `f32`, about `-37.9` for [`Log10`],
+/// `-126` for [`Log2`] and `-379.3` for [`Db`]. The square root of a negative value is `0`.
+///
+/// [`Log10`]: OutputTransform::Log10
+/// [`Log2`]: OutputTransform::Log2
+/// [`Db`]: OutputTransform::Db
+#[derive_cube_comptime]
+#[derive(Default)]
+pub enum OutputTransform {
+    /// Keep the reduced value as is.
+    #[default]
+    Identity,
+    /// The logarithm in base 10 `log10(x)`.
+    Log10,
+    /// The logarithm in base 2 `log2(x)`.
+    Log2,
+    /// The square root `sqrt(x)`.
+    Sqrt,
+    /// The decibels `10 * log10(x)` of a power, such as a sum of squared amplitudes.
+    Db,
+}
+
+/// The config of [`Transformed`], made of the config of the wrapped instruction and the
+/// transform applied to its output.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub struct TransformedConfig<C> {
+    pub inner: C,
+    pub transform: OutputTransform,
+}
+
+/// Reduce like the instruction of `R`, then apply an [`OutputTransform`] to each reduced value,
+/// such as the power in decibels of a sum of squares with [`OutputTransform::Db`].
+///
+/// The transform is fused into `merge_line` and `to_output_perpendicular`, so it runs once per
+/// output value and never on the accumulators. It is computed in `f32`, regardless of the
+/// reduce precision and the output type. Transformed outputs can't be combined by
+/// [`reduce_update`](crate::reduce_update), except with [`OutputTransform::Identity`].
+#[derive(Debug)]
+pub struct Transformed<R: ReduceFamily> {
+    _inner: PhantomData<R>,
+}
+
+impl<R: ReduceFamily> ReduceFamily for Transformed<R> {
+    type Instruction<P: ReducePrecision> = TransformedInstruction<R::Instruction<P>>;
+    type Config = TransformedConfig<R::Config>;
+
+    fn identity<Out: Numeric>(config: Self::Config) -> Option<Out> {
+        // The outputs are transformed, so they can't be reduced into.
+        match config.transform {
+            OutputTransform::Identity => R::identity(config.inner),
+            _ => None,
+        }
+    }
+
+    fn supports_combine(config: Self::Config) -> bool {
+        config.transform == OutputTransform::Identity && R::supports_combine(config.inner)
+    }
+}
+
+/// The instruction of [`Transformed`], wrapping the instruction `I`.
+#[derive(Debug, CubeType, Clone)]
+pub struct TransformedInstruction<I: CubeType> {
+    pub inner: I,
+    #[cube(comptime)]
+    pub transform: OutputTransform,
+}
+
+/// Apply `transform` to each value of the line, computed in `f32` unless it is
+/// [`OutputTransform::Identity`].
+#[cube]
+pub fn apply_output_transform<N: Numeric>(
+    values: Line<N>,
+    #[comptime] transform: OutputTransform,
+) -> Line<N> {
+    if comptime![transform == OutputTransform::Identity] {
+        values
+    } else {
+        Line::cast_from(transform_f32(Line::<f32>::cast_from(values), transform))
+    }
+}
+
+#[cube]
+fn transform_f32(values: Line<f32>, #[comptime] transform: OutputTransform) -> Line<f32> {
+    let line_size = values.size();
+    let floor = Line::empty(line_size).fill(f32::new(comptime![f32::MIN_POSITIVE]));
+    let log = Log::log(Max::max(values, floor));
+
+    match comptime![transform] {
+        OutputTransform::Identity => values,
+        OutputTransform::Log10 => {
+            log * Line::empty(line_size).fill(f32::new(comptime![std::f32::consts::LOG10_E]))
+        }
+        OutputTransform::Log2 => {
+            log * Line::empty(line_size).fill(f32::new(comptime![std::f32::consts::LOG2_E]))
+        }
+        OutputTransform::Sqrt => {
+            let zero = Line::empty(line_size).fill(f32::from_int(0));
+            Sqrt::sqrt(Max::max(values, zero))
+        }
+        OutputTransform::Db => {
+            let scale = comptime![10.0 * std::f32::consts::LOG10_E];
+            log * Line::empty(line_size).fill(f32::new(scale))
+        }
+    }
+}
+
+#[cube]
+impl<P: ReducePrecision, I: ReduceInstruction<P>> ReduceInstruction<P>
+    for TransformedInstruction<I>
+{
+    type AccumulatorItem = I::AccumulatorItem;
+    type SharedAccumulator = I::SharedAccumulator;
+    type Config = TransformedConfig<I::Config>;
+
+    fn requirements(this: &Self) -> ReduceRequirements {
+        I::requirements(&this.inner)
+    }
+
+    fn from_config(#[comptime] config: Self::Config) -> Self {
+        TransformedInstruction::<I> {
+            inner: I::from_config(comptime![config.inner]),
+            transform: comptime![config.transform],
+        }
+    }
+
+    fn null_input(this: &Self, #[comptime] line_size: u32) -> Line<P::EI> {
+        I::null_input(&this.inner, line_size)
+    }
+
+    fn identity(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        I::identity(&this.inner, line_size)
+    }
+
+    fn null_accumulator(this: &Self, #[comptime] line_size: u32) -> Self::AccumulatorItem {
+        I::null_accumulator(&this.inner, line_size)
+    }
+
+    fn assign_accumulator(
+        this: &Self,
+        destination: &mut Self::AccumulatorItem,
+        source: &Self::AccumulatorItem,
+    ) {
+        I::assign_accumulator(&this.inner, destination, source);
+    }
+
+    fn reduce(
+        this: &Self,
+        accumulator: &Self::AccumulatorItem,
+        item: Line<P::EI>,
+        coordinate: ReduceCoordinate,
+        #[comptime] use_planes: bool,
+    ) -> Self::AccumulatorItem {
+        I::reduce(&this.inner, accumulator, item, coordinate, use_planes)
+    }
+
+    fn fuse_accumulators(
+        this: &Self,
+        lhs: Self::AccumulatorItem,
+        rhs: Self::AccumulatorItem,
+    ) -> Self::AccumulatorItem {
+        I::fuse_accumulators(&this.inner, lhs, rhs)
+    }
+
+    fn merge_line<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        shape_axis_reduce: u32,
+    ) -> Out {
+        let value = I::merge_line::<Out>(&this.inner, accumulator, shape_axis_reduce);
+        apply_output_transform::<Out>(Line::new(value), comptime![this.transform])[0]
+    }
+
+    fn to_output_perpendicular<Out: Numeric>(
+        this: &Self,
+        accumulator: Self::AccumulatorItem,
+        shape_axis_reduce: u32,
+    ) -> Line<Out> {
+        let values = I::to_output_perpendicular::<Out>(&this.inner, accumulator, shape_axis_reduce);
+        apply_output_transform::<Out>(values, comptime![this.transform])
+    }
+
+    fn combine_outputs<Out: Numeric>(
+        this: &Self,
+        lhs: Line<Out>,
+        lhs_count: u32,
+        rhs: Line<Out>,
+        rhs_count: u32,
+    ) -> Line<Out> {
+        if comptime![this.transform == OutputTransform::Identity] {
+            I::combine_outputs::<Out>(&this.inner, lhs, lhs_count, rhs, rhs_count)
+        } else {
+            // Never called, `supports_combine` is false.
+            lhs
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use cubecl_core as cubecl;
 use cubecl_core::prelude::*;
 
+use super::TieBreak;
+
 // Using plane operations, return the lowest coordinate for each line element
 // for which the item equal the target.
 #[cube]
@@ -18,3 +20,56 @@ pub(crate) fn lowest_coordinate_matching<E: CubePrimitive>(
     );
     plane_min(candidate_coordinate)
 }
+
+/// Using plane operations, return the coordinate selected by `tie_break` for each line element
+/// among the items equal to the target.
+#[cube]
+pub(crate) fn coordinate_matching<E: CubePrimitive>(
+    target: Line<E>,
+    item: Line<E>,
+    coordinate: Line<u32>,
+    #[comptime] tie_break: TieBreak,
+) -> Line<u32> {
+    match comptime![tie_break] {
+        TieBreak::Any => lowest_coordinate_matching(target, item, coordinate),
+        TieBreak::LowestIndex => lowest_coordinate_matching(target, item, coordinate),
+        TieBreak::HighestIndex => {
+            let line_size = item.size();
+            let is_candidate = item
+                .equal(target)
+                .and(coordinate.not_equal(Line::empty(line_size).fill(u32::MAX)));
+            let candidate_coordinate =
+                select_many(is_candidate, coordinate, Line::empty(line_size).fill(0u32));
+            plane_max(candidate_coordinate)
+        }
+    }
+}
+
+/// Whether the accumulator `acc` is kept over `item` by an extremum reduction, given whether
+/// `acc` is strictly `better` than `item`.
+///
+/// A NaN accumulator is always kept, so NaN propagates to the output once reduced.
+#[cube]
+pub(crate) fn keeps_extremum<N: Numeric>(acc: Line<N>, better: Line<bool>) -> Line<bool> {
+    better.or(acc.not_equal(acc))
+}
+
+/// The lowest value of `N`, which is `-inf` for floats.
+#[cube]
+pub(crate) fn lowest_value<N: Numeric>() -> N {
+    if comptime![N::as_type_native_unchecked().is_float()] {
+        N::cast_from(f32::new(comptime![f32::NEG_INFINITY]))
+    } else {
+        N::min_value()
+    }
+}
+
+/// The highest value of `N`, which is `+inf` for floats.
+#[cube]
+pub(crate) fn highest_value<N: Numeric>() -> N {
+    if comptime![N::as_type_native_unchecked().is_float()] {
+        N::cast_from(f32::new(comptime![f32::INFINITY]))
+    } else {
+        N::max_value()
+    }
+}
@@ -0,0 +1,120 @@
+use cubecl_core::prelude::*;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise};
+
+use crate::instructions::{Sum, SumConfig};
+use crate::precision::ReducePrecision;
+use crate::primitives::{SliceAxes, slice_offsets};
+use crate::update::contiguous_strides;
+use crate::{ReduceError, ReduceStrategy, reduce, validate_axis};
+
+/// The output of [`l1_normalize_axis`] for the slices summing to zero, which can't be divided by
+/// their sum.
+#[derive_cube_comptime]
+#[derive(Default)]
+pub enum ZeroSumPolicy {
+    /// Write zeros for all the items of the slice.
+    #[default]
+    Zero,
+    /// Write `1 / shape[axis]` for all the items of the slice, as if they were all equal.
+    Uniform,
+}
+
+/// Divide each item of `input` by the sum of its slice along `axis`, so each slice of `output`
+/// sums to one, and write it into `output`, which must have the same shape as `input`.
+///
+/// The sums are computed first by [`reduce`] with [`Sum`] and the given `strategy`, accumulated
+/// in `f32` into a temporary tensor, then a second kernel divides every item by the sum of its
+/// slice. For non-negative inputs, such as the exponentials of a softmax, this is the
+/// L1-normalization along the axis. The slices whose sum is exactly zero are written according to
+/// `zero_sum` instead of being divided by zero.
+///
+/// This returns [`ReduceError::MismatchShape`] if `output` doesn't have the shape of `input`,
+/// and the errors of [`reduce`].
+pub fn l1_normalize_axis<R: Runtime, P: ReducePrecision, Out: Float>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    strategy: Option<ReduceStrategy>,
+    zero_sum: ZeroSumPolicy,
+) -> Result<(), ReduceError> {
+    validate_axis(input.shape.len(), axis)?;
+    if output.shape != input.shape {
+        return Err(ReduceError::MismatchShape {
+            expected_shape: input.shape.to_vec(),
+            output_shape: output.shape.to_vec(),
+        });
+    }
+
+    let len = input.shape.iter().product::<usize>();
+    if len == 0 {
+        return Ok(());
+    }
+
+    let mut sums_shape = input.shape.to_vec();
+    sums_shape[axis] = 1;
+    let sums_strides = contiguous_strides(&sums_shape);
+    let num_slices = len / input.shape[axis];
+    let sums_handle = client.empty(num_slices * size_of::<f32>());
+    let sums = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(
+            &sums_handle,
+            &sums_strides,
+            &sums_shape,
+            size_of::<f32>(),
+        )
+    };
+    reduce::<R, P, f32, Sum>(client, input, sums, axis, strategy, SumConfig::default())?;
+
+    let cube_dim = CubeDim::default();
+    let cube_count = calculate_cube_count_elemwise(len, cube_dim);
+
+    unsafe {
+        l1_normalize_kernel::launch_unchecked::<P::EI, Out, R>(
+            client,
+            cube_count,
+            cube_dim,
+            input.as_tensor_arg(1),
+            sums.as_tensor_arg(1),
+            output.as_tensor_arg(1),
+            ScalarArg::new(axis as u32),
+            zero_sum,
+        );
+    }
+    Ok(())
+}
+
+#[cube(launch_unchecked)]
+fn l1_normalize_kernel<In: Numeric, Out: Float>(
+    input: &Tensor<In>,
+    sums: &Tensor<f32>,
+    output: &mut Tensor<Out>,
+    axis: u32,
+    #[comptime] zero_sum: ZeroSumPolicy,
+) {
+    if ABSOLUTE_POS >= output.len() {
+        terminate!();
+    }
+
+    // Each unit normalizes the item at `position` in its slice.
+    let axis_len = input.shape(axis);
+    let slice_index = ABSOLUTE_POS / axis_len;
+    let position = ABSOLUTE_POS % axis_len;
+    let (input_offset, output_offset) =
+        slice_offsets(input, output, slice_index, axis, SliceAxes::Same);
+    let (_, sums_offset) = slice_offsets(input, sums, slice_index, axis, SliceAxes::Same);
+    let input_offset = input_offset + position * input.stride(axis);
+    let output_offset = output_offset + position * output.stride(axis);
+
+    let sum = sums[sums_offset];
+    let zero_sum_item = match comptime![zero_sum] {
+        ZeroSumPolicy::Zero => f32::new(0.0),
+        ZeroSumPolicy::Uniform => f32::new(1.0) / f32::cast_from(axis_len),
+    };
+    let normalized = select(
+        sum == f32::new(0.0),
+        zero_sum_item,
+        f32::cast_from(input[input_offset]) / sum,
+    );
+    output[output_offset] = Out::cast_from(normalized);
+}
@@ -23,21 +23,7 @@ pub(crate) fn launch_reduce<Run: Runtime, P: ReducePrecision, Out: Numeric, Rd:
     strategy: ReduceStrategy,
     inst: Rd::Config,
 ) {
-    let settings = ReduceParams {
-        shared: strategy.shared.then(|| {
-            if strategy.use_planes {
-                config.cube_dim.y
-            } else {
-                config.cube_dim.num_elems()
-            }
-        }),
-        use_planes: strategy.use_planes,
-        line_size_input: config.line_size_input,
-        line_size_output: config.line_size_output,
-        line_mode: config.line_mode,
-        bound_checks: config.bound_checks,
-        bound_checks_inner: config.bound_checks_inner,
-    };
+    let settings = ReduceParams::new(&config, &strategy);
     unsafe {
         reduce_kernel::launch_unchecked::<P::EI, Out, P::EA, Rd, TensorArgs, Run>(
             client,
@@ -61,6 +47,29 @@ pub struct ReduceParams {
     pub line_mode: LineMode,
     pub bound_checks: bool,
     pub bound_checks_inner: BoundChecksInner,
+    /// If true, each agent loops over the reductions with a stride of the total number of agents.
+    pub grid_stride: bool,
+}
+
+impl ReduceParams {
+    pub(crate) fn new(config: &ReduceConfig, strategy: &ReduceStrategy) -> Self {
+        Self {
+            shared: strategy.shared.then(|| {
+                if strategy.use_planes {
+                    config.cube_dim.y
+                } else {
+                    config.cube_dim.num_elems()
+                }
+            }),
+            use_planes: strategy.use_planes,
+            line_size_input: config.line_size_input,
+            line_size_output: config.line_size_output,
+            line_mode: config.line_mode,
+            bound_checks: config.bound_checks,
+            bound_checks_inner: config.bound_checks_inner,
+            grid_stride: config.grid_stride,
+        }
+    }
 }
 
 #[cube(launch_unchecked)]
@@ -83,40 +92,105 @@ pub fn reduce_kernel_virtual<In: Numeric, Out: Numeric, Acc: Numeric, R: ReduceF
     #[comptime] params: ReduceParams,
     #[comptime] config: R::Config,
 ) {
-    let reduce_index = get_reduce_index(params);
-
-    #[allow(clippy::collapsible_if)]
-    if comptime![params.bound_checks] {
-        if reduce_index >= get_reduce_count(output.len() * params.line_size_output, params) {
-            terminate!();
-        }
-    }
-
-    reduce_kernel_inner::<(In, Acc), Out, R>(
+    let inst = &R::Instruction::<(In, Acc)>::from_config(config);
+    reduce_kernel_virtual_with::<(In, Acc), Out, R::Instruction<(In, Acc)>>(
         input,
         output,
+        inst,
         axis_reduce,
-        reduce_index,
         params,
-        config,
-    )
+    );
 }
 
+/// Same as [reduce_kernel_virtual], but with the instruction `inst` instead of the one of a
+/// config, for instructions holding runtime values such as a
+/// [checked](crate::instructions::IntegerSum::checked) integer sum.
 #[cube]
-fn reduce_kernel_inner<P: ReducePrecision, Out: Numeric, R: ReduceFamily>(
+pub(crate) fn reduce_kernel_virtual_with<
+    P: ReducePrecision,
+    Out: Numeric,
+    I: ReduceInstruction<P>,
+>(
     input: &VirtualTensor<P::EI>,
     output: &mut VirtualTensor<Out, ReadWrite>,
+    inst: &I,
+    axis_reduce: u32,
+    #[comptime] params: ReduceParams,
+) {
+    let reduce_index = get_reduce_index(params);
+
+    if comptime![params.grid_stride] {
+        let reduce_count = get_reduce_count(output.len() * params.line_size_output, params);
+        let reduce_stride = get_reduce_stride(params);
+
+        let mut reduce_index = reduce_index;
+        while reduce_index < reduce_count {
+            reduce_kernel_inner::<P, Out, I>(
+                input,
+                output,
+                inst,
+                axis_reduce,
+                reduce_index,
+                params,
+            );
+            reduce_index += reduce_stride;
+
+            if comptime![params.shared.is_some()] {
+                // Wait for all units to be done with the shared accumulator before reusing it.
+                sync_cube();
+            }
+        }
+    } else {
+        #[allow(clippy::collapsible_if)]
+        if comptime![params.bound_checks] {
+            if reduce_index >= get_reduce_count(output.len() * params.line_size_output, params) {
+                terminate!();
+            }
+        }
+
+        reduce_kernel_inner::<P, Out, I>(input, output, inst, axis_reduce, reduce_index, params)
+    }
+}
+
+#[cube]
+fn reduce_kernel_inner<P: ReducePrecision, Out: Numeric, I: ReduceInstruction<P>>(
+    input: &VirtualTensor<P::EI>,
+    output: &mut VirtualTensor<Out, ReadWrite>,
+    inst: &I,
     axis_reduce: u32,
     reduce_index: u32,
     #[comptime] params: ReduceParams,
-    #[comptime] config: R::Config,
 ) {
+    let accumulator =
+        reduce_accumulator::<P, Out, I>(input, output, inst, axis_reduce, reduce_index, params);
+
+    if elected_writer(params) {
+        write_to_output::<P, Out, I>(
+            output,
+            accumulator,
+            reduce_index,
+            input.shape(axis_reduce),
+            params,
+            inst,
+        );
+    }
+}
+
+/// Reduce the slice of `reduce_index` into an accumulator, with the strategy of the `params`.
+#[cube]
+fn reduce_accumulator<P: ReducePrecision, Out: Numeric, I: ReduceInstruction<P>>(
+    input: &VirtualTensor<P::EI>,
+    output: &mut VirtualTensor<Out, ReadWrite>,
+    inst: &I,
+    axis_reduce: u32,
+    reduce_index: u32,
+    #[comptime] params: ReduceParams,
+) -> I::AccumulatorItem {
     let range = ReduceRange::new::<P, Out>(reduce_index, input, output, axis_reduce, params);
 
-    let inst = &R::Instruction::<P>::from_config(config);
-    let accumulator = match comptime!((params.shared, params.use_planes)) {
+    match comptime!((params.shared, params.use_planes)) {
         (Some(accumulator_size), use_planes) => {
-            let mut accumulator = reduce_slice_shared::<P, VirtualTensor<P::EI>, R::Instruction<P>>(
+            let mut accumulator = reduce_slice_shared::<P, VirtualTensor<P::EI>, I>(
                 input,
                 inst,
                 range,
@@ -127,9 +201,9 @@ fn reduce_kernel_inner<P: ReducePrecision, Out: Numeric, R: ReduceFamily>(
                 params.bound_checks_inner,
             );
             sync_cube();
-            reduce_tree::<P, R::Instruction<P>>(inst, &mut accumulator, accumulator_size)
+            reduce_tree::<P, I>(inst, &mut accumulator, accumulator_size)
         }
-        (None, true) => reduce_slice_plane::<P, VirtualTensor<P::EI>, R::Instruction<P>>(
+        (None, true) => reduce_slice_plane::<P, VirtualTensor<P::EI>, I>(
             input,
             inst,
             range,
@@ -137,21 +211,150 @@ fn reduce_kernel_inner<P: ReducePrecision, Out: Numeric, R: ReduceFamily>(
             params.line_mode,
             params.bound_checks_inner,
         ),
-        (None, false) => reduce_slice::<P, VirtualTensor<P::EI>, R::Instruction<P>>(
+        (None, false) => reduce_slice::<P, VirtualTensor<P::EI>, I>(
             input,
             range,
             inst,
             params.line_size_input,
             params.line_mode,
         ),
-    };
+    }
+}
+
+/// Launch a reduce kernel writing two outputs from the same accumulators, the second one with
+/// the instruction of `second_config`. This function assumes that all parameters are already
+/// validated, and that both outputs have the same shape and strides.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn launch_reduce_pair<
+    Run: Runtime,
+    P: ReducePrecision,
+    Out: Numeric,
+    Rd: ReduceFamily,
+>(
+    client: &ComputeClient<Run::Server, Run::Channel>,
+    input: TensorHandleRef<Run>,
+    output: TensorHandleRef<Run>,
+    second_output: TensorHandleRef<Run>,
+    axis: u32,
+    config: ReduceConfig,
+    strategy: ReduceStrategy,
+    inst: (Rd::Config, Rd::Config),
+) {
+    let settings = ReduceParams::new(&config, &strategy);
+    unsafe {
+        reduce_pair_kernel::launch_unchecked::<P::EI, Out, P::EA, Rd, Run>(
+            client,
+            config.cube_count,
+            config.cube_dim,
+            input.as_tensor_arg(config.line_size_input as u8),
+            output.as_tensor_arg(config.line_size_output as u8),
+            second_output.as_tensor_arg(config.line_size_output as u8),
+            ScalarArg::new(axis),
+            settings,
+            inst.0,
+            inst.1,
+        );
+    }
+}
+
+/// Same as [reduce_kernel], but also writing the accumulators into `second_output` with the
+/// instruction of `second_config`.
+///
+/// Both configs must accumulate the items the same way, only what they output can differ, such
+/// as the sum and the sum of squares of [`Moments2`].
+#[cube(launch_unchecked)]
+pub fn reduce_pair_kernel<In: Numeric, Out: Numeric, Acc: Numeric, R: ReduceFamily>(
+    input: &Tensor<Line<In>>,
+    output: &mut Tensor<Line<Out>>,
+    second_output: &mut Tensor<Line<Out>>,
+    axis_reduce: u32,
+    #[comptime] params: ReduceParams,
+    #[comptime] config: R::Config,
+    #[comptime] second_config: R::Config,
+) {
+    let (input, mut output) = init_tensors::<TensorArgs, In, Out>(input, output);
+    let mut second_output =
+        VirtualTensor::<Out, ReadWrite>::new::<Tensor<Line<Out>>>(second_output);
+    let reduce_index = get_reduce_index(params);
+
+    if comptime![params.grid_stride] {
+        let reduce_count = get_reduce_count(output.len() * params.line_size_output, params);
+        let reduce_stride = get_reduce_stride(params);
+
+        let mut reduce_index = reduce_index;
+        while reduce_index < reduce_count {
+            reduce_pair_inner::<(In, Acc), Out, R>(
+                &input,
+                &mut output,
+                &mut second_output,
+                axis_reduce,
+                reduce_index,
+                params,
+                (config, second_config),
+            );
+            reduce_index += reduce_stride;
+
+            if comptime![params.shared.is_some()] {
+                // Wait for all units to be done with the shared accumulator before reusing it.
+                sync_cube();
+            }
+        }
+    } else {
+        #[allow(clippy::collapsible_if)]
+        if comptime![params.bound_checks] {
+            if reduce_index >= get_reduce_count(output.len() * params.line_size_output, params) {
+                terminate!();
+            }
+        }
+
+        reduce_pair_inner::<(In, Acc), Out, R>(
+            &input,
+            &mut output,
+            &mut second_output,
+            axis_reduce,
+            reduce_index,
+            params,
+            (config, second_config),
+        )
+    }
+}
+
+#[cube]
+fn reduce_pair_inner<P: ReducePrecision, Out: Numeric, R: ReduceFamily>(
+    input: &VirtualTensor<P::EI>,
+    output: &mut VirtualTensor<Out, ReadWrite>,
+    second_output: &mut VirtualTensor<Out, ReadWrite>,
+    axis_reduce: u32,
+    reduce_index: u32,
+    #[comptime] params: ReduceParams,
+    #[comptime] configs: (R::Config, R::Config),
+) {
+    let inst = &R::Instruction::<P>::from_config(configs.0);
+    let second_inst = &R::Instruction::<P>::from_config(configs.1);
+    let accumulator = reduce_accumulator::<P, Out, R::Instruction<P>>(
+        input,
+        output,
+        inst,
+        axis_reduce,
+        reduce_index,
+        params,
+    );
 
     if elected_writer(params) {
+        let shape_axis_reduce = input.shape(axis_reduce);
+        write_to_output::<P, Out, R::Instruction<P>>(
+            second_output,
+            accumulator,
+            reduce_index,
+            shape_axis_reduce,
+            params,
+            second_inst,
+        );
         write_to_output::<P, Out, R::Instruction<P>>(
             output,
             accumulator,
             reduce_index,
-            input.shape(axis_reduce),
+            shape_axis_reduce,
             params,
             inst,
         );
@@ -169,6 +372,17 @@ fn get_reduce_index(#[comptime] params: ReduceParams) -> u32 {
     }
 }
 
+#[cube]
+fn get_reduce_stride(#[comptime] params: ReduceParams) -> u32 {
+    if params.shared.is_some() {
+        CUBE_COUNT
+    } else if params.use_planes {
+        CUBE_COUNT * CUBE_DIM_Y
+    } else {
+        CUBE_COUNT * CUBE_DIM
+    }
+}
+
 #[cube]
 fn get_reduce_count(output_size: u32, #[comptime] params: ReduceParams) -> u32 {
     match comptime!(params.line_mode) {
@@ -0,0 +1,109 @@
+use cubecl_core::prelude::*;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise};
+
+use crate::instructions::{ReduceCoordinate, ReduceFamily, ReduceInstruction, reduce_inplace};
+use crate::precision::ReducePrecision;
+use crate::primitives::{SliceAxes, slice_offsets};
+use crate::{ReduceError, valid_output_shape, validate_axis};
+
+/// Reduce each slice of `input` along `axis` only up to its length given by `lengths` using the
+/// instruction `Inst` and write the result into `output`, such as the mean of the valid items of
+/// padded rows with [`Mean`].
+///
+/// `lengths` is a tensor of `u32` with the shape of `input` without `axis`, holding the number
+/// of leading items of each slice to reduce. The items past the length of a slice are never read,
+/// so this is cheaper than masking them, and lengths greater than the axis are clamped to it.
+/// The length is given to the instruction as the size of the reduced axis, so [`Mean`] divides
+/// by the length of each slice. A slice of length zero reduces no items with a size of `1`, so
+/// it gets the null accumulator of the instruction, such as `0` for [`Sum`] and [`Mean`] and the
+/// lowest value for [`Max`].
+///
+/// The shape of `output` must be the same as input except with a value of 1 for the given
+/// `axis`. Each slice is reduced by a single unit, reading the input one item at a time. This
+/// returns [`ReduceError::InvalidLengths`] if `lengths` doesn't have the expected shape, and
+/// otherwise the same errors as [`reduce`](crate::reduce) for the axis and the output shape.
+///
+/// [`Sum`]: crate::instructions::Sum
+/// [`Mean`]: crate::instructions::Mean
+/// [`Max`]: crate::instructions::Max
+pub fn reduce_with_lengths<R: Runtime, P: ReducePrecision, Out: Numeric, Inst: ReduceFamily>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    lengths: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    inst_config: Inst::Config,
+) -> Result<(), ReduceError> {
+    validate_axis(input.shape.len(), axis)?;
+    valid_output_shape(input.shape, output.shape, axis)?;
+    let mut expected_shape = input.shape.to_vec();
+    expected_shape.remove(axis);
+    if lengths.shape != expected_shape {
+        return Err(ReduceError::InvalidLengths {
+            shape: lengths.shape.to_vec(),
+            expected_shape,
+        });
+    }
+
+    let num_elems = output.shape.iter().product::<usize>();
+    let cube_dim = CubeDim::default();
+    let cube_count = calculate_cube_count_elemwise(num_elems, cube_dim);
+
+    unsafe {
+        reduce_with_lengths_kernel::launch_unchecked::<P::EI, Out, P::EA, Inst, R>(
+            client,
+            cube_count,
+            cube_dim,
+            input.as_tensor_arg(1),
+            lengths.as_tensor_arg(1),
+            output.as_tensor_arg(1),
+            ScalarArg::new(axis as u32),
+            inst_config,
+        );
+    }
+    Ok(())
+}
+
+#[cube(launch_unchecked)]
+fn reduce_with_lengths_kernel<In: Numeric, Out: Numeric, Acc: Numeric, R: ReduceFamily>(
+    input: &Tensor<Line<In>>,
+    lengths: &Tensor<u32>,
+    output: &mut Tensor<Out>,
+    axis: u32,
+    #[comptime] config: R::Config,
+) {
+    if ABSOLUTE_POS >= output.len() {
+        terminate!();
+    }
+
+    let inst = &R::Instruction::<(In, Acc)>::from_config(config);
+    let requirements = R::Instruction::<(In, Acc)>::requirements(inst);
+
+    // The output has a single item along `axis`, so it has an item per slice.
+    // The lengths have the same axes without `axis`.
+    let (offset, output_offset) = slice_offsets(input, output, ABSOLUTE_POS, axis, SliceAxes::Same);
+    let (_, length_offset) =
+        slice_offsets(input, lengths, ABSOLUTE_POS, axis, SliceAxes::WithoutAxis);
+
+    let length = Min::min(lengths[length_offset], input.shape(axis));
+    let axis_stride = input.stride(axis);
+
+    let mut accumulator = R::Instruction::<(In, Acc)>::null_accumulator(inst, 1u32);
+    for k in 0..length {
+        let coordinate = if comptime![requirements.coordinates] {
+            ReduceCoordinate::new_Required(Line::new(k))
+        } else {
+            ReduceCoordinate::new_NotRequired()
+        };
+        reduce_inplace::<(In, Acc), R::Instruction<(In, Acc)>>(
+            inst,
+            &mut accumulator,
+            input[offset + k * axis_stride],
+            coordinate,
+            false,
+        );
+    }
+
+    output[output_offset] =
+        R::Instruction::<(In, Acc)>::merge_line::<Out>(inst, accumulator, Max::max(length, 1u32));
+}
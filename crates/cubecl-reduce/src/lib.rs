@@ -15,25 +15,85 @@ pub mod instructions;
 pub mod primitives;
 pub mod tune_key;
 
+mod argmax_global;
+mod checked_sum;
+mod concat;
 mod config;
+mod cube_partials;
+mod diagonal;
+mod dot;
+mod dynamic;
+mod enqueue;
 mod error;
+mod fallback;
+mod frobenius;
+mod gather;
+mod histogram;
+mod l1_normalize;
 mod launch;
+mod lengths;
+mod map;
+mod moments;
+mod naive;
+mod packed;
+mod permuted;
+mod plane_local;
+mod pool;
 mod precision;
+mod quantile;
+mod rounding;
+mod scan;
+mod scatter;
 mod shared_sum;
+mod shared_transpose;
+mod softmax;
 mod strategy;
+mod subnormal;
+mod update;
+mod weighted;
 
+pub use argmax_global::*;
+pub use checked_sum::*;
+pub use concat::*;
 pub use config::*;
+pub use cube_partials::*;
+pub use diagonal::*;
+pub use dot::*;
+pub use dynamic::*;
+pub use enqueue::*;
 pub use error::*;
+pub use fallback::*;
+pub use frobenius::*;
+pub use gather::*;
+pub use histogram::*;
 pub use instructions::ReduceFamily;
 pub use instructions::ReduceInstruction;
+pub use l1_normalize::*;
+pub use lengths::*;
+pub use map::*;
+pub use moments::*;
+pub use packed::*;
+pub use permuted::*;
+pub use plane_local::*;
+pub use pool::*;
 pub use precision::ReducePrecision;
+pub use quantile::*;
+pub use rounding::*;
+pub use scan::*;
+pub use scatter::*;
 pub use shared_sum::*;
+pub use softmax::*;
 pub use strategy::*;
+pub use subnormal::*;
+pub use update::*;
+pub use weighted::*;
 
 use launch::*;
+use naive::*;
+use shared_transpose::*;
 
 pub use args::init_tensors;
-pub use launch::{ReduceParams, reduce_kernel, reduce_kernel_virtual};
+pub use launch::{ReduceParams, reduce_kernel, reduce_kernel_virtual, reduce_pair_kernel};
 
 #[cfg(feature = "export_tests")]
 pub mod test;
@@ -43,12 +103,19 @@ use cubecl_core::prelude::*;
 /// Reduce the given `axis` of the `input` tensor using the instruction `Inst` and write the result into `output`.
 ///
 /// An optional [`ReduceStrategy`] can be provided to force the reduction to use a specific algorithm. If omitted, a best effort
-/// is done to try and pick the best strategy supported for the provided `client`.
+/// is done to try and pick the best strategy supported for the provided `client`, see [`ReduceStrategy::select`].
 ///
 /// Return an error if `strategy` is `Some(strategy)` and the specified strategy is not supported by the `client`.
 /// Also returns an error if the `axis` is larger than the `input` rank or if the shape of `output` is invalid.
-/// The shape of `output` must be the same as input except with a value of 1 for the given `axis`.
+/// The shape of `output` must be the same as input except with a value of 1 for the given `axis`,
+/// or without the given `axis`. Its strides can place the results in any arrangement that addresses
+/// each position of a contiguous buffer once, see [`ReduceConfig::output_mapping`].
 ///
+/// When more cubes are required than the runtime supports, the cube count is capped
+/// and each unit, plane or cube performs multiple reductions.
+///
+/// The reduction is only enqueued on the `client`, this never waits for it to complete.
+/// See [`reduce_enqueue`] to also allocate the output.
 ///
 /// # Example
 ///
@@ -100,21 +167,70 @@ pub fn reduce<R: Runtime, P: ReducePrecision, Out: Numeric, Inst: ReduceFamily>(
     axis: usize,
     strategy: Option<ReduceStrategy>,
     inst_config: Inst::Config,
+) -> Result<(), ReduceError> {
+    reduce_with_max_cube_count::<R, P, Out, Inst>(
+        client,
+        input,
+        output,
+        axis,
+        strategy,
+        inst_config,
+        R::max_cube_count(),
+    )
+}
+
+/// Same as [reduce], but with a lower cube count limit than the one of the runtime.
+pub(crate) fn reduce_with_max_cube_count<
+    R: Runtime,
+    P: ReducePrecision,
+    Out: Numeric,
+    Inst: ReduceFamily,
+>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    strategy: Option<ReduceStrategy>,
+    inst_config: Inst::Config,
+    max_cube_count: (u32, u32, u32),
 ) -> Result<(), ReduceError> {
     validate_axis(input.shape.len(), axis)?;
-    valid_output_shape(input.shape, output.shape, axis)?;
-    let strategy = strategy
-        .map(|s| s.validate::<R>(client))
-        .unwrap_or(Ok(ReduceStrategy::new::<R>(client, true)))?;
-    let config = ReduceConfig::generate::<R, P::EI>(client, &input, &output, axis, &strategy);
-
-    if let CubeCount::Static(x, y, z) = config.cube_count {
-        let (max_x, max_y, max_z) = R::max_cube_count();
-        if x > max_x || y > max_y || z > max_z {
-            return Err(ReduceError::CubeCountTooLarge);
-        }
+    let mapping = ReduceConfig::output_mapping(input.shape, output.shape, output.strides, axis)?;
+    let output = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(
+            output.handle,
+            &mapping.strides,
+            &mapping.shape,
+            output.elem_size,
+        )
+    };
+    let strategy = resolve_strategy::<R>(client, strategy, input.shape, axis, size_of::<P::EI>())?;
+
+    if strategy.naive {
+        launch_reduce_naive::<R, P, Out, Inst>(client, input, output, axis, inst_config);
+        return Ok(());
+    }
+
+    if supports_shared_transpose(&strategy, &input, axis) {
+        return launch_reduce_shared_transpose::<R, P, Out, Inst>(
+            client,
+            input,
+            output,
+            axis,
+            inst_config,
+            max_cube_count,
+        );
     }
 
+    let config = ReduceConfig::generate::<R, P::EI>(
+        client,
+        &input,
+        &output,
+        axis,
+        &strategy,
+        max_cube_count,
+    );
+
     launch_reduce::<R, P, Out, Inst>(
         client,
         input,
@@ -127,6 +243,27 @@ pub fn reduce<R: Runtime, P: ReducePrecision, Out: Numeric, Inst: ReduceFamily>(
     Ok(())
 }
 
+/// The strategy [reduce] runs for `axis` of an input of shape `input_shape` with items of
+/// `elem_size` bytes: the given `strategy` validated for the client, or the one picked by
+/// [ReduceStrategy::select] for the device of the client.
+pub(crate) fn resolve_strategy<R: Runtime>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    strategy: Option<ReduceStrategy>,
+    input_shape: &[usize],
+    axis: usize,
+    elem_size: usize,
+) -> Result<ReduceStrategy, ReduceError> {
+    match strategy {
+        Some(strategy) => strategy.validate::<R>(client),
+        None => Ok(ReduceStrategy::select(
+            input_shape.iter().product(),
+            input_shape[axis],
+            elem_size,
+            &ReduceDeviceProfile::new::<R>(client),
+        )),
+    }
+}
+
 // Check that the given axis is less than the rank of the input.
 fn validate_axis(rank: usize, axis: usize) -> Result<(), ReduceError> {
     if axis > rank {
@@ -0,0 +1,189 @@
+use std::marker::PhantomData;
+
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+
+use crate::args::{ReduceArgs, ReduceDType};
+use crate::instructions::ReduceFamily;
+use crate::precision::ReducePrecision;
+use crate::shared_transpose::supports_shared_transpose;
+use crate::{
+    ReduceConfig, ReduceError, ReduceParams, ReduceStrategy, reduce_kernel, resolve_strategy,
+    validate_axis,
+};
+
+/// An elementwise function applied by [`map_reduce`] to each item of the input before it is
+/// reduced, so the transform is fused into the reduction without a custom instruction.
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(Clone)]
+/// pub struct Relu;
+///
+/// #[cube]
+/// impl ReduceMap for Relu {
+///     fn apply<In: Numeric, Out: Numeric>(item: Line<In>) -> Line<Out> {
+///         let item = Line::<Out>::cast_from(item);
+///         Max::max(item, Line::empty(item.size()).fill(Out::from_int(0)))
+///     }
+/// }
+/// ```
+#[cube]
+pub trait ReduceMap: Send + Sync + 'static + Clone {
+    /// Map the items read from the input to the items reduced by the instruction.
+    fn apply<In: Numeric, Out: Numeric>(item: Line<In>) -> Line<Out>;
+}
+
+/// Arguments reading an input of type `In` and mapping each of its items with `M`.
+///
+/// The reduce kernels see the mapped input, whose items are of the input type of the
+/// [`ReducePrecision`] of the reduction.
+#[derive(Clone)]
+pub struct MapArgs<In: Numeric, M: ReduceMap> {
+    _input: PhantomData<In>,
+    _map: PhantomData<M>,
+}
+
+#[cube]
+impl<In: Numeric, M: ReduceMap> ReduceArgs for MapArgs<In, M> {
+    type Input<E: Numeric> = Tensor<Line<In>>;
+    type Output<E: Numeric> = Tensor<Line<E>>;
+    type State<P: ReduceDType> = (*const Tensor<Line<In>>, *mut Tensor<Line<P::Out>>);
+
+    fn init_state<P: ReduceDType>(
+        input: &Self::Input<P::In>,
+        output: &mut Self::Output<P::Out>,
+    ) -> Self::State<P> {
+        (input, output)
+    }
+
+    fn read_input<P: ReduceDType>(state: &Self::State<P>, index: u32) -> Line<P::In> {
+        M::apply::<In, P::In>(unsafe { (*state.0)[index] })
+    }
+
+    fn read_output<P: ReduceDType>(state: &Self::State<P>, index: u32) -> Line<P::Out> {
+        unsafe { (*state.1)[index] }
+    }
+
+    fn write_output<P: ReduceDType>(state: &mut Self::State<P>, index: u32, value: Line<P::Out>) {
+        unsafe { (*state.1)[index] = value }
+    }
+
+    fn buffer_len_input<P: ReduceDType>(state: &Self::State<P>) -> u32 {
+        unsafe { (*state.0).buffer_len() }
+    }
+
+    fn buffer_len_output<P: ReduceDType>(state: &Self::State<P>) -> u32 {
+        unsafe { (*state.1).buffer_len() }
+    }
+
+    fn len_input<P: ReduceDType>(state: &Self::State<P>) -> u32 {
+        unsafe { (*state.0).len() }
+    }
+
+    fn len_output<P: ReduceDType>(state: &Self::State<P>) -> u32 {
+        unsafe { (*state.1).len() }
+    }
+
+    fn rank_input<P: ReduceDType>(state: &Self::State<P>) -> u32 {
+        unsafe { (*state.0).rank() }
+    }
+
+    fn rank_output<P: ReduceDType>(state: &Self::State<P>) -> u32 {
+        unsafe { (*state.1).rank() }
+    }
+
+    fn shape_input<P: ReduceDType>(state: &Self::State<P>, dim: u32) -> u32 {
+        unsafe { (*state.0).shape(dim) }
+    }
+
+    fn shape_output<P: ReduceDType>(state: &Self::State<P>, dim: u32) -> u32 {
+        unsafe { (*state.1).shape(dim) }
+    }
+
+    fn stride_input<P: ReduceDType>(state: &Self::State<P>, dim: u32) -> u32 {
+        unsafe { (*state.0).stride(dim) }
+    }
+
+    fn stride_output<P: ReduceDType>(state: &Self::State<P>, dim: u32) -> u32 {
+        unsafe { (*state.1).stride(dim) }
+    }
+
+    fn line_size_input<P: ReduceDType>(state: &Self::State<P>) -> comptime_type!(u32) {
+        unsafe { (*state.0).line_size() }
+    }
+
+    fn line_size_output<P: ReduceDType>(state: &Self::State<P>) -> comptime_type!(u32) {
+        unsafe { (*state.1).line_size() }
+    }
+}
+
+/// Reduce the given `axis` of `input` mapped by `M` using the instruction `Inst`
+/// and write the result into `output`.
+///
+/// The `input` is of type `In`, and each of its items is mapped by [`ReduceMap::apply`] to an
+/// item of type `P::EI` where it is read, so the mapped input is never written to global memory.
+/// The padding of the reduction isn't mapped, so any map can be used with any instruction.
+///
+/// The `strategy` is validated or selected the same way as [`reduce`](crate::reduce), and this
+/// returns the same errors. The naive and shared transpose kernels don't map the input, so this
+/// returns [`ReduceError::NaiveUnsupported`] for the naive strategy, and
+/// [`ReduceError::SharedTransposeUnsupported`] when [`reduce`](crate::reduce) would run the
+/// shared transpose strategy.
+pub fn map_reduce<
+    R: Runtime,
+    In: Numeric,
+    M: ReduceMap,
+    P: ReducePrecision,
+    Out: Numeric,
+    Inst: ReduceFamily,
+>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    strategy: Option<ReduceStrategy>,
+    inst_config: Inst::Config,
+) -> Result<(), ReduceError> {
+    validate_axis(input.shape.len(), axis)?;
+    let mapping = ReduceConfig::output_mapping(input.shape, output.shape, output.strides, axis)?;
+    let output = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(
+            output.handle,
+            &mapping.strides,
+            &mapping.shape,
+            output.elem_size,
+        )
+    };
+
+    let strategy = resolve_strategy::<R>(client, strategy, input.shape, axis, size_of::<In>())?;
+    if strategy.naive {
+        return Err(ReduceError::NaiveUnsupported);
+    }
+    if supports_shared_transpose(&strategy, &input, axis) {
+        return Err(ReduceError::SharedTransposeUnsupported);
+    }
+    let config = ReduceConfig::generate::<R, In>(
+        client,
+        &input,
+        &output,
+        axis,
+        &strategy,
+        R::max_cube_count(),
+    );
+
+    unsafe {
+        reduce_kernel::launch_unchecked::<P::EI, Out, P::EA, Inst, MapArgs<In, M>, R>(
+            client,
+            config.cube_count,
+            config.cube_dim,
+            input.as_tensor_arg(config.line_size_input as u8),
+            output.as_tensor_arg(config.line_size_output as u8),
+            ScalarArg::new(axis as u32),
+            ReduceParams::new(&config, &strategy),
+            inst_config,
+        );
+    }
+    Ok(())
+}
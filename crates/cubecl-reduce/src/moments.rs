@@ -0,0 +1,71 @@
+use cubecl_core::prelude::*;
+
+use crate::instructions::{Moments2, Moments2Config, Moments2Output};
+use crate::launch::launch_reduce_pair;
+use crate::precision::ReducePrecision;
+use crate::{ReduceConfig, ReduceError, ReduceStrategy, valid_output_shape, validate_axis};
+
+/// Compute both the sums `sum(x)` and the sums of squares `sum(x^2)` along the given `axis` of
+/// `input` with [`Moments2`], and write them into `sums` and `squares`.
+///
+/// Both moments are accumulated together and written by the same kernel, so the input is only
+/// read once. The mean and variance can then be derived as `sum(x) / n` and
+/// `sum(x^2) / n - (sum(x) / n)^2`.
+///
+/// The `sums` and `squares` must have the same shape as `input` except for a value of 1 for the
+/// given `axis`, and the same strides. This returns the same errors as [`reduce`](crate::reduce),
+/// [`ReduceError::MismatchOutputStrides`] when the strides of the outputs differ, and
+/// [`ReduceError::NaiveUnsupported`] for the naive strategy. The `shared_transpose` strategy
+/// isn't supported and is ignored.
+pub fn reduce_moments2<R: Runtime, P: ReducePrecision, Out: Numeric>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    sums: TensorHandleRef<R>,
+    squares: TensorHandleRef<R>,
+    axis: usize,
+    strategy: Option<ReduceStrategy>,
+) -> Result<(), ReduceError> {
+    validate_axis(input.shape.len(), axis)?;
+    valid_output_shape(input.shape, sums.shape, axis)?;
+    valid_output_shape(input.shape, squares.shape, axis)?;
+    if sums.strides != squares.strides {
+        return Err(ReduceError::MismatchOutputStrides {
+            strides: sums.strides.to_vec(),
+            other_strides: squares.strides.to_vec(),
+        });
+    }
+
+    let strategy = strategy
+        .map(|s| s.validate::<R>(client))
+        .unwrap_or(Ok(ReduceStrategy::new::<R>(client, true)))?;
+    if strategy.naive {
+        return Err(ReduceError::NaiveUnsupported);
+    }
+    let config = ReduceConfig::generate::<R, P::EI>(
+        client,
+        &input,
+        &sums,
+        axis,
+        &strategy,
+        R::max_cube_count(),
+    );
+
+    launch_reduce_pair::<R, P, Out, Moments2>(
+        client,
+        input,
+        sums,
+        squares,
+        axis as u32,
+        config,
+        strategy,
+        (
+            Moments2Config {
+                output: Moments2Output::Sum,
+            },
+            Moments2Config {
+                output: Moments2Output::SumOfSquares,
+            },
+        ),
+    );
+    Ok(())
+}
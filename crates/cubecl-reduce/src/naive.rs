@@ -0,0 +1,94 @@
+use cubecl_core::prelude::*;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise};
+
+use crate::instructions::{ReduceCoordinate, ReduceFamily, ReduceInstruction, reduce_inplace};
+use crate::precision::ReducePrecision;
+use crate::primitives::{SliceAxes, slice_offsets};
+
+/// Launch the [naive](crate::ReduceStrategy::naive) reduction of `axis`, where each unit reduces
+/// the item of `output` at its position, reading `input` one item at a time.
+///
+/// The shape and strides of `output` must be the ones of the output mapping, with the rank of
+/// `input` and a single item along `axis`.
+pub(crate) fn launch_reduce_naive<
+    R: Runtime,
+    P: ReducePrecision,
+    Out: Numeric,
+    Inst: ReduceFamily,
+>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    inst_config: Inst::Config,
+) {
+    let (cube_count, cube_dim) = naive_cube_count(&output);
+
+    unsafe {
+        reduce_naive_kernel::launch_unchecked::<P::EI, Out, P::EA, Inst, R>(
+            client,
+            cube_count,
+            cube_dim,
+            input.as_tensor_arg(1),
+            output.as_tensor_arg(1),
+            ScalarArg::new(axis as u32),
+            inst_config,
+        );
+    }
+}
+
+/// The cube count and dimension of the naive reduction into `output`, with a unit per item.
+pub(crate) fn naive_cube_count<R: Runtime>(output: &TensorHandleRef<R>) -> (CubeCount, CubeDim) {
+    let num_elems = output.shape.iter().product::<usize>();
+    let cube_dim = CubeDim::default();
+    (calculate_cube_count_elemwise(num_elems, cube_dim), cube_dim)
+}
+
+#[cube(launch_unchecked)]
+fn reduce_naive_kernel<In: Numeric, Out: Numeric, Acc: Numeric, R: ReduceFamily>(
+    input: &Tensor<Line<In>>,
+    output: &mut Tensor<Out>,
+    axis: u32,
+    #[comptime] config: R::Config,
+) {
+    let inst = &R::Instruction::<(In, Acc)>::from_config(config);
+    reduce_naive_inner::<(In, Acc), Out, R::Instruction<(In, Acc)>>(input, output, inst, axis);
+}
+
+/// Reduce the slice along `axis` of the item of `output` at the position of the unit with `inst`.
+#[cube]
+pub(crate) fn reduce_naive_inner<P: ReducePrecision, Out: Numeric, I: ReduceInstruction<P>>(
+    input: &Tensor<Line<P::EI>>,
+    output: &mut Tensor<Out>,
+    inst: &I,
+    axis: u32,
+) {
+    if ABSOLUTE_POS >= output.len() {
+        terminate!();
+    }
+
+    let requirements = I::requirements(inst);
+
+    // The output has a single item along `axis`, so it has an item per reduced slice.
+    let (offset, output_offset) = slice_offsets(input, output, ABSOLUTE_POS, axis, SliceAxes::Same);
+
+    let axis_len = input.shape(axis);
+    let axis_stride = input.stride(axis);
+    let mut accumulator = I::null_accumulator(inst, 1u32);
+    for k in 0..axis_len {
+        let coordinate = if comptime![requirements.coordinates] {
+            ReduceCoordinate::new_Required(Line::new(k))
+        } else {
+            ReduceCoordinate::new_NotRequired()
+        };
+        reduce_inplace::<P, I>(
+            inst,
+            &mut accumulator,
+            input[offset + k * axis_stride],
+            coordinate,
+            false,
+        );
+    }
+
+    output[output_offset] = I::merge_line::<Out>(inst, accumulator, axis_len);
+}
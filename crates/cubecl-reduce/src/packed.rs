@@ -0,0 +1,219 @@
+use std::marker::PhantomData;
+
+use cubecl_core as cubecl;
+use cubecl_core::prelude::*;
+
+use crate::args::{ReduceArgs, ReduceDType};
+use crate::instructions::ReduceFamily;
+use crate::precision::ReducePrecision;
+use crate::{
+    ReduceConfig, ReduceError, ReduceParams, ReduceStrategy, reduce_kernel, valid_output_shape,
+    validate_axis,
+};
+
+/// The bit width of the unsigned integers packed by [`PackedArgs`].
+pub trait PackedBits: Send + Sync + 'static + Clone {
+    const BITS: u32;
+}
+
+/// Unsigned integers of 4 bits, two per byte.
+#[derive(Clone)]
+pub struct Bits4;
+
+impl PackedBits for Bits4 {
+    const BITS: u32 = 4;
+}
+
+/// Arguments reading an input whose last axis is packed into integers of type `S`, each holding
+/// as many unsigned values of `B::BITS` bits as fit in `S`, with the first one in the lowest bits.
+///
+/// The reduce kernels see the unpacked input, so its last axis is that many times longer than
+/// the one of the storage tensor. The storage must be contiguous along its last axis and be
+/// read one element at a time.
+#[derive(Clone)]
+pub struct PackedArgs<S: Int, B: PackedBits> {
+    _storage: PhantomData<S>,
+    _bits: PhantomData<B>,
+}
+
+/// Number of values packed in each element of the storage.
+fn values_per_element<S: Int, B: PackedBits>() -> u32 {
+    size_of::<S>() as u32 * 8 / B::BITS
+}
+
+#[cube]
+impl<S: Int, B: PackedBits> ReduceArgs for PackedArgs<S, B> {
+    type Input<E: Numeric> = Tensor<Line<S>>;
+    type Output<E: Numeric> = Tensor<Line<E>>;
+    type State<P: ReduceDType> = (*const Tensor<Line<S>>, *mut Tensor<Line<P::Out>>);
+
+    fn init_state<P: ReduceDType>(
+        input: &Self::Input<P::In>,
+        output: &mut Self::Output<P::Out>,
+    ) -> Self::State<P> {
+        (input, output)
+    }
+
+    fn read_input<P: ReduceDType>(state: &Self::State<P>, index: u32) -> Line<P::In> {
+        let count = comptime![values_per_element::<S, B>()];
+        let mask = comptime![(1u32 << B::BITS) - 1];
+
+        let element = unsafe { (*state.0)[index / count] };
+        let shift = (index % count) * comptime![B::BITS];
+        let value = (u32::cast_from(element[0]) >> shift) & mask;
+        Line::new(P::In::cast_from(value))
+    }
+
+    fn read_output<P: ReduceDType>(state: &Self::State<P>, index: u32) -> Line<P::Out> {
+        unsafe { (*state.1)[index] }
+    }
+
+    fn write_output<P: ReduceDType>(state: &mut Self::State<P>, index: u32, value: Line<P::Out>) {
+        unsafe { (*state.1)[index] = value }
+    }
+
+    fn buffer_len_input<P: ReduceDType>(state: &Self::State<P>) -> u32 {
+        unsafe { (*state.0).buffer_len() * comptime![values_per_element::<S, B>()] }
+    }
+
+    fn buffer_len_output<P: ReduceDType>(state: &Self::State<P>) -> u32 {
+        unsafe { (*state.1).buffer_len() }
+    }
+
+    fn len_input<P: ReduceDType>(state: &Self::State<P>) -> u32 {
+        unsafe { (*state.0).len() * comptime![values_per_element::<S, B>()] }
+    }
+
+    fn len_output<P: ReduceDType>(state: &Self::State<P>) -> u32 {
+        unsafe { (*state.1).len() }
+    }
+
+    fn rank_input<P: ReduceDType>(state: &Self::State<P>) -> u32 {
+        unsafe { (*state.0).rank() }
+    }
+
+    fn rank_output<P: ReduceDType>(state: &Self::State<P>) -> u32 {
+        unsafe { (*state.1).rank() }
+    }
+
+    fn shape_input<P: ReduceDType>(state: &Self::State<P>, dim: u32) -> u32 {
+        let shape = unsafe { (*state.0).shape(dim) };
+        let rank = unsafe { (*state.0).rank() };
+        let is_last = dim == rank - 1;
+        select(
+            is_last,
+            shape * comptime![values_per_element::<S, B>()],
+            shape,
+        )
+    }
+
+    fn shape_output<P: ReduceDType>(state: &Self::State<P>, dim: u32) -> u32 {
+        unsafe { (*state.1).shape(dim) }
+    }
+
+    fn stride_input<P: ReduceDType>(state: &Self::State<P>, dim: u32) -> u32 {
+        let stride = unsafe { (*state.0).stride(dim) };
+        let rank = unsafe { (*state.0).rank() };
+        let is_last = dim == rank - 1;
+        select(
+            is_last,
+            stride,
+            stride * comptime![values_per_element::<S, B>()],
+        )
+    }
+
+    fn stride_output<P: ReduceDType>(state: &Self::State<P>, dim: u32) -> u32 {
+        unsafe { (*state.1).stride(dim) }
+    }
+
+    fn line_size_input<P: ReduceDType>(state: &Self::State<P>) -> comptime_type!(u32) {
+        unsafe { (*state.0).line_size() }
+    }
+
+    fn line_size_output<P: ReduceDType>(state: &Self::State<P>) -> comptime_type!(u32) {
+        unsafe { (*state.1).line_size() }
+    }
+}
+
+/// Reduce the given `axis` of the unpacked `input` using the instruction `Inst`
+/// and write the result into `output`.
+///
+/// The `input` describes the storage tensor of type `S`, whose last axis packs values of
+/// `B::BITS` bits as described in [`PackedArgs`]. They are unpacked as `P::EI`, so `output` must
+/// have the shape of the unpacked input except for a value of 1 for the given `axis`.
+///
+/// This returns the same errors as [`reduce`](crate::reduce),
+/// [`ReduceError::PackedAxisNotContiguous`] when the storage isn't contiguous along its last axis,
+/// and [`ReduceError::NaiveUnsupported`] for the naive strategy. The `shared_transpose` strategy
+/// isn't supported and is ignored.
+pub fn reduce_packed<
+    R: Runtime,
+    S: Int,
+    B: PackedBits,
+    P: ReducePrecision,
+    Out: Numeric,
+    Inst: ReduceFamily,
+>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    strategy: Option<ReduceStrategy>,
+    inst_config: Inst::Config,
+) -> Result<(), ReduceError> {
+    let rank = input.shape.len();
+    validate_axis(rank, axis)?;
+
+    let last_stride = input.strides[rank - 1];
+    if last_stride != 1 {
+        return Err(ReduceError::PackedAxisNotContiguous {
+            stride: last_stride,
+        });
+    }
+
+    let count = values_per_element::<S, B>() as usize;
+    let mut shape = input.shape.to_vec();
+    shape[rank - 1] *= count;
+    let strides = input
+        .strides
+        .iter()
+        .enumerate()
+        .map(|(dim, stride)| {
+            if dim == rank - 1 {
+                *stride
+            } else {
+                stride * count
+            }
+        })
+        .collect::<Vec<_>>();
+    valid_output_shape(&shape, output.shape, axis)?;
+
+    let strategy = strategy
+        .map(|s| s.validate::<R>(client))
+        .unwrap_or(Ok(ReduceStrategy::new::<R>(client, true)))?;
+    if strategy.naive {
+        return Err(ReduceError::NaiveUnsupported);
+    }
+    let config = ReduceConfig::generate_unlined::<R>(
+        client,
+        &strides,
+        &output,
+        axis,
+        &strategy,
+        R::max_cube_count(),
+    );
+
+    unsafe {
+        reduce_kernel::launch_unchecked::<P::EI, Out, P::EA, Inst, PackedArgs<S, B>, R>(
+            client,
+            config.cube_count,
+            config.cube_dim,
+            input.as_tensor_arg(1),
+            output.as_tensor_arg(1),
+            ScalarArg::new(axis as u32),
+            ReduceParams::new(&config, &strategy),
+            inst_config,
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use cubecl_core::prelude::*;
+
+use crate::instructions::ReduceFamily;
+use crate::precision::ReducePrecision;
+use crate::{ReduceError, ReduceStrategy, reduce, validate_axis};
+
+/// Reduce the given `axis` of the `input` tensor using the instruction `Inst` and write the
+/// result into `output` with the remaining axes ordered as in `permutation`.
+///
+/// The `permutation` lists the axes of `input` other than `axis`, in the order they have in
+/// `output`, so `output` has a rank one lower than `input` and its axis `i` has the size of the
+/// input axis `permutation[i]`. As example, reducing the axis 1 of an input of shape `[2, 3, 4]`
+/// with the permutation `[2, 0]` writes an output of shape `[4, 2]`.
+///
+/// No transpose is launched: the reduction writes through a view of `output` in the order of the
+/// input axes, whose strides are the ones of the permuted `output` axes. The kernel already finds
+/// the coordinates of each reduction from the output strides, and the line size of the output is
+/// picked from them, so a permutation that keeps the output contiguous costs nothing more.
+///
+/// This returns [`ReduceError::InvalidPermutation`] if `permutation` isn't a permutation of the
+/// remaining axes, [`ReduceError::MismatchShape`] if `output` doesn't have the permuted shape,
+/// and otherwise the same errors as [`reduce`].
+pub fn reduce_permuted<R: Runtime, P: ReducePrecision, Out: Numeric, Inst: ReduceFamily>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    permutation: &[usize],
+    strategy: Option<ReduceStrategy>,
+    inst_config: Inst::Config,
+) -> Result<(), ReduceError> {
+    let rank = input.shape.len();
+    validate_axis(rank, axis)?;
+    validate_permutation(rank, axis, permutation)?;
+
+    let expected_shape = permutation
+        .iter()
+        .map(|input_axis| input.shape[*input_axis])
+        .collect::<Vec<_>>();
+    if output.shape != expected_shape {
+        return Err(ReduceError::MismatchShape {
+            expected_shape,
+            output_shape: output.shape.to_vec(),
+        });
+    }
+
+    let mut shape = input.shape.to_vec();
+    shape[axis] = 1;
+    let mut strides = vec![0; rank];
+    for (output_axis, input_axis) in permutation.iter().enumerate() {
+        strides[*input_axis] = output.strides[output_axis];
+    }
+    // Any stride is valid for the reduced axis since it has a single item, so use the one of a
+    // contiguous output, which doesn't prevent writing the output with lines.
+    strides[axis] = if axis + 1 < rank {
+        strides[axis + 1] * shape[axis + 1]
+    } else {
+        1
+    };
+
+    let view = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(output.handle, &strides, &shape, output.elem_size)
+    };
+    reduce::<R, P, Out, Inst>(client, input, view, axis, strategy, inst_config)
+}
+
+// Check that the permutation holds each axis of the input except the reduced one exactly once.
+fn validate_permutation(
+    rank: usize,
+    axis: usize,
+    permutation: &[usize],
+) -> Result<(), ReduceError> {
+    let mut sorted = permutation.to_vec();
+    sorted.sort_unstable();
+    let expected = (0..rank).filter(|a| *a != axis);
+    if sorted.len() + 1 != rank || !sorted.into_iter().eq(expected) {
+        return Err(ReduceError::InvalidPermutation {
+            permutation: permutation.to_vec(),
+            axis,
+            rank,
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use cubecl_core::prelude::*;
+use cubecl_core::{self as cubecl};
+use cubecl_std::tensor::is_contiguous;
+
+use crate::instructions::{ReduceFamily, ReduceInstruction};
+use crate::precision::ReducePrecision;
+use crate::primitives::{ReduceRange, reduce_slice_plane};
+use crate::{BoundChecksInner, LineMode, ReduceError, ReduceStrategy};
+
+/// Reduce the `input` tensor into one value per plane using the instruction `Inst`,
+/// and write the value of each plane into `output`.
+///
+/// The number of planes is the number of elements of `output`. The contiguous `input` is read as
+/// a flat buffer split into as many chunks of consecutive elements, each reduced by a single
+/// plane with the `plane_*` collectives, so there is no combine across planes in the kernel.
+/// Combining the outputs of the planes gives the reduction of the whole input, which makes this a
+/// building block for kernels doing their own combine. The coordinates given to the instruction
+/// are the positions in the flat input, so [`ArgMax`] finds the position of the maximum of each
+/// chunk. Chunks have `input.len().div_ceil(num_planes)` elements, so the last planes can be
+/// left with an empty chunk if there are almost as many planes as elements.
+///
+/// This returns an error if the `client` doesn't support plane instructions with a fixed plane
+/// size, like [`ReduceStrategy::validate`] with `use_planes`, and
+/// [`ReduceError::InputNotContiguous`] if `input` isn't contiguous.
+///
+/// [`ArgMax`]: crate::instructions::ArgMax
+pub fn reduce_plane_local<R: Runtime, P: ReducePrecision, Out: Numeric, Inst: ReduceFamily>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    inst_config: Inst::Config,
+) -> Result<(), ReduceError> {
+    ReduceStrategy {
+        use_planes: true,
+        shared: false,
+        shared_transpose: false,
+        plane_dim: None,
+        naive: false,
+    }
+    .validate::<R>(client)?;
+    if !is_contiguous(input.shape, input.strides) {
+        return Err(ReduceError::InputNotContiguous {
+            shape: input.shape.to_vec(),
+            strides: input.strides.to_vec(),
+        });
+    }
+
+    let num_planes = output.shape.iter().product::<usize>() as u32;
+    if num_planes == 0 {
+        return Ok(());
+    }
+    let input_len = input.shape.iter().product::<usize>() as u32;
+    let chunk_length = input_len.div_ceil(num_planes);
+
+    // The lines must not straddle two chunks.
+    let elem = P::EI::as_type_native_unchecked();
+    let line_size = R::io_optimized_line_sizes_unchecked(&elem)
+        .filter(|line_size| {
+            input_len.is_multiple_of(*line_size as u32)
+                && chunk_length.is_multiple_of(*line_size as u32)
+        })
+        .max()
+        .unwrap_or(1) as u32;
+
+    let plane_dim = client.properties().hardware.plane_size_min;
+    let planes_per_cube = (256 / plane_dim).clamp(1, num_planes);
+    let cube_dim = CubeDim::new_2d(plane_dim, planes_per_cube);
+    let cube_count = CubeCount::new_1d(num_planes.div_ceil(planes_per_cube));
+
+    unsafe {
+        reduce_plane_local_kernel::launch_unchecked::<P::EI, Out, P::EA, Inst, R>(
+            client,
+            cube_count,
+            cube_dim,
+            input.as_tensor_arg(line_size as u8),
+            output.as_tensor_arg(1),
+            ScalarArg::new(chunk_length),
+            line_size,
+            inst_config,
+        );
+    }
+    Ok(())
+}
+
+#[cube(launch_unchecked)]
+fn reduce_plane_local_kernel<In: Numeric, Out: Numeric, Acc: Numeric, R: ReduceFamily>(
+    input: &Tensor<Line<In>>,
+    output: &mut Tensor<Out>,
+    chunk_length: u32,
+    #[comptime] line_size: u32,
+    #[comptime] config: R::Config,
+) {
+    // All the units of a plane exit together, so the plane collectives stay valid.
+    let plane_index = CUBE_POS * CUBE_DIM_Y + UNIT_POS_Y;
+    if plane_index >= output.len() {
+        terminate!();
+    }
+
+    let input_len = input.len() * line_size;
+    let coordinate_start = Min::min(plane_index * chunk_length, input_len);
+    let coordinate_end = Min::min(coordinate_start + chunk_length, input_len);
+    let range = ReduceRange {
+        index_start: coordinate_start / line_size,
+        index_step: 1,
+        coordinate_start,
+        coordinate_end,
+        coordinate_step: CUBE_DIM_X * line_size,
+    };
+
+    let inst = &R::Instruction::<(In, Acc)>::from_config(config);
+    let accumulator = reduce_slice_plane::<(In, Acc), Tensor<Line<In>>, R::Instruction<(In, Acc)>>(
+        input,
+        inst,
+        range,
+        line_size,
+        LineMode::Parallel,
+        BoundChecksInner::Mask,
+    );
+
+    if UNIT_POS_X == 0 {
+        output[plane_index] = R::Instruction::<(In, Acc)>::merge_line::<Out>(
+            inst,
+            accumulator,
+            coordinate_end - coordinate_start,
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use cubecl_core::prelude::*;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise};
+
+use crate::ReduceError;
+use crate::instructions::{ReduceCoordinate, ReduceFamily, ReduceInstruction, reduce_inplace};
+use crate::precision::ReducePrecision;
+
+/// How [`pool_reduce`] handles the windows going past the end of a spatial axis.
+#[derive_cube_comptime]
+pub enum PoolBoundary {
+    /// Replace the missing items by the null input of the instruction, so [`Mean`] still divides
+    /// by the full window size and [`Max`] ignores them.
+    ///
+    /// [`Mean`]: crate::instructions::Mean
+    /// [`Max`]: crate::instructions::Max
+    Pad,
+    /// Shrink the window to the items inside the input, so [`Mean`] only divides by their count.
+    ///
+    /// [`Mean`]: crate::instructions::Mean
+    Clamp,
+    /// Treat the spatial axes as circular, so the windows going past the end of an axis continue
+    /// from its start, such as for periodic or angular data. A window then starts at every
+    /// stride along the axis, including the last items.
+    Wrap,
+}
+
+/// Reduce the windows of the last `window.len()` axes of `input`, moved by `stride` along those
+/// axes, using the instruction `Inst` and write the result into `output`, such as a max pooling
+/// with [`Max`] or an average pooling with [`Mean`].
+///
+/// The windows overlap when a stride is smaller than the window size along the same axis.
+/// Along each spatial axis of size `n`, there are `(n - window).div_ceil(stride) + 1` windows,
+/// or a single one if `n <= window`, so the last window can go past the end of the axis and is
+/// then handled as chosen by `boundary`. With [`PoolBoundary::Wrap`], there are instead
+/// `n.div_ceil(stride)` windows, each going past the end continuing from the start of the axis. The other axes, such as the batch and channel axes, are
+/// kept as is. Coordinates given to the instruction are the positions in the flattened window,
+/// so [`ArgMax`] finds the position of the maximum in its window.
+///
+/// The input is read one item at a time. This returns [`ReduceError::InvalidPoolWindow`] if the
+/// window sizes and strides aren't positive or don't match the spatial axes, and
+/// [`ReduceError::MismatchShape`] if `output` doesn't have the pooled shape.
+///
+/// [`Max`]: crate::instructions::Max
+/// [`Mean`]: crate::instructions::Mean
+/// [`ArgMax`]: crate::instructions::ArgMax
+#[allow(clippy::too_many_arguments)]
+pub fn pool_reduce<R: Runtime, P: ReducePrecision, Out: Numeric, Inst: ReduceFamily>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    window: &[usize],
+    stride: &[usize],
+    boundary: PoolBoundary,
+    inst_config: Inst::Config,
+) -> Result<(), ReduceError> {
+    let rank = input.shape.len();
+    if window.len() != stride.len()
+        || window.len() > rank
+        || window.iter().chain(stride).any(|size| *size == 0)
+    {
+        return Err(ReduceError::InvalidPoolWindow {
+            window: window.to_vec(),
+            stride: stride.to_vec(),
+            rank,
+        });
+    }
+
+    // The window and stride of the non-spatial axes are 1, so they are kept as is.
+    let num_kept = rank - window.len();
+    let window = [vec![1; num_kept], window.to_vec()].concat();
+    let stride = [vec![1; num_kept], stride.to_vec()].concat();
+
+    let expected_shape = input
+        .shape
+        .iter()
+        .zip(window.iter().zip(&stride))
+        .map(|(shape, (window, stride))| {
+            if boundary == PoolBoundary::Wrap {
+                shape.div_ceil(*stride)
+            } else if shape > window {
+                (shape - window).div_ceil(*stride) + 1
+            } else {
+                1
+            }
+        })
+        .collect::<Vec<_>>();
+    if output.shape != expected_shape {
+        return Err(ReduceError::MismatchShape {
+            expected_shape,
+            output_shape: output.shape.to_vec(),
+        });
+    }
+
+    let window = window.iter().map(|size| *size as u32).collect::<Vec<_>>();
+    let stride = stride.iter().map(|size| *size as u32).collect::<Vec<_>>();
+    let window_handle = client.create(u32::as_bytes(&window));
+    let stride_handle = client.create(u32::as_bytes(&stride));
+
+    let num_elems = output.shape.iter().product::<usize>();
+    let cube_dim = CubeDim::default();
+    let cube_count = calculate_cube_count_elemwise(num_elems, cube_dim);
+
+    unsafe {
+        pool_reduce_kernel::launch_unchecked::<P::EI, Out, P::EA, Inst, R>(
+            client,
+            cube_count,
+            cube_dim,
+            input.as_tensor_arg(1),
+            output.as_tensor_arg(1),
+            ArrayArg::from_raw_parts::<u32>(&window_handle, rank, 1),
+            ArrayArg::from_raw_parts::<u32>(&stride_handle, rank, 1),
+            boundary,
+            inst_config,
+        );
+    }
+    Ok(())
+}
+
+#[cube(launch_unchecked)]
+fn pool_reduce_kernel<In: Numeric, Out: Numeric, Acc: Numeric, R: ReduceFamily>(
+    input: &Tensor<Line<In>>,
+    output: &mut Tensor<Out>,
+    window: &Array<u32>,
+    stride: &Array<u32>,
+    #[comptime] boundary: PoolBoundary,
+    #[comptime] config: R::Config,
+) {
+    if ABSOLUTE_POS >= output.len() {
+        terminate!();
+    }
+
+    let inst = &R::Instruction::<(In, Acc)>::from_config(config);
+    let requirements = R::Instruction::<(In, Acc)>::requirements(inst);
+    let rank = input.rank();
+
+    let mut window_len = 1u32;
+    for axis in 0..rank {
+        window_len *= window[axis];
+    }
+
+    let mut accumulator = R::Instruction::<(In, Acc)>::null_accumulator(inst, 1u32);
+    let mut count = 0u32;
+    for window_index in 0..window_len {
+        // Find the input item at `window_index` in the window, going from the last axis.
+        let mut offset = 0u32;
+        let mut inside = true;
+        let mut remainder = window_index;
+        for i in 0..rank {
+            let axis = rank - 1 - i;
+            let window_coordinate = remainder % window[axis];
+            remainder /= window[axis];
+
+            let output_coordinate = (ABSOLUTE_POS / output.stride(axis)) % output.shape(axis);
+            let mut coordinate = output_coordinate * stride[axis] + window_coordinate;
+            if comptime![boundary == PoolBoundary::Wrap] {
+                coordinate %= input.shape(axis);
+            }
+            inside = inside && coordinate < input.shape(axis);
+            offset += coordinate * input.stride(axis);
+        }
+
+        let coordinate = if comptime![requirements.coordinates] {
+            ReduceCoordinate::new_Required(Line::new(select(inside, window_index, u32::MAX)))
+        } else {
+            ReduceCoordinate::new_NotRequired()
+        };
+        let offset = offset * u32::cast_from(inside);
+        let item = select(
+            inside,
+            input[offset],
+            R::Instruction::<(In, Acc)>::null_input(inst, 1u32),
+        );
+
+        match comptime!(boundary) {
+            PoolBoundary::Pad | PoolBoundary::Wrap => {
+                reduce_inplace::<(In, Acc), R::Instruction<(In, Acc)>>(
+                    inst,
+                    &mut accumulator,
+                    item,
+                    coordinate,
+                    false,
+                );
+                count += 1;
+            }
+            PoolBoundary::Clamp => {
+                if inside {
+                    reduce_inplace::<(In, Acc), R::Instruction<(In, Acc)>>(
+                        inst,
+                        &mut accumulator,
+                        item,
+                        coordinate,
+                        false,
+                    );
+                    count += 1;
+                }
+            }
+        }
+    }
+
+    output[ABSOLUTE_POS] = R::Instruction::<(In, Acc)>::merge_line::<Out>(inst, accumulator, count);
+}
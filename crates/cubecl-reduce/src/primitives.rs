@@ -192,11 +192,13 @@ pub fn reduce_slice_plane<P: ReducePrecision, I: List<Line<P::EI>>, R: ReduceIns
 
         let requirements = R::requirements(inst);
         let coordinates = if comptime![requirements.coordinates] {
-            ReduceCoordinate::new_Required(fill_coordinate_line(
-                unit_coordinate,
-                line_size,
-                line_mode,
-            ))
+            let coordinate = fill_coordinate_line(unit_coordinate, line_size, line_mode);
+            let coordinate = select(
+                unit_coordinate < range.coordinate_end,
+                coordinate,
+                Line::empty(line_size).fill(u32::MAX),
+            );
+            ReduceCoordinate::new_Required(coordinate)
         } else {
             ReduceCoordinate::new_NotRequired()
         };
@@ -398,3 +400,47 @@ pub fn reduce_tree<P: ReducePrecision, Inst: ReduceInstruction<P>>(
     sync_cube();
     Inst::SharedAccumulator::read(accumulator, 0)
 }
+
+/// How the axes of the second tensor of [`slice_offsets`] match the axes of the first one.
+#[derive_cube_comptime]
+pub enum SliceAxes {
+    /// The same axes.
+    Same,
+    /// The same axes after a leading one, such as the partials of each chunk of a slice.
+    Leading,
+    /// The same axes without the sliced one, such as a value per slice.
+    WithoutAxis,
+}
+
+/// The offsets in `lhs` and `rhs` of the start of the slice at `slice_index` along `axis`.
+///
+/// The slices are numbered over the shape of `lhs` without `axis`, going from the last axis,
+/// and `rhs` has the axes of `lhs` described by `rhs_axes`.
+#[cube]
+pub fn slice_offsets<L: CubeType, R: CubeType>(
+    lhs: &Tensor<L>,
+    rhs: &Tensor<R>,
+    slice_index: u32,
+    axis: u32,
+    #[comptime] rhs_axes: SliceAxes,
+) -> (u32, u32) {
+    let rank = lhs.rank();
+    let mut lhs_offset = 0u32;
+    let mut rhs_offset = 0u32;
+    let mut remainder = slice_index;
+    for i in 0..rank {
+        let current = rank - 1 - i;
+        if current != axis {
+            let coordinate = remainder % lhs.shape(current);
+            remainder /= lhs.shape(current);
+            lhs_offset += coordinate * lhs.stride(current);
+            let rhs_axis = match comptime![rhs_axes] {
+                SliceAxes::Same => current,
+                SliceAxes::Leading => current + 1,
+                SliceAxes::WithoutAxis => current - select(current > axis, 1u32, 0u32),
+            };
+            rhs_offset += coordinate * rhs.stride(rhs_axis);
+        }
+    }
+    (lhs_offset, rhs_offset)
+}
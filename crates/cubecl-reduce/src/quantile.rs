@@ -0,0 +1,140 @@
+use cubecl_core::prelude::*;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise};
+
+use crate::primitives::{SliceAxes, slice_offsets};
+use crate::update::contiguous_strides;
+use crate::{Histogram, HistogramOutliers, ReduceError, reduce_histogram, validate_axis};
+
+/// Estimate the `quantiles` of each slice along `axis` of `input` from a histogram of `bins`
+/// bins over the range `(min, max)`, and write them into `output`.
+///
+/// The items are first counted by [`reduce_histogram`], clamping those outside the range into
+/// the first and last bins, then each quantile is interpolated linearly within the bin holding
+/// it. The exact quantile `q` is the smallest item with at least a fraction `q` of the items
+/// not greater than it, so `0` is the minimum and `1` is the maximum. When all the items are in
+/// the range, each estimate is within one bin width `(max - min) / bins` of the exact quantile,
+/// so the error is tuned by the number of bins at the cost of a histogram of that size per
+/// slice. NaN items are ignored, and a slice without any other item gets NaN estimates.
+///
+/// The shape of `output` must be the same as input except with the number of quantiles for
+/// the given `axis`. This returns [`ReduceError::InvalidQuantile`] if a quantile isn't between
+/// `0` and `1`, and otherwise the same errors as [`reduce_histogram`].
+pub fn reduce_quantiles<R: Runtime, In: Numeric, Out: Float>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    quantiles: &[f32],
+    bins: u32,
+    range: (f32, f32),
+) -> Result<(), ReduceError> {
+    validate_axis(input.shape.len(), axis)?;
+    if let Some(index) = quantiles.iter().position(|q| !(0.0..=1.0).contains(q)) {
+        return Err(ReduceError::InvalidQuantile { index });
+    }
+    let mut expected_shape = input.shape.to_vec();
+    expected_shape[axis] = quantiles.len();
+    if output.shape != expected_shape {
+        return Err(ReduceError::MismatchShape {
+            expected_shape,
+            output_shape: output.shape.to_vec(),
+        });
+    }
+
+    let mut counts_shape = input.shape.to_vec();
+    counts_shape[axis] = bins as usize;
+    let counts_strides = contiguous_strides(&counts_shape);
+    let counts_handle = client.empty(counts_shape.iter().product::<usize>() * size_of::<f32>());
+    let counts = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(
+            &counts_handle,
+            &counts_strides,
+            &counts_shape,
+            size_of::<f32>(),
+        )
+    };
+    let histogram = Histogram {
+        bins,
+        outliers: HistogramOutliers::Clamp,
+        density: false,
+    };
+    reduce_histogram::<R, In, f32>(client, input, counts, axis, histogram, range)?;
+    if quantiles.is_empty() {
+        return Ok(());
+    }
+
+    let quantiles_handle = client.create(f32::as_bytes(quantiles));
+    let quantiles_shape = [quantiles.len()];
+    let quantiles = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(
+            &quantiles_handle,
+            &[1],
+            &quantiles_shape,
+            size_of::<f32>(),
+        )
+    };
+
+    let (min, max) = range;
+    let bin_width = (max - min) / bins as f32;
+    let num_slices = input.shape.iter().product::<usize>() / input.shape[axis];
+    let cube_dim = CubeDim::default();
+    let cube_count = calculate_cube_count_elemwise(num_slices, cube_dim);
+
+    unsafe {
+        quantile_kernel::launch_unchecked::<Out, R>(
+            client,
+            cube_count,
+            cube_dim,
+            counts.as_tensor_arg(1),
+            quantiles.as_tensor_arg(1),
+            output.as_tensor_arg(1),
+            ScalarArg::new(axis as u32),
+            ScalarArg::new(min),
+            ScalarArg::new(bin_width),
+            bins,
+        );
+    }
+    Ok(())
+}
+
+#[cube(launch_unchecked)]
+fn quantile_kernel<Out: Float>(
+    counts: &Tensor<f32>,
+    quantiles: &Tensor<f32>,
+    output: &mut Tensor<Out>,
+    axis: u32,
+    min: f32,
+    bin_width: f32,
+    #[comptime] bins: u32,
+) {
+    let num_quantiles = quantiles.shape(0);
+    if ABSOLUTE_POS * num_quantiles >= output.len() {
+        terminate!();
+    }
+
+    let (counts_offset, output_offset) =
+        slice_offsets(counts, output, ABSOLUTE_POS, axis, SliceAxes::Same);
+
+    let counts_stride = counts.stride(axis);
+    let mut total = f32::new(0.0);
+    for bin in 0..bins {
+        total += counts[counts_offset + bin * counts_stride];
+    }
+
+    for j in 0..num_quantiles {
+        // The rank of the quantile among the items, the first one for `0`.
+        let target = Max::max(quantiles[j * quantiles.stride(0)] * total, f32::new(1.0));
+        let mut estimate = f32::new(f32::NAN);
+        let mut below = f32::new(0.0);
+        let mut found = false;
+        for bin in 0..bins {
+            let count = counts[counts_offset + bin * counts_stride];
+            if !found && count > 0.0 && below + count >= target {
+                estimate = min + (f32::cast_from(bin) + (target - below) / count) * bin_width;
+                found = true;
+            }
+            below += count;
+        }
+        output[output_offset + j * output.stride(axis)] = Out::cast_from(estimate);
+    }
+}
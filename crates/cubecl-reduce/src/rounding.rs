@@ -0,0 +1,186 @@
+use cubecl_core::ir::{ElemType, FloatKind};
+use cubecl_core::prelude::*;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise};
+
+use crate::instructions::ReduceFamily;
+use crate::precision::ReducePrecision;
+use crate::update::contiguous_strides;
+use crate::{ReduceError, ReduceStrategy, reduce, valid_output_shape, validate_axis};
+
+/// How [`reduce_with_rounding`] rounds the reduced values that the output type can't represent.
+///
+/// No backend exposes the rounding mode of its conversions, so only
+/// [`NearestEven`](Self::NearestEven) uses the cast of the backend:
+///
+/// - CUDA, HIP and the CPU runtime round `f32` to `f16` and `bf16` to the nearest even value.
+/// - WebGPU, Vulkan and Metal leave the rounding of narrowing conversions to the driver, which
+///   usually rounds to the nearest even value but isn't required to.
+///
+/// The other modes are emulated on the bits of the `f32` value, so they give the same outputs on
+/// every backend.
+#[derive_cube_comptime]
+#[derive(Default)]
+pub enum ReduceRounding {
+    /// Round to the nearest value, and to the one with an even mantissa when halfway, which is
+    /// what [`reduce`] does.
+    #[default]
+    NearestEven,
+    /// Round to the value of smaller magnitude, never overflowing to an infinity.
+    TowardZero,
+    /// Round to the greater value.
+    Up,
+    /// Round to the smaller value.
+    Down,
+}
+
+/// The layout of a float narrower than `f32`, relative to the bits of an `f32`.
+#[derive_cube_comptime]
+struct NarrowFloat {
+    /// The number of explicit mantissa bits.
+    mantissa_bits: u32,
+    /// The biased `f32` exponent of the smallest normal value.
+    min_exponent: u32,
+    /// The `f32` bits of the smallest positive subnormal value.
+    smallest_bits: u32,
+    /// The `f32` bits of the largest finite value.
+    max_bits: u32,
+}
+
+impl NarrowFloat {
+    fn of(elem: ElemType) -> Option<Self> {
+        match elem {
+            ElemType::Float(FloatKind::F16) => Some(Self {
+                mantissa_bits: 10,
+                min_exponent: 113,
+                smallest_bits: 103 << 23,
+                max_bits: 0x477F_E000,
+            }),
+            ElemType::Float(FloatKind::BF16) => Some(Self {
+                mantissa_bits: 7,
+                min_exponent: 1,
+                smallest_bits: 1 << 16,
+                max_bits: 0x7F7F_0000,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Same as [`reduce`], but with the reduced values cast to the output type according to
+/// `rounding`.
+///
+/// With [`ReduceRounding::NearestEven`] or an `f32` output, this is exactly [`reduce`].
+/// Otherwise the reduction is written into an `f32` buffer, whose values are then rounded to
+/// `Out` by a second kernel. The accumulator is first cast to `f32` by the backend, so the mode
+/// only applies to accumulators of at most 32 bits. NaN and infinities are kept as is.
+///
+/// This returns [`ReduceError::UnsupportedOutputElem`] if the mode needs emulating and `Out`
+/// isn't `f16` or `bf16`, and otherwise the same errors as [`reduce`].
+pub fn reduce_with_rounding<R: Runtime, P: ReducePrecision, Out: Float, Inst: ReduceFamily>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    strategy: Option<ReduceStrategy>,
+    inst_config: Inst::Config,
+    rounding: ReduceRounding,
+) -> Result<(), ReduceError> {
+    let output_elem = Out::as_type_native_unchecked().elem_type();
+    if rounding == ReduceRounding::NearestEven || output_elem == ElemType::Float(FloatKind::F32) {
+        return reduce::<R, P, Out, Inst>(client, input, output, axis, strategy, inst_config);
+    }
+    let Some(format) = NarrowFloat::of(output_elem) else {
+        return Err(ReduceError::UnsupportedOutputElem(output_elem));
+    };
+    validate_axis(input.shape.len(), axis)?;
+    valid_output_shape(input.shape, output.shape, axis)?;
+
+    let num_elems = output.shape.iter().product::<usize>();
+    let exact_strides = contiguous_strides(output.shape);
+    let exact_handle = client.empty(num_elems * size_of::<f32>());
+    let exact = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(
+            &exact_handle,
+            &exact_strides,
+            output.shape,
+            size_of::<f32>(),
+        )
+    };
+    reduce::<R, P, f32, Inst>(client, input, exact, axis, strategy, inst_config)?;
+
+    let cube_dim = CubeDim::default();
+    let cube_count = calculate_cube_count_elemwise(num_elems, cube_dim);
+    unsafe {
+        round_kernel::launch_unchecked::<Out, R>(
+            client,
+            cube_count,
+            cube_dim,
+            exact.as_tensor_arg(1),
+            output.as_tensor_arg(1),
+            rounding,
+            format,
+        );
+    }
+    Ok(())
+}
+
+#[cube(launch_unchecked)]
+fn round_kernel<Out: Float>(
+    input: &Tensor<f32>,
+    output: &mut Tensor<Out>,
+    #[comptime] rounding: ReduceRounding,
+    #[comptime] format: NarrowFloat,
+) {
+    if ABSOLUTE_POS >= input.len() {
+        terminate!();
+    }
+
+    // The input is contiguous, so its strides give the coordinates of the position.
+    let mut offset = 0u32;
+    for i in 0..output.rank() {
+        let coordinate = (ABSOLUTE_POS / input.stride(i)) % input.shape(i);
+        offset += coordinate * output.stride(i);
+    }
+
+    let bits = u32::reinterpret(input[ABSOLUTE_POS]);
+    let sign = bits & 0x8000_0000u32;
+    let magnitude = bits & 0x7FFF_FFFFu32;
+    let negative = sign != 0;
+    let away = match comptime![rounding] {
+        ReduceRounding::Up => !negative,
+        ReduceRounding::Down => negative,
+        _ => false,
+    };
+
+    // The number of low bits dropped from the magnitude, more when the result is subnormal.
+    // Subnormal `f32` values have the same spacing as those with the smallest exponent.
+    let min_exponent = u32::new(comptime![format.min_exponent as i64]);
+    let exponent = Min::min(Max::max(magnitude >> 23, 1u32), min_exponent);
+    let drop = u32::new(comptime![(23 - format.mantissa_bits) as i64]) + min_exponent - exponent;
+
+    let mut rounded = 0u32;
+    if drop > 23 {
+        // Smaller than the smallest subnormal output.
+        if away && magnitude != 0 {
+            rounded = u32::new(comptime![format.smallest_bits as i64]);
+        }
+    } else {
+        let step = 1u32 << drop;
+        let remainder = magnitude & (step - 1);
+        rounded = magnitude - remainder;
+        if away && remainder != 0 {
+            rounded += step;
+        }
+    }
+
+    let max_bits = u32::new(comptime![format.max_bits as i64]);
+    if rounded > max_bits {
+        rounded = select(away, 0x7F80_0000u32, max_bits);
+    }
+    // Infinities and NaN are kept as is.
+    let finite = magnitude < 0x7F80_0000u32;
+    let result = select(finite, sign | rounded, bits);
+
+    // The result is representable by `Out`, so the cast of the backend is exact.
+    output[offset] = Out::cast_from(f32::reinterpret(result));
+}
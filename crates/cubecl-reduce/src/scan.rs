@@ -0,0 +1,149 @@
+use cubecl_core::prelude::*;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise};
+
+use crate::instructions::{ReduceCoordinate, ReduceFamily, ReduceInstruction, reduce_inplace};
+use crate::precision::ReducePrecision;
+use crate::primitives::{SliceAxes, slice_offsets};
+use crate::{ReduceError, validate_axis};
+
+/// Whether each position of a [segmented_scan] includes its own item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ScanMode {
+    /// Each position gets the reduction of the items of its segment up to and including itself.
+    Inclusive,
+    /// Each position gets the reduction of the items of its segment before itself.
+    Exclusive,
+}
+
+/// Compute the running reduction of `input` along `axis`, restarting at each segment boundary,
+/// such as a cumulative sum with [`Sum`] or a running maximum with [`Max`], into `output`.
+///
+/// `segments` is a vector of `u32` segment ids, one per position along `axis` of `input`. A new
+/// segment starts at each position whose id differs from the previous one, where the running
+/// value is reset, so the items of different segments are never reduced together. Segments are
+/// contiguous runs of the same id: an id that comes back after a different one starts a new
+/// segment.
+///
+/// With [`ScanMode::Exclusive`], the first position of each segment gets the reduction of no
+/// items, the null accumulator of the instruction, such as `0` for [`Sum`] and the lowest
+/// value for [`Max`]. Each position is written by the instruction as the reduction of the
+/// items so far, the same as [`reduce`](crate::reduce) over them, so instructions such as
+/// [`Mean`](crate::instructions::Mean) give the running mean of the segment.
+///
+/// Each slice along `axis` is scanned by a single unit in order, so the result doesn't depend on
+/// the scheduling of the units, and the input is read one item at a time. The shape of `output`
+/// must be the same as `input`. This returns [`ReduceError::InvalidSegmentIds`] if `segments`
+/// isn't a vector with one id per position along `axis`, [`ReduceError::MismatchShape`] for an
+/// invalid output shape, and [`ReduceError::InvalidAxis`] for an invalid axis.
+///
+/// [`Sum`]: crate::instructions::Sum
+/// [`Max`]: crate::instructions::Max
+pub fn segmented_scan<R: Runtime, P: ReducePrecision, Out: Numeric, Inst: ReduceFamily>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    segments: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    mode: ScanMode,
+    inst_config: Inst::Config,
+) -> Result<(), ReduceError> {
+    validate_axis(input.shape.len(), axis)?;
+    if output.shape != input.shape {
+        return Err(ReduceError::MismatchShape {
+            expected_shape: input.shape.to_vec(),
+            output_shape: output.shape.to_vec(),
+        });
+    }
+    if segments.shape != [input.shape[axis]] {
+        return Err(ReduceError::InvalidSegmentIds {
+            shape: segments.shape.to_vec(),
+            length: input.shape[axis],
+        });
+    }
+    if input.shape[axis] == 0 {
+        return Ok(());
+    }
+
+    let num_slices = input.shape.iter().product::<usize>() / input.shape[axis];
+    let cube_dim = CubeDim::default();
+    let cube_count = calculate_cube_count_elemwise(num_slices, cube_dim);
+
+    unsafe {
+        segmented_scan_kernel::launch_unchecked::<P::EI, Out, P::EA, Inst, R>(
+            client,
+            cube_count,
+            cube_dim,
+            input.as_tensor_arg(1),
+            segments.as_tensor_arg(1),
+            output.as_tensor_arg(1),
+            ScalarArg::new(axis as u32),
+            mode == ScanMode::Exclusive,
+            inst_config,
+        );
+    }
+    Ok(())
+}
+
+#[cube(launch_unchecked)]
+fn segmented_scan_kernel<In: Numeric, Out: Numeric, Acc: Numeric, R: ReduceFamily>(
+    input: &Tensor<Line<In>>,
+    segments: &Tensor<u32>,
+    output: &mut Tensor<Out>,
+    axis: u32,
+    #[comptime] exclusive: bool,
+    #[comptime] config: R::Config,
+) {
+    let length = output.shape(axis);
+    if ABSOLUTE_POS >= output.len() / length {
+        terminate!();
+    }
+
+    let inst = &R::Instruction::<(In, Acc)>::from_config(config);
+    let requirements = R::Instruction::<(In, Acc)>::requirements(inst);
+
+    // The start of the slice of this unit, in the output and the input.
+    let (output_offset, input_offset) =
+        slice_offsets(output, input, ABSOLUTE_POS, axis, SliceAxes::Same);
+
+    let input_stride = input.stride(axis);
+    let output_stride = output.stride(axis);
+    let segment_stride = segments.stride(0);
+
+    let mut accumulator = R::Instruction::<(In, Acc)>::null_accumulator(inst, 1u32);
+    let mut count = 0u32;
+    let mut segment = segments[0];
+    for k in 0..length {
+        let id = segments[k * segment_stride];
+        if id != segment {
+            let reset = R::Instruction::<(In, Acc)>::null_accumulator(inst, 1u32);
+            R::Instruction::<(In, Acc)>::assign_accumulator(inst, &mut accumulator, &reset);
+            count = 0;
+            segment = id;
+        }
+
+        let position = output_offset + k * output_stride;
+        if exclusive {
+            output[position] =
+                R::Instruction::<(In, Acc)>::merge_line::<Out>(inst, accumulator, count);
+        }
+
+        let coordinate = if comptime![requirements.coordinates] {
+            ReduceCoordinate::new_Required(Line::new(k))
+        } else {
+            ReduceCoordinate::new_NotRequired()
+        };
+        reduce_inplace::<(In, Acc), R::Instruction<(In, Acc)>>(
+            inst,
+            &mut accumulator,
+            input[input_offset + k * input_stride],
+            coordinate,
+            false,
+        );
+        count += 1;
+
+        if !exclusive {
+            output[position] =
+                R::Instruction::<(In, Acc)>::merge_line::<Out>(inst, accumulator, count);
+        }
+    }
+}
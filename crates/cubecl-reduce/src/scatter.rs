@@ -0,0 +1,339 @@
+use cubecl_core::ir::{ElemType, FloatKind};
+use cubecl_core::prelude::*;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise};
+use cubecl_runtime::TypeUsage;
+
+use crate::instructions::{ReduceCoordinate, ReduceFamily, ReduceInstruction, reduce_inplace};
+use crate::precision::ReducePrecision;
+use crate::primitives::{SliceAxes, slice_offsets};
+use crate::{ReduceError, validate_axis};
+
+/// How [`scatter_reduce_atomic`] combines each item into its destination.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ScatterAtomic {
+    /// Add the item to its destination with an atomic addition, like `index_add`.
+    Add,
+    /// Keep the greatest of the item and its destination, like `scatter_max`, with a
+    /// compare-and-swap loop on the bits of an `f32` output.
+    Max,
+}
+
+/// Reduce the items of `input` along `axis` sharing the same destination in `indices`, and
+/// combine each result into `output` at that destination along `axis`, such as `index_add` with
+/// [`Sum`] or `scatter_max` with [`Max`]. This is the inverse of
+/// [`gather_reduce`](crate::gather_reduce).
+///
+/// `indices` is a vector of `u32` destinations, one per position along `axis` of `input`.
+/// The items of a slice scattered to the same destination collide: they are reduced together by
+/// the instruction, in the order of their positions, and the result is merged with the value
+/// already in `output` using [`ReduceInstruction::combine_outputs`], so [`Sum`] adds them to
+/// `output` and [`Max`] keeps the greatest of them and `output`. Destinations receiving no item
+/// keep their value, and items with a destination past the end of the axis of `output` are
+/// ignored.
+///
+/// Each item of `output` is computed by a single unit scanning all the destinations, so the
+/// result doesn't depend on the scheduling of the units and no atomics are needed, at the cost
+/// of reading `indices` once per output item. See [`scatter_reduce_atomic`] to combine each item
+/// into its destination with an atomic instead, reading `indices` once. Only instructions whose
+/// [`combine_outputs`](ReduceInstruction::combine_outputs) doesn't depend on the counts, like
+/// [`Sum`], [`Max`] and [`Min`], give meaningful results, since the number of items already
+/// combined into `output` isn't known.
+///
+/// The shape of `output` must be the same as `input` except along `axis`, where it is the number
+/// of destinations. The input is read one item at a time. This returns
+/// [`ReduceError::InvalidScatterIndices`] if `indices` isn't a vector with one destination per
+/// position along `axis`, [`ReduceError::MismatchShape`] for an invalid output shape,
+/// [`ReduceError::InvalidAxis`] for an invalid axis, and [`ReduceError::CombineUnsupported`]
+/// when the instruction can't combine outputs.
+///
+/// [`Sum`]: crate::instructions::Sum
+/// [`Max`]: crate::instructions::Max
+/// [`Min`]: crate::instructions::Min
+pub fn scatter_reduce<R: Runtime, P: ReducePrecision, Out: Numeric, Inst: ReduceFamily>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    indices: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    inst_config: Inst::Config,
+) -> Result<(), ReduceError> {
+    if !Inst::supports_combine(inst_config) {
+        return Err(ReduceError::CombineUnsupported);
+    }
+    validate_scatter(&input, &indices, &output, axis)?;
+
+    let num_elems = output.shape.iter().product::<usize>();
+    let cube_dim = CubeDim::default();
+    let cube_count = calculate_cube_count_elemwise(num_elems, cube_dim);
+
+    unsafe {
+        scatter_reduce_kernel::launch_unchecked::<P::EI, Out, P::EA, Inst, R>(
+            client,
+            cube_count,
+            cube_dim,
+            input.as_tensor_arg(1),
+            indices.as_tensor_arg(1),
+            output.as_tensor_arg(1),
+            ScalarArg::new(axis as u32),
+            inst_config,
+        );
+    }
+    Ok(())
+}
+
+/// Combine each item of `input` into `output` at the destination of its position along `axis`
+/// in `indices`, with an atomic addition for [`ScatterAtomic::Add`] or a compare-and-swap loop
+/// keeping the greatest value for [`ScatterAtomic::Max`].
+///
+/// This has the same requirements and collision semantics as [`scatter_reduce`] with
+/// [`Sum`](crate::instructions::Sum) or [`Max`](crate::instructions::Max), but each unit
+/// combines a single item, so `indices` is read once per item of `input` rather than once per
+/// item of `output`. The order in which the colliding items are combined depends on the
+/// scheduling of the units, so the rounding of a sum can change from one launch to the next.
+/// The max ignores the NaN items, and keeps a NaN already in `output`.
+///
+/// This returns the same errors as [`scatter_reduce`] for invalid shapes,
+/// [`ReduceError::MissingAtomicAdd`] if the client can't add `Out` atomically,
+/// [`ReduceError::UnsupportedOutputElem`] for a max into another type than `f32`, and
+/// [`ReduceError::MissingAtomicCompareAndSwap`] if the client can't swap `u32` atomically.
+pub fn scatter_reduce_atomic<R: Runtime, In: Numeric, Out: Numeric>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    indices: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    atomic: ScatterAtomic,
+) -> Result<(), ReduceError> {
+    validate_scatter(&input, &indices, &output, axis)?;
+    match atomic {
+        ScatterAtomic::Add => {
+            let atomic_elem = Atomic::<Out>::as_type_native_unchecked();
+            if !client
+                .properties()
+                .type_usage(atomic_elem)
+                .contains(TypeUsage::AtomicAdd)
+            {
+                return Err(ReduceError::MissingAtomicAdd(
+                    Out::as_type_native_unchecked(),
+                ));
+            }
+        }
+        ScatterAtomic::Max => {
+            let elem = Out::as_type_native_unchecked().elem_type();
+            if elem != ElemType::Float(FloatKind::F32) {
+                return Err(ReduceError::UnsupportedOutputElem(elem));
+            }
+            let atomic_elem = Atomic::<u32>::as_type_native_unchecked();
+            if !client
+                .properties()
+                .type_usage(atomic_elem)
+                .contains(TypeUsage::AtomicLoadStore)
+            {
+                return Err(ReduceError::MissingAtomicCompareAndSwap(
+                    u32::as_type_native_unchecked(),
+                ));
+            }
+        }
+    }
+
+    let num_items = input.shape.iter().product::<usize>();
+    if num_items == 0 {
+        return Ok(());
+    }
+    let cube_dim = CubeDim::default();
+    let cube_count = calculate_cube_count_elemwise(num_items, cube_dim);
+
+    unsafe {
+        match atomic {
+            ScatterAtomic::Add => scatter_add_atomic_kernel::launch_unchecked::<In, Out, R>(
+                client,
+                cube_count,
+                cube_dim,
+                input.as_tensor_arg(1),
+                indices.as_tensor_arg(1),
+                output.as_tensor_arg(1),
+                ScalarArg::new(num_items as u32),
+                ScalarArg::new(axis as u32),
+            ),
+            ScatterAtomic::Max => scatter_max_atomic_kernel::launch_unchecked::<In, R>(
+                client,
+                cube_count,
+                cube_dim,
+                input.as_tensor_arg(1),
+                indices.as_tensor_arg(1),
+                output.as_tensor_arg(1),
+                ScalarArg::new(num_items as u32),
+                ScalarArg::new(axis as u32),
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// Check the axis, that `output` has the shape of `input` except along `axis`, and that
+/// `indices` has one destination per position along `axis`.
+fn validate_scatter<R: Runtime>(
+    input: &TensorHandleRef<R>,
+    indices: &TensorHandleRef<R>,
+    output: &TensorHandleRef<R>,
+    axis: usize,
+) -> Result<(), ReduceError> {
+    validate_axis(input.shape.len(), axis)?;
+    let mut expected_shape = input.shape.to_vec();
+    expected_shape[axis] = output.shape.get(axis).copied().unwrap_or(1);
+    if output.shape != expected_shape {
+        return Err(ReduceError::MismatchShape {
+            expected_shape,
+            output_shape: output.shape.to_vec(),
+        });
+    }
+    if indices.shape != [input.shape[axis]] {
+        return Err(ReduceError::InvalidScatterIndices {
+            shape: indices.shape.to_vec(),
+            length: input.shape[axis],
+        });
+    }
+    Ok(())
+}
+
+#[cube(launch_unchecked)]
+fn scatter_reduce_kernel<In: Numeric, Out: Numeric, Acc: Numeric, R: ReduceFamily>(
+    input: &Tensor<Line<In>>,
+    indices: &Tensor<u32>,
+    output: &mut Tensor<Out>,
+    axis: u32,
+    #[comptime] config: R::Config,
+) {
+    if ABSOLUTE_POS >= output.len() {
+        terminate!();
+    }
+
+    let inst = &R::Instruction::<(In, Acc)>::from_config(config);
+    let requirements = R::Instruction::<(In, Acc)>::requirements(inst);
+
+    // The destination along `axis` of this output item, and the start of the input slice.
+    let destination = ABSOLUTE_POS % output.shape(axis);
+    let (output_offset, input_offset) = slice_offsets(
+        output,
+        input,
+        ABSOLUTE_POS / output.shape(axis),
+        axis,
+        SliceAxes::Same,
+    );
+    let output_offset = output_offset + destination * output.stride(axis);
+
+    let axis_stride = input.stride(axis);
+    let num_indices = indices.shape(0);
+
+    let mut accumulator = R::Instruction::<(In, Acc)>::null_accumulator(inst, 1u32);
+    let mut count = 0u32;
+    for k in 0..num_indices {
+        if indices[k * indices.stride(0)] == destination {
+            let coordinate = if comptime![requirements.coordinates] {
+                ReduceCoordinate::new_Required(Line::new(k))
+            } else {
+                ReduceCoordinate::new_NotRequired()
+            };
+            reduce_inplace::<(In, Acc), R::Instruction<(In, Acc)>>(
+                inst,
+                &mut accumulator,
+                input[input_offset + k * axis_stride],
+                coordinate,
+                false,
+            );
+            count += 1;
+        }
+    }
+
+    if count > 0 {
+        let reduced = R::Instruction::<(In, Acc)>::merge_line::<Out>(inst, accumulator, count);
+        let combined = R::Instruction::<(In, Acc)>::combine_outputs::<Out>(
+            inst,
+            Line::new(output[output_offset]),
+            1u32,
+            Line::new(reduced),
+            count,
+        );
+        output[output_offset] = combined[0];
+    }
+}
+
+#[cube(launch_unchecked)]
+fn scatter_add_atomic_kernel<In: Numeric, Out: Numeric>(
+    input: &Tensor<In>,
+    indices: &Tensor<u32>,
+    output: &mut Tensor<Atomic<Out>>,
+    num_items: u32,
+    axis: u32,
+) {
+    if ABSOLUTE_POS >= num_items {
+        terminate!();
+    }
+
+    let (input_offset, output_offset, in_bounds) =
+        scatter_item_offsets::<In, Atomic<Out>>(input, indices, output, axis);
+    if in_bounds {
+        Atomic::add(&output[output_offset], Out::cast_from(input[input_offset]));
+    }
+}
+
+#[cube(launch_unchecked)]
+fn scatter_max_atomic_kernel<In: Numeric>(
+    input: &Tensor<In>,
+    indices: &Tensor<u32>,
+    output: &mut Tensor<Atomic<u32>>,
+    num_items: u32,
+    axis: u32,
+) {
+    if ABSOLUTE_POS >= num_items {
+        terminate!();
+    }
+
+    let (input_offset, output_offset, in_bounds) =
+        scatter_item_offsets::<In, Atomic<u32>>(input, indices, output, axis);
+    if in_bounds {
+        let value = f32::cast_from(input[input_offset]);
+        let mut current = Atomic::load(&output[output_offset]);
+        let mut done = false;
+        while !done {
+            // A NaN item or destination compares as false, so it is left as is.
+            if value > f32::reinterpret(current) {
+                let previous = Atomic::compare_and_swap(
+                    &output[output_offset],
+                    current,
+                    u32::reinterpret(value),
+                );
+                done = previous == current;
+                current = previous;
+            } else {
+                done = true;
+            }
+        }
+    }
+}
+
+/// The offset of the item of this unit in `input`, the offset of its destination in `output`,
+/// and whether the destination is within the axis of `output`.
+#[cube]
+fn scatter_item_offsets<In: Numeric, O: CubePrimitive>(
+    input: &Tensor<In>,
+    indices: &Tensor<u32>,
+    output: &Tensor<O>,
+    axis: u32,
+) -> (u32, u32, bool) {
+    let position = ABSOLUTE_POS % input.shape(axis);
+    let (input_offset, output_offset) = slice_offsets(
+        input,
+        output,
+        ABSOLUTE_POS / input.shape(axis),
+        axis,
+        SliceAxes::Same,
+    );
+
+    let destination = indices[position * indices.stride(0)];
+    (
+        input_offset + position * input.stride(axis),
+        output_offset + destination * output.stride(axis),
+        destination < output.shape(axis),
+    )
+}
@@ -0,0 +1,194 @@
+use cubecl_core::prelude::*;
+use cubecl_core::{self as cubecl};
+use cubecl_std::tensor::{index_offset_contiguous, is_contiguous};
+
+use crate::instructions::*;
+use crate::precision::ReducePrecision;
+use crate::{ReduceError, ReduceStrategy};
+
+// NOTE: Keep it a power of 2, the tile sizes are derived from it.
+const CUBE_SIZE: u32 = 256;
+const MAX_TILE_INNER: u32 = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct SharedTransposeParams {
+    /// The number of contiguous positions after the reduced axis covered by a cube.
+    tile_inner: u32,
+    /// The number of units reducing the same position in parallel.
+    tile_reduce: u32,
+}
+
+/// Whether [`ReduceStrategy::shared_transpose`] can be used to reduce the given `axis`.
+///
+/// This is only the case when the reduced axis isn't the innermost one of a contiguous input,
+/// since reading along the innermost axis is already coalesced.
+pub(crate) fn supports_shared_transpose<R: Runtime>(
+    strategy: &ReduceStrategy,
+    input: &TensorHandleRef<R>,
+    axis: usize,
+) -> bool {
+    strategy.shared_transpose
+        && input.strides[axis] != 1
+        && is_contiguous(input.shape, input.strides)
+}
+
+/// Launch a reduction where each cube reduces a tile of contiguous output positions.
+///
+/// The units of a cube read the input row by row so that neighbouring units read
+/// neighbouring elements. Their partial accumulators are then transposed in shared memory
+/// so that all the accumulators of the same output position are contiguous,
+/// and fused with a tree reduction.
+///
+/// This assumes that [`supports_shared_transpose`] is true for the given input and axis.
+pub(crate) fn launch_reduce_shared_transpose<
+    Run: Runtime,
+    P: ReducePrecision,
+    Out: Numeric,
+    Rd: ReduceFamily,
+>(
+    client: &ComputeClient<Run::Server, Run::Channel>,
+    input: TensorHandleRef<Run>,
+    output: TensorHandleRef<Run>,
+    axis: usize,
+    inst: Rd::Config,
+    max_cube_count: (u32, u32, u32),
+) -> Result<(), ReduceError> {
+    let (cube_count, cube_dim, params) = shared_transpose_cube_count(&input, axis, max_cube_count)?;
+
+    unsafe {
+        reduce_shared_transpose_kernel::launch_unchecked::<P::EI, Out, P::EA, Rd, Run>(
+            client,
+            cube_count,
+            cube_dim,
+            input.as_tensor_arg(1),
+            output.as_tensor_arg(1),
+            ScalarArg::new(axis as u32),
+            params,
+            inst,
+        );
+    }
+
+    Ok(())
+}
+
+/// The cube count and dimension, and the params of the reduction of `axis` by
+/// [launch_reduce_shared_transpose].
+pub(crate) fn shared_transpose_cube_count<R: Runtime>(
+    input: &TensorHandleRef<R>,
+    axis: usize,
+    max_cube_count: (u32, u32, u32),
+) -> Result<(CubeCount, CubeDim, SharedTransposeParams), ReduceError> {
+    let inner = input.strides[axis] as u32;
+    let outer = input.shape[..axis].iter().product::<usize>() as u32;
+
+    let tile_inner = inner.next_power_of_two().min(MAX_TILE_INNER);
+    let params = SharedTransposeParams {
+        tile_inner,
+        tile_reduce: CUBE_SIZE / tile_inner,
+    };
+
+    let cube_count = outer * inner.div_ceil(tile_inner);
+    let (max_x, _, _) = max_cube_count;
+    if cube_count > max_x {
+        return Err(ReduceError::CubeCountTooLarge);
+    }
+    Ok((
+        CubeCount::new_1d(cube_count),
+        CubeDim::new_1d(CUBE_SIZE),
+        params,
+    ))
+}
+
+#[cube(launch_unchecked)]
+fn reduce_shared_transpose_kernel<In: Numeric, Out: Numeric, Acc: Numeric, R: ReduceFamily>(
+    input: &Tensor<Line<In>>,
+    output: &mut Tensor<Line<Out>>,
+    axis_reduce: u32,
+    #[comptime] params: SharedTransposeParams,
+    #[comptime] config: R::Config,
+) {
+    let inst = &R::Instruction::<(In, Acc)>::from_config(config);
+    reduce_shared_transpose_inner::<(In, Acc), Out, R::Instruction<(In, Acc)>>(
+        input,
+        output,
+        axis_reduce,
+        inst,
+        params,
+    );
+}
+
+#[cube]
+pub(crate) fn reduce_shared_transpose_inner<
+    P: ReducePrecision,
+    Out: Numeric,
+    R: ReduceInstruction<P>,
+>(
+    input: &Tensor<Line<P::EI>>,
+    output: &mut Tensor<Line<Out>>,
+    axis_reduce: u32,
+    inst: &R,
+    #[comptime] params: SharedTransposeParams,
+) {
+    let requirements = R::requirements(inst);
+
+    let shape_axis = input.shape(axis_reduce);
+    // The input is contiguous, so this is also the number of elements after the reduced axis.
+    let inner = input.stride(axis_reduce);
+    let num_tiles_inner = inner.div_ceil(params.tile_inner);
+
+    let outer = CUBE_POS / num_tiles_inner;
+    let tile_start = (CUBE_POS % num_tiles_inner) * params.tile_inner;
+
+    // Neighbouring units read neighbouring positions of the same row.
+    let row = UNIT_POS / params.tile_inner;
+    let column = UNIT_POS % params.tile_inner;
+    let position = tile_start + column;
+    let in_bounds = position < inner;
+
+    let first_index = outer * shape_axis * inner + position;
+    let mut accumulator = R::null_accumulator(inst, 1u32);
+
+    for coordinate in range_stepped(row, shape_axis, params.tile_reduce) {
+        let item = if in_bounds {
+            input[first_index + coordinate * inner]
+        } else {
+            R::null_input(inst, 1u32)
+        };
+        let coordinates = if comptime![requirements.coordinates] {
+            ReduceCoordinate::new_Required(Line::empty(1u32).fill(coordinate))
+        } else {
+            ReduceCoordinate::new_NotRequired()
+        };
+        reduce_inplace::<P, R>(inst, &mut accumulator, item, coordinates, false);
+    }
+
+    // Transpose the accumulators so that those of the same position are contiguous.
+    let mut shared = R::SharedAccumulator::allocate(CUBE_SIZE, 1u32, requirements.coordinates);
+    R::SharedAccumulator::write(&mut shared, column * params.tile_reduce + row, accumulator);
+    sync_cube();
+
+    // Tree reduction within each group of `tile_reduce` accumulators.
+    let lane = UNIT_POS % params.tile_reduce;
+    let mut jump = comptime![1u32];
+
+    #[unroll]
+    #[allow(clippy::explicit_counter_loop)]
+    for _ in 0..comptime![params.tile_reduce.trailing_zeros()] {
+        if lane % comptime![2 * jump] == 0 {
+            fuse_accumulator_inplace::<P, R>(inst, &mut shared, UNIT_POS, UNIT_POS + jump);
+        }
+        sync_cube();
+        comptime![jump *= 2];
+    }
+
+    let position = tile_start + UNIT_POS / params.tile_reduce;
+    if lane == 0 && position < inner {
+        let result = R::merge_line::<Out>(
+            inst,
+            R::SharedAccumulator::read(&shared, UNIT_POS),
+            shape_axis,
+        );
+        let index = index_offset_contiguous(output, outer * inner + position, None);
+        output[index] = Line::cast_from(result);
+    }
+}
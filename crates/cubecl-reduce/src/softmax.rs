@@ -0,0 +1,104 @@
+use cubecl_core::prelude::*;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise};
+
+use crate::primitives::{SliceAxes, slice_offsets};
+use crate::{ReduceError, validate_axis};
+
+/// The longest axis [`softmax_axis`] can normalize, since each unit keeps its whole slice
+/// in registers.
+pub const SOFTMAX_MAX_AXIS: usize = 64;
+
+/// Compute the softmax of each slice along `axis` of `input` and write it into `output`,
+/// which must have the same shape as `input`.
+///
+/// Each unit loads its whole slice once, subtracts its maximum before the exponential so large
+/// items don't overflow, and writes the normalized items, all in a single launch. The items are
+/// computed in `f32` whatever the precision of `In` and `Out`. A slice holding `inf` or NaN, or
+/// only `-inf`, gives NaN.
+///
+/// The length of the axis is comptime, so each length compiles its own kernel. This returns
+/// [`ReduceError::SoftmaxAxisTooLong`] if the axis is longer than [`SOFTMAX_MAX_AXIS`], and
+/// [`ReduceError::MismatchShape`] if `output` doesn't have the shape of `input`.
+pub fn softmax_axis<R: Runtime, In: Float, Out: Float>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+) -> Result<(), ReduceError> {
+    validate_axis(input.shape.len(), axis)?;
+    if output.shape != input.shape {
+        return Err(ReduceError::MismatchShape {
+            expected_shape: input.shape.to_vec(),
+            output_shape: output.shape.to_vec(),
+        });
+    }
+
+    let axis_len = input.shape[axis];
+    if axis_len > SOFTMAX_MAX_AXIS {
+        return Err(ReduceError::SoftmaxAxisTooLong {
+            length: axis_len,
+            max: SOFTMAX_MAX_AXIS,
+        });
+    }
+
+    let num_slices = input.shape.iter().product::<usize>() / axis_len.max(1);
+    if axis_len == 0 || num_slices == 0 {
+        return Ok(());
+    }
+
+    let cube_dim = CubeDim::default();
+    let cube_count = calculate_cube_count_elemwise(num_slices, cube_dim);
+
+    unsafe {
+        softmax_kernel::launch_unchecked::<In, Out, R>(
+            client,
+            cube_count,
+            cube_dim,
+            input.as_tensor_arg(1),
+            output.as_tensor_arg(1),
+            ScalarArg::new(axis as u32),
+            axis_len as u32,
+        );
+    }
+    Ok(())
+}
+
+#[cube(launch_unchecked)]
+fn softmax_kernel<In: Float, Out: Float>(
+    input: &Tensor<In>,
+    output: &mut Tensor<Out>,
+    axis: u32,
+    #[comptime] axis_len: u32,
+) {
+    if ABSOLUTE_POS * axis_len >= output.len() {
+        terminate!();
+    }
+
+    let (input_offset, output_offset) =
+        slice_offsets(input, output, ABSOLUTE_POS, axis, SliceAxes::Same);
+
+    let input_stride = input.stride(axis);
+    let output_stride = output.stride(axis);
+
+    let mut items = Array::<f32>::new(axis_len);
+    let mut max = f32::min_value();
+    #[unroll]
+    for k in 0..axis_len {
+        let item = f32::cast_from(input[input_offset + k * input_stride]);
+        items[k] = item;
+        max = Max::max(max, item);
+    }
+
+    let mut sum = f32::new(0.0);
+    #[unroll]
+    for k in 0..axis_len {
+        let item = Exp::exp(items[k] - max);
+        items[k] = item;
+        sum += item;
+    }
+
+    #[unroll]
+    for k in 0..axis_len {
+        output[output_offset + k * output_stride] = Out::cast_from(items[k] / sum);
+    }
+}
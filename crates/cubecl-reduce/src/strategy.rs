@@ -1,8 +1,19 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
 use cubecl_core::prelude::*;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise, future};
 use cubecl_runtime::Plane;
 use serde::{Deserialize, Serialize};
 
 use crate::ReduceError;
+use crate::config::DEFAULT_PLANE_COUNT;
+
+/// The ratios measured by [`ReduceDeviceProfile::measure_bytes_per_op`], by runtime and client.
+static BYTES_PER_OP: LazyLock<Mutex<HashMap<(TypeId, usize), f32>>> =
+    LazyLock::new(Default::default);
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize)]
 pub struct ReduceStrategy {
@@ -14,6 +25,26 @@ pub struct ReduceStrategy {
     /// If true, all units within a single cube cooperate to reduce a single item in the output.
     /// Else, each unit or plane (if planes is true) reduce a single item by itself.
     pub shared: bool,
+
+    /// If true and the reduced axis isn't the innermost one of a contiguous input,
+    /// each cube reads a tile of the input row by row and transposes its partial results
+    /// in shared memory before fusing them, so that global memory reads stay coalesced.
+    /// This takes precedence over `use_planes` and `shared` when applicable.
+    #[serde(default)]
+    pub shared_transpose: bool,
+
+    /// Override the plane size used to shape the cubes, which is otherwise read from the
+    /// client properties. The client must support it, and it must be the only plane size
+    /// of the client when `use_planes` is true, since plane instructions rely on it.
+    #[serde(default)]
+    pub plane_dim: Option<u32>,
+
+    /// If true, each unit reduces a single item of the output, reading the reduced axis one item
+    /// at a time, without lines, planes or shared memory. This is slow, but simple enough to be
+    /// a reference for the other strategies, and every client supports it.
+    /// This takes precedence over all the other fields.
+    #[serde(default)]
+    pub naive: bool,
 }
 
 impl ReduceStrategy {
@@ -21,6 +52,10 @@ impl ReduceStrategy {
         self,
         client: &ComputeClient<R::Server, R::Channel>,
     ) -> Result<Self, ReduceError> {
+        if self.naive {
+            return Ok(Self::naive());
+        }
+
         if self.use_planes {
             if !support_plane::<R>(client) {
                 return Err(ReduceError::PlanesUnavailable);
@@ -30,6 +65,18 @@ impl ReduceStrategy {
             }
         }
 
+        if let Some(plane_dim) = self.plane_dim {
+            let hw_props = &client.properties().hardware;
+            let (min, max) = (hw_props.plane_size_min, hw_props.plane_size_max);
+            if !plane_dim.is_power_of_two() || plane_dim < min || plane_dim > max {
+                return Err(ReduceError::PlaneDimUnsupported {
+                    plane_dim,
+                    min,
+                    max,
+                });
+            }
+        }
+
         Ok(self)
     }
 
@@ -37,8 +84,288 @@ impl ReduceStrategy {
         Self {
             use_planes: support_plane::<R>(client) && precise_plane_dim::<R>(client),
             shared,
+            shared_transpose: false,
+            plane_dim: None,
+            naive: false,
+        }
+    }
+
+    /// The [naive](Self::naive) strategy, with all the other fields disabled.
+    pub fn naive() -> Self {
+        Self {
+            use_planes: false,
+            shared: false,
+            shared_transpose: false,
+            plane_dim: None,
+            naive: true,
+        }
+    }
+
+    /// Every strategy supported by the `client`, with each combination of the flags of the
+    /// strategy and the plane size of the client.
+    ///
+    /// Each new field must be added to the combinations, so that every new strategy is included
+    /// in the sweeps over strategies, such as the benchmarks of the `export_tests` feature.
+    pub fn all_supported<R: Runtime>(client: &ComputeClient<R::Server, R::Channel>) -> Vec<Self> {
+        let mut strategies = vec![Self::naive()];
+        for use_planes in [false, true] {
+            for shared in [false, true] {
+                for shared_transpose in [false, true] {
+                    // Without `..`, this fails to compile when a field is added.
+                    let strategy = Self {
+                        use_planes,
+                        shared,
+                        shared_transpose,
+                        plane_dim: None,
+                        naive: false,
+                    };
+                    if let Ok(strategy) = strategy.validate::<R>(client) {
+                        strategies.push(strategy);
+                    }
+                }
+            }
+        }
+        strategies
+    }
+
+    /// Pick the strategy expected to be the fastest to reduce an axis of `axis_length` items of an
+    /// input of `input_size` items taking `elem_size` bytes each, on a device described by `device`.
+    ///
+    /// The candidates are plane-only (`use_planes` without `shared`), hierarchical (`use_planes`
+    /// and `shared`) and, without planes, shared-memory (`shared` only) or one unit per reduction.
+    /// Each is given a cost in units of one instruction run by all the units of the device:
+    ///
+    /// - The agents of a candidate (a unit, a plane or a cube) use at most `axis_length` units
+    ///   each, and up to `device.num_units` of them run at the same time.
+    /// - Reading the input is the largest of its transfer time, given `device.bytes_per_op`, and
+    ///   the time to reduce the items, both spread over the running units.
+    /// - Fusing the partial results of an agent costs a step per halving for plane instructions
+    ///   and [`SYNC_COST`](Self::SYNC_COST) steps per halving through shared memory, for every
+    ///   wave of agents.
+    ///
+    /// Large inputs are bandwidth-bound, so long axes are spread over whole cubes to keep the
+    /// memory busy, while small or compute-bound reductions favor the cheaper plane fusion. This
+    /// only depends on its arguments, so the same shapes on the same device always pick the same
+    /// strategy.
+    pub fn select(
+        input_size: usize,
+        axis_length: usize,
+        elem_size: usize,
+        device: &ReduceDeviceProfile,
+    ) -> Self {
+        let axis_length = axis_length.max(1) as f64;
+        let num_reductions = (input_size as f64 / axis_length).max(1.0);
+        let input_bytes = (input_size * elem_size) as f64;
+        let num_units = device.num_units.max(1) as f64;
+        let plane_dim = device.plane_dim.max(1);
+        let cube_dim = plane_dim * DEFAULT_PLANE_COUNT;
+
+        let cost = |agent_dim: u32, fuse_steps: f64| {
+            let agent_units = axis_length.min(agent_dim as f64);
+            let active_units = (num_reductions * agent_units).min(num_units);
+            let read = input_bytes / (device.bytes_per_op as f64 * active_units);
+            let compute = input_size as f64 / active_units;
+            let waves = (num_reductions * agent_units / num_units).ceil();
+            read.max(compute) + fuse_steps * waves
+        };
+        let log2 = |value: u32| value.ilog2() as f64;
+
+        let candidates = if device.use_planes {
+            let hierarchical = log2(plane_dim) + Self::SYNC_COST * log2(DEFAULT_PLANE_COUNT);
+            [
+                (cost(plane_dim, log2(plane_dim)), true, false),
+                (cost(cube_dim, hierarchical), true, true),
+            ]
+        } else {
+            [
+                (cost(1, 0.0), false, false),
+                (
+                    cost(cube_dim, Self::SYNC_COST * log2(cube_dim)),
+                    false,
+                    true,
+                ),
+            ]
+        };
+        let (_, use_planes, shared) = candidates
+            .into_iter()
+            .min_by(|lhs, rhs| lhs.0.total_cmp(&rhs.0))
+            .unwrap();
+
+        Self {
+            use_planes,
+            shared,
+            shared_transpose: false,
+            plane_dim: None,
+            naive: false,
+        }
+    }
+
+    /// The cost of a step fusing partial results through shared memory in [`Self::select`],
+    /// relative to a plane instruction, accounting for the synchronization of the cube.
+    pub const SYNC_COST: f64 = 4.0;
+}
+
+/// What [`ReduceStrategy::select`] needs to know about a device.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ReduceDeviceProfile {
+    /// Whether plane instructions can be used, as in [`ReduceStrategy::new`].
+    pub use_planes: bool,
+    /// The number of units in a plane.
+    pub plane_dim: u32,
+    /// The number of units running at the same time on the whole device.
+    pub num_units: u32,
+    /// The ratio between the memory bandwidth and the compute throughput of the device,
+    /// as the bytes read from global memory in the time a unit runs one instruction.
+    pub bytes_per_op: f32,
+}
+
+impl ReduceDeviceProfile {
+    /// The ratio used when it can't be measured, in the range of current GPUs, where a unit can
+    /// run a few dozen instructions in the time its share of the bandwidth brings a single `f32`.
+    pub const DEFAULT_BYTES_PER_OP: f32 = 0.05;
+
+    /// The number of `f32` copied to measure the memory bandwidth, 16 MiB.
+    const BANDWIDTH_PROBE_LEN: usize = 1 << 22;
+    /// The number of units running the multiply-adds measuring the compute throughput.
+    const THROUGHPUT_PROBE_UNITS: usize = 1 << 18;
+    /// The number of multiply-adds of each unit measuring the compute throughput.
+    const THROUGHPUT_PROBE_OPS: u32 = 256;
+
+    /// Describe the device of the `client` from its properties, with the ratio between its
+    /// memory bandwidth and compute throughput [measured](Self::measure_bytes_per_op) once.
+    ///
+    /// When the number of streaming multiprocessors is unknown, the device is assumed to run 16
+    /// cubes of the maximum size at the same time.
+    pub fn new<R: Runtime>(client: &ComputeClient<R::Server, R::Channel>) -> Self {
+        let hw_props = &client.properties().hardware;
+        let num_cubes = hw_props.num_streaming_multiprocessors.unwrap_or(16);
+        Self {
+            use_planes: support_plane::<R>(client) && precise_plane_dim::<R>(client),
+            plane_dim: hw_props.plane_size_max,
+            num_units: num_cubes * hw_props.max_units_per_cube,
+            bytes_per_op: Self::measure_bytes_per_op::<R>(client),
         }
     }
+
+    /// Measure the ratio between the memory bandwidth and the compute throughput of the device of
+    /// the `client`, see [`Self::bytes_per_op`].
+    ///
+    /// The bandwidth is measured by profiling the copy of [`Self::BANDWIDTH_PROBE_LEN`] `f32`,
+    /// and the throughput by profiling the dependent multiply-adds of many units on registers,
+    /// each kernel being launched once before to leave its compilation out. This is only an
+    /// estimate, the loop of the multiply-adds isn't counted for instance.
+    ///
+    /// The ratio is cached for the client and its clones, so only the first call launches
+    /// kernels. It falls back to [`Self::DEFAULT_BYTES_PER_OP`] when the client can't profile.
+    pub fn measure_bytes_per_op<R: Runtime>(client: &ComputeClient<R::Server, R::Channel>) -> f32 {
+        // The clones of a client share their properties, so their address identifies the client.
+        let key = (
+            TypeId::of::<R>(),
+            std::ptr::from_ref(client.properties()) as usize,
+        );
+        if let Some(bytes_per_op) = BYTES_PER_OP.lock().unwrap().get(&key) {
+            return *bytes_per_op;
+        }
+
+        let len = Self::BANDWIDTH_PROBE_LEN;
+        let input_handle = client.empty(len * size_of::<f32>());
+        let output_handle = client.empty(len * size_of::<f32>());
+        let copy = || {
+            let cube_dim = CubeDim::default();
+            unsafe {
+                bandwidth_probe_kernel::launch_unchecked::<R>(
+                    client,
+                    calculate_cube_count_elemwise(len, cube_dim),
+                    cube_dim,
+                    ArrayArg::from_raw_parts::<f32>(&input_handle, len, 1),
+                    ArrayArg::from_raw_parts::<f32>(&output_handle, len, 1),
+                );
+            }
+        };
+
+        let units = Self::THROUGHPUT_PROBE_UNITS;
+        let values_handle = client.empty(units * size_of::<f32>());
+        let multiply_add = || {
+            let cube_dim = CubeDim::default();
+            unsafe {
+                throughput_probe_kernel::launch_unchecked::<R>(
+                    client,
+                    calculate_cube_count_elemwise(units, cube_dim),
+                    cube_dim,
+                    ArrayArg::from_raw_parts::<f32>(&values_handle, units, 1),
+                    ScalarArg::new(0.5),
+                    ScalarArg::new(1.0),
+                    Self::THROUGHPUT_PROBE_OPS,
+                );
+            }
+        };
+
+        let bytes_per_op = match (
+            profile::<R>(client, copy),
+            profile::<R>(client, multiply_add),
+        ) {
+            (Some(copy), Some(multiply_add)) => {
+                // Each item is read and written, and each multiply-add is two instructions.
+                let bandwidth = (2 * len * size_of::<f32>()) as f64 / copy.as_secs_f64();
+                let ops = units as f64 * 2.0 * Self::THROUGHPUT_PROBE_OPS as f64;
+                Self::bytes_per_op(bandwidth, ops / multiply_add.as_secs_f64())
+            }
+            _ => Self::DEFAULT_BYTES_PER_OP,
+        };
+        BYTES_PER_OP.lock().unwrap().insert(key, bytes_per_op);
+        bytes_per_op
+    }
+
+    /// The ratio between the memory `bandwidth`, in bytes per second, and the compute
+    /// `throughput`, in instructions per second summed over all the units of the device.
+    ///
+    /// This is the share of the bandwidth of a unit in the time it runs one instruction, since
+    /// both are divided by the number of units. Falls back to [`Self::DEFAULT_BYTES_PER_OP`]
+    /// when either isn't a positive finite number, such as a measured duration of zero.
+    pub fn bytes_per_op(bandwidth: f64, throughput: f64) -> f32 {
+        let valid = |value: f64| value.is_finite() && value > 0.0;
+        if valid(bandwidth) && valid(throughput) {
+            (bandwidth / throughput) as f32
+        } else {
+            Self::DEFAULT_BYTES_PER_OP
+        }
+    }
+}
+
+/// The duration of the profiled `launch`, launched once before to leave the compilation out, or
+/// `None` if the client can't profile it.
+fn profile<R: Runtime>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    launch: impl Fn(),
+) -> Option<Duration> {
+    launch();
+    let profile = client.profile(launch, "reduce device profile").ok()?;
+    Some(future::block_on(profile.resolve()).duration())
+}
+
+/// Copy `input` into `output`, one item per unit.
+#[cube(launch_unchecked)]
+fn bandwidth_probe_kernel(input: &Array<f32>, output: &mut Array<f32>) {
+    if ABSOLUTE_POS < output.len() {
+        output[ABSOLUTE_POS] = input[ABSOLUTE_POS];
+    }
+}
+
+/// Run `num_ops` dependent multiply-adds per unit, writing the result so they are kept.
+#[cube(launch_unchecked)]
+fn throughput_probe_kernel(
+    output: &mut Array<f32>,
+    scale: f32,
+    offset: f32,
+    #[comptime] num_ops: u32,
+) {
+    let mut value = f32::cast_from(ABSOLUTE_POS);
+    for _ in 0..num_ops {
+        value = value * scale + offset;
+    }
+    if ABSOLUTE_POS < output.len() {
+        output[ABSOLUTE_POS] = value;
+    }
 }
 
 fn support_plane<R: Runtime>(client: &ComputeClient<R::Server, R::Channel>) -> bool {
@@ -49,3 +376,80 @@ fn precise_plane_dim<R: Runtime>(client: &ComputeClient<R::Server, R::Channel>)
     let hw_props = &client.properties().hardware;
     hw_props.plane_size_min == hw_props.plane_size_max
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Check the strategies picked by [ReduceStrategy::select] on a few shapes, with and without
+    /// planes, and on devices where the memory is slow or fast compared to the compute units.
+    #[test]
+    fn select_strategy() {
+        let gpu = ReduceDeviceProfile {
+            use_planes: true,
+            plane_dim: 32,
+            num_units: 8192,
+            bytes_per_op: 0.05,
+        };
+        let fast_memory = ReduceDeviceProfile {
+            bytes_per_op: 4.0,
+            ..gpu
+        };
+        let no_planes = ReduceDeviceProfile {
+            use_planes: false,
+            ..gpu
+        };
+        let select = |num_reductions: usize, axis_length: usize, device| {
+            let strategy = ReduceStrategy::select(
+                num_reductions * axis_length,
+                axis_length,
+                size_of::<f32>(),
+                device,
+            );
+            (strategy.use_planes, strategy.shared)
+        };
+
+        // Enough short reductions to fill the device with one plane each.
+        assert_eq!(select(16384, 64, &gpu), (true, false));
+        // A single long reduction must be spread over a cube to use the bandwidth.
+        assert_eq!(select(1, 1 << 20, &gpu), (true, true));
+        // A single short reduction is bandwidth-bound on a typical device,
+        // but fusing through shared memory costs more than reading when the memory is fast.
+        assert_eq!(select(1, 64, &gpu), (true, true));
+        assert_eq!(select(1, 64, &fast_memory), (true, false));
+        // Without planes, the same choice is made between shared memory and one unit per reduction.
+        assert_eq!(select(1, 1 << 20, &no_planes), (false, true));
+        assert_eq!(select(16384, 64, &no_planes), (false, false));
+    }
+
+    /// Check that devices with different measured bandwidths and throughputs get profiles
+    /// selecting different strategies, and that invalid measurements fall back to the default.
+    #[test]
+    fn measured_bytes_per_op() {
+        let profile = |bandwidth, throughput| ReduceDeviceProfile {
+            use_planes: true,
+            plane_dim: 32,
+            num_units: 8192,
+            bytes_per_op: ReduceDeviceProfile::bytes_per_op(bandwidth, throughput),
+        };
+        // 1 TB/s for 20 T instructions/s, and 4 TB/s for 1 T instructions/s.
+        let gpu = profile(1e12, 2e13);
+        let fast_memory = profile(4e12, 1e12);
+        assert!((gpu.bytes_per_op - 0.05).abs() < 1e-6);
+        assert_eq!(fast_memory.bytes_per_op, 4.0);
+
+        let select = |device| {
+            let strategy = ReduceStrategy::select(64, 64, size_of::<f32>(), device);
+            (strategy.use_planes, strategy.shared)
+        };
+        assert_eq!(select(&gpu), (true, true));
+        assert_eq!(select(&fast_memory), (true, false));
+
+        let default = ReduceDeviceProfile::DEFAULT_BYTES_PER_OP;
+        assert_eq!(ReduceDeviceProfile::bytes_per_op(1e12, 0.0), default);
+        assert_eq!(
+            ReduceDeviceProfile::bytes_per_op(f64::INFINITY, 1e12),
+            default
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+use cubecl_core::ir::{ElemType, FloatKind};
+use cubecl_core::prelude::*;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise};
+
+use crate::instructions::ReduceFamily;
+use crate::precision::ReducePrecision;
+use crate::update::contiguous_strides;
+use crate::{ReduceError, ReduceStrategy, reduce, valid_output_shape, validate_axis};
+
+/// The answers of [`flushes_subnormals`], by runtime, float type and client.
+static FLUSHES_SUBNORMALS: LazyLock<Mutex<HashMap<(TypeId, usize), bool>>> =
+    LazyLock::new(Default::default);
+
+/// How [`reduce_with_subnormals`] handles the subnormal floats of the input.
+///
+/// Whether a backend flushes subnormals to zero (FTZ) depends on how its kernels are compiled:
+///
+/// - CUDA and HIP keep subnormals, unless the kernel is compiled with
+///   [`FastMath::ReducedPrecision`](cubecl_core::prelude::FastMath::ReducedPrecision), which
+///   flushes the `f32` ones.
+/// - WebGPU and Vulkan leave it to the driver, unless the float controls of the SPIR-V kernel
+///   ask otherwise. Metal flushes them when compiling with its default fast math.
+/// - The CPU runtime keeps subnormals, like a reference computed on the host.
+///
+/// Use [`flushes_subnormals`] to find what the backend of a client does.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SubnormalPolicy {
+    /// Let the backend handle the subnormals, which is what [`reduce`] does.
+    Backend,
+    /// Replace the subnormals of the input by zero before reducing it, on any backend.
+    /// The results then match a reference computed on the host with flushed inputs.
+    FlushToZero,
+    /// Keep the subnormals of the input and output, emulating them on a backend flushing them.
+    Preserve,
+}
+
+/// Same as [`reduce`], but with subnormal input floats handled according to `policy`.
+///
+/// With [`SubnormalPolicy::FlushToZero`], this first copies the input with its subnormals set
+/// to zero, which is done by comparing their magnitude with the smallest normal float, so it
+/// gives the same copy whether or not the backend flushes its own arithmetic. Only the inputs
+/// are handled, the accumulation of normal floats can still round to zero a sum small enough to
+/// be subnormal on a flushing backend.
+///
+/// With [`SubnormalPolicy::Preserve`], this checks whether the backend flushes subnormals, see
+/// [`flushes_subnormals`]. If it does, the `f32` input is decoded from its bits into an `f64`
+/// copy, where every `f32` subnormal is a normal float. The copy is reduced in `f64`, and the
+/// results are encoded from their bits into an `f32` output, so the subnormal inputs and
+/// outputs are the same as on a backend keeping them. This returns
+/// [`ReduceError::SubnormalsFlushed`] if the input isn't `f32` or the backend doesn't support
+/// `f64`.
+pub fn reduce_with_subnormals<R: Runtime, P: ReducePrecision, Out: Numeric, Inst: ReduceFamily>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    strategy: Option<ReduceStrategy>,
+    inst_config: Inst::Config,
+    policy: SubnormalPolicy,
+) -> Result<(), ReduceError>
+where
+    P::EI: Float + CubeElement,
+{
+    match policy {
+        SubnormalPolicy::Backend => {}
+        SubnormalPolicy::Preserve => {
+            if flushes_subnormals::<R, P::EI>(client) {
+                return reduce_emulating_subnormals::<R, P, Out, Inst>(
+                    client,
+                    input,
+                    output,
+                    axis,
+                    strategy,
+                    inst_config,
+                );
+            }
+        }
+        SubnormalPolicy::FlushToZero => {
+            let span = strided_span(input.shape, input.strides);
+            let flushed_handle = client.empty(span * size_of::<P::EI>());
+            let cube_dim = CubeDim::default();
+            let cube_count = calculate_cube_count_elemwise(span, cube_dim);
+            unsafe {
+                flush_subnormals_kernel::launch_unchecked::<P::EI, R>(
+                    client,
+                    cube_count,
+                    cube_dim,
+                    ArrayArg::from_raw_parts::<P::EI>(input.handle, span, 1),
+                    ArrayArg::from_raw_parts::<P::EI>(&flushed_handle, span, 1),
+                    ScalarArg::new(P::EI::MIN_POSITIVE),
+                );
+            }
+            let flushed = unsafe {
+                TensorHandleRef::<R>::from_raw_parts(
+                    &flushed_handle,
+                    input.strides,
+                    input.shape,
+                    input.elem_size,
+                )
+            };
+            return reduce::<R, P, Out, Inst>(client, flushed, output, axis, strategy, inst_config);
+        }
+    }
+    reduce::<R, P, Out, Inst>(client, input, output, axis, strategy, inst_config)
+}
+
+/// Check whether the backend of the `client` flushes subnormal `F` floats to zero.
+///
+/// This launches a kernel adding two subnormals, whose sum is still subnormal, and waits for it
+/// to read back the sum, so the answer is what the backend does rather than what it's expected
+/// to do. Kernels compiled with other options than the default ones can behave differently.
+///
+/// The answer is cached for the client and its clones, so only the first call launches a kernel.
+pub fn flushes_subnormals<R: Runtime, F: Float + CubeElement>(
+    client: &ComputeClient<R::Server, R::Channel>,
+) -> bool {
+    // The clones of a client share their properties, so their address identifies the client.
+    let key = (
+        TypeId::of::<(R, F)>(),
+        std::ptr::from_ref(client.properties()) as usize,
+    );
+    if let Some(flushes) = FLUSHES_SUBNORMALS.lock().unwrap().get(&key) {
+        return *flushes;
+    }
+
+    let subnormal = F::MIN_POSITIVE / F::new(4.0);
+    let input_handle = client.create(F::as_bytes(&[subnormal, subnormal]));
+    let output_handle = client.empty(size_of::<F>());
+    unsafe {
+        subnormal_probe_kernel::launch_unchecked::<F, R>(
+            client,
+            CubeCount::new_single(),
+            CubeDim::new(1, 1, 1),
+            ArrayArg::from_raw_parts::<F>(&input_handle, 2, 1),
+            ArrayArg::from_raw_parts::<F>(&output_handle, 1, 1),
+        );
+    }
+    let bytes = client.read_one(output_handle);
+    let flushes = F::from_bytes(&bytes)[0] == F::new(0.0);
+    FLUSHES_SUBNORMALS.lock().unwrap().insert(key, flushes);
+    flushes
+}
+
+/// Reduce the `f32` input in `f64` on a backend flushing `f32` subnormals, decoding the inputs
+/// and encoding the outputs from their bits.
+fn reduce_emulating_subnormals<R: Runtime, P: ReducePrecision, Out: Numeric, Inst: ReduceFamily>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    strategy: Option<ReduceStrategy>,
+    inst_config: Inst::Config,
+) -> Result<(), ReduceError> {
+    if P::EI::as_type_native_unchecked().elem_type() != ElemType::Float(FloatKind::F32)
+        || !client
+            .properties()
+            .supports_type(f64::as_type_native_unchecked())
+    {
+        return Err(ReduceError::SubnormalsFlushed);
+    }
+    validate_axis(input.shape.len(), axis)?;
+    valid_output_shape(input.shape, output.shape, axis)?;
+
+    let span = strided_span(input.shape, input.strides);
+    let wide_input_handle = client.empty(span * size_of::<f64>());
+    let cube_dim = CubeDim::default();
+    unsafe {
+        widen_subnormals_kernel::launch_unchecked::<R>(
+            client,
+            calculate_cube_count_elemwise(span, cube_dim),
+            cube_dim,
+            ArrayArg::from_raw_parts::<f32>(input.handle, span, 1),
+            ArrayArg::from_raw_parts::<f64>(&wide_input_handle, span, 1),
+        );
+    }
+    let wide_input = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(
+            &wide_input_handle,
+            input.strides,
+            input.shape,
+            size_of::<f64>(),
+        )
+    };
+
+    let num_elems = output.shape.iter().product::<usize>();
+    let wide_output_strides = contiguous_strides(output.shape);
+    let wide_output_handle = client.empty(num_elems * size_of::<f64>());
+    let wide_output = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(
+            &wide_output_handle,
+            &wide_output_strides,
+            output.shape,
+            size_of::<f64>(),
+        )
+    };
+    reduce::<R, f64, f64, Inst>(client, wide_input, wide_output, axis, strategy, inst_config)?;
+
+    unsafe {
+        narrow_subnormals_kernel::launch_unchecked::<Out, R>(
+            client,
+            calculate_cube_count_elemwise(num_elems, cube_dim),
+            cube_dim,
+            wide_output.as_tensor_arg(1),
+            output.as_tensor_arg(1),
+        );
+    }
+    Ok(())
+}
+
+/// The number of items covered by the strides, so a copy of them keeps the same layout.
+fn strided_span(shape: &[usize], strides: &[usize]) -> usize {
+    shape
+        .iter()
+        .zip(strides)
+        .map(|(shape, stride)| shape.saturating_sub(1) * stride)
+        .sum::<usize>()
+        + 1
+}
+
+#[cube(launch_unchecked)]
+fn flush_subnormals_kernel<F: Float>(input: &Array<F>, output: &mut Array<F>, min_positive: F) {
+    if ABSOLUTE_POS < output.len() {
+        let value = input[ABSOLUTE_POS];
+        output[ABSOLUTE_POS] = select(Abs::abs(value) < min_positive, F::new(0.0), value);
+    }
+}
+
+/// Write the `f32` input as `f64`, decoding the subnormals from their bits since the cast of the
+/// backend would flush them.
+#[cube(launch_unchecked)]
+fn widen_subnormals_kernel(input: &Array<f32>, output: &mut Array<f64>) {
+    if ABSOLUTE_POS < output.len() {
+        let value = input[ABSOLUTE_POS];
+        let bits = u32::reinterpret(value);
+        let magnitude = bits & 0x7FFF_FFFFu32;
+        // A subnormal is its mantissa times `2^-149`, the smallest normal float times `2^-23`.
+        let subnormal = f64::cast_from(magnitude)
+            * f64::new(comptime![f32::MIN_POSITIVE])
+            * f64::new(comptime![2.0f32.powi(-23)]);
+        let subnormal = select(magnitude != bits, -subnormal, subnormal);
+        output[ABSOLUTE_POS] = select(magnitude < 0x0080_0000u32, subnormal, f64::cast_from(value));
+    }
+}
+
+/// Write the contiguous `f64` input into `output`, encoding the values in the subnormal range of
+/// an `f32` output from their bits since the cast of the backend would flush them.
+#[cube(launch_unchecked)]
+fn narrow_subnormals_kernel<Out: Numeric>(input: &Tensor<f64>, output: &mut Tensor<Out>) {
+    if ABSOLUTE_POS >= input.len() {
+        terminate!();
+    }
+
+    // The input is contiguous, so its strides give the coordinates of the position.
+    let mut offset = 0u32;
+    for i in 0..output.rank() {
+        let coordinate = (ABSOLUTE_POS / input.stride(i)) % input.shape(i);
+        offset += coordinate * output.stride(i);
+    }
+
+    let value = input[ABSOLUTE_POS];
+    if comptime![Out::as_type_native_unchecked().elem_type() == ElemType::Float(FloatKind::F32)] {
+        let min_positive = f64::new(comptime![f32::MIN_POSITIVE]);
+        let magnitude = Abs::abs(value);
+        if magnitude < min_positive {
+            let mantissa = u32::cast_from(Round::round(
+                magnitude / min_positive * f64::new(comptime![2.0f32.powi(23)]),
+            ));
+            let sign = select(value < f64::new(0.0), 0x8000_0000u32, 0u32);
+            output[offset] = Out::reinterpret(sign | mantissa);
+        } else {
+            output[offset] = Out::cast_from(value);
+        }
+    } else {
+        output[offset] = Out::cast_from(value);
+    }
+}
+
+#[cube(launch_unchecked)]
+fn subnormal_probe_kernel<F: Float>(input: &Array<F>, output: &mut Array<F>) {
+    output[0] = input[0] + input[1];
+}
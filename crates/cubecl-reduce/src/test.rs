@@ -1,5 +1,9 @@
 #![allow(missing_docs)]
 
+pub mod benchmark;
+
+use cubecl_core as cubecl;
+use cubecl_core::ir::{ElemType, FloatKind};
 use cubecl_core::prelude::*;
 use rand::{
     SeedableRng,
@@ -7,8 +11,20 @@ use rand::{
     rngs::StdRng,
 };
 
+// Explicit, so they aren't ambiguous with the traits of the prelude.
+use crate::instructions::{Max, Min};
+use crate::update::contiguous_strides;
 use crate::{
-    ReduceError, ReduceStrategy, instructions::*, precision::ReducePrecision, reduce, shared_sum,
+    Bits4, Histogram, HistogramOutliers, PoolBoundary, ReduceAccumulator, ReduceConfig,
+    ReduceError, ReduceMap, ReduceRounding, ReduceStrategy, SOFTMAX_MAX_AXIS, ScanMode,
+    ScatterAtomic, SubnormalPolicy, ZeroSumPolicy, frobenius_norm, gather_reduce, instructions::*,
+    l1_normalize_axis, map_reduce, pool_reduce, precision::ReducePrecision, reduce,
+    reduce_argmax_global, reduce_concat, reduce_cube_partials, reduce_diagonal, reduce_dot_product,
+    reduce_dyn, reduce_enqueue, reduce_histogram, reduce_moments2, reduce_packed, reduce_permuted,
+    reduce_plane_local, reduce_quantiles, reduce_sum_checked, reduce_update,
+    reduce_update_with_scratch, reduce_weighted_mean, reduce_weighted_sum, reduce_with_lengths,
+    reduce_with_max_cube_count, reduce_with_rounding, reduce_with_subnormals, scatter_reduce,
+    scatter_reduce_atomic, segmented_scan, shared_sum, softmax_axis, try_reduce,
 };
 
 // All random values generated for tests will be in the set
@@ -17,6 +33,71 @@ use crate::{
 // also to add multiple similar values to properly test ArgMax and ArgMin.
 const PRECISION: i32 = 4;
 
+/// Reduce a single line of `input` with the instruction `R`, writing its merged lanes.
+#[cube(launch)]
+fn merge_line_kernel<R: ReduceFamily>(
+    input: &Array<Line<f32>>,
+    output: &mut Array<f32>,
+    #[comptime] config: R::Config,
+) {
+    let inst = &R::Instruction::<(f32, f32)>::from_config(config);
+    let line_size = input.line_size();
+    let identity = R::Instruction::<(f32, f32)>::identity(inst, line_size);
+    let accumulator = R::Instruction::<(f32, f32)>::reduce(
+        inst,
+        &identity,
+        input[0],
+        ReduceCoordinate::new_NotRequired(),
+        false,
+    );
+    output[0] = R::Instruction::<(f32, f32)>::merge_line::<f32>(inst, accumulator, line_size);
+}
+
+/// Merge a line of 4 lanes `[1, 2^-24, 2^-24, 2^-24]` with the [serial](LaneOrder::Serial) and
+/// the [pairwise](LaneOrder::Pairwise) [Sum], and check that the pairwise sum is closer to the
+/// exact one: each small lane is lost when added to `1` alone, but not when added to another.
+pub fn test_pairwise_lane_sum<R: Runtime>(device: &R::Device) {
+    let client = R::client(device);
+    let tiny = 2.0f32.powi(-24);
+    let input_handle = client.create(f32::as_bytes(&[1.0, tiny, tiny, tiny]));
+    let exact = 1.0 + 3.0 * tiny as f64;
+
+    let sum = |lane_order: LaneOrder| {
+        let output_handle = client.empty(size_of::<f32>());
+        merge_line_kernel::launch::<Sum, R>(
+            &client,
+            CubeCount::new_single(),
+            CubeDim::new(1, 1, 1),
+            unsafe { ArrayArg::from_raw_parts::<f32>(&input_handle, 4, 4) },
+            unsafe { ArrayArg::from_raw_parts::<f32>(&output_handle, 1, 1) },
+            SumConfig {
+                compensated: false,
+                lane_order,
+            },
+        );
+        f32::from_bytes(&client.read_one(output_handle))[0] as f64
+    };
+
+    let serial = sum(LaneOrder::Serial);
+    let pairwise = sum(LaneOrder::Pairwise);
+    assert_eq!(serial, 1.0);
+    assert_eq!(pairwise, 1.0 + 2.0 * tiny as f64);
+    assert!((pairwise - exact).abs() < (serial - exact).abs());
+}
+
+/// The map of `sum(relu(x))`, as an example of a [ReduceMap] fused into a reduction.
+#[derive(Clone)]
+pub struct Relu;
+
+#[cube]
+impl ReduceMap for Relu {
+    fn apply<In: Numeric, Out: Numeric>(item: Line<In>) -> Line<Out> {
+        let item = Line::<Out>::cast_from(item);
+        let zero = Line::empty(item.size()).fill(Out::from_int(0));
+        select_many(item.greater_than(zero), item, zero)
+    }
+}
+
 #[macro_export]
 macro_rules! testgen_shared_sum {
     // Generate all the tests for a list of types.
@@ -87,6 +168,8 @@ macro_rules! testgen_reduce {
                     $crate::testgen_reduce!($float);
                 })*
             }
+
+            $crate::testgen_reduce!(@fixed_types);
         }
     };
 
@@ -95,6 +178,118 @@ macro_rules! testgen_reduce {
         mod test_reduce {
             use super::*;
             $crate::testgen_reduce!(f32);
+            $crate::testgen_reduce!(@fixed_types);
+        }
+    };
+
+    // Generate the tests using a fixed element type, once for all the float types.
+    (@fixed_types) => {
+        mod fixed_types {
+            use super::*;
+            use cubecl_reduce::test::TestCase;
+
+            $crate::testgen_reduce!(
+                @fixed_types
+                unit: [use_planes: false, shared: false],
+                plane: [use_planes: true, shared: false],
+                shared: [use_planes: false, shared: true]
+            );
+
+            #[test]
+            pub fn pairwise_lane_sum() {
+                $crate::test::test_pairwise_lane_sum::<TestRuntime>(&Default::default());
+            }
+        }
+    };
+
+    (@fixed_types $($id:ident: [use_planes: $use_planes:expr, shared: $shared:expr]),*) => {
+        ::paste::paste! {
+            $(
+                #[test]
+                pub fn [< popcount_ $id >]() {
+                    let test = TestCase {
+                        shape: [4, 64].into(),
+                        stride: [64, 1].into(),
+                        axis: Some(1),
+                        strategy: Some($crate::ReduceStrategy {
+                            use_planes: $use_planes,
+                            shared: $shared,
+                            shared_transpose: false,
+                            plane_dim: None,
+                            naive: false,
+                        }),
+                    };
+                    test.test_popcount::<TestRuntime>(&Default::default());
+                }
+
+                #[test]
+                pub fn [< compensated_sum_ $id >]() {
+                    let test = TestCase {
+                        shape: [4, 4096].into(),
+                        stride: [4096, 1].into(),
+                        axis: Some(1),
+                        strategy: Some($crate::ReduceStrategy {
+                            use_planes: $use_planes,
+                            shared: $shared,
+                            shared_transpose: false,
+                            plane_dim: None,
+                            naive: false,
+                        }),
+                    };
+                    test.test_compensated_sum::<TestRuntime>(&Default::default());
+                }
+
+                #[test]
+                pub fn [< count_equal_exact_ $id >]() {
+                    let test = TestCase {
+                        shape: [4, 64].into(),
+                        stride: [64, 1].into(),
+                        axis: Some(1),
+                        strategy: Some($crate::ReduceStrategy {
+                            use_planes: $use_planes,
+                            shared: $shared,
+                            shared_transpose: false,
+                            plane_dim: None,
+                            naive: false,
+                        }),
+                    };
+                    test.test_count_equal_exact::<TestRuntime>(&Default::default());
+                }
+
+                #[test]
+                pub fn [< packed_4bit_parallel_ $id >]() {
+                    let test = TestCase {
+                        shape: [4, 64].into(),
+                        stride: [64, 1].into(),
+                        axis: Some(1),
+                        strategy: Some($crate::ReduceStrategy {
+                            use_planes: $use_planes,
+                            shared: $shared,
+                            shared_transpose: false,
+                            plane_dim: None,
+                            naive: false,
+                        }),
+                    };
+                    test.test_packed_4bit::<TestRuntime>(&Default::default());
+                }
+
+                #[test]
+                pub fn [< packed_4bit_perpendicular_ $id >]() {
+                    let test = TestCase {
+                        shape: [16, 32].into(),
+                        stride: [32, 1].into(),
+                        axis: Some(0),
+                        strategy: Some($crate::ReduceStrategy {
+                            use_planes: $use_planes,
+                            shared: $shared,
+                            shared_transpose: false,
+                            plane_dim: None,
+                            naive: false,
+                        }),
+                    };
+                    test.test_packed_4bit::<TestRuntime>(&Default::default());
+                }
+            )*
         }
     };
 
@@ -204,6 +399,1097 @@ macro_rules! testgen_reduce {
                 }
             ]
         );
+
+        #[test]
+        pub fn shared_transpose_rank_three_tensor() {
+            let test = TestCase {
+                shape: [256, 256, 4].into(),
+                stride: [1024, 4, 1].into(),
+                axis: Some(1),
+                strategy: Some($crate::ReduceStrategy {
+                    use_planes: false,
+                    shared: false,
+                    shared_transpose: true,
+                    plane_dim: None,
+                    naive: false,
+                }),
+            };
+            test.test_against_naive::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn sum_plane_dim_64() {
+            let client = TestRuntime::client(&Default::default());
+            let hw_props = &client.properties().hardware;
+            if hw_props.plane_size_min > 64 || hw_props.plane_size_max < 64 {
+                println!(
+                    "Skipping test, the client only supports the plane sizes {}..={}",
+                    hw_props.plane_size_min, hw_props.plane_size_max
+                );
+                return;
+            }
+
+            for use_planes in [false, true] {
+                if use_planes && hw_props.plane_size_min != hw_props.plane_size_max {
+                    println!("Skipping plane instructions, the plane size of the client varies");
+                    continue;
+                }
+                let test = TestCase {
+                    shape: [64, 256].into(),
+                    stride: [256, 1].into(),
+                    axis: Some(1),
+                    strategy: Some($crate::ReduceStrategy {
+                        use_planes,
+                        shared: false,
+                        shared_transpose: false,
+                        plane_dim: Some(64),
+                        naive: false,
+                    }),
+                };
+                test.test_sum::<$float, TestRuntime>(&Default::default());
+            }
+        }
+
+        #[test]
+        pub fn sum_grid_stride_unit() {
+            let test = TestCase {
+                shape: [4096, 8].into(),
+                stride: [8, 1].into(),
+                axis: Some(1),
+                strategy: Some($crate::ReduceStrategy {
+                    use_planes: false,
+                    shared: false,
+                    shared_transpose: false,
+                    plane_dim: None,
+                    naive: false,
+                }),
+            };
+            test.test_sum_with_max_cube_count::<$float, TestRuntime>(&Default::default(), (2, 1, 1));
+        }
+
+        #[test]
+        pub fn sum_grid_stride_shared() {
+            let test = TestCase {
+                shape: [64, 256].into(),
+                stride: [256, 1].into(),
+                axis: Some(1),
+                strategy: Some($crate::ReduceStrategy {
+                    use_planes: false,
+                    shared: true,
+                    shared_transpose: false,
+                    plane_dim: None,
+                    naive: false,
+                }),
+            };
+            test.test_sum_with_max_cube_count::<$float, TestRuntime>(&Default::default(), (4, 2, 1));
+        }
+
+        #[test]
+        pub fn prod_masked_lanes_use_identity() {
+            let test = TestCase {
+                shape: [8, 3].into(),
+                stride: [3, 1].into(),
+                axis: Some(1),
+                strategy: Some($crate::ReduceStrategy {
+                    use_planes: true,
+                    shared: false,
+                    shared_transpose: false,
+                    plane_dim: None,
+                    naive: false,
+                }),
+            };
+            test.test_prod::<$float, TestRuntime>(&Default::default());
+            test.test_prod_identity::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn stable_prod_without_underflow() {
+            let test = TestCase {
+                shape: [2, 4096].into(),
+                stride: [4096, 1].into(),
+                axis: Some(1),
+                strategy: Some($crate::ReduceStrategy {
+                    use_planes: false,
+                    shared: false,
+                    shared_transpose: false,
+                    plane_dim: None,
+                    naive: false,
+                }),
+            };
+            test.test_stable_prod_underflow::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn kth_smallest_parallel() {
+            for use_planes in [false, true] {
+                let test = TestCase {
+                    shape: [4, 8].into(),
+                    stride: [8, 1].into(),
+                    axis: Some(1),
+                    strategy: Some($crate::ReduceStrategy {
+                        use_planes,
+                        shared: false,
+                        shared_transpose: false,
+                        plane_dim: None,
+                        naive: false,
+                    }),
+                };
+                test.test_kth_smallest::<$float, TestRuntime>(&Default::default());
+            }
+        }
+
+        #[test]
+        pub fn kth_smallest_perpendicular() {
+            for use_planes in [false, true] {
+                let test = TestCase {
+                    shape: [8, 4].into(),
+                    stride: [4, 1].into(),
+                    axis: Some(0),
+                    strategy: Some($crate::ReduceStrategy {
+                        use_planes,
+                        shared: false,
+                        shared_transpose: false,
+                        plane_dim: None,
+                        naive: false,
+                    }),
+                };
+                test.test_kth_smallest::<$float, TestRuntime>(&Default::default());
+            }
+        }
+
+        #[test]
+        pub fn concat_parallel() {
+            let test = TestCase {
+                shape: [8, 16].into(),
+                stride: [16, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_concat::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn concat_perpendicular() {
+            let test = TestCase {
+                shape: [12, 4, 8].into(),
+                stride: [32, 8, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_concat::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn moments2_parallel() {
+            for shared in [false, true] {
+                let test = TestCase {
+                    shape: [8, 16].into(),
+                    stride: [16, 1].into(),
+                    axis: Some(1),
+                    strategy: Some($crate::ReduceStrategy {
+                        use_planes: false,
+                        shared,
+                        shared_transpose: false,
+                        plane_dim: None,
+                        naive: false,
+                    }),
+                };
+                test.test_moments2::<$float, TestRuntime>(&Default::default());
+            }
+        }
+
+        #[test]
+        pub fn moments2_perpendicular() {
+            let test = TestCase {
+                shape: [16, 8].into(),
+                stride: [8, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_moments2::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn entropy_parallel() {
+            for use_planes in [false, true] {
+                let test = TestCase {
+                    shape: [8, 16].into(),
+                    stride: [16, 1].into(),
+                    axis: Some(1),
+                    strategy: Some($crate::ReduceStrategy {
+                        use_planes,
+                        shared: false,
+                        shared_transpose: false,
+                        plane_dim: None,
+                        naive: false,
+                    }),
+                };
+                test.test_entropy::<$float, TestRuntime>(&Default::default());
+            }
+        }
+
+        #[test]
+        pub fn entropy_perpendicular() {
+            for use_planes in [false, true] {
+                let test = TestCase {
+                    shape: [16, 8].into(),
+                    stride: [8, 1].into(),
+                    axis: Some(0),
+                    strategy: Some($crate::ReduceStrategy {
+                        use_planes,
+                        shared: false,
+                        shared_transpose: false,
+                        plane_dim: None,
+                        naive: false,
+                    }),
+                };
+                test.test_entropy::<$float, TestRuntime>(&Default::default());
+            }
+        }
+
+        #[test]
+        pub fn lp_norm_parallel() {
+            for use_planes in [false, true] {
+                let test = TestCase {
+                    shape: [8, 16].into(),
+                    stride: [16, 1].into(),
+                    axis: Some(1),
+                    strategy: Some($crate::ReduceStrategy {
+                        use_planes,
+                        shared: false,
+                        shared_transpose: false,
+                        plane_dim: None,
+                        naive: false,
+                    }),
+                };
+                for p in [1.0, 2.0, 3.0, f32::INFINITY] {
+                    test.test_lp_norm::<$float, TestRuntime>(&Default::default(), p);
+                }
+            }
+        }
+
+        #[test]
+        pub fn db_sum_parallel() {
+            for use_planes in [false, true] {
+                let test = TestCase {
+                    shape: [9, 16].into(),
+                    stride: [16, 1].into(),
+                    axis: Some(1),
+                    strategy: Some($crate::ReduceStrategy {
+                        use_planes,
+                        shared: false,
+                        shared_transpose: false,
+                        plane_dim: None,
+                        naive: false,
+                    }),
+                };
+                test.test_db_sum::<$float, TestRuntime>(&Default::default());
+            }
+        }
+
+        #[test]
+        pub fn db_sum_perpendicular() {
+            for use_planes in [false, true] {
+                let test = TestCase {
+                    shape: [16, 9].into(),
+                    stride: [9, 1].into(),
+                    axis: Some(0),
+                    strategy: Some($crate::ReduceStrategy {
+                        use_planes,
+                        shared: false,
+                        shared_transpose: false,
+                        plane_dim: None,
+                        naive: false,
+                    }),
+                };
+                test.test_db_sum::<$float, TestRuntime>(&Default::default());
+            }
+        }
+
+        #[test]
+        pub fn count_equal_tolerance_parallel() {
+            for use_planes in [false, true] {
+                let test = TestCase {
+                    shape: [8, 64].into(),
+                    stride: [64, 1].into(),
+                    axis: Some(1),
+                    strategy: Some($crate::ReduceStrategy {
+                        use_planes,
+                        shared: false,
+                        shared_transpose: false,
+                        plane_dim: None,
+                        naive: false,
+                    }),
+                };
+                test.test_count_equal_tolerance::<$float, TestRuntime>(&Default::default());
+            }
+        }
+
+        #[test]
+        pub fn count_equal_tolerance_perpendicular() {
+            for use_planes in [false, true] {
+                let test = TestCase {
+                    shape: [64, 8].into(),
+                    stride: [8, 1].into(),
+                    axis: Some(0),
+                    strategy: Some($crate::ReduceStrategy {
+                        use_planes,
+                        shared: false,
+                        shared_transpose: false,
+                        plane_dim: None,
+                        naive: false,
+                    }),
+                };
+                test.test_count_equal_tolerance::<$float, TestRuntime>(&Default::default());
+            }
+        }
+
+        #[test]
+        pub fn lp_norm_perpendicular() {
+            for use_planes in [false, true] {
+                let test = TestCase {
+                    shape: [16, 8].into(),
+                    stride: [8, 1].into(),
+                    axis: Some(0),
+                    strategy: Some($crate::ReduceStrategy {
+                        use_planes,
+                        shared: false,
+                        shared_transpose: false,
+                        plane_dim: None,
+                        naive: false,
+                    }),
+                };
+                for p in [1.0, 2.0, 3.0, f32::INFINITY] {
+                    test.test_lp_norm::<$float, TestRuntime>(&Default::default(), p);
+                }
+            }
+        }
+
+        #[test]
+        pub fn reduce_enqueue_back_to_back() {
+            let test = TestCase {
+                shape: [8, 16].into(),
+                stride: [16, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_reduce_enqueue::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn reduce_dyn_f32_output() {
+            let test = TestCase {
+                shape: [8, 16].into(),
+                stride: [16, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_reduce_dyn::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn trace_square_batched() {
+            let test = TestCase {
+                shape: [3, 5, 5].into(),
+                stride: [25, 5, 1].into(),
+                axis: None,
+                strategy: None,
+            };
+            test.test_trace::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn trace_wide_batched() {
+            let test = TestCase {
+                shape: [4, 3, 7].into(),
+                stride: [21, 7, 1].into(),
+                axis: None,
+                strategy: None,
+            };
+            test.test_trace::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn trace_tall() {
+            let test = TestCase {
+                shape: [9, 4].into(),
+                stride: [4, 1].into(),
+                axis: None,
+                strategy: None,
+            };
+            test.test_trace::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn permuted_swap_remaining_axes() {
+            let test = TestCase {
+                shape: [4, 6, 8].into(),
+                stride: [48, 8, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_permuted::<$float, TestRuntime>(&Default::default(), &[2, 0]);
+        }
+
+        #[test]
+        pub fn permuted_identity() {
+            let test = TestCase {
+                shape: [4, 6, 8].into(),
+                stride: [48, 8, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_permuted::<$float, TestRuntime>(&Default::default(), &[0, 2]);
+        }
+
+        #[test]
+        pub fn lower_rank_output_custom_strides() {
+            let test = TestCase {
+                shape: [2, 3, 4, 5].into(),
+                stride: [60, 20, 5, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_lower_rank_output::<$float, TestRuntime>(&Default::default(), &[1, 10, 2]);
+        }
+
+        #[test]
+        pub fn try_reduce_fallback_parallel() {
+            let test = TestCase {
+                shape: [8, 32].into(),
+                stride: [32, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_try_reduce_fallback::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn try_reduce_fallback_perpendicular() {
+            let test = TestCase {
+                shape: [32, 8].into(),
+                stride: [8, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_try_reduce_fallback::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn plane_local_even_chunks() {
+            let test = TestCase {
+                shape: [8, 64].into(),
+                stride: [64, 1].into(),
+                axis: None,
+                strategy: None,
+            };
+            test.test_plane_local::<$float, TestRuntime>(&Default::default(), 8);
+        }
+
+        #[test]
+        pub fn plane_local_uneven_chunks() {
+            let test = TestCase {
+                shape: [6, 50].into(),
+                stride: [50, 1].into(),
+                axis: None,
+                strategy: None,
+            };
+            test.test_plane_local::<$float, TestRuntime>(&Default::default(), 7);
+        }
+
+        #[test]
+        pub fn argmax_global_single_cube() {
+            let test = TestCase {
+                shape: [16, 100].into(),
+                stride: [100, 1].into(),
+                axis: None,
+                strategy: None,
+            };
+            test.test_argmax_global::<$float, TestRuntime>(
+                &Default::default(),
+                1,
+                TieBreak::LowestIndex,
+            );
+        }
+
+        #[test]
+        pub fn argmax_global_many_cubes() {
+            let test = TestCase {
+                shape: [64, 100].into(),
+                stride: [100, 1].into(),
+                axis: None,
+                strategy: None,
+            };
+            test.test_argmax_global::<$float, TestRuntime>(
+                &Default::default(),
+                7,
+                TieBreak::LowestIndex,
+            );
+        }
+
+        #[test]
+        pub fn argmax_global_many_cubes_highest_index() {
+            let test = TestCase {
+                shape: [64, 100].into(),
+                stride: [100, 1].into(),
+                axis: None,
+                strategy: None,
+            };
+            test.test_argmax_global::<$float, TestRuntime>(
+                &Default::default(),
+                7,
+                TieBreak::HighestIndex,
+            );
+        }
+
+        #[test]
+        pub fn cube_partials_sum_contiguous_axis() {
+            let test = TestCase {
+                shape: [6, 1024].into(),
+                stride: [1024, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_cube_partials_sum::<$float, TestRuntime>(&Default::default(), 3);
+        }
+
+        #[test]
+        pub fn cube_partials_sum_strided_axis() {
+            let test = TestCase {
+                shape: [4, 300, 5].into(),
+                stride: [1500, 5, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_cube_partials_sum::<$float, TestRuntime>(&Default::default(), 7);
+        }
+
+        #[test]
+        pub fn dot_product_parallel() {
+            let test = TestCase {
+                shape: [8, 16].into(),
+                stride: [16, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_dot_product::<$float, TestRuntime>(&Default::default(), true);
+        }
+
+        #[test]
+        pub fn dot_product_perpendicular() {
+            let test = TestCase {
+                shape: [16, 8].into(),
+                stride: [8, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_dot_product::<$float, TestRuntime>(&Default::default(), true);
+        }
+
+        #[test]
+        pub fn dot_product_without_squares() {
+            let test = TestCase {
+                shape: [4, 16, 8].into(),
+                stride: [128, 8, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_dot_product::<$float, TestRuntime>(&Default::default(), false);
+        }
+
+        #[test]
+        pub fn pool_2x2() {
+            let test = TestCase {
+                shape: [2, 4, 6].into(),
+                stride: [24, 6, 1].into(),
+                axis: None,
+                strategy: None,
+            };
+            test.test_pool::<$float, TestRuntime>(
+                &Default::default(),
+                &[2, 2],
+                &[2, 2],
+                PoolBoundary::Pad,
+            );
+        }
+
+        #[test]
+        pub fn pool_non_divisible_pad() {
+            let test = TestCase {
+                shape: [3, 5, 5].into(),
+                stride: [25, 5, 1].into(),
+                axis: None,
+                strategy: None,
+            };
+            test.test_pool::<$float, TestRuntime>(
+                &Default::default(),
+                &[2, 2],
+                &[2, 2],
+                PoolBoundary::Pad,
+            );
+        }
+
+        #[test]
+        pub fn pool_non_divisible_clamp() {
+            let test = TestCase {
+                shape: [3, 5, 5].into(),
+                stride: [25, 5, 1].into(),
+                axis: None,
+                strategy: None,
+            };
+            test.test_pool::<$float, TestRuntime>(
+                &Default::default(),
+                &[2, 2],
+                &[2, 2],
+                PoolBoundary::Clamp,
+            );
+        }
+
+        #[test]
+        pub fn pool_overlapping_windows() {
+            let test = TestCase {
+                shape: [2, 7, 6].into(),
+                stride: [42, 6, 1].into(),
+                axis: None,
+                strategy: None,
+            };
+            test.test_pool::<$float, TestRuntime>(
+                &Default::default(),
+                &[3, 3],
+                &[2, 1],
+                PoolBoundary::Pad,
+            );
+        }
+
+        #[test]
+        pub fn pool_wrap() {
+            let test = TestCase {
+                shape: [3, 5, 7].into(),
+                stride: [35, 7, 1].into(),
+                axis: None,
+                strategy: None,
+            };
+            test.test_pool::<$float, TestRuntime>(
+                &Default::default(),
+                &[3, 3],
+                &[2, 1],
+                PoolBoundary::Wrap,
+            );
+        }
+
+        #[test]
+        pub fn histogram_clamp() {
+            let test = TestCase {
+                shape: [6, 40].into(),
+                stride: [40, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_histogram::<$float, TestRuntime>(
+                &Default::default(),
+                HistogramOutliers::Clamp,
+            );
+        }
+
+        #[test]
+        pub fn histogram_drop_perpendicular() {
+            let test = TestCase {
+                shape: [40, 6].into(),
+                stride: [6, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_histogram::<$float, TestRuntime>(
+                &Default::default(),
+                HistogramOutliers::Drop,
+            );
+        }
+
+        #[test]
+        pub fn frobenius_norm_rank_three() {
+            let test = TestCase {
+                shape: [4, 6, 5].into(),
+                stride: [30, 5, 1].into(),
+                axis: None,
+                strategy: None,
+            };
+            test.test_frobenius_norm::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn quantiles_parallel() {
+            let test = TestCase {
+                shape: [6, 40].into(),
+                stride: [40, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_quantiles::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn quantiles_perpendicular() {
+            let test = TestCase {
+                shape: [40, 6].into(),
+                stride: [6, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_quantiles::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn gather_reduce_duplicates() {
+            let test = TestCase {
+                shape: [6, 16].into(),
+                stride: [16, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_gather_reduce::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn gather_reduce_perpendicular() {
+            let test = TestCase {
+                shape: [16, 6].into(),
+                stride: [6, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_gather_reduce::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn scatter_reduce_collisions() {
+            let test = TestCase {
+                shape: [6, 16].into(),
+                stride: [16, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_scatter_reduce::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn scatter_reduce_perpendicular() {
+            let test = TestCase {
+                shape: [16, 6].into(),
+                stride: [6, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_scatter_reduce::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn reduce_with_lengths_parallel() {
+            let test = TestCase {
+                shape: [6, 10].into(),
+                stride: [10, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_reduce_with_lengths::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn reduce_with_lengths_perpendicular() {
+            let test = TestCase {
+                shape: [10, 6].into(),
+                stride: [6, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_reduce_with_lengths::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn segmented_scan_parallel() {
+            let test = TestCase {
+                shape: [4, 20].into(),
+                stride: [20, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_segmented_scan::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn segmented_scan_perpendicular() {
+            let test = TestCase {
+                shape: [20, 4].into(),
+                stride: [4, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_segmented_scan::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn all_strategies_against_naive() {
+            let test = TestCase {
+                shape: [8, 64].into(),
+                stride: [64, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_all_strategies_against_naive::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn all_strategies_against_naive_perpendicular() {
+            let test = TestCase {
+                shape: [64, 8].into(),
+                stride: [8, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_all_strategies_against_naive::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn softmax_axis() {
+            let test = TestCase {
+                shape: [6, 40].into(),
+                stride: [40, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_softmax::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn softmax_axis_perpendicular() {
+            let test = TestCase {
+                shape: [40, 6].into(),
+                stride: [6, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_softmax::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn l1_normalize_axis_zero() {
+            let test = TestCase {
+                shape: [6, 40].into(),
+                stride: [40, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_l1_normalize::<$float, TestRuntime>(
+                &Default::default(),
+                ZeroSumPolicy::Zero,
+            );
+        }
+
+        #[test]
+        pub fn l1_normalize_axis_uniform_perpendicular() {
+            let test = TestCase {
+                shape: [40, 6].into(),
+                stride: [6, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_l1_normalize::<$float, TestRuntime>(
+                &Default::default(),
+                ZeroSumPolicy::Uniform,
+            );
+        }
+
+        #[test]
+        pub fn rounding_toward_zero() {
+            let test = TestCase {
+                shape: [4, 2].into(),
+                stride: [2, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_rounding::<$float, TestRuntime>(
+                &Default::default(),
+                cubecl_reduce::ReduceRounding::TowardZero,
+            );
+        }
+
+        #[test]
+        pub fn rounding_up() {
+            let test = TestCase {
+                shape: [4, 2].into(),
+                stride: [2, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_rounding::<$float, TestRuntime>(
+                &Default::default(),
+                cubecl_reduce::ReduceRounding::Up,
+            );
+        }
+
+        #[test]
+        pub fn rounding_down() {
+            let test = TestCase {
+                shape: [4, 2].into(),
+                stride: [2, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_rounding::<$float, TestRuntime>(
+                &Default::default(),
+                cubecl_reduce::ReduceRounding::Down,
+            );
+        }
+
+        #[test]
+        pub fn subnormals_flush_to_zero() {
+            let test = TestCase {
+                shape: [8, 32].into(),
+                stride: [32, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_subnormals::<$float, TestRuntime>(
+                &Default::default(),
+                SubnormalPolicy::FlushToZero,
+            );
+        }
+
+        #[test]
+        pub fn subnormals_preserve() {
+            let test = TestCase {
+                shape: [8, 32].into(),
+                stride: [32, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_subnormals::<$float, TestRuntime>(
+                &Default::default(),
+                SubnormalPolicy::Preserve,
+            );
+        }
+
+        #[test]
+        pub fn weighted_parallel() {
+            let test = TestCase {
+                shape: [8, 16].into(),
+                stride: [16, 1].into(),
+                axis: Some(1),
+                strategy: None,
+            };
+            test.test_weighted::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn weighted_perpendicular() {
+            let test = TestCase {
+                shape: [16, 8].into(),
+                stride: [8, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_weighted::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn map_reduce_selected_strategy() {
+            let test = TestCase {
+                shape: [16, 8].into(),
+                stride: [8, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_map_reduce_relu_sum::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn map_reduce_unsupported_strategies() {
+            let test = TestCase {
+                shape: [16, 8].into(),
+                stride: [8, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_map_reduce_unsupported_strategies::<$float, TestRuntime>(
+                &Default::default(),
+            );
+        }
+
+        #[test]
+        pub fn weighted_naive_unsupported() {
+            let test = TestCase {
+                shape: [8, 16].into(),
+                stride: [16, 1].into(),
+                axis: Some(1),
+                strategy: Some($crate::ReduceStrategy::naive()),
+            };
+            test.test_weighted::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn mean_update_two_chunks() {
+            let test = TestCase {
+                shape: [8, 16].into(),
+                stride: [16, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_mean_update::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn decayed_max_update_chunks() {
+            let test = TestCase {
+                shape: [8, 16].into(),
+                stride: [16, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_decayed_update::<$float, TestRuntime>(&Default::default(), false);
+        }
+
+        #[test]
+        pub fn decayed_min_update_chunks() {
+            let test = TestCase {
+                shape: [8, 16].into(),
+                stride: [16, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_decayed_update::<$float, TestRuntime>(&Default::default(), true);
+        }
+
+        #[test]
+        pub fn sum_update_two_chunks() {
+            let test = TestCase {
+                shape: [8, 16].into(),
+                stride: [16, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_sum_update::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn sum_update_reused_scratch() {
+            let test = TestCase {
+                shape: [8, 16].into(),
+                stride: [16, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_sum_update_with_scratch::<$float, TestRuntime>(&Default::default());
+        }
+
+        #[test]
+        pub fn argmax_update_unsupported() {
+            let test = TestCase {
+                shape: [8, 16].into(),
+                stride: [16, 1].into(),
+                axis: Some(0),
+                strategy: None,
+            };
+            test.test_argmax_update_unsupported::<$float, TestRuntime>(&Default::default());
+        }
     };
 }
 
@@ -266,60 +1552,262 @@ macro_rules! impl_test_reduce_with_strategy {
                         shape: $shape.into(),
                         stride: $stride.into(),
                         axis: Some($axis),
-                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared }),
+                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared, shared_transpose: false, plane_dim: None, naive: false }),
                     };
                     test.test_argmax::<$float, TestRuntime>(&Default::default());
                 }
 
 
                 #[test]
-                pub fn [< argmin_plane_ $use_planes _shared_ $shared _ $id >]() {
+                pub fn [< argmax_repeated_max_plane_ $use_planes _shared_ $shared _ $id >]() {
                     let test = TestCase {
                         shape: $shape.into(),
                         stride: $stride.into(),
                         axis: Some($axis),
-                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared }),
+                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared, shared_transpose: false, plane_dim: None, naive: false }),
                     };
-                    test.test_argmin::<$float, TestRuntime>(&Default::default());
+                    test.test_argmax_repeated_max::<$float, TestRuntime>(&Default::default());
                 }
 
                 #[test]
-                pub fn [< mean_plane_ $use_planes _shared_ $shared _ $id >]() {
+                pub fn [< arg_tie_break_lowest_plane_ $use_planes _shared_ $shared _ $id >]() {
                     let test = TestCase {
                         shape: $shape.into(),
                         stride: $stride.into(),
                         axis: Some($axis),
-                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared }),
+                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared, shared_transpose: false, plane_dim: None, naive: false }),
                     };
-                    test.test_mean::<$float, TestRuntime>(&Default::default());
+                    test.test_arg_tie_break::<$float, TestRuntime>(&Default::default(), TieBreak::LowestIndex);
                 }
 
                 #[test]
-                pub fn [< prod_plane_ $use_planes _shared_ $shared _ $id >]() {
+                pub fn [< arg_tie_break_highest_plane_ $use_planes _shared_ $shared _ $id >]() {
                     let test = TestCase {
                         shape: $shape.into(),
                         stride: $stride.into(),
                         axis: Some($axis),
-                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared }),
+                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared, shared_transpose: false, plane_dim: None, naive: false }),
                     };
-                    test.test_prod::<$float, TestRuntime>(&Default::default());
+                    test.test_arg_tie_break::<$float, TestRuntime>(&Default::default(), TieBreak::HighestIndex);
                 }
 
                 #[test]
-                pub fn [< sum_plane_ $use_planes _shared_ $shared _ $id >]() {
+                pub fn [< max_min_infinities_plane_ $use_planes _shared_ $shared _ $id >]() {
                     let test = TestCase {
                         shape: $shape.into(),
                         stride: $stride.into(),
                         axis: Some($axis),
-                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared }),
+                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared, shared_transpose: false, plane_dim: None, naive: false }),
                     };
-                    test.test_sum::<$float, TestRuntime>(&Default::default());
+                    test.test_max_min_infinities::<$float, TestRuntime>(&Default::default());
+                }
+
+                #[test]
+                pub fn [< max_min_infinities_with_nan_plane_ $use_planes _shared_ $shared _ $id >]() {
+                    let test = TestCase {
+                        shape: $shape.into(),
+                        stride: $stride.into(),
+                        axis: Some($axis),
+                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared, shared_transpose: false, plane_dim: None, naive: false }),
+                    };
+                    test.test_max_min_infinities_with_nan::<$float, TestRuntime>(&Default::default());
+                }
+
+                #[test]
+                pub fn [< range_plane_ $use_planes _shared_ $shared _ $id >]() {
+                    let test = TestCase {
+                        shape: $shape.into(),
+                        stride: $stride.into(),
+                        axis: Some($axis),
+                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared, shared_transpose: false, plane_dim: None, naive: false }),
+                    };
+                    test.test_range::<$float, TestRuntime>(&Default::default());
+                }
+
+                #[test]
+                pub fn [< range_with_nan_plane_ $use_planes _shared_ $shared _ $id >]() {
+                    let test = TestCase {
+                        shape: $shape.into(),
+                        stride: $stride.into(),
+                        axis: Some($axis),
+                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared, shared_transpose: false, plane_dim: None, naive: false }),
+                    };
+                    test.test_range_with_nan::<$float, TestRuntime>(&Default::default());
+                }
+
+                #[test]
+                pub fn [< all_equal_flag_plane_ $use_planes _shared_ $shared _ $id >]() {
+                    let test = TestCase {
+                        shape: $shape.into(),
+                        stride: $stride.into(),
+                        axis: Some($axis),
+                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared, shared_transpose: false, plane_dim: None, naive: false }),
+                    };
+                    test.test_all_equal_flag::<$float, TestRuntime>(&Default::default());
+                }
+
+                #[test]
+                pub fn [< all_equal_value_plane_ $use_planes _shared_ $shared _ $id >]() {
+                    let test = TestCase {
+                        shape: $shape.into(),
+                        stride: $stride.into(),
+                        axis: Some($axis),
+                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared, shared_transpose: false, plane_dim: None, naive: false }),
+                    };
+                    test.test_all_equal_value::<$float, TestRuntime>(&Default::default());
+                }
+
+                #[test]
+                pub fn [< centroid_plane_ $use_planes _shared_ $shared _ $id >]() {
+                    let test = TestCase {
+                        shape: $shape.into(),
+                        stride: $stride.into(),
+                        axis: Some($axis),
+                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared, shared_transpose: false, plane_dim: None, naive: false }),
+                    };
+                    test.test_centroid::<$float, TestRuntime>(&Default::default());
+                }
+
+                #[test]
+                pub fn [< centroid_sentinel_plane_ $use_planes _shared_ $shared _ $id >]() {
+                    let test = TestCase {
+                        shape: $shape.into(),
+                        stride: $stride.into(),
+                        axis: Some($axis),
+                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared, shared_transpose: false, plane_dim: None, naive: false }),
+                    };
+                    test.test_centroid_sentinel::<$float, TestRuntime>(&Default::default());
+                }
+
+                #[test]
+                pub fn [< map_reduce_relu_sum_plane_ $use_planes _shared_ $shared _ $id >]() {
+                    let test = TestCase {
+                        shape: $shape.into(),
+                        stride: $stride.into(),
+                        axis: Some($axis),
+                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared, shared_transpose: false, plane_dim: None, naive: false }),
+                    };
+                    test.test_map_reduce_relu_sum::<$float, TestRuntime>(&Default::default());
+                }
+
+                #[test]
+                pub fn [< argmin_plane_ $use_planes _shared_ $shared _ $id >]() {
+                    let test = TestCase {
+                        shape: $shape.into(),
+                        stride: $stride.into(),
+                        axis: Some($axis),
+                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared, shared_transpose: false, plane_dim: None, naive: false }),
+                    };
+                    test.test_argmin::<$float, TestRuntime>(&Default::default());
+                }
+
+                #[test]
+                pub fn [< mean_plane_ $use_planes _shared_ $shared _ $id >]() {
+                    let test = TestCase {
+                        shape: $shape.into(),
+                        stride: $stride.into(),
+                        axis: Some($axis),
+                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared, shared_transpose: false, plane_dim: None, naive: false }),
+                    };
+                    test.test_mean::<$float, TestRuntime>(&Default::default());
+                }
+
+                #[test]
+                pub fn [< prod_plane_ $use_planes _shared_ $shared _ $id >]() {
+                    let test = TestCase {
+                        shape: $shape.into(),
+                        stride: $stride.into(),
+                        axis: Some($axis),
+                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared, shared_transpose: false, plane_dim: None, naive: false }),
+                    };
+                    test.test_prod::<$float, TestRuntime>(&Default::default());
+                }
+
+                #[test]
+                pub fn [< sum_plane_ $use_planes _shared_ $shared _ $id >]() {
+                    let test = TestCase {
+                        shape: $shape.into(),
+                        stride: $stride.into(),
+                        axis: Some($axis),
+                        strategy: Some($crate::ReduceStrategy { use_planes: $use_planes, shared: $shared, shared_transpose: false, plane_dim: None, naive: false }),
+                    };
+                    test.test_sum::<$float, TestRuntime>(&Default::default());
+                }
+            )*
+        }
+    };
+}
+
+// Generate the tests of the integer sum overflow modes, using `u8`.
+#[macro_export]
+macro_rules! testgen_reduce_overflow {
+    () => {
+        mod test_reduce_overflow {
+            use super::*;
+            use cubecl_reduce::test::TestCase;
+
+            $crate::testgen_reduce_overflow!(
+                unit: [use_planes: false, shared: false],
+                plane: [use_planes: true, shared: false],
+                shared: [use_planes: false, shared: true]
+            );
+        }
+    };
+
+    ($($id:ident: [use_planes: $use_planes:expr, shared: $shared:expr]),*) => {
+        ::paste::paste! {
+            $(
+                #[test]
+                pub fn [< integer_sum_overflow_ $id >]() {
+                    let test = TestCase {
+                        shape: [4, 64].into(),
+                        stride: [64, 1].into(),
+                        axis: Some(1),
+                        strategy: Some($crate::ReduceStrategy {
+                            use_planes: $use_planes,
+                            shared: $shared,
+                            shared_transpose: false,
+                            plane_dim: None,
+                            naive: false,
+                        }),
+                    };
+                    test.test_integer_sum_overflow::<TestRuntime>(&Default::default());
                 }
             )*
         }
     };
 }
 
+/// Print the timings of every reduce strategy supported by the runtime on the
+/// [default shapes](benchmark::default_benchmark_shapes), as an ignored test.
+///
+/// Run with `cargo test --features export_tests -- --ignored reduce_benchmark --nocapture`.
+#[macro_export]
+macro_rules! testgen_reduce_benchmark {
+    () => {
+        mod test_reduce_benchmark {
+            use super::*;
+            use cubecl_reduce::test::benchmark::{
+                benchmark_reduce_strategies, default_benchmark_shapes,
+            };
+
+            #[test]
+            #[ignore = "benchmark"]
+            pub fn reduce_benchmark_sum_f32() {
+                let client = TestRuntime::client(&Default::default());
+                let results = benchmark_reduce_strategies::<
+                    TestRuntime,
+                    f32,
+                    cubecl_reduce::instructions::Sum,
+                >(&client, &default_benchmark_shapes(), 10);
+                for result in results {
+                    println!("{result}");
+                }
+            }
+        }
+    };
+}
+
 #[derive(Debug)]
 pub struct TestCase {
     pub shape: Vec<usize>,
@@ -340,181 +1828,3928 @@ impl TestCase {
             Some(axis) if self.stride[axis] == 0 => vec![0; input_values.len()],
             _ => self.cpu_argmax(&input_values),
         };
-        self.run_reduce_test::<F, u32, R, ArgMax>(device, input_values, expected_values)
-    }
+        self.run_reduce_test::<F, u32, R, ArgMax>(device, input_values, expected_values)
+    }
+
+    fn cpu_argmax<F: Float>(&self, values: &[F]) -> Vec<u32> {
+        let mut expected = vec![(F::min_value(), 0_u32); self.num_output_values()];
+        for (input_index, &value) in values.iter().enumerate() {
+            if let Some(output_index) = self.to_output_index(input_index) {
+                let (best, _) = expected[output_index];
+                if value > best {
+                    let coordinate = self.to_input_coordinate(input_index).unwrap();
+                    expected[output_index] = (value, coordinate[self.axis.unwrap()] as u32);
+                }
+            }
+        }
+        expected.into_iter().map(|(_, i)| i).collect()
+    }
+
+    /// Reduce with a stable [ArgMax] an input where the maximum is repeated many times
+    /// along the axis, so the lowest coordinate must win over every strategy.
+    pub fn test_argmax_repeated_max<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let max = F::EI::new(1.0);
+        let input_values: Vec<F::EI> = self
+            .random_input_values()
+            .into_iter()
+            .map(|value| if value > max { max } else { value })
+            .collect();
+        let expected_values = match self.axis {
+            Some(axis) if self.stride[axis] == 0 => vec![0; input_values.len()],
+            _ => self.cpu_argmax(&input_values),
+        };
+        self.run_reduce_test_with_config::<F, u32, R, ArgMax>(
+            device,
+            input_values,
+            expected_values,
+            TieBreak::LowestIndex,
+            R::max_cube_count(),
+        )
+    }
+
+    /// Reduce with [ArgMax] and [ArgMin] an input where both extrema are repeated many times
+    /// along the axis, so the coordinate chosen by `tie_break` must win over every strategy.
+    pub fn test_arg_tie_break<F, R>(&self, device: &R::Device, tie_break: TieBreak)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let max = F::EI::new(1.0);
+        let min = F::EI::new(-1.0);
+        let input_values: Vec<F::EI> = self
+            .random_input_values()
+            .into_iter()
+            .map(|value| if value > max { max } else { value })
+            .map(|value| if value < min { min } else { value })
+            .collect();
+        let expected_argmax = self.cpu_arg_tie_break(&input_values, false, tie_break);
+        let expected_argmin = self.cpu_arg_tie_break(&input_values, true, tie_break);
+
+        self.run_reduce_test_with_config::<F, u32, R, ArgMax>(
+            device,
+            input_values.clone(),
+            expected_argmax,
+            tie_break,
+            R::max_cube_count(),
+        );
+        self.run_reduce_test_with_config::<F, u32, R, ArgMin>(
+            device,
+            input_values,
+            expected_argmin,
+            tie_break,
+            R::max_cube_count(),
+        );
+    }
+
+    /// The coordinates of the maximum of each slice, or of the minimum if `min` is set, chosen by
+    /// `tie_break` among equal extrema.
+    fn cpu_arg_tie_break<F: Float>(
+        &self,
+        values: &[F],
+        min: bool,
+        tie_break: TieBreak,
+    ) -> Vec<u32> {
+        let axis = self.axis.unwrap();
+        let mut expected: Vec<Option<(F, u32)>> = vec![None; self.num_output_values()];
+        for flat_index in 0..self.shape.iter().product::<usize>() {
+            let mut coordinate = vec![0; self.shape.len()];
+            let mut remainder = flat_index;
+            for i in (0..self.shape.len()).rev() {
+                coordinate[i] = remainder % self.shape[i];
+                remainder /= self.shape[i];
+            }
+            let input_index = coordinate
+                .iter()
+                .zip(&self.stride)
+                .map(|(coordinate, stride)| coordinate * stride)
+                .sum::<usize>();
+            let value = values[input_index];
+            let position = coordinate[axis] as u32;
+            coordinate[axis] = 0;
+            let output_index = self.from_output_coordinate(coordinate);
+
+            let wins = match expected[output_index] {
+                None => true,
+                Some((best, best_position)) => {
+                    let better = if min { value < best } else { value > best };
+                    let wins_tie = match tie_break {
+                        TieBreak::LowestIndex => position < best_position,
+                        TieBreak::HighestIndex => position > best_position,
+                    };
+                    better || (value == best && wins_tie)
+                }
+            };
+            if wins {
+                expected[output_index] = Some((value, position));
+            }
+        }
+        expected.into_iter().map(|it| it.unwrap().1).collect()
+    }
+
+    /// Find the position of the maximum of the whole input with [reduce_argmax_global] over
+    /// `cube_count` cubes, where the maximum is repeated in many chunks, so the position chosen by
+    /// `tie_break` must win across the cubes.
+    pub fn test_argmax_global<F, R>(&self, device: &R::Device, cube_count: u32, tie_break: TieBreak)
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let max = F::EI::new(1.0);
+        let input_values: Vec<F::EI> = self
+            .random_input_values()
+            .into_iter()
+            .map(|value| if value > max { max } else { value })
+            .collect();
+        let expected = input_values
+            .iter()
+            .enumerate()
+            .fold(
+                (F::EI::min_value(), 0),
+                |(best, position), (index, &value)| {
+                    let wins_tie = tie_break == TieBreak::HighestIndex && value == best;
+                    if value > best || wins_tie {
+                        (value, index as u32)
+                    } else {
+                        (best, position)
+                    }
+                },
+            )
+            .1;
+
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let output_handle = client.empty(size_of::<u32>());
+        let output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(&output_handle, &[1], &[1], size_of::<u32>())
+        };
+
+        reduce_argmax_global::<R, F, u32>(&client, input, output, cube_count, tie_break).unwrap();
+
+        let bytes = client.read_one(output_handle);
+        assert_eq!(u32::from_bytes(&bytes), &[expected]);
+    }
+
+    /// Reduce with [Max] and [Min] an input where each slice is either only made of the
+    /// infinity of the identity, finite values mixed with it, or finite values with the other
+    /// infinity, against a reduction on the host.
+    pub fn test_max_min_infinities<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        for is_max in [true, false] {
+            let input_values = self.infinite_input_values::<F::EI>(is_max, false);
+            let expected_values = self.cpu_max_min(&input_values, is_max);
+            if is_max {
+                self.run_reduce_test::<F, F::EI, R, Max>(device, input_values, expected_values);
+            } else {
+                self.run_reduce_test::<F, F::EI, R, Min>(device, input_values, expected_values);
+            }
+        }
+    }
+
+    /// Reduce with [Max] and [Min] an input where each slice mixes infinities with a NaN,
+    /// which must give NaN.
+    ///
+    /// Plane instructions handle NaN as defined by the backend, so nothing is tested with planes.
+    pub fn test_max_min_infinities_with_nan<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        if self.strategy.is_some_and(|strategy| strategy.use_planes) {
+            return;
+        }
+
+        for is_max in [true, false] {
+            let input_values = self.infinite_input_values::<F::EI>(is_max, true);
+            let expected_values = self.cpu_max_min(&input_values, is_max);
+            if is_max {
+                self.run_reduce_test::<F, F::EI, R, Max>(device, input_values, expected_values);
+            } else {
+                self.run_reduce_test::<F, F::EI, R, Min>(device, input_values, expected_values);
+            }
+        }
+    }
+
+    /// Random values where each slice along the axis follows one of the patterns of
+    /// [TestCase::test_max_min_infinities], or with a NaN when `with_nan` is true,
+    /// using `-inf` as the infinity of the identity when `is_max` is true and `+inf` otherwise.
+    fn infinite_input_values<F: Float>(&self, is_max: bool, with_nan: bool) -> Vec<F> {
+        let (identity, other) = if is_max {
+            (f32::NEG_INFINITY, f32::INFINITY)
+        } else {
+            (f32::INFINITY, f32::NEG_INFINITY)
+        };
+        let axis = self.axis.unwrap();
+        let length = self.shape[axis];
+
+        let mut values: Vec<F> = self.random_input_values();
+        for (index, value) in values.iter_mut().enumerate() {
+            let (Some(output_index), Some(coordinate)) =
+                (self.to_output_index(index), self.to_input_coordinate(index))
+            else {
+                continue;
+            };
+            let coordinate = coordinate[axis];
+
+            let pattern = if with_nan {
+                3 + output_index % 2
+            } else {
+                output_index % 3
+            };
+            match pattern {
+                0 => *value = F::new(identity),
+                1 if coordinate % 3 == 0 => *value = F::new(identity),
+                2 if coordinate == length / 2 => *value = F::new(other),
+                3 if coordinate == length / 2 => *value = F::new(f32::NAN),
+                3 => *value = F::new(identity),
+                4 if coordinate == 0 => *value = F::new(other),
+                4 if coordinate == length - 1 => *value = F::new(f32::NAN),
+                _ => {}
+            }
+        }
+        values
+    }
+
+    /// The maximum, or the minimum when `is_max` is false, of each slice along the axis,
+    /// giving NaN when the slice contains a NaN.
+    fn cpu_max_min<F: Float>(&self, values: &[F], is_max: bool) -> Vec<F> {
+        let identity = if is_max {
+            f32::NEG_INFINITY
+        } else {
+            f32::INFINITY
+        };
+        let mut expected = vec![identity; self.num_output_values()];
+        for (input_index, value) in values.iter().enumerate() {
+            if let Some(output_index) = self.to_output_index(input_index) {
+                let best = expected[output_index];
+                let value = value.to_f32().unwrap();
+                expected[output_index] = if best.is_nan() || value.is_nan() {
+                    f32::NAN
+                } else if is_max {
+                    best.max(value)
+                } else {
+                    best.min(value)
+                };
+            }
+        }
+        expected.into_iter().map(F::new).collect()
+    }
+
+    /// Reduce with [Range] an input where every other slice along the axis is constant,
+    /// against the difference of the extrema computed on the host.
+    pub fn test_range<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let input_values = self.range_input_values::<F::EI>(false);
+        let expected_values = self.cpu_range(&input_values);
+        self.run_reduce_test::<F, F::EI, R, Range>(device, input_values, expected_values);
+    }
+
+    /// Reduce with [Range] an input where every other slice along the axis contains a NaN,
+    /// which must give NaN.
+    ///
+    /// Plane instructions handle NaN as defined by the backend, so nothing is tested with planes.
+    pub fn test_range_with_nan<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        if self.strategy.is_some_and(|strategy| strategy.use_planes) {
+            return;
+        }
+
+        let input_values = self.range_input_values::<F::EI>(true);
+        let expected_values = self.cpu_range(&input_values);
+        self.run_reduce_test::<F, F::EI, R, Range>(device, input_values, expected_values);
+    }
+
+    /// Random values where every other slice along the axis is either constant,
+    /// or contains a NaN when `with_nan` is true.
+    fn range_input_values<F: Float>(&self, with_nan: bool) -> Vec<F> {
+        let axis = self.axis.unwrap();
+        let length = self.shape[axis];
+
+        let mut values: Vec<F> = self.random_input_values();
+        for (index, value) in values.iter_mut().enumerate() {
+            let (Some(output_index), Some(coordinate)) =
+                (self.to_output_index(index), self.to_input_coordinate(index))
+            else {
+                continue;
+            };
+            if output_index % 2 == 0 {
+                continue;
+            }
+
+            if !with_nan {
+                *value = F::new(0.75);
+            } else if coordinate[axis] == length / 2 {
+                *value = F::new(f32::NAN);
+            }
+        }
+        values
+    }
+
+    /// The difference of the maximum and the minimum of each slice along the axis.
+    fn cpu_range<F: Float>(&self, values: &[F]) -> Vec<F> {
+        self.cpu_max_min(values, true)
+            .into_iter()
+            .zip(self.cpu_max_min(values, false))
+            .map(|(max, min)| max - min)
+            .collect()
+    }
+
+    /// Reduce with [AllEqual] for the flag an input whose slices along the axis are in turn
+    /// constant, constant except for one slightly different item, and random.
+    pub fn test_all_equal_flag<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let axis = self.axis.unwrap();
+        let length = self.shape[axis];
+
+        let mut input_values: Vec<F::EI> = self.random_input_values();
+        for (index, value) in input_values.iter_mut().enumerate() {
+            let (Some(output_index), Some(coordinate)) =
+                (self.to_output_index(index), self.to_input_coordinate(index))
+            else {
+                continue;
+            };
+            match output_index % 3 {
+                0 => *value = F::EI::new(0.75),
+                1 if coordinate[axis] == length / 2 => *value = F::EI::new(0.8125),
+                1 => *value = F::EI::new(0.75),
+                _ => {}
+            }
+        }
+        let expected_values = self.cpu_all_equal(&input_values);
+
+        self.run_reduce_test_with_config::<F, F::EI, R, AllEqual>(
+            device,
+            input_values,
+            expected_values,
+            AllEqualConfig {
+                output: AllEqualOutput::Flag,
+            },
+            R::max_cube_count(),
+        );
+    }
+
+    /// Reduce with [AllEqual] for the value an input where each slice along the axis is constant.
+    pub fn test_all_equal_value<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let constant = |output_index: usize| F::EI::new((output_index % 8) as f32 * 0.25 - 1.0);
+
+        let mut input_values: Vec<F::EI> = self.random_input_values();
+        for (index, value) in input_values.iter_mut().enumerate() {
+            if let Some(output_index) = self.to_output_index(index) {
+                *value = constant(output_index);
+            }
+        }
+        let expected_values = (0..self.num_output_values()).map(constant).collect();
+
+        self.run_reduce_test_with_config::<F, F::EI, R, AllEqual>(
+            device,
+            input_values,
+            expected_values,
+            AllEqualConfig {
+                output: AllEqualOutput::Value,
+            },
+            R::max_cube_count(),
+        );
+    }
+
+    /// `1` for each slice along the axis whose items are all equal, `0` otherwise.
+    fn cpu_all_equal<F: Float>(&self, values: &[F]) -> Vec<F> {
+        let mut seen = vec![None; self.num_output_values()];
+        let mut expected = vec![F::new(1.0); self.num_output_values()];
+        for (input_index, value) in values.iter().enumerate() {
+            if let Some(output_index) = self.to_output_index(input_index) {
+                match seen[output_index] {
+                    None => seen[output_index] = Some(*value),
+                    Some(first) if first != *value => expected[output_index] = F::new(0.0),
+                    Some(_) => {}
+                }
+            }
+        }
+        expected
+    }
+
+    /// Reduce with [Centroid] an input of positive weights, except for every fourth slice along
+    /// the axis which has no weight and outputs NaN.
+    pub fn test_centroid<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        self.run_centroid_test::<F, R>(device, CentroidConfig::nan());
+    }
+
+    /// Same as [test_centroid](Self::test_centroid), with the slices without weight outputting
+    /// `-1`.
+    pub fn test_centroid_sentinel<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        self.run_centroid_test::<F, R>(device, CentroidConfig::sentinel(-1.0));
+    }
+
+    fn run_centroid_test<F, R>(&self, device: &R::Device, config: CentroidConfig)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let mut input_values: Vec<F::EI> = self.random_input_values();
+        for (index, value) in input_values.iter_mut().enumerate() {
+            if let Some(output_index) = self.to_output_index(index) {
+                *value = match output_index % 4 {
+                    0 => F::EI::new(0.0),
+                    _ => F::EI::new(value.to_f32().unwrap().abs() + 0.125),
+                };
+            }
+        }
+        let expected_values = self.cpu_centroid(&input_values, config.zero_weight());
+
+        self.run_reduce_test_with_config::<F, F::EI, R, Centroid>(
+            device,
+            input_values,
+            expected_values,
+            config,
+            R::max_cube_count(),
+        );
+    }
+
+    /// `sum(i * x_i) / sum(x_i)` for each slice along the axis, or `zero_weight` if the weights
+    /// sum to zero.
+    fn cpu_centroid<F: Float>(&self, values: &[F], zero_weight: f32) -> Vec<F> {
+        let axis = self.axis.unwrap();
+        let mut weighted = vec![0.0; self.num_output_values()];
+        let mut weights = vec![0.0; self.num_output_values()];
+        for (input_index, value) in values.iter().enumerate() {
+            if let (Some(output_index), Some(coordinate)) = (
+                self.to_output_index(input_index),
+                self.to_input_coordinate(input_index),
+            ) {
+                let value = value.to_f32().unwrap();
+                weighted[output_index] += coordinate[axis] as f32 * value;
+                weights[output_index] += value;
+            }
+        }
+        weighted
+            .into_iter()
+            .zip(weights)
+            .map(|(weighted, weights)| match weights == 0.0 {
+                true => F::new(zero_weight),
+                false => F::new(weighted / weights),
+            })
+            .collect()
+    }
+
+    /// Check that [map_reduce] rejects the naive strategy, and the shared transpose strategy for
+    /// the contiguous input of this case reduced along an axis that isn't the innermost one.
+    pub fn test_map_reduce_unsupported_strategies<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let output_handle = client.empty(self.num_output_values() * size_of::<F::EI>());
+        let mut output_shape = self.shape.clone();
+        output_shape[self.axis.unwrap()] = 1;
+        let output_stride = self.output_stride();
+
+        let shared_transpose = ReduceStrategy {
+            use_planes: false,
+            shared: false,
+            shared_transpose: true,
+            plane_dim: None,
+            naive: false,
+        };
+        for (strategy, expected) in [
+            (ReduceStrategy::naive(), ReduceError::NaiveUnsupported),
+            (shared_transpose, ReduceError::SharedTransposeUnsupported),
+        ] {
+            let result = map_reduce::<R, F::EI, Relu, F, F::EI, Sum>(
+                &client,
+                unsafe {
+                    TensorHandleRef::<R>::from_raw_parts(
+                        &input_handle,
+                        &self.stride,
+                        &self.shape,
+                        size_of::<F::EI>(),
+                    )
+                },
+                unsafe {
+                    TensorHandleRef::<R>::from_raw_parts(
+                        &output_handle,
+                        &output_stride,
+                        &output_shape,
+                        size_of::<F::EI>(),
+                    )
+                },
+                self.axis.unwrap(),
+                Some(strategy),
+                SumConfig::default(),
+            );
+            assert_eq!(result, Err(expected));
+        }
+    }
+
+    /// Reduce with [map_reduce] the [Relu] of the input with [Sum],
+    /// against the reduction of the input mapped with [Relu] on the host.
+    pub fn test_map_reduce_relu_sum<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let zero = F::EI::new(0.0);
+        let mapped_values: Vec<F::EI> = input_values
+            .iter()
+            .map(|value| if *value > zero { *value } else { zero })
+            .collect();
+
+        let mut output_shape = self.shape.clone();
+        output_shape[self.axis.unwrap()] = 1;
+        let output_stride = self.output_stride();
+
+        let mut outputs = Vec::new();
+        for (values, is_mapped) in [(&input_values, true), (&mapped_values, false)] {
+            let input_handle = client.create(F::EI::as_bytes(values));
+            let output_handle =
+                client.create(F::EI::as_bytes(&vec![zero; self.num_output_values()]));
+            let input = unsafe {
+                TensorHandleRef::<R>::from_raw_parts(
+                    &input_handle,
+                    &self.stride,
+                    &self.shape,
+                    size_of::<F::EI>(),
+                )
+            };
+            let output = unsafe {
+                TensorHandleRef::<R>::from_raw_parts(
+                    &output_handle,
+                    &output_stride,
+                    &output_shape,
+                    size_of::<F::EI>(),
+                )
+            };
+
+            let result = if is_mapped {
+                map_reduce::<R, F::EI, Relu, F, F::EI, Sum>(
+                    &client,
+                    input,
+                    output,
+                    self.axis.unwrap(),
+                    self.strategy,
+                    SumConfig::default(),
+                )
+            } else {
+                reduce::<R, F, F::EI, Sum>(
+                    &client,
+                    input,
+                    output,
+                    self.axis.unwrap(),
+                    self.strategy,
+                    SumConfig::default(),
+                )
+            };
+            if result.is_err_and(|e| {
+                e == ReduceError::PlanesUnavailable || e == ReduceError::ImprecisePlaneDim
+            }) {
+                return; // We don't test in that case.
+            }
+
+            let bytes = client.read_one(output_handle);
+            outputs.push(F::EI::from_bytes(&bytes).to_vec());
+        }
+
+        assert_approx_equal(&outputs[0], &outputs[1]);
+    }
+
+    pub fn test_argmin<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let expected_values = match self.axis {
+            Some(axis) if self.stride[axis] == 0 => vec![0; input_values.len()],
+            _ => self.cpu_argmin(&input_values),
+        };
+        self.run_reduce_test::<F, u32, R, ArgMin>(device, input_values, expected_values)
+    }
+
+    fn cpu_argmin<F: Float>(&self, values: &[F]) -> Vec<u32> {
+        let mut expected = vec![(F::max_value(), 0_u32); self.num_output_values()];
+        for (input_index, &value) in values.iter().enumerate() {
+            if let Some(output_index) = self.to_output_index(input_index) {
+                let (best, _) = expected[output_index];
+                if value < best {
+                    let coordinate = self.to_input_coordinate(input_index).unwrap();
+                    expected[output_index] = (value, coordinate[self.axis.unwrap()] as u32);
+                }
+            }
+        }
+        expected.into_iter().map(|(_, i)| i).collect()
+    }
+
+    pub fn test_mean<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let expected_values = match self.axis {
+            Some(axis) if self.stride[axis] == 0 => input_values.clone(),
+            _ => self.cpu_mean(&input_values),
+        };
+        self.run_reduce_test::<F, F::EI, R, Mean>(device, input_values, expected_values)
+    }
+
+    fn cpu_mean<F: Float>(&self, values: &[F]) -> Vec<F> {
+        self.cpu_sum(values)
+            .into_iter()
+            .map(|sum| sum / F::new(self.shape[self.axis.unwrap()] as f32))
+            .collect()
+    }
+
+    pub fn test_prod<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let expected_values = match self.axis {
+            Some(axis) if self.stride[axis] == 0 => input_values
+                .iter()
+                .map(|v| Self::powf(*v, self.shape[axis]))
+                .collect(),
+            _ => self.cpu_prod(&input_values),
+        };
+        self.run_reduce_test::<F, F::EI, R, Prod>(device, input_values, expected_values)
+    }
+
+    /// Reduce with [Prod] an input filled with its identity,
+    /// which must be left unchanged even when some lanes are masked.
+    pub fn test_prod_identity<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let identity = <Prod as ReduceFamily>::identity::<F::EI>(ProdConfig::default()).unwrap();
+        let input_values = vec![identity; self.input_size()];
+        let expected_values = vec![identity; self.num_output_values()];
+        self.run_reduce_test::<F, F::EI, R, Prod>(device, input_values, expected_values)
+    }
+
+    /// Reduce rows made of small values followed by their inverses, with the first inverse
+    /// negated on odd rows. The product of each row is `1` or `-1`, but the partial products
+    /// underflow to zero with [Prod] while [StableProd] stays accurate.
+    ///
+    /// Assumes a contiguous input reduced along its last axis.
+    pub fn test_stable_prod_underflow<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let num_rows = self.num_output_values();
+        let row_length = self.shape[self.axis.unwrap()];
+        let small = F::EI::new(1.0 / 4096.0);
+        let large = F::EI::new(4096.0);
+
+        let mut input_values = Vec::with_capacity(num_rows * row_length);
+        for row in 0..num_rows {
+            input_values.extend(std::iter::repeat_n(small, row_length / 2));
+            input_values.push(if row % 2 == 1 { -large } else { large });
+            input_values.extend(std::iter::repeat_n(large, row_length / 2 - 1));
+        }
+        let expected_values = (0..num_rows)
+            .map(|row| F::EI::new(if row % 2 == 1 { -1.0 } else { 1.0 }))
+            .collect();
+        let underflow_values = vec![F::EI::from_int(0); num_rows];
+
+        self.run_reduce_test::<F, F::EI, R, Prod>(device, input_values.clone(), underflow_values);
+        self.run_reduce_test::<F, F::EI, R, StableProd>(device, input_values, expected_values)
+    }
+
+    /// Reduce with [Sum] and [Max](crate::instructions::Max) over the input split into chunks
+    /// along the axis.
+    pub fn test_concat<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let input_values: Vec<F::EI> = self.random_input_values();
+        self.run_reduce_concat_test::<F, F::EI, R, Sum>(device, input_values.clone());
+        self.run_reduce_concat_test::<F, F::EI, R, crate::instructions::Max>(device, input_values);
+    }
+
+    /// Reduce with [Moments2] for both the sum and the sum of squares, one output at a time and
+    /// then both at once with [reduce_moments2].
+    pub fn test_moments2<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let squares = input_values.iter().map(|x| *x * *x).collect::<Vec<_>>();
+        let expected = [self.cpu_sum(&input_values), self.cpu_sum(&squares)];
+
+        for (output, expected_values) in [Moments2Output::Sum, Moments2Output::SumOfSquares]
+            .into_iter()
+            .zip(expected.iter())
+        {
+            self.run_reduce_test_with_config::<F, F::EI, R, Moments2>(
+                device,
+                input_values.clone(),
+                expected_values.clone(),
+                Moments2Config { output },
+                R::max_cube_count(),
+            );
+        }
+
+        let client = R::client(device);
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+
+        let mut output_shape = self.shape.clone();
+        output_shape[self.axis.unwrap()] = 1;
+        let output_stride = self.output_stride();
+        let output_size = self.num_output_values() * size_of::<F::EI>();
+        let handles = [(); 2].map(|_| client.empty(output_size));
+        let [sums, sums_of_squares] = [0, 1].map(|i| unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &handles[i],
+                &output_stride,
+                &output_shape,
+                size_of::<F::EI>(),
+            )
+        });
+
+        let result = reduce_moments2::<R, F, F::EI>(
+            &client,
+            input,
+            sums,
+            sums_of_squares,
+            self.axis.unwrap(),
+            self.strategy,
+        );
+        if result.is_err_and(|e| e.is_unsupported_strategy()) {
+            return; // We don't test in that case.
+        }
+
+        for (handle, expected) in handles.into_iter().zip(expected.iter()) {
+            let bytes = client.read_one(handle);
+            assert_approx_equal(F::EI::from_bytes(&bytes), expected);
+        }
+    }
+
+    /// Reduce with [KthSmallest] for the minimum, a few ranks and the median of the axis,
+    /// outputting both the values and the coordinates.
+    pub fn test_kth_smallest<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let row_length = self.shape[self.axis.unwrap()] as u32;
+
+        for k in [1, 2, 3, row_length / 2] {
+            let (values, coordinates) = self.cpu_kth_smallest(&input_values, k as usize);
+            self.run_reduce_test_with_config::<F, F::EI, R, KthSmallest>(
+                device,
+                input_values.clone(),
+                values,
+                KthSmallestConfig {
+                    k,
+                    output: KthSmallestOutput::Value,
+                },
+                R::max_cube_count(),
+            );
+            self.run_reduce_test_with_config::<F, u32, R, KthSmallest>(
+                device,
+                input_values.clone(),
+                coordinates,
+                KthSmallestConfig {
+                    k,
+                    output: KthSmallestOutput::Index,
+                },
+                R::max_cube_count(),
+            );
+        }
+    }
+
+    /// Sort the items of each output by value then coordinate and take the k-th one.
+    fn cpu_kth_smallest<F: Float>(&self, values: &[F], k: usize) -> (Vec<F>, Vec<u32>) {
+        let mut items = vec![Vec::new(); self.num_output_values()];
+        for (input_index, &value) in values.iter().enumerate() {
+            if let Some(output_index) = self.to_output_index(input_index) {
+                let coordinate = self.to_input_coordinate(input_index).unwrap();
+                items[output_index].push((value, coordinate[self.axis.unwrap()] as u32));
+            }
+        }
+        items
+            .into_iter()
+            .map(|mut items| {
+                items.sort_by(|(lhs, lhs_coord), (rhs, rhs_coord)| {
+                    lhs.partial_cmp(rhs).unwrap().then(lhs_coord.cmp(rhs_coord))
+                });
+                items[k - 1]
+            })
+            .unzip()
+    }
+
+    /// Reduce with [Entropy] items cycling through `0`, `0.25`, ..., `1`, so each output has
+    /// zero probabilities. Without normalization, the items are divided by their sum beforehand.
+    pub fn test_entropy<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let input_values: Vec<F::EI> = (0..self.input_size())
+            .map(|i| F::EI::new(((i * 7) % 5) as f32 / 4.0))
+            .collect();
+        let sums = self.cpu_sum(&input_values);
+        let probabilities = input_values
+            .iter()
+            .enumerate()
+            .map(
+                |(input_index, value)| match self.to_output_index(input_index) {
+                    Some(output_index) => *value / sums[output_index],
+                    None => *value,
+                },
+            )
+            .collect::<Vec<_>>();
+
+        let mut expected_values = vec![0.0f32; self.num_output_values()];
+        for (input_index, p) in probabilities.iter().enumerate() {
+            let p = p.to_f32().unwrap();
+            let plogp = if p > 0.0 { p * p.ln() } else { 0.0 };
+            if let Some(output_index) = self.to_output_index(input_index) {
+                expected_values[output_index] -= plogp;
+            }
+        }
+        let expected_values = expected_values
+            .into_iter()
+            .map(F::EI::new)
+            .collect::<Vec<_>>();
+
+        self.run_reduce_test_with_config::<F, F::EI, R, Entropy>(
+            device,
+            input_values,
+            expected_values.clone(),
+            EntropyConfig { normalize: true },
+            R::max_cube_count(),
+        );
+        self.run_reduce_test_with_config::<F, F::EI, R, Entropy>(
+            device,
+            probabilities,
+            expected_values,
+            EntropyConfig { normalize: false },
+            R::max_cube_count(),
+        );
+    }
+
+    /// Reduce with the [LpNorm] of order `p` items cycling through `-1`, `-0.75`, ..., `1`,
+    /// against the norm computed on the host.
+    pub fn test_lp_norm<F, R>(&self, device: &R::Device, p: f32)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let input_values: Vec<F::EI> = (0..self.input_size())
+            .map(|i| F::EI::new(((i * 7) % 9) as f32 / 4.0 - 1.0))
+            .collect();
+
+        let mut expected_values = vec![0.0f32; self.num_output_values()];
+        for (input_index, value) in input_values.iter().enumerate() {
+            let value = value.to_f32().unwrap().abs();
+            if let Some(output_index) = self.to_output_index(input_index) {
+                let expected = &mut expected_values[output_index];
+                if p == f32::INFINITY {
+                    *expected = expected.max(value);
+                } else {
+                    *expected += value.powf(p);
+                }
+            }
+        }
+        let expected_values = expected_values
+            .into_iter()
+            .map(|value| match p == f32::INFINITY {
+                true => F::EI::new(value),
+                false => F::EI::new(value.powf(1.0 / p)),
+            })
+            .collect::<Vec<_>>();
+
+        self.run_reduce_test_with_config::<F, F::EI, R, LpNorm>(
+            device,
+            input_values,
+            expected_values,
+            LpNormConfig::new(p),
+            R::max_cube_count(),
+        );
+    }
+
+    /// Reduce with the [OutputTransform::Db] of a [Sum] of powers, where every third output
+    /// sums only zeros and the next one only negative items, so both are floored by the guard
+    /// of the logarithm, against the decibels computed on the host.
+    pub fn test_db_sum<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let input_values: Vec<F::EI> = (0..self.input_size())
+            .map(|i| {
+                let power = (((i * 7) % 5) + 1) as f32 / 4.0;
+                match self.to_output_index(i).map(|output_index| output_index % 3) {
+                    Some(0) => F::EI::new(0.0),
+                    Some(1) => F::EI::new(-power),
+                    _ => F::EI::new(power),
+                }
+            })
+            .collect();
+
+        let expected_values = self
+            .cpu_sum(&input_values)
+            .into_iter()
+            .map(|sum| F::EI::new(10.0 * sum.to_f32().unwrap().max(f32::MIN_POSITIVE).log10()))
+            .collect::<Vec<_>>();
+
+        self.run_reduce_test_with_config::<F, F::EI, R, Transformed<Sum>>(
+            device,
+            input_values,
+            expected_values,
+            TransformedConfig {
+                inner: SumConfig::default(),
+                transform: OutputTransform::Db,
+            },
+            R::max_cube_count(),
+        );
+    }
+
+    fn powf<F: Float>(base: F, power: usize) -> F {
+        let mut result = F::new(1.0);
+        for _ in 0..power {
+            result *= base;
+        }
+        result
+    }
+
+    fn cpu_prod<F: Float>(&self, values: &[F]) -> Vec<F> {
+        let mut expected = vec![F::new(1.0); self.num_output_values()];
+
+        for (input_index, value) in values.iter().enumerate() {
+            if let Some(output_index) = self.to_output_index(input_index) {
+                expected[output_index] *= *value;
+            }
+        }
+        expected
+    }
+
+    pub fn test_sum<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let expected_values = match self.axis {
+            Some(axis) if self.stride[axis] == 0 => input_values
+                .iter()
+                .map(|v| *v * F::EI::from_int(self.shape[axis] as i64))
+                .collect(),
+            _ => self.cpu_sum(&input_values),
+        };
+        self.run_reduce_test::<F, F::EI, R, Sum>(device, input_values, expected_values)
+    }
+
+    fn cpu_sum<F: Float>(&self, values: &[F]) -> Vec<F> {
+        let mut expected = vec![F::new(0.0); self.num_output_values()];
+
+        for (input_index, value) in values.iter().enumerate() {
+            if let Some(output_index) = self.to_output_index(input_index) {
+                expected[output_index] += *value;
+            }
+        }
+        expected
+    }
+
+    pub fn test_mean_update<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let expected_values = self.cpu_mean(&input_values);
+        self.run_reduce_update_test::<F, F::EI, R, Mean>(device, input_values, expected_values)
+    }
+
+    pub fn test_sum_with_max_cube_count<F, R>(
+        &self,
+        device: &R::Device,
+        max_cube_count: (u32, u32, u32),
+    ) where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let expected_values = self.cpu_sum(&input_values);
+        self.run_reduce_test_with_max_cube_count::<F, F::EI, R, Sum>(
+            device,
+            input_values,
+            expected_values,
+            max_cube_count,
+        )
+    }
+
+    pub fn test_sum_update<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let expected_values = self.cpu_sum(&input_values);
+        self.run_reduce_update_test::<F, F::EI, R, Sum>(device, input_values, expected_values)
+    }
+
+    /// Feed the input one row at a time to [reduce_update_with_scratch] into several
+    /// accumulators in turn, all reusing a single scratch buffer, and check a scratch buffer too
+    /// small is rejected. The reduced axis must be the outermost one of a contiguous tensor.
+    pub fn test_sum_update_with_scratch<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let axis = self.axis.unwrap();
+        assert_eq!(axis, 0, "Chunks are split along the outermost axis");
+
+        let client = R::client(device);
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let expected_values = self.cpu_sum(&input_values);
+
+        let mut output_shape = self.shape.clone();
+        output_shape[axis] = 1;
+        let output_stride = self.output_stride();
+        let num_outputs = expected_values.len();
+        let mut input_shape = self.shape.clone();
+        input_shape[axis] = 1;
+        let row_handles = (0..self.shape[axis])
+            .map(|row| {
+                let values = &input_values[row * self.stride[axis]..(row + 1) * self.stride[axis]];
+                client.create(F::EI::as_bytes(values))
+            })
+            .collect::<Vec<_>>();
+
+        let scratch = client.empty(num_outputs * size_of::<F::EI>());
+        let output_handles = (0..3)
+            .map(|_| client.empty(num_outputs * size_of::<F::EI>()))
+            .collect::<Vec<_>>();
+        let mut accumulators = output_handles
+            .iter()
+            .map(|handle| {
+                ReduceAccumulator::new(unsafe {
+                    TensorHandleRef::<R>::from_raw_parts(
+                        handle,
+                        &output_stride,
+                        &output_shape,
+                        size_of::<F::EI>(),
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            accumulators[0].required_scratch_bytes::<F::EI>(),
+            num_outputs * size_of::<F::EI>()
+        );
+
+        for row_handle in &row_handles {
+            let input = unsafe {
+                TensorHandleRef::<R>::from_raw_parts(
+                    row_handle,
+                    &self.stride,
+                    &input_shape,
+                    size_of::<F::EI>(),
+                )
+            };
+            for accumulator in &mut accumulators {
+                reduce_update_with_scratch::<R, F, F::EI, Sum>(
+                    &client,
+                    input,
+                    accumulator,
+                    &scratch,
+                    axis,
+                    self.strategy,
+                    SumConfig::default(),
+                )
+                .unwrap();
+            }
+        }
+
+        let small_scratch = client.empty(size_of::<F::EI>());
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &row_handles[0],
+                &self.stride,
+                &input_shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let result = reduce_update_with_scratch::<R, F, F::EI, Sum>(
+            &client,
+            input,
+            &mut accumulators[0],
+            &small_scratch,
+            axis,
+            self.strategy,
+            SumConfig::default(),
+        );
+        assert_eq!(
+            result,
+            Err(ReduceError::ScratchTooSmall {
+                size: size_of::<F::EI>(),
+                required: num_outputs * size_of::<F::EI>(),
+            })
+        );
+
+        for (accumulator, handle) in accumulators.iter().zip(&output_handles) {
+            assert_eq!(accumulator.count, self.shape[axis]);
+            let bytes = client.read_one(handle.clone());
+            assert_approx_equal(F::EI::from_bytes(&bytes), &expected_values);
+        }
+    }
+
+    /// Check that [reduce_update] rejects [ArgMax], whose outputs can't be combined, before
+    /// reducing anything into the accumulator.
+    pub fn test_argmax_update_unsupported<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let axis = self.axis.unwrap();
+        let client = R::client(device);
+
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let output_handle = client.empty(self.num_output_values() * size_of::<u32>());
+        let mut output_shape = self.shape.clone();
+        output_shape[axis] = 1;
+        let output_stride = self.output_stride();
+        let output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &output_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<u32>(),
+            )
+        };
+        let mut accumulator = ReduceAccumulator::new(output);
+
+        let result = reduce_update::<R, F, u32, ArgMax>(
+            &client,
+            input,
+            &mut accumulator,
+            axis,
+            self.strategy,
+            TieBreak::default(),
+        );
+        assert_eq!(result, Err(ReduceError::CombineUnsupported));
+        assert_eq!(accumulator.count, 0);
+    }
+
+    /// Feed the input in 4 chunks to [reduce_update] with [DecayedMax] or [DecayedMin],
+    /// and compare with the exponential moving extremum of the chunks computed on the host.
+    pub fn test_decayed_update<F, R>(&self, device: &R::Device, minimum: bool)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let decay = 0.5;
+        let num_chunks = 4;
+        let axis = self.axis.unwrap();
+        let input_values: Vec<F::EI> = self.random_input_values();
+
+        let mut running = vec![None::<f32>; self.num_output_values()];
+        for chunk in 0..num_chunks {
+            let begin = chunk * self.shape[axis] / num_chunks;
+            let end = (chunk + 1) * self.shape[axis] / num_chunks;
+            let mut extrema = vec![None::<f32>; self.num_output_values()];
+            for input_index in begin * self.stride[axis]..end * self.stride[axis] {
+                let value = input_values[input_index].to_f32().unwrap();
+                if let Some(output_index) = self.to_output_index(input_index) {
+                    let extremum = &mut extrema[output_index];
+                    *extremum = Some(match *extremum {
+                        None => value,
+                        Some(extremum) if minimum => extremum.min(value),
+                        Some(extremum) => extremum.max(value),
+                    });
+                }
+            }
+            for (running, extremum) in running.iter_mut().zip(extrema) {
+                let extremum = extremum.unwrap();
+                *running = Some(match *running {
+                    None => extremum,
+                    Some(running) if minimum => (decay * running).min(extremum),
+                    Some(running) => (decay * running).max(extremum),
+                });
+            }
+        }
+        let expected_values = running
+            .into_iter()
+            .map(|value| F::EI::new(value.unwrap()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(DecayConfig::new(1.5).err(), Some(ReduceError::InvalidDecay));
+        let config = DecayConfig::new(decay).unwrap();
+        if minimum {
+            self.run_reduce_update_test_with_config::<F, F::EI, R, DecayedMin>(
+                device,
+                input_values,
+                expected_values,
+                config,
+                num_chunks,
+            )
+        } else {
+            self.run_reduce_update_test_with_config::<F, F::EI, R, DecayedMax>(
+                device,
+                input_values,
+                expected_values,
+                config,
+                num_chunks,
+            )
+        }
+    }
+
+    /// Enqueue [Sum], [Mean] and [Prod] back-to-back with [reduce_enqueue]
+    /// and read all the outputs after the last one was enqueued.
+    pub fn test_reduce_enqueue<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision + std::fmt::Display,
+        F::EI: CubeElement + Float + std::fmt::Display,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let axis = self.axis.unwrap();
+
+        let outputs = [
+            reduce_enqueue::<R, F, F::EI, Sum>(
+                &client,
+                input,
+                axis,
+                self.strategy,
+                SumConfig::default(),
+            ),
+            reduce_enqueue::<R, F, F::EI, Mean>(&client, input, axis, self.strategy, ()),
+            reduce_enqueue::<R, F, F::EI, Prod>(
+                &client,
+                input,
+                axis,
+                self.strategy,
+                ProdConfig::default(),
+            ),
+        ];
+        if outputs.iter().any(|output| {
+            output.as_ref().is_err_and(|e| {
+                *e == ReduceError::PlanesUnavailable || *e == ReduceError::ImprecisePlaneDim
+            })
+        }) {
+            return; // We don't test in that case.
+        }
+        let outputs = outputs.map(|output| output.unwrap());
+
+        let bytes = client.read_tensor(
+            outputs
+                .iter()
+                .map(|output| output.as_copy_descriptor())
+                .collect(),
+        );
+        let expected_values = [
+            self.cpu_sum(&input_values),
+            self.cpu_mean(&input_values),
+            self.cpu_prod(&input_values),
+        ];
+        for (bytes, expected_values) in bytes.iter().zip(expected_values.iter()) {
+            assert_approx_equal(F::EI::from_bytes(bytes), expected_values);
+        }
+    }
+
+    /// Check that [reduce_dyn] with a `f32` output matches the typed [reduce].
+    pub fn test_reduce_dyn<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let axis = self.axis.unwrap();
+
+        let mut output_shape = self.shape.clone();
+        output_shape[axis] = 1;
+        let output_stride = self.output_stride();
+        let output_len = self.num_output_values();
+        let typed_handle = client.empty(output_len * size_of::<f32>());
+        let dyn_handle = client.empty(output_len * size_of::<f32>());
+        let typed_output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &typed_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<f32>(),
+            )
+        };
+        let dyn_output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &dyn_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<f32>(),
+            )
+        };
+
+        let typed = reduce::<R, F, f32, Sum>(
+            &client,
+            input,
+            typed_output,
+            axis,
+            self.strategy,
+            SumConfig::default(),
+        );
+        if typed.is_err_and(|e| {
+            e == ReduceError::PlanesUnavailable || e == ReduceError::ImprecisePlaneDim
+        }) {
+            return; // We don't test in that case.
+        }
+        reduce_dyn::<R, F, Sum>(
+            &client,
+            input,
+            dyn_output,
+            ElemType::Float(FloatKind::F32),
+            axis,
+            self.strategy,
+            SumConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            reduce_dyn::<R, F, Sum>(
+                &client,
+                input,
+                dyn_output,
+                ElemType::Bool,
+                axis,
+                self.strategy,
+                SumConfig::default(),
+            ),
+            Err(ReduceError::UnsupportedOutputElem(ElemType::Bool))
+        );
+
+        let typed_bytes = client.read_one(typed_handle);
+        let dyn_bytes = client.read_one(dyn_handle);
+        assert_eq!(f32::from_bytes(&dyn_bytes), f32::from_bytes(&typed_bytes));
+    }
+
+    /// Sum the diagonal of each matrix with [reduce_diagonal].
+    ///
+    /// Assumes a contiguous input, whose last two axes are the matrices.
+    pub fn test_trace<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let input_values: Vec<F::EI> = self.random_input_values();
+
+        let rank = self.shape.len();
+        let (rows, cols) = (self.shape[rank - 2], self.shape[rank - 1]);
+        let num_matrices = self.shape[..rank - 2].iter().product::<usize>();
+        let expected_values = (0..num_matrices)
+            .map(|matrix| {
+                let mut trace = F::EI::from_int(0);
+                for i in 0..rows.min(cols) {
+                    trace += input_values[matrix * rows * cols + i * cols + i];
+                }
+                trace
+            })
+            .collect::<Vec<_>>();
+
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+
+        let mut output_shape = self.shape[..rank - 1].to_vec();
+        output_shape[rank - 2] = 1;
+        let mut output_stride = vec![1; rank - 1];
+        for axis in (0..rank - 2).rev() {
+            output_stride[axis] = output_stride[axis + 1] * output_shape[axis + 1];
+        }
+        let output_handle = client.empty(num_matrices * size_of::<F::EI>());
+        let output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &output_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<F::EI>(),
+            )
+        };
+
+        reduce_diagonal::<R, F, F::EI, Sum>(
+            &client,
+            input,
+            output,
+            self.strategy,
+            SumConfig::default(),
+        )
+        .unwrap();
+
+        let bytes = client.read_one(output_handle);
+        assert_approx_equal(F::EI::from_bytes(&bytes), &expected_values);
+    }
+
+    /// Sum the axis with [reduce_permuted] writing the remaining axes in the order of
+    /// `permutation`, and compare with a sum by [reduce] followed by a transpose on the host.
+    pub fn test_permuted<F, R>(&self, device: &R::Device, permutation: &[usize])
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let axis = self.axis.unwrap();
+        let num_outputs = self.num_output_values();
+
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+
+        let mut reduced_shape = self.shape.clone();
+        reduced_shape[axis] = 1;
+        let reduced_stride = contiguous_strides(&reduced_shape);
+        let reduced_handle = client.empty(num_outputs * size_of::<F::EI>());
+        let reduced = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &reduced_handle,
+                &reduced_stride,
+                &reduced_shape,
+                size_of::<F::EI>(),
+            )
+        };
+        reduce::<R, F, F::EI, Sum>(
+            &client,
+            input,
+            reduced,
+            axis,
+            self.strategy,
+            SumConfig::default(),
+        )
+        .unwrap();
+
+        let permuted_shape = permutation
+            .iter()
+            .map(|input_axis| self.shape[*input_axis])
+            .collect::<Vec<_>>();
+        let permuted_stride = contiguous_strides(&permuted_shape);
+        let permuted_handle = client.empty(num_outputs * size_of::<F::EI>());
+        let permuted = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &permuted_handle,
+                &permuted_stride,
+                &permuted_shape,
+                size_of::<F::EI>(),
+            )
+        };
+        reduce_permuted::<R, F, F::EI, Sum>(
+            &client,
+            input,
+            permuted,
+            axis,
+            permutation,
+            self.strategy,
+            SumConfig::default(),
+        )
+        .unwrap();
+
+        let bytes = client.read_one(reduced_handle);
+        let reduced_values = F::EI::from_bytes(&bytes);
+        let expected_values = (0..num_outputs)
+            .map(|index| {
+                let reduced_index = permutation
+                    .iter()
+                    .zip(permuted_shape.iter().zip(&permuted_stride))
+                    .map(|(input_axis, (shape, stride))| {
+                        (index / stride) % shape * reduced_stride[*input_axis]
+                    })
+                    .sum::<usize>();
+                reduced_values[reduced_index]
+            })
+            .collect::<Vec<_>>();
+
+        let bytes = client.read_one(permuted_handle);
+        assert_approx_equal(F::EI::from_bytes(&bytes), &expected_values);
+    }
+
+    /// Sum the axis with [reduce] into an output without the reduced axis, laid out with
+    /// `output_strides`, and check the mapping of [ReduceConfig::output_mapping] against the
+    /// position of each result.
+    pub fn test_lower_rank_output<F, R>(&self, device: &R::Device, output_strides: &[usize])
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let axis = self.axis.unwrap();
+        let num_outputs = self.num_output_values();
+
+        let output_shape = self
+            .shape
+            .iter()
+            .enumerate()
+            .filter(|(a, _)| *a != axis)
+            .map(|(_, shape)| *shape)
+            .collect::<Vec<_>>();
+        let mapping =
+            ReduceConfig::output_mapping(&self.shape, &output_shape, output_strides, axis).unwrap();
+
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let output_handle = client.empty(num_outputs * size_of::<F::EI>());
+        let output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &output_handle,
+                output_strides,
+                &output_shape,
+                size_of::<F::EI>(),
+            )
+        };
+        reduce::<R, F, F::EI, Sum>(
+            &client,
+            input,
+            output,
+            axis,
+            self.strategy,
+            SumConfig::default(),
+        )
+        .unwrap();
+
+        let sums = self.cpu_sum(&input_values);
+        let expected_values = (0..num_outputs)
+            .map(|index| {
+                let coordinates = mapping.coordinates(index);
+                let position = coordinates
+                    .iter()
+                    .zip(&mapping.strides)
+                    .map(|(coordinate, stride)| coordinate * stride)
+                    .sum::<usize>();
+                assert_eq!(position, index);
+                sums[self.from_output_coordinate(coordinates)]
+            })
+            .collect::<Vec<_>>();
+
+        let bytes = client.read_one(output_handle);
+        assert_approx_equal(F::EI::from_bytes(&bytes), &expected_values);
+    }
+
+    /// Sum with [try_reduce] and a strategy forcing a plane size no client supports, so the
+    /// reduction has to fall back to the default strategy.
+    pub fn test_try_reduce_fallback<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let expected_values = self.cpu_sum(&input_values);
+        let strategy = ReduceStrategy {
+            use_planes: false,
+            shared: false,
+            shared_transpose: false,
+            plane_dim: Some(3),
+            naive: false,
+        };
+
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let mut output_shape = self.shape.clone();
+        output_shape[self.axis.unwrap()] = 1;
+        let output_stride = self.output_stride();
+        let output_handle = client.empty(expected_values.len() * size_of::<F::EI>());
+        let output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &output_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<F::EI>(),
+            )
+        };
+
+        let error = reduce::<R, F, F::EI, Sum>(
+            &client,
+            input,
+            output,
+            self.axis.unwrap(),
+            Some(strategy),
+            SumConfig::default(),
+        )
+        .unwrap_err();
+        assert!(error.is_unsupported_strategy());
+
+        try_reduce::<R, F, F::EI, Sum>(
+            &client,
+            input,
+            output,
+            self.axis.unwrap(),
+            strategy,
+            SumConfig::default(),
+        )
+        .unwrap();
+
+        let bytes = client.read_one(output_handle);
+        assert_approx_equal(F::EI::from_bytes(&bytes), &expected_values);
+    }
+
+    /// Sum the contiguous input into `num_planes` values with [reduce_plane_local], and check the
+    /// sum of each chunk as well as their combination into the sum of the whole input.
+    pub fn test_plane_local<F, R>(&self, device: &R::Device, num_planes: usize)
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let chunk_length = input_values.len().div_ceil(num_planes);
+        let expected_values = (0..num_planes)
+            .map(|plane| {
+                input_values
+                    .iter()
+                    .skip(plane * chunk_length)
+                    .take(chunk_length)
+                    .fold(F::EI::from_int(0), |sum, value| sum + *value)
+            })
+            .collect::<Vec<_>>();
+
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let output_handle = client.empty(num_planes * size_of::<F::EI>());
+        let output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &output_handle,
+                &[1],
+                &[num_planes],
+                size_of::<F::EI>(),
+            )
+        };
+
+        let result =
+            reduce_plane_local::<R, F, F::EI, Sum>(&client, input, output, SumConfig::default());
+        if result.is_err_and(|e| e.is_unsupported_strategy()) {
+            return; // We don't test in that case.
+        }
+
+        let bytes = client.read_one(output_handle);
+        let output_values = F::EI::from_bytes(&bytes);
+        assert_approx_equal(output_values, &expected_values);
+
+        let combined = output_values
+            .iter()
+            .fold(F::EI::from_int(0), |sum, value| sum + *value);
+        let total = input_values
+            .iter()
+            .fold(F::EI::from_int(0), |sum, value| sum + *value);
+        assert_approx_equal(&[combined], &[total]);
+    }
+
+    /// Sum the input along the axis into `num_cubes` partials with [reduce_cube_partials], and
+    /// check that summing the partials on the host gives the sum of each slice.
+    pub fn test_cube_partials_sum<F, R>(&self, device: &R::Device, num_cubes: usize)
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let expected_values = self.cpu_sum(&input_values);
+
+        let num_outputs = self.num_output_values();
+        let mut output_shape = vec![num_cubes];
+        output_shape.extend(self.shape.iter().copied());
+        output_shape[self.axis.unwrap() + 1] = 1;
+        let mut output_stride = vec![num_outputs];
+        output_stride.extend(self.output_stride());
+
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let output_handle = client.empty(num_cubes * num_outputs * size_of::<F::EI>());
+        let output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &output_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<F::EI>(),
+            )
+        };
+
+        reduce_cube_partials::<R, F, F::EI, Sum>(
+            &client,
+            input,
+            output,
+            self.axis.unwrap(),
+            SumConfig::default(),
+        )
+        .unwrap();
+
+        let bytes = client.read_one(output_handle);
+        let partials = F::EI::from_bytes(&bytes);
+        let combined = (0..num_outputs)
+            .map(|index| {
+                (0..num_cubes).fold(F::EI::from_int(0), |sum, cube| {
+                    sum + partials[cube * num_outputs + index]
+                })
+            })
+            .collect::<Vec<_>>();
+        assert_approx_equal(&combined, &expected_values);
+    }
+
+    /// Check the dot products of [reduce_dot_product], and both sums of squares when `squares`
+    /// is true.
+    pub fn test_dot_product<F, R>(&self, device: &R::Device, squares: bool)
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let lhs_values: Vec<F::EI> = self.random_input_values();
+        let rhs_values = (0..lhs_values.len())
+            .map(|i| F::EI::new(0.25 * ((i * 3) % 8) as f32 - 1.0))
+            .collect::<Vec<_>>();
+
+        let products = |lhs: &[F::EI], rhs: &[F::EI]| {
+            let products = lhs
+                .iter()
+                .zip(rhs.iter())
+                .map(|(lhs, rhs)| *lhs * *rhs)
+                .collect::<Vec<_>>();
+            self.cpu_sum(&products)
+        };
+        let expected = [
+            products(&lhs_values, &rhs_values),
+            products(&lhs_values, &lhs_values),
+            products(&rhs_values, &rhs_values),
+        ];
+
+        let lhs_handle = client.create(F::EI::as_bytes(&lhs_values));
+        let rhs_handle = client.create(F::EI::as_bytes(&rhs_values));
+        let lhs = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &lhs_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let rhs = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &rhs_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+
+        let mut output_shape = self.shape.clone();
+        output_shape[self.axis.unwrap()] = 1;
+        let output_stride = self.output_stride();
+        let output_size = self.num_output_values() * size_of::<F::EI>();
+        let handles = [(); 3].map(|_| client.empty(output_size));
+        let [dot, lhs_squares, rhs_squares] = [0, 1, 2].map(|i| unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &handles[i],
+                &output_stride,
+                &output_shape,
+                size_of::<F::EI>(),
+            )
+        });
+
+        reduce_dot_product::<R, F, F::EI>(
+            &client,
+            lhs,
+            rhs,
+            dot,
+            squares.then_some((lhs_squares, rhs_squares)),
+            self.axis.unwrap(),
+        )
+        .unwrap();
+
+        let num_outputs = if squares { 3 } else { 1 };
+        for (handle, expected) in handles.into_iter().zip(expected.iter()).take(num_outputs) {
+            let bytes = client.read_one(handle);
+            assert_approx_equal(F::EI::from_bytes(&bytes), expected);
+        }
+    }
+
+    /// Check the max pooling, average pooling and windowed sum of [pool_reduce] with the given
+    /// windows and strides over the last axes, against a reference computed on the host.
+    pub fn test_pool<F, R>(
+        &self,
+        device: &R::Device,
+        window: &[usize],
+        stride: &[usize],
+        boundary: PoolBoundary,
+    ) where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let input_values: Vec<F::EI> = self.random_input_values();
+
+        let rank = self.shape.len();
+        let num_kept = rank - window.len();
+        let window = [vec![1; num_kept], window.to_vec()].concat();
+        let stride = [vec![1; num_kept], stride.to_vec()].concat();
+        let output_shape = (0..rank)
+            .map(|axis| {
+                if boundary == PoolBoundary::Wrap {
+                    self.shape[axis].div_ceil(stride[axis])
+                } else if self.shape[axis] > window[axis] {
+                    (self.shape[axis] - window[axis]).div_ceil(stride[axis]) + 1
+                } else {
+                    1
+                }
+            })
+            .collect::<Vec<_>>();
+        let output_stride = contiguous_strides(&output_shape);
+        let num_output_values = output_shape.iter().product::<usize>();
+        let window_len = window.iter().product::<usize>();
+
+        let (mut expected_max, mut expected_mean, mut expected_sum) =
+            (Vec::new(), Vec::new(), Vec::new());
+        for output_index in 0..num_output_values {
+            let mut items = Vec::new();
+            for window_index in 0..window_len {
+                let mut offset = 0;
+                let mut inside = true;
+                let mut remainder = window_index;
+                for axis in (0..rank).rev() {
+                    let window_coordinate = remainder % window[axis];
+                    remainder /= window[axis];
+                    let output_coordinate =
+                        (output_index / output_stride[axis]) % output_shape[axis];
+                    let mut coordinate = output_coordinate * stride[axis] + window_coordinate;
+                    if boundary == PoolBoundary::Wrap {
+                        coordinate %= self.shape[axis];
+                    }
+                    inside &= coordinate < self.shape[axis];
+                    offset += coordinate * self.stride[axis];
+                }
+                if inside {
+                    items.push(input_values[offset]);
+                }
+            }
+            let count = match boundary {
+                PoolBoundary::Pad | PoolBoundary::Wrap => window_len,
+                PoolBoundary::Clamp => items.len(),
+            };
+            let sum = items
+                .iter()
+                .fold(F::EI::from_int(0), |sum, item| sum + *item);
+            let max = items.iter().fold(
+                F::EI::min_value(),
+                |max, item| {
+                    if *item > max { *item } else { max }
+                },
+            );
+            expected_max.push(max);
+            expected_mean.push(sum / F::EI::from_int(count as i64));
+            expected_sum.push(sum);
+        }
+
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let spatial = num_kept..rank;
+        let output_handles = [(); 3].map(|_| client.empty(num_output_values * size_of::<F::EI>()));
+        let [max_output, mean_output, sum_output] = [0, 1, 2].map(|i| unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &output_handles[i],
+                &output_stride,
+                &output_shape,
+                size_of::<F::EI>(),
+            )
+        });
+
+        pool_reduce::<R, F, F::EI, Max>(
+            &client,
+            input,
+            max_output,
+            &window[spatial.clone()],
+            &stride[spatial.clone()],
+            boundary,
+            (),
+        )
+        .unwrap();
+        pool_reduce::<R, F, F::EI, Mean>(
+            &client,
+            input,
+            mean_output,
+            &window[spatial.clone()],
+            &stride[spatial.clone()],
+            boundary,
+            (),
+        )
+        .unwrap();
+        pool_reduce::<R, F, F::EI, Sum>(
+            &client,
+            input,
+            sum_output,
+            &window[spatial.clone()],
+            &stride[spatial],
+            boundary,
+            SumConfig::default(),
+        )
+        .unwrap();
+
+        let [max_handle, mean_handle, sum_handle] = output_handles;
+        let bytes = client.read_one(max_handle);
+        assert_approx_equal(F::EI::from_bytes(&bytes), &expected_max);
+        let bytes = client.read_one(mean_handle);
+        assert_approx_equal(F::EI::from_bytes(&bytes), &expected_mean);
+        let bytes = client.read_one(sum_handle);
+        assert_approx_equal(F::EI::from_bytes(&bytes), &expected_sum);
+    }
+
+    /// Check the sum and the max of [gather_reduce] with duplicated indices, against a gather
+    /// then reduce on the host.
+    pub fn test_gather_reduce<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let axis = self.axis.unwrap();
+        let axis_len = self.shape[axis];
+        let indices = [axis_len - 1, 0, 2, 2, axis_len / 2, 0]
+            .map(|index| index as u32)
+            .to_vec();
+
+        let mut slices = vec![vec![F::EI::from_int(0); axis_len]; self.num_output_values()];
+        for input_index in 0..self.input_size() {
+            let Some(coordinate) = self.to_input_coordinate(input_index) else {
+                continue;
+            };
+            let output_index = self.to_output_index(input_index).unwrap();
+            slices[output_index][coordinate[axis]] = input_values[input_index];
+        }
+        let (mut expected_sum, mut expected_max) = (Vec::new(), Vec::new());
+        for slice in slices {
+            let gathered = indices.iter().map(|index| slice[*index as usize]);
+            expected_sum.push(
+                gathered
+                    .clone()
+                    .fold(F::EI::from_int(0), |sum, item| sum + item),
+            );
+            expected_max.push(gathered.fold(F::EI::min_value(), |max, item| {
+                if item > max { item } else { max }
+            }));
+        }
+
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let indices_handle = client.create(u32::as_bytes(&indices));
+        let indices_shape = [indices.len()];
+        let indices = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &indices_handle,
+                &[1],
+                &indices_shape,
+                size_of::<u32>(),
+            )
+        };
+        let mut output_shape = self.shape.clone();
+        output_shape[axis] = 1;
+        let output_stride = self.output_stride();
+        let output_handles =
+            [(); 2].map(|_| client.empty(self.num_output_values() * size_of::<F::EI>()));
+        let [sum_output, max_output] = [0, 1].map(|i| unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &output_handles[i],
+                &output_stride,
+                &output_shape,
+                size_of::<F::EI>(),
+            )
+        });
+
+        gather_reduce::<R, F, F::EI, Sum>(
+            &client,
+            input,
+            indices,
+            sum_output,
+            axis,
+            SumConfig::default(),
+        )
+        .unwrap();
+        gather_reduce::<R, F, F::EI, Max>(&client, input, indices, max_output, axis, ()).unwrap();
+
+        let [sum_handle, max_handle] = output_handles;
+        let bytes = client.read_one(sum_handle);
+        assert_approx_equal(F::EI::from_bytes(&bytes), &expected_sum);
+        let bytes = client.read_one(max_handle);
+        assert_approx_equal(F::EI::from_bytes(&bytes), &expected_max);
+    }
+
+    /// Check the sum and the max of [scatter_reduce] and [scatter_reduce_atomic] into 4
+    /// destinations, with colliding destinations, one left empty and one out of bounds, against a
+    /// reference on the host.
+    pub fn test_scatter_reduce<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let axis = self.axis.unwrap();
+        let num_destinations = 4;
+        // Most positions collide on the first three destinations, the last one receives nothing.
+        let indices = (0..self.shape[axis])
+            .map(|k| match k {
+                5 => num_destinations as u32 + 2,
+                k => (k * 5 % 3) as u32,
+            })
+            .collect::<Vec<_>>();
+
+        let mut output_shape = self.shape.clone();
+        output_shape[axis] = num_destinations;
+        let output_stride = contiguous_strides(&output_shape);
+        let num_output_values = output_shape.iter().product::<usize>();
+
+        // The sum is added to ones, and the max is taken with zeros.
+        let initial_sum = vec![F::EI::from_int(1); num_output_values];
+        let initial_max = vec![F::EI::from_int(0); num_output_values];
+        let (mut expected_sum, mut expected_max) = (initial_sum.clone(), initial_max.clone());
+        for input_index in 0..self.input_size() {
+            let Some(mut coordinate) = self.to_input_coordinate(input_index) else {
+                continue;
+            };
+            let destination = indices[coordinate[axis]] as usize;
+            if destination >= num_destinations {
+                continue;
+            }
+            coordinate[axis] = destination;
+            let output_index = coordinate
+                .iter()
+                .zip(output_stride.iter())
+                .map(|(coordinate, stride)| coordinate * stride)
+                .sum::<usize>();
+            let item = input_values[input_index];
+            expected_sum[output_index] += item;
+            if item > expected_max[output_index] {
+                expected_max[output_index] = item;
+            }
+        }
+
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let indices_handle = client.create(u32::as_bytes(&indices));
+        let indices_shape = [indices.len()];
+        let indices = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &indices_handle,
+                &[1],
+                &indices_shape,
+                size_of::<u32>(),
+            )
+        };
+        let output_handles =
+            [&initial_sum, &initial_max].map(|values| client.create(F::EI::as_bytes(values)));
+        let [sum_output, max_output] = [0, 1].map(|i| unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &output_handles[i],
+                &output_stride,
+                &output_shape,
+                size_of::<F::EI>(),
+            )
+        });
+
+        scatter_reduce::<R, F, F::EI, Sum>(
+            &client,
+            input,
+            indices,
+            sum_output,
+            axis,
+            SumConfig::default(),
+        )
+        .unwrap();
+        scatter_reduce::<R, F, F::EI, Max>(&client, input, indices, max_output, axis, ()).unwrap();
+
+        let [sum_handle, max_handle] = output_handles;
+        let bytes = client.read_one(sum_handle);
+        assert_approx_equal(F::EI::from_bytes(&bytes), &expected_sum);
+        let bytes = client.read_one(max_handle);
+        assert_approx_equal(F::EI::from_bytes(&bytes), &expected_max);
+
+        // The atomic scatters into fresh outputs must give the same results.
+        for (atomic, initial, expected) in [
+            (ScatterAtomic::Add, &initial_sum, &expected_sum),
+            (ScatterAtomic::Max, &initial_max, &expected_max),
+        ] {
+            let output_handle = client.create(F::EI::as_bytes(initial));
+            let output = unsafe {
+                TensorHandleRef::<R>::from_raw_parts(
+                    &output_handle,
+                    &output_stride,
+                    &output_shape,
+                    size_of::<F::EI>(),
+                )
+            };
+            let result = scatter_reduce_atomic::<R, F::EI, F::EI>(
+                &client, input, indices, output, axis, atomic,
+            );
+            if result.is_err_and(|e| {
+                matches!(
+                    e,
+                    ReduceError::MissingAtomicAdd(_)
+                        | ReduceError::MissingAtomicCompareAndSwap(_)
+                        | ReduceError::UnsupportedOutputElem(_)
+                )
+            }) {
+                continue; // We don't test in that case.
+            }
+
+            let bytes = client.read_one(output_handle);
+            assert_approx_equal(F::EI::from_bytes(&bytes), expected);
+        }
+    }
+
+    /// Check the mean of [reduce_with_lengths] against a reference computed on the host, with
+    /// lengths from zero to past the end of the axis.
+    pub fn test_reduce_with_lengths<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let axis = self.axis.unwrap();
+        let axis_len = self.shape[axis];
+        let lengths = (0..self.num_output_values())
+            .map(|i| [0, 3, axis_len, 1, axis_len / 2, axis_len + 2][i % 6] as u32)
+            .collect::<Vec<_>>();
+
+        let mut sums = vec![0.0f32; self.num_output_values()];
+        for input_index in 0..self.input_size() {
+            let Some(coordinate) = self.to_input_coordinate(input_index) else {
+                continue;
+            };
+            let output_index = self.to_output_index(input_index).unwrap();
+            if coordinate[axis] < lengths[output_index] as usize {
+                sums[output_index] += input_values[input_index].to_f32().unwrap();
+            }
+        }
+        let expected = sums
+            .iter()
+            .zip(lengths.iter())
+            .map(|(sum, length)| F::EI::new(sum / (*length as usize).clamp(1, axis_len) as f32))
+            .collect::<Vec<_>>();
+
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let mut lengths_shape = self.shape.clone();
+        lengths_shape.remove(axis);
+        let lengths_stride = contiguous_strides(&lengths_shape);
+        let lengths_handle = client.create(u32::as_bytes(&lengths));
+        let lengths = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &lengths_handle,
+                &lengths_stride,
+                &lengths_shape,
+                size_of::<u32>(),
+            )
+        };
+        let mut output_shape = self.shape.clone();
+        output_shape[axis] = 1;
+        let output_stride = self.output_stride();
+        let output_handle = client.empty(self.num_output_values() * size_of::<F::EI>());
+        let output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &output_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<F::EI>(),
+            )
+        };
+
+        reduce_with_lengths::<R, F, F::EI, Mean>(&client, input, lengths, output, axis, ())
+            .unwrap();
+
+        let bytes = client.read_one(output_handle);
+        assert_approx_equal(F::EI::from_bytes(&bytes), &expected);
+    }
+
+    /// Check the inclusive and exclusive cumulative sums and the inclusive running max of
+    /// [segmented_scan] with segments of 3 positions alternating between two ids, so each id
+    /// starts several segments, against a reference on the host.
+    pub fn test_segmented_scan<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let axis = self.axis.unwrap();
+        let segments = (0..self.shape[axis])
+            .map(|k| (k / 3 % 2) as u32)
+            .collect::<Vec<_>>();
+
+        let output_stride = contiguous_strides(&self.shape);
+        let num_output_values = self.shape.iter().product::<usize>();
+        let mut expected_inclusive = vec![F::EI::from_int(0); num_output_values];
+        let mut expected_exclusive = vec![F::EI::from_int(0); num_output_values];
+        let mut expected_max = vec![F::EI::from_int(0); num_output_values];
+        for input_index in 0..self.input_size() {
+            let Some(mut coordinate) = self.to_input_coordinate(input_index) else {
+                continue;
+            };
+            let output_index = coordinate
+                .iter()
+                .zip(output_stride.iter())
+                .map(|(coordinate, stride)| coordinate * stride)
+                .sum::<usize>();
+
+            let position = coordinate[axis];
+            let mut start = position;
+            while start > 0 && segments[start - 1] == segments[position] {
+                start -= 1;
+            }
+            let items = (start..=position)
+                .map(|k| {
+                    coordinate[axis] = k;
+                    let index = coordinate
+                        .iter()
+                        .zip(self.stride.iter())
+                        .map(|(coordinate, stride)| coordinate * stride)
+                        .sum::<usize>();
+                    input_values[index].to_f32().unwrap()
+                })
+                .collect::<Vec<_>>();
+            let (last, previous) = items.split_last().unwrap();
+
+            let exclusive = previous.iter().sum::<f32>();
+            expected_exclusive[output_index] = F::EI::new(exclusive);
+            expected_inclusive[output_index] = F::EI::new(exclusive + last);
+            expected_max[output_index] = F::EI::new(items.iter().copied().fold(f32::MIN, f32::max));
+        }
+
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let segments_handle = client.create(u32::as_bytes(&segments));
+        let segments_shape = [segments.len()];
+        let segments = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &segments_handle,
+                &[1],
+                &segments_shape,
+                size_of::<u32>(),
+            )
+        };
+        let output_handles = [0, 1, 2].map(|_| {
+            client.create(F::EI::as_bytes(&vec![
+                F::EI::from_int(0);
+                num_output_values
+            ]))
+        });
+        let [inclusive, exclusive, max] = [0, 1, 2].map(|i| unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &output_handles[i],
+                &output_stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        });
+
+        segmented_scan::<R, F, F::EI, Sum>(
+            &client,
+            input,
+            segments,
+            inclusive,
+            axis,
+            ScanMode::Inclusive,
+            SumConfig::default(),
+        )
+        .unwrap();
+        segmented_scan::<R, F, F::EI, Sum>(
+            &client,
+            input,
+            segments,
+            exclusive,
+            axis,
+            ScanMode::Exclusive,
+            SumConfig::default(),
+        )
+        .unwrap();
+        segmented_scan::<R, F, F::EI, Max>(
+            &client,
+            input,
+            segments,
+            max,
+            axis,
+            ScanMode::Inclusive,
+            (),
+        )
+        .unwrap();
+
+        let [inclusive_handle, exclusive_handle, max_handle] = output_handles;
+        for (handle, expected) in [
+            (inclusive_handle, expected_inclusive),
+            (exclusive_handle, expected_exclusive),
+            (max_handle, expected_max),
+        ] {
+            let bytes = client.read_one(handle);
+            assert_approx_equal(F::EI::from_bytes(&bytes), &expected);
+        }
+    }
+
+    /// Check the counts and the density of [reduce_histogram] with 5 bins over `(-1.5, 1.0)`,
+    /// against a reference computed on the host.
+    ///
+    /// The random items fall on the edges of the bins, and some of them are outside the range.
+    pub fn test_histogram<F, R>(&self, device: &R::Device, outliers: HistogramOutliers)
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let axis = self.axis.unwrap();
+        let (bins, min, max) = (5, -1.5f32, 1.0f32);
+        let scale = bins as f32 / (max - min);
+        let bin_width = (max - min) / bins as f32;
+
+        let mut output_shape = self.shape.clone();
+        output_shape[axis] = bins;
+        let output_stride = contiguous_strides(&output_shape);
+        let num_output_values = output_shape.iter().product::<usize>();
+
+        // The totals are kept at the index of the first bin of each slice.
+        let mut counts = vec![0.0f32; num_output_values];
+        let mut totals = vec![0.0f32; num_output_values];
+        for index in 0..self.input_size() {
+            let Some(mut coordinate) = self.to_input_coordinate(index) else {
+                continue;
+            };
+            let item = input_values[index].to_f32().unwrap();
+            if matches!(outliers, HistogramOutliers::Drop) && !(min..=max).contains(&item) {
+                continue;
+            }
+            let bin = (((item - min) * scale).max(0.0) as usize).min(bins - 1);
+            coordinate[axis] = 0;
+            let first = coordinate
+                .iter()
+                .zip(&output_stride)
+                .map(|(c, s)| c * s)
+                .sum::<usize>();
+            counts[first + bin * output_stride[axis]] += 1.0;
+            totals[first] += 1.0;
+        }
+        let density = (0..num_output_values)
+            .map(|i| {
+                let first = i - (i / output_stride[axis]) % bins * output_stride[axis];
+                if totals[first] > 0.0 {
+                    counts[i] / (totals[first] * bin_width)
+                } else {
+                    0.0
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        for (normalize, expected) in [(false, counts), (true, density)] {
+            let output_handle = client.empty(num_output_values * size_of::<F::EI>());
+            let output = unsafe {
+                TensorHandleRef::<R>::from_raw_parts(
+                    &output_handle,
+                    &output_stride,
+                    &output_shape,
+                    size_of::<F::EI>(),
+                )
+            };
+            reduce_histogram::<R, F::EI, F::EI>(
+                &client,
+                input,
+                output,
+                axis,
+                Histogram {
+                    bins: bins as u32,
+                    outliers,
+                    density: normalize,
+                },
+                (min, max),
+            )
+            .unwrap();
+
+            let expected = expected.into_iter().map(F::EI::new).collect::<Vec<_>>();
+            let bytes = client.read_one(output_handle);
+            assert_approx_equal(F::EI::from_bytes(&bytes), &expected);
+        }
+    }
+
+    /// Check the [frobenius_norm] of the whole contiguous input against the norm computed on the
+    /// host, then check that a transposed view of it is refused.
+    pub fn test_frobenius_norm<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let expected = input_values
+            .iter()
+            .map(|value| value.to_f64().unwrap().powi(2))
+            .sum::<f64>()
+            .sqrt();
+
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let norm = frobenius_norm::<R, F>(&client, input).unwrap() as f64;
+        assert!(
+            (norm - expected).abs() <= 1e-4 * expected,
+            "Frobenius norm {norm}, expected {expected}"
+        );
+
+        let rank = self.shape.len();
+        let mut shape = self.shape.clone();
+        let mut stride = self.stride.clone();
+        shape.swap(rank - 2, rank - 1);
+        stride.swap(rank - 2, rank - 1);
+        let transposed = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(&input_handle, &stride, &shape, size_of::<F::EI>())
+        };
+        assert_eq!(
+            frobenius_norm::<R, F>(&client, transposed),
+            Err(ReduceError::InputNotContiguous {
+                shape,
+                strides: stride
+            })
+        );
+    }
+
+    /// Check the p50 and p90 estimates of [reduce_quantiles] with 16 bins over `(-2.0, 2.0)`,
+    /// against the exact quantiles of the sorted slices on the host, within one bin width.
+    pub fn test_quantiles<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let axis = self.axis.unwrap();
+        let quantiles = [0.5f32, 0.9];
+        let (bins, min, max) = (16, -2.0f32, 2.0f32);
+        let bin_width = (max - min) / bins as f32;
+
+        let mut output_shape = self.shape.clone();
+        output_shape[axis] = quantiles.len();
+        let output_stride = contiguous_strides(&output_shape);
+        let num_output_values = output_shape.iter().product::<usize>();
+
+        let mut slices = vec![Vec::new(); self.num_output_values()];
+        let mut firsts = vec![0; self.num_output_values()];
+        for index in 0..self.input_size() {
+            let Some(mut coordinate) = self.to_input_coordinate(index) else {
+                continue;
+            };
+            let output_index = self.to_output_index(index).unwrap();
+            slices[output_index].push(input_values[index].to_f32().unwrap());
+            coordinate[axis] = 0;
+            firsts[output_index] = coordinate
+                .iter()
+                .zip(&output_stride)
+                .map(|(c, s)| c * s)
+                .sum::<usize>();
+        }
+        let mut expected = vec![0.0f32; num_output_values];
+        for (mut slice, first) in slices.into_iter().zip(firsts) {
+            slice.sort_by(f32::total_cmp);
+            for (j, q) in quantiles.iter().enumerate() {
+                let rank = ((q * slice.len() as f32).ceil() as usize).max(1);
+                expected[first + j * output_stride[axis]] = slice[rank - 1];
+            }
+        }
+
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let output_handle = client.empty(num_output_values * size_of::<F::EI>());
+        let output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &output_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<F::EI>(),
+            )
+        };
+        reduce_quantiles::<R, F::EI, F::EI>(
+            &client,
+            input,
+            output,
+            axis,
+            &quantiles,
+            bins,
+            (min, max),
+        )
+        .unwrap();
+
+        let bytes = client.read_one(output_handle);
+        let actual = F::EI::from_bytes(&bytes);
+        for (i, (actual, expected)) in actual.iter().zip(expected).enumerate() {
+            let actual = actual.to_f32().unwrap();
+            // One bin width, with some room for the rounding of the output.
+            assert!(
+                (actual - expected).abs() <= bin_width + 1e-2,
+                "Quantile {i}: estimated {actual}, exact {expected}"
+            );
+        }
+    }
+
+    /// Normalize the slices along the axis with [softmax_axis] and compare with a softmax
+    /// computed on the host, then check that an axis longer than [SOFTMAX_MAX_AXIS] is refused.
+    pub fn test_softmax<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let input_values: Vec<F::EI> = self.random_input_values();
+        let axis = self.axis.unwrap();
+        let output_stride = contiguous_strides(&self.shape);
+        let num_output_values = self.shape.iter().product::<usize>();
+
+        let input_index = |output_index: usize| {
+            self.shape
+                .iter()
+                .zip(&output_stride)
+                .zip(&self.stride)
+                .map(|((shape, output_stride), stride)| {
+                    (output_index / output_stride) % shape * stride
+                })
+                .sum::<usize>()
+        };
+        let mut expected = vec![0.0f32; num_output_values];
+        for first in 0..num_output_values {
+            if (first / output_stride[axis]) % self.shape[axis] != 0 {
+                continue;
+            }
+            let positions = (0..self.shape[axis])
+                .map(|k| first + k * output_stride[axis])
+                .collect::<Vec<_>>();
+            let items = positions
+                .iter()
+                .map(|position| input_values[input_index(*position)].to_f32().unwrap())
+                .collect::<Vec<_>>();
+            let max = items.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+            let sum = items.iter().map(|item| (item - max).exp()).sum::<f32>();
+            for (position, item) in positions.into_iter().zip(items) {
+                expected[position] = (item - max).exp() / sum;
+            }
+        }
+
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let output_handle = client.empty(num_output_values * size_of::<F::EI>());
+        let output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &output_handle,
+                &output_stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        softmax_axis::<R, F::EI, F::EI>(&client, input, output, axis).unwrap();
+
+        let expected = expected.into_iter().map(F::EI::new).collect::<Vec<_>>();
+        let bytes = client.read_one(output_handle);
+        assert_approx_equal(F::EI::from_bytes(&bytes), &expected);
+
+        let long_shape = [2, SOFTMAX_MAX_AXIS + 1];
+        let long_stride = contiguous_strides(&long_shape);
+        let long_handle = client.empty(long_shape.iter().product::<usize>() * size_of::<F::EI>());
+        let long = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &long_handle,
+                &long_stride,
+                &long_shape,
+                size_of::<F::EI>(),
+            )
+        };
+        assert_eq!(
+            softmax_axis::<R, F::EI, F::EI>(&client, long, long, 1),
+            Err(ReduceError::SoftmaxAxisTooLong {
+                length: SOFTMAX_MAX_AXIS + 1,
+                max: SOFTMAX_MAX_AXIS,
+            })
+        );
+    }
+
+    /// Normalize the slices along the axis with [l1_normalize_axis] and compare with a
+    /// normalization computed on the host, where the absolute values of the random inputs are
+    /// normalized and the first slice is all zeros, so it's written according to `zero_sum`.
+    pub fn test_l1_normalize<F, R>(&self, device: &R::Device, zero_sum: ZeroSumPolicy)
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let mut input_values: Vec<F::EI> = self
+            .random_input_values::<F::EI>()
+            .into_iter()
+            .map(|item| F::EI::new(item.to_f32().unwrap().abs()))
+            .collect();
+        let axis = self.axis.unwrap();
+        let output_stride = contiguous_strides(&self.shape);
+        let num_output_values = self.shape.iter().product::<usize>();
+
+        let input_index = |output_index: usize| {
+            self.shape
+                .iter()
+                .zip(&output_stride)
+                .zip(&self.stride)
+                .map(|((shape, output_stride), stride)| {
+                    (output_index / output_stride) % shape * stride
+                })
+                .sum::<usize>()
+        };
+        for k in 0..self.shape[axis] {
+            input_values[input_index(k * output_stride[axis])] = F::EI::from_int(0);
+        }
+
+        let mut expected = vec![0.0f32; num_output_values];
+        for first in 0..num_output_values {
+            if (first / output_stride[axis]) % self.shape[axis] != 0 {
+                continue;
+            }
+            let positions = (0..self.shape[axis])
+                .map(|k| first + k * output_stride[axis])
+                .collect::<Vec<_>>();
+            let items = positions
+                .iter()
+                .map(|position| input_values[input_index(*position)].to_f32().unwrap())
+                .collect::<Vec<_>>();
+            let sum = items.iter().sum::<f32>();
+            for (position, item) in positions.into_iter().zip(items) {
+                expected[position] = match (sum == 0.0, zero_sum) {
+                    (false, _) => item / sum,
+                    (true, ZeroSumPolicy::Zero) => 0.0,
+                    (true, ZeroSumPolicy::Uniform) => 1.0 / self.shape[axis] as f32,
+                };
+            }
+        }
+
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let output_handle = client.empty(num_output_values * size_of::<F::EI>());
+        let output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &output_handle,
+                &output_stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        l1_normalize_axis::<R, F, F::EI>(&client, input, output, axis, self.strategy, zero_sum)
+            .unwrap();
+
+        let expected = expected.into_iter().map(F::EI::new).collect::<Vec<_>>();
+        let bytes = client.read_one(output_handle);
+        assert_approx_equal(F::EI::from_bytes(&bytes), &expected);
+    }
+
+    /// Sum rows whose exact sums lie halfway or a quarter of the way between two `f16` values
+    /// with [reduce_with_rounding], and check that each sum rounds according to `rounding`.
+    ///
+    /// Skipped on backends without `f16`.
+    pub fn test_rounding<F, R>(&self, device: &R::Device, rounding: ReduceRounding)
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        if !client
+            .properties()
+            .supports_type(half::f16::as_type_native_unchecked())
+        {
+            return; // We don't test in that case.
+        }
+        let axis = self.axis.unwrap();
+
+        // The `f16` values around one are spaced by `2^-10`, so `1 + 2^-11` is halfway between
+        // `1` and `1 + 2^-10`, and `1 + 3 * 2^-11` is halfway between it and `1 + 2^-9`.
+        let half_step = 2.0f32.powi(-11);
+        let rows = [
+            [1.0, half_step],
+            [-1.0, -half_step],
+            [1.0, 3.0 * half_step],
+            [-1.0, -3.0 * half_step],
+        ];
+        let input_values = (0..self.input_size())
+            .map(|i| {
+                let row = (i / self.stride[0]) % rows.len();
+                F::EI::new(rows[row][(i / self.stride[1]) % 2])
+            })
+            .collect::<Vec<_>>();
+
+        let low = 1.0 + 2.0f32.powi(-10);
+        let high = 1.0 + 2.0f32.powi(-9);
+        let expected = match rounding {
+            ReduceRounding::NearestEven => [1.0, -1.0, high, -high],
+            ReduceRounding::TowardZero => [1.0, -1.0, low, -low],
+            ReduceRounding::Up => [low, -1.0, high, -low],
+            ReduceRounding::Down => [1.0, -low, low, -high],
+        };
+        let expected = (0..self.num_output_values())
+            .map(|i| half::f16::from_f32(expected[i % expected.len()]))
+            .collect::<Vec<_>>();
+
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let mut output_shape = self.shape.clone();
+        output_shape[axis] = 1;
+        let output_stride = self.output_stride();
+        let output_handle = client.empty(self.num_output_values() * size_of::<half::f16>());
+        let output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &output_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<half::f16>(),
+            )
+        };
+
+        reduce_with_rounding::<R, F, half::f16, Sum>(
+            &client,
+            input,
+            output,
+            axis,
+            self.strategy,
+            SumConfig::default(),
+            rounding,
+        )
+        .unwrap();
+
+        let bytes = client.read_one(output_handle);
+        assert_eq!(half::f16::from_bytes(&bytes), expected.as_slice());
+    }
+
+    /// Sum rows mixing subnormals with normal floats using [reduce_with_subnormals], where the
+    /// even rows hold only subnormals and zeros. The sums are exact, so the outputs are compared
+    /// bit for bit, and the even rows are exactly zero when flushing.
+    ///
+    /// Preserving the subnormals is skipped on backends flushing them where they can't be
+    /// emulated.
+    pub fn test_subnormals<F, R>(&self, device: &R::Device, policy: SubnormalPolicy)
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let axis = self.axis.unwrap();
+        let tiny = F::EI::MIN_POSITIVE / F::EI::new(64.0);
+        let normal = F::EI::MIN_POSITIVE * F::EI::new(2.0);
+        let input_values = (0..self.input_size())
+            .map(|i| {
+                let row = i / self.stride[0];
+                if row % 2 == 1 && i % 8 == 0 {
+                    normal
+                } else {
+                    tiny * F::EI::new((i % 4) as f32)
+                }
+            })
+            .collect::<Vec<_>>();
+        let kept_values = input_values
+            .iter()
+            .map(|value| match policy {
+                SubnormalPolicy::FlushToZero if *value != normal => F::EI::new(0.0),
+                _ => *value,
+            })
+            .collect::<Vec<_>>();
+        let expected_values = self.cpu_sum(&kept_values);
+
+        let input_handle = client.create(F::EI::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let mut output_shape = self.shape.clone();
+        output_shape[axis] = 1;
+        let output_stride = self.output_stride();
+        let output_handle = client.empty(self.num_output_values() * size_of::<F::EI>());
+        let output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &output_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<F::EI>(),
+            )
+        };
+
+        let result = reduce_with_subnormals::<R, F, F::EI, Sum>(
+            &client,
+            input,
+            output,
+            axis,
+            self.strategy,
+            SumConfig::default(),
+            policy,
+        );
+        if matches!(result, Err(ReduceError::SubnormalsFlushed)) {
+            return; // We don't test in that case.
+        }
+        result.unwrap();
+
+        let bytes = client.read_one(output_handle);
+        assert_eq!(F::EI::from_bytes(&bytes), expected_values.as_slice());
+    }
+
+    /// Check [reduce_weighted_sum] and [reduce_weighted_mean] with weights in `[0.25, 2]`.
+    pub fn test_weighted<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let client = R::client(device);
+        let values: Vec<F::EI> = self.random_input_values();
+        let weights = (0..values.len())
+            .map(|i| F::EI::new(0.25 * ((i * 5) % 8 + 1) as f32))
+            .collect::<Vec<_>>();
+
+        let products = values
+            .iter()
+            .zip(weights.iter())
+            .map(|(value, weight)| *value * *weight)
+            .collect::<Vec<_>>();
+        let expected_sum = self.cpu_sum(&products);
+        let expected_mean = expected_sum
+            .iter()
+            .zip(self.cpu_sum(&weights))
+            .map(|(sum, weight)| *sum / weight)
+            .collect::<Vec<_>>();
+
+        let values_handle = client.create(F::EI::as_bytes(&values));
+        let weights_handle = client.create(F::EI::as_bytes(&weights));
+        let values = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &values_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let weights = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &weights_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<F::EI>(),
+            )
+        };
+
+        let mut output_shape = self.shape.clone();
+        output_shape[self.axis.unwrap()] = 1;
+        let output_stride = self.output_stride();
+        let output_size = self.num_output_values() * size_of::<F::EI>();
+        let sum_handle = client.empty(output_size);
+        let mean_handle = client.empty(output_size);
+        let sum_output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &sum_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<F::EI>(),
+            )
+        };
+        let mean_output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &mean_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<F::EI>(),
+            )
+        };
+
+        let axis = self.axis.unwrap();
+        let result = reduce_weighted_sum::<R, F, F::EI>(
+            &client,
+            values,
+            weights,
+            sum_output,
+            axis,
+            self.strategy,
+        );
+        if self.strategy.is_some_and(|strategy| strategy.naive) {
+            assert_eq!(result, Err(ReduceError::NaiveUnsupported));
+            return;
+        }
+        if result.is_err_and(|e| {
+            e == ReduceError::PlanesUnavailable || e == ReduceError::ImprecisePlaneDim
+        }) {
+            return; // We don't test in that case.
+        }
+        reduce_weighted_mean::<R, F, F::EI>(
+            &client,
+            values,
+            weights,
+            mean_output,
+            axis,
+            self.strategy,
+        )
+        .unwrap();
+
+        let bytes = client.read_one(sum_handle);
+        assert_approx_equal(F::EI::from_bytes(&bytes), &expected_sum);
+        let bytes = client.read_one(mean_handle);
+        assert_approx_equal(F::EI::from_bytes(&bytes), &expected_mean);
+        let bytes = client.read_one(sum_handle);
+        assert_approx_equal(F::EI::from_bytes(&bytes), &expected_sum);
+    }
+
+    pub fn test_shared_sum<F, R>(&self, device: &R::Device)
+    where
+        F: Float + CubeElement + std::fmt::Display,
+        R: Runtime,
+    {
+        let input_values: Vec<F> = self.random_input_values();
+        let mut expected = F::from_int(0);
+        for v in input_values.iter() {
+            expected += *v;
+        }
+        self.run_shared_sum_test::<F, R>(device, input_values, expected);
+    }
+
+    /// Sum `u8` rows with every [OverflowMode], where the even rows overflow the output
+    /// and the odd rows don't, then check that [reduce_sum_checked] keeps the wrapped sums and
+    /// raises its overflow flag only when a row overflows, with the strategy of the test.
+    ///
+    /// Assumes a contiguous input reduced along its last axis. Skipped on backends without `u8`.
+    pub fn test_integer_sum_overflow<R: Runtime>(&self, device: &R::Device) {
+        if !R::client(device)
+            .properties()
+            .supports_type(u8::as_type_native_unchecked())
+        {
+            return; // We don't test in that case.
+        }
+        let num_rows = self.num_output_values();
+        let row_length = self.shape[self.axis.unwrap()];
+        let row_value = |row: usize| if row % 2 == 0 { 10 } else { 1 };
+
+        let input_values: Vec<u8> = (0..num_rows)
+            .flat_map(|row| std::iter::repeat_n(row_value(row), row_length))
+            .collect();
+        let sums: Vec<usize> = (0..num_rows)
+            .map(|row| row_value(row) as usize * row_length)
+            .collect();
+
+        let wrapped: Vec<u8> = sums.iter().map(|sum| *sum as u8).collect();
+        let saturated = sums.iter().map(|sum| (*sum).min(255) as u8).collect();
+        for (mode, expected_values) in [
+            (OverflowMode::Wrap, wrapped.clone()),
+            (OverflowMode::Saturate, saturated),
+            (OverflowMode::Error, wrapped.clone()),
+        ] {
+            self.run_reduce_test_with_config::<u8, u8, R, IntegerSum>(
+                device,
+                input_values.clone(),
+                expected_values,
+                mode,
+                R::max_cube_count(),
+            );
+        }
+
+        // The checked sum writes the wrapped sums along with the overflow.
+        let Some((result, output)) = self.run_reduce_sum_checked::<R>(device, &input_values) else {
+            return; // The strategy isn't supported by the client.
+        };
+        assert_eq!(result, Err(ReduceError::Overflow));
+        assert_eq!(output, wrapped);
+
+        let ones = vec![1; input_values.len()];
+        let (result, output) = self
+            .run_reduce_sum_checked::<R>(device, &ones)
+            .expect("The strategy is supported");
+        assert_eq!(result, Ok(()));
+        assert_eq!(output, vec![row_length as u8; num_rows]);
+    }
+
+    /// Sum `f32` rows starting with `±1` followed by items of `±2^-24`, which are lost when added
+    /// one at a time to `1`, and check that the [compensated](SumConfig::compensated) [Sum]
+    /// recovers them while the plain one of the [naive](ReduceStrategy::naive) strategy doesn't.
+    ///
+    /// Assumes a contiguous input reduced along its last axis.
+    pub fn test_compensated_sum<R: Runtime>(&self, device: &R::Device) {
+        let client = R::client(device);
+        let num_rows = self.num_output_values();
+        let row_length = self.shape[self.axis.unwrap()];
+        let tiny = 2.0f32.powi(-24);
+        let sign = |row: usize| if row % 2 == 0 { 1.0 } else { -1.0 };
+
+        let input_values: Vec<f32> = (0..num_rows)
+            .flat_map(|row| {
+                std::iter::once(sign(row))
+                    .chain(std::iter::repeat_n(sign(row) * tiny, row_length - 1))
+            })
+            .collect();
+        let exact: Vec<f64> = (0..num_rows)
+            .map(|row| sign(row) as f64 * (1.0 + (row_length - 1) as f64 * tiny as f64))
+            .collect();
+
+        let input_handle = client.create(f32::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<f32>(),
+            )
+        };
+        let mut output_shape = self.shape.clone();
+        output_shape[self.axis.unwrap()] = 1;
+        let output_stride = self.output_stride();
+        let sum = |strategy: Option<ReduceStrategy>, compensated: bool| {
+            let output_handle = client.empty(num_rows * size_of::<f32>());
+            let output = unsafe {
+                TensorHandleRef::<R>::from_raw_parts(
+                    &output_handle,
+                    &output_stride,
+                    &output_shape,
+                    size_of::<f32>(),
+                )
+            };
+            reduce::<R, f32, f32, Sum>(
+                &client,
+                input,
+                output,
+                self.axis.unwrap(),
+                strategy,
+                SumConfig {
+                    compensated,
+                    ..Default::default()
+                },
+            )
+            .map(|_| f32::from_bytes(&client.read_one(output_handle)).to_vec())
+        };
+        let max_error = |values: &[f32]| {
+            values
+                .iter()
+                .zip(&exact)
+                .map(|(value, exact)| (*value as f64 - exact).abs())
+                .fold(0.0, f64::max)
+        };
+
+        // Each item is at most half the spacing of the floats around `1`.
+        let plain = sum(Some(ReduceStrategy::naive()), false).unwrap();
+        assert_eq!(max_error(&plain), (row_length - 1) as f64 * tiny as f64);
+
+        let compensated = sum(self.strategy, true);
+        if compensated.as_ref().is_err_and(|e| {
+            *e == ReduceError::PlanesUnavailable || *e == ReduceError::ImprecisePlaneDim
+        }) {
+            return; // We don't test in that case.
+        }
+        // A few items may still be lost when a plane adds them to `1` inside `plane_sum`.
+        assert!(
+            max_error(&compensated.unwrap()) < 64.0 * tiny as f64,
+            "The compensated sum doesn't recover the small items",
+        );
+    }
+
+    /// Reduce random `u32` words with [Popcount], with every bit valid and with the last 5 bits
+    /// of the final word of each row masked out, against the popcount of the host.
+    pub fn test_popcount<R: Runtime>(&self, device: &R::Device) {
+        let rng = StdRng::seed_from_u64(self.pseudo_random_seed());
+        let distribution = Uniform::new_inclusive(0, u32::MAX).unwrap();
+        let input_values: Vec<u32> = distribution
+            .sample_iter(rng)
+            .take(self.input_size())
+            .collect();
+
+        let axis = self.axis.unwrap();
+        let row_bits = self.shape[axis] as u32 * 32;
+        for valid_bits in [None, Some(row_bits - 5)] {
+            let mut expected_values = vec![0; self.num_output_values()];
+            for (input_index, &word) in input_values.iter().enumerate() {
+                if let Some(output_index) = self.to_output_index(input_index) {
+                    let coordinate = self.to_input_coordinate(input_index).unwrap()[axis] as u32;
+                    let mask = match valid_bits {
+                        Some(valid_bits) => {
+                            let remaining = valid_bits.saturating_sub(coordinate * 32);
+                            if remaining >= 32 {
+                                u32::MAX
+                            } else {
+                                (1 << remaining) - 1
+                            }
+                        }
+                        None => u32::MAX,
+                    };
+                    expected_values[output_index] += (word & mask).count_ones();
+                }
+            }
+
+            self.run_reduce_test_with_config::<u32, u32, R, Popcount>(
+                device,
+                input_values.clone(),
+                expected_values,
+                PopcountConfig { valid_bits },
+                R::max_cube_count(),
+            );
+        }
+    }
+
+    /// Count the `i32` items equal to each label with [CountEqual], including the label `0`
+    /// which is also the null input of most instructions, against the counts of the host.
+    pub fn test_count_equal_exact<R: Runtime>(&self, device: &R::Device) {
+        let input_values: Vec<i32> = (0..self.input_size())
+            .map(|i| ((i * 7) % 5) as i32 - 2)
+            .collect();
+
+        for target in [-2, 0, 1, 3] {
+            let mut expected_values = vec![0u32; self.num_output_values()];
+            for (input_index, &value) in input_values.iter().enumerate() {
+                if let Some(output_index) = self.to_output_index(input_index)
+                    && value == target
+                {
+                    expected_values[output_index] += 1;
+                }
+            }
+
+            self.run_reduce_test_with_config::<i32, u32, R, CountEqual>(
+                device,
+                input_values.clone(),
+                expected_values,
+                CountEqualConfig::exact(target as i64),
+                R::max_cube_count(),
+            );
+        }
+    }
+
+    /// Count the random float items within a tolerance of a target with [CountEqual],
+    /// against the counts of the host computed with the same `f32` comparison.
+    pub fn test_count_equal_tolerance<F, R>(&self, device: &R::Device)
+    where
+        F: ReducePrecision,
+        F::EI: CubeElement + Float,
+        R: Runtime,
+    {
+        let input_values: Vec<F::EI> = self.random_input_values();
+
+        for (target, tolerance) in [(0.5, 0.3), (0.0, 0.05), (-1.5, 1.0)] {
+            let mut expected_values = vec![0u32; self.num_output_values()];
+            for (input_index, value) in input_values.iter().enumerate() {
+                if let Some(output_index) = self.to_output_index(input_index)
+                    && (value.to_f32().unwrap() - target).abs() <= tolerance
+                {
+                    expected_values[output_index] += 1;
+                }
+            }
+
+            self.run_reduce_test_with_config::<F, u32, R, CountEqual>(
+                device,
+                input_values.clone(),
+                expected_values,
+                CountEqualConfig::approx(target, tolerance),
+                R::max_cube_count(),
+            );
+        }
+    }
+
+    /// Reduce 4-bit values packed in `u8` and `u32` storage with [Sum] and [CountNonzero],
+    /// comparing with the same values unpacked on the host.
+    ///
+    /// The test case describes the unpacked input, which must be contiguous.
+    pub fn test_packed_4bit<R: Runtime>(&self, device: &R::Device) {
+        let client = R::client(device);
+
+        // On little-endian storage, both `u8` and `u32` hold the low nibble of each byte first.
+        let rng = StdRng::seed_from_u64(self.pseudo_random_seed());
+        let bytes: Vec<u8> = Uniform::new_inclusive(0, u8::MAX)
+            .unwrap()
+            .sample_iter(rng)
+            .take(self.input_size() / 2)
+            .collect();
+        let values: Vec<u32> = bytes
+            .iter()
+            .flat_map(|byte| [(byte & 0xF) as u32, (byte >> 4) as u32])
+            .collect();
+
+        let mut sums = vec![0; self.num_output_values()];
+        let mut counts = vec![0; self.num_output_values()];
+        for (input_index, value) in values.iter().enumerate() {
+            if let Some(output_index) = self.to_output_index(input_index) {
+                sums[output_index] += value;
+                counts[output_index] += (*value != 0) as u32;
+            }
+        }
+
+        let input_handle = client.create(&bytes);
+        self.run_reduce_packed_test::<R, u8, Sum>(&client, &input_handle, 2, &sums);
+        self.run_reduce_packed_test::<R, u8, CountNonzero>(&client, &input_handle, 2, &counts);
+        self.run_reduce_packed_test::<R, u32, Sum>(&client, &input_handle, 8, &sums);
+        self.run_reduce_packed_test::<R, u32, CountNonzero>(&client, &input_handle, 8, &counts);
+    }
+
+    fn run_reduce_packed_test<R: Runtime, S: Int, K: ReduceFamily<Config: Default>>(
+        &self,
+        client: &ComputeClient<R::Server, R::Channel>,
+        input_handle: &cubecl_core::server::Handle,
+        values_per_element: usize,
+        expected_values: &[u32],
+    ) {
+        let rank = self.shape.len();
+        let mut shape = self.shape.clone();
+        shape[rank - 1] /= values_per_element;
+        let stride = self
+            .stride
+            .iter()
+            .enumerate()
+            .map(|(axis, stride)| match axis == rank - 1 {
+                true => *stride,
+                false => stride / values_per_element,
+            })
+            .collect::<Vec<_>>();
+
+        let output_handle = client.create(u32::as_bytes(&vec![0; expected_values.len()]));
+        let mut output_shape = self.shape.clone();
+        output_shape[self.axis.unwrap()] = 1;
+        let output_stride = self.output_stride();
+
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(input_handle, &stride, &shape, size_of::<S>())
+        };
+        let output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &output_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<u32>(),
+            )
+        };
+
+        let result = reduce_packed::<R, S, Bits4, u32, u32, K>(
+            client,
+            input,
+            output,
+            self.axis.unwrap(),
+            self.strategy,
+            K::Config::default(),
+        );
+        if result.is_err_and(|e| {
+            e == ReduceError::PlanesUnavailable || e == ReduceError::ImprecisePlaneDim
+        }) {
+            return; // We don't test in that case.
+        }
+
+        let bytes = client.read_one(output_handle);
+        assert_eq!(u32::from_bytes(&bytes), expected_values);
+    }
+
+    /// Sum the `u8` input with [reduce_sum_checked] and the strategy of the test, returning its
+    /// result and the output, or `None` when the client doesn't support the strategy.
+    fn run_reduce_sum_checked<R: Runtime>(
+        &self,
+        device: &R::Device,
+        input_values: &[u8],
+    ) -> Option<(Result<(), ReduceError>, Vec<u8>)> {
+        let client = R::client(device);
+
+        let input_handle = client.create(u8::as_bytes(input_values));
+        let output_handle = client.empty(self.num_output_values());
+        let mut output_shape = self.shape.clone();
+        output_shape[self.axis.unwrap()] = 1;
+        let output_stride = self.output_stride();
+
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(&input_handle, &self.stride, &self.shape, 1)
+        };
+        let output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(&output_handle, &output_stride, &output_shape, 1)
+        };
+
+        let result = reduce_sum_checked::<R, u8, u8>(
+            &client,
+            input,
+            output,
+            self.axis.unwrap(),
+            self.strategy,
+        );
+        if result.as_ref().is_err_and(|e| {
+            matches!(
+                e,
+                ReduceError::PlanesUnavailable
+                    | ReduceError::ImprecisePlaneDim
+                    | ReduceError::PlaneDimUnsupported { .. }
+            )
+        }) {
+            return None;
+        }
+        Some((result, client.read_one(output_handle).to_vec()))
+    }
+
+    pub fn run_reduce_test<P, O, R, K>(
+        &self,
+        device: &R::Device,
+        input_values: Vec<P::EI>,
+        expected_values: Vec<O>,
+    ) where
+        P: ReducePrecision,
+        P::EI: CubeElement,
+        O: Numeric + CubeElement + std::fmt::Display,
+        R: Runtime,
+        K: ReduceFamily,
+        K::Config: Default,
+    {
+        self.run_reduce_test_with_max_cube_count::<P, O, R, K>(
+            device,
+            input_values,
+            expected_values,
+            R::max_cube_count(),
+        )
+    }
+
+    /// Same as [TestCase::run_reduce_test], but reduce as if the runtime couldn't launch
+    /// more than `max_cube_count` cubes.
+    pub fn run_reduce_test_with_max_cube_count<P, O, R, K>(
+        &self,
+        device: &R::Device,
+        input_values: Vec<P::EI>,
+        expected_values: Vec<O>,
+        max_cube_count: (u32, u32, u32),
+    ) where
+        P: ReducePrecision,
+        P::EI: CubeElement,
+        O: Numeric + CubeElement + std::fmt::Display,
+        R: Runtime,
+        K: ReduceFamily,
+        K::Config: Default,
+    {
+        self.run_reduce_test_with_config::<P, O, R, K>(
+            device,
+            input_values,
+            expected_values,
+            K::Config::default(),
+            max_cube_count,
+        )
+    }
+
+    /// Same as [TestCase::run_reduce_test_with_max_cube_count], but with an instruction
+    /// taking a config.
+    pub fn run_reduce_test_with_config<P, O, R, K>(
+        &self,
+        device: &R::Device,
+        input_values: Vec<P::EI>,
+        expected_values: Vec<O>,
+        config: K::Config,
+        max_cube_count: (u32, u32, u32),
+    ) where
+        P: ReducePrecision,
+        P::EI: CubeElement,
+        O: Numeric + CubeElement + std::fmt::Display,
+        R: Runtime,
+        K: ReduceFamily,
+    {
+        let client = R::client(device);
+
+        let input_handle = client.create(<P::EI as CubeElement>::as_bytes(&input_values));
+
+        // Zero initialize a tensor with the same shape as input
+        // except for the `self.axis` axis where the shape is 1.
+        let output_handle =
+            client.create(O::as_bytes(&vec![O::from_int(0); expected_values.len()]));
+        let mut output_shape = self.shape.clone();
+        output_shape[self.axis.unwrap()] = 1;
+        let output_stride = self.output_stride();
+
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<P>(),
+            )
+        };
+        let output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &output_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<O>(),
+            )
+        };
 
-    fn cpu_argmax<F: Float>(&self, values: &[F]) -> Vec<u32> {
-        let mut expected = vec![(F::min_value(), 0_u32); self.num_output_values()];
-        for (input_index, &value) in values.iter().enumerate() {
-            if let Some(output_index) = self.to_output_index(input_index) {
-                let (best, _) = expected[output_index];
-                if value > best {
-                    let coordinate = self.to_input_coordinate(input_index).unwrap();
-                    expected[output_index] = (value, coordinate[self.axis.unwrap()] as u32);
-                }
-            }
+        let result = reduce_with_max_cube_count::<R, P, O, K>(
+            &client,
+            input,
+            output,
+            self.axis.unwrap(),
+            self.strategy,
+            config,
+            max_cube_count,
+        );
+        if result.is_err_and(|e| {
+            matches!(
+                e,
+                ReduceError::PlanesUnavailable
+                    | ReduceError::ImprecisePlaneDim
+                    | ReduceError::PlaneDimUnsupported { .. }
+            )
+        }) {
+            return; // We don't test in that case.
         }
-        expected.into_iter().map(|(_, i)| i).collect()
+
+        let bytes = client.read_one(output_handle);
+        let output_values = O::from_bytes(&bytes);
+        assert_approx_equal(output_values, &expected_values);
     }
 
-    pub fn test_argmin<F, R>(&self, device: &R::Device)
+    /// Reduce the input split into three chunks along the axis with [reduce_concat], and compare
+    /// with reducing the whole input with [reduce].
+    pub fn run_reduce_concat_test<P, O, R, K>(&self, device: &R::Device, input_values: Vec<P::EI>)
     where
-        F: ReducePrecision + std::fmt::Display,
-        F::EI: CubeElement + Float,
+        P: ReducePrecision,
+        P::EI: CubeElement,
+        O: Numeric + CubeElement + std::fmt::Display,
         R: Runtime,
+        K: ReduceFamily,
+        K::Config: Default,
     {
-        let input_values: Vec<F::EI> = self.random_input_values();
-        let expected_values = match self.axis {
-            Some(axis) if self.stride[axis] == 0 => vec![0; input_values.len()],
-            _ => self.cpu_argmin(&input_values),
+        let client = R::client(device);
+        let axis = self.axis.unwrap();
+        let length = self.shape[axis];
+
+        let mut output_shape = self.shape.clone();
+        output_shape[axis] = 1;
+        let output_stride = self.output_stride();
+        let num_outputs = self.num_output_values();
+        let expected_handle = client.create(O::as_bytes(&vec![O::from_int(0); num_outputs]));
+        let output_handle = client.create(O::as_bytes(&vec![O::from_int(0); num_outputs]));
+
+        let input_handle = client.create(<P::EI as CubeElement>::as_bytes(&input_values));
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<P>(),
+            )
         };
-        self.run_reduce_test::<F, u32, R, ArgMin>(device, input_values, expected_values)
-    }
+        let expected = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &expected_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<O>(),
+            )
+        };
+        let result = reduce::<R, P, O, K>(
+            &client,
+            input,
+            expected,
+            axis,
+            self.strategy,
+            K::Config::default(),
+        );
+        if result.is_err_and(|e| {
+            matches!(
+                e,
+                ReduceError::PlanesUnavailable
+                    | ReduceError::ImprecisePlaneDim
+                    | ReduceError::PlaneDimUnsupported { .. }
+            )
+        }) {
+            return; // We don't test in that case.
+        }
 
-    fn cpu_argmin<F: Float>(&self, values: &[F]) -> Vec<u32> {
-        let mut expected = vec![(F::max_value(), 0_u32); self.num_output_values()];
-        for (input_index, &value) in values.iter().enumerate() {
-            if let Some(output_index) = self.to_output_index(input_index) {
-                let (best, _) = expected[output_index];
-                if value < best {
-                    let coordinate = self.to_input_coordinate(input_index).unwrap();
-                    expected[output_index] = (value, coordinate[self.axis.unwrap()] as u32);
-                }
-            }
+        // Copy each chunk into its own contiguous tensor.
+        let bounds = [0, length / 4, length / 2, length];
+        let mut chunks = Vec::new();
+        for range in bounds.windows(2) {
+            let mut shape = self.shape.clone();
+            shape[axis] = range[1] - range[0];
+            let strides = contiguous_strides(&shape);
+            let num_elems = shape.iter().product::<usize>();
+            let values = (0..num_elems)
+                .map(|index| {
+                    let input_index = (0..shape.len())
+                        .map(|dim| {
+                            let mut coordinate = (index / strides[dim]) % shape[dim];
+                            if dim == axis {
+                                coordinate += range[0];
+                            }
+                            coordinate * self.stride[dim]
+                        })
+                        .sum::<usize>();
+                    input_values[input_index]
+                })
+                .collect::<Vec<_>>();
+            let handle = client.create(<P::EI as CubeElement>::as_bytes(&values));
+            chunks.push((handle, shape, strides));
         }
-        expected.into_iter().map(|(_, i)| i).collect()
+        let inputs = chunks
+            .iter()
+            .map(|(handle, shape, strides)| unsafe {
+                TensorHandleRef::<R>::from_raw_parts(handle, strides, shape, size_of::<P>())
+            })
+            .collect::<Vec<_>>();
+        let output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &output_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<O>(),
+            )
+        };
+        reduce_concat::<R, P, O, K>(
+            &client,
+            &inputs,
+            output,
+            axis,
+            self.strategy,
+            K::Config::default(),
+        )
+        .unwrap();
+
+        let bytes = client.read_one(expected_handle);
+        let expected_values = O::from_bytes(&bytes).to_vec();
+        let bytes = client.read_one(output_handle);
+        let output_values = O::from_bytes(&bytes);
+        assert_approx_equal(output_values, &expected_values);
     }
 
-    pub fn test_mean<F, R>(&self, device: &R::Device)
+    /// Compare `Sum`, `Mean` and `ArgMax` using the strategy of the test case
+    /// with the naive strategy where each unit reduces a single output.
+    pub fn test_against_naive<F, R>(&self, device: &R::Device)
     where
         F: ReducePrecision + std::fmt::Display,
         F::EI: CubeElement + Float + std::fmt::Display,
         R: Runtime,
     {
         let input_values: Vec<F::EI> = self.random_input_values();
-        let expected_values = match self.axis {
-            Some(axis) if self.stride[axis] == 0 => input_values.clone(),
-            _ => self.cpu_mean(&input_values),
-        };
-        self.run_reduce_test::<F, F::EI, R, Mean>(device, input_values, expected_values)
-    }
 
-    fn cpu_mean<F: Float>(&self, values: &[F]) -> Vec<F> {
-        self.cpu_sum(values)
-            .into_iter()
-            .map(|sum| sum / F::new(self.shape[self.axis.unwrap()] as f32))
-            .collect()
+        let expected_values = self.reduce_naive::<F, F::EI, R, Sum>(device, &input_values);
+        self.run_reduce_test::<F, F::EI, R, Sum>(device, input_values.clone(), expected_values);
+
+        let expected_values = self.reduce_naive::<F, F::EI, R, Mean>(device, &input_values);
+        self.run_reduce_test::<F, F::EI, R, Mean>(device, input_values.clone(), expected_values);
+
+        let expected_values = self.reduce_naive::<F, u32, R, ArgMax>(device, &input_values);
+        self.run_reduce_test::<F, u32, R, ArgMax>(device, input_values, expected_values);
     }
 
-    pub fn test_prod<F, R>(&self, device: &R::Device)
+    /// Run [TestCase::test_against_naive] with each strategy listed by
+    /// [ReduceStrategy::all_supported], ignoring the strategy of the test case.
+    pub fn test_all_strategies_against_naive<F, R>(&self, device: &R::Device)
     where
         F: ReducePrecision + std::fmt::Display,
         F::EI: CubeElement + Float + std::fmt::Display,
         R: Runtime,
     {
-        let input_values: Vec<F::EI> = self.random_input_values();
-        let expected_values = match self.axis {
-            Some(axis) if self.stride[axis] == 0 => input_values
-                .iter()
-                .map(|v| Self::powf(*v, self.shape[axis]))
-                .collect(),
-            _ => self.cpu_prod(&input_values),
-        };
-        self.run_reduce_test::<F, F::EI, R, Prod>(device, input_values, expected_values)
-    }
-
-    fn powf<F: Float>(base: F, power: usize) -> F {
-        let mut result = F::new(1.0);
-        for _ in 0..power {
-            result *= base;
-        }
-        result
-    }
-
-    fn cpu_prod<F: Float>(&self, values: &[F]) -> Vec<F> {
-        let mut expected = vec![F::new(1.0); self.num_output_values()];
-
-        for (input_index, value) in values.iter().enumerate() {
-            if let Some(output_index) = self.to_output_index(input_index) {
-                expected[output_index] *= *value;
+        let client = R::client(device);
+        for strategy in ReduceStrategy::all_supported::<R>(&client) {
+            if strategy.naive {
+                continue;
             }
+            let test = TestCase {
+                shape: self.shape.clone(),
+                stride: self.stride.clone(),
+                axis: self.axis,
+                strategy: Some(strategy),
+            };
+            test.test_against_naive::<F, R>(device);
         }
-        expected
     }
 
-    pub fn test_sum<F, R>(&self, device: &R::Device)
+    fn reduce_naive<P, O, R, K>(&self, device: &R::Device, input_values: &[P::EI]) -> Vec<O>
     where
-        F: ReducePrecision + std::fmt::Display,
-        F::EI: CubeElement + Float + std::fmt::Display,
+        P: ReducePrecision,
+        P::EI: CubeElement,
+        O: Numeric + CubeElement,
         R: Runtime,
+        K: ReduceFamily,
+        K::Config: Default,
     {
-        let input_values: Vec<F::EI> = self.random_input_values();
-        let expected_values = match self.axis {
-            Some(axis) if self.stride[axis] == 0 => input_values
-                .iter()
-                .map(|v| *v * F::EI::from_int(self.shape[axis] as i64))
-                .collect(),
-            _ => self.cpu_sum(&input_values),
+        let client = R::client(device);
+
+        let input_handle = client.create(<P::EI as CubeElement>::as_bytes(input_values));
+        let mut output_shape = self.shape.clone();
+        output_shape[self.axis.unwrap()] = 1;
+        let output_stride = self.output_stride();
+        let output_handle =
+            client.create(O::as_bytes(&vec![O::from_int(0); self.num_output_values()]));
+
+        let input = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &input_handle,
+                &self.stride,
+                &self.shape,
+                size_of::<P>(),
+            )
+        };
+        let output = unsafe {
+            TensorHandleRef::<R>::from_raw_parts(
+                &output_handle,
+                &output_stride,
+                &output_shape,
+                size_of::<O>(),
+            )
         };
-        self.run_reduce_test::<F, F::EI, R, Sum>(device, input_values, expected_values)
-    }
 
-    fn cpu_sum<F: Float>(&self, values: &[F]) -> Vec<F> {
-        let mut expected = vec![F::new(0.0); self.num_output_values()];
+        reduce::<R, P, O, K>(
+            &client,
+            input,
+            output,
+            self.axis.unwrap(),
+            Some(ReduceStrategy::naive()),
+            K::Config::default(),
+        )
+        .unwrap();
 
-        for (input_index, value) in values.iter().enumerate() {
-            if let Some(output_index) = self.to_output_index(input_index) {
-                expected[output_index] += *value;
-            }
-        }
-        expected
+        let bytes = client.read_one(output_handle);
+        O::from_bytes(&bytes).to_vec()
     }
 
-    pub fn test_shared_sum<F, R>(&self, device: &R::Device)
-    where
-        F: Float + CubeElement + std::fmt::Display,
+    /// Split the input in two chunks along the reduced axis and feed them one after the other
+    /// to [reduce_update]. The reduced axis must be the outermost one of a contiguous tensor.
+    pub fn run_reduce_update_test<P, O, R, K>(
+        &self,
+        device: &R::Device,
+        input_values: Vec<P::EI>,
+        expected_values: Vec<O>,
+    ) where
+        P: ReducePrecision,
+        P::EI: CubeElement,
+        O: Numeric + CubeElement + std::fmt::Display,
         R: Runtime,
+        K: ReduceFamily,
+        K::Config: Default,
     {
-        let input_values: Vec<F> = self.random_input_values();
-        let mut expected = F::from_int(0);
-        for v in input_values.iter() {
-            expected += *v;
-        }
-        self.run_shared_sum_test::<F, R>(device, input_values, expected);
+        self.run_reduce_update_test_with_config::<P, O, R, K>(
+            device,
+            input_values,
+            expected_values,
+            K::Config::default(),
+            2,
+        )
     }
 
-    pub fn run_reduce_test<P, O, R, K>(
+    /// Same as [run_reduce_update_test](Self::run_reduce_update_test), but with the given
+    /// instruction config and number of chunks.
+    pub fn run_reduce_update_test_with_config<P, O, R, K>(
         &self,
         device: &R::Device,
         input_values: Vec<P::EI>,
         expected_values: Vec<O>,
+        config: K::Config,
+        num_chunks: usize,
     ) where
         P: ReducePrecision,
         P::EI: CubeElement,
         O: Numeric + CubeElement + std::fmt::Display,
         R: Runtime,
-        K: ReduceFamily<Config = ()>,
+        K: ReduceFamily,
     {
-        let client = R::client(device);
+        let axis = self.axis.unwrap();
+        assert_eq!(axis, 0, "Chunks are split along the outermost axis");
 
-        let input_handle = client.create(<P::EI as CubeElement>::as_bytes(&input_values));
+        let client = R::client(device);
 
-        // Zero initialize a tensor with the same shape as input
-        // except for the `self.axis` axis where the shape is 1.
         let output_handle =
             client.create(O::as_bytes(&vec![O::from_int(0); expected_values.len()]));
         let mut output_shape = self.shape.clone();
-        output_shape[self.axis.unwrap()] = 1;
+        output_shape[axis] = 1;
         let output_stride = self.output_stride();
-
-        let input = unsafe {
-            TensorHandleRef::<R>::from_raw_parts(
-                &input_handle,
-                &self.stride,
-                &self.shape,
-                size_of::<P>(),
-            )
-        };
         let output = unsafe {
             TensorHandleRef::<R>::from_raw_parts(
                 &output_handle,
@@ -523,20 +5758,41 @@ impl TestCase {
                 size_of::<O>(),
             )
         };
+        let mut accumulator = ReduceAccumulator::new(output);
 
-        let result = reduce::<R, P, O, K>(
-            &client,
-            input,
-            output,
-            self.axis.unwrap(),
-            self.strategy,
-            (),
-        );
-        if result.is_err_and(|e| {
-            e == ReduceError::PlanesUnavailable || e == ReduceError::ImprecisePlaneDim
-        }) {
-            return; // We don't test in that case.
+        for chunk in 0..num_chunks {
+            let begin = chunk * self.shape[axis] / num_chunks;
+            let end = (chunk + 1) * self.shape[axis] / num_chunks;
+            let len = end - begin;
+            let values = &input_values[begin * self.stride[axis]..end * self.stride[axis]];
+            let input_handle = client.create(<P::EI as CubeElement>::as_bytes(values));
+            let mut input_shape = self.shape.clone();
+            input_shape[axis] = len;
+
+            let input = unsafe {
+                TensorHandleRef::<R>::from_raw_parts(
+                    &input_handle,
+                    &self.stride,
+                    &input_shape,
+                    size_of::<P>(),
+                )
+            };
+
+            let result = reduce_update::<R, P, O, K>(
+                &client,
+                input,
+                &mut accumulator,
+                axis,
+                self.strategy,
+                config,
+            );
+            if result.is_err_and(|e| {
+                e == ReduceError::PlanesUnavailable || e == ReduceError::ImprecisePlaneDim
+            }) {
+                return; // We don't test in that case.
+            }
         }
+        assert_eq!(accumulator.count, self.shape[axis]);
 
         let bytes = client.read_one(output_handle);
         let output_values = O::from_bytes(&bytes);
@@ -670,6 +5926,9 @@ pub fn assert_approx_equal<N: Numeric>(actual: &[N], expected: &[N]) {
     for (i, (a, e)) in actual.iter().zip(expected.iter()).enumerate() {
         let a = a.to_f32().unwrap();
         let e = e.to_f32().unwrap();
+        if a == e || (a.is_nan() && e.is_nan()) {
+            continue;
+        }
         let diff = (a - e).abs();
         if e == 0.0 {
             assert!(
@@ -0,0 +1,169 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+use cubecl_core::future;
+use cubecl_core::prelude::*;
+use rand::{
+    SeedableRng,
+    distr::{Distribution, Uniform},
+    rngs::StdRng,
+};
+
+use crate::update::contiguous_strides;
+use crate::{
+    ReduceError, ReduceStrategy, instructions::ReduceFamily, precision::ReducePrecision, reduce,
+};
+
+/// A shape to reduce in [benchmark_reduce_strategies].
+#[derive(Debug, Clone)]
+pub struct ReduceBenchmarkShape {
+    pub shape: Vec<usize>,
+    pub axis: usize,
+}
+
+/// The timing of one strategy on one shape, measured by [benchmark_reduce_strategies].
+#[derive(Debug, Clone)]
+pub struct ReduceBenchmarkResult {
+    pub shape: ReduceBenchmarkShape,
+    pub strategy: ReduceStrategy,
+    /// The median duration of the reductions, or `None` if they couldn't be launched or
+    /// profiled.
+    pub median: Option<Duration>,
+}
+
+impl Display for ReduceBenchmarkResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let strategy = &self.strategy;
+        write!(
+            f,
+            "shape={:?} axis={} naive={} use_planes={} shared={} shared_transpose={}: ",
+            self.shape.shape,
+            self.shape.axis,
+            strategy.naive,
+            strategy.use_planes,
+            strategy.shared,
+            strategy.shared_transpose,
+        )?;
+        match self.median {
+            Some(median) => write!(f, "{median:?}"),
+            None => write!(f, "unavailable"),
+        }
+    }
+}
+
+/// The shapes reduced by [testgen_reduce_benchmark](crate::testgen_reduce_benchmark),
+/// covering short and long reductions along the innermost and outermost axes.
+pub fn default_benchmark_shapes() -> Vec<ReduceBenchmarkShape> {
+    [
+        (vec![4096, 64], 1),
+        (vec![64, 4096], 1),
+        (vec![16, 1 << 16], 1),
+        (vec![4096, 64], 0),
+        (vec![64, 4096], 0),
+    ]
+    .into_iter()
+    .map(|(shape, axis)| ReduceBenchmarkShape { shape, axis })
+    .collect()
+}
+
+/// Reduce each of the `shapes` with the instruction `Inst` and every strategy supported by the
+/// `client`, as listed by [ReduceStrategy::all_supported], and measure the median duration of
+/// `num_samples` reductions with the profiling of the client.
+///
+/// The inputs are contiguous and filled with pseudo-random values. Each strategy is launched
+/// once before it is profiled, so compiling its kernel isn't part of the timings.
+pub fn benchmark_reduce_strategies<R, P, Inst>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    shapes: &[ReduceBenchmarkShape],
+    num_samples: usize,
+) -> Vec<ReduceBenchmarkResult>
+where
+    R: Runtime,
+    P: ReducePrecision,
+    P::EI: CubeElement + Float,
+    Inst: ReduceFamily,
+    Inst::Config: Default,
+{
+    let strategies = ReduceStrategy::all_supported::<R>(client);
+    let mut results = Vec::with_capacity(shapes.len() * strategies.len());
+
+    for shape in shapes {
+        let size = shape.shape.iter().product::<usize>();
+        let rng = StdRng::seed_from_u64(size as u64);
+        let distribution = Uniform::new_inclusive(-8, 8).unwrap();
+        let input_values: Vec<P::EI> = distribution
+            .sample_iter(rng)
+            .take(size)
+            .map(|r| P::EI::new(r as f32 / 4.0))
+            .collect();
+        let input_strides = contiguous_strides(&shape.shape);
+
+        let mut output_shape = shape.shape.clone();
+        output_shape[shape.axis] = 1;
+        let output_strides = contiguous_strides(&output_shape);
+        let output_size = size / shape.shape[shape.axis];
+
+        let input_handle = client.create(P::EI::as_bytes(&input_values));
+        let output_handle = client.empty(output_size * size_of::<P::EI>());
+
+        for strategy in strategies.iter().copied() {
+            let launch = || {
+                let input = unsafe {
+                    TensorHandleRef::<R>::from_raw_parts(
+                        &input_handle,
+                        &input_strides,
+                        &shape.shape,
+                        size_of::<P::EI>(),
+                    )
+                };
+                let output = unsafe {
+                    TensorHandleRef::<R>::from_raw_parts(
+                        &output_handle,
+                        &output_strides,
+                        &output_shape,
+                        size_of::<P::EI>(),
+                    )
+                };
+                reduce::<R, P, P::EI, Inst>(
+                    client,
+                    input,
+                    output,
+                    shape.axis,
+                    Some(strategy),
+                    Inst::Config::default(),
+                )
+            };
+
+            let median = match launch() {
+                Ok(()) => profile_median::<R>(client, launch, num_samples),
+                Err(_) => None,
+            };
+            results.push(ReduceBenchmarkResult {
+                shape: shape.clone(),
+                strategy,
+                median,
+            });
+        }
+    }
+
+    results
+}
+
+/// The median duration of `num_samples` profiled calls to `launch`, or `None` if any of them
+/// couldn't be profiled.
+fn profile_median<R: Runtime>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    launch: impl Fn() -> Result<(), ReduceError>,
+    num_samples: usize,
+) -> Option<Duration> {
+    let mut durations = Vec::with_capacity(num_samples);
+    for _ in 0..num_samples.max(1) {
+        let profile = client
+            .profile(|| launch(), "reduce strategy benchmark")
+            .ok()?;
+        durations.push(future::block_on(profile.resolve()).duration());
+    }
+
+    durations.sort();
+    Some(durations[durations.len() / 2])
+}
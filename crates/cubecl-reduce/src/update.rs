@@ -0,0 +1,203 @@
+use cubecl_core::prelude::*;
+use cubecl_core::server::Handle;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise};
+use cubecl_std::tensor::index_offset_contiguous;
+
+use crate::{
+    ReduceError, ReduceStrategy, instructions::*, precision::ReducePrecision, reduce,
+    valid_output_shape, validate_axis,
+};
+
+/// The running result of a reduction updated chunk by chunk with [`reduce_update`].
+pub struct ReduceAccumulator<'a, R: Runtime> {
+    /// The output of the reduction over all the chunks seen so far.
+    ///
+    /// Its content is ignored as long as `count` is 0.
+    pub values: TensorHandleRef<'a, R>,
+    /// The number of elements already reduced along the axis.
+    pub count: usize,
+}
+
+impl<'a, R: Runtime> ReduceAccumulator<'a, R> {
+    /// Create an empty accumulator writing its results into `values`.
+    pub fn new(values: TensorHandleRef<'a, R>) -> Self {
+        Self { values, count: 0 }
+    }
+
+    /// The size in bytes of the scratch buffer needed by [`reduce_update_with_scratch`] to merge
+    /// a chunk into this accumulator, for outputs of type `Out`.
+    pub fn required_scratch_bytes<Out: Numeric>(&self) -> usize {
+        self.values.shape.iter().product::<usize>() * size_of::<Out>()
+    }
+}
+
+/// Reduce the given `axis` of the `input` chunk and merge the result into the `accumulator`.
+///
+/// After a call, the accumulator contains the reduction over the concatenation along `axis`
+/// of all the chunks given so far, so that streaming data doesn't need to be reduced from scratch.
+/// The chunk is first reduced with [`reduce`] and then merged using
+/// [`ReduceInstruction::combine_outputs`], weighting both sides by their element count.
+///
+/// Return [`ReduceError::CombineUnsupported`] when the instruction can't combine outputs, such
+/// as [`ArgMax`] or [`ArgMin`], see [`ReduceFamily::supports_combine`], and otherwise the same
+/// errors as [`reduce`], where the shape of the accumulator values plays the role of the output
+/// shape.
+pub fn reduce_update<R: Runtime, P: ReducePrecision, Out: Numeric, Inst: ReduceFamily>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    accumulator: &mut ReduceAccumulator<R>,
+    axis: usize,
+    strategy: Option<ReduceStrategy>,
+    inst_config: Inst::Config,
+) -> Result<(), ReduceError> {
+    update::<R, P, Out, Inst>(
+        client,
+        input,
+        accumulator,
+        None,
+        axis,
+        strategy,
+        inst_config,
+    )
+}
+
+/// Same as [`reduce_update`], but the chunk is reduced into the caller-provided `scratch`
+/// buffer instead of a temporary allocated at each call, so a stream of chunks can be reduced
+/// without any allocation.
+///
+/// The scratch buffer must hold at least
+/// [`required_scratch_bytes`](ReduceAccumulator::required_scratch_bytes) bytes, and can be
+/// reused by any number of calls, as long as they are on the same stream. Its content is
+/// overwritten. This returns [`ReduceError::ScratchTooSmall`] if it is smaller, and otherwise
+/// the same errors as [`reduce_update`].
+pub fn reduce_update_with_scratch<
+    R: Runtime,
+    P: ReducePrecision,
+    Out: Numeric,
+    Inst: ReduceFamily,
+>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    accumulator: &mut ReduceAccumulator<R>,
+    scratch: &Handle,
+    axis: usize,
+    strategy: Option<ReduceStrategy>,
+    inst_config: Inst::Config,
+) -> Result<(), ReduceError> {
+    let required = accumulator.required_scratch_bytes::<Out>();
+    if (scratch.size() as usize) < required {
+        return Err(ReduceError::ScratchTooSmall {
+            size: scratch.size() as usize,
+            required,
+        });
+    }
+    update::<R, P, Out, Inst>(
+        client,
+        input,
+        accumulator,
+        Some(scratch),
+        axis,
+        strategy,
+        inst_config,
+    )
+}
+
+fn update<R: Runtime, P: ReducePrecision, Out: Numeric, Inst: ReduceFamily>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    input: TensorHandleRef<R>,
+    accumulator: &mut ReduceAccumulator<R>,
+    scratch: Option<&Handle>,
+    axis: usize,
+    strategy: Option<ReduceStrategy>,
+    inst_config: Inst::Config,
+) -> Result<(), ReduceError> {
+    if !Inst::supports_combine(inst_config) {
+        return Err(ReduceError::CombineUnsupported);
+    }
+    validate_axis(input.shape.len(), axis)?;
+    valid_output_shape(input.shape, accumulator.values.shape, axis)?;
+
+    let chunk_count = input.shape[axis];
+
+    if accumulator.count == 0 {
+        reduce::<R, P, Out, Inst>(
+            client,
+            input,
+            accumulator.values,
+            axis,
+            strategy,
+            inst_config,
+        )?;
+        accumulator.count = chunk_count;
+        return Ok(());
+    }
+
+    // The chunk is reduced into a contiguous temporary before being merged.
+    let shape = accumulator.values.shape;
+    let num_elems = shape.iter().product::<usize>();
+    let strides = contiguous_strides(shape);
+    let allocated;
+    let chunk_handle = match scratch {
+        Some(scratch) => scratch,
+        None => {
+            allocated = client.empty(num_elems * size_of::<Out>());
+            &allocated
+        }
+    };
+    let chunk = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(chunk_handle, &strides, shape, size_of::<Out>())
+    };
+
+    reduce::<R, P, Out, Inst>(client, input, chunk, axis, strategy, inst_config)?;
+
+    let cube_dim = CubeDim::default();
+    let cube_count = calculate_cube_count_elemwise(num_elems, cube_dim);
+
+    unsafe {
+        combine_kernel::launch_unchecked::<P::EI, P::EA, Out, Inst, R>(
+            client,
+            cube_count,
+            cube_dim,
+            accumulator.values.as_tensor_arg(1),
+            chunk.as_tensor_arg(1),
+            ScalarArg::new(accumulator.count as u32),
+            ScalarArg::new(chunk_count as u32),
+            inst_config,
+        );
+    }
+
+    accumulator.count += chunk_count;
+    Ok(())
+}
+
+pub(crate) fn contiguous_strides(shape: &[usize]) -> Vec<usize> {
+    let mut strides = vec![1; shape.len()];
+    for i in (0..shape.len().saturating_sub(1)).rev() {
+        strides[i] = strides[i + 1] * shape[i + 1];
+    }
+    strides
+}
+
+#[cube(launch_unchecked)]
+fn combine_kernel<In: Numeric, Acc: Numeric, Out: Numeric, R: ReduceFamily>(
+    accumulator: &mut Tensor<Line<Out>>,
+    chunk: &Tensor<Line<Out>>,
+    accumulated_count: u32,
+    chunk_count: u32,
+    #[comptime] config: R::Config,
+) {
+    if ABSOLUTE_POS >= chunk.len() {
+        terminate!();
+    }
+
+    let inst = &R::Instruction::<(In, Acc)>::from_config(config);
+    let index = index_offset_contiguous(accumulator, ABSOLUTE_POS, None);
+
+    accumulator[index] = R::Instruction::<(In, Acc)>::combine_outputs::<Out>(
+        inst,
+        accumulator[index],
+        accumulated_count,
+        chunk[ABSOLUTE_POS],
+        chunk_count,
+    );
+}
@@ -0,0 +1,218 @@
+use cubecl_core::prelude::*;
+use cubecl_core::{self as cubecl, calculate_cube_count_elemwise};
+use cubecl_std::tensor::index_offset_contiguous;
+
+use crate::args::{ReduceArgs, ReduceDType};
+use crate::instructions::{Sum, SumConfig};
+use crate::precision::ReducePrecision;
+use crate::update::contiguous_strides;
+use crate::{
+    ReduceConfig, ReduceError, ReduceParams, ReduceStrategy, reduce, reduce_kernel,
+    valid_output_shape, validate_axis,
+};
+
+/// Arguments reading the products of the `values` with their `weights`, which share the same
+/// shape and strides.
+#[derive(Clone)]
+pub struct WeightedArgs;
+
+#[derive(CubeLaunch, CubeType)]
+/// Input representation for [WeightedArgs] implementing [ReduceArgs].
+pub struct WeightedInput<E: Numeric> {
+    pub values: Tensor<Line<E>>,
+    pub weights: Tensor<Line<E>>,
+}
+
+#[cube]
+impl ReduceArgs for WeightedArgs {
+    type Input<E: Numeric> = WeightedInput<E>;
+    type Output<E: Numeric> = Tensor<Line<E>>;
+    type State<P: ReduceDType> = (*const WeightedInput<P::In>, *mut Tensor<Line<P::Out>>);
+
+    fn init_state<P: ReduceDType>(
+        input: &Self::Input<P::In>,
+        output: &mut Self::Output<P::Out>,
+    ) -> Self::State<P> {
+        (input, output)
+    }
+
+    fn read_input<P: ReduceDType>(state: &Self::State<P>, index: u32) -> Line<P::In> {
+        unsafe { (*state.0).values[index] * (*state.0).weights[index] }
+    }
+
+    fn read_output<P: ReduceDType>(state: &Self::State<P>, index: u32) -> Line<P::Out> {
+        unsafe { (*state.1)[index] }
+    }
+
+    fn write_output<P: ReduceDType>(state: &mut Self::State<P>, index: u32, value: Line<P::Out>) {
+        unsafe { (*state.1)[index] = value }
+    }
+
+    fn buffer_len_input<P: ReduceDType>(state: &Self::State<P>) -> u32 {
+        unsafe { (*state.0).values.buffer_len() }
+    }
+
+    fn buffer_len_output<P: ReduceDType>(state: &Self::State<P>) -> u32 {
+        unsafe { (*state.1).buffer_len() }
+    }
+
+    fn len_input<P: ReduceDType>(state: &Self::State<P>) -> u32 {
+        unsafe { (*state.0).values.len() }
+    }
+
+    fn len_output<P: ReduceDType>(state: &Self::State<P>) -> u32 {
+        unsafe { (*state.1).len() }
+    }
+
+    fn rank_input<P: ReduceDType>(state: &Self::State<P>) -> u32 {
+        unsafe { (*state.0).values.rank() }
+    }
+
+    fn rank_output<P: ReduceDType>(state: &Self::State<P>) -> u32 {
+        unsafe { (*state.1).rank() }
+    }
+
+    fn shape_input<P: ReduceDType>(state: &Self::State<P>, dim: u32) -> u32 {
+        unsafe { (*state.0).values.shape(dim) }
+    }
+
+    fn shape_output<P: ReduceDType>(state: &Self::State<P>, dim: u32) -> u32 {
+        unsafe { (*state.1).shape(dim) }
+    }
+
+    fn stride_input<P: ReduceDType>(state: &Self::State<P>, dim: u32) -> u32 {
+        unsafe { (*state.0).values.stride(dim) }
+    }
+
+    fn stride_output<P: ReduceDType>(state: &Self::State<P>, dim: u32) -> u32 {
+        unsafe { (*state.1).stride(dim) }
+    }
+
+    fn line_size_input<P: ReduceDType>(state: &Self::State<P>) -> comptime_type!(u32) {
+        unsafe { (*state.0).values.line_size() }
+    }
+
+    fn line_size_output<P: ReduceDType>(state: &Self::State<P>) -> comptime_type!(u32) {
+        unsafe { (*state.1).line_size() }
+    }
+}
+
+/// Compute the sum of the `values` multiplied by their `weights` along the given `axis`
+/// and write the result into `output`.
+///
+/// The `weights` must have the same shape and strides as the `values`, and `output` the same
+/// shape except for a value of 1 for the given `axis`. The products are computed as `P::EI`.
+///
+/// This returns the same errors as [`reduce`], [`ReduceError::MismatchWeights`] when the
+/// layouts of the `values` and `weights` differ, and [`ReduceError::NaiveUnsupported`] for the
+/// naive strategy. The `shared_transpose` strategy isn't supported and is ignored.
+pub fn reduce_weighted_sum<R: Runtime, P: ReducePrecision, Out: Numeric>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    values: TensorHandleRef<R>,
+    weights: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    strategy: Option<ReduceStrategy>,
+) -> Result<(), ReduceError> {
+    validate_axis(values.shape.len(), axis)?;
+    valid_output_shape(values.shape, output.shape, axis)?;
+    if values.shape != weights.shape || values.strides != weights.strides {
+        return Err(ReduceError::MismatchWeights {
+            values_shape: values.shape.to_vec(),
+            values_strides: values.strides.to_vec(),
+            weights_shape: weights.shape.to_vec(),
+            weights_strides: weights.strides.to_vec(),
+        });
+    }
+
+    let strategy = strategy
+        .map(|s| s.validate::<R>(client))
+        .unwrap_or(Ok(ReduceStrategy::new::<R>(client, true)))?;
+    if strategy.naive {
+        return Err(ReduceError::NaiveUnsupported);
+    }
+    let config = ReduceConfig::generate::<R, P::EI>(
+        client,
+        &values,
+        &output,
+        axis,
+        &strategy,
+        R::max_cube_count(),
+    );
+
+    unsafe {
+        reduce_kernel::launch_unchecked::<P::EI, Out, P::EA, Sum, WeightedArgs, R>(
+            client,
+            config.cube_count,
+            config.cube_dim,
+            WeightedInputLaunch::new(
+                values.as_tensor_arg(config.line_size_input as u8),
+                weights.as_tensor_arg(config.line_size_input as u8),
+            ),
+            output.as_tensor_arg(config.line_size_output as u8),
+            ScalarArg::new(axis as u32),
+            ReduceParams::new(&config, &strategy),
+            SumConfig::default(),
+        );
+    }
+    Ok(())
+}
+
+/// Compute the mean of the `values` weighted by their `weights` along the given `axis`
+/// and write the result into `output`.
+///
+/// This is the [weighted sum](reduce_weighted_sum) divided by the sum of the weights, which is
+/// reduced into a temporary, with the same requirements and errors. The weights of each
+/// reduction must not sum to zero.
+pub fn reduce_weighted_mean<R: Runtime, P: ReducePrecision, Out: Numeric>(
+    client: &ComputeClient<R::Server, R::Channel>,
+    values: TensorHandleRef<R>,
+    weights: TensorHandleRef<R>,
+    output: TensorHandleRef<R>,
+    axis: usize,
+    strategy: Option<ReduceStrategy>,
+) -> Result<(), ReduceError> {
+    reduce_weighted_sum::<R, P, Out>(client, values, weights, output, axis, strategy)?;
+
+    // The weights are summed into a contiguous temporary before dividing.
+    let shape = output.shape;
+    let num_elems = shape.iter().product::<usize>();
+    let strides = contiguous_strides(shape);
+    let denominator_handle = client.empty(num_elems * size_of::<Out>());
+    let denominator = unsafe {
+        TensorHandleRef::<R>::from_raw_parts(&denominator_handle, &strides, shape, size_of::<Out>())
+    };
+
+    reduce::<R, P, Out, Sum>(
+        client,
+        weights,
+        denominator,
+        axis,
+        strategy,
+        SumConfig::default(),
+    )?;
+
+    let cube_dim = CubeDim::default();
+    let cube_count = calculate_cube_count_elemwise(num_elems, cube_dim);
+
+    unsafe {
+        divide_kernel::launch_unchecked::<Out, R>(
+            client,
+            cube_count,
+            cube_dim,
+            output.as_tensor_arg(1),
+            denominator.as_tensor_arg(1),
+        );
+    }
+    Ok(())
+}
+
+#[cube(launch_unchecked)]
+fn divide_kernel<Out: Numeric>(numerator: &mut Tensor<Line<Out>>, denominator: &Tensor<Line<Out>>) {
+    if ABSOLUTE_POS >= denominator.len() {
+        terminate!();
+    }
+
+    let index = index_offset_contiguous(numerator, ABSOLUTE_POS, None);
+    numerator[index] = numerator[index] / denominator[ABSOLUTE_POS];
+}
@@ -12,6 +12,10 @@ pub struct CompilationConfig {
     #[serde(default)]
     #[cfg(std_io)]
     pub cache: Option<CacheConfig>,
+    /// Maximum number of times the body of an `#[unroll]` loop is expanded. Loops with more
+    /// iterations are only partially unrolled, followed by a remainder loop. Unbounded if unset.
+    #[serde(default)]
+    pub max_unroll_factor: Option<u32>,
 }
 
 /// Log levels for compilation in CubeCL.
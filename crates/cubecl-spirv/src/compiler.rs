@@ -226,7 +226,13 @@ impl<Target: SpirvTarget> SpirvCompiler<Target> {
 
         let mut target = self.target.clone();
 
+        // Contracting into fused multiply-adds rounds once instead of twice, so it needs the
+        // kernel to allow contraction, and kernels relying on two roundings keep separate ops.
+        let contract_fma = options.fp_math_mode.contains(FastMath::AllowContraction)
+            || options.fp_math_mode.contains(FastMath::AllowTransform);
+
         let mut opt = OptimizerBuilder::default()
+            .with_fma_contraction(contract_fma)
             .with_transformer(ErfTransform)
             .with_transformer(BitwiseTransform)
             .with_processor(CheckedIoProcessor::new(self.mode))
@@ -311,8 +311,12 @@ impl Display for Variable {
             }
             // We do the conversion in Rust and then render the number to avoid overflow or other
             // precision related problems.
-            Variable::ConstantScalar(number, _elem) => match number {
+            Variable::ConstantScalar(number, elem) => match number {
                 ConstantScalarValue::Int(val, _) => write!(f, "{}", *val),
+                ConstantScalarValue::Float(val, _) if val.is_infinite() => {
+                    let sign = if val.is_sign_negative() { "-" } else { "" };
+                    write!(f, "{elem}({sign}bitcast<f32>(inf_bits))")
+                }
                 ConstantScalarValue::Float(val, kind) => match kind {
                     FloatKind::BF16
                     | FloatKind::TF32
@@ -45,6 +45,7 @@ pub struct WgslCompiler {
     strategy: ExecutionMode,
     subgroup_instructions_used: bool,
     f16_used: bool,
+    infinity_used: bool,
 }
 
 impl core::fmt::Debug for WgslCompiler {
@@ -145,6 +146,7 @@ impl WgslCompiler {
             workgroup_size_no_axis: self.workgroup_size_no_axis,
             subgroup_instructions_used: self.subgroup_instructions_used,
             f16_used: self.f16_used,
+            infinity_used: self.infinity_used,
             kernel_name: value.options.kernel_name,
         }
     }
@@ -250,6 +252,11 @@ impl WgslCompiler {
                 wgsl::Variable::GlobalOutputArray(id, self.compile_type(item))
             }
             cube::VariableKind::ConstantScalar(value) => {
+                if let ConstantScalarValue::Float(val, _) = value
+                    && val.is_infinite()
+                {
+                    self.infinity_used = true;
+                }
                 wgsl::Variable::ConstantScalar(value, self.compile_elem(value.elem_type()))
             }
             cube::VariableKind::SharedMemory {
@@ -83,6 +83,9 @@ pub struct ComputeShader {
     pub kernel_name: String,
     pub subgroup_instructions_used: bool,
     pub f16_used: bool,
+    /// Whether an infinite constant is used, which reads the bits of the infinity from
+    /// `inf_bits`.
+    pub infinity_used: bool,
 }
 
 impl Display for ComputeShader {
@@ -137,6 +140,12 @@ impl Display for ComputeShader {
             f.write_str(");\n\n")?;
         }
 
+        // Infinite literals and constant expressions are rejected, so infinities are bitcast from
+        // a private variable, which isn't a constant expression.
+        if self.infinity_used {
+            f.write_str("var<private> inf_bits: u32 = 0x7f800000u;\n\n")?;
+        }
+
         write!(
             f,
             "const WORKGROUP_SIZE_X = {}u;
@@ -40,6 +40,8 @@ mod tests {
     cubecl_random::testgen_random!();
     cubecl_attention::testgen_attention!();
     cubecl_reduce::testgen_shared_sum!([f32]);
+    cubecl_reduce::testgen_reduce_overflow!();
+    cubecl_reduce::testgen_reduce_benchmark!();
     cubecl_quant::testgen_quant!();
 }
 
@@ -61,6 +63,8 @@ mod tests_spirv {
     cubecl_reduce::testgen_reduce!();
     cubecl_random::testgen_random!();
     cubecl_reduce::testgen_shared_sum!([f32]);
+    cubecl_reduce::testgen_reduce_overflow!();
+    cubecl_reduce::testgen_reduce_benchmark!();
     cubecl_quant::testgen_quant!();
 }
 
@@ -82,4 +86,6 @@ mod tests_msl {
     cubecl_reduce::testgen_reduce!();
     cubecl_random::testgen_random!();
     cubecl_reduce::testgen_shared_sum!([f32]);
+    cubecl_reduce::testgen_reduce_overflow!();
+    cubecl_reduce::testgen_reduce_benchmark!();
 }